@@ -3,17 +3,67 @@ use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, JtvError>;
 
+/// A byte-offset range into the source an error's location was computed
+/// from. Carried alongside the 1-based line/column already on the `*At`
+/// variants below: the parser has both on hand at the point it raises an
+/// error (`pest::Span::start()`/`end()`), and a byte range is what lets
+/// `render_diagnostic` underline the whole offending token instead of
+/// caret-pointing at just its first character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Error, Debug, Clone)]
 pub enum JtvError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    /// Same as `ParseError`, but carrying the 1-based source position the
+    /// underlying parser reported (plus the byte `span` it came from, when
+    /// the caller had one), so callers can render a source-context window
+    /// instead of a bare message.
+    #[error("Parse error: {message} (line {line}, column {column})")]
+    ParseErrorAt {
+        message: String,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Type error: {0}")]
     TypeError(String),
 
+    /// Same as `TypeError`, but located like `ParseErrorAt`. Nothing in
+    /// this crate constructs it yet -- type checking lives in `jtv-lang`,
+    /// which doesn't depend on `JtvError` -- but it exists so a type
+    /// checker built against this crate has a located variant to reach
+    /// for instead of adding its own ad hoc one.
+    #[error("Type error: {message} (line {line}, column {column})")]
+    TypeErrorAt {
+        message: String,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Runtime error: {0}")]
     RuntimeError(String),
 
+    /// Same as `RuntimeError`, but carrying a source position. Nothing
+    /// constructs this yet: the AST carries no span information, so the
+    /// interpreter can't attribute a runtime failure to a precise line and
+    /// column until it does. The variant exists so error-rendering code has
+    /// a single case to handle once that support lands.
+    #[error("Runtime error: {message} (line {line}, column {column})")]
+    RuntimeErrorAt {
+        message: String,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Purity violation: {0}")]
     PurityViolation(String),
 
@@ -23,12 +73,34 @@ pub enum JtvError {
     #[error("Undefined variable: {0}")]
     UndefinedVariable(String),
 
+    /// Same as `UndefinedVariable`, but located. See `TypeErrorAt` for why
+    /// nothing in this crate constructs it yet -- name resolution happens
+    /// downstream of here, in `jtv-lang`'s interpreter and type checker.
+    #[error("Undefined variable: {name} (line {line}, column {column})")]
+    UndefinedVariableAt {
+        name: String,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Undefined function: {0}")]
     UndefinedFunction(String),
 
     #[error("Arity mismatch: expected {expected}, got {got}")]
     ArityMismatch { expected: usize, got: usize },
 
+    /// Same as `ArityMismatch`, but located. See `TypeErrorAt` for why
+    /// nothing in this crate constructs it yet.
+    #[error("Arity mismatch: expected {expected}, got {got} (line {line}, column {column})")]
+    ArityMismatchAt {
+        expected: usize,
+        got: usize,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Division by zero")]
     DivisionByZero,
 
@@ -41,13 +113,92 @@ pub enum JtvError {
     #[error("Code injection attempt detected: {0}")]
     InjectionAttempt(String),
 
+    /// Same as `InjectionAttempt`, but located. See `TypeErrorAt` for why
+    /// nothing in this crate constructs it yet.
+    #[error("Code injection attempt detected: {message} (line {line}, column {column})")]
+    InjectionAttemptAt {
+        message: String,
+        line: usize,
+        column: usize,
+        span: Option<Span>,
+    },
+
     #[error("Maximum iteration count exceeded (possible infinite loop)")]
     MaxIterationsExceeded,
 
+    /// Expression/statement nesting passed the parser's configured limit
+    /// (see `parse_program_with_limits`) before the input was exhausted --
+    /// recoverable, unlike the stack overflow it replaces.
+    #[error("Nesting too deep: {depth} levels exceeds the limit of {limit}")]
+    NestingTooDeep { depth: usize, limit: usize },
+
     #[error("IO error: {0}")]
     IoError(String),
 }
 
+impl JtvError {
+    /// The (line, column) the error occurred at, if known. Both are 1-based.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            JtvError::ParseErrorAt { line, column, .. } => Some((*line, *column)),
+            JtvError::TypeErrorAt { line, column, .. } => Some((*line, *column)),
+            JtvError::RuntimeErrorAt { line, column, .. } => Some((*line, *column)),
+            JtvError::UndefinedVariableAt { line, column, .. } => Some((*line, *column)),
+            JtvError::ArityMismatchAt { line, column, .. } => Some((*line, *column)),
+            JtvError::InjectionAttemptAt { line, column, .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    /// The byte-offset `Span` the error was raised at, if the caller had
+    /// one on hand. Can be `Some` only where `position()` is also `Some`,
+    /// but may still be `None` there -- e.g. a `pest::error::Error` whose
+    /// `InputLocation` didn't resolve to a span.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            JtvError::ParseErrorAt { span, .. } => *span,
+            JtvError::TypeErrorAt { span, .. } => *span,
+            JtvError::RuntimeErrorAt { span, .. } => *span,
+            JtvError::UndefinedVariableAt { span, .. } => *span,
+            JtvError::ArityMismatchAt { span, .. } => *span,
+            JtvError::InjectionAttemptAt { span, .. } => *span,
+            _ => None,
+        }
+    }
+
+    /// Renders this error with the offending line from `source` and an
+    /// underline below the reported position, e.g.:
+    ///
+    /// ```text
+    /// Parse error: expected an operand (line 2, column 12)
+    ///     y = 1 +
+    ///            ^
+    /// ```
+    ///
+    /// The underline spans the full `span()` width when one is available
+    /// (so a multi-character token is underlined, not just caret-pointed
+    /// at its first character), and falls back to a single `^` under
+    /// `column` otherwise. Falls back to the plain `Display` message for
+    /// variants that carry no source position at all.
+    pub fn render_diagnostic(&self, source: &str) -> String {
+        let Some((line, column)) = self.position() else {
+            return self.to_string();
+        };
+
+        let source_line = source.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        let underline_width = self
+            .span()
+            .map_or(1, |span| span.end.saturating_sub(span.start).max(1));
+        let underline = format!(
+            "{}{}",
+            " ".repeat(column.saturating_sub(1)),
+            "^".repeat(underline_width)
+        );
+
+        format!("{}\n{}\n{}", self, source_line, underline)
+    }
+}
+
 impl From<std::io::Error> for JtvError {
     fn from(err: std::io::Error) -> Self {
         JtvError::IoError(err.to_string())
@@ -8,22 +8,147 @@ use pest_derive::Parser;
 #[grammar = "grammar.pest"]
 pub struct JtvParser;
 
+/// Default maximum nesting depth for `parse_program`. Chosen to comfortably
+/// fit any real program (deeply nested expressions/blocks are rare past a
+/// handful of levels) while staying well short of what it takes to overflow
+/// the stack during AST construction.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
+/// RAII nesting-depth counter for the recursive-descent parsing below.
+/// `enter` increments the shared counter and fails once it passes a limit;
+/// the guard decrements it again on drop, so depth is tracked correctly
+/// even across the early returns the `?` operator produces on parse
+/// errors. This is what keeps pathologically nested input (adversarial or
+/// otherwise) from aborting the process via stack overflow -- it returns
+/// a recoverable `JtvError::NestingTooDeep` instead.
+struct DepthGuard<'a> {
+    current: &'a mut usize,
+}
+
+impl<'a> DepthGuard<'a> {
+    fn enter(current: &'a mut usize, limit: usize) -> Result<Self> {
+        *current += 1;
+        if *current > limit {
+            return Err(JtvError::NestingTooDeep {
+                depth: *current,
+                limit,
+            });
+        }
+        Ok(DepthGuard { current })
+    }
+}
+
+impl Drop for DepthGuard<'_> {
+    fn drop(&mut self) {
+        *self.current -= 1;
+    }
+}
+
+/// Builds a `JtvError::ParseErrorAt` located at `pair`'s starting position,
+/// for the "unexpected rule" fallbacks scattered through the parse_*
+/// functions below -- `JtvError::render_diagnostic` can then show the
+/// offending line with a caret instead of just the bare message.
+fn unexpected_rule_error(pair: &pest::iterators::Pair<Rule>, what: &str) -> JtvError {
+    let span = pair.as_span();
+    let (line, column) = span.start_pos().line_col();
+    JtvError::ParseErrorAt {
+        message: format!("Unexpected {}: {:?}", what, pair.as_rule()),
+        line,
+        column,
+        span: Some(crate::error::Span {
+            start: span.start(),
+            end: span.end(),
+        }),
+    }
+}
+
+/// Toggles for experimental or restricted language features, consulted by
+/// the parser wherever a feature can't just be expressed structurally in
+/// the grammar (e.g. rejecting a `reverse` block needs a runtime check,
+/// since the grammar alone can't tell an allowed block from a disallowed
+/// one). `Default` matches `parse_program`'s historical, permissive
+/// behavior, so existing callers see no change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Maximum nesting depth for expressions and statements before
+    /// `JtvError::NestingTooDeep` is raised. See `DEFAULT_MAX_NESTING_DEPTH`.
+    pub max_nesting_depth: usize,
+    /// Whether `reverse { ... }` blocks are accepted at all.
+    pub allow_reverse_blocks: bool,
+    /// Whether every function declaration must carry a `@pure` or `@total`
+    /// purity marker, rejecting plain (implicitly impure) `fn` headers.
+    pub require_purity_markers: bool,
+    /// Whether the `Symbolic` basic type is accepted in type annotations.
+    pub allow_symbolic_numbers: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            allow_reverse_blocks: true,
+            require_purity_markers: false,
+            allow_symbolic_numbers: true,
+        }
+    }
+}
+
 pub fn parse_program(input: &str) -> Result<Program> {
-    let mut pairs = JtvParser::parse(Rule::program, input)
-        .map_err(|e| JtvError::ParseError(format!("Parse error: {}", e)))?;
+    parse_program_with(input, &ParseOptions::default())
+}
+
+/// Same as `parse_program`, but with a caller-chosen maximum nesting depth
+/// for expressions and statements, instead of `DEFAULT_MAX_NESTING_DEPTH`.
+pub fn parse_program_with_limits(input: &str, max_depth: usize) -> Result<Program> {
+    parse_program_with(
+        input,
+        &ParseOptions {
+            max_nesting_depth: max_depth,
+            ..ParseOptions::default()
+        },
+    )
+}
+
+/// Same as `parse_program`, but gating experimental or restricted features
+/// according to `options` instead of always parsing the full language.
+pub fn parse_program_with(input: &str, options: &ParseOptions) -> Result<Program> {
+    let mut pairs = JtvParser::parse(Rule::program, input).map_err(|e| {
+        let (line, column) = match e.line_col {
+            pest::error::LineColLocation::Pos((line, column)) => (line, column),
+            pest::error::LineColLocation::Span((line, column), _) => (line, column),
+        };
+        let span = match e.location {
+            pest::error::InputLocation::Pos(pos) => Some(crate::error::Span {
+                start: pos,
+                end: pos,
+            }),
+            pest::error::InputLocation::Span((start, end)) => {
+                Some(crate::error::Span { start, end })
+            }
+        };
+        JtvError::ParseErrorAt {
+            message: e.variant.message().to_string(),
+            line,
+            column,
+            span,
+        }
+    })?;
 
     let program_pair = pairs
         .next()
         .ok_or_else(|| JtvError::ParseError("Expected program".to_string()))?;
 
     let mut statements = Vec::new();
+    let mut depth = 0usize;
 
     for pair in program_pair.into_inner() {
         match pair.as_rule() {
-            Rule::module_decl => statements.push(parse_module(pair)?),
+            Rule::module_decl => statements.push(parse_module(pair, &mut depth, options)?),
             Rule::import_stmt => statements.push(parse_import(pair)?),
-            Rule::function_decl => statements.push(parse_function(pair)?),
-            Rule::control_stmt => statements.push(TopLevel::Control(parse_control_stmt(pair)?)),
+            Rule::function_decl => statements.push(parse_function(pair, &mut depth, options)?),
+            Rule::control_stmt => statements.push(TopLevel::Control(parse_control_stmt(
+                pair, &mut depth, options,
+            )?)),
             Rule::EOI => break,
             _ => {}
         }
@@ -32,15 +157,97 @@ pub fn parse_program(input: &str) -> Result<Program> {
     Ok(Program { statements })
 }
 
-fn parse_module(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
+/// Outcome of `validate_incomplete`, meant for a REPL deciding whether to
+/// keep reading more lines before attempting a real parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Incompleteness {
+    /// Parses as a complete program on its own.
+    Complete,
+    /// Doesn't parse yet, but only because it ends mid-construct (an
+    /// unclosed `{`/`(`/`[`, or a `fn`/`reverse` header with no body block
+    /// yet) -- more input could still complete it.
+    Incomplete,
+    /// Doesn't parse, and not for a reason more input would fix.
+    Invalid,
+}
+
+/// Checks whether `input` is a complete program, a plausibly-still-valid
+/// incomplete prefix, or outright invalid syntax -- for a REPL deciding
+/// whether to keep prompting for more lines (`Incomplete`) or report the
+/// error now (`Invalid`) instead of waiting forever for input that would
+/// never complete it. On a parse failure this falls back to a delimiter-
+/// depth scan plus a check for a trailing body-less `fn`/`reverse` header
+/// (it doesn't re-lex the input) rather than inspecting the
+/// pest error in more detail, since pest doesn't distinguish "ran out of
+/// input" from "found the wrong token" in its public error type.
+pub fn validate_incomplete(input: &str) -> Incompleteness {
+    match JtvParser::parse(Rule::program, input) {
+        Ok(_) => Incompleteness::Complete,
+        Err(e) => {
+            // A `fn`/`reverse` header still waiting for its `{ ... }` body.
+            let last_line = input.trim_end().lines().last().unwrap_or("").trim();
+            let header = last_line
+                .trim_start_matches("@pure")
+                .trim_start_matches("@total")
+                .trim_start();
+            let bodyless_header = !last_line.contains('{')
+                && (header == "reverse" || header.starts_with("fn "));
+            if error_at_end_of_input(input, &e)
+                && (has_unclosed_delimiter(input) || bodyless_header)
+            {
+                Incompleteness::Incomplete
+            } else {
+                Incompleteness::Invalid
+            }
+        }
+    }
+}
+
+/// Whether `err`'s reported position is past the last character of
+/// `input` (including on a line after the last one, as a trailing newline
+/// produces), i.e. parsing ran off the end rather than stumbling over a
+/// token somewhere in the middle.
+fn error_at_end_of_input(input: &str, err: &pest::error::Error<Rule>) -> bool {
+    let (line, column) = match err.line_col {
+        pest::error::LineColLocation::Pos(pos) => pos,
+        pest::error::LineColLocation::Span(pos, _) => pos,
+    };
+    let last_line = input.lines().count().max(1);
+    let last_column = input.lines().last().map_or(1, |l| l.chars().count() + 1);
+    line > last_line || (line == last_line && column >= last_column)
+}
+
+/// True if `input` has more opening `{`/`(`/`[` than matching closers.
+/// A plain character scan, not a real lexer -- it doesn't skip delimiters
+/// that appear inside string literals or comments, so it's a heuristic
+/// fallback rather than a precise check.
+fn has_unclosed_delimiter(input: &str) -> bool {
+    let mut depth = 0i64;
+    for c in input.chars() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+fn parse_module(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<TopLevel> {
     let mut inner = pair.into_inner();
     let name = inner.next().unwrap().as_str().to_string();
 
     let mut body = Vec::new();
     for pair in inner {
         match pair.as_rule() {
-            Rule::function_decl => body.push(parse_function(pair)?),
-            Rule::control_stmt => body.push(TopLevel::Control(parse_control_stmt(pair)?)),
+            Rule::function_decl => body.push(parse_function(pair, depth, options)?),
+            Rule::control_stmt => {
+                body.push(TopLevel::Control(parse_control_stmt(pair, depth, options)?))
+            }
             _ => {}
         }
     }
@@ -62,7 +269,11 @@ fn parse_import(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
     Ok(TopLevel::Import(ImportStmt { path, alias }))
 }
 
-fn parse_function(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
+fn parse_function(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<TopLevel> {
     let mut inner = pair.into_inner();
 
     let mut purity = Purity::Impure;
@@ -76,6 +287,11 @@ fn parse_function(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
             _ => Purity::Impure,
         };
         first = inner.next().unwrap();
+    } else if options.require_purity_markers {
+        return Err(JtvError::ParseError(format!(
+            "Function '{}' is missing a @pure or @total purity marker, which ParseOptions::require_purity_markers requires",
+            first.as_str()
+        )));
     }
 
     let name = first.as_str().to_string();
@@ -88,15 +304,19 @@ fn parse_function(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
         match pair.as_rule() {
             Rule::param_list => {
                 for param_pair in pair.into_inner() {
-                    params.push(parse_param(param_pair)?);
+                    params.push(parse_param(param_pair, depth, options)?);
                 }
             }
             Rule::return_type => {
-                return_type = Some(parse_type_annotation(pair.into_inner().next().unwrap())?);
+                return_type = Some(parse_type_annotation(
+                    pair.into_inner().next().unwrap(),
+                    depth,
+                    options,
+                )?);
             }
             Rule::block => {
                 for stmt_pair in pair.into_inner() {
-                    body.push(parse_control_stmt(stmt_pair)?);
+                    body.push(parse_control_stmt(stmt_pair, depth, options)?);
                 }
             }
             _ => {}
@@ -112,10 +332,17 @@ fn parse_function(pair: pest::iterators::Pair<Rule>) -> Result<TopLevel> {
     }))
 }
 
-fn parse_param(pair: pest::iterators::Pair<Rule>) -> Result<Param> {
+fn parse_param(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<Param> {
     let mut inner = pair.into_inner();
     let name = inner.next().unwrap().as_str().to_string();
-    let type_annotation = inner.next().map(|p| parse_type_annotation(p)).transpose()?;
+    let type_annotation = inner
+        .next()
+        .map(|p| parse_type_annotation(p, depth, options))
+        .transpose()?;
 
     Ok(Param {
         name,
@@ -123,7 +350,12 @@ fn parse_param(pair: pest::iterators::Pair<Rule>) -> Result<Param> {
     })
 }
 
-fn parse_control_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ControlStmt> {
+fn parse_control_stmt(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlStmt> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let inner = pair.into_inner().next().unwrap();
 
     match inner.as_rule() {
@@ -133,30 +365,33 @@ fn parse_control_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ControlStmt>
             let value_pair = parts.next().unwrap();
 
             let value = if value_pair.as_rule() == Rule::data_expr {
-                Expr::Data(parse_data_expr(value_pair)?)
+                Expr::Data(parse_data_expr(value_pair, depth, options)?)
             } else {
-                Expr::Control(parse_control_expr(value_pair)?)
+                Expr::Control(parse_control_expr(value_pair, depth, options)?)
             };
 
             Ok(ControlStmt::Assignment(Assignment { target, value }))
         }
         Rule::if_stmt => {
             let mut parts = inner.into_inner();
-            let condition = parse_control_expr(parts.next().unwrap())?;
+            let condition = parse_control_expr(parts.next().unwrap(), depth, options)?;
 
             let then_block = parts.next().unwrap();
             let mut then_branch = Vec::new();
             for stmt in then_block.into_inner() {
-                then_branch.push(parse_control_stmt(stmt)?);
+                then_branch.push(parse_control_stmt(stmt, depth, options)?);
             }
 
-            let else_branch = parts.next().map(|else_block| {
-                let mut stmts = Vec::new();
-                for stmt in else_block.into_inner() {
-                    stmts.push(parse_control_stmt(stmt).unwrap());
-                }
-                stmts
-            });
+            let else_branch = parts
+                .next()
+                .map(|else_block| {
+                    let mut stmts = Vec::new();
+                    for stmt in else_block.into_inner() {
+                        stmts.push(parse_control_stmt(stmt, depth, options)?);
+                    }
+                    Ok(stmts)
+                })
+                .transpose()?;
 
             Ok(ControlStmt::If(IfStmt {
                 condition,
@@ -166,11 +401,11 @@ fn parse_control_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ControlStmt>
         }
         Rule::while_stmt => {
             let mut parts = inner.into_inner();
-            let condition = parse_control_expr(parts.next().unwrap())?;
+            let condition = parse_control_expr(parts.next().unwrap(), depth, options)?;
 
             let mut body = Vec::new();
             for stmt in parts.next().unwrap().into_inner() {
-                body.push(parse_control_stmt(stmt)?);
+                body.push(parse_control_stmt(stmt, depth, options)?);
             }
 
             Ok(ControlStmt::While(WhileStmt { condition, body }))
@@ -178,11 +413,11 @@ fn parse_control_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ControlStmt>
         Rule::for_stmt => {
             let mut parts = inner.into_inner();
             let variable = parts.next().unwrap().as_str().to_string();
-            let range = parse_range_expr(parts.next().unwrap())?;
+            let range = parse_range_expr(parts.next().unwrap(), depth, options)?;
 
             let mut body = Vec::new();
             for stmt in parts.next().unwrap().into_inner() {
-                body.push(parse_control_stmt(stmt)?);
+                body.push(parse_control_stmt(stmt, depth, options)?);
             }
 
             Ok(ControlStmt::For(ForStmt {
@@ -195,39 +430,58 @@ fn parse_control_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ControlStmt>
             let value = inner
                 .into_inner()
                 .next()
-                .map(|p| parse_data_expr(p))
+                .map(|p| parse_data_expr(p, depth, options))
                 .transpose()?;
             Ok(ControlStmt::Return(value))
         }
         Rule::print_stmt => {
             let mut exprs = Vec::new();
             for expr_pair in inner.into_inner() {
-                exprs.push(parse_data_expr(expr_pair)?);
+                exprs.push(parse_data_expr(expr_pair, depth, options)?);
             }
             Ok(ControlStmt::Print(exprs))
         }
         Rule::reverse_block => {
+            if !options.allow_reverse_blocks {
+                return Err(JtvError::ParseError(
+                    "reverse { ... } blocks are disabled by ParseOptions::allow_reverse_blocks"
+                        .to_string(),
+                ));
+            }
             let mut body = Vec::new();
             for stmt_pair in inner.into_inner() {
-                body.push(parse_reversible_stmt(stmt_pair)?);
+                body.push(parse_reversible_stmt(stmt_pair, depth, options)?);
             }
             Ok(ControlStmt::ReverseBlock(ReverseBlock { body }))
         }
         Rule::block => {
             let mut stmts = Vec::new();
             for stmt_pair in inner.into_inner() {
-                stmts.push(parse_control_stmt(stmt_pair)?);
+                stmts.push(parse_control_stmt(stmt_pair, depth, options)?);
             }
             Ok(ControlStmt::Block(stmts))
         }
-        _ => Err(JtvError::ParseError(format!(
-            "Unexpected control statement: {:?}",
-            inner.as_rule()
-        ))),
+        Rule::match_stmt => {
+            let mut parts = inner.into_inner();
+            let scrutinee = parse_data_expr(parts.next().unwrap(), depth, options)?;
+
+            let mut arms = Vec::new();
+            for arm_pair in parts {
+                arms.push(parse_match_arm(arm_pair, depth, options)?);
+            }
+
+            Ok(ControlStmt::Match { scrutinee, arms })
+        }
+        _ => Err(unexpected_rule_error(&inner, "control statement")),
     }
 }
 
-fn parse_reversible_stmt(pair: pest::iterators::Pair<Rule>) -> Result<ReversibleStmt> {
+fn parse_reversible_stmt(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ReversibleStmt> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let inner = pair.into_inner().next().unwrap();
 
     match inner.as_rule() {
@@ -235,7 +489,7 @@ fn parse_reversible_stmt(pair: pest::iterators::Pair<Rule>) -> Result<Reversible
             let mut parts = inner.into_inner();
             let target = parts.next().unwrap().as_str().to_string();
             let op = parts.next().unwrap().as_str();
-            let expr = parse_data_expr(parts.next().unwrap())?;
+            let expr = parse_data_expr(parts.next().unwrap(), depth, options)?;
 
             match op {
                 "+=" => Ok(ReversibleStmt::AddAssign(target, expr)),
@@ -249,21 +503,24 @@ fn parse_reversible_stmt(pair: pest::iterators::Pair<Rule>) -> Result<Reversible
         Rule::if_stmt => {
             // Parse as regular if statement
             let mut parts = inner.into_inner();
-            let condition = parse_control_expr(parts.next().unwrap())?;
+            let condition = parse_control_expr(parts.next().unwrap(), depth, options)?;
 
             let then_block = parts.next().unwrap();
             let mut then_branch = Vec::new();
             for stmt in then_block.into_inner() {
-                then_branch.push(parse_control_stmt(stmt)?);
+                then_branch.push(parse_control_stmt(stmt, depth, options)?);
             }
 
-            let else_branch = parts.next().map(|else_block| {
-                let mut stmts = Vec::new();
-                for stmt in else_block.into_inner() {
-                    stmts.push(parse_control_stmt(stmt).unwrap());
-                }
-                stmts
-            });
+            let else_branch = parts
+                .next()
+                .map(|else_block| {
+                    let mut stmts = Vec::new();
+                    for stmt in else_block.into_inner() {
+                        stmts.push(parse_control_stmt(stmt, depth, options)?);
+                    }
+                    Ok(stmts)
+                })
+                .transpose()?;
 
             Ok(ReversibleStmt::If(IfStmt {
                 condition,
@@ -271,111 +528,397 @@ fn parse_reversible_stmt(pair: pest::iterators::Pair<Rule>) -> Result<Reversible
                 else_branch,
             }))
         }
-        _ => Err(JtvError::ParseError(format!(
-            "Unexpected reversible statement: {:?}",
-            inner.as_rule()
-        ))),
+        _ => Err(unexpected_rule_error(&inner, "reversible statement")),
     }
 }
 
-fn parse_data_expr(pair: pest::iterators::Pair<Rule>) -> Result<DataExpr> {
+fn parse_data_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let inner = pair.into_inner().next().unwrap();
-    parse_additive_expr(inner)
+    parse_pipeline_expr(inner, depth, options)
 }
 
-fn parse_additive_expr(pair: pest::iterators::Pair<Rule>) -> Result<DataExpr> {
-    let mut inner = pair.into_inner();
-    let mut left = parse_term(inner.next().unwrap())?;
+/// `|>` threads its left operand into its right operand as a function
+/// call's leading argument: `x |> f |> g(2)` desugars to `g(f(x), 2)`.
+/// It sits below every other data operator --
+/// `pipeline_expr = { binary_expr ~ ("|>" ~ pipeline_target)* }` -- and is
+/// left-associative, so each stage's result feeds the next.
+fn parse_pipeline_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    let mut stages = pair.into_inner();
+    let mut value = parse_binary_expr(stages.next().unwrap(), depth, options)?;
+
+    for stage in stages {
+        value = parse_pipeline_stage(stage, value, depth, options)?;
+    }
+
+    Ok(value)
+}
 
-    while let Some(right_pair) = inner.next() {
-        let right = parse_term(right_pair)?;
-        left = DataExpr::add(left, right);
+/// The right-hand side of one `|>` stage: a bare identifier becomes a
+/// single-argument call fed the piped value, and an existing
+/// `function_call` has the piped value prepended to its argument list.
+fn parse_pipeline_stage(
+    pair: pest::iterators::Pair<Rule>,
+    piped_value: DataExpr,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    match pair.as_rule() {
+        Rule::identifier => Ok(DataExpr::FunctionCall(FunctionCall {
+            module: None,
+            name: pair.as_str().to_string(),
+            args: vec![piped_value],
+        })),
+        Rule::function_call => {
+            let mut call = parse_function_call(pair, depth, options)?;
+            call.args.insert(0, piped_value);
+            Ok(DataExpr::FunctionCall(call))
+        }
+        rule => Err(JtvError::ParseError(format!(
+            "Expected a pipeline target (identifier or function call), found {:?}",
+            rule
+        ))),
     }
+}
 
-    Ok(left)
+/// Precedence tier a binary operator belongs to. Additive binds loosest,
+/// Exponential tightest; `parse_binary` climbs these via `binding_power`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpType {
+    Additive,
+    Multiplicative,
+    Exponential,
 }
 
-fn parse_term(pair: pest::iterators::Pair<Rule>) -> Result<DataExpr> {
-    let inner = pair.into_inner().next().unwrap();
-    parse_factor(inner)
+/// Binding power of an `OpType` -- higher binds tighter. Only the relative
+/// ordering matters, so these are small and dense rather than spaced out.
+fn binding_power(op_type: OpType) -> u8 {
+    match op_type {
+        OpType::Additive => 1,
+        OpType::Multiplicative => 2,
+        OpType::Exponential => 3,
+    }
 }
 
-fn parse_factor(pair: pest::iterators::Pair<Rule>) -> Result<DataExpr> {
-    match pair.as_rule() {
-        Rule::number => {
-            let num = parse_number(pair.into_inner().next().unwrap())?;
-            Ok(DataExpr::Number(num))
-        }
-        Rule::identifier => Ok(DataExpr::Identifier(pair.as_str().to_string())),
-        Rule::function_call => {
-            let mut parts = pair.into_inner();
-            let qualified_name = parts.next().unwrap();
+/// `^` is the only right-associative tier (`2 ^ 3 ^ 2` parses as
+/// `2 ^ (3 ^ 2)`); everything else is left-associative.
+fn is_right_assoc(op_type: OpType) -> bool {
+    matches!(op_type, OpType::Exponential)
+}
 
-            // Parse qualified name: Module.submodule.function
-            let name_parts: Vec<String> = qualified_name
-                .into_inner()
-                .map(|p| p.as_str().to_string())
-                .collect();
+fn classify_op(op: &str) -> Option<(BinaryOp, OpType)> {
+    match op {
+        "+" => Some((BinaryOp::Add, OpType::Additive)),
+        "-" => Some((BinaryOp::Sub, OpType::Additive)),
+        "*" => Some((BinaryOp::Mul, OpType::Multiplicative)),
+        "/" => Some((BinaryOp::Div, OpType::Multiplicative)),
+        "%" => Some((BinaryOp::Mod, OpType::Multiplicative)),
+        "^" => Some((BinaryOp::Pow, OpType::Exponential)),
+        _ => None,
+    }
+}
 
-            let (module, name) = if name_parts.len() > 1 {
-                // Has module path: ["Module", "submod", "func"] -> module=["Module", "submod"], name="func"
-                let last = name_parts.len() - 1;
-                (Some(name_parts[..last].to_vec()), name_parts[last].clone())
-            } else {
-                // No module path
-                (None, name_parts[0].clone())
-            };
+/// Precedence-climbing (Pratt) entry point. `binary_expr`'s grammar
+/// flattens to `primary (bin_op primary)*`, so the inner pairs alternate
+/// between operand and operator tokens; `parse_binary` folds that flat
+/// stream into a left- or right-leaning `DataExpr::BinOp` tree depending on
+/// each operator's associativity.
+fn parse_binary_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    let mut pairs = pair.into_inner().peekable();
+    let first = pairs
+        .next()
+        .ok_or_else(|| JtvError::ParseError("Expected an operand".to_string()))?;
+    let lhs = parse_factor(first, depth, options)?;
+    parse_binary(&mut pairs, lhs, 0, depth, options)
+}
 
-            let mut args = Vec::new();
-            if let Some(arg_list) = parts.next() {
-                for arg in arg_list.into_inner() {
-                    args.push(parse_data_expr(arg)?);
+/// Consume zero or more `(bin_op, primary)` pairs from `pairs`, folding them
+/// onto `lhs`. Stops -- without consuming -- as soon as the next operator's
+/// binding power drops below `min_bp`. A tighter-binding operator
+/// immediately following an operand recurses first (via `next_min_bp`) so it
+/// grabs that operand before the looser-binding operator folds it in,
+/// e.g. `1 + 2 * 3` parses `2 * 3` before building the `+`.
+fn parse_binary(
+    pairs: &mut std::iter::Peekable<pest::iterators::Pairs<Rule>>,
+    mut lhs: DataExpr,
+    min_bp: u8,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    loop {
+        let op_str = match pairs.peek() {
+            Some(op_pair) if op_pair.as_rule() == Rule::bin_op => op_pair.as_str().to_string(),
+            _ => break,
+        };
+        let (op, op_type) = classify_op(&op_str)
+            .ok_or_else(|| JtvError::ParseError(format!("Unknown binary operator: {}", op_str)))?;
+
+        let bp = binding_power(op_type);
+        if bp < min_bp {
+            break;
+        }
+        pairs.next(); // consume the operator
+
+        let rhs_pair = pairs.next().ok_or_else(|| {
+            JtvError::ParseError(format!("Expected an operand after '{}'", op_str))
+        })?;
+        let mut rhs = parse_factor(rhs_pair, depth, options)?;
+
+        let next_min_bp = if is_right_assoc(op_type) { bp } else { bp + 1 };
+        if let Some(next_op) = pairs.peek() {
+            if next_op.as_rule() == Rule::bin_op {
+                if let Some((_, next_type)) = classify_op(next_op.as_str()) {
+                    if binding_power(next_type) >= next_min_bp {
+                        rhs = parse_binary(pairs, rhs, next_min_bp, depth, options)?;
+                    }
                 }
             }
+        }
+
+        lhs = DataExpr::BinOp(Box::new(lhs), op, Box::new(rhs));
+    }
+    Ok(lhs)
+}
+
+fn parse_function_call(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<FunctionCall> {
+    let mut parts = pair.into_inner();
+    let qualified_name = parts.next().unwrap();
+
+    // Parse qualified name: Module.submodule.function
+    let name_parts: Vec<String> = qualified_name
+        .into_inner()
+        .map(|p| p.as_str().to_string())
+        .collect();
+
+    let (module, name) = if name_parts.len() > 1 {
+        // Has module path: ["Module", "submod", "func"] -> module=["Module", "submod"], name="func"
+        let last = name_parts.len() - 1;
+        (Some(name_parts[..last].to_vec()), name_parts[last].clone())
+    } else {
+        // No module path
+        (None, name_parts[0].clone())
+    };
+
+    let mut args = Vec::new();
+    if let Some(arg_list) = parts.next() {
+        for arg in arg_list.into_inner() {
+            args.push(parse_data_expr(arg, depth, options)?);
+        }
+    }
+
+    Ok(FunctionCall { module, name, args })
+}
 
-            Ok(DataExpr::FunctionCall(FunctionCall { module, name, args }))
+fn parse_factor(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    match pair.as_rule() {
+        Rule::number => {
+            let num = parse_number(pair.into_inner().next().unwrap())?;
+            Ok(DataExpr::Number(num))
         }
+        Rule::identifier => Ok(DataExpr::Identifier(pair.as_str().to_string())),
+        Rule::function_call => Ok(DataExpr::FunctionCall(parse_function_call(
+            pair, depth, options,
+        )?)),
         Rule::list_literal => {
             let mut elements = Vec::new();
             for elem in pair.into_inner() {
-                elements.push(parse_data_expr(elem)?);
+                elements.push(parse_data_expr(elem, depth, options)?);
             }
             Ok(DataExpr::List(elements))
         }
         Rule::tuple_literal => {
             let mut elements = Vec::new();
             for elem in pair.into_inner() {
-                elements.push(parse_data_expr(elem)?);
+                elements.push(parse_data_expr(elem, depth, options)?);
             }
             Ok(DataExpr::Tuple(elements))
         }
+        Rule::string_literal => parse_string_literal(pair, depth, options),
         Rule::factor => {
             let mut inner = pair.into_inner();
             let first = inner.next().unwrap();
 
-            if first.as_rule() == Rule::unary_op {
+            let mut expr = if first.as_rule() == Rule::unary_op {
                 let op = first.as_str();
-                let expr = parse_factor(inner.next().unwrap())?;
+                let operand = parse_factor(inner.next().unwrap(), depth, options)?;
 
                 match op {
-                    "-" => Ok(DataExpr::Negate(Box::new(expr))),
-                    _ => Err(JtvError::ParseError(format!(
-                        "Unknown unary operator: {}",
-                        op
-                    ))),
+                    "-" => DataExpr::Negate(Box::new(operand)),
+                    _ => {
+                        return Err(JtvError::ParseError(format!(
+                            "Unknown unary operator: {}",
+                            op
+                        )))
+                    }
                 }
             } else {
-                parse_factor(first)
+                parse_factor(first, depth, options)?
+            };
+
+            // Any remaining pairs are postfix `[ data_expr ]` / `. identifier`
+            // operators, consumed left-to-right so e.g. `m.rows[0]` folds
+            // into Field(Index(...)) in source order.
+            for postfix in inner {
+                expr = parse_postfix(postfix, expr, depth, options)?;
             }
+
+            Ok(expr)
         }
-        Rule::data_expr => parse_data_expr(pair),
-        _ => Err(JtvError::ParseError(format!(
-            "Unexpected factor: {:?}",
-            pair.as_rule()
-        ))),
+        Rule::data_expr => parse_data_expr(pair, depth, options),
+        _ => Err(unexpected_rule_error(&pair, "factor")),
     }
 }
 
+/// Applies a single postfix operator -- `[ data_expr ]` indexing or
+/// `. identifier` field access -- onto an already-parsed base expression.
+/// Bounds/field-existence checking happens at evaluation time, not here;
+/// the parser only builds the `Index`/`Field` node.
+fn parse_postfix(
+    pair: pest::iterators::Pair<Rule>,
+    base: DataExpr,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    match pair.as_rule() {
+        Rule::index_postfix => {
+            let index = parse_data_expr(pair.into_inner().next().unwrap(), depth, options)?;
+            Ok(DataExpr::Index(Box::new(base), Box::new(index)))
+        }
+        Rule::field_postfix => {
+            let field = pair.into_inner().next().unwrap().as_str().to_string();
+            Ok(DataExpr::Field(Box::new(base), field))
+        }
+        _ => Err(unexpected_rule_error(&pair, "postfix operator")),
+    }
+}
+
+/// A `string_literal` pair's inner production is a sequence of plain-text
+/// fragments (`Rule::string_text`) and `{ expr }` interpolation fragments
+/// (`Rule::string_interp`). No interpolation fragments at all collapses to
+/// a plain `DataExpr::Str`; otherwise the fragments become a
+/// `DataExpr::StrInterp`, evaluated by concatenating each part in order.
+fn parse_string_literal(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<DataExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    let mut parts = Vec::new();
+    let mut has_interpolation = false;
+
+    for part in pair.into_inner() {
+        match part.as_rule() {
+            Rule::string_text => parts.push(StrPart::Literal(decode_escapes(part.as_str())?)),
+            Rule::string_interp => {
+                has_interpolation = true;
+                let expr = parse_data_expr(part.into_inner().next().unwrap(), depth, options)?;
+                parts.push(StrPart::Expr(expr));
+            }
+            _ => return Err(unexpected_rule_error(&part, "string literal fragment")),
+        }
+    }
+
+    if has_interpolation {
+        Ok(DataExpr::StrInterp(parts))
+    } else {
+        let literal = parts
+            .into_iter()
+            .map(|part| match part {
+                StrPart::Literal(s) => s,
+                StrPart::Expr(_) => unreachable!("has_interpolation would be true"),
+            })
+            .collect();
+        Ok(DataExpr::Str(literal))
+    }
+}
+
+/// Decodes the standard escape sequences -- `\n`, `\t`, `\\`, `\"`, and
+/// `\u{...}` -- in a string literal's raw text.
+fn decode_escapes(raw: &str) -> Result<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('\\') => out.push('\\'),
+            Some('"') => out.push('"'),
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err(JtvError::ParseError(
+                        "Invalid \\u escape: expected '{' after \\u".to_string(),
+                    ));
+                }
+                let mut hex = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(h) => hex.push(h),
+                        None => {
+                            return Err(JtvError::ParseError(
+                                "Invalid \\u escape: unterminated \\u{...}".to_string(),
+                            ))
+                        }
+                    }
+                }
+                let code = u32::from_str_radix(&hex, 16)
+                    .map_err(|e| JtvError::ParseError(format!("Invalid \\u escape: {}", e)))?;
+                let ch = char::from_u32(code).ok_or_else(|| {
+                    JtvError::ParseError(format!(
+                        "Invalid \\u escape: {:#x} is not a valid codepoint",
+                        code
+                    ))
+                })?;
+                out.push(ch);
+            }
+            Some(other) => {
+                return Err(JtvError::ParseError(format!(
+                    "Unknown escape sequence: \\{}",
+                    other
+                )))
+            }
+            None => {
+                return Err(JtvError::ParseError(
+                    "Unterminated escape sequence at end of string".to_string(),
+                ))
+            }
+        }
+    }
+
+    Ok(out)
+}
+
 fn parse_number(pair: pest::iterators::Pair<Rule>) -> Result<Number> {
     match pair.as_rule() {
         Rule::integer => {
@@ -428,67 +971,89 @@ fn parse_number(pair: pest::iterators::Pair<Rule>) -> Result<Number> {
     }
 }
 
-fn parse_control_expr(pair: pest::iterators::Pair<Rule>) -> Result<ControlExpr> {
+fn parse_control_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let inner = pair.into_inner().next().unwrap();
 
     match inner.as_rule() {
-        Rule::logical_expr => parse_logical_expr(inner),
-        Rule::comparison_expr => parse_comparison_expr(inner),
-        Rule::data_expr => Ok(ControlExpr::Data(parse_data_expr(inner)?)),
-        _ => Err(JtvError::ParseError(format!(
-            "Unexpected control expression: {:?}",
-            inner.as_rule()
-        ))),
+        Rule::logical_expr => parse_logical_expr(inner, depth, options),
+        Rule::comparison_expr => parse_comparison_expr(inner, depth, options),
+        Rule::data_expr => Ok(ControlExpr::Data(parse_data_expr(inner, depth, options)?)),
+        _ => Err(unexpected_rule_error(&inner, "control expression")),
     }
 }
 
-fn parse_logical_expr(pair: pest::iterators::Pair<Rule>) -> Result<ControlExpr> {
+fn parse_logical_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let mut inner = pair.into_inner();
-    let mut left = parse_logical_term(inner.next().unwrap())?;
+    let mut left = parse_logical_term(inner.next().unwrap(), depth, options)?;
 
     for right_pair in inner {
-        let right = parse_logical_term(right_pair)?;
+        let right = parse_logical_term(right_pair, depth, options)?;
         left = ControlExpr::Logical(Box::new(left), LogicalOp::Or, Box::new(right));
     }
 
     Ok(left)
 }
 
-fn parse_logical_term(pair: pest::iterators::Pair<Rule>) -> Result<ControlExpr> {
+fn parse_logical_term(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let mut inner = pair.into_inner();
-    let mut left = parse_logical_factor(inner.next().unwrap())?;
+    let mut left = parse_logical_factor(inner.next().unwrap(), depth, options)?;
 
     for right_pair in inner {
-        let right = parse_logical_factor(right_pair)?;
+        let right = parse_logical_factor(right_pair, depth, options)?;
         left = ControlExpr::Logical(Box::new(left), LogicalOp::And, Box::new(right));
     }
 
     Ok(left)
 }
 
-fn parse_logical_factor(pair: pest::iterators::Pair<Rule>) -> Result<ControlExpr> {
+fn parse_logical_factor(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let mut inner = pair.into_inner();
     let first = inner.next().unwrap();
 
     match first.as_str() {
         "!" => {
-            let expr = parse_logical_factor(inner.next().unwrap())?;
+            let expr = parse_logical_factor(inner.next().unwrap(), depth, options)?;
             Ok(ControlExpr::Not(Box::new(expr)))
         }
         _ => match first.as_rule() {
-            Rule::comparison_expr => parse_comparison_expr(first),
-            Rule::data_expr => Ok(ControlExpr::Data(parse_data_expr(first)?)),
-            Rule::control_expr => parse_control_expr(first),
-            _ => parse_logical_factor(first),
+            Rule::comparison_expr => parse_comparison_expr(first, depth, options),
+            Rule::data_expr => Ok(ControlExpr::Data(parse_data_expr(first, depth, options)?)),
+            Rule::control_expr => parse_control_expr(first, depth, options),
+            _ => parse_logical_factor(first, depth, options),
         },
     }
 }
 
-fn parse_comparison_expr(pair: pest::iterators::Pair<Rule>) -> Result<ControlExpr> {
+fn parse_comparison_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<ControlExpr> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let mut inner = pair.into_inner();
-    let left = parse_data_expr(inner.next().unwrap())?;
+    let left = parse_data_expr(inner.next().unwrap(), depth, options)?;
     let op = inner.next().unwrap().as_str();
-    let right = parse_data_expr(inner.next().unwrap())?;
+    let right = parse_data_expr(inner.next().unwrap(), depth, options)?;
 
     let comparator = match op {
         "==" => Comparator::Eq,
@@ -507,16 +1072,87 @@ fn parse_comparison_expr(pair: pest::iterators::Pair<Rule>) -> Result<ControlExp
     ))
 }
 
-fn parse_range_expr(pair: pest::iterators::Pair<Rule>) -> Result<RangeExpr> {
+fn parse_range_expr(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<RangeExpr> {
     let mut inner = pair.into_inner();
-    let start = Box::new(parse_data_expr(inner.next().unwrap())?);
-    let end = Box::new(parse_data_expr(inner.next().unwrap())?);
-    let step = inner.next().map(|p| Box::new(parse_data_expr(p).unwrap()));
+    let start = Box::new(parse_data_expr(inner.next().unwrap(), depth, options)?);
+    let end = Box::new(parse_data_expr(inner.next().unwrap(), depth, options)?);
+    let step = inner
+        .next()
+        .map(|p| parse_data_expr(p, depth, options).map(Box::new))
+        .transpose()?;
 
     Ok(RangeExpr { start, end, step })
 }
 
-fn parse_type_annotation(pair: pest::iterators::Pair<Rule>) -> Result<TypeAnnotation> {
+fn parse_match_arm(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<MatchArm> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    let mut parts = pair.into_inner();
+    let pattern = parse_pattern(parts.next().unwrap(), depth, options)?;
+
+    let mut body = Vec::new();
+    for stmt_pair in parts {
+        body.push(parse_control_stmt(stmt_pair, depth, options)?);
+    }
+
+    Ok(MatchArm { pattern, body })
+}
+
+/// Arms are tried in source order and the first matching pattern wins --
+/// the parser doesn't check exhaustiveness, that's left to the
+/// interpreter (or left unchecked, matching how `if`/`while` conditions
+/// aren't checked for totality either). `_` is the universal wildcard;
+/// `[head, ..tail]` binds the list's leading elements positionally plus a
+/// rest binding for whatever remains.
+fn parse_pattern(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<Pattern> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
+    match pair.as_rule() {
+        Rule::pattern => parse_pattern(pair.into_inner().next().unwrap(), depth, options),
+        Rule::number => Ok(Pattern::Number(parse_number(
+            pair.into_inner().next().unwrap(),
+        )?)),
+        Rule::wildcard_pattern => Ok(Pattern::Wildcard),
+        Rule::identifier => Ok(Pattern::Identifier(pair.as_str().to_string())),
+        Rule::tuple_pattern => {
+            let mut elements = Vec::new();
+            for elem in pair.into_inner() {
+                elements.push(parse_pattern(elem, depth, options)?);
+            }
+            Ok(Pattern::Tuple(elements))
+        }
+        Rule::list_pattern => {
+            let mut elements = Vec::new();
+            let mut rest = None;
+            for elem in pair.into_inner() {
+                if elem.as_rule() == Rule::rest_pattern {
+                    rest = Some(elem.into_inner().next().unwrap().as_str().to_string());
+                } else {
+                    elements.push(parse_pattern(elem, depth, options)?);
+                }
+            }
+            Ok(Pattern::List { elements, rest })
+        }
+        _ => Err(unexpected_rule_error(&pair, "pattern")),
+    }
+}
+
+fn parse_type_annotation(
+    pair: pest::iterators::Pair<Rule>,
+    depth: &mut usize,
+    options: &ParseOptions,
+) -> Result<TypeAnnotation> {
+    let _guard = DepthGuard::enter(depth, options.max_nesting_depth)?;
     let inner = pair.into_inner().next().unwrap();
 
     match inner.as_rule() {
@@ -529,7 +1165,13 @@ fn parse_type_annotation(pair: pest::iterators::Pair<Rule>) -> Result<TypeAnnota
                 "Complex" => BasicType::Complex,
                 "Hex" => BasicType::Hex,
                 "Binary" => BasicType::Binary,
-                "Symbolic" => BasicType::Symbolic,
+                "Symbolic" if options.allow_symbolic_numbers => BasicType::Symbolic,
+                "Symbolic" => {
+                    return Err(JtvError::ParseError(
+                        "Symbolic numbers are disabled by ParseOptions::allow_symbolic_numbers"
+                            .to_string(),
+                    ))
+                }
                 "Bool" => BasicType::Bool,
                 "String" => BasicType::String,
                 _ => return Err(JtvError::ParseError(format!("Unknown type: {}", type_str))),
@@ -537,13 +1179,13 @@ fn parse_type_annotation(pair: pest::iterators::Pair<Rule>) -> Result<TypeAnnota
             Ok(TypeAnnotation::Basic(basic))
         }
         Rule::list_type => {
-            let elem_type = parse_type_annotation(inner.into_inner().next().unwrap())?;
+            let elem_type = parse_type_annotation(inner.into_inner().next().unwrap(), depth, options)?;
             Ok(TypeAnnotation::List(Box::new(elem_type)))
         }
         Rule::tuple_type => {
             let mut types = Vec::new();
             for type_pair in inner.into_inner() {
-                types.push(parse_type_annotation(type_pair)?);
+                types.push(parse_type_annotation(type_pair, depth, options)?);
             }
             Ok(TypeAnnotation::Tuple(types))
         }
@@ -556,10 +1198,10 @@ fn parse_type_annotation(pair: pest::iterators::Pair<Rule>) -> Result<TypeAnnota
             let return_type = all_types.last().unwrap();
 
             for i in 0..all_types.len() - 1 {
-                param_types.push(parse_type_annotation(all_types[i].clone())?);
+                param_types.push(parse_type_annotation(all_types[i].clone(), depth, options)?);
             }
 
-            let ret = Box::new(parse_type_annotation(return_type.clone())?);
+            let ret = Box::new(parse_type_annotation(return_type.clone(), depth, options)?);
             Ok(TypeAnnotation::Function(param_types, ret))
         }
         _ => Err(JtvError::ParseError(format!(
@@ -1,5 +1,5 @@
 // Parser tests for Julia the Viper
-use jtv_core::{parse_program, ControlStmt, DataExpr, Number};
+use jtv_core::{parse_program, parse_program_with_limits, ControlStmt, DataExpr, JtvError, Number};
 
 #[test]
 fn test_simple_addition() {
@@ -243,3 +243,23 @@ fn test_security_data_language_only_addition() {
     // This grammatically prevents code injection
     // Even malicious input cannot create loops or conditionals in Data context
 }
+
+#[test]
+fn test_deeply_nested_expression_hits_the_nesting_limit() {
+    // 500 levels of parentheses around a literal would otherwise recurse once per
+    // level while building the AST -- a low limit should reject it cleanly
+    // instead of risking a stack overflow.
+    let nested = "(".repeat(500) + "1" + &")".to_string().repeat(500);
+    let code = format!("x = {}", nested);
+
+    let err = parse_program_with_limits(&code, 32).unwrap_err();
+    assert!(matches!(err, JtvError::NestingTooDeep { .. }));
+}
+
+#[test]
+fn test_reasonable_nesting_still_parses_under_the_default_limit() {
+    let nested = "(".repeat(20) + "1" + &")".to_string().repeat(20);
+    let code = format!("x = {}", nested);
+
+    assert!(parse_program(&code).is_ok());
+}
@@ -0,0 +1,237 @@
+// Debug Adapter Protocol transport and dispatcher for `jtv-debug --dap`
+//
+// Speaks the same `Content-Length: N\r\n\r\n`-framed JSON used by every other
+// DAP backend, so editors (VS Code, Helix, etc.) can drive a Julia the Viper
+// session the same way they drive gdb or lldb. The dispatcher reuses the
+// existing `Debugger` state machine (breakpoints, `Interpreter`, call stack)
+// rather than duplicating it, so the REPL and DAP front ends stay in sync.
+//
+// `continue`/`configurationDone` run to the next verified breakpoint (or
+// watched value change) via `Debugger::run_to_next_breakpoint`; `next`,
+// `stepIn`, and `stepOut` advance one statement via `Debugger::step`, and
+// `stepBack`/`reverseContinue` replay one statement backward via
+// `Debugger::step_back` -- `stopped` fires when one of these leaves the
+// debugger paused, `terminated` once the program has actually finished.
+use crate::Debugger;
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+/// Reads and dispatches DAP requests from `stdin` until `disconnect` or EOF.
+pub fn run(mut debugger: Debugger) -> io::Result<()> {
+    let mut seq: i64 = 1;
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    while let Some(request) = read_message(&mut input)? {
+        let command = request["command"].as_str().unwrap_or("").to_string();
+        let request_seq = request["seq"].as_i64().unwrap_or(0);
+        let arguments = &request["arguments"];
+
+        match command.as_str() {
+            "initialize" => {
+                send_response(
+                    &mut seq,
+                    request_seq,
+                    &command,
+                    true,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsConditionalBreakpoints": true,
+                        "supportsStepBack": true,
+                    }),
+                );
+                send_event(&mut seq, "initialized", json!({}));
+            }
+            "launch" | "attach" => {
+                let loaded = arguments["program"]
+                    .as_str()
+                    .map(|program| debugger.load_file(PathBuf::from(program)));
+                match loaded {
+                    Some(Ok(())) => send_response(&mut seq, request_seq, &command, true, json!({})),
+                    Some(Err(e)) => send_error(&mut seq, request_seq, &command, &e.to_string()),
+                    None => send_error(&mut seq, request_seq, &command, "missing 'program' argument"),
+                }
+            }
+            "setBreakpoints" => {
+                let path = arguments["source"]["path"].as_str().unwrap_or("").to_string();
+                let requested = arguments["breakpoints"].as_array().cloned().unwrap_or_default();
+                debugger.clear_breakpoints();
+                let verified: Vec<Value> = requested
+                    .iter()
+                    .filter_map(|bp| bp["line"].as_u64())
+                    .map(|line| {
+                        let verified = debugger.set_breakpoint(line as usize);
+                        json!({ "verified": verified, "line": line, "source": { "path": path } })
+                    })
+                    .collect();
+                send_response(&mut seq, request_seq, &command, true, json!({ "breakpoints": verified }));
+            }
+            "configurationDone" => {
+                send_response(&mut seq, request_seq, &command, true, json!({}));
+                run_or_stop(&mut debugger, &mut seq);
+            }
+            "threads" => {
+                send_response(
+                    &mut seq,
+                    request_seq,
+                    &command,
+                    true,
+                    json!({ "threads": [{ "id": 1, "name": "main" }] }),
+                );
+            }
+            "stackTrace" => {
+                let frames: Vec<Value> = debugger
+                    .call_stack()
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(i, name)| json!({ "id": i, "name": name, "line": debugger.current_line(), "column": 0 }))
+                    .collect();
+                send_response(
+                    &mut seq,
+                    request_seq,
+                    &command,
+                    true,
+                    json!({ "stackFrames": frames, "totalFrames": frames.len() }),
+                );
+            }
+            "scopes" => {
+                send_response(
+                    &mut seq,
+                    request_seq,
+                    &command,
+                    true,
+                    json!({ "scopes": [{ "name": "Locals", "variablesReference": 1, "expensive": false }] }),
+                );
+            }
+            "variables" => {
+                let vars: Vec<Value> = debugger
+                    .interpreter()
+                    .get_variables()
+                    .into_iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value, "variablesReference": 0 }))
+                    .collect();
+                send_response(&mut seq, request_seq, &command, true, json!({ "variables": vars }));
+            }
+            "continue" => {
+                send_response(&mut seq, request_seq, &command, true, json!({ "allThreadsContinued": true }));
+                run_or_stop(&mut debugger, &mut seq);
+            }
+            "next" | "stepIn" | "stepOut" => {
+                send_response(&mut seq, request_seq, &command, true, json!({}));
+                step_or_stop(&mut debugger, &mut seq);
+            }
+            "stepBack" | "reverseContinue" => {
+                send_response(&mut seq, request_seq, &command, true, json!({}));
+                step_back_or_stop(&mut debugger, &mut seq);
+            }
+            "disconnect" => {
+                send_response(&mut seq, request_seq, &command, true, json!({}));
+                break;
+            }
+            other => send_error(&mut seq, request_seq, other, "unsupported request"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs until the next breakpoint (or watched value change) is hit, or the
+/// program finishes -- the `run_to_next_breakpoint`-backed counterpart of
+/// the old run-to-completion behavior, used by every DAP request that
+/// should actually honor the breakpoints `setBreakpoints` verified instead
+/// of blowing through them.
+fn run_or_stop(debugger: &mut Debugger, seq: &mut i64) {
+    if debugger.run_to_next_breakpoint() {
+        send_event(seq, "stopped", json!({ "reason": "breakpoint", "threadId": 1 }));
+    } else {
+        send_event(seq, "terminated", json!({}));
+    }
+}
+
+/// Advances one statement. `next`/`stepIn`/`stepOut` share this: the
+/// interpreter doesn't expose a pause point inside a function call, so (as
+/// `Debugger::step`'s own doc comment notes) all three behave identically.
+fn step_or_stop(debugger: &mut Debugger, seq: &mut i64) {
+    debugger.step();
+    if debugger.is_finished() {
+        send_event(seq, "terminated", json!({}));
+    } else {
+        send_event(seq, "stopped", json!({ "reason": "step", "threadId": 1 }));
+    }
+}
+
+/// `stepBack`/`reverseContinue`: the debugger only exposes a single reverse
+/// step (`Debugger::step_back`, which replays from the start), not a
+/// reverse run-to-breakpoint, so both DAP requests get that one step.
+fn step_back_or_stop(debugger: &mut Debugger, seq: &mut i64) {
+    debugger.step_back();
+    send_event(seq, "stopped", json!({ "reason": "step", "threadId": 1 }));
+}
+
+fn read_message<R: Read>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut header = Vec::new();
+    loop {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        header.push(byte[0]);
+        if header.ends_with(b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let content_length = String::from_utf8_lossy(&header)
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Length:").map(|v| v.trim().to_string()))
+        .and_then(|v| v.parse::<usize>().ok());
+
+    let Some(len) = content_length else {
+        return Ok(None);
+    };
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok(serde_json::from_slice(&body).ok())
+}
+
+fn send_message(seq: &mut i64, body: Value) {
+    let mut message = body;
+    message["seq"] = json!(*seq);
+    *seq += 1;
+
+    let text = message.to_string();
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", text.len(), text);
+    let _ = stdout.flush();
+}
+
+fn send_response(seq: &mut i64, request_seq: i64, command: &str, success: bool, body: Value) {
+    send_message(
+        seq,
+        json!({
+            "type": "response",
+            "request_seq": request_seq,
+            "success": success,
+            "command": command,
+            "body": body,
+        }),
+    );
+}
+
+fn send_error(seq: &mut i64, request_seq: i64, command: &str, message: &str) {
+    send_response(seq, request_seq, command, false, json!({ "error": message }));
+}
+
+fn send_event(seq: &mut i64, event: &str, body: Value) {
+    send_message(
+        seq,
+        json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }),
+    );
+}
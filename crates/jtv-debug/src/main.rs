@@ -1,26 +1,53 @@
 // SPDX-License-Identifier: PMPL-1.0-or-later
 // Interactive debugger for Julia the Viper with reversibility inspection
 
+mod dap;
+mod render;
+
 use colored::*;
-use jtv_core::{parser::parse_program, Interpreter};
+use jtv_core::{parser::parse_program, Interpreter, Program};
 use rustyline::error::ReadlineError;
 use rustyline::{DefaultEditor, Result};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-struct Debugger {
+/// A breakpoint at a given source line. `verified` mirrors the DAP notion of
+/// the same name: false if the line falls outside the loaded source.
+pub(crate) struct Breakpoint {
+    line: usize,
+    verified: bool,
+    /// A restricted comparison expression (`result > 100`) checked against
+    /// interpreter state before pausing; `None` means unconditional.
+    condition: Option<String>,
+}
+
+/// An expression re-evaluated and printed after every pause. `break_on_change`
+/// additionally stops execution as soon as the value differs from the
+/// previous step.
+struct Watch {
+    expr: String,
+    break_on_change: bool,
+    last_value: Option<String>,
+}
+
+pub(crate) struct Debugger {
     source_file: Option<PathBuf>,
     source_code: String,
-    breakpoints: HashSet<usize>,
+    breakpoints: HashMap<usize, Breakpoint>,
     current_line: usize,
     variables: HashMap<String, String>,
     call_stack: Vec<String>,
     paused: bool,
     interpreter: Interpreter,
+    /// The parsed program being stepped through, and the 1-based index of
+    /// the next statement to execute (`current_line`). `None` until `step`
+    /// or `continue` first starts a session.
+    program: Option<Program>,
+    watches: Vec<Watch>,
 }
 
 impl Debugger {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         let mut interpreter = Interpreter::new();
         interpreter.enable_output_capture();
         interpreter.enable_trace();
@@ -28,16 +55,18 @@ impl Debugger {
         Debugger {
             source_file: None,
             source_code: String::new(),
-            breakpoints: HashSet::new(),
+            breakpoints: HashMap::new(),
             current_line: 0,
             variables: HashMap::new(),
             call_stack: Vec::new(),
             paused: false,
             interpreter,
+            program: None,
+            watches: Vec::new(),
         }
     }
 
-    fn load_file(&mut self, path: PathBuf) -> Result<()> {
+    pub(crate) fn load_file(&mut self, path: PathBuf) -> Result<()> {
         self.source_code = std::fs::read_to_string(&path)
             .map_err(|e| ReadlineError::Io(e))?;
         self.source_file = Some(path);
@@ -45,7 +74,7 @@ impl Debugger {
         Ok(())
     }
 
-    fn run_program(&mut self) {
+    pub(crate) fn run_program(&mut self) {
         if self.source_code.is_empty() {
             println!("{}", "No source file loaded".red());
             return;
@@ -66,40 +95,344 @@ impl Debugger {
                         println!("\n{}", "Program completed successfully".green());
                     }
                     Err(e) => {
-                        println!("{} {}", "Runtime error:".red(), e);
+                        render::render(&self.source_code, &"Runtime error:".red().to_string(), &e);
                     }
                 }
             }
+            Err(e) => {
+                render::render(&self.source_code, &"Parse error:".red().to_string(), &e);
+            }
+        }
+    }
+
+    /// Parses the loaded source and resets the interpreter, ready to step
+    /// through statement by statement. Returns false (and reports why) if
+    /// there's no source or it fails to parse.
+    fn start_execution(&mut self) -> bool {
+        if self.source_code.is_empty() {
+            println!("{}", "No source file loaded".red());
+            return false;
+        }
+        match parse_program(&self.source_code) {
+            Ok(program) => {
+                self.interpreter.reset();
+                self.program = Some(program);
+                self.current_line = 1;
+                self.call_stack.clear();
+                self.paused = true;
+                true
+            }
             Err(e) => {
                 println!("{} {}", "Parse error:".red(), e);
+                false
+            }
+        }
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        match &self.program {
+            Some(program) => self.current_line > program.statements.len(),
+            None => true,
+        }
+    }
+
+    /// Executes exactly the statement at `current_line` and advances past
+    /// it. A top-level statement may itself be a whole loop or function
+    /// body, which the interpreter still runs to completion in one go: the
+    /// AST carries no sub-statement positions, so this is as fine-grained as
+    /// stepping gets without deeper interpreter support. Returns false on a
+    /// runtime error (the debugger stays paused there).
+    fn step_statement(&mut self, announce: bool) -> bool {
+        let statement = match &self.program {
+            Some(program) if self.current_line <= program.statements.len() => {
+                program.statements[self.current_line - 1].clone()
+            }
+            _ => return false,
+        };
+
+        self.call_stack.push(format!("line {}", self.current_line));
+        let result = self.interpreter.run(&Program { statements: vec![statement] });
+        self.call_stack.pop();
+        self.current_line += 1;
+
+        let output = self.interpreter.take_output();
+        if announce && !output.is_empty() {
+            for line in output {
+                println!("  {}", line);
+            }
+        }
+
+        if self.refresh_watches() {
+            self.paused = true;
+        }
+
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                if announce {
+                    println!("{} {}", "Runtime error:".red(), e);
+                }
+                self.paused = true;
+                false
+            }
+        }
+    }
+
+    fn announce_pause(&self) {
+        let start = self.current_line.saturating_sub(3);
+        self.list_source(start, 5);
+        self.list_variables();
+        self.print_watches();
+    }
+
+    /// `step`/`s` and `next`/`n`: advances one statement. The interpreter
+    /// doesn't expose a pause point inside a function call, so (unlike a
+    /// native debugger) the two currently behave identically.
+    pub(crate) fn step(&mut self) {
+        if self.program.is_none() && !self.start_execution() {
+            return;
+        }
+        if self.is_finished() {
+            println!("{}", "Program already completed".yellow());
+            return;
+        }
+        if self.step_statement(true) {
+            if self.is_finished() {
+                self.paused = false;
+                println!("\n{}", "Program completed successfully".green());
+            } else {
+                self.paused = true;
+                self.announce_pause();
+            }
+        }
+    }
+
+    /// `continue`/`c`: runs until the next breakpoint or the program ends.
+    pub(crate) fn run_to_next_breakpoint(&mut self) -> bool {
+        let resuming = self.program.is_some();
+        if !resuming && !self.start_execution() {
+            return false;
+        }
+        if resuming && self.paused && !self.is_finished() {
+            // We're sitting on the breakpoint from the last stop; step past
+            // it first so `continue` always makes forward progress.
+            self.paused = false;
+            if !self.step_statement(true) {
+                return false;
+            }
+            if self.paused {
+                println!("{}", "Stopped: a watched expression changed".yellow());
+                self.announce_pause();
+                return true;
+            }
+        }
+
+        loop {
+            if self.is_finished() {
+                self.paused = false;
+                println!("\n{}", "Program completed successfully".green());
+                return false;
+            }
+            if self.breakpoint_hit() {
+                self.paused = true;
+                println!("{} {}", "Breakpoint hit at line".green(), self.current_line);
+                self.announce_pause();
+                return true;
+            }
+            if !self.step_statement(true) {
+                return false;
+            }
+            if self.paused {
+                println!("{}", "Stopped: a watched expression changed".yellow());
+                self.announce_pause();
+                return true;
+            }
+        }
+    }
+
+    fn breakpoint_hit(&self) -> bool {
+        match self.breakpoints.get(&self.current_line) {
+            Some(bp) => match &bp.condition {
+                // An unevaluable condition (unknown variable, bad syntax)
+                // defaults to pausing, since that's the safer failure mode
+                // for a debugger.
+                Some(condition) => self.eval_condition(condition).unwrap_or(true),
+                None => true,
+            },
+            None => false,
+        }
+    }
+
+    const CONDITION_OPERATORS: [&'static str; 6] = ["==", "!=", ">=", "<=", ">", "<"];
+
+    /// A deliberately small comparison-expression language for breakpoint
+    /// conditions and watches (`result > 100`, `done`). The debugger doesn't
+    /// have its own access to the real JtV expression parser, so conditions
+    /// are restricted to `IDENT`, or `LHS OP RHS` where each side is either a
+    /// variable name or a literal, and OP is one of `== != < <= > >=`.
+    fn eval_condition(&self, expr: &str) -> Option<bool> {
+        let expr = expr.trim();
+        for op in Self::CONDITION_OPERATORS {
+            if let Some(idx) = expr.find(op) {
+                let lhs = self.resolve_operand(expr[..idx].trim())?;
+                let rhs = self.resolve_operand(expr[idx + op.len()..].trim())?;
+                return Some(Self::compare(&lhs, &rhs, op));
             }
         }
+        // A bare identifier (or literal) is truthy if it stringifies to "true".
+        self.resolve_operand(expr).map(|v| v == "true")
+    }
+
+    fn resolve_operand(&self, token: &str) -> Option<String> {
+        match self.interpreter.get_variable(token) {
+            Ok(value) => Some(format!("{}", value)),
+            Err(_) => Some(token.to_string()),
+        }
     }
 
-    fn set_breakpoint(&mut self, line: usize) {
-        self.breakpoints.insert(line);
-        println!("{} {}", "Breakpoint set at line".green(), line);
+    fn compare(lhs: &str, rhs: &str, op: &str) -> bool {
+        if let (Ok(l), Ok(r)) = (lhs.parse::<f64>(), rhs.parse::<f64>()) {
+            return match op {
+                "==" => l == r,
+                "!=" => l != r,
+                ">=" => l >= r,
+                "<=" => l <= r,
+                ">" => l > r,
+                "<" => l < r,
+                _ => false,
+            };
+        }
+        match op {
+            "==" => lhs == rhs,
+            "!=" => lhs != rhs,
+            _ => false,
+        }
+    }
+
+    fn eval_watch_value(&self, expr: &str) -> Option<String> {
+        if Self::CONDITION_OPERATORS.iter().any(|op| expr.contains(op)) {
+            self.eval_condition(expr).map(|b| b.to_string())
+        } else {
+            self.resolve_operand(expr.trim())
+        }
+    }
+
+    pub(crate) fn add_watch(&mut self, expr: String, break_on_change: bool) {
+        let last_value = self.eval_watch_value(&expr);
+        println!("{} {}", "Watching".cyan(), expr);
+        self.watches.push(Watch { expr, break_on_change, last_value });
+    }
+
+    /// Re-evaluates every watch, returning true if a `watch --break`
+    /// expression's value differs from what it was last step.
+    fn refresh_watches(&mut self) -> bool {
+        let mut changed = false;
+        for i in 0..self.watches.len() {
+            let new_value = self.eval_watch_value(&self.watches[i].expr.clone());
+            let watch = &mut self.watches[i];
+            if watch.break_on_change {
+                if let (Some(old), Some(new)) = (&watch.last_value, &new_value) {
+                    if old != new {
+                        changed = true;
+                    }
+                }
+            }
+            watch.last_value = new_value;
+        }
+        changed
+    }
+
+    fn print_watches(&self) {
+        if self.watches.is_empty() {
+            return;
+        }
+        println!("{}", "Watches:".cyan());
+        for watch in &self.watches {
+            let value = watch.last_value.clone().unwrap_or_else(|| "<unresolved>".to_string());
+            println!("  {} = {}", watch.expr.cyan(), value);
+        }
+    }
+
+    /// `back`/`rstep`: undoes the last executed statement. The interpreter
+    /// keeps no incremental undo log, but execution here is deterministic
+    /// and free of external side effects besides captured output, so
+    /// replaying the program from scratch up to the previous statement
+    /// reconstructs the prior variable environment and call stack exactly.
+    pub(crate) fn step_back(&mut self) {
+        if self.program.is_none() || self.current_line <= 1 {
+            println!("{}", "Already at the start of the program".yellow());
+            return;
+        }
+        let target = self.current_line - 1;
+        self.interpreter.reset();
+        self.call_stack.clear();
+        self.current_line = 1;
+        self.paused = true;
+        while self.current_line < target {
+            if !self.step_statement(false) {
+                break;
+            }
+        }
+        self.interpreter.take_output(); // discard replayed output
+        self.announce_pause();
+    }
+
+    /// Records a breakpoint and reports whether it lands inside the loaded
+    /// source (DAP calls this "verified"; the REPL just prints either way).
+    pub(crate) fn set_breakpoint(&mut self, line: usize) -> bool {
+        self.set_conditional_breakpoint(line, None)
+    }
+
+    fn set_conditional_breakpoint(&mut self, line: usize, condition: Option<String>) -> bool {
+        let verified = line >= 1 && line <= self.source_code.lines().count();
+        self.breakpoints.insert(line, Breakpoint { line, verified, condition: condition.clone() });
+        if verified {
+            match &condition {
+                Some(cond) => println!("{} {} {} {}", "Breakpoint set at line".green(), line, "if".green(), cond),
+                None => println!("{} {}", "Breakpoint set at line".green(), line),
+            }
+        } else {
+            println!("{} {}", "Breakpoint set at line (outside loaded source):".yellow(), line);
+        }
+        verified
     }
 
     fn delete_breakpoint(&mut self, line: usize) {
-        if self.breakpoints.remove(&line) {
+        if self.breakpoints.remove(&line).is_some() {
             println!("{} {}", "Breakpoint removed from line".green(), line);
         } else {
             println!("{}", "No breakpoint at that line".yellow());
         }
     }
 
+    pub(crate) fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
     fn list_breakpoints(&self) {
         if self.breakpoints.is_empty() {
             println!("{}", "No breakpoints set".yellow());
         } else {
             println!("{}", "Breakpoints:".cyan());
-            for line in &self.breakpoints {
-                println!("  Line {}", line);
+            for bp in self.breakpoints.values() {
+                let status = if bp.verified { "" } else { " (unverified)" };
+                println!("  Line {}{}", bp.line, status);
             }
         }
     }
 
+    pub(crate) fn call_stack(&self) -> &[String] {
+        &self.call_stack
+    }
+
+    pub(crate) fn current_line(&self) -> usize {
+        self.current_line
+    }
+
+    pub(crate) fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
     fn list_source(&self, start: usize, count: usize) {
         if self.source_code.is_empty() {
             println!("{}", "No source file loaded".red());
@@ -111,7 +444,9 @@ impl Debugger {
 
         for (i, line) in lines.iter().enumerate().skip(start).take(end - start) {
             let line_num = i + 1;
-            let bp_marker = if self.breakpoints.contains(&line_num) {
+            let bp_marker = if self.paused && line_num == self.current_line {
+                ">".green().bold()
+            } else if self.breakpoints.contains_key(&line_num) {
                 "â—".red()
             } else {
                 " ".normal()
@@ -154,12 +489,18 @@ impl Debugger {
     fn show_help(&self) {
         println!("\n{}", "Julia the Viper Debugger Commands:".bold().cyan());
         println!("  {}              - Run the loaded program", "run".green());
-        println!("  {}        - Set breakpoint at line N", "break N".green());
+        println!("  {}         - Run/resume until the next breakpoint", "continue|c".green());
+        println!("  {}             - Execute the next statement", "step|s".green());
+        println!("  {}             - Execute the next statement (alias of step)", "next|n".green());
+        println!("  {}          - Undo the last executed statement", "back|rstep".green());
+        println!("  {}        - Set breakpoint at line N, optionally conditional", "break N [if COND]".green());
         println!("  {}       - Delete breakpoint at line N", "delete N".green());
         println!("  {}             - List all breakpoints", "breakpoints".green());
         println!("  {}      - List source code (from line N, M lines)", "list [N] [M]".green());
         println!("  {}         - Print variable value", "print VAR".green());
         println!("  {}          - List all variables", "locals".green());
+        println!("  {}  - Watch EXPR, optionally breaking when it changes", "watch [--break] EXPR".green());
+        println!("  {}            - List active watches and their values", "watches".green());
         println!("  {}            - Show execution trace", "trace".green());
         println!("  {}      - Load source file", "load FILE".green());
         println!("  {}            - Reset interpreter state", "reset".green());
@@ -170,21 +511,31 @@ impl Debugger {
 }
 
 fn main() -> Result<()> {
-    println!("{}", "Julia the Viper Interactive Debugger".bold().cyan());
-    println!("{}", "Type 'help' for commands\n".yellow());
+    let args: Vec<String> = std::env::args().collect();
+    let dap_mode = args.iter().any(|a| a == "--dap");
 
     let mut debugger = Debugger::new();
-    let mut editor = DefaultEditor::new()?;
 
-    // Check for source file argument
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        let path = PathBuf::from(&args[1]);
+    // A positional source file argument works the same in both modes; in
+    // `--dap` mode a DAP `launch` request can also supply it via `program`.
+    if let Some(path) = args.iter().skip(1).find(|a| *a != "--dap") {
+        let path = PathBuf::from(path);
         if let Err(e) = debugger.load_file(path) {
-            println!("{} {}", "Failed to load file:".red(), e);
+            if !dap_mode {
+                println!("{} {}", "Failed to load file:".red(), e);
+            }
         }
     }
 
+    if dap_mode {
+        return dap::run(debugger).map_err(ReadlineError::Io);
+    }
+
+    println!("{}", "Julia the Viper Interactive Debugger".bold().cyan());
+    println!("{}", "Type 'help' for commands\n".yellow());
+
+    let mut editor = DefaultEditor::new()?;
+
     loop {
         let prompt = "jtv-debug> ";
         match editor.readline(prompt) {
@@ -199,15 +550,25 @@ fn main() -> Result<()> {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 match parts.get(0).map(|s| *s) {
                     Some("run") => debugger.run_program(),
+                    Some("continue") | Some("c") => {
+                        debugger.run_to_next_breakpoint();
+                    }
+                    Some("step") | Some("s") | Some("next") | Some("n") => debugger.step(),
+                    Some("back") | Some("rstep") => debugger.step_back(),
                     Some("break") | Some("b") => {
-                        if let Some(line_str) = parts.get(1) {
-                            if let Ok(line_num) = line_str.parse::<usize>() {
-                                debugger.set_breakpoint(line_num);
-                            } else {
-                                println!("{}", "Invalid line number".red());
-                            }
+                        let rest = line.splitn(2, char::is_whitespace).nth(1).unwrap_or("").trim();
+                        if rest.is_empty() {
+                            println!("{}", "Usage: break N [if CONDITION]".yellow());
                         } else {
-                            println!("{}", "Usage: break N".yellow());
+                            let mut halves = rest.splitn(2, " if ");
+                            let line_str = halves.next().unwrap_or("").trim();
+                            let condition = halves.next().map(|c| c.trim().to_string());
+                            match line_str.parse::<usize>() {
+                                Ok(line_num) => {
+                                    debugger.set_conditional_breakpoint(line_num, condition);
+                                }
+                                Err(_) => println!("{}", "Invalid line number".red()),
+                            }
                         }
                     }
                     Some("delete") | Some("d") => {
@@ -235,6 +596,19 @@ fn main() -> Result<()> {
                         }
                     }
                     Some("locals") => debugger.list_variables(),
+                    Some("watch") => {
+                        let rest = line.splitn(2, char::is_whitespace).nth(1).unwrap_or("").trim();
+                        if rest.is_empty() {
+                            println!("{}", "Usage: watch [--break] EXPR".yellow());
+                        } else {
+                            let (break_on_change, expr) = match rest.strip_prefix("--break") {
+                                Some(remainder) => (true, remainder.trim().to_string()),
+                                None => (false, rest.to_string()),
+                            };
+                            debugger.add_watch(expr, break_on_change);
+                        }
+                    }
+                    Some("watches") => debugger.print_watches(),
                     Some("trace") | Some("t") => debugger.show_trace(),
                     Some("load") => {
                         if let Some(file) = parts.get(1) {
@@ -274,3 +648,197 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `Debugger` with `source` loaded directly into `source_code`,
+    /// bypassing `load_file` so tests don't need a real file on disk. Each
+    /// line must be exactly one top-level statement, since `step`/breakpoint
+    /// logic indexes `program.statements` by `current_line`.
+    fn debugger_with_source(source: &str) -> Debugger {
+        let mut debugger = Debugger::new();
+        debugger.source_code = source.to_string();
+        debugger
+    }
+
+    const COUNT_UP: &str = "x = 1\nx = x + 1\nx = x + 1";
+
+    #[test]
+    fn test_step_executes_one_statement_at_a_time() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+
+        debugger.step();
+        assert_eq!(debugger.current_line(), 2);
+        assert_eq!(debugger.interpreter().get_variable("x").unwrap().to_string(), "1");
+
+        debugger.step();
+        assert_eq!(debugger.current_line(), 3);
+        assert_eq!(debugger.interpreter().get_variable("x").unwrap().to_string(), "2");
+
+        assert!(!debugger.is_finished());
+        debugger.step();
+        assert!(debugger.is_finished());
+        assert_eq!(debugger.interpreter().get_variable("x").unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn test_step_on_a_finished_program_does_not_advance_further() {
+        let mut debugger = debugger_with_source("x = 1");
+        debugger.step();
+        assert!(debugger.is_finished());
+
+        debugger.step();
+        assert_eq!(debugger.current_line(), 2);
+    }
+
+    #[test]
+    fn test_run_to_next_breakpoint_stops_at_an_unconditional_breakpoint() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.set_breakpoint(2);
+
+        let stopped = debugger.run_to_next_breakpoint();
+
+        assert!(stopped);
+        assert_eq!(debugger.current_line(), 2);
+        assert!(!debugger.is_finished());
+    }
+
+    #[test]
+    fn test_run_to_next_breakpoint_runs_to_completion_with_no_breakpoints() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+
+        let stopped = debugger.run_to_next_breakpoint();
+
+        assert!(!stopped);
+        assert!(debugger.is_finished());
+    }
+
+    #[test]
+    fn test_run_to_next_breakpoint_resumes_past_the_breakpoint_it_stopped_on() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.set_breakpoint(2);
+
+        assert!(debugger.run_to_next_breakpoint());
+        assert_eq!(debugger.current_line(), 2);
+
+        // `continue` again should step past the breakpoint it's sitting on
+        // rather than hitting it again immediately, and (with no further
+        // breakpoints set) run the rest of the program to completion.
+        let stopped = debugger.run_to_next_breakpoint();
+        assert!(!stopped);
+        assert!(debugger.is_finished());
+    }
+
+    #[test]
+    fn test_conditional_breakpoint_is_skipped_while_its_condition_is_false() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.set_conditional_breakpoint(2, Some("x > 1".to_string()));
+
+        // `x` is 1 at line 2, so the condition is false there; the
+        // breakpoint should be skipped and the program should finish.
+        let stopped = debugger.run_to_next_breakpoint();
+        assert!(!stopped);
+        assert!(debugger.is_finished());
+    }
+
+    #[test]
+    fn test_conditional_breakpoint_stops_once_its_condition_becomes_true() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.set_conditional_breakpoint(3, Some("x >= 2".to_string()));
+
+        let stopped = debugger.run_to_next_breakpoint();
+        assert!(stopped);
+        assert_eq!(debugger.current_line(), 3);
+    }
+
+    #[test]
+    fn test_set_breakpoint_reports_unverified_outside_loaded_source() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+
+        assert!(!debugger.set_breakpoint(100));
+        assert!(debugger.set_breakpoint(1));
+    }
+
+    #[test]
+    fn test_compare_numeric_operators() {
+        assert!(Debugger::compare("2", "1", ">"));
+        assert!(!Debugger::compare("2", "1", "<"));
+        assert!(Debugger::compare("2", "2", ">="));
+        assert!(Debugger::compare("2", "2", "<="));
+        assert!(Debugger::compare("2", "2", "=="));
+        assert!(Debugger::compare("2", "3", "!="));
+    }
+
+    #[test]
+    fn test_compare_falls_back_to_string_equality_for_non_numeric_operands() {
+        assert!(Debugger::compare("done", "done", "=="));
+        assert!(!Debugger::compare("done", "pending", "=="));
+        // Non-numeric operands have no ordering, so these report false
+        // rather than panicking on the failed `f64` parse.
+        assert!(!Debugger::compare("done", "pending", ">"));
+    }
+
+    #[test]
+    fn test_resolve_operand_prefers_a_bound_variable_over_the_literal_token() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.step();
+
+        assert_eq!(debugger.resolve_operand("x").as_deref(), Some("1"));
+        // An unbound name falls back to being treated as a literal token.
+        assert_eq!(debugger.resolve_operand("y").as_deref(), Some("y"));
+    }
+
+    #[test]
+    fn test_eval_condition_handles_bare_identifiers_as_truthy_checks() {
+        let debugger = debugger_with_source(COUNT_UP);
+        assert_eq!(debugger.eval_condition("true"), Some(true));
+        assert_eq!(debugger.eval_condition("false"), Some(false));
+    }
+
+    #[test]
+    fn test_watch_break_on_change_stops_execution_once_the_value_differs() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.add_watch("x".to_string(), true);
+
+        // Before the first statement `x` is unbound, so the watch resolves
+        // to the bare token "x"; binding it to `1` counts as a change and
+        // should pause execution right after it runs, even with no
+        // breakpoints set.
+        let stopped = debugger.run_to_next_breakpoint();
+        assert!(stopped);
+        assert_eq!(debugger.current_line(), 2);
+    }
+
+    #[test]
+    fn test_watch_without_break_on_change_does_not_stop_execution() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.add_watch("x".to_string(), false);
+
+        let stopped = debugger.run_to_next_breakpoint();
+        assert!(!stopped);
+        assert!(debugger.is_finished());
+    }
+
+    #[test]
+    fn test_step_back_replays_to_the_previous_statement() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.step();
+        debugger.step();
+        assert_eq!(debugger.current_line(), 3);
+        assert_eq!(debugger.interpreter().get_variable("x").unwrap().to_string(), "2");
+
+        debugger.step_back();
+
+        assert_eq!(debugger.current_line(), 2);
+        assert_eq!(debugger.interpreter().get_variable("x").unwrap().to_string(), "1");
+    }
+
+    #[test]
+    fn test_step_back_at_the_start_of_the_program_is_a_no_op() {
+        let mut debugger = debugger_with_source(COUNT_UP);
+        debugger.step_back();
+        assert_eq!(debugger.current_line(), 0);
+    }
+}
@@ -0,0 +1,48 @@
+// Source-context error rendering, in the style of Rhai's error printer: a
+// small window of surrounding lines with a `^` caret under the offending
+// column, instead of a bare one-line message.
+use colored::*;
+use jtv_core::JtvError;
+
+const CONTEXT_LINES: usize = 2;
+
+/// Prints `err` under `label`. Falls back to the plain message when the
+/// error carries no (line, column) position.
+pub fn render(source: &str, label: &str, err: &JtvError) {
+    match err.position() {
+        Some((line, column)) => render_window(source, label, &err.to_string(), line, column),
+        None => println!("{} {}", label, err),
+    }
+}
+
+fn render_window(source: &str, label: &str, message: &str, line: usize, column: usize) {
+    println!("{} {}", label, message);
+
+    let lines: Vec<&str> = source.lines().collect();
+    if line == 0 || line > lines.len() {
+        return;
+    }
+
+    let start = line.saturating_sub(CONTEXT_LINES).max(1);
+    let end = (line + CONTEXT_LINES).min(lines.len());
+    let gutter_width = end.to_string().len();
+
+    for lineno in start..=end {
+        let text = lines[lineno - 1];
+        let marker_plain = if lineno == line { "> " } else { "  " };
+        // Measured without color codes, since those inflate `.chars().count()`
+        // and would throw off the caret's column alignment below.
+        let plain_prefix = format!("{}{:>width$} ", marker_plain, lineno, width = gutter_width);
+        let marker = if lineno == line {
+            marker_plain.red().bold()
+        } else {
+            marker_plain.normal()
+        };
+        println!("{}{:>width$} {}", marker, lineno, text, width = gutter_width);
+
+        if lineno == line {
+            let offset = plain_prefix.chars().count() + column.saturating_sub(1);
+            println!("{}{}", " ".repeat(offset), "^".red().bold());
+        }
+    }
+}
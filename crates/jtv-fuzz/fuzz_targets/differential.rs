@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+#![no_main]
+
+use jtv_fuzz::{program_from_bytes, run_differential};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(program) = program_from_bytes(data) else {
+        return;
+    };
+    if let Err(divergence) = run_differential(&program) {
+        panic!(
+            "interpreter/WASM backend diverged:\n--- source ---\n{}\n--- detail ---\n{}",
+            divergence.source, divergence.detail
+        );
+    }
+});
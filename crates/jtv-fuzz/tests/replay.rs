@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Deterministic replay of the differential-fuzzing corpus: every file under
+// `corpus/` is raw bytes `cargo fuzz` once found interesting (including any
+// that previously triggered a divergence), so re-running them here turns
+// each discovered bug into a permanent regression fixture without needing
+// `cargo fuzz` itself in CI.
+use jtv_fuzz::{program_from_bytes, run_differential};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn corpus_replays_without_divergence() {
+    let corpus_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let Ok(entries) = fs::read_dir(&corpus_dir) else {
+        // No corpus collected yet; nothing to replay.
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let data = fs::read(&path).unwrap_or_else(|e| panic!("reading {:?}: {}", path, e));
+        let Ok(program) = program_from_bytes(&data) else {
+            continue;
+        };
+        if let Err(divergence) = run_differential(&program) {
+            panic!(
+                "corpus file {:?} reproduces a divergence:\n--- source ---\n{}\n--- detail ---\n{}",
+                path, divergence.source, divergence.detail
+            );
+        }
+    }
+}
+
+#[test]
+fn simple_addition_program_agrees() {
+    // A minimal hand-built program, independent of any byte-level fuzzer
+    // encoding, as a smoke test that `run_differential` itself is wired up
+    // correctly before trusting corpus replay to catch real bugs.
+    use jtv_lang::ast::{Assignment, ControlStmt, DataExpr, Expr, Number, Program, TopLevel};
+
+    let program = Program {
+        statements: vec![TopLevel::Control(ControlStmt::Assignment(Assignment {
+            target: "x".to_string(),
+            value: Expr::Data(DataExpr::Add(
+                Box::new(DataExpr::Number(Number::Int(5))),
+                Box::new(DataExpr::Number(Number::Int(3))),
+            )),
+        }))],
+    };
+
+    assert!(run_differential(&program).is_ok());
+}
@@ -0,0 +1,489 @@
+// Abstract Syntax Tree for Julia the Viper
+use serde::{Serialize, Deserialize};
+
+/// A byte-offset range into the source text, plus the 1-based (line, col)
+/// of its start, so a diagnostic can point at an exact location without
+/// re-scanning the source to recover it. `start`/`end` are byte offsets
+/// (not char offsets), consistent with `str::len`/slicing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub col: u32,
+}
+
+impl Span {
+    /// A span with no real source location -- for AST nodes built
+    /// programmatically (tests, `Program::new()`) rather than parsed from
+    /// text.
+    pub fn unknown() -> Self {
+        Span { start: 0, end: 0, line: 0, col: 0 }
+    }
+
+    /// Computes the `Span` of the byte range `[start, end)` in `source`,
+    /// walking it once to find `start`'s line and column. `line`/`col` are
+    /// 1-based; `end` is left a plain byte offset since multi-line spans
+    /// don't have a single meaningful "end column".
+    pub fn from_offsets(source: &str, start: usize, end: usize) -> Self {
+        let mut line = 1u32;
+        let mut col = 1u32;
+        for ch in source[..start.min(source.len())].chars() {
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        Span { start, end, line, col }
+    }
+
+    /// The text of the source line this span starts on, for rendering a
+    /// diagnostic's source-context excerpt. `None` if `line` is out of
+    /// range for `source` (e.g. an `unknown()` span).
+    pub fn source_line<'a>(&self, source: &'a str) -> Option<&'a str> {
+        source.lines().nth(self.line.checked_sub(1)? as usize)
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Span::unknown()
+    }
+}
+
+/// Pairs an AST node with the [`Span`] of source it was parsed from.
+/// Nodes that don't carry a span field of their own (most `DataExpr`/
+/// `ControlStmt` variants, for now -- see `crate::diagnostics`) can still
+/// be given one by a caller wrapping them in `Spanned<T>` rather than every
+/// variant needing its own field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Spanned { node, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Program {
+    pub statements: Vec<TopLevel>,
+    /// Covers the whole parsed source. `Span::unknown()` for a
+    /// programmatically-built `Program` (see `Program::new()`).
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TopLevel {
+    Module(ModuleDecl),
+    Import(ImportStmt),
+    Function(FunctionDecl),
+    Struct(StructDecl),
+    Test(TestDecl),
+    Control(ControlStmt),
+}
+
+/// Comments and blank lines attached to a top-level declaration, so the
+/// formatter can re-emit them at their original anchor instead of
+/// silently dropping them. Populated by the parser from comment tokens
+/// immediately surrounding the declaration; empty/default for anything
+/// built programmatically.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Trivia {
+    /// `//` comments on their own line(s) immediately before the
+    /// declaration, in source order, with the leading `//` stripped.
+    pub leading_comments: Vec<String>,
+    /// A `//` comment on the same line as the declaration's opening
+    /// line (e.g. `fn add(a: Int, b: Int): Int { // adds two ints`),
+    /// with the leading `//` stripped.
+    pub trailing_comment: Option<String>,
+    /// Whether the user left a blank line between this declaration and
+    /// the previous one. Runs of 2+ blank lines collapse to this single
+    /// bool, matching the formatter's own one-blank-line output.
+    pub blank_line_before: bool,
+}
+
+/// A named record type, e.g. `struct Complex3D { re: Float, im: Float, w: Symbolic }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructDecl {
+    pub name: String,
+    pub fields: Vec<(String, TypeAnnotation)>,
+    #[serde(default)]
+    pub trivia: Trivia,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleDecl {
+    pub name: String,
+    pub body: Vec<TopLevel>,
+    #[serde(default)]
+    pub trivia: Trivia,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportStmt {
+    pub path: Vec<String>,
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub trivia: Trivia,
+}
+
+// ===== CONTROL LANGUAGE (Turing-complete) =====
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ControlStmt {
+    Assignment(Assignment),
+    If(IfStmt),
+    While(WhileStmt),
+    For(ForStmt),
+    Return(Option<DataExpr>),
+    Print(Vec<DataExpr>),
+    ReverseBlock(ReverseBlock),
+    Block(Vec<ControlStmt>),
+    /// Exit the nearest enclosing `While`/`For`. The optional label is for
+    /// breaking an *outer* loop from inside a nested one; `WhileStmt`/
+    /// `ForStmt` don't carry a label of their own yet, so a labeled
+    /// `Break` has no loop to resolve against today -- callers should
+    /// treat `Some(_)` as "unsupported" until loop labels land, the same
+    /// as an unlabeled `Break` outside any loop.
+    Break(Option<String>),
+    /// Skip to the next iteration of the nearest enclosing `While`/`For`.
+    /// See `Break`'s doc comment for the label's current (non-)support.
+    Continue(Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub target: String,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IfStmt {
+    pub condition: ControlExpr,
+    pub then_branch: Vec<ControlStmt>,
+    pub else_branch: Option<Vec<ControlStmt>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhileStmt {
+    pub condition: ControlExpr,
+    pub body: Vec<ControlStmt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForStmt {
+    pub variable: String,
+    pub range: RangeExpr,
+    pub body: Vec<ControlStmt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReverseBlock {
+    pub body: Vec<ReversibleStmt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReversibleStmt {
+    AddAssign(String, DataExpr),  // x += expr
+    SubAssign(String, DataExpr),  // x -= expr (auto-generated in reverse)
+    /// `x *= expr`. Only reversible under strict conditions -- see
+    /// `crate::reversible::RecordedOp::MulAssign`'s doc comment -- so a
+    /// multiplier of zero, or (for `Value::Int`) a non-exact reverse
+    /// division, is rejected at the point it would actually occur rather
+    /// than silently losing information.
+    MulAssign(String, DataExpr),
+    /// `x /= expr` (auto-generated in reverse, the inverse of `MulAssign`).
+    DivAssign(String, DataExpr),
+    /// `x = expr` inside a reverse block -- unlike `AddAssign`/`SubAssign`,
+    /// this has no algebraic inverse, so it's reversed Bennett-style: the
+    /// target's previous value is snapshotted into `RecordedOp::Store`
+    /// before the overwrite and simply restored on the way back. See
+    /// `crate::reversible::RecordedOp::Store`'s doc comment.
+    Assign(String, DataExpr),
+    If(IfStmt),
+    /// `for var in from..to [step s] { body }` inside a reverse block.
+    /// `from`/`to`/`step` are evaluated once, before the first iteration,
+    /// so the reverse pass can replay the recorded iteration count even if
+    /// `body` mutates something `from`/`to`/`step` would otherwise read.
+    For {
+        var: String,
+        from: DataExpr,
+        to: DataExpr,
+        step: Option<DataExpr>,
+        body: Vec<ReversibleStmt>,
+    },
+    /// `switch scrutinee { case1 => { .. } case2 => { .. } default => { .. } }`
+    /// inside a reverse block, the recorded-arm analogue of `If` for
+    /// multi-way branching. Cases are tried in order, comparing each
+    /// case's value against `scrutinee` with `Value::eq`; `default`, if
+    /// present, is conceptually the last arm and only runs when no case
+    /// matched.
+    Switch {
+        scrutinee: DataExpr,
+        cases: Vec<(DataExpr, Vec<ReversibleStmt>)>,
+        default: Option<Vec<ReversibleStmt>>,
+    },
+}
+
+// ===== EXPRESSIONS =====
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expr {
+    Data(DataExpr),
+    Control(ControlExpr),
+}
+
+// DATA LANGUAGE (Total, addition-only)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DataExpr {
+    Number(Number),
+    Identifier(String),
+    Add(Box<DataExpr>, Box<DataExpr>),
+    Negate(Box<DataExpr>),
+    FunctionCall(FunctionCall),
+    List(Vec<DataExpr>),
+    Tuple(Vec<DataExpr>),
+    /// `point.x` — access a named field of a struct value.
+    FieldAccess(Box<DataExpr>, String),
+    /// `point[1]` — read the element of a List or Tuple at `index`.
+    Index(Box<DataExpr>, Box<DataExpr>),
+    /// `Complex3D { re: 1.0, im: 2.0, w: x }` — construct a struct value.
+    StructLiteral(String, Vec<(String, DataExpr)>),
+    /// `[x + 1 for x in numbers if x > 0]` — build a list from one or more
+    /// chained generator clauses, optionally filtered.
+    ListComprehension(Comprehension),
+}
+
+/// A list comprehension: evaluate `body` once per combination of its
+/// generator clauses, in order, keeping only the results for which
+/// `condition` (if present) holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comprehension {
+    pub body: Box<DataExpr>,
+    /// `(variable, source)` pairs, evaluated left-to-right so a later
+    /// generator's source expression may reference an earlier variable —
+    /// e.g. `[x * y for x in xs for y in ys]`.
+    pub generators: Vec<(String, DataExpr)>,
+    pub condition: Option<Box<ControlExpr>>,
+}
+
+// CONTROL EXPRESSIONS (can have side effects)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ControlExpr {
+    Data(DataExpr),
+    Comparison(Box<DataExpr>, Comparator, Box<DataExpr>),
+    Logical(Box<ControlExpr>, LogicalOp, Box<ControlExpr>),
+    Not(Box<ControlExpr>),
+    /// `x in [1, 2, 3]` — true when `left` equals any element of `right`,
+    /// a `Value::List` or `Value::Tuple`. A reusable containment primitive
+    /// generalizing the fixed `Comparator` set to collections.
+    Contains(Box<DataExpr>, Box<DataExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LogicalOp {
+    And,
+    Or,
+}
+
+/// A `test "name" { ... }` block. `jtv test` discovers these among a
+/// file's top-level items and runs each `body` in its own fresh
+/// `Interpreter`, so state assigned in one test (or left over from the
+/// module's own top-level `Control` statements) can never leak into the
+/// next one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestDecl {
+    pub name: String,
+    /// Set by the `pure` modifier (`pure test "name" { ... }`): the
+    /// runner checks `body` against `PurityChecker` before executing it,
+    /// so a test that claims purity but isn't actually fails instead of
+    /// silently passing.
+    pub pure: bool,
+    pub body: Vec<ControlStmt>,
+    /// From the `test` keyword through the closing `}` of `body` --
+    /// what a failing assertion's diagnostic points at when the
+    /// interpreter can't attribute the failure to a narrower span.
+    /// `Span::unknown()` for a programmatically-built `TestDecl`.
+    pub span: Span,
+    #[serde(default)]
+    pub trivia: Trivia,
+}
+
+// ===== FUNCTIONS =====
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDecl {
+    pub name: String,
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<Param>,
+    pub return_type: Option<TypeAnnotation>,
+    pub purity: Purity,
+    pub body: Vec<ControlStmt>,
+    /// From the `fn` keyword through the closing `}` of `body`.
+    /// `Span::unknown()` for a programmatically-built `FunctionDecl`.
+    pub span: Span,
+    #[serde(default)]
+    pub trivia: Trivia,
+}
+
+/// A declared generic, e.g. the `T` in `fn id<T>(x: T) -> T` or the
+/// `T: Numeric` in `fn double<T: Numeric>(x: T) -> T`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeParam {
+    pub name: String,
+    pub bound: Option<TypeBound>,
+}
+
+/// A restriction on which concrete types a [`TypeParam`] may be
+/// instantiated with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TypeBound {
+    /// Any of the 7 number systems (Int, Float, Rational, Complex, Hex,
+    /// Binary, Symbolic) — written `T: Numeric`.
+    Numeric,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Purity {
+    Pure,    // @pure - no loops, no IO
+    Total,   // @total - guaranteed to terminate
+    Impure,  // default - may loop, may have side effects
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Param {
+    pub name: String,
+    pub type_annotation: Option<TypeAnnotation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    pub args: Vec<DataExpr>,
+}
+
+// ===== TYPE SYSTEM =====
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TypeAnnotation {
+    Basic(BasicType),
+    List(Box<TypeAnnotation>),
+    Tuple(Vec<TypeAnnotation>),
+    Function(Vec<TypeAnnotation>, Box<TypeAnnotation>),
+    /// A reference to an enclosing function's declared type parameter, e.g.
+    /// the `T` in `fn id<T>(x: T) -> T`.
+    Generic(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BasicType {
+    Int,
+    Float,
+    Rational,
+    Complex,
+    Hex,
+    Binary,
+    Symbolic,
+    Bool,
+    String,
+}
+
+// ===== LITERALS =====
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+    Rational(i64, i64),  // numerator, denominator
+    Complex(f64, f64),   // real, imaginary
+    Hex(String),
+    Binary(String),
+    Symbolic(String),    // For symbolic math (e.g., "x", "pi")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeExpr {
+    pub start: Box<DataExpr>,
+    pub end: Box<DataExpr>,
+    pub step: Option<Box<DataExpr>>,
+}
+
+pub mod walk;
+
+// ===== VISITOR PATTERN FOR TRAVERSAL =====
+
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program);
+    fn visit_control_stmt(&mut self, stmt: &ControlStmt);
+    fn visit_data_expr(&mut self, expr: &DataExpr);
+    fn visit_function_decl(&mut self, func: &FunctionDecl);
+}
+
+impl Program {
+    pub fn new() -> Self {
+        Program { statements: vec![], span: Span::unknown() }
+    }
+
+    pub fn accept<V: Visitor>(&self, visitor: &mut V) {
+        visitor.visit_program(self);
+    }
+}
+
+impl Default for Program {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Helper functions for AST construction
+impl DataExpr {
+    pub fn add(left: DataExpr, right: DataExpr) -> Self {
+        DataExpr::Add(Box::new(left), Box::new(right))
+    }
+
+    pub fn negate(expr: DataExpr) -> Self {
+        DataExpr::Negate(Box::new(expr))
+    }
+
+    pub fn number(n: Number) -> Self {
+        DataExpr::Number(n)
+    }
+
+    pub fn identifier(name: impl Into<String>) -> Self {
+        DataExpr::Identifier(name.into())
+    }
+}
+
+impl Number {
+    pub fn int(n: i64) -> Self {
+        Number::Int(n)
+    }
+
+    pub fn float(n: f64) -> Self {
+        Number::Float(n)
+    }
+
+    pub fn rational(num: i64, den: i64) -> Self {
+        Number::Rational(num, den)
+    }
+
+    pub fn complex(real: f64, imag: f64) -> Self {
+        Number::Complex(real, imag)
+    }
+}
@@ -0,0 +1,382 @@
+// Julia the Viper - Generic AST traversal
+//
+// `crate::ast::Visitor` (see `mod.rs`) is a fixed four-method interface a
+// pass implements once and drives through `Program::accept`. This module is
+// the opposite shape: a free `walk_*` function per node kind that takes a
+// plain closure, for a one-off query or rewrite that doesn't want to define
+// a whole `Visitor` impl just to ask "does this contain a `Print`?" or "fold
+// every constant-foldable node".
+//
+// Every `walk_*` function returns `bool`, with the same meaning the closure
+// itself uses: `true` means "keep walking", `false` means "stop here and
+// everywhere else" -- not just this subtree. A `find`-style closure returns
+// `false` the moment it has its answer, and that `false` unwinds the whole
+// call stack without visiting the rest of the tree, however large, which is
+// what makes "does this function body contain any `Print`?" or "find the
+// node at cursor" short-circuit instead of always walking to the end.
+//
+// Each node kind only recurses into children of its *own* kind -- e.g.
+// `walk_control_stmt` never calls its closure on a `DataExpr`. The one
+// exception is `DataExpr`/`ControlExpr`, which embed each other (a
+// `ListComprehension`'s `condition` is a `ControlExpr`; a `Comparison`'s
+// operands are `DataExpr`s), so `walk_data_expr` and `walk_control_expr`
+// each reach through the other kind via a private helper to avoid missing
+// nodes, without invoking the *other* kind's closure along the way.
+//
+// `Interpreter`, `TypeChecker`, `PurityChecker`, and the optimizer's
+// constant folder each still have their own hand-written recursive
+// `match`es predating this module; nothing here replaces them. This is an
+// extension point for new queries/rewrites, not a mandated migration of
+// existing ones.
+
+use super::{Comprehension, ControlExpr, ControlStmt, DataExpr, ModuleDecl, ReverseBlock, ReversibleStmt, TopLevel};
+
+// ===== TopLevel =====
+
+/// Walks `item`, calling `f` on it and then on every `TopLevel` nested in
+/// a `ModuleDecl` body. A `FunctionDecl`/`TestDecl`/`TopLevel::Control`'s
+/// statements are `ControlStmt`, not `TopLevel` -- reach those with
+/// [`walk_control_stmt`] instead.
+pub fn walk_top_level<F: FnMut(&TopLevel) -> bool>(item: &TopLevel, f: &mut F) -> bool {
+    if !f(item) {
+        return false;
+    }
+    match item {
+        TopLevel::Module(ModuleDecl { body, .. }) => walk_top_levels(body, f),
+        TopLevel::Import(_) | TopLevel::Function(_) | TopLevel::Struct(_) | TopLevel::Test(_) | TopLevel::Control(_) => true,
+    }
+}
+
+/// Calls [`walk_top_level`] on each item in `items`, stopping at the
+/// first one that asks to halt.
+pub fn walk_top_levels<F: FnMut(&TopLevel) -> bool>(items: &[TopLevel], f: &mut F) -> bool {
+    items.iter().all(|item| walk_top_level(item, f))
+}
+
+// ===== ControlStmt =====
+
+/// Walks `stmt`, calling `f` on it and then on every nested `ControlStmt`
+/// -- an `If`/`While`/`For`/`Block`'s body, or a `ReverseBlock`'s `If`
+/// branches (its `AddAssign`/`SubAssign` have none). `Assignment`/
+/// `Return`/`Print` carry only `DataExpr`/`ControlExpr` children, a
+/// different kind with its own walker below.
+pub fn walk_control_stmt<F: FnMut(&ControlStmt) -> bool>(stmt: &ControlStmt, f: &mut F) -> bool {
+    if !f(stmt) {
+        return false;
+    }
+    match stmt {
+        ControlStmt::If(if_stmt) => {
+            walk_control_stmts(&if_stmt.then_branch, f)
+                && if_stmt.else_branch.as_ref().map_or(true, |branch| walk_control_stmts(branch, f))
+        }
+        ControlStmt::While(while_stmt) => walk_control_stmts(&while_stmt.body, f),
+        ControlStmt::For(for_stmt) => walk_control_stmts(&for_stmt.body, f),
+        ControlStmt::Block(stmts) => walk_control_stmts(stmts, f),
+        ControlStmt::ReverseBlock(ReverseBlock { body }) => body.iter().all(|rstmt| walk_reversible_stmt(rstmt, f)),
+        ControlStmt::Assignment(_) | ControlStmt::Return(_) | ControlStmt::Print(_) | ControlStmt::Break(_) | ControlStmt::Continue(_) => true,
+    }
+}
+
+/// Walks a single `ReversibleStmt`, reaching the `ControlStmt`s nested in
+/// an `If`'s branches (which may themselves hold a `ReverseBlock`) and
+/// recursing into a `For`/`Switch`'s bodies, which are themselves
+/// `ReversibleStmt`. `AddAssign`/`SubAssign` carry only a `DataExpr`, a
+/// different kind.
+fn walk_reversible_stmt<F: FnMut(&ControlStmt) -> bool>(stmt: &ReversibleStmt, f: &mut F) -> bool {
+    match stmt {
+        ReversibleStmt::If(if_stmt) => {
+            walk_control_stmts(&if_stmt.then_branch, f)
+                && if_stmt.else_branch.as_ref().map_or(true, |branch| walk_control_stmts(branch, f))
+        }
+        ReversibleStmt::For { body, .. } => body.iter().all(|rstmt| walk_reversible_stmt(rstmt, f)),
+        ReversibleStmt::Switch { cases, default, .. } => {
+            cases.iter().all(|(_, body)| body.iter().all(|rstmt| walk_reversible_stmt(rstmt, f)))
+                && default.as_ref().map_or(true, |body| body.iter().all(|rstmt| walk_reversible_stmt(rstmt, f)))
+        }
+        ReversibleStmt::AddAssign(..)
+        | ReversibleStmt::SubAssign(..)
+        | ReversibleStmt::MulAssign(..)
+        | ReversibleStmt::DivAssign(..)
+        | ReversibleStmt::Assign(..) => true,
+    }
+}
+
+/// Calls [`walk_control_stmt`] on each statement in `stmts`, stopping at
+/// the first one that asks to halt.
+pub fn walk_control_stmts<F: FnMut(&ControlStmt) -> bool>(stmts: &[ControlStmt], f: &mut F) -> bool {
+    stmts.iter().all(|stmt| walk_control_stmt(stmt, f))
+}
+
+// ===== ControlExpr =====
+
+/// Walks `expr`, calling `f` on it and then on every nested `ControlExpr`
+/// -- a `Logical`'s operands, a `Not`'s operand, or a `ControlExpr`
+/// reachable through a `DataExpr` operand's own `ListComprehension`
+/// condition (see the module doc comment).
+pub fn walk_control_expr<F: FnMut(&ControlExpr) -> bool>(expr: &ControlExpr, f: &mut F) -> bool {
+    if !f(expr) {
+        return false;
+    }
+    match expr {
+        ControlExpr::Data(data) => control_exprs_in_data_expr(data, f),
+        ControlExpr::Comparison(left, _, right) | ControlExpr::Contains(left, right) => {
+            control_exprs_in_data_expr(left, f) && control_exprs_in_data_expr(right, f)
+        }
+        ControlExpr::Logical(left, _, right) => walk_control_expr(left, f) && walk_control_expr(right, f),
+        ControlExpr::Not(inner) => walk_control_expr(inner, f),
+    }
+}
+
+/// Reaches the `ControlExpr`s embedded inside `expr` on `f`'s behalf --
+/// today that's only a `ListComprehension`'s `condition`, the one place a
+/// `DataExpr` embeds a `ControlExpr` (e.g. `[x for x in xs if x in allowed]`).
+fn control_exprs_in_data_expr<F: FnMut(&ControlExpr) -> bool>(expr: &DataExpr, f: &mut F) -> bool {
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => true,
+        DataExpr::Add(left, right) | DataExpr::Index(left, right) => {
+            control_exprs_in_data_expr(left, f) && control_exprs_in_data_expr(right, f)
+        }
+        DataExpr::Negate(inner) | DataExpr::FieldAccess(inner, _) => control_exprs_in_data_expr(inner, f),
+        DataExpr::FunctionCall(call) => call.args.iter().all(|arg| control_exprs_in_data_expr(arg, f)),
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter().all(|item| control_exprs_in_data_expr(item, f)),
+        DataExpr::StructLiteral(_, fields) => fields.iter().all(|(_, value)| control_exprs_in_data_expr(value, f)),
+        DataExpr::ListComprehension(Comprehension { body, generators, condition }) => {
+            control_exprs_in_data_expr(body, f)
+                && generators.iter().all(|(_, source)| control_exprs_in_data_expr(source, f))
+                && condition.as_ref().map_or(true, |cond| walk_control_expr(cond, f))
+        }
+    }
+}
+
+// ===== DataExpr =====
+
+/// Walks `expr`, calling `f` on it and then on every nested `DataExpr` --
+/// `Add`/`Index`'s operands, `Negate`/`FieldAccess`'s inner expression, a
+/// `FunctionCall`'s args, a `List`/`Tuple`/`StructLiteral`'s elements, or
+/// a `ListComprehension`'s body/generator sources/condition (the last
+/// reached through a `ControlExpr`, see the module doc comment).
+pub fn walk_data_expr<F: FnMut(&DataExpr) -> bool>(expr: &DataExpr, f: &mut F) -> bool {
+    if !f(expr) {
+        return false;
+    }
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => true,
+        DataExpr::Add(left, right) | DataExpr::Index(left, right) => walk_data_expr(left, f) && walk_data_expr(right, f),
+        DataExpr::Negate(inner) | DataExpr::FieldAccess(inner, _) => walk_data_expr(inner, f),
+        DataExpr::FunctionCall(call) => call.args.iter().all(|arg| walk_data_expr(arg, f)),
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter().all(|item| walk_data_expr(item, f)),
+        DataExpr::StructLiteral(_, fields) => fields.iter().all(|(_, value)| walk_data_expr(value, f)),
+        DataExpr::ListComprehension(Comprehension { body, generators, condition }) => {
+            walk_data_expr(body, f)
+                && generators.iter().all(|(_, source)| walk_data_expr(source, f))
+                && condition.as_ref().map_or(true, |cond| data_exprs_in_control_expr(cond, f))
+        }
+    }
+}
+
+/// Calls [`walk_data_expr`] on each expression in `items`, stopping at
+/// the first one that asks to halt.
+pub fn walk_data_exprs<F: FnMut(&DataExpr) -> bool>(items: &[DataExpr], f: &mut F) -> bool {
+    items.iter().all(|item| walk_data_expr(item, f))
+}
+
+/// Reaches the `DataExpr`s embedded inside `expr` on `f`'s behalf -- the
+/// mirror image of [`control_exprs_in_data_expr`].
+fn data_exprs_in_control_expr<F: FnMut(&DataExpr) -> bool>(expr: &ControlExpr, f: &mut F) -> bool {
+    match expr {
+        ControlExpr::Data(data) => walk_data_expr(data, f),
+        ControlExpr::Comparison(left, _, right) | ControlExpr::Contains(left, right) => {
+            walk_data_expr(left, f) && walk_data_expr(right, f)
+        }
+        ControlExpr::Logical(left, _, right) => data_exprs_in_control_expr(left, f) && data_exprs_in_control_expr(right, f),
+        ControlExpr::Not(inner) => data_exprs_in_control_expr(inner, f),
+    }
+}
+
+// ===== DataExpr, &mut rewriting variant =====
+
+/// Like [`walk_data_expr`], but for a rewrite pass (e.g. a constant
+/// folder) that needs to replace a node in place rather than only read
+/// it. Visits children *before* calling `f` on `expr` itself, so `f` sees
+/// each child already rewritten -- the order `optimizer::fold_data_expr`
+/// uses to fold a `FunctionCall`'s args before asking whether the call
+/// itself is now foldable. `f` returning `false` skips the rest of
+/// `expr`'s siblings, same as the immutable walker.
+pub fn walk_data_expr_mut<F: FnMut(&mut DataExpr) -> bool>(expr: &mut DataExpr, f: &mut F) -> bool {
+    let descended = match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => true,
+        DataExpr::Add(left, right) | DataExpr::Index(left, right) => {
+            walk_data_expr_mut(left, f) && walk_data_expr_mut(right, f)
+        }
+        DataExpr::Negate(inner) | DataExpr::FieldAccess(inner, _) => walk_data_expr_mut(inner, f),
+        DataExpr::FunctionCall(call) => call.args.iter_mut().all(|arg| walk_data_expr_mut(arg, f)),
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter_mut().all(|item| walk_data_expr_mut(item, f)),
+        DataExpr::StructLiteral(_, fields) => fields.iter_mut().all(|(_, value)| walk_data_expr_mut(value, f)),
+        DataExpr::ListComprehension(comprehension) => {
+            walk_data_expr_mut(&mut comprehension.body, f)
+                && comprehension.generators.iter_mut().all(|(_, source)| walk_data_expr_mut(source, f))
+                && comprehension
+                    .condition
+                    .as_deref_mut()
+                    .map_or(true, |cond| data_exprs_in_control_expr_mut(cond, f))
+        }
+    };
+    descended && f(expr)
+}
+
+/// Calls [`walk_data_expr_mut`] on each expression in `items`, stopping
+/// at the first one that asks to halt.
+pub fn walk_data_exprs_mut<F: FnMut(&mut DataExpr) -> bool>(items: &mut [DataExpr], f: &mut F) -> bool {
+    items.iter_mut().all(|item| walk_data_expr_mut(item, f))
+}
+
+/// The `&mut` mirror of [`data_exprs_in_control_expr`].
+fn data_exprs_in_control_expr_mut<F: FnMut(&mut DataExpr) -> bool>(expr: &mut ControlExpr, f: &mut F) -> bool {
+    match expr {
+        ControlExpr::Data(data) => walk_data_expr_mut(data, f),
+        ControlExpr::Comparison(left, _, right) | ControlExpr::Contains(left, right) => {
+            walk_data_expr_mut(left, f) && walk_data_expr_mut(right, f)
+        }
+        ControlExpr::Logical(left, _, right) => {
+            data_exprs_in_control_expr_mut(left, f) && data_exprs_in_control_expr_mut(right, f)
+        }
+        ControlExpr::Not(inner) => data_exprs_in_control_expr_mut(inner, f),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Assignment, Comparator, DataExpr, Expr, FunctionCall, IfStmt, Number};
+
+    #[test]
+    fn test_walk_data_expr_visits_every_nested_node() {
+        let expr = DataExpr::add(
+            DataExpr::number(Number::Int(1)),
+            DataExpr::add(DataExpr::number(Number::Int(2)), DataExpr::identifier("x")),
+        );
+        let mut seen = Vec::new();
+        walk_data_expr(&expr, &mut |node| {
+            seen.push(node.clone());
+            true
+        });
+        // outer Add, 1, inner Add, 2, x
+        assert_eq!(seen.len(), 5);
+    }
+
+    #[test]
+    fn test_walk_data_expr_short_circuits_on_first_match() {
+        let expr = DataExpr::List(vec![
+            DataExpr::identifier("a"),
+            DataExpr::identifier("target"),
+            DataExpr::identifier("b"),
+        ]);
+        let mut visited = 0;
+        let found = !walk_data_expr(&expr, &mut |node| {
+            visited += 1;
+            !matches!(node, DataExpr::Identifier(name) if name == "target")
+        });
+        assert!(found);
+        // The List itself, "a", then "target" -- stops before visiting "b".
+        assert_eq!(visited, 3);
+    }
+
+    #[test]
+    fn test_walk_control_stmt_finds_nested_print() {
+        let program_body = vec![ControlStmt::If(IfStmt {
+            condition: ControlExpr::Comparison(
+                Box::new(DataExpr::identifier("x")),
+                Comparator::Gt,
+                Box::new(DataExpr::number(Number::Int(0))),
+            ),
+            then_branch: vec![ControlStmt::Print(vec![DataExpr::identifier("x")])],
+            else_branch: None,
+        })];
+
+        let mut found_print = false;
+        walk_control_stmts(&program_body, &mut |stmt| {
+            if matches!(stmt, ControlStmt::Print(_)) {
+                found_print = true;
+            }
+            true
+        });
+        assert!(found_print);
+    }
+
+    #[test]
+    fn test_walk_control_stmt_does_not_descend_into_assignment_value() {
+        // An Assignment's value is a different kind (Expr), so only the
+        // Assignment itself is visited -- not the FunctionCall inside it.
+        let stmts = vec![ControlStmt::Assignment(Assignment {
+            target: "y".to_string(),
+            value: Expr::Data(DataExpr::FunctionCall(FunctionCall { name: "f".to_string(), args: vec![] })),
+        })];
+        let mut visited = 0;
+        walk_control_stmts(&stmts, &mut |_| {
+            visited += 1;
+            true
+        });
+        assert_eq!(visited, 1);
+    }
+
+    #[test]
+    fn test_walk_data_expr_mut_rewrites_in_place() {
+        let mut expr = DataExpr::List(vec![DataExpr::number(Number::Int(1)), DataExpr::number(Number::Int(2))]);
+        walk_data_expr_mut(&mut expr, &mut |node| {
+            if let DataExpr::Number(Number::Int(n)) = node {
+                *n *= 10;
+            }
+            true
+        });
+        assert_eq!(expr, DataExpr::List(vec![DataExpr::number(Number::Int(10)), DataExpr::number(Number::Int(20))]));
+    }
+
+    #[test]
+    fn test_walk_control_expr_reaches_comprehension_condition() {
+        let comprehension = Comprehension {
+            body: Box::new(DataExpr::identifier("x")),
+            generators: vec![("x".to_string(), DataExpr::identifier("xs"))],
+            condition: Some(Box::new(ControlExpr::Comparison(
+                Box::new(DataExpr::identifier("x")),
+                Comparator::Gt,
+                Box::new(DataExpr::number(Number::Int(0))),
+            ))),
+        };
+        let expr = DataExpr::ListComprehension(comprehension);
+
+        let mut saw_comparison = false;
+        walk_control_expr(&ControlExpr::Data(expr), &mut |node| {
+            if matches!(node, ControlExpr::Comparison(..)) {
+                saw_comparison = true;
+            }
+            true
+        });
+        assert!(saw_comparison);
+    }
+
+    #[test]
+    fn test_walk_top_level_descends_into_module_body() {
+        use crate::ast::{FunctionDecl, Purity, Span, Trivia};
+
+        let nested_fn = TopLevel::Function(FunctionDecl {
+            name: "inner".to_string(),
+            type_params: vec![],
+            params: vec![],
+            return_type: None,
+            purity: Purity::Pure,
+            body: vec![],
+            span: Span::unknown(),
+            trivia: Trivia::default(),
+        });
+        let module = TopLevel::Module(ModuleDecl { name: "m".to_string(), body: vec![nested_fn], trivia: Trivia::default() });
+
+        let mut names = Vec::new();
+        walk_top_level(&module, &mut |item| {
+            if let TopLevel::Function(func) = item {
+                names.push(func.name.clone());
+            }
+            true
+        });
+        assert_eq!(names, vec!["inner"]);
+    }
+}
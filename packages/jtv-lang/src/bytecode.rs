@@ -4,14 +4,26 @@
 // Julia the Viper - Bytecode IR for compilation backends
 
 use crate::ast::*;
+use crate::diagnostics::{Diagnostic, DiagnosticKind};
 use crate::error::{JtvError, Result};
 use std::collections::HashMap;
 
+/// Safety limit for back-edges executed by `BytecodeVM`, mirroring
+/// `interpreter::MAX_ITERATIONS` so a compiled loop that can't terminate
+/// fails the same way the tree-walker does instead of hanging the process.
+const MAX_ITERATIONS: usize = 1_000_000;
+
 /// Bytecode instructions for the JtV virtual machine
 #[derive(Debug, Clone, PartialEq)]
 pub enum Opcode {
     // Stack operations
     Push(Value),           // Push value onto stack
+    /// Push the `Value` at index `.0` of the module's constant pool (see
+    /// `CompiledModule::constants`) onto the stack. Emitted by
+    /// `BytecodeCompiler` in place of `Push` for every literal it compiles,
+    /// so identical literals share one pool slot instead of each getting
+    /// its own inline copy of a possibly-large `Value`.
+    LoadConst(u32),
     Pop,                   // Pop top of stack
     Dup,                   // Duplicate top of stack
 
@@ -37,6 +49,7 @@ pub enum Opcode {
     And,                   // Logical AND
     Or,                    // Logical OR
     Not,                   // Logical NOT
+    Contains,              // Pop collection, pop value, push whether value is an element of collection
 
     // Control flow
     Jump(u32),             // Unconditional jump to instruction
@@ -53,6 +66,7 @@ pub enum Opcode {
     // Collection operations
     MakeList(u32),         // Create list from n stack values
     MakeTuple(u32),        // Create tuple from n stack values
+    Index,                 // Pop index, pop list/tuple, push the element at that index
 
     // Reversible operations (for reverse blocks)
     BeginReverse,          // Mark start of reversible section
@@ -119,6 +133,36 @@ pub struct CompiledFunction {
     pub arity: usize,
     pub locals: usize,
     pub code: Vec<Opcode>,
+    /// Whether each parameter was declared `Float`, so backends that care
+    /// about numeric representation (the WASM codegen) can give it an `f64`
+    /// local instead of assuming every value is an `i64`.
+    pub param_is_float: Vec<bool>,
+    /// Whether the declared return type is `Float`.
+    pub returns_float: bool,
+}
+
+/// Whether a (possibly absent) type annotation is `Float`.
+fn is_float_type(ty: &Option<TypeAnnotation>) -> bool {
+    matches!(ty, Some(TypeAnnotation::Basic(BasicType::Float)))
+}
+
+/// Dedup key for `BytecodeCompiler::constant_idx`'s constant pool. `Value`
+/// can't derive `Hash`/`Eq` itself (its `Float`/`Complex` variants hold
+/// `f64`s), so this stringifies each variant instead, using `.to_bits()`
+/// for floats so the key compares by exact bit pattern rather than by a
+/// lossy `f64` equality.
+fn constant_key(value: &Value) -> String {
+    match value {
+        Value::Int(n) => format!("i{}", n),
+        Value::Float(f) => format!("f{}", f.to_bits()),
+        Value::Rational(n, d) => format!("r{}/{}", n, d),
+        Value::Complex(re, im) => format!("c{}/{}", re.to_bits(), im.to_bits()),
+        Value::Bool(b) => format!("b{}", b),
+        Value::String(s) => format!("s{}", s),
+        Value::List(items) => format!("l[{}]", items.iter().map(constant_key).collect::<Vec<_>>().join(",")),
+        Value::Tuple(items) => format!("t({})", items.iter().map(constant_key).collect::<Vec<_>>().join(",")),
+        Value::Unit => "u".to_string(),
+    }
 }
 
 /// A compiled module/program
@@ -128,15 +172,576 @@ pub struct CompiledModule {
     pub globals: Vec<String>,
     pub entry_point: usize,  // Index of main/entry function
     pub code: Vec<Opcode>,   // Top-level code
+    /// Deduplicated literal pool shared by every function and the
+    /// top-level code. `Opcode::LoadConst(idx)` indexes into this.
+    /// Populated by `BytecodeCompiler::constant_idx`; always empty on a
+    /// module reloaded via `deserialize`, since `Chunk::encode` already
+    /// resolves each `LoadConst` into the chunk's own constant pool before
+    /// the bytes hit disk (see its doc comment).
+    pub constants: Vec<Value>,
+}
+
+/// One-byte instruction tags for `Chunk`'s encoding, one per `Opcode`
+/// variant. `PushConst` replaces `Push(Value)`: the `Value` itself moves
+/// into the chunk's constant pool and only its index travels inline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum OpTag {
+    PushConst = 0,
+    Pop = 1,
+    Dup = 2,
+    LoadLocal = 3,
+    StoreLocal = 4,
+    LoadGlobal = 5,
+    StoreGlobal = 6,
+    Add = 7,
+    Neg = 8,
+    Eq = 9,
+    Ne = 10,
+    Lt = 11,
+    Le = 12,
+    Gt = 13,
+    Ge = 14,
+    And = 15,
+    Or = 16,
+    Not = 17,
+    Contains = 18,
+    Jump = 19,
+    JumpIfFalse = 20,
+    JumpIfTrue = 21,
+    Call = 22,
+    Return = 23,
+    Print = 24,
+    MakeList = 25,
+    MakeTuple = 26,
+    Index = 27,
+    BeginReverse = 28,
+    EndReverse = 29,
+    Halt = 30,
+}
+
+/// How many bytes (tag included) `op` takes up once encoded.
+fn instruction_len(op: &Opcode) -> u32 {
+    match op {
+        Opcode::Pop | Opcode::Dup | Opcode::Add | Opcode::Neg | Opcode::Eq | Opcode::Ne
+        | Opcode::Lt | Opcode::Le | Opcode::Gt | Opcode::Ge | Opcode::And | Opcode::Or
+        | Opcode::Not | Opcode::Contains | Opcode::Return | Opcode::Print | Opcode::Index
+        | Opcode::BeginReverse | Opcode::EndReverse | Opcode::Halt => 1,
+        Opcode::Push(_) | Opcode::LoadConst(_) | Opcode::LoadLocal(_) | Opcode::StoreLocal(_)
+        | Opcode::LoadGlobal(_) | Opcode::StoreGlobal(_) | Opcode::Jump(_) | Opcode::JumpIfFalse(_)
+        | Opcode::JumpIfTrue(_) | Opcode::Call(_) | Opcode::MakeList(_) | Opcode::MakeTuple(_) => 5,
+    }
+}
+
+/// How many bytes (tag included) the instruction tagged `tag` takes up.
+/// Errors out on a tag byte `Chunk::encode` never writes -- the only way
+/// to hit one is a corrupt or hand-edited `.jtvc` file.
+fn tag_len(tag: u8) -> Result<u32> {
+    const NO_OPERAND: &[OpTag] = &[
+        OpTag::Pop, OpTag::Dup, OpTag::Add, OpTag::Neg, OpTag::Eq, OpTag::Ne, OpTag::Lt,
+        OpTag::Le, OpTag::Gt, OpTag::Ge, OpTag::And, OpTag::Or, OpTag::Not, OpTag::Contains,
+        OpTag::Return, OpTag::Print, OpTag::Index, OpTag::BeginReverse, OpTag::EndReverse,
+        OpTag::Halt,
+    ];
+    const ONE_OPERAND: &[OpTag] = &[
+        OpTag::PushConst, OpTag::LoadLocal, OpTag::StoreLocal, OpTag::LoadGlobal,
+        OpTag::StoreGlobal, OpTag::Jump, OpTag::JumpIfFalse, OpTag::JumpIfTrue, OpTag::Call,
+        OpTag::MakeList, OpTag::MakeTuple,
+    ];
+    if NO_OPERAND.iter().any(|t| *t as u8 == tag) {
+        Ok(1)
+    } else if ONE_OPERAND.iter().any(|t| *t as u8 == tag) {
+        Ok(5)
+    } else {
+        Err(JtvError::RuntimeError(format!("unknown opcode tag {} in chunk", tag)))
+    }
+}
+
+/// Encodes one instruction of `ops` into `out`, interning any literal it
+/// carries into `constants` (the chunk's own constant pool). `LoadConst`
+/// carries an index into the *module's* constant pool (`module_constants`)
+/// rather than a `Value`, so its arm resolves that first -- the chunk
+/// format only ever has one pool, so `LoadConst` collapses into the same
+/// `PushConst` encoding `Push` uses, just sourced from a different place.
+fn encode_one(op: &Opcode, offsets: &[u32], module_constants: &[Value], constants: &mut Vec<Value>, out: &mut Vec<u8>) {
+    fn with_operand(tag: OpTag, operand: u32, out: &mut Vec<u8>) {
+        out.push(tag as u8);
+        out.extend_from_slice(&operand.to_le_bytes());
+    }
+    match op {
+        Opcode::Push(value) => {
+            let idx = constants.len() as u32;
+            constants.push(value.clone());
+            with_operand(OpTag::PushConst, idx, out);
+        }
+        Opcode::LoadConst(idx) => {
+            let value = module_constants.get(*idx as usize).cloned().unwrap_or(Value::Unit);
+            let chunk_idx = constants.len() as u32;
+            constants.push(value);
+            with_operand(OpTag::PushConst, chunk_idx, out);
+        }
+        Opcode::Pop => out.push(OpTag::Pop as u8),
+        Opcode::Dup => out.push(OpTag::Dup as u8),
+        Opcode::LoadLocal(idx) => with_operand(OpTag::LoadLocal, *idx, out),
+        Opcode::StoreLocal(idx) => with_operand(OpTag::StoreLocal, *idx, out),
+        Opcode::LoadGlobal(idx) => with_operand(OpTag::LoadGlobal, *idx, out),
+        Opcode::StoreGlobal(idx) => with_operand(OpTag::StoreGlobal, *idx, out),
+        Opcode::Add => out.push(OpTag::Add as u8),
+        Opcode::Neg => out.push(OpTag::Neg as u8),
+        Opcode::Eq => out.push(OpTag::Eq as u8),
+        Opcode::Ne => out.push(OpTag::Ne as u8),
+        Opcode::Lt => out.push(OpTag::Lt as u8),
+        Opcode::Le => out.push(OpTag::Le as u8),
+        Opcode::Gt => out.push(OpTag::Gt as u8),
+        Opcode::Ge => out.push(OpTag::Ge as u8),
+        Opcode::And => out.push(OpTag::And as u8),
+        Opcode::Or => out.push(OpTag::Or as u8),
+        Opcode::Not => out.push(OpTag::Not as u8),
+        Opcode::Contains => out.push(OpTag::Contains as u8),
+        // `target` is an instruction index into the source `&[Opcode]`;
+        // `offsets[target]` is where that instruction starts once encoded.
+        Opcode::Jump(target) => with_operand(OpTag::Jump, offsets[*target as usize], out),
+        Opcode::JumpIfFalse(target) => with_operand(OpTag::JumpIfFalse, offsets[*target as usize], out),
+        Opcode::JumpIfTrue(target) => with_operand(OpTag::JumpIfTrue, offsets[*target as usize], out),
+        Opcode::Call(idx) => with_operand(OpTag::Call, *idx, out),
+        Opcode::Return => out.push(OpTag::Return as u8),
+        Opcode::Print => out.push(OpTag::Print as u8),
+        Opcode::MakeList(n) => with_operand(OpTag::MakeList, *n, out),
+        Opcode::MakeTuple(n) => with_operand(OpTag::MakeTuple, *n, out),
+        Opcode::Index => out.push(OpTag::Index as u8),
+        Opcode::BeginReverse => out.push(OpTag::BeginReverse as u8),
+        Opcode::EndReverse => out.push(OpTag::EndReverse as u8),
+        Opcode::Halt => out.push(OpTag::Halt as u8),
+    }
+}
+
+fn read_operand(code: &[u8], pos: usize) -> Result<u32> {
+    let bytes = code.get(pos + 1..pos + 5).ok_or_else(|| {
+        JtvError::RuntimeError("truncated instruction operand in chunk".to_string())
+    })?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Decode the instruction tagged `tag` at byte offset `pos`, resolving its
+/// jump operand (if any) through `resolve_jump`. `Chunk::decode` passes a
+/// closure that maps byte offsets back to instruction indices;
+/// `BytecodeVM::execute_chunk` passes the identity, since there the VM's
+/// own `ip` already walks `code` in byte offsets.
+fn decode_one(
+    tag: u8,
+    pos: usize,
+    code: &[u8],
+    constants: &[Value],
+    resolve_jump: impl Fn(u32) -> Result<u32>,
+) -> Result<(Opcode, u32)> {
+    let op = match tag {
+        t if t == OpTag::PushConst as u8 => {
+            let idx = read_operand(code, pos)? as usize;
+            let value = constants.get(idx).cloned().ok_or_else(|| {
+                JtvError::RuntimeError(format!("constant pool index {} out of range", idx))
+            })?;
+            Opcode::Push(value)
+        }
+        t if t == OpTag::Pop as u8 => Opcode::Pop,
+        t if t == OpTag::Dup as u8 => Opcode::Dup,
+        t if t == OpTag::LoadLocal as u8 => Opcode::LoadLocal(read_operand(code, pos)?),
+        t if t == OpTag::StoreLocal as u8 => Opcode::StoreLocal(read_operand(code, pos)?),
+        t if t == OpTag::LoadGlobal as u8 => Opcode::LoadGlobal(read_operand(code, pos)?),
+        t if t == OpTag::StoreGlobal as u8 => Opcode::StoreGlobal(read_operand(code, pos)?),
+        t if t == OpTag::Add as u8 => Opcode::Add,
+        t if t == OpTag::Neg as u8 => Opcode::Neg,
+        t if t == OpTag::Eq as u8 => Opcode::Eq,
+        t if t == OpTag::Ne as u8 => Opcode::Ne,
+        t if t == OpTag::Lt as u8 => Opcode::Lt,
+        t if t == OpTag::Le as u8 => Opcode::Le,
+        t if t == OpTag::Gt as u8 => Opcode::Gt,
+        t if t == OpTag::Ge as u8 => Opcode::Ge,
+        t if t == OpTag::And as u8 => Opcode::And,
+        t if t == OpTag::Or as u8 => Opcode::Or,
+        t if t == OpTag::Not as u8 => Opcode::Not,
+        t if t == OpTag::Contains as u8 => Opcode::Contains,
+        t if t == OpTag::Jump as u8 => Opcode::Jump(resolve_jump(read_operand(code, pos)?)?),
+        t if t == OpTag::JumpIfFalse as u8 => Opcode::JumpIfFalse(resolve_jump(read_operand(code, pos)?)?),
+        t if t == OpTag::JumpIfTrue as u8 => Opcode::JumpIfTrue(resolve_jump(read_operand(code, pos)?)?),
+        t if t == OpTag::Call as u8 => Opcode::Call(read_operand(code, pos)?),
+        t if t == OpTag::Return as u8 => Opcode::Return,
+        t if t == OpTag::Print as u8 => Opcode::Print,
+        t if t == OpTag::MakeList as u8 => Opcode::MakeList(read_operand(code, pos)?),
+        t if t == OpTag::MakeTuple as u8 => Opcode::MakeTuple(read_operand(code, pos)?),
+        t if t == OpTag::Index as u8 => Opcode::Index,
+        t if t == OpTag::BeginReverse as u8 => Opcode::BeginReverse,
+        t if t == OpTag::EndReverse as u8 => Opcode::EndReverse,
+        t if t == OpTag::Halt as u8 => Opcode::Halt,
+        other => {
+            return Err(JtvError::RuntimeError(format!("unknown opcode tag {} in chunk", other)));
+        }
+    };
+    Ok((op, tag_len(tag)?))
+}
+
+/// The byte-oriented encoding of a `Vec<Opcode>` sequence: one tag byte
+/// per instruction (see `OpTag`) with little-endian `u32` operands inline,
+/// plus a constant pool so `Push(Value)` shrinks to a one-byte `PushConst`
+/// and a pool index instead of embedding the whole `Value` in the stream.
+/// This is what `CompiledModule::serialize` actually writes to disk, and
+/// what `BytecodeVM::execute_chunk` dispatches directly off of without
+/// ever rebuilding a `Vec<Opcode>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub code: Vec<u8>,
+    pub constants: Vec<Value>,
+}
+
+impl Chunk {
+    /// Encode `ops` into its byte-oriented form. Jump targets in `ops` are
+    /// instruction indices; since instructions don't all encode to the
+    /// same width, this first computes the byte offset each instruction
+    /// ends up at, then remaps every `Jump`/`JumpIfFalse`/`JumpIfTrue`
+    /// through that table as it writes each instruction out.
+    ///
+    /// `module_constants` resolves any `Opcode::LoadConst` in `ops` against
+    /// the module-level pool it was compiled against (normally
+    /// `CompiledModule::constants`); each resolved value is re-interned
+    /// into this chunk's own pool, so `decode` hands it back as `Push`, not
+    /// `LoadConst` -- same value, addressed against the chunk's pool
+    /// instead of the module's (which doesn't travel with a lone `Chunk`).
+    pub fn encode(ops: &[Opcode], module_constants: &[Value]) -> Chunk {
+        let mut offsets = Vec::with_capacity(ops.len() + 1);
+        let mut cursor: u32 = 0;
+        for op in ops {
+            offsets.push(cursor);
+            cursor += instruction_len(op);
+        }
+        offsets.push(cursor); // sentinel: the "one past the end" target a trailing jump can land on
+
+        let mut constants = vec![];
+        let mut code = vec![];
+        for op in ops {
+            encode_one(op, &offsets, module_constants, &mut constants, &mut code);
+        }
+        Chunk { code, constants }
+    }
+
+    /// The inverse of `encode`: reconstructs the original `Vec<Opcode>`,
+    /// remapping jump targets from byte offsets back to instruction
+    /// indices. The only way this errors is a corrupt or hand-edited
+    /// `.jtvc` file -- anything `encode` produced round-trips cleanly.
+    pub fn decode(&self) -> Result<Vec<Opcode>> {
+        let mut byte_to_index = HashMap::new();
+        let mut pos = 0usize;
+        let mut index = 0u32;
+        while pos < self.code.len() {
+            byte_to_index.insert(pos as u32, index);
+            pos += tag_len(self.code[pos])? as usize;
+            index += 1;
+        }
+        byte_to_index.insert(self.code.len() as u32, index);
+        let resolve_jump = |offset: u32| {
+            byte_to_index.get(&offset).copied().ok_or_else(|| {
+                JtvError::RuntimeError(format!("jump target {} lands mid-instruction", offset))
+            })
+        };
+
+        let mut ops = Vec::with_capacity(index as usize);
+        let mut pos = 0usize;
+        while pos < self.code.len() {
+            let (op, len) = decode_one(self.code[pos], pos, &self.code, &self.constants, resolve_jump)?;
+            ops.push(op);
+            pos += len as usize;
+        }
+        Ok(ops)
+    }
+}
+
+fn write_u32(n: u32, out: &mut Vec<u8>) {
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_len_prefixed(bytes: &[u8], out: &mut Vec<u8>) {
+    write_u32(bytes.len() as u32, out);
+    out.extend_from_slice(bytes);
+}
+
+fn write_chunk(chunk: &Chunk, out: &mut Vec<u8>) {
+    write_len_prefixed(&chunk.code, out);
+    write_u32(chunk.constants.len() as u32, out);
+    for value in &chunk.constants {
+        write_value(value, out);
+    }
+}
+
+/// One-byte tags for `Value`, used by `Chunk`'s constant pool encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum ValueTag {
+    Int = 0,
+    Float = 1,
+    Rational = 2,
+    Complex = 3,
+    Bool = 4,
+    String = 5,
+    List = 6,
+    Tuple = 7,
+    Unit = 8,
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Int(n) => {
+            out.push(ValueTag::Int as u8);
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+        Value::Float(n) => {
+            out.push(ValueTag::Float as u8);
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+        Value::Rational(n, d) => {
+            out.push(ValueTag::Rational as u8);
+            out.extend_from_slice(&n.to_le_bytes());
+            out.extend_from_slice(&d.to_le_bytes());
+        }
+        Value::Complex(re, im) => {
+            out.push(ValueTag::Complex as u8);
+            out.extend_from_slice(&re.to_le_bytes());
+            out.extend_from_slice(&im.to_le_bytes());
+        }
+        Value::Bool(b) => {
+            out.push(ValueTag::Bool as u8);
+            out.push(*b as u8);
+        }
+        Value::String(s) => {
+            out.push(ValueTag::String as u8);
+            write_len_prefixed(s.as_bytes(), out);
+        }
+        Value::List(items) => {
+            out.push(ValueTag::List as u8);
+            write_u32(items.len() as u32, out);
+            for item in items {
+                write_value(item, out);
+            }
+        }
+        Value::Tuple(items) => {
+            out.push(ValueTag::Tuple as u8);
+            write_u32(items.len() as u32, out);
+            for item in items {
+                write_value(item, out);
+            }
+        }
+        Value::Unit => out.push(ValueTag::Unit as u8),
+    }
+}
+
+fn read_u32(bytes: &[u8], pos: &mut usize) -> Result<u32> {
+    let end = *pos + 4;
+    let slice = bytes
+        .get(*pos..end)
+        .ok_or_else(|| JtvError::RuntimeError("truncated .jtvc module".to_string()))?;
+    *pos = end;
+    Ok(u32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_i64(bytes: &[u8], pos: &mut usize) -> Result<i64> {
+    let end = *pos + 8;
+    let slice = bytes
+        .get(*pos..end)
+        .ok_or_else(|| JtvError::RuntimeError("truncated .jtvc module".to_string()))?;
+    *pos = end;
+    Ok(i64::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_f64(bytes: &[u8], pos: &mut usize) -> Result<f64> {
+    let end = *pos + 8;
+    let slice = bytes
+        .get(*pos..end)
+        .ok_or_else(|| JtvError::RuntimeError("truncated .jtvc module".to_string()))?;
+    *pos = end;
+    Ok(f64::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_u8(bytes: &[u8], pos: &mut usize) -> Result<u8> {
+    let byte = bytes
+        .get(*pos)
+        .copied()
+        .ok_or_else(|| JtvError::RuntimeError("truncated .jtvc module".to_string()))?;
+    *pos += 1;
+    Ok(byte)
+}
+
+fn read_len_prefixed(bytes: &[u8], pos: &mut usize) -> Result<Vec<u8>> {
+    let len = read_u32(bytes, pos)? as usize;
+    let end = *pos + len;
+    let slice = bytes
+        .get(*pos..end)
+        .ok_or_else(|| JtvError::RuntimeError("truncated .jtvc module".to_string()))?;
+    *pos = end;
+    Ok(slice.to_vec())
+}
+
+fn read_string(bytes: &[u8], pos: &mut usize) -> Result<String> {
+    let raw = read_len_prefixed(bytes, pos)?;
+    String::from_utf8(raw).map_err(|_| JtvError::RuntimeError("invalid utf-8 in .jtvc module".to_string()))
+}
+
+fn read_value(bytes: &[u8], pos: &mut usize) -> Result<Value> {
+    let tag = read_u8(bytes, pos)?;
+    match tag {
+        t if t == ValueTag::Int as u8 => Ok(Value::Int(read_i64(bytes, pos)?)),
+        t if t == ValueTag::Float as u8 => Ok(Value::Float(read_f64(bytes, pos)?)),
+        t if t == ValueTag::Rational as u8 => {
+            Ok(Value::Rational(read_i64(bytes, pos)?, read_i64(bytes, pos)?))
+        }
+        t if t == ValueTag::Complex as u8 => {
+            Ok(Value::Complex(read_f64(bytes, pos)?, read_f64(bytes, pos)?))
+        }
+        t if t == ValueTag::Bool as u8 => Ok(Value::Bool(read_u8(bytes, pos)? != 0)),
+        t if t == ValueTag::String as u8 => Ok(Value::String(read_string(bytes, pos)?)),
+        t if t == ValueTag::List as u8 => {
+            let n = read_u32(bytes, pos)?;
+            let mut items = Vec::with_capacity(n as usize);
+            for _ in 0..n {
+                items.push(read_value(bytes, pos)?);
+            }
+            Ok(Value::List(items))
+        }
+        t if t == ValueTag::Tuple as u8 => {
+            let n = read_u32(bytes, pos)?;
+            let mut items = Vec::with_capacity(n as usize);
+            for _ in 0..n {
+                items.push(read_value(bytes, pos)?);
+            }
+            Ok(Value::Tuple(items))
+        }
+        t if t == ValueTag::Unit as u8 => Ok(Value::Unit),
+        other => Err(JtvError::RuntimeError(format!("unknown value tag {} in .jtvc module", other))),
+    }
+}
+
+fn read_chunk(bytes: &[u8], pos: &mut usize) -> Result<Chunk> {
+    let code = read_len_prefixed(bytes, pos)?;
+    let constant_count = read_u32(bytes, pos)?;
+    let mut constants = Vec::with_capacity(constant_count as usize);
+    for _ in 0..constant_count {
+        constants.push(read_value(bytes, pos)?);
+    }
+    Ok(Chunk { code, constants })
+}
+
+impl CompiledModule {
+    /// Serialize to the `.jtvc` binary format, so a compiled program can be
+    /// written to disk and `deserialize`d back without recompiling. Each
+    /// function's and the top-level code's `Vec<Opcode>` goes through
+    /// `Chunk::encode` first -- what actually hits disk is the compact
+    /// byte-oriented form, never the in-memory `Opcode` enum.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![];
+        write_u32(self.functions.len() as u32, &mut out);
+        for func in &self.functions {
+            write_len_prefixed(func.name.as_bytes(), &mut out);
+            write_u32(func.arity as u32, &mut out);
+            write_u32(func.locals as u32, &mut out);
+            write_chunk(&Chunk::encode(&func.code, &self.constants), &mut out);
+            write_u32(func.param_is_float.len() as u32, &mut out);
+            for is_float in &func.param_is_float {
+                out.push(*is_float as u8);
+            }
+            out.push(func.returns_float as u8);
+        }
+        write_u32(self.globals.len() as u32, &mut out);
+        for name in &self.globals {
+            write_len_prefixed(name.as_bytes(), &mut out);
+        }
+        write_u32(self.entry_point as u32, &mut out);
+        write_chunk(&Chunk::encode(&self.code, &self.constants), &mut out);
+        out
+    }
+
+    /// The inverse of `serialize`: reloads a `.jtvc` file's bytes into a
+    /// `CompiledModule` with ordinary `Vec<Opcode>` bodies, so every
+    /// existing call to `BytecodeVM::execute` keeps working on a
+    /// deserialized module exactly as it would on a freshly compiled one.
+    pub fn deserialize(bytes: &[u8]) -> Result<CompiledModule> {
+        let mut pos = 0usize;
+        let function_count = read_u32(bytes, &mut pos)?;
+        let mut functions = Vec::with_capacity(function_count as usize);
+        for _ in 0..function_count {
+            let name = read_string(bytes, &mut pos)?;
+            let arity = read_u32(bytes, &mut pos)? as usize;
+            let locals = read_u32(bytes, &mut pos)? as usize;
+            let code = read_chunk(bytes, &mut pos)?.decode()?;
+            let param_count = read_u32(bytes, &mut pos)?;
+            let mut param_is_float = Vec::with_capacity(param_count as usize);
+            for _ in 0..param_count {
+                param_is_float.push(read_u8(bytes, &mut pos)? != 0);
+            }
+            let returns_float = read_u8(bytes, &mut pos)? != 0;
+            functions.push(CompiledFunction {
+                name,
+                arity,
+                locals,
+                code,
+                param_is_float,
+                returns_float,
+            });
+        }
+        let global_count = read_u32(bytes, &mut pos)?;
+        let mut globals = Vec::with_capacity(global_count as usize);
+        for _ in 0..global_count {
+            globals.push(read_string(bytes, &mut pos)?);
+        }
+        let entry_point = read_u32(bytes, &mut pos)? as usize;
+        let code = read_chunk(bytes, &mut pos)?.decode()?;
+        Ok(CompiledModule {
+            functions,
+            globals,
+            entry_point,
+            code,
+            // `encode` already resolved every `LoadConst` into a chunk-local
+            // `Push` before these bytes were written, so there's nothing
+            // left to repopulate the module-level pool with.
+            constants: vec![],
+        })
+    }
 }
 
 /// Bytecode compiler
+/// One enclosing `While`/`For` a `Break`/`Continue` inside its body can
+/// target. Pushed before compiling the loop's body and popped after, so
+/// nesting is just a stack: `Break`/`Continue` always resolve against
+/// `loop_stack.last()` unless a label is given (see
+/// `BytecodeCompiler::compile_control_stmt`'s `Break`/`Continue` arms).
+struct LoopContext {
+    /// Indices into `code` of each `Continue`'s `Jump(0)` placeholder.
+    /// Back-patched once the continue target is known: the condition
+    /// re-check for a `While` (already fixed before its body is
+    /// compiled), or the start of the increment sequence for a `For`
+    /// (only known *after* its body is compiled, so this can't just be a
+    /// fixed index recorded up front the way `While`'s can).
+    continue_sites: Vec<usize>,
+    /// Indices into `code` of each `Break`'s `Jump(0)` placeholder,
+    /// back-patched to the loop's end address once that's known.
+    break_sites: Vec<usize>,
+}
+
 pub struct BytecodeCompiler {
     module: CompiledModule,
     local_vars: HashMap<String, u32>,
     global_vars: HashMap<String, u32>,
     function_indices: HashMap<String, u32>,
     next_local: u32,
+    /// Local slots reclaimed by `exit_scope`, available for the next
+    /// `get_or_create_var` to hand out before `next_local` grows any
+    /// further. See `enter_scope`/`exit_scope`.
+    free_locals: Vec<u32>,
+    /// Stack of lexical scopes opened by `enter_scope`, one entry per
+    /// currently-open `Block`/loop body, each holding the names
+    /// `get_or_create_var` declared fresh since that scope opened. Popped
+    /// and reclaimed by `exit_scope`.
+    scope_stack: Vec<Vec<String>>,
+    /// Dedup map for `constant_idx`, keyed by `constant_key(&value)` since
+    /// `Value` can't derive `Hash`/`Eq` itself (its `Float`/`Complex`
+    /// variants hold `f64`s).
+    constant_indices: HashMap<String, u32>,
+    loop_stack: Vec<LoopContext>,
 }
 
 impl BytecodeCompiler {
@@ -147,11 +752,16 @@ impl BytecodeCompiler {
                 globals: vec![],
                 entry_point: 0,
                 code: vec![],
+                constants: vec![],
             },
             local_vars: HashMap::new(),
             global_vars: HashMap::new(),
             function_indices: HashMap::new(),
             next_local: 0,
+            free_locals: vec![],
+            scope_stack: vec![],
+            constant_indices: HashMap::new(),
+            loop_stack: vec![],
         }
     }
 
@@ -168,6 +778,8 @@ impl BytecodeCompiler {
                     arity: func.params.len(),
                     locals: 0,
                     code: vec![],
+                    param_is_float: func.params.iter().map(|p| is_float_type(&p.type_annotation)).collect(),
+                    returns_float: is_float_type(&func.return_type),
                 });
             }
         }
@@ -191,6 +803,14 @@ impl BytecodeCompiler {
                 TopLevel::Import(_) => {
                     // Imports handled at link time
                 }
+                TopLevel::Struct(_) => {
+                    // Struct declarations carry no runtime code of their own;
+                    // struct literals compile their fields inline.
+                }
+                TopLevel::Test(_) => {
+                    // Tests run through `jtv test`'s own interpreter, never
+                    // through compiled bytecode.
+                }
             }
         }
 
@@ -202,6 +822,8 @@ impl BytecodeCompiler {
         let mut code = vec![];
         self.local_vars.clear();
         self.next_local = 0;
+        self.free_locals.clear();
+        self.scope_stack.clear();
 
         // Register parameters as locals
         for param in &func.params {
@@ -269,12 +891,22 @@ impl BytecodeCompiler {
                 let jump_end = code.len();
                 code.push(Opcode::JumpIfFalse(0)); // Placeholder
 
+                self.loop_stack.push(LoopContext { continue_sites: vec![], break_sites: vec![] });
+                self.enter_scope();
                 for s in &while_stmt.body {
                     self.compile_control_stmt(s, code)?;
                 }
+                self.exit_scope();
+                let ctx = self.loop_stack.pop().unwrap();
+                for site in ctx.continue_sites {
+                    code[site] = Opcode::Jump(loop_start as u32);
+                }
 
                 code.push(Opcode::Jump(loop_start as u32));
                 code[jump_end] = Opcode::JumpIfFalse(code.len() as u32);
+                for site in ctx.break_sites {
+                    code[site] = Opcode::Jump(code.len() as u32);
+                }
             }
             ControlStmt::For(for_stmt) => {
                 // Compile range start
@@ -293,28 +925,71 @@ impl BytecodeCompiler {
                 code.push(Opcode::JumpIfFalse(0)); // Placeholder
 
                 // Body
+                self.loop_stack.push(LoopContext { continue_sites: vec![], break_sites: vec![] });
+                self.enter_scope();
                 for s in &for_stmt.body {
                     self.compile_control_stmt(s, code)?;
                 }
+                self.exit_scope();
+                let ctx = self.loop_stack.pop().unwrap();
+
+                // `Continue` targets the increment below, not `loop_start`
+                // (the condition check) -- it still has to run the
+                // increment before the loop re-tests its bound.
+                let increment_start = code.len() as u32;
+                for site in ctx.continue_sites {
+                    code[site] = Opcode::Jump(increment_start);
+                }
 
                 // Increment: iter = iter + step (default 1)
                 code.push(Opcode::LoadLocal(iter_var));
                 if let Some(step) = &for_stmt.range.step {
                     self.compile_data_expr(step, code)?;
                 } else {
-                    code.push(Opcode::Push(Value::Int(1)));
+                    code.push(Opcode::LoadConst(self.constant_idx(Value::Int(1))));
                 }
                 code.push(Opcode::Add);
                 code.push(Opcode::StoreLocal(iter_var));
 
                 code.push(Opcode::Jump(loop_start as u32));
                 code[jump_end] = Opcode::JumpIfFalse(code.len() as u32);
+                for site in ctx.break_sites {
+                    code[site] = Opcode::Jump(code.len() as u32);
+                }
+            }
+            ControlStmt::Break(label) => {
+                if label.is_some() {
+                    return Err(JtvError::RuntimeError(
+                        "labeled break is not supported yet -- no loop carries a label to match against"
+                            .to_string(),
+                    ));
+                }
+                let ctx = self
+                    .loop_stack
+                    .last_mut()
+                    .ok_or_else(|| JtvError::RuntimeError("break outside of a loop".to_string()))?;
+                ctx.break_sites.push(code.len());
+                code.push(Opcode::Jump(0)); // Placeholder, patched once the loop's end is known
+            }
+            ControlStmt::Continue(label) => {
+                if label.is_some() {
+                    return Err(JtvError::RuntimeError(
+                        "labeled continue is not supported yet -- no loop carries a label to match against"
+                            .to_string(),
+                    ));
+                }
+                let ctx = self
+                    .loop_stack
+                    .last_mut()
+                    .ok_or_else(|| JtvError::RuntimeError("continue outside of a loop".to_string()))?;
+                ctx.continue_sites.push(code.len());
+                code.push(Opcode::Jump(0)); // Placeholder, patched once the continue target is known
             }
             ControlStmt::Return(expr) => {
                 if let Some(e) = expr {
                     self.compile_data_expr(e, code)?;
                 } else {
-                    code.push(Opcode::Push(Value::Unit));
+                    code.push(Opcode::LoadConst(self.constant_idx(Value::Unit)));
                 }
                 code.push(Opcode::Return);
             }
@@ -332,9 +1007,11 @@ impl BytecodeCompiler {
                 code.push(Opcode::EndReverse);
             }
             ControlStmt::Block(stmts) => {
+                self.enter_scope();
                 for s in stmts {
                     self.compile_control_stmt(s, code)?;
                 }
+                self.exit_scope();
             }
         }
         Ok(())
@@ -357,6 +1034,24 @@ impl BytecodeCompiler {
                 code.push(Opcode::Add);
                 code.push(Opcode::StoreLocal(idx));
             }
+            ReversibleStmt::MulAssign(..) | ReversibleStmt::DivAssign(..) => {
+                return Err(JtvError::RuntimeError(
+                    "reversible *=/ /= are not yet supported by the bytecode compiler -- \
+                     arithmetic here is addition-only (see the `Opcode` doc comment), run it \
+                     through `ReversibleInterpreter` instead"
+                        .to_string(),
+                ));
+            }
+            ReversibleStmt::Assign(target, expr) => {
+                // An ordinary overwrite compiles to ordinary bytecode --
+                // `BeginReverse`/`EndReverse`'s `reverse_journal` records
+                // whatever `StoreLocal` overwrites regardless of how the new
+                // value was computed, so this is automatically reversible
+                // the same way `For`/`Switch` are.
+                let idx = self.get_or_create_var(target);
+                self.compile_data_expr(expr, code)?;
+                code.push(Opcode::StoreLocal(idx));
+            }
             ReversibleStmt::If(if_stmt) => {
                 self.compile_control_expr(&if_stmt.condition, code)?;
                 let jump_else = code.len();
@@ -379,6 +1074,117 @@ impl BytecodeCompiler {
                     code[jump_else] = Opcode::JumpIfFalse(code.len() as u32);
                 }
             }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                // Compile range start
+                self.compile_data_expr(from, code)?;
+                let iter_var = self.get_or_create_var(var);
+                code.push(Opcode::StoreLocal(iter_var));
+
+                let loop_start = code.len();
+
+                // Condition: (step > 0 && iter < to) || (step < 0 && iter > to),
+                // supporting both ascending and descending ranges the same way
+                // `ReversibleInterpreter::execute_reversible_stmt`'s `For` arm
+                // does. `BeginReverse`/`EndReverse`'s journal-based reversal
+                // doesn't care how these writes were produced, so this is just
+                // ordinary jump-based loop bytecode, mirroring `ControlStmt::
+                // For` above.
+                if let Some(step) = step {
+                    self.compile_data_expr(step, code)?;
+                } else {
+                    code.push(Opcode::LoadConst(self.constant_idx(Value::Int(1))));
+                }
+                code.push(Opcode::LoadConst(self.constant_idx(Value::Int(0))));
+                code.push(Opcode::Gt);
+                code.push(Opcode::LoadLocal(iter_var));
+                self.compile_data_expr(to, code)?;
+                code.push(Opcode::Lt);
+                code.push(Opcode::And);
+
+                if let Some(step) = step {
+                    self.compile_data_expr(step, code)?;
+                } else {
+                    code.push(Opcode::LoadConst(self.constant_idx(Value::Int(1))));
+                }
+                code.push(Opcode::LoadConst(self.constant_idx(Value::Int(0))));
+                code.push(Opcode::Lt);
+                code.push(Opcode::LoadLocal(iter_var));
+                self.compile_data_expr(to, code)?;
+                code.push(Opcode::Gt);
+                code.push(Opcode::And);
+
+                code.push(Opcode::Or);
+
+                let jump_end = code.len();
+                code.push(Opcode::JumpIfFalse(0));
+
+                self.enter_scope();
+                for s in body {
+                    self.compile_reversible_stmt(s, code)?;
+                }
+                self.exit_scope();
+
+                // Increment: iter = iter + step (default 1)
+                code.push(Opcode::LoadLocal(iter_var));
+                if let Some(step) = step {
+                    self.compile_data_expr(step, code)?;
+                } else {
+                    code.push(Opcode::LoadConst(self.constant_idx(Value::Int(1))));
+                }
+                code.push(Opcode::Add);
+                code.push(Opcode::StoreLocal(iter_var));
+
+                code.push(Opcode::Jump(loop_start as u32));
+                code[jump_end] = Opcode::JumpIfFalse(code.len() as u32);
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                // Cases are tried in order, re-evaluating `scrutinee` (and
+                // each case's own value) at every comparison -- the same
+                // accepted simplification `ControlStmt::For`'s condition
+                // check above makes for its re-evaluated `end`. `BeginReverse`
+                // /`EndReverse`'s journal doesn't care how these writes were
+                // produced, so this is just ordinary jump-based bytecode.
+                let mut end_jumps = Vec::new();
+                let mut next_case_jump: Option<usize> = None;
+
+                for (value, body) in cases {
+                    if let Some(jump) = next_case_jump.take() {
+                        code[jump] = Opcode::JumpIfFalse(code.len() as u32);
+                    }
+
+                    self.compile_data_expr(scrutinee, code)?;
+                    self.compile_data_expr(value, code)?;
+                    code.push(Opcode::Eq);
+
+                    next_case_jump = Some(code.len());
+                    code.push(Opcode::JumpIfFalse(0));
+
+                    self.enter_scope();
+                    for s in body {
+                        self.compile_reversible_stmt(s, code)?;
+                    }
+                    self.exit_scope();
+
+                    end_jumps.push(code.len());
+                    code.push(Opcode::Jump(0));
+                }
+
+                if let Some(jump) = next_case_jump {
+                    code[jump] = Opcode::JumpIfFalse(code.len() as u32);
+                }
+
+                if let Some(default) = default {
+                    self.enter_scope();
+                    for s in default {
+                        self.compile_reversible_stmt(s, code)?;
+                    }
+                    self.exit_scope();
+                }
+
+                for jump in end_jumps {
+                    code[jump] = Opcode::Jump(code.len() as u32);
+                }
+            }
         }
         Ok(())
     }
@@ -394,7 +1200,7 @@ impl BytecodeCompiler {
         match expr {
             DataExpr::Number(num) => {
                 let value = self.number_to_value(num);
-                code.push(Opcode::Push(value));
+                code.push(Opcode::LoadConst(self.constant_idx(value)));
             }
             DataExpr::Identifier(name) => {
                 if let Some(&idx) = self.local_vars.get(name) {
@@ -439,6 +1245,21 @@ impl BytecodeCompiler {
                 }
                 code.push(Opcode::MakeTuple(elements.len() as u32));
             }
+            DataExpr::FieldAccess(_, _) | DataExpr::StructLiteral(_, _) => {
+                return Err(JtvError::RuntimeError(
+                    "Struct values are not yet supported by the bytecode compiler".to_string(),
+                ));
+            }
+            DataExpr::ListComprehension(_) => {
+                return Err(JtvError::RuntimeError(
+                    "List comprehensions are not yet supported by the bytecode compiler".to_string(),
+                ));
+            }
+            DataExpr::Index(base, index) => {
+                self.compile_data_expr(base, code)?;
+                self.compile_data_expr(index, code)?;
+                code.push(Opcode::Index);
+            }
         }
         Ok(())
     }
@@ -460,12 +1281,26 @@ impl BytecodeCompiler {
                 Ok(())
             }
             ControlExpr::Logical(left, op, right) => {
+                // Short-circuit: compile `left`, duplicate it so the
+                // conditional jump can consume one copy while leaving the
+                // other as the overall result if it already decides the
+                // outcome. Otherwise pop that leftover copy and let `right`
+                // push the real result. `end` is back-patched to `code.len()`
+                // once `right` is compiled, same as `If`/`While` above.
                 self.compile_control_expr(left, code)?;
-                self.compile_control_expr(right, code)?;
+                code.push(Opcode::Dup);
+                let jump_end = code.len();
                 code.push(match op {
-                    LogicalOp::And => Opcode::And,
-                    LogicalOp::Or => Opcode::Or,
+                    LogicalOp::And => Opcode::JumpIfFalse(0), // Placeholder
+                    LogicalOp::Or => Opcode::JumpIfTrue(0),   // Placeholder
                 });
+                code.push(Opcode::Pop);
+                self.compile_control_expr(right, code)?;
+                let end = code.len() as u32;
+                code[jump_end] = match op {
+                    LogicalOp::And => Opcode::JumpIfFalse(end),
+                    LogicalOp::Or => Opcode::JumpIfTrue(end),
+                };
                 Ok(())
             }
             ControlExpr::Not(inner) => {
@@ -473,6 +1308,12 @@ impl BytecodeCompiler {
                 code.push(Opcode::Not);
                 Ok(())
             }
+            ControlExpr::Contains(left, right) => {
+                self.compile_data_expr(left, code)?;
+                self.compile_data_expr(right, code)?;
+                code.push(Opcode::Contains);
+                Ok(())
+            }
         }
     }
 
@@ -480,13 +1321,61 @@ impl BytecodeCompiler {
         if let Some(&idx) = self.local_vars.get(name) {
             idx
         } else {
-            let idx = self.next_local;
+            let idx = self.free_locals.pop().unwrap_or_else(|| {
+                let idx = self.next_local;
+                self.next_local += 1;
+                idx
+            });
             self.local_vars.insert(name.to_string(), idx);
-            self.next_local += 1;
+            if let Some(scope) = self.scope_stack.last_mut() {
+                scope.push(name.to_string());
+            }
             idx
         }
     }
 
+    /// Opens a new lexical scope for locals declared inside a `Block` or
+    /// loop body. Paired with `exit_scope`, which frees every local
+    /// `get_or_create_var` declares fresh while this is the innermost open
+    /// scope, so a later, unrelated block can reuse its slot instead of
+    /// `next_local` only ever growing. Variables that already existed
+    /// before the scope opened are untouched.
+    fn enter_scope(&mut self) {
+        self.scope_stack.push(Vec::new());
+    }
+
+    /// Closes the scope opened by the matching `enter_scope`, returning
+    /// every local first declared inside it to `free_locals` for reuse.
+    /// Referencing one of those names again afterwards is now an
+    /// `UndefinedVariable` error rather than silently resolving to its old
+    /// slot -- ordinary block scoping, even though this compiler otherwise
+    /// treats variables as function-flat (see `get_or_create_var`).
+    fn exit_scope(&mut self) {
+        if let Some(names) = self.scope_stack.pop() {
+            for name in names {
+                if let Some(idx) = self.local_vars.remove(&name) {
+                    self.free_locals.push(idx);
+                }
+            }
+        }
+    }
+
+    /// Returns `value`'s index into the module's constant pool, compiling
+    /// it in the first time this exact value is seen. Two literals (or the
+    /// `For`/`Return` default-value pushes) that compile to the same
+    /// `Value` share one pool slot instead of each getting its own inline
+    /// copy.
+    fn constant_idx(&mut self, value: Value) -> u32 {
+        let key = constant_key(&value);
+        if let Some(&idx) = self.constant_indices.get(&key) {
+            return idx;
+        }
+        let idx = self.module.constants.len() as u32;
+        self.constant_indices.insert(key, idx);
+        self.module.constants.push(value);
+        idx
+    }
+
     fn number_to_value(&self, num: &Number) -> Value {
         match num {
             Number::Int(n) => Value::Int(*n),
@@ -519,6 +1408,19 @@ pub struct BytecodeVM {
     globals: Vec<Value>,
     call_stack: Vec<(usize, usize, usize)>,  // (return_addr, locals_base, prev_locals_count)
     ip: usize,
+    back_edge_count: usize,
+    /// How many `BeginReverse`s we're nested inside of right now. Only the
+    /// outermost span (0 -> 1) starts a fresh journal and only its close
+    /// (1 -> 0) hands the journal off to `last_reverse_journal` -- a nested
+    /// `reverse` (reachable through a reversible `If`'s branches) just adds
+    /// its writes to the same journal as the block enclosing it.
+    reverse_depth: usize,
+    /// `(local_index, value_before_this_store)` for every `StoreLocal`
+    /// executed while `reverse_depth > 0`, oldest first.
+    reverse_journal: Vec<(usize, Value)>,
+    /// The journal from the most recently closed top-level reverse block,
+    /// consumed (and cleared) by `execute_reverse`.
+    last_reverse_journal: Option<Vec<(usize, Value)>>,
 }
 
 impl BytecodeVM {
@@ -529,14 +1431,27 @@ impl BytecodeVM {
             globals: vec![],
             call_stack: vec![],
             ip: 0,
+            back_edge_count: 0,
+            reverse_depth: 0,
+            reverse_journal: vec![],
+            last_reverse_journal: None,
         }
     }
 
+    /// The VM's local-variable slots as they currently stand -- read-only
+    /// introspection for callers (and tests) that want to check a local's
+    /// value directly rather than through whatever the top-level code left
+    /// on the stack, e.g. to confirm `execute_reverse` actually restored it.
+    pub fn locals(&self) -> &[Value] {
+        &self.locals
+    }
+
     pub fn execute(&mut self, module: &CompiledModule) -> Result<Option<Value>> {
         self.ip = 0;
         self.stack.clear();
         self.locals.clear();
         self.globals.clear();
+        self.back_edge_count = 0;
 
         // Initialize globals
         for _ in &module.globals {
@@ -554,11 +1469,73 @@ impl BytecodeVM {
         Ok(self.stack.pop())
     }
 
+    /// Execute a `Chunk`'s code directly off its byte-oriented form,
+    /// without ever rebuilding a `Vec<Opcode>` -- this is the path a
+    /// `.jtvc` file loaded through `CompiledModule::deserialize` can run
+    /// straight from. `self.ip` walks `chunk.code` in byte offsets: each
+    /// iteration reads the tag byte, decodes just that one instruction's
+    /// operand (resolving `PushConst` through `chunk.constants`), dispatches
+    /// it through the same `execute_instruction` every other path uses, and
+    /// then advances by the instruction's encoded width instead of by one
+    /// `Opcode` slot.
+    pub fn execute_chunk(&mut self, chunk: &Chunk, module: &CompiledModule) -> Result<Option<Value>> {
+        self.ip = 0;
+        self.stack.clear();
+        self.locals.clear();
+        self.globals.clear();
+        self.back_edge_count = 0;
+
+        for _ in &module.globals {
+            self.globals.push(Value::Unit);
+        }
+
+        while self.ip < chunk.code.len() {
+            let (op, len) = decode_one(chunk.code[self.ip], self.ip, &chunk.code, &chunk.constants, Ok)?;
+            if !self.execute_instruction(&op, module)? {
+                break;
+            }
+            self.ip += len as usize;
+        }
+
+        Ok(self.stack.pop())
+    }
+
+    /// Undo the most recently executed top-level `reverse { ... }` block:
+    /// restores every local `execute`'s forward pass recorded a write to,
+    /// oldest write last so a local touched more than once inside the
+    /// block unwinds back through each intermediate value to the one it
+    /// held just before the block ran. `module` isn't needed to replay the
+    /// journal itself, but is taken to mirror `execute`/`execute_chunk`'s
+    /// signature and leave room for validating the journal against it
+    /// later.
+    ///
+    /// Errors if no reverse block has run since the last `execute` (or
+    /// since the journal was last consumed) -- there is nothing to undo.
+    pub fn execute_reverse(&mut self, _module: &CompiledModule) -> Result<()> {
+        let journal = self.last_reverse_journal.take().ok_or_else(|| {
+            JtvError::RuntimeError(
+                "execute_reverse called with no reverse block to undo -- run `execute` over a \
+                 module containing a `reverse` block first"
+                    .to_string(),
+            )
+        })?;
+        for (index, old_value) in journal.into_iter().rev() {
+            if index < self.locals.len() {
+                self.locals[index] = old_value;
+            }
+        }
+        Ok(())
+    }
+
     fn execute_instruction(&mut self, op: &Opcode, module: &CompiledModule) -> Result<bool> {
         match op {
             Opcode::Push(value) => {
                 self.stack.push(value.clone());
             }
+            Opcode::LoadConst(idx) => {
+                let value = module.constants.get(*idx as usize).cloned().unwrap_or(Value::Unit);
+                self.stack.push(value);
+            }
             Opcode::Pop => {
                 self.stack.pop();
             }
@@ -589,6 +1566,9 @@ impl BytecodeVM {
                 while self.locals.len() <= target {
                     self.locals.push(Value::Unit);
                 }
+                if self.reverse_depth > 0 {
+                    self.reverse_journal.push((target, self.locals[target].clone()));
+                }
                 self.locals[target] = value;
             }
             Opcode::LoadGlobal(idx) => {
@@ -657,21 +1637,35 @@ impl BytecodeVM {
                 let a = self.stack.pop().unwrap_or(Value::Bool(true));
                 self.stack.push(Value::Bool(!self.is_truthy(&a)));
             }
+            Opcode::Contains => {
+                let collection = self.stack.pop().unwrap_or(Value::Unit);
+                let needle = self.stack.pop().unwrap_or(Value::Unit);
+                let found = match &collection {
+                    Value::List(items) | Value::Tuple(items) => items.contains(&needle),
+                    other => {
+                        return Err(JtvError::TypeError(format!(
+                            "cannot test membership in {} (expected a List or Tuple)",
+                            other
+                        )));
+                    }
+                };
+                self.stack.push(Value::Bool(found));
+            }
             Opcode::Jump(addr) => {
-                self.ip = *addr as usize;
+                self.take_branch(*addr)?;
                 return Ok(true);
             }
             Opcode::JumpIfFalse(addr) => {
                 let cond = self.stack.pop().unwrap_or(Value::Bool(false));
                 if !self.is_truthy(&cond) {
-                    self.ip = *addr as usize;
+                    self.take_branch(*addr)?;
                     return Ok(true);
                 }
             }
             Opcode::JumpIfTrue(addr) => {
                 let cond = self.stack.pop().unwrap_or(Value::Bool(false));
                 if self.is_truthy(&cond) {
-                    self.ip = *addr as usize;
+                    self.take_branch(*addr)?;
                     return Ok(true);
                 }
             }
@@ -740,8 +1734,38 @@ impl BytecodeVM {
                 items.reverse();
                 self.stack.push(Value::Tuple(items));
             }
-            Opcode::BeginReverse | Opcode::EndReverse => {
-                // Markers for reversible sections
+            Opcode::Index => {
+                let index = self.stack.pop().unwrap_or(Value::Unit);
+                let base = self.stack.pop().unwrap_or(Value::Unit);
+                let i = match index {
+                    Value::Int(n) if n >= 0 => n as usize,
+                    _ => return Err(JtvError::TypeError("Index must be a non-negative integer".to_string())),
+                };
+                let result = match base {
+                    Value::List(items) | Value::Tuple(items) => items.get(i).cloned().ok_or_else(|| {
+                        let diag = Diagnostic::new(DiagnosticKind::IndexOutOfRange {
+                            index: i as i64,
+                            size: items.len(),
+                        });
+                        JtvError::RuntimeError(diag.to_string())
+                    })?,
+                    other => {
+                        return Err(JtvError::TypeError(format!("Cannot index into {:?}", other)));
+                    }
+                };
+                self.stack.push(result);
+            }
+            Opcode::BeginReverse => {
+                if self.reverse_depth == 0 {
+                    self.reverse_journal.clear();
+                }
+                self.reverse_depth += 1;
+            }
+            Opcode::EndReverse => {
+                self.reverse_depth = self.reverse_depth.saturating_sub(1);
+                if self.reverse_depth == 0 {
+                    self.last_reverse_journal = Some(std::mem::take(&mut self.reverse_journal));
+                }
             }
             Opcode::Halt => {
                 return Ok(false);
@@ -750,6 +1774,22 @@ impl BytecodeVM {
         Ok(true)
     }
 
+    /// Jump to `addr`, counting it as a back-edge (and enforcing
+    /// `MAX_ITERATIONS`) whenever it jumps to an address we've already
+    /// passed -- exactly the loop-repeat edges `While`/`For` compile down
+    /// to in `BytecodeCompiler::compile_control_stmt`.
+    fn take_branch(&mut self, addr: u32) -> Result<()> {
+        let addr = addr as usize;
+        if addr <= self.ip {
+            self.back_edge_count += 1;
+            if self.back_edge_count > MAX_ITERATIONS {
+                return Err(JtvError::MaxIterationsExceeded);
+            }
+        }
+        self.ip = addr;
+        Ok(())
+    }
+
     fn add_values(&self, a: &Value, b: &Value) -> Result<Value> {
         match (a, b) {
             (Value::Int(x), Value::Int(y)) => Ok(Value::Int(x + y)),
@@ -866,4 +1906,453 @@ mod tests {
         let mut vm = BytecodeVM::new();
         vm.execute(&module).unwrap();
     }
+
+    #[test]
+    fn test_compile_contains_condition() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::If(IfStmt {
+                condition: ControlExpr::Contains(
+                    Box::new(DataExpr::Number(Number::Int(2))),
+                    Box::new(DataExpr::List(vec![
+                        DataExpr::Number(Number::Int(1)),
+                        DataExpr::Number(Number::Int(2)),
+                        DataExpr::Number(Number::Int(3)),
+                    ])),
+                ),
+                then_branch: vec![ControlStmt::Assignment(Assignment {
+                    target: "y".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                })],
+                else_branch: None,
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+    }
+
+    #[test]
+    fn test_and_short_circuits_a_failing_right_operand() {
+        // `5 in 3` would error at runtime (3 is not a List/Tuple), but the
+        // left side of `&&` is false, so a short-circuiting compile must
+        // never execute it.
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Control(ControlExpr::Logical(
+                    Box::new(ControlExpr::Data(DataExpr::Number(Number::Int(0)))),
+                    LogicalOp::And,
+                    Box::new(ControlExpr::Contains(
+                        Box::new(DataExpr::Number(Number::Int(5))),
+                        Box::new(DataExpr::Number(Number::Int(3))),
+                    )),
+                )),
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+    }
+
+    #[test]
+    fn test_or_short_circuits_a_failing_right_operand() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Control(ControlExpr::Logical(
+                    Box::new(ControlExpr::Comparison(
+                        Box::new(DataExpr::Number(Number::Int(1))),
+                        Comparator::Eq,
+                        Box::new(DataExpr::Number(Number::Int(1))),
+                    )),
+                    LogicalOp::Or,
+                    Box::new(ControlExpr::Contains(
+                        Box::new(DataExpr::Number(Number::Int(5))),
+                        Box::new(DataExpr::Number(Number::Int(3))),
+                    )),
+                )),
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+    }
+
+    #[test]
+    fn test_and_still_evaluates_right_operand_when_left_is_true() {
+        // The left side doesn't decide the result here, so the right side
+        // must actually run -- and its error (3 isn't a List/Tuple) must
+        // surface, proving the jump correctly falls through to `right`
+        // instead of always skipping it.
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Control(ControlExpr::Logical(
+                    Box::new(ControlExpr::Comparison(
+                        Box::new(DataExpr::Number(Number::Int(1))),
+                        Comparator::Eq,
+                        Box::new(DataExpr::Number(Number::Int(1))),
+                    )),
+                    LogicalOp::And,
+                    Box::new(ControlExpr::Contains(
+                        Box::new(DataExpr::Number(Number::Int(5))),
+                        Box::new(DataExpr::Number(Number::Int(3))),
+                    )),
+                )),
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let mut vm = BytecodeVM::new();
+        assert!(vm.execute(&module).is_err());
+    }
+
+    #[test]
+    fn test_break_compiles_inside_a_while_loop() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                    Comparator::Eq,
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                ),
+                body: vec![ControlStmt::Break(None)],
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        assert!(compiler.compile(&program).is_ok());
+    }
+
+    #[test]
+    fn test_continue_compiles_inside_a_for_loop() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::For(ForStmt {
+                variable: "i".to_string(),
+                range: RangeExpr {
+                    start: Box::new(DataExpr::Number(Number::Int(0))),
+                    end: Box::new(DataExpr::Number(Number::Int(5))),
+                    step: None,
+                },
+                body: vec![ControlStmt::Continue(None)],
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        assert!(compiler.compile(&program).is_ok());
+    }
+
+    #[test]
+    fn test_break_outside_a_loop_is_a_compile_error() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::Break(None))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        assert!(compiler.compile(&program).is_err());
+    }
+
+    #[test]
+    fn test_continue_outside_a_loop_is_a_compile_error() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::Continue(None))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        assert!(compiler.compile(&program).is_err());
+    }
+
+    #[test]
+    fn test_labeled_break_is_not_supported_yet() {
+        let program = Program {
+            statements: vec![TopLevel::Control(ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                    Comparator::Eq,
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                ),
+                body: vec![ControlStmt::Break(Some("outer".to_string()))],
+            }))],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        assert!(compiler.compile(&program).is_err());
+    }
+
+    #[test]
+    fn test_nonterminating_loop_hits_max_iterations() {
+        let code = r#"
+            x = 0
+            while x == 0 {
+                x = x
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let mut vm = BytecodeVM::new();
+        assert!(matches!(
+            vm.execute(&module),
+            Err(JtvError::MaxIterationsExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_constant_pool_dedups_identical_literals() {
+        let code = r#"
+            fn f(): Int {
+                x = 5
+                y = 5
+                return x + y
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        assert_eq!(module.constants, vec![Value::Int(5)]);
+        let load_consts: Vec<&Opcode> = module.functions[0]
+            .code
+            .iter()
+            .filter(|op| matches!(op, Opcode::LoadConst(_)))
+            .collect();
+        assert_eq!(load_consts.len(), 2);
+        assert!(load_consts.iter().all(|op| matches!(op, Opcode::LoadConst(0))));
+    }
+
+    #[test]
+    fn test_scoped_locals_are_reused_after_block_exit() {
+        // Two sibling top-level blocks, each introducing exactly one new
+        // local under a different name. Without scope-exit reuse this
+        // would need two distinct slots; with it, the second block's `b`
+        // reuses the slot `a` freed when the first block exited.
+        let program = Program {
+            statements: vec![
+                TopLevel::Control(ControlStmt::Block(vec![ControlStmt::Assignment(Assignment {
+                    target: "a".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                })])),
+                TopLevel::Control(ControlStmt::Block(vec![ControlStmt::Assignment(Assignment {
+                    target: "b".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(2))),
+                })])),
+            ],
+            span: Span::unknown(),
+        };
+
+        let mut compiler = BytecodeCompiler::new();
+        compiler.compile(&program).unwrap();
+        assert_eq!(compiler.next_local, 1);
+    }
+
+    #[test]
+    fn test_chunk_roundtrips_through_encode_and_decode() {
+        let code = r#"
+            fn add(a: Int, b: Int): Int {
+                return a + b
+            }
+            x = 5
+            if x > 0 {
+                y = add(x, 1)
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        // `Chunk::encode` resolves every `LoadConst` against the module's
+        // constant pool and re-interns the literal as a chunk-local
+        // `PushConst`, so `decode` hands back `Push(value)` rather than the
+        // original `LoadConst(idx)` -- same runtime value, different
+        // opcode shape. Normalize that one difference away before
+        // comparing.
+        let expected: Vec<Opcode> = module.code.iter().map(|op| match op {
+            Opcode::LoadConst(idx) => Opcode::Push(module.constants[*idx as usize].clone()),
+            other => other.clone(),
+        }).collect();
+
+        let chunk = Chunk::encode(&module.code, &module.constants);
+        assert_eq!(chunk.decode().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_chunk_push_becomes_a_constant_pool_lookup() {
+        let ops = vec![Opcode::Push(Value::Int(42)), Opcode::Halt];
+        let chunk = Chunk::encode(&ops, &[]);
+        assert_eq!(chunk.constants, vec![Value::Int(42)]);
+        assert_eq!(chunk.decode().unwrap(), ops);
+    }
+
+    #[test]
+    fn test_chunk_load_const_resolves_against_the_module_pool() {
+        let module_constants = vec![Value::Int(7)];
+        let ops = vec![Opcode::LoadConst(0), Opcode::Halt];
+        let chunk = Chunk::encode(&ops, &module_constants);
+        assert_eq!(chunk.constants, vec![Value::Int(7)]);
+        assert_eq!(chunk.decode().unwrap(), vec![Opcode::Push(Value::Int(7)), Opcode::Halt]);
+    }
+
+    #[test]
+    fn test_chunk_jump_targets_are_byte_offsets_not_instruction_indices() {
+        // `Jump(2)` targets the third instruction (`Halt`); `Jump` itself
+        // encodes to 5 bytes and `Pop` to 1, so `Halt`'s byte offset is 6,
+        // not its instruction index of 2.
+        let ops = vec![Opcode::Jump(2), Opcode::Pop, Opcode::Halt];
+        let chunk = Chunk::encode(&ops, &[]);
+        assert_eq!(&chunk.code[1..5], &6u32.to_le_bytes());
+        assert_eq!(chunk.decode().unwrap(), ops);
+    }
+
+    #[test]
+    fn test_execute_chunk_matches_execute_on_the_same_module() {
+        let code = "x = 5 + 3";
+        let program = parse_program(code).unwrap();
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+        let chunk = Chunk::encode(&module.code, &module.constants);
+
+        let mut vm = BytecodeVM::new();
+        assert!(vm.execute_chunk(&chunk, &module).is_ok());
+    }
+
+    #[test]
+    fn test_compiled_module_serialize_deserialize_roundtrip() {
+        let code = r#"
+            fn add(a: Int, b: Int): Int {
+                return a + b
+            }
+            result = add(5, 3)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut compiler = BytecodeCompiler::new();
+        let module = compiler.compile(&program).unwrap();
+
+        let bytes = module.serialize();
+        let restored = CompiledModule::deserialize(&bytes).unwrap();
+
+        // As in `test_chunk_roundtrips_through_encode_and_decode`, the
+        // round trip normalizes `LoadConst` into a `Push` carrying the
+        // same resolved value.
+        let expected: Vec<Opcode> = module.code.iter().map(|op| match op {
+            Opcode::LoadConst(idx) => Opcode::Push(module.constants[*idx as usize].clone()),
+            other => other.clone(),
+        }).collect();
+        assert_eq!(restored.code, expected);
+        assert_eq!(restored.functions.len(), module.functions.len());
+        assert_eq!(restored.functions[0].name, module.functions[0].name);
+        assert_eq!(restored.functions[0].code, module.functions[0].code);
+
+        let mut vm = BytecodeVM::new();
+        assert!(vm.execute(&restored).is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_truncated_bytes() {
+        let ops = vec![Opcode::Halt];
+        let module = CompiledModule {
+            functions: vec![],
+            globals: vec![],
+            entry_point: 0,
+            code: ops,
+            constants: vec![],
+        };
+        let mut bytes = module.serialize();
+        bytes.truncate(bytes.len() - 1);
+        assert!(CompiledModule::deserialize(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_execute_reverse_undoes_a_reverse_blocks_local_writes() {
+        let module = CompiledModule {
+            functions: vec![],
+            globals: vec![],
+            entry_point: 0,
+            code: vec![
+                Opcode::Push(Value::Int(10)),
+                Opcode::StoreLocal(0),
+                Opcode::BeginReverse,
+                Opcode::LoadLocal(0),
+                Opcode::Push(Value::Int(5)),
+                Opcode::Add,
+                Opcode::StoreLocal(0),
+                Opcode::EndReverse,
+                Opcode::Halt,
+            ],
+            constants: vec![],
+        };
+
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+        assert_eq!(vm.locals()[0], Value::Int(15));
+
+        vm.execute_reverse(&module).unwrap();
+        assert_eq!(vm.locals()[0], Value::Int(10));
+    }
+
+    #[test]
+    fn test_execute_reverse_unwinds_multiple_writes_to_the_same_local() {
+        let module = CompiledModule {
+            functions: vec![],
+            globals: vec![],
+            entry_point: 0,
+            code: vec![
+                Opcode::Push(Value::Int(0)),
+                Opcode::StoreLocal(0),
+                Opcode::BeginReverse,
+                Opcode::LoadLocal(0),
+                Opcode::Push(Value::Int(3)),
+                Opcode::Add,
+                Opcode::StoreLocal(0),
+                Opcode::LoadLocal(0),
+                Opcode::Push(Value::Int(4)),
+                Opcode::Add,
+                Opcode::StoreLocal(0),
+                Opcode::EndReverse,
+                Opcode::Halt,
+            ],
+            constants: vec![],
+        };
+
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+        assert_eq!(vm.locals()[0], Value::Int(7));
+
+        vm.execute_reverse(&module).unwrap();
+        assert_eq!(vm.locals()[0], Value::Int(0));
+    }
+
+    #[test]
+    fn test_execute_reverse_without_a_prior_reverse_block_is_an_error() {
+        let module = CompiledModule {
+            functions: vec![],
+            globals: vec![],
+            entry_point: 0,
+            code: vec![Opcode::Halt],
+            constants: vec![],
+        };
+        let mut vm = BytecodeVM::new();
+        vm.execute(&module).unwrap();
+        assert!(vm.execute_reverse(&module).is_err());
+    }
 }
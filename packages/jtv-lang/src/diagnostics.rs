@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Structured diagnostics
+//
+// A `zinc`-style `expected`/`found` diagnostic layer, built on the `Span`
+// that `crate::ast` now carries on its major nodes. Unlike `JtvError`
+// (still the interpreter's `Result<T, _>` error type, unchanged so nothing
+// downstream breaks), a `Diagnostic` is data: it can be serialized,
+// compared in a test, and rendered against a source string once a caller
+// has one on hand, instead of only being a formatted message.
+
+use crate::ast::Span;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A classified problem, analogous to `TypeErrorKind`
+/// (`crate::typechecker`) but for the runtime/bytecode layer rather than
+/// the type checker -- the two don't share a type because they're
+/// populated by different passes with different concerns (inferred types
+/// vs. concrete runtime values).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// An operation expected one shape of value and got another -- e.g. the
+    /// bytecode VM's operand stack receiving a value it can't push into the
+    /// typed slot a `StoreLocal` targets.
+    PushingInvalidType { expected: String, found: String },
+    /// A `List`/`Tuple` index fell outside `[0, size)`.
+    IndexOutOfRange { index: i64, size: usize },
+    /// A zinc-style expected/found mismatch, e.g. from
+    /// `crate::typechecker::TypeChecker` failing to unify two types. The
+    /// fields are pre-rendered strings (rather than `crate::typechecker::Type`)
+    /// so this module doesn't need to depend on the type checker's internals.
+    TypeMismatch { expected: String, found: String },
+    /// A reference to a variable the type checker has no binding for in
+    /// scope.
+    UndefinedVariable { name: String },
+    /// A call naming a function the type checker couldn't resolve.
+    UndefinedFunction { name: String },
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::PushingInvalidType { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            DiagnosticKind::IndexOutOfRange { index, size } => {
+                write!(f, "index {} out of range for a collection of size {}", index, size)
+            }
+            DiagnosticKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            DiagnosticKind::UndefinedVariable { name } => {
+                write!(f, "undefined variable `{}`", name)
+            }
+            DiagnosticKind::UndefinedFunction { name } => {
+                write!(f, "undefined function `{}`", name)
+            }
+        }
+    }
+}
+
+/// A `DiagnosticKind` located at a (possibly unknown) source `Span`.
+/// `location` is `None` when the value that triggered it never had a span
+/// attached to begin with -- e.g. a list built by `Value::List` at
+/// runtime rather than parsed from source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub location: Option<Span>,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind) -> Self {
+        Diagnostic { location: None, kind }
+    }
+
+    pub fn at(location: Span, kind: DiagnosticKind) -> Self {
+        Diagnostic { location: Some(location), kind }
+    }
+
+    /// Renders `file:line:col: {kind}` followed by the offending source
+    /// line and a `^` caret under its start column, the way
+    /// `TypeChecker::render_diagnostics` renders a byte-offset span today.
+    /// Falls back to a location-less one-liner when `self.location` is
+    /// `None` or doesn't resolve against `source` (e.g. `Span::unknown()`).
+    pub fn render(&self, file: &str, source: &str) -> String {
+        match self.location.and_then(|span| span.source_line(source).map(|line| (span, line))) {
+            Some((span, line_text)) => format!(
+                "{}:{}:{}: {}\n  | {}\n  | {}^",
+                file,
+                span.line,
+                span.col,
+                self.kind,
+                line_text,
+                " ".repeat(span.col.saturating_sub(1) as usize)
+            ),
+            None => format!("{}: {}", file, self.kind),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(span) => write!(f, "{}:{}: {}", span.line, span.col, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_out_of_range_display() {
+        let diag = Diagnostic::new(DiagnosticKind::IndexOutOfRange { index: 5, size: 3 });
+        assert_eq!(diag.to_string(), "index 5 out of range for a collection of size 3");
+    }
+
+    #[test]
+    fn test_type_mismatch_display() {
+        let diag = Diagnostic::new(DiagnosticKind::TypeMismatch {
+            expected: "Int".to_string(),
+            found: "List<Int>".to_string(),
+        });
+        assert_eq!(diag.to_string(), "expected Int, found List<Int>");
+    }
+
+    #[test]
+    fn test_render_with_source_location() {
+        let source = "let xs = [1, 2, 3]\nprint(xs[5])\n";
+        let span = Span::from_offsets(source, 27, 30); // the `[5]` subscript on line 2
+        let diag = Diagnostic::at(span, DiagnosticKind::IndexOutOfRange { index: 5, size: 3 });
+        let rendered = diag.render("example.jtv", source);
+        assert!(rendered.starts_with("example.jtv:2:9:"));
+        assert!(rendered.contains("print(xs[5])"));
+    }
+
+    #[test]
+    fn test_unknown_span_falls_back_to_plain_message() {
+        let diag = Diagnostic::new(DiagnosticKind::PushingInvalidType {
+            expected: "Int".to_string(),
+            found: "Float".to_string(),
+        });
+        assert_eq!(diag.render("example.jtv", ""), "example.jtv: expected Int, found Float");
+    }
+
+    #[test]
+    fn test_undefined_variable_display() {
+        let diag = Diagnostic::new(DiagnosticKind::UndefinedVariable { name: "x".to_string() });
+        assert_eq!(diag.to_string(), "undefined variable `x`");
+    }
+
+    #[test]
+    fn test_diagnostic_round_trips_through_json() {
+        let diag = Diagnostic::at(
+            Span { start: 10, end: 12, line: 2, col: 3 },
+            DiagnosticKind::IndexOutOfRange { index: -1, size: 0 },
+        );
+        let json = serde_json::to_string(&diag).unwrap();
+        let back: Diagnostic = serde_json::from_str(&json).unwrap();
+        assert_eq!(diag, back);
+    }
+}
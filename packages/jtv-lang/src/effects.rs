@@ -0,0 +1,133 @@
+// A fine-grained effect lattice backing `purity::Purity`'s three coarse
+// levels, so a `PurityDiagnostic` can say exactly which effect a function
+// performs instead of lumping "contains a `while`" and "calls `print`"
+// together as one generic complaint.
+//
+// This only backs `Purity` -- it doesn't replace it. There's no
+// `@effects(io, alloc)` annotation surface here: `parser` (see `lib.rs`'s
+// `mod parser`) isn't present in this checkout to extend with one, so
+// `FunctionDecl::purity` stays the only thing a program can actually
+// declare. `Effect`/`EffectSet` exist so `purity::PurityChecker` can
+// *attribute* a violation to a specific effect while still gating on the
+// same three levels it always has.
+//
+// Only the effects this checker can actually observe today are ever
+// produced: `Io` (a `print`), `Mutation` (a write that escapes the
+// function's own frame), and `NonTermination` (an unbounded `while`/`for`).
+// `Alloc`, `Random`, and `Partial` are modeled here for completeness with
+// the full effect set a richer annotation language would eventually need,
+// but nothing in `PurityChecker` raises them yet -- there's no random-number
+// primitive in this AST, and list/tuple construction and indexing aren't
+// restricted under `@pure` today. Adding a check for either would change
+// which already-accepted programs compile, so that's left for a future
+// request rather than folded in here silently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Printing or any other observable input/output.
+    Io,
+    /// Allocating a new collection (a `List`/`Tuple` construction).
+    Alloc,
+    /// A loop the checker can't prove terminates.
+    NonTermination,
+    /// A write to a name bound outside the writer's own frame.
+    Mutation,
+    /// Drawing from a non-deterministic source.
+    Random,
+    /// An operation that can fail at runtime (e.g. an out-of-bounds index).
+    Partial,
+}
+
+impl Effect {
+    /// The lowercase name a `@effects(...)` annotation would spell this
+    /// effect with, and what diagnostic text names it by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Effect::Io => "io",
+            Effect::Alloc => "alloc",
+            Effect::NonTermination => "non-termination",
+            Effect::Mutation => "mutation",
+            Effect::Random => "random",
+            Effect::Partial => "partial",
+        }
+    }
+
+    const fn bit(&self) -> u8 {
+        match self {
+            Effect::Io => 1 << 0,
+            Effect::Alloc => 1 << 1,
+            Effect::NonTermination => 1 << 2,
+            Effect::Mutation => 1 << 3,
+            Effect::Random => 1 << 4,
+            Effect::Partial => 1 << 5,
+        }
+    }
+}
+
+/// A set of `Effect`s, represented as a bitmask -- a function's declared
+/// purity corresponds to an allowed `EffectSet`, and its body's actual
+/// effects (unioned together with `combine`) must `satisfy` it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EffectSet(u8);
+
+impl EffectSet {
+    pub const fn empty() -> Self {
+        EffectSet(0)
+    }
+
+    pub const fn all() -> Self {
+        EffectSet(
+            Effect::Io.bit()
+                | Effect::Alloc.bit()
+                | Effect::NonTermination.bit()
+                | Effect::Mutation.bit()
+                | Effect::Random.bit()
+                | Effect::Partial.bit(),
+        )
+    }
+
+    pub const fn of(effect: Effect) -> Self {
+        EffectSet(effect.bit())
+    }
+
+    pub fn contains(&self, effect: Effect) -> bool {
+        self.0 & effect.bit() != 0
+    }
+
+    /// Set union: every effect either set names.
+    pub fn combine(self, other: EffectSet) -> EffectSet {
+        EffectSet(self.0 | other.0)
+    }
+
+    /// Is every effect in `self` also allowed by `allowed` -- i.e. is
+    /// `self` a subset of `allowed`?
+    pub fn satisfies(&self, allowed: EffectSet) -> bool {
+        self.0 & !allowed.0 == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_set_satisfies_any_mask() {
+        assert!(EffectSet::empty().satisfies(EffectSet::empty()));
+        assert!(EffectSet::empty().satisfies(EffectSet::of(Effect::Io)));
+    }
+
+    #[test]
+    fn combine_is_union() {
+        let set = EffectSet::of(Effect::Io).combine(EffectSet::of(Effect::Alloc));
+        assert!(set.contains(Effect::Io));
+        assert!(set.contains(Effect::Alloc));
+        assert!(!set.contains(Effect::Mutation));
+    }
+
+    #[test]
+    fn satisfies_is_subset_containment() {
+        let effects = EffectSet::of(Effect::Io).combine(EffectSet::of(Effect::Mutation));
+        assert!(!effects.satisfies(EffectSet::of(Effect::Io)));
+        assert!(effects.satisfies(EffectSet::all()));
+        assert!(effects.satisfies(effects));
+    }
+}
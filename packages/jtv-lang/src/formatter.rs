@@ -4,6 +4,8 @@
 // Julia the Viper - Code Formatter
 
 use crate::ast::*;
+use crate::error::JtvError;
+use std::collections::HashMap;
 
 /// Configuration options for the formatter
 #[derive(Debug, Clone)]
@@ -16,6 +18,15 @@ pub struct FormatConfig {
     pub blank_lines_between_items: bool,
     /// Use spaces around operators
     pub spaces_around_operators: bool,
+    /// Reorder the leading run of `import` statements at the top of a
+    /// program/module into groups (standard-library paths, then local
+    /// ones -- see `is_stdlib_import`), sorted lexicographically by path
+    /// within each group and separated by a blank line -- the layout
+    /// ruff's isort and rustfmt's `group_imports` produce. Off by default
+    /// so existing callers see import order preserved exactly as written.
+    /// Reordering doesn't check for import cycles itself; call
+    /// `check_import_cycles` first if that matters to the caller.
+    pub sort_imports: bool,
 }
 
 impl Default for FormatConfig {
@@ -25,14 +36,378 @@ impl Default for FormatConfig {
             max_line_length: 100,
             blank_lines_between_items: true,
             spaces_around_operators: true,
+            sort_imports: false,
         }
     }
 }
 
+/// A Wadler/Oppen-style pretty-printing document. `Formatter`'s `format_*`
+/// methods build one of these instead of writing straight to a string, so
+/// a single `render` pass can decide -- per `Group`, based on whether its
+/// contents fit in the remaining width -- whether to lay it out on one
+/// line or explode it across several.
+#[derive(Debug, Clone)]
+enum Doc {
+    /// Literal text, opaque to layout. May itself contain `\n` (used for
+    /// constructs, like blocks, that always break regardless of width).
+    Text(String),
+    /// A break that renders as a single space when its enclosing group is
+    /// flattened, or a newline + current indent when it isn't.
+    Line,
+    /// Like `Line`, but renders as nothing at all when flattened.
+    SoftLine,
+    Concat(Vec<Doc>),
+    /// Increases the indent used by `Line`/`SoftLine` breaks within `Doc`
+    /// by `usize` columns.
+    Indent(usize, Box<Doc>),
+    /// Tries to render its contents on one line; falls back to breaking
+    /// every `Line`/`SoftLine` inside if that doesn't fit in the
+    /// remaining width. Does not look past its own contents to decide.
+    Group(Box<Doc>),
+    /// Renders as the first `Doc` if the enclosing group broke, or the
+    /// second if it stayed flat -- the standard companion to `Group`/
+    /// `Line` needed for things like trailing commas that should only
+    /// appear when a list is exploded one-per-line.
+    IfBreak(Box<Doc>, Box<Doc>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Renders `doc` to a string, wrapping `Group`s that don't fit within
+/// `max_width` columns.
+fn render(doc: &Doc, max_width: usize) -> String {
+    let mut out = String::new();
+    let mut column = 0usize;
+    // (indent, mode, doc) triples, popped in render order -- `Concat`
+    // pushes its children in reverse so they pop left-to-right.
+    let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(s) => {
+                out.push_str(s);
+                column = match s.rfind('\n') {
+                    Some(pos) => s[pos + 1..].chars().count(),
+                    None => column + s.chars().count(),
+                };
+            }
+            Doc::Line => match mode {
+                Mode::Flat => {
+                    out.push(' ');
+                    column += 1;
+                }
+                Mode::Break => {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent));
+                    column = indent;
+                }
+            },
+            Doc::SoftLine => match mode {
+                Mode::Flat => {}
+                Mode::Break => {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent));
+                    column = indent;
+                }
+            },
+            Doc::Concat(parts) => {
+                for part in parts.iter().rev() {
+                    stack.push((indent, mode, part));
+                }
+            }
+            Doc::Indent(extra, inner) => stack.push((indent + extra, mode, inner)),
+            Doc::Group(inner) => {
+                let group_mode = if fits(max_width.saturating_sub(column), inner) {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.push((indent, group_mode, inner));
+            }
+            Doc::IfBreak(break_doc, flat_doc) => {
+                let chosen = if mode == Mode::Break { break_doc } else { flat_doc };
+                stack.push((indent, mode, chosen));
+            }
+        }
+    }
+
+    out
+}
+
+/// Whether `doc` would fit within `remaining_width` columns if rendered
+/// flat: a forward scan summing `Text` lengths and counting each
+/// `Line` as one column (`SoftLine` as zero), bailing out as soon as the
+/// budget is exceeded.
+fn fits(remaining_width: usize, doc: &Doc) -> bool {
+    let mut width = remaining_width as i64;
+    let mut stack: Vec<&Doc> = vec![doc];
+
+    while let Some(doc) = stack.pop() {
+        if width < 0 {
+            return false;
+        }
+        match doc {
+            Doc::Text(s) => width -= s.chars().count() as i64,
+            Doc::Line => width -= 1,
+            Doc::SoftLine => {}
+            Doc::Concat(parts) => {
+                for part in parts.iter().rev() {
+                    stack.push(part);
+                }
+            }
+            Doc::Indent(_, inner) => stack.push(inner),
+            Doc::Group(inner) => stack.push(inner),
+            // A flat measurement always takes the flat branch.
+            Doc::IfBreak(_, flat_doc) => stack.push(flat_doc),
+        }
+    }
+
+    width >= 0
+}
+
+/// Builds a bracket-delimited, comma-separated `Group` that stays inline
+/// when it fits and explodes one item per line (with a trailing comma)
+/// when it doesn't -- the shape every wrappable list in this formatter
+/// (parameters, call arguments, list/tuple literal elements) shares.
+fn bracketed_list(open: &str, items: Vec<Doc>, close: &str, indent_size: usize) -> Doc {
+    if items.is_empty() {
+        return Doc::Text(format!("{}{}", open, close));
+    }
+
+    let last = items.len() - 1;
+    let mut body = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        body.push(item);
+        if i != last {
+            body.push(Doc::Text(",".to_string()));
+            body.push(Doc::Line);
+        } else {
+            body.push(Doc::IfBreak(
+                Box::new(Doc::Text(",".to_string())),
+                Box::new(Doc::Text(String::new())),
+            ));
+        }
+    }
+
+    Doc::Group(Box::new(Doc::Concat(vec![
+        Doc::Text(open.to_string()),
+        Doc::Indent(
+            indent_size,
+            Box::new(Doc::Concat(vec![Doc::SoftLine, Doc::Concat(body)])),
+        ),
+        Doc::SoftLine,
+        Doc::Text(close.to_string()),
+    ])))
+}
+
+/// Binding levels for the data language, used to decide when
+/// `format_data_expr_prec` must parenthesize a child expression. Higher
+/// binds tighter; everything not listed (numbers, identifiers, calls,
+/// lists/tuples, field access, indexing, struct literals, comprehensions)
+/// is already self-delimiting and never needs parentheses.
+const PREC_DATA_ADD: u8 = 1;
+const PREC_DATA_UNARY: u8 = 2;
+const PREC_DATA_ATOM: u8 = 3;
+
+/// `Add` is left-associative, so only a lower-precedence child -- there
+/// isn't one below `+` here -- or a same-precedence child on the right
+/// would need parens; `format_data_expr`'s call sites pass `PREC_DATA_ADD`
+/// for the left operand and `PREC_DATA_ADD + 1` for the right to encode
+/// that asymmetry.
+fn data_prec(expr: &DataExpr) -> u8 {
+    match expr {
+        DataExpr::Add(..) => PREC_DATA_ADD,
+        DataExpr::Negate(..) => PREC_DATA_UNARY,
+        _ => PREC_DATA_ATOM,
+    }
+}
+
+/// Binding levels for the control language's boolean/comparison
+/// expressions, mirroring [`data_prec`]. `||` binds loosest, `&&` next,
+/// then comparisons/`!`/`in` (`PREC_CONTROL_NOT`) and, above them, bare
+/// data expressions (`PREC_CONTROL_ATOM`) -- all of which the grammar
+/// treats as atomic with respect to `&&`/`||`.
+const PREC_CONTROL_OR: u8 = 0;
+const PREC_CONTROL_AND: u8 = 1;
+const PREC_CONTROL_NOT: u8 = 2;
+const PREC_CONTROL_ATOM: u8 = 3;
+
+fn control_prec(expr: &ControlExpr) -> u8 {
+    match expr {
+        ControlExpr::Logical(_, LogicalOp::Or, _) => PREC_CONTROL_OR,
+        ControlExpr::Logical(_, LogicalOp::And, _) => PREC_CONTROL_AND,
+        ControlExpr::Comparison(..) | ControlExpr::Not(..) | ControlExpr::Contains(..) => PREC_CONTROL_NOT,
+        ControlExpr::Data(..) => PREC_CONTROL_ATOM,
+    }
+}
+
+/// The [`Trivia`] attached to a top-level item, regardless of which
+/// `TopLevel` variant it is. `ControlStmt` doesn't carry trivia of its own
+/// yet (see `Formatter`'s doc on trivia support), so bare top-level control
+/// statements always get the default (empty) trivia.
+fn trivia_of(top_level: &TopLevel) -> Trivia {
+    match top_level {
+        TopLevel::Module(module) => module.trivia.clone(),
+        TopLevel::Import(import) => import.trivia.clone(),
+        TopLevel::Function(func) => func.trivia.clone(),
+        TopLevel::Struct(decl) => decl.trivia.clone(),
+        TopLevel::Test(test) => test.trivia.clone(),
+        TopLevel::Control(_) => Trivia::default(),
+    }
+}
+
+/// Whether `import`'s path names one of the library namespaces
+/// documented in `crate::libraries` (`jtv`/`common`) rather than a module
+/// declared elsewhere in the program being formatted -- the "standard
+/// library" side of the grouping `sort_leading_imports` produces.
+fn is_stdlib_import(import: &ImportStmt) -> bool {
+    matches!(import.path.first().map(String::as_str), Some("jtv") | Some("common"))
+}
+
+/// Returns `statements` with its leading run of consecutive
+/// `TopLevel::Import` items reordered: standard-library imports (see
+/// `is_stdlib_import`) first, then local ones, each group sorted
+/// lexicographically by path, with a blank line separating the two
+/// groups -- the layout ruff's isort and rustfmt's `group_imports`
+/// produce. Every import's `trivia` (so its attached comments) travels
+/// with it; items after the leading run, and non-import items, are left
+/// exactly where they are.
+fn sort_leading_imports(statements: &[TopLevel]) -> Vec<TopLevel> {
+    let run_len = statements
+        .iter()
+        .take_while(|stmt| matches!(stmt, TopLevel::Import(_)))
+        .count();
+    if run_len < 2 {
+        return statements.to_vec();
+    }
+
+    let (mut stdlib, mut local): (Vec<ImportStmt>, Vec<ImportStmt>) = statements[..run_len]
+        .iter()
+        .map(|stmt| match stmt {
+            TopLevel::Import(import) => import.clone(),
+            _ => unreachable!("run_len only counts TopLevel::Import items"),
+        })
+        .partition(is_stdlib_import);
+    stdlib.sort_by(|a, b| a.path.join("/").cmp(&b.path.join("/")));
+    local.sort_by(|a, b| a.path.join("/").cmp(&b.path.join("/")));
+    if !stdlib.is_empty() && !local.is_empty() {
+        local[0].trivia.blank_line_before = true;
+    }
+
+    stdlib
+        .into_iter()
+        .chain(local)
+        .map(TopLevel::Import)
+        .chain(statements[run_len..].iter().cloned())
+        .collect()
+}
+
+/// Coloring used by `check_import_cycles`'s depth-first search: `White`
+/// is unvisited, `Gray` is on the current root-to-node path (so an edge
+/// into another `Gray` node is a back edge, i.e. a cycle), `Black` is
+/// fully explored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    White,
+    Gray,
+    Black,
+}
+
+/// Builds the directed graph of module-to-import edges implied by every
+/// `module Foo { import Bar }` in `program` -- an edge `Foo -> Bar` for
+/// each import inside `Foo`'s body whose path names another module
+/// declared in `program` -- and walks it with the adjacency-list +
+/// recursion-stack depth-first search graph libraries like `ugraphs`
+/// use. Returns `Err(JtvError::InvalidOperation)` naming the cycle (e.g.
+/// `"import cycle: A -> B -> A"`) if the modules import each other
+/// circularly.
+///
+/// Sorting the imports inside a cyclic module (`FormatConfig::sort_imports`)
+/// wouldn't itself be wrong, but it would silently sit on top of a
+/// dependency problem the user should fix first -- callers that enable
+/// `sort_imports` are expected to run this check before formatting.
+pub fn check_import_cycles(program: &Program) -> crate::error::Result<()> {
+    let modules: Vec<&ModuleDecl> = program
+        .statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            TopLevel::Module(module) => Some(module),
+            _ => None,
+        })
+        .collect();
+
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for module in &modules {
+        let targets = module
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                TopLevel::Import(import) => import.path.first().map(String::as_str),
+                _ => None,
+            })
+            .filter(|name| modules.iter().any(|m| m.name == *name))
+            .collect();
+        edges.insert(module.name.as_str(), targets);
+    }
+
+    let mut state: HashMap<&str, VisitState> = HashMap::new();
+    // Roots are tried in declaration order, so the cycle reported is deterministic.
+    for name in modules.iter().map(|m| m.name.as_str()) {
+        if state.get(name).copied().unwrap_or(VisitState::White) == VisitState::White {
+            visit_for_cycle(name, &edges, &mut state, &mut Vec::new())?;
+        }
+    }
+    Ok(())
+}
+
+/// Depth-first visit from `node`, used only by `check_import_cycles`.
+/// `path` is the current root-to-`node` chain, so a back edge into a
+/// `Gray` node can be reported as the exact cycle found rather than just
+/// "a cycle exists somewhere".
+fn visit_for_cycle<'a>(
+    node: &'a str,
+    edges: &HashMap<&'a str, Vec<&'a str>>,
+    state: &mut HashMap<&'a str, VisitState>,
+    path: &mut Vec<&'a str>,
+) -> crate::error::Result<()> {
+    state.insert(node, VisitState::Gray);
+    path.push(node);
+
+    for &target in edges.get(node).into_iter().flatten() {
+        match state.get(target).copied().unwrap_or(VisitState::White) {
+            VisitState::White => visit_for_cycle(target, edges, state, path)?,
+            VisitState::Gray => {
+                let start = path.iter().position(|&n| n == target).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(target);
+                return Err(JtvError::InvalidOperation(format!(
+                    "import cycle: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+            VisitState::Black => {}
+        }
+    }
+
+    path.pop();
+    state.insert(node, VisitState::Black);
+    Ok(())
+}
+
 /// Code formatter for JtV programs
+///
+/// Trivia support: comments and blank lines attached to top-level items
+/// (modules, imports, functions, structs -- see `ast::Trivia`) survive a
+/// format pass. Comments and blank lines *between statements inside a
+/// function/module body* are not yet preserved, since `ControlStmt` has no
+/// trivia field of its own to carry them -- see the request that added
+/// this support for the follow-up needed to extend it to statement level.
 pub struct Formatter {
     config: FormatConfig,
-    output: String,
     indent_level: usize,
 }
 
@@ -44,330 +419,566 @@ impl Formatter {
     pub fn with_config(config: FormatConfig) -> Self {
         Formatter {
             config,
-            output: String::new(),
             indent_level: 0,
         }
     }
 
     /// Format a complete program
     pub fn format_program(&mut self, program: &Program) -> String {
-        self.output.clear();
         self.indent_level = 0;
 
-        for (i, stmt) in program.statements.iter().enumerate() {
-            if i > 0 && self.config.blank_lines_between_items {
-                self.output.push('\n');
-            }
-            self.format_top_level(stmt);
+        let owned;
+        let statements: &[TopLevel] = if self.config.sort_imports {
+            owned = sort_leading_imports(&program.statements);
+            &owned
+        } else {
+            &program.statements
+        };
+
+        let mut parts = Vec::new();
+        for (i, stmt) in statements.iter().enumerate() {
+            let trivia = trivia_of(stmt);
+            let blank_line_before =
+                i > 0 && (self.config.blank_lines_between_items || trivia.blank_line_before);
+            parts.push(self.format_leading_trivia(&trivia, blank_line_before));
+            parts.push(self.format_top_level(stmt));
         }
 
-        self.output.trim_end().to_string() + "\n"
+        let rendered = render(&Doc::Concat(parts), self.config.max_line_length);
+        rendered.trim_end().to_string() + "\n"
+    }
+
+    fn indent(&self) -> String {
+        " ".repeat(self.indent_level * self.config.indent_size)
+    }
+
+    /// Renders `trivia`'s leading comments (one per line, at the current
+    /// indent) and, if `blank_line_before`, a preceding blank line. Does
+    /// *not* render `trivia.trailing_comment` -- that's emitted by the
+    /// item's own `format_*` method, since it belongs on the item's first
+    /// line rather than before it.
+    fn format_leading_trivia(&self, trivia: &Trivia, blank_line_before: bool) -> Doc {
+        let mut parts = Vec::new();
+        if blank_line_before {
+            parts.push(Doc::Text("\n".to_string()));
+        }
+        for comment in &trivia.leading_comments {
+            parts.push(Doc::Text(format!("{}// {}\n", self.indent(), comment)));
+        }
+        Doc::Concat(parts)
     }
 
-    fn format_top_level(&mut self, top_level: &TopLevel) {
+    /// Appends `trailing` as a `// comment` on `line` (if present) before
+    /// the newline that ends it -- the shared tail of every `format_*`
+    /// method that can have a same-line trailing comment.
+    fn with_trailing_comment(line: String, trailing: &Option<String>) -> String {
+        match trailing {
+            Some(comment) => format!("{} // {}\n", line, comment),
+            None => format!("{}\n", line),
+        }
+    }
+
+    fn format_top_level(&mut self, top_level: &TopLevel) -> Doc {
         match top_level {
             TopLevel::Module(module) => self.format_module(module),
             TopLevel::Import(import) => self.format_import(import),
             TopLevel::Function(func) => self.format_function(func),
+            TopLevel::Struct(decl) => self.format_struct(decl),
+            TopLevel::Test(test) => self.format_test(test),
             TopLevel::Control(stmt) => {
-                self.format_control_stmt(stmt);
-                self.output.push('\n');
+                Doc::Concat(vec![self.format_control_stmt(stmt), Doc::Text("\n".to_string())])
             }
         }
     }
 
-    fn format_module(&mut self, module: &ModuleDecl) {
-        self.write_indent();
-        self.output.push_str(&format!("module {} {{\n", module.name));
+    fn format_module(&mut self, module: &ModuleDecl) -> Doc {
+        let mut parts = vec![
+            Doc::Text(self.indent()),
+            Doc::Text(Self::with_trailing_comment(
+                format!("module {} {{", module.name),
+                &module.trivia.trailing_comment,
+            )),
+        ];
         self.indent_level += 1;
-
-        for stmt in &module.body {
-            self.format_top_level(stmt);
+        let owned;
+        let body: &[TopLevel] = if self.config.sort_imports {
+            owned = sort_leading_imports(&module.body);
+            &owned
+        } else {
+            &module.body
+        };
+        for (i, stmt) in body.iter().enumerate() {
+            let trivia = trivia_of(stmt);
+            let blank_line_before =
+                i > 0 && (self.config.blank_lines_between_items || trivia.blank_line_before);
+            parts.push(self.format_leading_trivia(&trivia, blank_line_before));
+            parts.push(self.format_top_level(stmt));
         }
-
         self.indent_level -= 1;
-        self.write_indent();
-        self.output.push_str("}\n");
+        parts.push(Doc::Text(self.indent()));
+        parts.push(Doc::Text("}\n".to_string()));
+        Doc::Concat(parts)
     }
 
-    fn format_import(&mut self, import: &ImportStmt) {
-        self.write_indent();
-        self.output.push_str("import ");
-        self.output.push_str(&import.path.join("/"));
+    fn format_import(&mut self, import: &ImportStmt) -> Doc {
+        let mut s = self.indent();
+        s.push_str("import ");
+        s.push_str(&import.path.join("/"));
         if let Some(alias) = &import.alias {
-            self.output.push_str(" as ");
-            self.output.push_str(alias);
+            s.push_str(" as ");
+            s.push_str(alias);
         }
-        self.output.push('\n');
+        Doc::Text(Self::with_trailing_comment(s, &import.trivia.trailing_comment))
     }
 
-    fn format_function(&mut self, func: &FunctionDecl) {
-        self.write_indent();
+    fn format_function(&mut self, func: &FunctionDecl) -> Doc {
+        let mut header = self.indent();
 
-        // Purity annotation
         match &func.purity {
-            Purity::Total => self.output.push_str("@total "),
-            Purity::Pure => self.output.push_str("@pure "),
+            Purity::Total => header.push_str("@total "),
+            Purity::Pure => header.push_str("@pure "),
             Purity::Impure => {}
         }
 
-        // Function signature
-        self.output.push_str("fn ");
-        self.output.push_str(&func.name);
-        self.output.push('(');
+        header.push_str("fn ");
+        header.push_str(&func.name);
 
-        // Parameters
-        for (i, param) in func.params.iter().enumerate() {
-            if i > 0 {
-                self.output.push_str(", ");
-            }
-            self.output.push_str(&param.name);
-            if let Some(ty) = &param.type_annotation {
-                self.output.push_str(": ");
-                self.format_type_annotation(ty);
+        if !func.type_params.is_empty() {
+            header.push('<');
+            for (i, tp) in func.type_params.iter().enumerate() {
+                if i > 0 {
+                    header.push_str(", ");
+                }
+                header.push_str(&tp.name);
+                if let Some(TypeBound::Numeric) = &tp.bound {
+                    header.push_str(": Numeric");
+                }
             }
+            header.push('>');
         }
 
-        self.output.push(')');
+        let params = self.format_params(&func.params);
 
-        // Return type
+        let mut tail = String::new();
         if let Some(ret_type) = &func.return_type {
-            self.output.push_str(": ");
-            self.format_type_annotation(ret_type);
+            tail.push_str(": ");
+            tail.push_str(&self.render_type_annotation(ret_type));
         }
+        tail.push_str(" {");
+        let tail = Self::with_trailing_comment(tail, &func.trivia.trailing_comment);
 
-        self.output.push_str(" {\n");
-        self.indent_level += 1;
+        let mut parts = vec![Doc::Text(header), params, Doc::Text(tail)];
 
-        // Function body
+        self.indent_level += 1;
         for stmt in &func.body {
-            self.format_control_stmt(stmt);
-            self.output.push('\n');
+            parts.push(self.format_control_stmt(stmt));
+            parts.push(Doc::Text("\n".to_string()));
+        }
+        self.indent_level -= 1;
+
+        parts.push(Doc::Text(self.indent()));
+        parts.push(Doc::Text("}\n".to_string()));
+
+        Doc::Concat(parts)
+    }
+
+    fn format_test(&mut self, test: &TestDecl) -> Doc {
+        let mut header = self.indent();
+        if test.pure {
+            header.push_str("pure ");
+        }
+        header.push_str("test \"");
+        header.push_str(&test.name);
+        header.push_str("\" {");
+        let header = Self::with_trailing_comment(header, &test.trivia.trailing_comment);
+
+        let mut parts = vec![Doc::Text(header)];
+
+        self.indent_level += 1;
+        for stmt in &test.body {
+            parts.push(self.format_control_stmt(stmt));
+            parts.push(Doc::Text("\n".to_string()));
+        }
+        self.indent_level -= 1;
+
+        parts.push(Doc::Text(self.indent()));
+        parts.push(Doc::Text("}\n".to_string()));
+
+        Doc::Concat(parts)
+    }
+
+    fn format_params(&self, params: &[Param]) -> Doc {
+        let items = params
+            .iter()
+            .map(|param| {
+                let mut s = param.name.clone();
+                if let Some(ty) = &param.type_annotation {
+                    s.push_str(": ");
+                    s.push_str(&self.render_type_annotation(ty));
+                }
+                Doc::Text(s)
+            })
+            .collect();
+        bracketed_list("(", items, ")", self.config.indent_size)
+    }
+
+    fn format_struct(&mut self, decl: &StructDecl) -> Doc {
+        let mut parts = vec![
+            Doc::Text(self.indent()),
+            Doc::Text(Self::with_trailing_comment(
+                format!("struct {} {{", decl.name),
+                &decl.trivia.trailing_comment,
+            )),
+        ];
+        self.indent_level += 1;
+
+        for (i, (name, ty)) in decl.fields.iter().enumerate() {
+            if i > 0 {
+                parts.push(Doc::Text(",\n".to_string()));
+            }
+            let mut s = self.indent();
+            s.push_str(name);
+            s.push_str(": ");
+            s.push_str(&self.render_type_annotation(ty));
+            parts.push(Doc::Text(s));
         }
 
+        parts.push(Doc::Text("\n".to_string()));
         self.indent_level -= 1;
-        self.write_indent();
-        self.output.push_str("}\n");
+        parts.push(Doc::Text(self.indent()));
+        parts.push(Doc::Text("}\n".to_string()));
+        Doc::Concat(parts)
     }
 
-    fn format_type_annotation(&mut self, ty: &TypeAnnotation) {
+    /// Type annotations are never wrapped, so they're rendered straight to
+    /// a `String` rather than built up as a `Doc`.
+    fn render_type_annotation(&self, ty: &TypeAnnotation) -> String {
         match ty {
-            TypeAnnotation::Basic(basic) => {
-                self.output.push_str(match basic {
-                    BasicType::Int => "Int",
-                    BasicType::Float => "Float",
-                    BasicType::Rational => "Rational",
-                    BasicType::Complex => "Complex",
-                    BasicType::Hex => "Hex",
-                    BasicType::Binary => "Binary",
-                    BasicType::Symbolic => "Symbolic",
-                    BasicType::Bool => "Bool",
-                    BasicType::String => "String",
-                });
-            }
-            TypeAnnotation::List(inner) => {
-                self.output.push_str("List<");
-                self.format_type_annotation(inner);
-                self.output.push('>');
-            }
+            TypeAnnotation::Basic(basic) => match basic {
+                BasicType::Int => "Int".to_string(),
+                BasicType::Float => "Float".to_string(),
+                BasicType::Rational => "Rational".to_string(),
+                BasicType::Complex => "Complex".to_string(),
+                BasicType::Hex => "Hex".to_string(),
+                BasicType::Binary => "Binary".to_string(),
+                BasicType::Symbolic => "Symbolic".to_string(),
+                BasicType::Bool => "Bool".to_string(),
+                BasicType::String => "String".to_string(),
+            },
+            TypeAnnotation::List(inner) => format!("List<{}>", self.render_type_annotation(inner)),
             TypeAnnotation::Tuple(types) => {
-                self.output.push('(');
-                for (i, t) in types.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.format_type_annotation(t);
-                }
-                self.output.push(')');
+                let inner: Vec<String> =
+                    types.iter().map(|t| self.render_type_annotation(t)).collect();
+                format!("({})", inner.join(", "))
             }
             TypeAnnotation::Function(params, ret) => {
-                self.output.push_str("Fn(");
-                for (i, p) in params.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.format_type_annotation(p);
-                }
-                self.output.push_str(") -> ");
-                self.format_type_annotation(ret);
+                let inner: Vec<String> =
+                    params.iter().map(|p| self.render_type_annotation(p)).collect();
+                format!("Fn({}) -> {}", inner.join(", "), self.render_type_annotation(ret))
             }
+            TypeAnnotation::Generic(name) => name.clone(),
         }
     }
 
-    fn format_control_stmt(&mut self, stmt: &ControlStmt) {
-        self.write_indent();
+    fn format_control_stmt(&mut self, stmt: &ControlStmt) -> Doc {
+        let indent = self.indent();
         match stmt {
             ControlStmt::Assignment(assign) => {
-                self.output.push_str(&assign.target);
-                if self.config.spaces_around_operators {
-                    self.output.push_str(" = ");
+                let mut s = indent;
+                s.push_str(&assign.target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " = "
                 } else {
-                    self.output.push('=');
-                }
-                self.format_expr(&assign.value);
+                    "="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_expr(&assign.value)])
             }
             ControlStmt::If(if_stmt) => {
-                self.output.push_str("if ");
-                self.format_control_expr(&if_stmt.condition);
-                self.output.push_str(" {\n");
+                let mut parts = vec![Doc::Text(indent.clone() + "if ")];
+                parts.push(self.format_control_expr(&if_stmt.condition));
+                parts.push(Doc::Text(" {\n".to_string()));
                 self.indent_level += 1;
                 for s in &if_stmt.then_branch {
-                    self.format_control_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_control_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent.clone()));
+                parts.push(Doc::Text("}".to_string()));
 
                 if let Some(else_branch) = &if_stmt.else_branch {
-                    self.output.push_str(" else {\n");
+                    parts.push(Doc::Text(" else {\n".to_string()));
                     self.indent_level += 1;
                     for s in else_branch {
-                        self.format_control_stmt(s);
-                        self.output.push('\n');
+                        parts.push(self.format_control_stmt(s));
+                        parts.push(Doc::Text("\n".to_string()));
                     }
                     self.indent_level -= 1;
-                    self.write_indent();
-                    self.output.push('}');
+                    parts.push(Doc::Text(indent));
+                    parts.push(Doc::Text("}".to_string()));
                 }
+
+                Doc::Concat(parts)
             }
             ControlStmt::While(while_stmt) => {
-                self.output.push_str("while ");
-                self.format_control_expr(&while_stmt.condition);
-                self.output.push_str(" {\n");
+                let mut parts = vec![Doc::Text(indent.clone() + "while ")];
+                parts.push(self.format_control_expr(&while_stmt.condition));
+                parts.push(Doc::Text(" {\n".to_string()));
                 self.indent_level += 1;
                 for s in &while_stmt.body {
-                    self.format_control_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_control_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
             }
             ControlStmt::For(for_stmt) => {
-                self.output.push_str("for ");
-                self.output.push_str(&for_stmt.variable);
-                self.output.push_str(" in ");
-                self.format_data_expr(&for_stmt.range.start);
-                self.output.push_str("..");
-                self.format_data_expr(&for_stmt.range.end);
+                let mut header = indent.clone();
+                header.push_str("for ");
+                header.push_str(&for_stmt.variable);
+                header.push_str(" in ");
+                let mut parts = vec![Doc::Text(header), self.format_data_expr(&for_stmt.range.start)];
+                parts.push(Doc::Text("..".to_string()));
+                parts.push(self.format_data_expr(&for_stmt.range.end));
                 if let Some(step) = &for_stmt.range.step {
-                    self.output.push_str("..");
-                    self.format_data_expr(step);
+                    parts.push(Doc::Text("..".to_string()));
+                    parts.push(self.format_data_expr(step));
                 }
-                self.output.push_str(" {\n");
+                parts.push(Doc::Text(" {\n".to_string()));
                 self.indent_level += 1;
                 for s in &for_stmt.body {
-                    self.format_control_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_control_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
             }
             ControlStmt::Return(expr) => {
-                self.output.push_str("return");
+                let mut parts = vec![Doc::Text(indent + "return")];
                 if let Some(e) = expr {
-                    self.output.push(' ');
-                    self.format_data_expr(e);
+                    parts.push(Doc::Text(" ".to_string()));
+                    parts.push(self.format_data_expr(e));
                 }
+                Doc::Concat(parts)
             }
             ControlStmt::Print(exprs) => {
-                self.output.push_str("print(");
-                for (i, e) in exprs.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.format_data_expr(e);
-                }
-                self.output.push(')');
+                let items = exprs.iter().map(|e| self.format_data_expr(e)).collect();
+                Doc::Concat(vec![
+                    Doc::Text(indent + "print"),
+                    bracketed_list("(", items, ")", self.config.indent_size),
+                ])
             }
             ControlStmt::ReverseBlock(block) => {
-                self.output.push_str("reverse {\n");
+                let mut parts = vec![Doc::Text(indent.clone() + "reverse {\n")];
                 self.indent_level += 1;
                 for s in &block.body {
-                    self.format_reversible_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_reversible_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
             }
             ControlStmt::Block(stmts) => {
-                self.output.push_str("{\n");
+                let mut parts = vec![Doc::Text(indent.clone() + "{\n")];
                 self.indent_level += 1;
                 for s in stmts {
-                    self.format_control_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_control_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
+            }
+            ControlStmt::Break(label) => {
+                let mut s = indent + "break";
+                if let Some(label) = label {
+                    s.push(' ');
+                    s.push_str(label);
+                }
+                Doc::Text(s)
+            }
+            ControlStmt::Continue(label) => {
+                let mut s = indent + "continue";
+                if let Some(label) = label {
+                    s.push(' ');
+                    s.push_str(label);
+                }
+                Doc::Text(s)
             }
         }
     }
 
-    fn format_reversible_stmt(&mut self, stmt: &ReversibleStmt) {
-        self.write_indent();
+    fn format_reversible_stmt(&mut self, stmt: &ReversibleStmt) -> Doc {
+        let indent = self.indent();
         match stmt {
             ReversibleStmt::AddAssign(target, expr) => {
-                self.output.push_str(target);
-                if self.config.spaces_around_operators {
-                    self.output.push_str(" += ");
+                let mut s = indent;
+                s.push_str(target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " += "
                 } else {
-                    self.output.push_str("+=");
-                }
-                self.format_data_expr(expr);
+                    "+="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_data_expr(expr)])
             }
             ReversibleStmt::SubAssign(target, expr) => {
-                self.output.push_str(target);
-                if self.config.spaces_around_operators {
-                    self.output.push_str(" -= ");
+                let mut s = indent;
+                s.push_str(target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " -= "
                 } else {
-                    self.output.push_str("-=");
-                }
-                self.format_data_expr(expr);
+                    "-="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_data_expr(expr)])
+            }
+            ReversibleStmt::MulAssign(target, expr) => {
+                let mut s = indent;
+                s.push_str(target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " *= "
+                } else {
+                    "*="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_data_expr(expr)])
+            }
+            ReversibleStmt::DivAssign(target, expr) => {
+                let mut s = indent;
+                s.push_str(target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " /= "
+                } else {
+                    "/="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_data_expr(expr)])
+            }
+            ReversibleStmt::Assign(target, expr) => {
+                let mut s = indent;
+                s.push_str(target);
+                s.push_str(if self.config.spaces_around_operators {
+                    " = "
+                } else {
+                    "="
+                });
+                Doc::Concat(vec![Doc::Text(s), self.format_data_expr(expr)])
             }
             ReversibleStmt::If(if_stmt) => {
-                self.output.push_str("if ");
-                self.format_control_expr(&if_stmt.condition);
-                self.output.push_str(" {\n");
+                let mut parts = vec![Doc::Text(indent.clone() + "if ")];
+                parts.push(self.format_control_expr(&if_stmt.condition));
+                parts.push(Doc::Text(" {\n".to_string()));
                 self.indent_level += 1;
                 for s in &if_stmt.then_branch {
-                    self.format_control_stmt(s);
-                    self.output.push('\n');
+                    parts.push(self.format_control_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
                 }
                 self.indent_level -= 1;
-                self.write_indent();
-                self.output.push('}');
+                parts.push(Doc::Text(indent.clone()));
+                parts.push(Doc::Text("}".to_string()));
 
                 if let Some(else_branch) = &if_stmt.else_branch {
-                    self.output.push_str(" else {\n");
+                    parts.push(Doc::Text(" else {\n".to_string()));
                     self.indent_level += 1;
                     for s in else_branch {
-                        self.format_control_stmt(s);
-                        self.output.push('\n');
+                        parts.push(self.format_control_stmt(s));
+                        parts.push(Doc::Text("\n".to_string()));
+                    }
+                    self.indent_level -= 1;
+                    parts.push(Doc::Text(indent));
+                    parts.push(Doc::Text("}".to_string()));
+                }
+
+                Doc::Concat(parts)
+            }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                let mut header = indent.clone();
+                header.push_str("for ");
+                header.push_str(var);
+                header.push_str(" in ");
+                let mut parts = vec![Doc::Text(header), self.format_data_expr(from)];
+                parts.push(Doc::Text("..".to_string()));
+                parts.push(self.format_data_expr(to));
+                if let Some(step) = step {
+                    parts.push(Doc::Text("..".to_string()));
+                    parts.push(self.format_data_expr(step));
+                }
+                parts.push(Doc::Text(" {\n".to_string()));
+                self.indent_level += 1;
+                for s in body {
+                    parts.push(self.format_reversible_stmt(s));
+                    parts.push(Doc::Text("\n".to_string()));
+                }
+                self.indent_level -= 1;
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                let mut parts = vec![Doc::Text(indent.clone() + "switch ")];
+                parts.push(self.format_data_expr(scrutinee));
+                parts.push(Doc::Text(" {\n".to_string()));
+                self.indent_level += 1;
+                let case_indent = self.indent();
+                for (value, body) in cases {
+                    parts.push(Doc::Text(case_indent.clone()));
+                    parts.push(self.format_data_expr(value));
+                    parts.push(Doc::Text(" => {\n".to_string()));
+                    self.indent_level += 1;
+                    for s in body {
+                        parts.push(self.format_reversible_stmt(s));
+                        parts.push(Doc::Text("\n".to_string()));
+                    }
+                    self.indent_level -= 1;
+                    parts.push(Doc::Text(case_indent.clone()));
+                    parts.push(Doc::Text("}\n".to_string()));
+                }
+                if let Some(default) = default {
+                    parts.push(Doc::Text(case_indent.clone()));
+                    parts.push(Doc::Text("default => {\n".to_string()));
+                    self.indent_level += 1;
+                    for s in default {
+                        parts.push(self.format_reversible_stmt(s));
+                        parts.push(Doc::Text("\n".to_string()));
                     }
                     self.indent_level -= 1;
-                    self.write_indent();
-                    self.output.push('}');
+                    parts.push(Doc::Text(case_indent));
+                    parts.push(Doc::Text("}\n".to_string()));
                 }
+                self.indent_level -= 1;
+                parts.push(Doc::Text(indent));
+                parts.push(Doc::Text("}".to_string()));
+                Doc::Concat(parts)
             }
         }
     }
 
-    fn format_expr(&mut self, expr: &Expr) {
+    fn format_expr(&mut self, expr: &Expr) -> Doc {
         match expr {
             Expr::Data(data) => self.format_data_expr(data),
             Expr::Control(ctrl) => self.format_control_expr(ctrl),
         }
     }
 
-    fn format_control_expr(&mut self, expr: &ControlExpr) {
+    /// `format_control_expr`, but parenthesized if `expr`'s precedence is
+    /// lower than `min_prec` -- see [`control_prec`].
+    fn format_control_expr_prec(&mut self, expr: &ControlExpr, min_prec: u8) -> Doc {
+        let doc = self.format_control_expr(expr);
+        if control_prec(expr) < min_prec {
+            Doc::Concat(vec![Doc::Text("(".to_string()), doc, Doc::Text(")".to_string())])
+        } else {
+            doc
+        }
+    }
+
+    fn format_control_expr(&mut self, expr: &ControlExpr) -> Doc {
         match expr {
             ControlExpr::Data(data) => self.format_data_expr(data),
             ControlExpr::Comparison(left, op, right) => {
-                self.format_data_expr(left);
                 let op_str = match op {
                     Comparator::Eq => "==",
                     Comparator::Ne => "!=",
@@ -376,126 +987,158 @@ impl Formatter {
                     Comparator::Gt => ">",
                     Comparator::Ge => ">=",
                 };
-                if self.config.spaces_around_operators {
-                    self.output.push_str(&format!(" {} ", op_str));
+                let sep = if self.config.spaces_around_operators {
+                    format!(" {} ", op_str)
                 } else {
-                    self.output.push_str(op_str);
-                }
-                self.format_data_expr(right);
+                    op_str.to_string()
+                };
+                Doc::Concat(vec![
+                    self.format_data_expr_prec(left, PREC_DATA_ADD),
+                    Doc::Text(sep),
+                    self.format_data_expr_prec(right, PREC_DATA_ADD),
+                ])
             }
             ControlExpr::Logical(left, op, right) => {
-                self.format_control_expr(left);
                 let op_str = match op {
                     LogicalOp::And => "&&",
                     LogicalOp::Or => "||",
                 };
-                if self.config.spaces_around_operators {
-                    self.output.push_str(&format!(" {} ", op_str));
+                let sep = if self.config.spaces_around_operators {
+                    format!(" {} ", op_str)
                 } else {
-                    self.output.push_str(op_str);
-                }
-                self.format_control_expr(right);
-            }
-            ControlExpr::Not(inner) => {
-                self.output.push('!');
-                self.format_control_expr(inner);
+                    op_str.to_string()
+                };
+                let prec = control_prec(expr);
+                Doc::Concat(vec![
+                    self.format_control_expr_prec(left, prec),
+                    Doc::Text(sep),
+                    self.format_control_expr_prec(right, prec + 1),
+                ])
             }
+            ControlExpr::Not(inner) => Doc::Concat(vec![
+                Doc::Text("!".to_string()),
+                self.format_control_expr_prec(inner, PREC_CONTROL_NOT),
+            ]),
+            // Unlike the symbolic comparison/logical operators, `in` is a
+            // keyword and always needs surrounding spaces regardless of
+            // `spaces_around_operators`.
+            ControlExpr::Contains(left, right) => Doc::Concat(vec![
+                self.format_data_expr_prec(left, PREC_DATA_ADD),
+                Doc::Text(" in ".to_string()),
+                self.format_data_expr_prec(right, PREC_DATA_ADD),
+            ]),
         }
     }
 
-    fn format_data_expr(&mut self, expr: &DataExpr) {
+    /// `format_data_expr`, but parenthesized if `expr`'s precedence is
+    /// lower than `min_prec` -- see [`data_prec`].
+    fn format_data_expr_prec(&mut self, expr: &DataExpr, min_prec: u8) -> Doc {
+        let doc = self.format_data_expr(expr);
+        if data_prec(expr) < min_prec {
+            Doc::Concat(vec![Doc::Text("(".to_string()), doc, Doc::Text(")".to_string())])
+        } else {
+            doc
+        }
+    }
+
+    fn format_data_expr(&mut self, expr: &DataExpr) -> Doc {
         match expr {
-            DataExpr::Number(num) => self.format_number(num),
-            DataExpr::Identifier(name) => self.output.push_str(name),
+            DataExpr::Number(num) => Doc::Text(Self::render_number(num)),
+            DataExpr::Identifier(name) => Doc::Text(name.clone()),
             DataExpr::Add(left, right) => {
-                self.format_data_expr(left);
-                if self.config.spaces_around_operators {
-                    self.output.push_str(" + ");
+                let sep = if self.config.spaces_around_operators {
+                    " + "
                 } else {
-                    self.output.push('+');
-                }
-                self.format_data_expr(right);
-            }
-            DataExpr::Negate(inner) => {
-                self.output.push('-');
-                self.format_data_expr(inner);
+                    "+"
+                };
+                Doc::Concat(vec![
+                    self.format_data_expr_prec(left, PREC_DATA_ADD),
+                    Doc::Text(sep.to_string()),
+                    self.format_data_expr_prec(right, PREC_DATA_ADD + 1),
+                ])
             }
+            DataExpr::Negate(inner) => Doc::Concat(vec![
+                Doc::Text("-".to_string()),
+                self.format_data_expr_prec(inner, PREC_DATA_UNARY),
+            ]),
             DataExpr::FunctionCall(call) => {
-                self.output.push_str(&call.name);
-                self.output.push('(');
-                for (i, arg) in call.args.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.format_data_expr(arg);
-                }
-                self.output.push(')');
+                let items = call.args.iter().map(|arg| self.format_data_expr(arg)).collect();
+                Doc::Concat(vec![
+                    Doc::Text(call.name.clone()),
+                    bracketed_list("(", items, ")", self.config.indent_size),
+                ])
             }
             DataExpr::List(elements) => {
-                self.output.push('[');
-                for (i, e) in elements.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.format_data_expr(e);
-                }
-                self.output.push(']');
+                let items = elements.iter().map(|e| self.format_data_expr(e)).collect();
+                bracketed_list("[", items, "]", self.config.indent_size)
             }
             DataExpr::Tuple(elements) => {
-                self.output.push('(');
-                for (i, e) in elements.iter().enumerate() {
+                let items = elements.iter().map(|e| self.format_data_expr(e)).collect();
+                bracketed_list("(", items, ")", self.config.indent_size)
+            }
+            DataExpr::FieldAccess(base, field) => Doc::Concat(vec![
+                self.format_data_expr(base),
+                Doc::Text(format!(".{}", field)),
+            ]),
+            DataExpr::StructLiteral(name, fields) => {
+                let mut parts = vec![Doc::Text(format!("{} {{ ", name))];
+                for (i, (field_name, e)) in fields.iter().enumerate() {
                     if i > 0 {
-                        self.output.push_str(", ");
+                        parts.push(Doc::Text(", ".to_string()));
                     }
-                    self.format_data_expr(e);
+                    parts.push(Doc::Text(format!("{}: ", field_name)));
+                    parts.push(self.format_data_expr(e));
+                }
+                parts.push(Doc::Text(" }".to_string()));
+                Doc::Concat(parts)
+            }
+            DataExpr::ListComprehension(comp) => {
+                let mut parts = vec![Doc::Text("[".to_string()), self.format_data_expr(&comp.body)];
+                for (variable, source) in &comp.generators {
+                    parts.push(Doc::Text(format!(" for {} in ", variable)));
+                    parts.push(self.format_data_expr(source));
+                }
+                if let Some(condition) = &comp.condition {
+                    parts.push(Doc::Text(" if ".to_string()));
+                    parts.push(self.format_control_expr(condition));
                 }
-                self.output.push(')');
+                parts.push(Doc::Text("]".to_string()));
+                Doc::Concat(parts)
             }
+            DataExpr::Index(base, index) => Doc::Concat(vec![
+                self.format_data_expr(base),
+                Doc::Text("[".to_string()),
+                self.format_data_expr(index),
+                Doc::Text("]".to_string()),
+            ]),
         }
     }
 
-    fn format_number(&mut self, num: &Number) {
+    fn render_number(num: &Number) -> String {
         match num {
-            Number::Int(n) => self.output.push_str(&n.to_string()),
+            Number::Int(n) => n.to_string(),
             Number::Float(f) => {
                 let s = f.to_string();
                 if s.contains('.') {
-                    self.output.push_str(&s);
+                    s
                 } else {
-                    self.output.push_str(&format!("{}.0", s));
+                    format!("{}.0", s)
                 }
             }
-            Number::Rational(num, denom) => {
-                self.output.push_str(&format!("{}/{}", num, denom));
-            }
+            Number::Rational(num, denom) => format!("{}/{}", num, denom),
             Number::Complex(re, im) => {
+                let mut s = String::new();
                 if *re != 0.0 {
-                    self.output.push_str(&format!("{}", re));
+                    s.push_str(&re.to_string());
                     if *im >= 0.0 {
-                        self.output.push('+');
+                        s.push('+');
                     }
                 }
-                self.output.push_str(&format!("{}i", im));
-            }
-            Number::Hex(s) => {
-                // Hex values are stored as strings (e.g., "0xFF")
-                self.output.push_str(s);
-            }
-            Number::Binary(s) => {
-                // Binary values are stored as strings (e.g., "0b1010")
-                self.output.push_str(s);
-            }
-            Number::Symbolic(s) => {
-                self.output.push_str(s);
-            }
-        }
-    }
-
-    fn write_indent(&mut self) {
-        for _ in 0..self.indent_level {
-            for _ in 0..self.config.indent_size {
-                self.output.push(' ');
+                s.push_str(&format!("{}i", im));
+                s
             }
+            // Hex/Binary/Symbolic values are stored as strings (e.g. "0xFF").
+            Number::Hex(s) | Number::Binary(s) | Number::Symbolic(s) => s.clone(),
         }
     }
 }
@@ -522,6 +1165,172 @@ pub fn format_code_with_config(code: &str, config: FormatConfig) -> Result<Strin
     Ok(formatter.format_program(&program))
 }
 
+/// One line of a line-based diff between the original and formatted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Unchanged, present in both texts.
+    Context(String),
+    /// Present only in the original (pre-formatting) text.
+    Removed(String),
+    /// Present only in the formatted text.
+    Added(String),
+}
+
+/// A contiguous run of non-[`DiffLine::Context`] lines, as 1-indexed,
+/// end-exclusive line ranges into the original and formatted text. An
+/// insertion has an empty `original` range; a deletion has an empty
+/// `formatted` range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedRange {
+    pub original: std::ops::Range<usize>,
+    pub formatted: std::ops::Range<usize>,
+}
+
+/// The result of [`format_check`]: whether `code` was already canonically
+/// formatted and, if not, a diff explaining what would change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub is_formatted: bool,
+    pub diff: Vec<DiffLine>,
+    pub changed_ranges: Vec<ChangedRange>,
+}
+
+impl CheckResult {
+    /// Renders [`Self::diff`] as a standard `-`/`+`/` ` prefixed unified
+    /// diff body (no `@@` hunk headers, since callers that want those can
+    /// derive them from `changed_ranges`).
+    pub fn to_unified_diff(&self) -> String {
+        let mut out = String::new();
+        for line in &self.diff {
+            match line {
+                DiffLine::Context(s) => {
+                    out.push(' ');
+                    out.push_str(s);
+                }
+                DiffLine::Removed(s) => {
+                    out.push('-');
+                    out.push_str(s);
+                }
+                DiffLine::Added(s) => {
+                    out.push('+');
+                    out.push_str(s);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Checks whether `code` is already canonically formatted, without
+/// rewriting it -- the `--check` mode CI integrations of formatters like
+/// rustfmt/ruff use to fail a build on unformatted input.
+pub fn format_check(code: &str) -> Result<CheckResult, String> {
+    let formatted = format_code(code)?;
+    let diff = diff_lines(code, &formatted);
+    let changed_ranges = changed_ranges(&diff);
+    Ok(CheckResult {
+        is_formatted: changed_ranges.is_empty(),
+        diff,
+        changed_ranges,
+    })
+}
+
+/// Line-based diff via the classic LCS dynamic program: `table[i][j]` is
+/// the length of the longest common subsequence of `original[i..]` and
+/// `formatted[j..]`, and walking it from `(0, 0)` forward reconstructs the
+/// diff by always preferring a context line when one is available.
+fn diff_lines(original: &str, formatted: &str) -> Vec<DiffLine> {
+    let original: Vec<&str> = original.lines().collect();
+    let formatted: Vec<&str> = formatted.lines().collect();
+    let (n, m) = (original.len(), formatted.len());
+
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if original[i] == formatted[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if original[i] == formatted[j] {
+            diff.push(DiffLine::Context(original[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            diff.push(DiffLine::Removed(original[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(formatted[j].to_string()));
+            j += 1;
+        }
+    }
+    while i < n {
+        diff.push(DiffLine::Removed(original[i].to_string()));
+        i += 1;
+    }
+    while j < m {
+        diff.push(DiffLine::Added(formatted[j].to_string()));
+        j += 1;
+    }
+
+    diff
+}
+
+/// Groups `diff` into 1-indexed, end-exclusive `ChangedRange`s, merging
+/// adjacent `Removed`/`Added` lines (with no intervening `Context`) into a
+/// single range the way a unified diff groups them into one hunk.
+fn changed_ranges(diff: &[DiffLine]) -> Vec<ChangedRange> {
+    let mut ranges = Vec::new();
+    let (mut orig_line, mut fmt_line) = (1usize, 1usize);
+    let (mut current, mut run_start) = (None::<ChangedRange>, (0usize, 0usize));
+
+    for line in diff {
+        match line {
+            DiffLine::Context(_) => {
+                if let Some(range) = current.take() {
+                    ranges.push(range);
+                }
+                orig_line += 1;
+                fmt_line += 1;
+            }
+            DiffLine::Removed(_) => {
+                if current.is_none() {
+                    run_start = (orig_line, fmt_line);
+                    current = Some(ChangedRange {
+                        original: run_start.0..run_start.0,
+                        formatted: run_start.1..run_start.1,
+                    });
+                }
+                current.as_mut().unwrap().original.end = orig_line + 1;
+                orig_line += 1;
+            }
+            DiffLine::Added(_) => {
+                if current.is_none() {
+                    run_start = (orig_line, fmt_line);
+                    current = Some(ChangedRange {
+                        original: run_start.0..run_start.0,
+                        formatted: run_start.1..run_start.1,
+                    });
+                }
+                current.as_mut().unwrap().formatted.end = fmt_line + 1;
+                fmt_line += 1;
+            }
+        }
+    }
+    if let Some(range) = current.take() {
+        ranges.push(range);
+    }
+
+    ranges
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -596,4 +1405,285 @@ mod tests {
         assert!(formatted.contains("while x > 0 {"));
         assert!(formatted.contains("x = x + -1"));
     }
+
+    #[test]
+    fn test_long_function_call_wraps_one_arg_per_line() {
+        let code = "result=reallyQuiteLongFunctionNameHere(firstArgument,secondArgument,thirdArgument,fourthArgument)";
+        let formatted = format_code(code).unwrap();
+        assert!(formatted.contains("result = reallyQuiteLongFunctionNameHere(\n"));
+        assert!(formatted.contains("    firstArgument,\n"));
+        assert!(formatted.contains("    fourthArgument,\n"));
+        assert!(formatted.lines().all(|line| line.len() <= 100));
+    }
+
+    #[test]
+    fn test_short_function_call_stays_inline() {
+        let code = "result=add(1,2)";
+        let formatted = format_code(code).unwrap();
+        assert_eq!(formatted, "result = add(1, 2)\n");
+    }
+
+    #[test]
+    fn test_negate_of_add_keeps_parens() {
+        let code = "x=-(a+b)";
+        let formatted = format_code(code).unwrap();
+        assert_eq!(formatted, "x = -(a + b)\n");
+    }
+
+    #[test]
+    fn test_add_right_nested_keeps_parens() {
+        let code = "x=a+(b+c)";
+        let formatted = format_code(code).unwrap();
+        assert_eq!(formatted, "x = a + (b + c)\n");
+    }
+
+    #[test]
+    fn test_add_left_nested_drops_parens() {
+        let code = "x=(a+b)+c";
+        let formatted = format_code(code).unwrap();
+        assert_eq!(formatted, "x = a + b + c\n");
+    }
+
+    #[test]
+    fn test_logical_or_inside_and_keeps_parens() {
+        let code = "if (a||b)&&c{y=1}";
+        let formatted = format_code(code).unwrap();
+        assert!(formatted.contains("if (a || b) && c {"));
+    }
+
+    #[test]
+    fn test_logical_and_inside_or_drops_parens() {
+        let code = "if a&&b||c{y=1}";
+        let formatted = format_code(code).unwrap();
+        assert!(formatted.contains("if a && b || c {"));
+    }
+
+    #[test]
+    fn test_not_of_logical_keeps_parens() {
+        let code = "if !(a||b){y=1}";
+        let formatted = format_code(code).unwrap();
+        assert!(formatted.contains("if !(a || b) {"));
+    }
+
+    #[test]
+    fn test_format_check_reports_already_formatted() {
+        let code = "x = 5 + 3\n";
+        let result = format_check(code).unwrap();
+        assert!(result.is_formatted);
+        assert!(result.changed_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_format_check_diffs_unformatted_input() {
+        let code = "x=5+3";
+        let result = format_check(code).unwrap();
+        assert!(!result.is_formatted);
+        assert_eq!(
+            result.changed_ranges,
+            vec![ChangedRange {
+                original: 1..2,
+                formatted: 1..2,
+            }]
+        );
+        assert!(result.diff.contains(&DiffLine::Removed("x=5+3".to_string())));
+        assert!(result.diff.contains(&DiffLine::Added("x = 5 + 3".to_string())));
+        let rendered = result.to_unified_diff();
+        assert!(rendered.contains("-x=5+3\n"));
+        assert!(rendered.contains("+x = 5 + 3\n"));
+    }
+
+    fn trivial_function(name: &str, trivia: Trivia) -> FunctionDecl {
+        FunctionDecl {
+            name: name.to_string(),
+            type_params: vec![],
+            params: vec![],
+            return_type: None,
+            purity: Purity::Impure,
+            body: vec![],
+            span: Span::unknown(),
+            trivia,
+        }
+    }
+
+    #[test]
+    fn test_function_trivia_is_preserved() {
+        let func = trivial_function(
+            "add",
+            Trivia {
+                leading_comments: vec!["adds two numbers".to_string()],
+                trailing_comment: Some("entry point".to_string()),
+                blank_line_before: false,
+            },
+        );
+        let program = Program { statements: vec![TopLevel::Function(func)], span: Span::unknown() };
+        let formatted = Formatter::new().format_program(&program);
+        assert_eq!(formatted, "// adds two numbers\nfn add() { // entry point\n}\n");
+    }
+
+    #[test]
+    fn test_blank_line_before_is_preserved_independent_of_config() {
+        let program = Program {
+            statements: vec![
+                TopLevel::Function(trivial_function("a", Trivia::default())),
+                TopLevel::Function(trivial_function(
+                    "b",
+                    Trivia { blank_line_before: true, ..Default::default() },
+                )),
+            ],
+            span: Span::unknown(),
+        };
+        let config = FormatConfig { blank_lines_between_items: false, ..Default::default() };
+        let formatted = Formatter::with_config(config).format_program(&program);
+        assert_eq!(formatted, "fn a() {\n}\n\nfn b() {\n}\n");
+    }
+
+    #[test]
+    fn test_formatting_is_idempotent() {
+        let inputs = [
+            "x=5+3",
+            "fn add(a:Int,b:Int):Int{return a+b}",
+            "if x>0{y=1}",
+            "@pure fn double(x:Int):Int{return x+x}",
+            "reverse{x+=5}",
+            "nums=[1,2,3,4,5]",
+            "for i in 0..10{x=x+i}",
+            "while x>0{x=x+-1}",
+            "result=reallyQuiteLongFunctionNameHere(firstArgument,secondArgument,thirdArgument,fourthArgument)",
+            "result=add(1,2)",
+            "x=-(a+b)",
+            "x=a+(b+c)",
+            "x=(a+b)+c",
+            "if (a||b)&&c{y=1}",
+            "if a&&b||c{y=1}",
+            "if !(a||b){y=1}",
+        ];
+        for code in inputs {
+            let once = format_code(code).unwrap();
+            let twice = format_code(&once).unwrap();
+            assert_eq!(once, twice, "formatting not idempotent for: {}", code);
+        }
+    }
+
+    fn trivial_import(path: &[&str]) -> TopLevel {
+        TopLevel::Import(ImportStmt {
+            path: path.iter().map(|s| s.to_string()).collect(),
+            alias: None,
+            trivia: Trivia::default(),
+        })
+    }
+
+    #[test]
+    fn test_sort_imports_groups_stdlib_before_local_and_sorts_each() {
+        let program = Program {
+            statements: vec![
+                trivial_import(&["Zebra"]),
+                trivial_import(&["common", "collections"]),
+                trivial_import(&["jtv", "math"]),
+                trivial_import(&["Apple"]),
+            ],
+            span: Span::unknown(),
+        };
+        let config = FormatConfig {
+            sort_imports: true,
+            blank_lines_between_items: false,
+            ..Default::default()
+        };
+        let formatted = Formatter::with_config(config).format_program(&program);
+        assert_eq!(
+            formatted,
+            "import common/collections\nimport jtv/math\n\nimport Apple\nimport Zebra\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_imports_leaves_single_import_untouched() {
+        let program = Program {
+            statements: vec![trivial_import(&["Zebra"])],
+            span: Span::unknown(),
+        };
+        let config = FormatConfig {
+            sort_imports: true,
+            blank_lines_between_items: false,
+            ..Default::default()
+        };
+        let formatted = Formatter::with_config(config).format_program(&program);
+        assert_eq!(formatted, "import Zebra\n");
+    }
+
+    #[test]
+    fn test_sort_imports_off_by_default_preserves_source_order() {
+        let program = Program {
+            statements: vec![trivial_import(&["Zebra"]), trivial_import(&["Apple"])],
+            span: Span::unknown(),
+        };
+        let config = FormatConfig { blank_lines_between_items: false, ..Default::default() };
+        let formatted = Formatter::with_config(config).format_program(&program);
+        assert_eq!(formatted, "import Zebra\nimport Apple\n");
+    }
+
+    #[test]
+    fn test_sort_imports_does_not_touch_imports_after_the_leading_run() {
+        let program = Program {
+            statements: vec![
+                trivial_import(&["Zebra"]),
+                TopLevel::Function(trivial_function("f", Trivia::default())),
+                trivial_import(&["Apple"]),
+            ],
+            span: Span::unknown(),
+        };
+        let config = FormatConfig {
+            sort_imports: true,
+            blank_lines_between_items: false,
+            ..Default::default()
+        };
+        let formatted = Formatter::with_config(config).format_program(&program);
+        assert_eq!(formatted, "import Zebra\nfn f() {\n}\nimport Apple\n");
+    }
+
+    fn module_with_imports(name: &str, imports: &[&str]) -> TopLevel {
+        TopLevel::Module(ModuleDecl {
+            name: name.to_string(),
+            body: imports.iter().map(|target| trivial_import(&[target])).collect(),
+            trivia: Trivia::default(),
+        })
+    }
+
+    #[test]
+    fn test_check_import_cycles_accepts_an_acyclic_graph() {
+        let program = Program {
+            statements: vec![
+                module_with_imports("A", &["B"]),
+                module_with_imports("B", &[]),
+            ],
+            span: Span::unknown(),
+        };
+        assert!(check_import_cycles(&program).is_ok());
+    }
+
+    #[test]
+    fn test_check_import_cycles_reports_a_direct_cycle() {
+        let program = Program {
+            statements: vec![
+                module_with_imports("A", &["B"]),
+                module_with_imports("B", &["A"]),
+            ],
+            span: Span::unknown(),
+        };
+        let err = check_import_cycles(&program).unwrap_err();
+        match err {
+            JtvError::InvalidOperation(message) => {
+                assert!(message.contains("A -> B -> A"), "{}", message);
+            }
+            other => panic!("expected InvalidOperation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_import_cycles_ignores_imports_of_unknown_modules() {
+        let program = Program {
+            statements: vec![module_with_imports("A", &["jtv/math"])],
+            span: Span::unknown(),
+        };
+        assert!(check_import_cycles(&program).is_ok());
+    }
 }
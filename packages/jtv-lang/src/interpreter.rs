@@ -2,6 +2,8 @@
 use crate::ast::*;
 use crate::number::Value;
 use crate::error::{JtvError, Result};
+use crate::pvector::PVector;
+use crate::stdlib::StdLib;
 use std::collections::HashMap;
 
 const MAX_ITERATIONS: usize = 1_000_000; // Safety limit for loops
@@ -13,6 +15,7 @@ pub struct Interpreter {
     iteration_count: usize,
     trace_enabled: bool,
     trace: Vec<TraceEntry>,
+    stdlib: StdLib,
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +25,32 @@ pub struct TraceEntry {
     pub env: HashMap<String, String>,
 }
 
+/// What running a `ControlStmt` produced, threaded back up through
+/// `eval_control_stmt`'s recursive calls. `Normal` means "keep going with
+/// the next statement"; the other three are signals that short-circuit the
+/// rest of whatever block they're in -- `If`/`Block` just pass them further
+/// up unchanged, `While`/`For` catch `Break`/`Continue` themselves, and
+/// `call_named_function_values` is where `Return` finally lands.
+enum StmtFlow {
+    Normal,
+    Return(Value),
+    Break,
+    Continue,
+}
+
+/// Extract an `Int` out of a reversible for loop's `from`/`to`/`step`, the
+/// same "Range must be integers" restriction `eval_control_stmt` enforces
+/// for an ordinary `ControlStmt::For`.
+fn reversible_loop_bound(value: Value) -> Result<i64> {
+    match value {
+        Value::Int(n) => Ok(n),
+        other => Err(JtvError::TypeError(format!(
+            "reversible for loop bounds must be Int, got {}",
+            other
+        ))),
+    }
+}
+
 impl Interpreter {
     pub fn new() -> Self {
         Interpreter {
@@ -31,6 +60,7 @@ impl Interpreter {
             iteration_count: 0,
             trace_enabled: false,
             trace: vec![],
+            stdlib: StdLib::new(),
         }
     }
 
@@ -42,6 +72,21 @@ impl Interpreter {
         &self.trace
     }
 
+    /// Top-level variable bindings as they stand after `run` returns. Used
+    /// by callers (e.g. the differential fuzzer in `jtv-fuzz`) that need to
+    /// compare the interpreter's final state against some other execution
+    /// of the same program, not just its side effects.
+    pub fn globals(&self) -> &HashMap<String, Value> {
+        &self.globals
+    }
+
+    /// Every builtin name the standard library registers -- for a caller
+    /// (e.g. the REPL's tab completion) that wants the full set of names a
+    /// user could call, without reaching into `StdLib` itself.
+    pub fn builtin_names(&self) -> impl Iterator<Item = &str> {
+        self.stdlib.function_names()
+    }
+
     fn add_trace(&mut self, stmt_type: &str, line: &str) {
         if self.trace_enabled {
             let env: HashMap<String, String> = self.globals
@@ -80,14 +125,38 @@ impl Interpreter {
                 self.functions.insert(func.name.clone(), func.clone());
                 Ok(())
             }
-            TopLevel::Control(stmt) => {
-                self.eval_control_stmt(stmt)?;
+            TopLevel::Struct(_) => {
+                // Struct literals carry their own name and fields, so there's
+                // nothing for the interpreter to register up front.
                 Ok(())
             }
+            TopLevel::Test(_) => {
+                // `run` executes a program as a whole, with `jtv test`'s own
+                // fresh `Interpreter` per test handling discovery and
+                // execution of `TestDecl` bodies -- a plain `run` ignores
+                // them, the same way it doesn't register struct decls.
+                Ok(())
+            }
+            TopLevel::Control(stmt) => {
+                match self.eval_control_stmt(stmt)? {
+                    StmtFlow::Break | StmtFlow::Continue => Err(JtvError::RuntimeError(
+                        "break/continue outside of a loop".to_string(),
+                    )),
+                    StmtFlow::Normal | StmtFlow::Return(_) => Ok(()),
+                }
+            }
         }
     }
 
-    fn eval_control_stmt(&mut self, stmt: &ControlStmt) -> Result<Option<Value>> {
+    /// What a single `ControlStmt` handed back up to its caller: either it
+    /// ran to completion (`Normal`), or it's a signal that has to keep
+    /// propagating past `If`/`Block` until something that actually
+    /// terminates it is reached -- a `Return` bubbles all the way to
+    /// `call_named_function_values`, while `Break`/`Continue` stop at the
+    /// nearest enclosing `While`/`For` in this function. Replaces the
+    /// `Return`-only `Option<Value>` this used to be; private to this file,
+    /// same as `eval_control_stmt` and every one of its callers.
+    fn eval_control_stmt(&mut self, stmt: &ControlStmt) -> Result<StmtFlow> {
         self.check_iteration_limit()?;
 
         match stmt {
@@ -99,7 +168,7 @@ impl Interpreter {
 
                 self.add_trace("assignment", &format!("{} = {}", assignment.target, value));
                 self.set_variable(assignment.target.clone(), value);
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::If(if_stmt) => {
                 let condition = self.eval_control_expr_to_value(&if_stmt.condition)?;
@@ -108,18 +177,20 @@ impl Interpreter {
 
                 if condition.is_truthy() {
                     for stmt in &if_stmt.then_branch {
-                        if let Some(val) = self.eval_control_stmt(stmt)? {
-                            return Ok(Some(val));
+                        match self.eval_control_stmt(stmt)? {
+                            StmtFlow::Normal => {}
+                            flow => return Ok(flow),
                         }
                     }
                 } else if let Some(else_branch) = &if_stmt.else_branch {
                     for stmt in else_branch {
-                        if let Some(val) = self.eval_control_stmt(stmt)? {
-                            return Ok(Some(val));
+                        match self.eval_control_stmt(stmt)? {
+                            StmtFlow::Normal => {}
+                            flow => return Ok(flow),
                         }
                     }
                 }
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::While(while_stmt) => {
                 self.add_trace("while", "entering while loop");
@@ -128,13 +199,23 @@ impl Interpreter {
                     self.iteration_count += 1;
                     self.check_iteration_limit()?;
 
+                    let mut broke = false;
                     for stmt in &while_stmt.body {
-                        if let Some(val) = self.eval_control_stmt(stmt)? {
-                            return Ok(Some(val));
+                        match self.eval_control_stmt(stmt)? {
+                            StmtFlow::Normal => {}
+                            StmtFlow::Continue => break,
+                            StmtFlow::Break => {
+                                broke = true;
+                                break;
+                            }
+                            StmtFlow::Return(val) => return Ok(StmtFlow::Return(val)),
                         }
                     }
+                    if broke {
+                        break;
+                    }
                 }
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::For(for_stmt) => {
                 let range = &for_stmt.range;
@@ -164,15 +245,25 @@ impl Interpreter {
 
                     self.set_variable(for_stmt.variable.clone(), Value::Int(i));
 
+                    let mut broke = false;
                     for stmt in &for_stmt.body {
-                        if let Some(val) = self.eval_control_stmt(stmt)? {
-                            return Ok(Some(val));
+                        match self.eval_control_stmt(stmt)? {
+                            StmtFlow::Normal => {}
+                            StmtFlow::Continue => break,
+                            StmtFlow::Break => {
+                                broke = true;
+                                break;
+                            }
+                            StmtFlow::Return(val) => return Ok(StmtFlow::Return(val)),
                         }
                     }
+                    if broke {
+                        break;
+                    }
 
                     i += step;
                 }
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::Return(expr) => {
                 let value = if let Some(expr) = expr {
@@ -181,7 +272,7 @@ impl Interpreter {
                     Value::Unit
                 };
                 self.add_trace("return", &format!("return {}", value));
-                Ok(Some(value))
+                Ok(StmtFlow::Return(value))
             }
             ControlStmt::Print(exprs) => {
                 let mut output = String::new();
@@ -194,61 +285,315 @@ impl Interpreter {
                 }
                 println!("{}", output);
                 self.add_trace("print", &output);
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::ReverseBlock(block) => {
                 self.eval_reverse_block(block)?;
-                Ok(None)
+                Ok(StmtFlow::Normal)
             }
             ControlStmt::Block(stmts) => {
                 for stmt in stmts {
-                    if let Some(val) = self.eval_control_stmt(stmt)? {
-                        return Ok(Some(val));
+                    match self.eval_control_stmt(stmt)? {
+                        StmtFlow::Normal => {}
+                        flow => return Ok(flow),
                     }
                 }
-                Ok(None)
+                Ok(StmtFlow::Normal)
+            }
+            ControlStmt::Break(label) => {
+                if label.is_some() {
+                    return Err(JtvError::RuntimeError(
+                        "labeled break is not supported yet -- no loop carries a label to match against"
+                            .to_string(),
+                    ));
+                }
+                self.add_trace("break", "break");
+                Ok(StmtFlow::Break)
             }
+            ControlStmt::Continue(label) => {
+                if label.is_some() {
+                    return Err(JtvError::RuntimeError(
+                        "labeled continue is not supported yet -- no loop carries a label to match against"
+                            .to_string(),
+                    ));
+                }
+                self.add_trace("continue", "continue");
+                Ok(StmtFlow::Continue)
+            }
+        }
+    }
+
+    /// Run `block`'s inverse: `crate::reversible::check_fully_reversible`,
+    /// then undo it by calling `eval_reverse_block_backward` directly.
+    /// Useful on its own to roll back a block that was already run forward
+    /// (e.g. a checkpoint/undo), independent of `run_bidirectional`'s
+    /// within-one-call round trip.
+    pub fn run_reverse(&mut self, block: &ReverseBlock) -> Result<()> {
+        crate::reversible::check_fully_reversible(block)?;
+        self.eval_reverse_block_backward(block)
+    }
+
+    /// Run `block` forward, then immediately back, and confirm every
+    /// top-level variable the block could have touched landed back on its
+    /// starting value -- the checkpoint/undo guarantee a reversible
+    /// language exists to provide.
+    pub fn run_bidirectional(&mut self, block: &ReverseBlock) -> Result<()> {
+        crate::reversible::check_fully_reversible(block)?;
+        let before = self.globals.clone();
+        self.eval_reverse_block(block)?;
+        self.eval_reverse_block_backward(block)?;
+        if self.globals != before {
+            return Err(JtvError::NonReversible(
+                "round trip through the reverse block did not restore the original environment"
+                    .to_string(),
+            ));
         }
+        Ok(())
     }
 
     fn eval_reverse_block(&mut self, block: &ReverseBlock) -> Result<()> {
-        // Forward execution
         for stmt in &block.body {
+            self.eval_reversible_stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn eval_reversible_stmt(&mut self, stmt: &ReversibleStmt) -> Result<()> {
+        match stmt {
+            ReversibleStmt::AddAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                let current = self.get_variable(target)?;
+                let new_value = current.add(&value)?;
+                self.set_variable(target.clone(), new_value);
+            }
+            ReversibleStmt::SubAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                let current = self.get_variable(target)?;
+                let neg_value = value.negate()?;
+                let new_value = current.add(&neg_value)?;
+                self.set_variable(target.clone(), new_value);
+            }
+            ReversibleStmt::MulAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                if value.eq(&Value::Int(0))? {
+                    return Err(JtvError::RuntimeError(
+                        "reversible *= multiplier cannot be zero".to_string(),
+                    ));
+                }
+                let current = self.get_variable(target)?;
+                let new_value = current.mul(&value)?;
+                self.set_variable(target.clone(), new_value);
+            }
+            ReversibleStmt::DivAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                if value.eq(&Value::Int(0))? {
+                    return Err(JtvError::RuntimeError(
+                        "reversible /= divisor cannot be zero".to_string(),
+                    ));
+                }
+                let current = self.get_variable(target)?;
+                let new_value = current.div(&value)?;
+                self.set_variable(target.clone(), new_value);
+            }
+            ReversibleStmt::Assign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                self.set_variable(target.clone(), value);
+            }
+            ReversibleStmt::If(if_stmt) => {
+                let condition = self.eval_control_expr_to_value(&if_stmt.condition)?;
+                if condition.is_truthy() {
+                    for stmt in &if_stmt.then_branch {
+                        self.eval_control_stmt(stmt)?;
+                    }
+                } else if let Some(else_branch) = &if_stmt.else_branch {
+                    for stmt in else_branch {
+                        self.eval_control_stmt(stmt)?;
+                    }
+                }
+            }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                // `from`/`to`/`step` are evaluated once, before the first
+                // iteration -- same invariant `ReversibleInterpreter` relies
+                // on so a `body` that mutates something they'd otherwise
+                // read can't change how many times the loop runs.
+                let from_val = reversible_loop_bound(self.eval_data_expr(from)?)?;
+                let to_val = reversible_loop_bound(self.eval_data_expr(to)?)?;
+                let step_val = match step {
+                    Some(step_expr) => reversible_loop_bound(self.eval_data_expr(step_expr)?)?,
+                    None => 1,
+                };
+                if step_val == 0 {
+                    return Err(JtvError::RuntimeError(
+                        "reversible for loop step cannot be zero".to_string(),
+                    ));
+                }
+
+                let mut i = from_val;
+                while (step_val > 0 && i < to_val) || (step_val < 0 && i > to_val) {
+                    self.iteration_count += 1;
+                    self.check_iteration_limit()?;
+
+                    self.set_variable(var.clone(), Value::Int(i));
+                    for stmt in body {
+                        self.eval_reversible_stmt(stmt)?;
+                    }
+
+                    i += step_val;
+                }
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                let scrutinee_val = self.eval_data_expr(scrutinee)?;
+                let mut matched = false;
+                for (value, body) in cases {
+                    let case_val = self.eval_data_expr(value)?;
+                    if scrutinee_val.eq(&case_val)? {
+                        for stmt in body {
+                            self.eval_reversible_stmt(stmt)?;
+                        }
+                        matched = true;
+                        break;
+                    }
+                }
+                if !matched {
+                    for stmt in default.iter().flatten() {
+                        self.eval_reversible_stmt(stmt)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Undo `block`, the inverse of `eval_reverse_block`: walk `body` in
+    /// reverse order and invert each statement -- `AddAssign`'s `current -
+    /// eval(expr)`, `SubAssign`'s `current + eval(expr)` -- so running this
+    /// right after `eval_reverse_block` restores every variable it touched.
+    fn eval_reverse_block_backward(&mut self, block: &ReverseBlock) -> Result<()> {
+        for stmt in block.body.iter().rev() {
             match stmt {
                 ReversibleStmt::AddAssign(target, expr) => {
                     let value = self.eval_data_expr(expr)?;
                     let current = self.get_variable(target)?;
-                    let new_value = current.add(&value)?;
+                    let neg_value = value.negate()?;
+                    let new_value = current.add(&neg_value)?;
                     self.set_variable(target.clone(), new_value);
                 }
                 ReversibleStmt::SubAssign(target, expr) => {
                     let value = self.eval_data_expr(expr)?;
                     let current = self.get_variable(target)?;
-                    let neg_value = value.negate()?;
-                    let new_value = current.add(&neg_value)?;
+                    let new_value = current.add(&value)?;
+                    self.set_variable(target.clone(), new_value);
+                }
+                ReversibleStmt::MulAssign(target, expr) => {
+                    // Inverse of `MulAssign` is `current / value` -- for
+                    // `Value::Int` this must divide exactly, or the forward
+                    // multiplication lost information and isn't actually
+                    // reversible (see `crate::reversible::RecordedOp::MulAssign`).
+                    let value = self.eval_data_expr(expr)?;
+                    let current = self.get_variable(target)?;
+                    if let (Value::Int(c), Value::Int(v)) = (&current, &value) {
+                        if v != &0 && c % v != 0 {
+                            return Err(JtvError::RuntimeError(format!(
+                                "cannot reverse `{} *= ...`: {} is not evenly divisible by {}",
+                                target, c, v
+                            )));
+                        }
+                    }
+                    let new_value = current.div(&value)?;
+                    self.set_variable(target.clone(), new_value);
+                }
+                ReversibleStmt::DivAssign(target, expr) => {
+                    let value = self.eval_data_expr(expr)?;
+                    let current = self.get_variable(target)?;
+                    let new_value = current.mul(&value)?;
                     self.set_variable(target.clone(), new_value);
                 }
+                ReversibleStmt::Assign(..) => {
+                    // `check_fully_reversible` already rejects a plain
+                    // `Assign` before execution ever reaches here -- it has
+                    // no algebraic inverse, only the trace-based
+                    // `ReversibleInterpreter`'s `RecordedOp::Store` can undo
+                    // it, because only a trace carries the overwritten value.
+                    return Err(JtvError::NonReversible(
+                        "a plain reversible assignment doesn't have a defined static inverse -- \
+                         only AddAssign/SubAssign/MulAssign/DivAssign/If can run backward here; run it through \
+                         `ReversibleInterpreter` instead"
+                            .to_string(),
+                    ));
+                }
                 ReversibleStmt::If(if_stmt) => {
+                    // The environment is already restored up to this point,
+                    // so re-evaluating the condition here picks the same
+                    // branch the forward pass took (guaranteed by
+                    // `check_fully_reversible`'s condition-independence
+                    // check) -- then that branch is replayed in reverse.
                     let condition = self.eval_control_expr_to_value(&if_stmt.condition)?;
                     if condition.is_truthy() {
-                        for stmt in &if_stmt.then_branch {
-                            self.eval_control_stmt(stmt)?;
+                        for stmt in if_stmt.then_branch.iter().rev() {
+                            self.eval_control_stmt_backward(stmt)?;
                         }
                     } else if let Some(else_branch) = &if_stmt.else_branch {
-                        for stmt in else_branch {
-                            self.eval_control_stmt(stmt)?;
+                        for stmt in else_branch.iter().rev() {
+                            self.eval_control_stmt_backward(stmt)?;
                         }
                     }
                 }
+                ReversibleStmt::For { .. } => {
+                    // `run_reverse`/`run_bidirectional` both call
+                    // `crate::reversible::check_fully_reversible` first,
+                    // which already rejects a `For` before execution ever
+                    // reaches here -- see its doc comment for why a loop
+                    // doesn't have a defined static inverse yet.
+                    return Err(JtvError::NonReversible(
+                        "a reversible for loop doesn't have a defined static inverse yet -- only \
+                         AddAssign/SubAssign/MulAssign/DivAssign/If can run backward here"
+                            .to_string(),
+                    ));
+                }
+                ReversibleStmt::Switch { .. } => {
+                    // Same reasoning as `For` above: `check_fully_reversible`
+                    // already rejects a `Switch` before execution ever
+                    // reaches here.
+                    return Err(JtvError::NonReversible(
+                        "a reversible switch doesn't have a defined static inverse yet -- only \
+                         AddAssign/SubAssign/MulAssign/DivAssign/If can run backward here"
+                            .to_string(),
+                    ));
+                }
             }
         }
         Ok(())
     }
 
+    /// The only `ControlStmt`s with a defined inverse inside a reversible
+    /// if-branch are nested reverse blocks -- matching
+    /// `reversible::invert_control_stmt`'s restriction for the same reason.
+    fn eval_control_stmt_backward(&mut self, stmt: &ControlStmt) -> Result<()> {
+        match stmt {
+            ControlStmt::ReverseBlock(block) => self.eval_reverse_block_backward(block),
+            other => Err(JtvError::NonReversible(format!(
+                "cannot run {:?} backward inside a reversible if branch: only a nested reverse \
+                 block has a defined inverse there",
+                other
+            ))),
+        }
+    }
+
     fn eval_data_expr(&mut self, expr: &DataExpr) -> Result<Value> {
         match expr {
             DataExpr::Number(num) => Value::from_number(num),
-            DataExpr::Identifier(name) => self.get_variable(name),
+            DataExpr::Identifier(name) => self.get_variable(name).or_else(|err| {
+                // Not a variable -- if it names a known function or builtin,
+                // resolve it to a first-class value instead (so it can be
+                // passed to `map`/`filter`/... or bound to a variable).
+                if self.functions.contains_key(name) {
+                    Ok(Value::Closure(name.clone()))
+                } else if self.stdlib.has(name) || self.stdlib.has_hof(name) {
+                    Ok(Value::Builtin(name.clone()))
+                } else {
+                    Err(err)
+                }
+            }),
             DataExpr::Add(left, right) => {
                 let left_val = self.eval_data_expr(left)?;
                 let right_val = self.eval_data_expr(right)?;
@@ -264,7 +609,7 @@ impl Interpreter {
                 for elem in elements {
                     values.push(self.eval_data_expr(elem)?);
                 }
-                Ok(Value::List(values))
+                Ok(Value::List(PVector::from_vec(values)))
             }
             DataExpr::Tuple(elements) => {
                 let mut values = Vec::new();
@@ -273,7 +618,123 @@ impl Interpreter {
                 }
                 Ok(Value::Tuple(values))
             }
+            DataExpr::FieldAccess(base, field) => {
+                let base_val = self.eval_data_expr(base)?;
+                match base_val {
+                    Value::Struct(name, fields) => fields
+                        .into_iter()
+                        .find(|(n, _)| n == field)
+                        .map(|(_, v)| v)
+                        .ok_or_else(|| {
+                            JtvError::RuntimeError(format!("no field `{}` on {}", field, name))
+                        }),
+                    other => Err(JtvError::TypeError(format!(
+                        "no field `{}` on {}",
+                        field, other
+                    ))),
+                }
+            }
+            DataExpr::StructLiteral(name, provided) => {
+                let mut fields = Vec::with_capacity(provided.len());
+                for (field_name, field_expr) in provided {
+                    fields.push((field_name.clone(), self.eval_data_expr(field_expr)?));
+                }
+                Ok(Value::Struct(name.clone(), fields))
+            }
+            DataExpr::ListComprehension(comp) => {
+                self.call_stack.push(HashMap::new());
+                let result = self.eval_comprehension_generators(comp, 0);
+                self.call_stack.pop();
+                result.map(|items| Value::List(PVector::from_vec(items)))
+            }
+            DataExpr::Index(base, index) => {
+                let base_val = self.eval_data_expr(base)?;
+                let index_val = self.eval_data_expr(index)?;
+                let i = match index_val {
+                    Value::Int(i) => i,
+                    other => {
+                        return Err(JtvError::TypeError(format!(
+                            "subscript index must be Int, got {}",
+                            other
+                        )))
+                    }
+                };
+                match base_val {
+                    Value::Tuple(items) => {
+                        if i < 0 || i as usize >= items.len() {
+                            Err(JtvError::IndexOutOfRange {
+                                index: i.to_string(),
+                                size: items.len(),
+                            })
+                        } else {
+                            Ok(items[i as usize].clone())
+                        }
+                    }
+                    Value::List(items) => {
+                        if i < 0 || i as usize >= items.len() {
+                            Err(JtvError::IndexOutOfRange {
+                                index: i.to_string(),
+                                size: items.len(),
+                            })
+                        } else {
+                            Ok(items[i as usize].clone())
+                        }
+                    }
+                    Value::String(s) => {
+                        let chars: Vec<char> = s.chars().collect();
+                        if i < 0 || i as usize >= chars.len() {
+                            Err(JtvError::IndexOutOfRange {
+                                index: i.to_string(),
+                                size: chars.len(),
+                            })
+                        } else {
+                            Ok(Value::String(chars[i as usize].to_string()))
+                        }
+                    }
+                    other => Err(JtvError::TypeError(format!(
+                        "cannot index into {}",
+                        other
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Evaluate generator clause `index` of `comp`, binding its variable and
+    /// recursing into the remaining clauses left-to-right so later clauses
+    /// see earlier-bound variables, then the filter and body once all
+    /// clauses are bound.
+    fn eval_comprehension_generators(
+        &mut self,
+        comp: &Comprehension,
+        index: usize,
+    ) -> Result<Vec<Value>> {
+        if index == comp.generators.len() {
+            if let Some(condition) = &comp.condition {
+                if !self.eval_control_expr_to_value(condition)?.is_truthy() {
+                    return Ok(Vec::new());
+                }
+            }
+            return Ok(vec![self.eval_data_expr(&comp.body)?]);
+        }
+
+        let (variable, source) = &comp.generators[index];
+        let items = match self.eval_data_expr(source)? {
+            Value::List(items) => items,
+            other => {
+                return Err(JtvError::RuntimeError(format!(
+                    "cannot iterate over {} in a comprehension",
+                    other
+                )))
+            }
+        };
+
+        let mut results = Vec::new();
+        for item in items {
+            self.set_variable(variable.clone(), item);
+            results.extend(self.eval_comprehension_generators(comp, index + 1)?);
         }
+        Ok(results)
     }
 
     fn eval_control_expr_to_value(&mut self, expr: &ControlExpr) -> Result<Value> {
@@ -320,50 +781,132 @@ impl Interpreter {
                 let value = self.eval_control_expr_to_value(expr)?;
                 Ok(Value::Bool(!value.is_truthy()))
             }
+            ControlExpr::Contains(left, right) => {
+                let left_val = self.eval_data_expr(left)?;
+                let right_val = self.eval_data_expr(right)?;
+                Ok(Value::Bool(left_val.contains(&right_val)?))
+            }
         }
     }
 
     fn eval_function_call(&mut self, call: &FunctionCall) -> Result<Value> {
-        let func = self.functions.get(&call.name)
-            .ok_or_else(|| JtvError::UndefinedFunction(call.name.clone()))?
+        // A name that already resolves to a first-class function value
+        // (e.g. a parameter bound by an earlier HOF call, or a plain
+        // `f = someFunction` assignment) is called through that value,
+        // shadowing any same-named function/builtin -- this is what lets a
+        // higher-order JtV function like `fn applyTwice(f, x)` call its `f`
+        // parameter as `f(x)`.
+        if let Ok(value) = self.get_variable(&call.name) {
+            match value {
+                Value::Closure(name) => {
+                    let arg_values = self.eval_args(&call.args)?;
+                    return self.call_named_function_values(&name, &arg_values);
+                }
+                Value::Builtin(name) => {
+                    let arg_values = self.eval_args(&call.args)?;
+                    return self.stdlib.call(&name, &arg_values);
+                }
+                Value::PartialApp { name, collected } => {
+                    let mut arg_values = collected;
+                    arg_values.extend(self.eval_args(&call.args)?);
+                    return self.stdlib.call(&name, &arg_values);
+                }
+                _ => {} // not callable; fall through to direct name resolution
+            }
+        }
+
+        if self.functions.contains_key(&call.name) {
+            let arg_values = self.eval_args(&call.args)?;
+            return self.call_named_function_values(&call.name.clone(), &arg_values);
+        }
+
+        if self.stdlib.has_hof(&call.name) {
+            return self.call_hof(&call.name.clone(), &call.args);
+        }
+
+        let arg_values = self.eval_args(&call.args)?;
+        self.stdlib.call(&call.name, &arg_values)
+    }
+
+    fn eval_args(&mut self, args: &[DataExpr]) -> Result<Vec<Value>> {
+        args.iter().map(|arg| self.eval_data_expr(arg)).collect()
+    }
+
+    /// Runs a user-defined function's body against already-evaluated
+    /// arguments. Split out from `eval_function_call` so the HOF apply
+    /// callback (see `call_hof`) can invoke a `Value::Closure` without
+    /// re-evaluating `DataExpr`s that were never there to begin with (the
+    /// arguments came from a list being mapped/folded over, not a call
+    /// site).
+    fn call_named_function_values(&mut self, name: &str, arg_values: &[Value]) -> Result<Value> {
+        let func = self.functions.get(name)
+            .ok_or_else(|| JtvError::UndefinedFunction(name.to_string()))?
             .clone();
 
-        if func.params.len() != call.args.len() {
+        if func.params.len() != arg_values.len() {
             return Err(JtvError::ArityMismatch {
                 expected: func.params.len(),
-                got: call.args.len(),
+                got: arg_values.len(),
             });
         }
 
-        // Evaluate arguments
-        let mut arg_values = Vec::new();
-        for arg in &call.args {
-            arg_values.push(self.eval_data_expr(arg)?);
-        }
-
-        // Create new scope
         self.call_stack.push(HashMap::new());
 
-        // Bind parameters
         for (param, value) in func.params.iter().zip(arg_values.iter()) {
             self.set_variable(param.name.clone(), value.clone());
         }
 
-        // Execute function body
         let mut result = Value::Unit;
         for stmt in &func.body {
-            if let Some(val) = self.eval_control_stmt(stmt)? {
-                result = val;
-                break;
+            match self.eval_control_stmt(stmt)? {
+                StmtFlow::Normal => {}
+                StmtFlow::Return(val) => {
+                    result = val;
+                    break;
+                }
+                StmtFlow::Break | StmtFlow::Continue => {
+                    self.call_stack.pop();
+                    return Err(JtvError::RuntimeError(
+                        "break/continue outside of a loop".to_string(),
+                    ));
+                }
             }
         }
 
-        // Pop scope
         self.call_stack.pop();
 
         Ok(result)
     }
 
+    /// Evaluates a call to one of `StdLib`'s higher-order builtins
+    /// (`map`, `filter`, `foldl`, ...), supplying the callback it uses to
+    /// invoke whichever `Value::Closure`/`Value::Builtin` argument it was
+    /// passed.
+    fn call_hof(&mut self, name: &str, args: &[DataExpr]) -> Result<Value> {
+        let arg_values = self.eval_args(args)?;
+
+        // `apply` needs `&mut self` (to push/pop call_stack frames for a
+        // `Closure`) at the same time `stdlib` needs a borrow to dispatch
+        // the call itself, so the stdlib is held outside `self` for the
+        // duration of this call rather than borrowed through it.
+        let stdlib = std::mem::take(&mut self.stdlib);
+        let mut apply = |f: &Value, call_args: &[Value]| -> Result<Value> {
+            match f {
+                Value::Closure(fname) => self.call_named_function_values(fname, call_args),
+                Value::Builtin(bname) => stdlib.call(bname, call_args),
+                Value::PartialApp { name, collected } => {
+                    let mut arg_values = collected.clone();
+                    arg_values.extend_from_slice(call_args);
+                    stdlib.call(name, &arg_values)
+                }
+                other => Err(JtvError::TypeError(format!("{} is not callable", other))),
+            }
+        };
+        let result = stdlib.call_hof(name, &arg_values, &mut apply);
+        self.stdlib = stdlib;
+        result
+    }
+
     fn get_variable(&self, name: &str) -> Result<Value> {
         // Check call stack (local variables)
         for scope in self.call_stack.iter().rev() {
@@ -471,4 +1014,168 @@ fn double(x: Int): Int {
         let sum = interpreter.get_variable("sum").unwrap();
         assert_eq!(sum, Value::Int(15)); // 1+2+3+4+5
     }
+
+    #[test]
+    fn test_run_bidirectional_restores_original_state() {
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::SubAssign("y".to_string(), DataExpr::Number(Number::Int(3))),
+            ],
+        };
+
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x".to_string(), Value::Int(10));
+        interpreter.set_variable("y".to_string(), Value::Int(20));
+
+        interpreter.run_bidirectional(&block).unwrap();
+
+        assert_eq!(interpreter.get_variable("x").unwrap(), Value::Int(10));
+        assert_eq!(interpreter.get_variable("y").unwrap(), Value::Int(20));
+    }
+
+    #[test]
+    fn test_run_reverse_undoes_a_forward_run() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::AddAssign(
+                "x".to_string(),
+                DataExpr::Number(Number::Int(7)),
+            )],
+        };
+
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x".to_string(), Value::Int(1));
+        interpreter.run(&Program {
+            statements: vec![TopLevel::Control(ControlStmt::ReverseBlock(block.clone()))],
+            span: Span::unknown(),
+        })
+        .unwrap();
+        assert_eq!(interpreter.get_variable("x").unwrap(), Value::Int(8));
+
+        interpreter.run_reverse(&block).unwrap();
+        assert_eq!(interpreter.get_variable("x").unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    fn test_run_reverse_rejects_self_referential_assignment() {
+        // x += x can't be undone: the expression needed to subtract back
+        // out is already gone once `x` has been overwritten.
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::AddAssign(
+                "x".to_string(),
+                DataExpr::Identifier("x".to_string()),
+            )],
+        };
+
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x".to_string(), Value::Int(1));
+
+        assert!(matches!(
+            interpreter.run_reverse(&block),
+            Err(JtvError::NonReversible(_))
+        ));
+    }
+
+    #[test]
+    fn test_index_into_list_returns_element() {
+        let list = DataExpr::List(vec![
+            DataExpr::Number(Number::Int(10)),
+            DataExpr::Number(Number::Int(20)),
+        ]);
+        let mut interpreter = Interpreter::new();
+        let value = interpreter
+            .eval_data_expr(&DataExpr::Index(
+                Box::new(list),
+                Box::new(DataExpr::Number(Number::Int(1))),
+            ))
+            .unwrap();
+        assert_eq!(value, Value::Int(20));
+    }
+
+    #[test]
+    fn test_index_out_of_range_on_list_is_reported() {
+        let list = DataExpr::List(vec![DataExpr::Number(Number::Int(10))]);
+        let mut interpreter = Interpreter::new();
+        let err = interpreter
+            .eval_data_expr(&DataExpr::Index(
+                Box::new(list),
+                Box::new(DataExpr::Number(Number::Int(5))),
+            ))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JtvError::IndexOutOfRange { index, size: 1 } if index == "5"
+        ));
+    }
+
+    #[test]
+    fn test_negative_index_on_tuple_is_reported() {
+        let tuple = DataExpr::Tuple(vec![DataExpr::Number(Number::Int(10))]);
+        let mut interpreter = Interpreter::new();
+        let err = interpreter
+            .eval_data_expr(&DataExpr::Index(
+                Box::new(tuple),
+                Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+            ))
+            .unwrap_err();
+        assert!(matches!(err, JtvError::IndexOutOfRange { index, .. } if index == "-1"));
+    }
+
+    #[test]
+    fn test_index_into_string_value_returns_single_char() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("s".to_string(), Value::String("abc".to_string()));
+        let value = interpreter
+            .eval_data_expr(&DataExpr::Index(
+                Box::new(DataExpr::Identifier("s".to_string())),
+                Box::new(DataExpr::Number(Number::Int(1))),
+            ))
+            .unwrap();
+        assert_eq!(value, Value::String("b".to_string()));
+    }
+
+    #[test]
+    fn test_contains_true_when_value_is_a_list_element() {
+        let list = DataExpr::List(vec![
+            DataExpr::Number(Number::Int(1)),
+            DataExpr::Number(Number::Int(2)),
+            DataExpr::Number(Number::Int(3)),
+        ]);
+        let mut interpreter = Interpreter::new();
+        let result = interpreter
+            .eval_control_expr_to_value(&ControlExpr::Contains(
+                Box::new(DataExpr::Number(Number::Int(2))),
+                Box::new(list),
+            ))
+            .unwrap();
+        assert_eq!(result, Value::Bool(true));
+    }
+
+    #[test]
+    fn test_contains_false_when_value_is_not_a_tuple_element() {
+        let tuple = DataExpr::Tuple(vec![
+            DataExpr::Number(Number::Int(1)),
+            DataExpr::Number(Number::Int(2)),
+        ]);
+        let mut interpreter = Interpreter::new();
+        let result = interpreter
+            .eval_control_expr_to_value(&ControlExpr::Contains(
+                Box::new(DataExpr::Number(Number::Int(5))),
+                Box::new(tuple),
+            ))
+            .unwrap();
+        assert_eq!(result, Value::Bool(false));
+    }
+
+    #[test]
+    fn test_contains_rejects_non_collection_right_side() {
+        let mut interpreter = Interpreter::new();
+        let err = interpreter
+            .eval_control_expr_to_value(&ControlExpr::Contains(
+                Box::new(DataExpr::Number(Number::Int(1))),
+                Box::new(DataExpr::Number(Number::Int(5))),
+            ))
+            .unwrap_err();
+        assert!(matches!(err, JtvError::TypeError(_)));
+    }
 }
@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Lazy, pull-based sequences
+
+use crate::error::Result;
+use crate::number::Value;
+use serde::{Deserialize, Serialize};
+
+/// A lazy sequence of values, pulled one element at a time instead of being
+/// materialized up front. Modeled as plain data (an enum of combinator
+/// states) rather than a `Box<dyn Iterator>` so it stays `Clone` /
+/// `PartialEq` / `Debug` like every other `Value` variant -- a boxed trait
+/// object couldn't derive any of those. Each variant holds just enough
+/// state to produce its next element on demand; `step` is the only place
+/// any work happens, and `crate::stdlib`'s `stdlib_collect` is the only place a
+/// `ValueIter` gets forced into a `Value::List`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ValueIter {
+    Range {
+        current: i64,
+        end: i64,
+        step: i64,
+    },
+    List {
+        items: Vec<Value>,
+        index: usize,
+    },
+    Take {
+        inner: Box<ValueIter>,
+        remaining: usize,
+    },
+    Drop {
+        inner: Box<ValueIter>,
+        remaining: usize,
+    },
+    TakeWhile {
+        inner: Box<ValueIter>,
+        predicate: Box<Value>,
+        done: bool,
+    },
+    Enumerate {
+        inner: Box<ValueIter>,
+        index: i64,
+    },
+    Chain {
+        first: Box<ValueIter>,
+        second: Box<ValueIter>,
+        on_second: bool,
+    },
+}
+
+impl ValueIter {
+    pub fn range(start: i64, end: i64, step: i64) -> Self {
+        ValueIter::Range { current: start, end, step }
+    }
+
+    pub fn from_list(items: Vec<Value>) -> Self {
+        ValueIter::List { items, index: 0 }
+    }
+
+    pub fn take(self, n: usize) -> Self {
+        ValueIter::Take { inner: Box::new(self), remaining: n }
+    }
+
+    pub fn drop(self, n: usize) -> Self {
+        ValueIter::Drop { inner: Box::new(self), remaining: n }
+    }
+
+    /// `predicate` is a `Value::Closure`/`Value::Builtin` -- it isn't
+    /// called here, only stashed, so building a `takeWhile` pipeline never
+    /// runs user code; only pulling from it (via `step`) does.
+    pub fn take_while(self, predicate: Value) -> Self {
+        ValueIter::TakeWhile {
+            inner: Box::new(self),
+            predicate: Box::new(predicate),
+            done: false,
+        }
+    }
+
+    pub fn enumerate(self) -> Self {
+        ValueIter::Enumerate { inner: Box::new(self), index: 0 }
+    }
+
+    pub fn chain(self, other: ValueIter) -> Self {
+        ValueIter::Chain { first: Box::new(self), second: Box::new(other), on_second: false }
+    }
+
+    /// Pulls the next element, if any. `apply` is only invoked by
+    /// `TakeWhile`, to test its predicate against the candidate element --
+    /// every other combinator is pure bookkeeping over values `step` has
+    /// already produced.
+    pub fn step(
+        &mut self,
+        apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>,
+    ) -> Result<Option<Value>> {
+        match self {
+            ValueIter::Range { current, end, step } => {
+                if (*step > 0 && current < end) || (*step < 0 && current > end) {
+                    let value = Value::Int(*current);
+                    *current += *step;
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+            ValueIter::List { items, index } => {
+                if *index < items.len() {
+                    let value = items[*index].clone();
+                    *index += 1;
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+            ValueIter::Take { inner, remaining } => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+                match inner.step(apply)? {
+                    Some(value) => {
+                        *remaining -= 1;
+                        Ok(Some(value))
+                    }
+                    None => {
+                        *remaining = 0;
+                        Ok(None)
+                    }
+                }
+            }
+            ValueIter::Drop { inner, remaining } => {
+                while *remaining > 0 {
+                    if inner.step(apply)?.is_none() {
+                        *remaining = 0;
+                        return Ok(None);
+                    }
+                    *remaining -= 1;
+                }
+                inner.step(apply)
+            }
+            ValueIter::TakeWhile { inner, predicate, done } => {
+                if *done {
+                    return Ok(None);
+                }
+                match inner.step(apply)? {
+                    Some(value) => {
+                        if apply(predicate, &[value.clone()])?.is_truthy() {
+                            Ok(Some(value))
+                        } else {
+                            *done = true;
+                            Ok(None)
+                        }
+                    }
+                    None => {
+                        *done = true;
+                        Ok(None)
+                    }
+                }
+            }
+            ValueIter::Enumerate { inner, index } => match inner.step(apply)? {
+                Some(value) => {
+                    let i = *index;
+                    *index += 1;
+                    Ok(Some(Value::Tuple(vec![Value::Int(i), value])))
+                }
+                None => Ok(None),
+            },
+            ValueIter::Chain { first, second, on_second } => {
+                if !*on_second {
+                    if let Some(value) = first.step(apply)? {
+                        return Ok(Some(value));
+                    }
+                    *on_second = true;
+                }
+                second.step(apply)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_predicate(_f: &Value, _args: &[Value]) -> Result<Value> {
+        unreachable!("this test never constructs a takeWhile")
+    }
+
+    #[test]
+    fn test_range_steps_to_exhaustion() {
+        let mut iter = ValueIter::range(0, 3, 1);
+        let mut apply = no_predicate;
+        assert_eq!(iter.step(&mut apply).unwrap(), Some(Value::Int(0)));
+        assert_eq!(iter.step(&mut apply).unwrap(), Some(Value::Int(1)));
+        assert_eq!(iter.step(&mut apply).unwrap(), Some(Value::Int(2)));
+        assert_eq!(iter.step(&mut apply).unwrap(), None);
+    }
+
+    #[test]
+    fn test_take_and_drop() {
+        let mut apply = no_predicate;
+        let mut taken = ValueIter::range(0, 10, 1).take(2);
+        assert_eq!(taken.step(&mut apply).unwrap(), Some(Value::Int(0)));
+        assert_eq!(taken.step(&mut apply).unwrap(), Some(Value::Int(1)));
+        assert_eq!(taken.step(&mut apply).unwrap(), None);
+
+        let mut dropped = ValueIter::range(0, 5, 1).drop(3);
+        assert_eq!(dropped.step(&mut apply).unwrap(), Some(Value::Int(3)));
+        assert_eq!(dropped.step(&mut apply).unwrap(), Some(Value::Int(4)));
+        assert_eq!(dropped.step(&mut apply).unwrap(), None);
+    }
+
+    #[test]
+    fn test_take_while_stops_at_first_failure() {
+        let mut iter = ValueIter::from_list(vec![Value::Int(2), Value::Int(4), Value::Int(5), Value::Int(6)])
+            .take_while(Value::Builtin("isEven".to_string()));
+        let mut apply = |_f: &Value, args: &[Value]| -> Result<Value> {
+            match &args[0] {
+                Value::Int(n) => Ok(Value::Bool(n % 2 == 0)),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(iter.step(&mut apply).unwrap(), Some(Value::Int(2)));
+        assert_eq!(iter.step(&mut apply).unwrap(), Some(Value::Int(4)));
+        assert_eq!(iter.step(&mut apply).unwrap(), None);
+    }
+
+    #[test]
+    fn test_enumerate_and_chain() {
+        let mut apply = no_predicate;
+        let mut enumerated = ValueIter::from_list(vec![Value::Int(7), Value::Int(8)]).enumerate();
+        assert_eq!(
+            enumerated.step(&mut apply).unwrap(),
+            Some(Value::Tuple(vec![Value::Int(0), Value::Int(7)]))
+        );
+        assert_eq!(
+            enumerated.step(&mut apply).unwrap(),
+            Some(Value::Tuple(vec![Value::Int(1), Value::Int(8)]))
+        );
+
+        let mut chained = ValueIter::from_list(vec![Value::Int(1)])
+            .chain(ValueIter::from_list(vec![Value::Int(2)]));
+        assert_eq!(chained.step(&mut apply).unwrap(), Some(Value::Int(1)));
+        assert_eq!(chained.step(&mut apply).unwrap(), Some(Value::Int(2)));
+        assert_eq!(chained.step(&mut apply).unwrap(), None);
+    }
+}
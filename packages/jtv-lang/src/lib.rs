@@ -8,16 +8,24 @@ pub mod ast;
 pub mod parser;
 pub mod interpreter;
 pub mod number;
+pub mod pvector;
 pub mod error;
 pub mod typechecker;
 pub mod purity;
+pub mod effects;
 pub mod reversible;
+pub mod reversible_vm;
+pub mod symbolic;
 pub mod wasm;
 pub mod wasmgen;
 pub mod bytecode;
+pub mod iterator;
 pub mod stdlib;
 pub mod formatter;
+pub mod diagnostics;
+pub mod lint;
 pub mod libraries;  // Library system (common + JtV-specific)
+pub mod optimizer;
 
 pub use ast::*;
 pub use parser::*;
@@ -27,6 +35,7 @@ pub use error::*;
 pub use typechecker::*;
 pub use purity::*;
 pub use reversible::*;
+pub use symbolic::*;
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm::*;
@@ -5,6 +5,7 @@
 // These are language-agnostic and could be shared across implementations
 
 use crate::number::Value;
+use crate::pvector::PVector;
 use crate::error::{JtvError, Result};
 
 /// Get length of a list or tuple
@@ -68,7 +69,7 @@ pub fn tail(args: &[Value]) -> Result<Value> {
             if items.is_empty() {
                 return Err(JtvError::RuntimeError("tail of empty list".to_string()));
             }
-            Ok(Value::List(items[1..].to_vec()))
+            Ok(Value::List(items.split_at(1).1))
         }
         _ => Err(JtvError::TypeError("tail requires a list".to_string())),
     }
@@ -93,7 +94,7 @@ pub fn init(args: &[Value]) -> Result<Value> {
             if items.is_empty() {
                 return Err(JtvError::RuntimeError("init of empty list".to_string()));
             }
-            Ok(Value::List(items[..items.len()-1].to_vec()))
+            Ok(Value::List(items.split_at(items.len() - 1).0))
         }
         _ => Err(JtvError::TypeError("init requires a list".to_string())),
     }
@@ -103,9 +104,7 @@ pub fn init(args: &[Value]) -> Result<Value> {
 pub fn reverse(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::List(items) => {
-            let mut reversed = items.clone();
-            reversed.reverse();
-            Ok(Value::List(reversed))
+            Ok(Value::List(items.reverse()))
         }
         _ => Err(JtvError::TypeError("reverse requires a list".to_string())),
     }
@@ -116,7 +115,7 @@ pub fn range(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::Int(start), Value::Int(end)) => {
             let list: Vec<Value> = (*start..*end).map(Value::Int).collect();
-            Ok(Value::List(list))
+            Ok(Value::List(PVector::from_vec(list)))
         }
         _ => Err(JtvError::TypeError("range requires integer arguments".to_string())),
     }
@@ -126,9 +125,7 @@ pub fn range(args: &[Value]) -> Result<Value> {
 pub fn concat(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::List(a), Value::List(b)) => {
-            let mut result = a.clone();
-            result.extend(b.clone());
-            Ok(Value::List(result))
+            Ok(Value::List(a.concat(b)))
         }
         (Value::String(a), Value::String(b)) => {
             Ok(Value::String(format!("{}{}", a, b)))
@@ -171,7 +168,7 @@ pub fn take(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::List(items), Value::Int(n)) => {
             let n = (*n as usize).min(items.len());
-            Ok(Value::List(items[..n].to_vec()))
+            Ok(Value::List(items.split_at(n).0))
         }
         _ => Err(JtvError::TypeError("take requires a list and integer".to_string())),
     }
@@ -182,7 +179,7 @@ pub fn drop(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::List(items), Value::Int(n)) => {
             let n = (*n as usize).min(items.len());
-            Ok(Value::List(items[n..].to_vec()))
+            Ok(Value::List(items.split_at(n).1))
         }
         _ => Err(JtvError::TypeError("drop requires a list and integer".to_string())),
     }
@@ -192,7 +189,7 @@ pub fn drop(args: &[Value]) -> Result<Value> {
 pub fn zip(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::List(a), Value::List(b)) => {
-            let zipped: Vec<Value> = a.iter().zip(b.iter())
+            let zipped: PVector<Value> = a.iter().zip(b.iter())
                 .map(|(x, y)| Value::Tuple(vec![x.clone(), y.clone()]))
                 .collect();
             Ok(Value::List(zipped))
@@ -267,23 +264,23 @@ mod tests {
 
     #[test]
     fn test_length() {
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
         assert_eq!(length(&[list]).unwrap(), Value::Int(3));
     }
 
     #[test]
     fn test_sum() {
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
         assert_eq!(sum(&[list]).unwrap(), Value::Int(6));
     }
 
     #[test]
     fn test_head_tail() {
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
         assert_eq!(head(&[list.clone()]).unwrap(), Value::Int(1));
         assert_eq!(
             tail(&[list]).unwrap(),
-            Value::List(vec![Value::Int(2), Value::Int(3)])
+            Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(3)]))
         );
     }
 
@@ -291,16 +288,16 @@ mod tests {
     fn test_range() {
         assert_eq!(
             range(&[Value::Int(1), Value::Int(4)]).unwrap(),
-            Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]))
         );
     }
 
     #[test]
     fn test_reverse() {
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
         assert_eq!(
             reverse(&[list]).unwrap(),
-            Value::List(vec![Value::Int(3), Value::Int(2), Value::Int(1)])
+            Value::List(PVector::from_vec(vec![Value::Int(3), Value::Int(2), Value::Int(1)]))
         );
     }
 }
@@ -4,14 +4,17 @@
 // Common Math Library - Universal mathematical functions
 // These are language-agnostic and could be shared across implementations
 
-use crate::number::Value;
+use crate::number::{ratio_to_f64, ArithmeticMode, EvalOptions, Value};
 use crate::error::{JtvError, Result};
-use num_traits::Signed;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_traits::{Signed, ToPrimitive};
 
 /// Absolute value
 pub fn abs(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Int(n) => Ok(Value::Int(n.abs())),
+        Value::BigInt(n) => Ok(Value::BigInt(n.abs())),
         Value::Float(f) => Ok(Value::Float(f.abs())),
         Value::Rational(r) => Ok(Value::Rational(r.abs())),
         _ => Err(JtvError::TypeError("abs requires a numeric argument".to_string())),
@@ -129,8 +132,16 @@ pub fn lcm(args: &[Value]) -> Result<Value> {
     }
 }
 
-/// Factorial
+/// Factorial. Accumulates in `i64` and promotes to `BigInt` once the
+/// result would overflow, so large factorials stay exact.
 pub fn factorial(args: &[Value]) -> Result<Value> {
+    factorial_with(args, &EvalOptions::default())
+}
+
+/// Factorial with an explicit [`EvalOptions`]: `Promoting` (the default)
+/// widens to `BigInt` on overflow as before, while `Checked` reports
+/// `JtvError::IntegerOverflow` instead and `Wrapping` wraps in `i64`.
+pub fn factorial_with(args: &[Value], opts: &EvalOptions) -> Result<Value> {
     match &args[0] {
         Value::Int(n) => {
             if *n < 0 {
@@ -138,7 +149,20 @@ pub fn factorial(args: &[Value]) -> Result<Value> {
             }
             let mut result: i64 = 1;
             for i in 2..=*n {
-                result = result.saturating_mul(i);
+                match result.checked_mul(i) {
+                    Some(r) => result = r,
+                    None => match opts.arithmetic_mode {
+                        ArithmeticMode::Checked => return Err(JtvError::IntegerOverflow),
+                        ArithmeticMode::Wrapping => result = result.wrapping_mul(i),
+                        ArithmeticMode::Promoting => {
+                            let mut big = BigInt::from(result);
+                            for j in i..=*n {
+                                big *= j;
+                            }
+                            return Ok(normalize_bigint(big));
+                        }
+                    },
+                }
             }
             Ok(Value::Int(result))
         }
@@ -146,6 +170,27 @@ pub fn factorial(args: &[Value]) -> Result<Value> {
     }
 }
 
+/// Finish a square-and-multiply exponentiation in `BigInt` once the `i64`
+/// accumulators have overflowed.
+fn pow_bigint(mut result: BigInt, mut base: BigInt, mut exp: u32) -> BigInt {
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result *= &base;
+        }
+        base = &base * &base;
+        exp >>= 1;
+    }
+    result
+}
+
+/// Demote a `BigInt` result back to `Int` when it fits in an `i64`.
+fn normalize_bigint(n: BigInt) -> Value {
+    match n.to_i64() {
+        Some(i) => Value::Int(i),
+        None => Value::BigInt(n),
+    }
+}
+
 /// Check if a number is prime
 pub fn is_prime(args: &[Value]) -> Result<Value> {
     match &args[0] {
@@ -172,8 +217,17 @@ pub fn is_prime(args: &[Value]) -> Result<Value> {
     }
 }
 
-/// Power function
+/// Power function. Squares-and-multiplies in `i64` and, the moment either
+/// accumulator would overflow, switches to the same recurrence over `BigInt`
+/// so large powers stay exact instead of wrapping.
 pub fn pow(args: &[Value]) -> Result<Value> {
+    pow_with(args, &EvalOptions::default())
+}
+
+/// Power with an explicit [`EvalOptions`]: `Promoting` (the default) widens
+/// to `BigInt` on overflow as before, while `Checked` reports
+/// `JtvError::IntegerOverflow` instead and `Wrapping` wraps in `i64`.
+pub fn pow_with(args: &[Value], opts: &EvalOptions) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::Int(base), Value::Int(exp)) => {
             if *exp < 0 {
@@ -181,13 +235,36 @@ pub fn pow(args: &[Value]) -> Result<Value> {
             }
             let mut result: i64 = 1;
             let mut base = *base;
-            let mut exp = *exp as u32;
+            let mut exp = u32::try_from(*exp).map_err(|_| {
+                JtvError::RuntimeError(format!("exponent {} is too large", exp))
+            })?;
             while exp > 0 {
-                if exp & 1 == 1 {
-                    result = result.saturating_mul(base);
+                let next_result = if exp & 1 == 1 { result.checked_mul(base) } else { Some(result) };
+                let next_base = base.checked_mul(base);
+                match (next_result, next_base) {
+                    (Some(r), Some(b)) => {
+                        result = r;
+                        base = b;
+                        exp >>= 1;
+                    }
+                    _ => match opts.arithmetic_mode {
+                        ArithmeticMode::Checked => return Err(JtvError::IntegerOverflow),
+                        ArithmeticMode::Wrapping => {
+                            let wrapped_result = if exp & 1 == 1 { result.wrapping_mul(base) } else { result };
+                            let wrapped_base = base.wrapping_mul(base);
+                            result = wrapped_result;
+                            base = wrapped_base;
+                            exp >>= 1;
+                        }
+                        ArithmeticMode::Promoting => {
+                            return Ok(normalize_bigint(pow_bigint(
+                                BigInt::from(result),
+                                BigInt::from(base),
+                                exp,
+                            )))
+                        }
+                    },
                 }
-                base = base.saturating_mul(base);
-                exp >>= 1;
             }
             Ok(Value::Int(result))
         }
@@ -201,22 +278,312 @@ pub fn pow(args: &[Value]) -> Result<Value> {
     }
 }
 
-/// Square root
+/// Square root. Negative real inputs return a `Complex` result instead of
+/// erroring, matching the behavior of `Complex` inputs.
 pub fn sqrt(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::Int(n) => {
-            if *n < 0 {
-                return Err(JtvError::RuntimeError("sqrt of negative number".to_string()));
+        Value::Complex(c) => Ok(Value::Complex(c.sqrt())),
+        other => {
+            let f = to_f64(other)
+                .ok_or_else(|| JtvError::TypeError("sqrt requires a numeric argument".to_string()))?;
+            if f < 0.0 {
+                Ok(Value::Complex(Complex64::new(f, 0.0).sqrt()))
+            } else {
+                Ok(Value::Float(f.sqrt()))
             }
-            Ok(Value::Float((*n as f64).sqrt()))
         }
-        Value::Float(f) => {
-            if *f < 0.0 {
-                return Err(JtvError::RuntimeError("sqrt of negative number".to_string()));
+    }
+}
+
+/// Lift `Int`/`BigInt`/`Rational`/`Hex`/`Binary`/`Float` to `f64`.
+fn to_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Int(n) | Value::Hex(n) | Value::Binary(n) => Some(*n as f64),
+        Value::BigInt(n) => n.to_f64(),
+        Value::Rational(r) => Some(ratio_to_f64(r)),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Lift any numeric `Value` (including `Complex` itself) to `Complex64`.
+fn to_complex(v: &Value) -> Option<Complex64> {
+    match v {
+        Value::Complex(c) => Some(*c),
+        other => to_f64(other).map(|f| Complex64::new(f, 0.0)),
+    }
+}
+
+macro_rules! real_or_complex_unary {
+    ($name:ident, $real:ident, $complex_method:ident) => {
+        #[doc = concat!("`", stringify!($name), "`: real inputs lift to `f64`, `Complex` inputs dispatch to `num_complex`.")]
+        pub fn $name(args: &[Value]) -> Result<Value> {
+            match &args[0] {
+                Value::Complex(c) => Ok(Value::Complex(c.$complex_method())),
+                other => to_f64(other)
+                    .map(|f| Value::Float(f.$real()))
+                    .ok_or_else(|| JtvError::TypeError(format!(
+                        "{} requires a numeric argument", stringify!($name)
+                    ))),
             }
-            Ok(Value::Float(f.sqrt()))
         }
-        _ => Err(JtvError::TypeError("sqrt requires a numeric argument".to_string())),
+    };
+}
+
+real_or_complex_unary!(sin, sin, sin);
+real_or_complex_unary!(cos, cos, cos);
+real_or_complex_unary!(tan, tan, tan);
+real_or_complex_unary!(asin, asin, asin);
+real_or_complex_unary!(acos, acos, acos);
+real_or_complex_unary!(atan, atan, atan);
+real_or_complex_unary!(sinh, sinh, sinh);
+real_or_complex_unary!(cosh, cosh, cosh);
+real_or_complex_unary!(tanh, tanh, tanh);
+real_or_complex_unary!(exp, exp, exp);
+real_or_complex_unary!(ln, ln, ln);
+
+/// Base-10 logarithm
+pub fn log10(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Complex(c) => Ok(Value::Complex(c.ln() / 10.0_f64.ln())),
+        other => to_f64(other)
+            .map(|f| Value::Float(f.log10()))
+            .ok_or_else(|| JtvError::TypeError("log10 requires a numeric argument".to_string())),
+    }
+}
+
+/// Logarithm of `args[0]` in base `args[1]`
+pub fn log(args: &[Value]) -> Result<Value> {
+    let base = to_f64(&args[1])
+        .ok_or_else(|| JtvError::TypeError("log requires a numeric base".to_string()))?;
+    match &args[0] {
+        Value::Complex(c) => Ok(Value::Complex(c.ln() / base.ln())),
+        other => to_f64(other)
+            .map(|f| Value::Float(f.log(base)))
+            .ok_or_else(|| JtvError::TypeError("log requires a numeric argument".to_string())),
+    }
+}
+
+/// Two-argument arctangent, `atan2(y, x)`
+pub fn atan2(args: &[Value]) -> Result<Value> {
+    let y = to_f64(&args[0])
+        .ok_or_else(|| JtvError::TypeError("atan2 requires numeric arguments".to_string()))?;
+    let x = to_f64(&args[1])
+        .ok_or_else(|| JtvError::TypeError("atan2 requires numeric arguments".to_string()))?;
+    Ok(Value::Float(y.atan2(x)))
+}
+
+/// Real part of a `Complex` (or the value itself, for real inputs)
+pub fn re(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Complex(c) => Ok(Value::Float(c.re)),
+        other => to_f64(other)
+            .map(Value::Float)
+            .ok_or_else(|| JtvError::TypeError("re requires a numeric argument".to_string())),
+    }
+}
+
+/// Imaginary part of a `Complex` (zero for real inputs)
+pub fn im(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Complex(c) => Ok(Value::Float(c.im)),
+        other if to_f64(other).is_some() => Ok(Value::Float(0.0)),
+        _ => Err(JtvError::TypeError("im requires a numeric argument".to_string())),
+    }
+}
+
+/// Complex conjugate (identity for real inputs)
+pub fn conj(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Complex(c) => Ok(Value::Complex(c.conj())),
+        other if to_f64(other).is_some() => Ok(other.clone()),
+        _ => Err(JtvError::TypeError("conj requires a numeric argument".to_string())),
+    }
+}
+
+/// Argument (phase angle) of a `Complex` number
+pub fn arg(args: &[Value]) -> Result<Value> {
+    to_complex(&args[0])
+        .map(|c| Value::Float(c.arg()))
+        .ok_or_else(|| JtvError::TypeError("arg requires a numeric argument".to_string()))
+}
+
+/// Euclidean norm (modulus) of a `Complex` number
+pub fn norm(args: &[Value]) -> Result<Value> {
+    to_complex(&args[0])
+        .map(|c| Value::Float(c.norm()))
+        .ok_or_else(|| JtvError::TypeError("norm requires a numeric argument".to_string()))
+}
+
+/// Squared Euclidean norm of a `Complex` number
+pub fn norm_sq(args: &[Value]) -> Result<Value> {
+    to_complex(&args[0])
+        .map(|c| Value::Float(c.norm_sqr()))
+        .ok_or_else(|| JtvError::TypeError("normSq requires a numeric argument".to_string()))
+}
+
+/// Archimedes' constant, pi
+pub fn pi(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(std::f64::consts::PI))
+}
+
+/// Euler's number, e
+pub fn e(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(std::f64::consts::E))
+}
+
+/// tau = 2*pi
+pub fn tau(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(std::f64::consts::TAU))
+}
+
+/// The golden ratio, phi
+pub fn phi(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(1.618_033_988_749_895))
+}
+
+/// The Euler-Mascheroni constant
+pub fn egamma(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(0.577_215_664_901_532_9))
+}
+
+/// Numerator of a `Rational` (or the `Int` itself, treated as over 1)
+pub fn numer(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Rational(r) => Ok(Value::Int(*r.numer())),
+        Value::Int(n) | Value::Hex(n) | Value::Binary(n) => Ok(Value::Int(*n)),
+        _ => Err(JtvError::TypeError("numer requires a Rational argument".to_string())),
+    }
+}
+
+/// Denominator of a `Rational` (1 for an `Int`)
+pub fn denom(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Rational(r) => Ok(Value::Int(*r.denom())),
+        Value::Int(_) | Value::Hex(_) | Value::Binary(_) => Ok(Value::Int(1)),
+        _ => Err(JtvError::TypeError("denom requires a Rational argument".to_string())),
+    }
+}
+
+/// Recover the simplest exact `Rational` within `eps` of a `Float`, via the
+/// continued-fraction convergent recurrence: `a = floor(t)`, convergents
+/// `h = a*h_prev + h_prev2`, `k = a*k_prev + k_prev2` (seeded `h=[0,1]`,
+/// `k=[1,0]`), then `t = 1/(t - a)`, stopping when `|h/k - x| <= eps*|x|`,
+/// `k` would exceed `max_denominator`, or `t`'s fractional part vanishes.
+pub fn rationalize(args: &[Value]) -> Result<Value> {
+    let x = to_f64(&args[0])
+        .ok_or_else(|| JtvError::TypeError("rationalize requires a numeric argument".to_string()))?;
+    let eps = if args.len() > 1 {
+        to_f64(&args[1])
+            .ok_or_else(|| JtvError::TypeError("rationalize epsilon must be numeric".to_string()))?
+    } else {
+        1e-10
+    };
+    rationalize_impl(x, eps, 1_000_000_000)
+}
+
+fn rationalize_impl(x: f64, eps: f64, max_denominator: i64) -> Result<Value> {
+    if x.is_nan() || x.is_infinite() {
+        return Err(JtvError::RuntimeError("rationalize requires a finite number".to_string()));
+    }
+    let sign = if x < 0.0 { -1 } else { 1 };
+    let x = x.abs();
+    if x.fract() == 0.0 {
+        return Ok(Value::Int(sign * x as i64));
+    }
+
+    let (mut h_prev2, mut h_prev1) = (0i64, 1i64);
+    let (mut k_prev2, mut k_prev1) = (1i64, 0i64);
+    let mut t = x;
+
+    loop {
+        let a = t.floor() as i64;
+        let h = a.saturating_mul(h_prev1).saturating_add(h_prev2);
+        let k = a.saturating_mul(k_prev1).saturating_add(k_prev2);
+
+        if k == 0 || k.abs() > max_denominator {
+            break Ok(Value::Rational(num_rational::Ratio::new(sign * h_prev1, k_prev1)));
+        }
+
+        let convergent = h as f64 / k as f64;
+        if (convergent - x).abs() <= eps * x {
+            break Ok(Value::Rational(num_rational::Ratio::new(sign * h, k)));
+        }
+
+        let frac = t - t.floor();
+        if frac.abs() < 1e-15 {
+            break Ok(Value::Rational(num_rational::Ratio::new(sign * h, k)));
+        }
+
+        h_prev2 = h_prev1;
+        h_prev1 = h;
+        k_prev2 = k_prev1;
+        k_prev1 = k;
+        t = 1.0 / frac;
+    }
+}
+
+/// Positive infinity
+pub fn inf(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(f64::INFINITY))
+}
+
+/// Not-a-number
+pub fn nan(_args: &[Value]) -> Result<Value> {
+    Ok(Value::Float(f64::NAN))
+}
+
+/// Classify a `Float`/`Complex` value: `"nan"`, `"infinite"`, `"zero"`,
+/// `"subnormal"`, or `"normal"`.
+pub fn classify(args: &[Value]) -> Result<Value> {
+    let tag = match &args[0] {
+        Value::Float(f) => classify_f64(*f),
+        Value::Complex(c) => {
+            if c.re.is_nan() || c.im.is_nan() {
+                "nan"
+            } else if c.re.is_infinite() || c.im.is_infinite() {
+                "infinite"
+            } else if c.re == 0.0 && c.im == 0.0 {
+                "zero"
+            } else if c.re.classify() == std::num::FpCategory::Subnormal
+                || c.im.classify() == std::num::FpCategory::Subnormal
+            {
+                "subnormal"
+            } else {
+                "normal"
+            }
+        }
+        _ => return Err(JtvError::TypeError("classify requires a Float or Complex argument".to_string())),
+    };
+    Ok(Value::Symbolic(tag.to_string()))
+}
+
+fn classify_f64(f: f64) -> &'static str {
+    use std::num::FpCategory;
+    match f.classify() {
+        FpCategory::Nan => "nan",
+        FpCategory::Infinite => "infinite",
+        FpCategory::Zero => "zero",
+        FpCategory::Subnormal => "subnormal",
+        FpCategory::Normal => "normal",
+    }
+}
+
+/// `true` if the value is a NaN `Float` or a `Complex` with a NaN component
+pub fn is_nan(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Float(f) => Ok(Value::Bool(f.is_nan())),
+        Value::Complex(c) => Ok(Value::Bool(c.re.is_nan() || c.im.is_nan())),
+        _ => Err(JtvError::TypeError("isNan requires a Float or Complex argument".to_string())),
+    }
+}
+
+/// `true` if the value is a finite `Float`, or a `Complex` with finite components
+pub fn is_finite(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Float(f) => Ok(Value::Bool(f.is_finite())),
+        Value::Complex(c) => Ok(Value::Bool(c.re.is_finite() && c.im.is_finite())),
+        _ => Err(JtvError::TypeError("isFinite requires a Float or Complex argument".to_string())),
     }
 }
 
@@ -255,10 +622,118 @@ mod tests {
         assert_eq!(factorial(&[Value::Int(0)]).unwrap(), Value::Int(1));
     }
 
+    #[test]
+    fn test_factorial_promotes_to_bigint() {
+        // 21! overflows i64 (max factorial that fits is 20!)
+        let result = factorial(&[Value::Int(21)]).unwrap();
+        assert_eq!(result, Value::BigInt("51090942171709440000".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_pow_promotes_to_bigint() {
+        // 2^100 overflows i64
+        let result = pow(&[Value::Int(2), Value::Int(100)]).unwrap();
+        let expected: BigInt = "1267650600228229401496703205376".parse().unwrap();
+        assert_eq!(result, Value::BigInt(expected));
+    }
+
+    #[test]
+    fn test_factorial_checked_mode_errors_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Checked,
+            ..EvalOptions::default()
+        };
+        let result = factorial_with(&[Value::Int(21)], &opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_pow_checked_mode_errors_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Checked,
+            ..EvalOptions::default()
+        };
+        let result = pow_with(&[Value::Int(2), Value::Int(100)], &opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_pow_rejects_an_exponent_too_large_to_fit_a_u32() {
+        // An exponent this large would previously be truncated by `as u32`
+        // (4294967297 wraps to 1), returning a silently wrong `Int(2)`
+        // instead of erroring or computing the real (astronomically large)
+        // result.
+        let result = pow(&[Value::Int(2), Value::Int(i64::from(u32::MAX) + 2)]);
+        assert!(matches!(result, Err(JtvError::RuntimeError(_))));
+    }
+
     #[test]
     fn test_is_prime() {
         assert_eq!(is_prime(&[Value::Int(7)]).unwrap(), Value::Bool(true));
         assert_eq!(is_prime(&[Value::Int(8)]).unwrap(), Value::Bool(false));
         assert_eq!(is_prime(&[Value::Int(2)]).unwrap(), Value::Bool(true));
     }
+
+    #[test]
+    fn test_sqrt_of_negative_returns_complex() {
+        let result = sqrt(&[Value::Int(-4)]).unwrap();
+        assert_eq!(result, Value::Complex(Complex64::new(0.0, 2.0)));
+    }
+
+    #[test]
+    fn test_sin_cos_accept_int() {
+        let result = sin(&[Value::Int(0)]).unwrap();
+        assert_eq!(result, Value::Float(0.0));
+        let result = cos(&[Value::Int(0)]).unwrap();
+        assert_eq!(result, Value::Float(1.0));
+    }
+
+    #[test]
+    fn test_complex_accessors() {
+        let c = Value::Complex(Complex64::new(3.0, 4.0));
+        assert_eq!(re(&[c.clone()]).unwrap(), Value::Float(3.0));
+        assert_eq!(im(&[c.clone()]).unwrap(), Value::Float(4.0));
+        assert_eq!(norm(&[c.clone()]).unwrap(), Value::Float(5.0));
+        assert_eq!(conj(&[c]).unwrap(), Value::Complex(Complex64::new(3.0, -4.0)));
+    }
+
+    #[test]
+    fn test_named_constants() {
+        assert_eq!(pi(&[]).unwrap(), Value::Float(std::f64::consts::PI));
+        assert_eq!(tau(&[]).unwrap(), Value::Float(std::f64::consts::TAU));
+    }
+
+    #[test]
+    fn test_rationalize_recovers_simple_fraction() {
+        let result = rationalize(&[Value::Float(0.3333333333333333)]).unwrap();
+        assert_eq!(result, Value::Rational(num_rational::Ratio::new(1, 3)));
+    }
+
+    #[test]
+    fn test_rationalize_integral_float() {
+        let result = rationalize(&[Value::Float(4.0)]).unwrap();
+        assert_eq!(result, Value::Int(4));
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(&[Value::Float(f64::NAN)]).unwrap(), Value::Symbolic("nan".to_string()));
+        assert_eq!(classify(&[Value::Float(f64::INFINITY)]).unwrap(), Value::Symbolic("infinite".to_string()));
+        assert_eq!(classify(&[Value::Float(0.0)]).unwrap(), Value::Symbolic("zero".to_string()));
+        assert_eq!(classify(&[Value::Float(1.0)]).unwrap(), Value::Symbolic("normal".to_string()));
+    }
+
+    #[test]
+    fn test_is_nan_is_finite() {
+        assert_eq!(is_nan(&[Value::Float(f64::NAN)]).unwrap(), Value::Bool(true));
+        assert_eq!(is_finite(&[Value::Float(1.0)]).unwrap(), Value::Bool(true));
+        assert_eq!(is_finite(&[Value::Float(f64::INFINITY)]).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_numer_denom() {
+        let r = Value::Rational(num_rational::Ratio::new(3, 4));
+        assert_eq!(numer(&[r.clone()]).unwrap(), Value::Int(3));
+        assert_eq!(denom(&[r]).unwrap(), Value::Int(4));
+    }
 }
@@ -10,6 +10,7 @@
 
 pub mod number_systems;
 pub mod reversible;
+pub mod sym;
 
 pub use number_systems::*;
 pub use reversible::*;
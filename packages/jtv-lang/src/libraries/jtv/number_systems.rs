@@ -7,8 +7,10 @@
 
 use crate::number::Value;
 use crate::error::{JtvError, Result};
+use num_bigint::BigInt;
 use num_rational::Ratio;
 use num_complex::Complex64;
+use num_traits::ToPrimitive;
 
 // ===== RATIONAL NUMBER OPERATIONS =====
 
@@ -30,6 +32,9 @@ pub fn numerator(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Rational(r) => Ok(Value::Int(*r.numer())),
         Value::Int(n) => Ok(Value::Int(*n)),
+        // Already an integer, so it's its own numerator -- no precision is
+        // lost the way `to_int`/`to_float` can lose it.
+        Value::BigInt(n) => Ok(Value::normalize_bigint(n.clone())),
         _ => Err(JtvError::TypeError("numerator requires a rational".to_string())),
     }
 }
@@ -38,7 +43,7 @@ pub fn numerator(args: &[Value]) -> Result<Value> {
 pub fn denominator(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Rational(r) => Ok(Value::Int(*r.denom())),
-        Value::Int(_) => Ok(Value::Int(1)),
+        Value::Int(_) | Value::BigInt(_) => Ok(Value::Int(1)),
         _ => Err(JtvError::TypeError("denominator requires a rational".to_string())),
     }
 }
@@ -113,6 +118,73 @@ pub fn conjugate(args: &[Value]) -> Result<Value> {
     }
 }
 
+/// Promote a numeric value to `Complex64` -- `Int`/`Float` become a real
+/// number with zero imaginary part, same as `complex`'s arguments.
+fn as_complex(value: &Value, fn_name: &str) -> Result<Complex64> {
+    match value {
+        Value::Complex(c) => Ok(*c),
+        Value::Int(n) => Ok(Complex64::new(*n as f64, 0.0)),
+        Value::Float(f) => Ok(Complex64::new(*f, 0.0)),
+        other => Err(JtvError::TypeError(format!(
+            "{} requires a Complex/Int/Float argument, got {}",
+            fn_name, other
+        ))),
+    }
+}
+
+/// `cexp(z)` -- complex exponential, via `num_complex`'s own `exp`.
+pub fn cexp(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "cexp")?.exp()))
+}
+
+/// `cln(z)` -- principal branch of the complex natural logarithm.
+pub fn cln(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "cln")?.ln()))
+}
+
+/// `csqrt(z)` -- principal branch of the complex square root.
+pub fn csqrt(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "csqrt")?.sqrt()))
+}
+
+/// `cpow(base, exp)` -- complex exponentiation, principal branch.
+pub fn cpow(args: &[Value]) -> Result<Value> {
+    let base = as_complex(&args[0], "cpow")?;
+    let exp = as_complex(&args[1], "cpow")?;
+    Ok(Value::Complex(base.powc(exp)))
+}
+
+/// `csin(z)` -- complex sine.
+pub fn csin(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "csin")?.sin()))
+}
+
+/// `ccos(z)` -- complex cosine.
+pub fn ccos(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "ccos")?.cos()))
+}
+
+/// `ctan(z)` -- complex tangent.
+pub fn ctan(args: &[Value]) -> Result<Value> {
+    Ok(Value::Complex(as_complex(&args[0], "ctan")?.tan()))
+}
+
+/// `fromPolar(r, theta)` -- the inverse of `magnitude`/`phase`: builds the
+/// complex number with modulus `r` and argument `theta`.
+pub fn from_polar(args: &[Value]) -> Result<Value> {
+    let r = match &args[0] {
+        Value::Int(n) => *n as f64,
+        Value::Float(f) => *f,
+        other => return Err(JtvError::TypeError(format!("fromPolar requires numeric arguments, got {}", other))),
+    };
+    let theta = match &args[1] {
+        Value::Int(n) => *n as f64,
+        Value::Float(f) => *f,
+        other => return Err(JtvError::TypeError(format!("fromPolar requires numeric arguments, got {}", other))),
+    };
+    Ok(Value::Complex(Complex64::from_polar(r, theta)))
+}
+
 // ===== HEX AND BINARY OPERATIONS =====
 
 /// Convert integer to hex representation
@@ -143,6 +215,9 @@ pub fn to_int(args: &[Value]) -> Result<Value> {
         Value::Hex(n) => Ok(Value::Int(*n)),
         Value::Binary(n) => Ok(Value::Int(*n)),
         Value::Rational(r) => Ok(Value::Int((*r.numer()) / (*r.denom()))),
+        // Already integral -- demoted back to `Int` when it fits, same as
+        // every other place a `BigInt` result gets normalized.
+        Value::BigInt(n) => Ok(Value::normalize_bigint(n.clone())),
         _ => Err(JtvError::TypeError("toInt requires a numeric value".to_string())),
     }
 }
@@ -155,6 +230,12 @@ pub fn to_float(args: &[Value]) -> Result<Value> {
         Value::Hex(n) => Ok(Value::Float(*n as f64)),
         Value::Binary(n) => Ok(Value::Float(*n as f64)),
         Value::Rational(r) => Ok(Value::Float(*r.numer() as f64 / *r.denom() as f64)),
+        // `BigInt::to_f64` only fails for a magnitude so large it can't
+        // even be approximated as `f64` (not merely rounded) -- report that
+        // clearly instead of silently truncating or panicking.
+        Value::BigInt(n) => n.to_f64().map(Value::Float).ok_or_else(|| {
+            JtvError::TypeError(format!("{} is too large to convert to Float", n))
+        }),
         _ => Err(JtvError::TypeError("toFloat requires a numeric value".to_string())),
     }
 }
@@ -178,6 +259,78 @@ pub fn is_symbolic(args: &[Value]) -> Result<Value> {
     }
 }
 
+fn as_symbolic(value: &Value, fn_name: &str) -> Result<super::sym::Sym> {
+    match value {
+        Value::Symbolic(s) => super::sym::parse(s),
+        other => Err(JtvError::TypeError(format!(
+            "{} requires a Symbolic argument, got {}",
+            fn_name, other
+        ))),
+    }
+}
+
+/// `simplify(expr)` -- fold numeric constants, drop `+0`/`*1`, collapse
+/// `*0` to `0`, and flatten nested `Add`/`Mul` chains.
+pub fn simplify(args: &[Value]) -> Result<Value> {
+    let expr = as_symbolic(&args[0], "simplify")?;
+    Ok(Value::Symbolic(expr.simplify().to_string()))
+}
+
+/// `expand(expr)` -- distribute multiplication over addition.
+pub fn expand(args: &[Value]) -> Result<Value> {
+    let expr = as_symbolic(&args[0], "expand")?;
+    Ok(Value::Symbolic(expr.expand().to_string()))
+}
+
+/// `differentiate(expr, var)` -- apply the standard differentiation rules
+/// with respect to the named variable.
+pub fn differentiate(args: &[Value]) -> Result<Value> {
+    let expr = as_symbolic(&args[0], "differentiate")?;
+    let var = match &args[1] {
+        Value::String(s) => s.clone(),
+        other => return Err(JtvError::TypeError(format!(
+            "differentiate requires a String variable name, got {}",
+            other
+        ))),
+    };
+    let derivative = expr.differentiate(&var)?.simplify();
+    Ok(Value::Symbolic(derivative.to_string()))
+}
+
+/// `substitute(expr, var, value)` -- replace every occurrence of `var`
+/// with `value`, reducing the result to `Int`/`Float` if it is fully
+/// ground (no variables left).
+pub fn substitute(args: &[Value]) -> Result<Value> {
+    let expr = as_symbolic(&args[0], "substitute")?;
+    let var = match &args[1] {
+        Value::String(s) => s.clone(),
+        other => return Err(JtvError::TypeError(format!(
+            "substitute requires a String variable name, got {}",
+            other
+        ))),
+    };
+    let replacement = match &args[2] {
+        Value::Int(n) => super::sym::Sym::Num(*n as f64),
+        Value::Float(f) => super::sym::Sym::Num(*f),
+        Value::Rational(r) => super::sym::Sym::Num(*r.numer() as f64 / *r.denom() as f64),
+        Value::Symbolic(s) => super::sym::parse(s)?,
+        other => return Err(JtvError::TypeError(format!(
+            "substitute requires a numeric or Symbolic replacement value, got {}",
+            other
+        ))),
+    };
+    let result = expr.substitute(&var, &replacement).simplify();
+    if result.is_ground() {
+        if let Some(n) = result.as_num() {
+            if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+                return Ok(Value::Int(n as i64));
+            }
+            return Ok(Value::Float(n));
+        }
+    }
+    Ok(Value::Symbolic(result.to_string()))
+}
+
 // ===== TYPE CHECKING =====
 
 /// Check if value is a rational number
@@ -192,7 +345,7 @@ pub fn is_complex(args: &[Value]) -> Result<Value> {
 
 /// Check if value is an integer
 pub fn is_int(args: &[Value]) -> Result<Value> {
-    Ok(Value::Bool(matches!(&args[0], Value::Int(_))))
+    Ok(Value::Bool(matches!(&args[0], Value::Int(_) | Value::BigInt(_))))
 }
 
 /// Check if value is a float
@@ -204,6 +357,13 @@ pub fn is_float(args: &[Value]) -> Result<Value> {
 pub fn type_of(args: &[Value]) -> Result<Value> {
     let type_name = match &args[0] {
         Value::Int(_) => "Int",
+        Value::BigInt(_) => "BigInt",
+        Value::Struct(..) => "Struct",
+        Value::Closure(_) => "Closure",
+        Value::Builtin(_) => "Builtin",
+        Value::PartialApp { .. } => "PartialApp",
+        Value::Iterator(_) => "Iterator",
+        Value::Qubits(_) => "Qubits",
         Value::Float(_) => "Float",
         Value::Rational(_) => "Rational",
         Value::Complex(_) => "Complex",
@@ -255,5 +415,152 @@ mod tests {
     fn test_type_of() {
         assert_eq!(type_of(&[Value::Int(5)]).unwrap(), Value::String("Int".to_string()));
         assert_eq!(type_of(&[Value::Float(3.14)]).unwrap(), Value::String("Float".to_string()));
+        assert_eq!(
+            type_of(&[Value::BigInt(BigInt::from(u64::MAX))]).unwrap(),
+            Value::String("BigInt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_numerator_denominator_of_big_int() {
+        let n = Value::BigInt(BigInt::from(u64::MAX));
+        assert_eq!(numerator(&[n.clone()]).unwrap(), n);
+        assert_eq!(denominator(&[n]).unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    fn test_to_int_normalizes_big_int_that_fits_back_to_int() {
+        let small = Value::BigInt(BigInt::from(42));
+        assert_eq!(to_int(&[small]).unwrap(), Value::Int(42));
+    }
+
+    #[test]
+    fn test_to_float_converts_big_int_lossily() {
+        let n = Value::BigInt(BigInt::from(u64::MAX));
+        let f = to_float(&[n]).unwrap();
+        assert!(matches!(f, Value::Float(x) if (x - u64::MAX as f64).abs() < 1.0));
+    }
+
+    #[test]
+    fn test_is_int_accepts_big_int() {
+        assert_eq!(is_int(&[Value::BigInt(BigInt::from(u64::MAX))]).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_simplify_builtin_folds_constants() {
+        let expr = Value::Symbolic("x * 1 + 0 + 2 + 3".to_string());
+        assert_eq!(simplify(&[expr]).unwrap(), Value::Symbolic("5 + x".to_string()));
+    }
+
+    #[test]
+    fn test_expand_builtin_distributes() {
+        let expr = Value::Symbolic("x * (y + 1)".to_string());
+        assert_eq!(expand(&[expr]).unwrap(), Value::Symbolic("x * y + x".to_string()));
+    }
+
+    #[test]
+    fn test_differentiate_builtin_power_rule() {
+        let expr = Value::Symbolic("x ^ 2".to_string());
+        let var = Value::String("x".to_string());
+        assert_eq!(
+            differentiate(&[expr, var]).unwrap(),
+            Value::Symbolic("2 * x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_substitute_builtin_reduces_ground_result_to_int() {
+        let expr = Value::Symbolic("x + 1".to_string());
+        let var = Value::String("x".to_string());
+        let value = Value::Int(5);
+        assert_eq!(substitute(&[expr, var, value]).unwrap(), Value::Int(6));
+    }
+
+    #[test]
+    fn test_substitute_builtin_leaves_non_ground_result_symbolic() {
+        let expr = Value::Symbolic("x + y".to_string());
+        let var = Value::String("x".to_string());
+        let value = Value::Int(5);
+        assert_eq!(
+            substitute(&[expr, var, value]).unwrap(),
+            Value::Symbolic("5 + y".to_string())
+        );
+    }
+
+    #[test]
+    fn test_symbolic_builtins_reject_non_symbolic_argument() {
+        assert!(simplify(&[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_cexp_of_i_pi_is_negative_one() {
+        let z = Value::Complex(Complex64::new(0.0, std::f64::consts::PI));
+        let result = cexp(&[z]).unwrap();
+        if let Value::Complex(c) = result {
+            assert!((c.re - (-1.0)).abs() < 1e-9);
+            assert!(c.im.abs() < 1e-9);
+        } else {
+            panic!("Expected Complex");
+        }
+    }
+
+    #[test]
+    fn test_cln_is_inverse_of_cexp() {
+        let z = Complex64::new(1.5, -2.0);
+        let result = cln(&[Value::Complex(z.exp())]).unwrap();
+        if let Value::Complex(c) = result {
+            assert!((c.re - z.re).abs() < 1e-9);
+            assert!((c.im - z.im).abs() < 1e-9);
+        } else {
+            panic!("Expected Complex");
+        }
+    }
+
+    #[test]
+    fn test_csqrt_of_negative_one_is_i() {
+        let result = csqrt(&[Value::Int(-1)]).unwrap();
+        if let Value::Complex(c) = result {
+            assert!(c.re.abs() < 1e-9);
+            assert!((c.im - 1.0).abs() < 1e-9);
+        } else {
+            panic!("Expected Complex");
+        }
+    }
+
+    #[test]
+    fn test_cpow_promotes_real_arguments() {
+        let result = cpow(&[Value::Int(2), Value::Int(3)]).unwrap();
+        if let Value::Complex(c) = result {
+            assert!((c.re - 8.0).abs() < 1e-9);
+            assert!(c.im.abs() < 1e-9);
+        } else {
+            panic!("Expected Complex");
+        }
+    }
+
+    #[test]
+    fn test_complex_trig_functions_accept_real_input() {
+        for f in [csin, ccos, ctan] {
+            assert!(matches!(f(&[Value::Float(0.5)]).unwrap(), Value::Complex(_)));
+        }
+    }
+
+    #[test]
+    fn test_from_polar_is_inverse_of_magnitude_and_phase() {
+        let z = Value::Complex(Complex64::new(3.0, 4.0));
+        let mag = magnitude(&[z.clone()]).unwrap();
+        let ph = phase(&[z.clone()]).unwrap();
+        let rebuilt = from_polar(&[mag, ph]).unwrap();
+        if let Value::Complex(c) = rebuilt {
+            assert!((c.re - 3.0).abs() < 1e-9);
+            assert!((c.im - 4.0).abs() < 1e-9);
+        } else {
+            panic!("Expected Complex");
+        }
+    }
+
+    #[test]
+    fn test_complex_builtins_reject_non_numeric_argument() {
+        assert!(cexp(&[Value::Bool(true)]).is_err());
     }
 }
@@ -8,16 +8,30 @@
 // - Quantum simulation primitives
 
 use crate::number::Value;
+use crate::pvector::PVector;
 use crate::error::{JtvError, Result};
+use num_bigint::BigInt;
+use num_complex::Complex64;
 
 // ===== INVERSE OPERATIONS =====
 // These functions provide explicit inverses for addition-only arithmetic
 
 /// Explicit subtraction (inverse of addition)
 /// In JtV, subtraction is addition of negation: a - b = a + (-b)
+///
+/// `Int - Int` goes through `checked_sub` rather than `-`, promoting to
+/// `Value::BigInt` on overflow instead of erroring: reversible computing
+/// depends on exact invertibility (`a + b` then `a + b - b` must always
+/// recover `a`), which a fixed `i64` range cannot guarantee on its own.
 pub fn subtract(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+        (Value::Int(a), Value::Int(b)) => match a.checked_sub(*b) {
+            Some(r) => Ok(Value::Int(r)),
+            None => Ok(Value::normalize_bigint(BigInt::from(*a) - BigInt::from(*b))),
+        },
+        (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::normalize_bigint(a - b)),
+        (Value::BigInt(a), Value::Int(b)) => Ok(Value::normalize_bigint(a - BigInt::from(*b))),
+        (Value::Int(a), Value::BigInt(b)) => Ok(Value::normalize_bigint(BigInt::from(*a) - b)),
         (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
         (Value::Int(a), Value::Float(b)) => Ok(Value::Float(*a as f64 - b)),
         (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a - *b as f64)),
@@ -25,19 +39,29 @@ pub fn subtract(args: &[Value]) -> Result<Value> {
     }
 }
 
-/// Increment by 1 (commonly used in reversible computing)
+/// Increment by 1 (commonly used in reversible computing), promoting to
+/// `Value::BigInt` on overflow.
 pub fn increment(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::Int(n) => Ok(Value::Int(n + 1)),
+        Value::Int(n) => match n.checked_add(1) {
+            Some(r) => Ok(Value::Int(r)),
+            None => Ok(Value::normalize_bigint(BigInt::from(*n) + 1)),
+        },
+        Value::BigInt(n) => Ok(Value::normalize_bigint(n + 1)),
         Value::Float(f) => Ok(Value::Float(f + 1.0)),
         _ => Err(JtvError::TypeError("increment requires a numeric argument".to_string())),
     }
 }
 
-/// Decrement by 1 (inverse of increment)
+/// Decrement by 1 (inverse of increment), promoting to `Value::BigInt` on
+/// overflow so it stays an exact inverse across the full `BigInt` range.
 pub fn decrement(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::Int(n) => Ok(Value::Int(n - 1)),
+        Value::Int(n) => match n.checked_sub(1) {
+            Some(r) => Ok(Value::Int(r)),
+            None => Ok(Value::normalize_bigint(BigInt::from(*n) - 1)),
+        },
+        Value::BigInt(n) => Ok(Value::normalize_bigint(n - 1)),
         Value::Float(f) => Ok(Value::Float(f - 1.0)),
         _ => Err(JtvError::TypeError("decrement requires a numeric argument".to_string())),
     }
@@ -56,17 +80,29 @@ pub fn swap(args: &[Value]) -> Result<Value> {
 }
 
 /// Controlled NOT (CNOT) - quantum-inspired operation
-/// If control is truthy, negate the target
+/// If control is truthy, negate the target. Like `additive_inverse`, the
+/// `Int` case promotes to `Value::BigInt` on the one value `checked_neg`
+/// can't handle (`i64::MIN`), so the gate stays self-inverse everywhere.
 pub fn cnot(args: &[Value]) -> Result<Value> {
     let control = is_truthy(&args[0]);
     match &args[1] {
         Value::Int(n) => {
             if control {
-                Ok(Value::Int(-n))
+                match n.checked_neg() {
+                    Some(r) => Ok(Value::Int(r)),
+                    None => Ok(Value::normalize_bigint(-BigInt::from(*n))),
+                }
             } else {
                 Ok(Value::Int(*n))
             }
         }
+        Value::BigInt(n) => {
+            if control {
+                Ok(Value::normalize_bigint(-n))
+            } else {
+                Ok(Value::BigInt(n.clone()))
+            }
+        }
         Value::Bool(b) => {
             if control {
                 Ok(Value::Bool(!b))
@@ -78,6 +114,48 @@ pub fn cnot(args: &[Value]) -> Result<Value> {
     }
 }
 
+/// Toffoli (CCNOT) - if both controls are truthy, negate the target. Same
+/// `i64::MIN` promotion as `cnot` above.
+pub fn toffoli(args: &[Value]) -> Result<Value> {
+    let control = is_truthy(&args[0]) && is_truthy(&args[1]);
+    match &args[2] {
+        Value::Int(n) => {
+            if control {
+                match n.checked_neg() {
+                    Some(r) => Ok(Value::Int(r)),
+                    None => Ok(Value::normalize_bigint(-BigInt::from(*n))),
+                }
+            } else {
+                Ok(Value::Int(*n))
+            }
+        }
+        Value::BigInt(n) => {
+            if control {
+                Ok(Value::normalize_bigint(-n))
+            } else {
+                Ok(Value::BigInt(n.clone()))
+            }
+        }
+        Value::Bool(b) => {
+            if control {
+                Ok(Value::Bool(!b))
+            } else {
+                Ok(Value::Bool(*b))
+            }
+        }
+        _ => Err(JtvError::TypeError("toffoli target must be Int or Bool".to_string())),
+    }
+}
+
+/// Fredkin (CSWAP) - if control is truthy, swap the two operands
+pub fn fredkin(args: &[Value]) -> Result<Value> {
+    if is_truthy(&args[0]) {
+        Ok(Value::Tuple(vec![args[2].clone(), args[1].clone()]))
+    } else {
+        Ok(Value::Tuple(vec![args[1].clone(), args[2].clone()]))
+    }
+}
+
 // ===== REVERSIBILITY HELPERS =====
 
 /// Check if a value can be inverted
@@ -91,10 +169,16 @@ pub fn is_invertible(args: &[Value]) -> Result<Value> {
     }
 }
 
-/// Get the additive inverse of a value
+/// Get the additive inverse of a value. Total over all integers: `Int`
+/// promotes to `Value::BigInt` on the one case `checked_neg` can't handle
+/// (`-i64::MIN`), rather than erroring.
 pub fn additive_inverse(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::Int(n) => Ok(Value::Int(-n)),
+        Value::Int(n) => match n.checked_neg() {
+            Some(r) => Ok(Value::Int(r)),
+            None => Ok(Value::normalize_bigint(-BigInt::from(*n))),
+        },
+        Value::BigInt(n) => Ok(Value::normalize_bigint(-n)),
         Value::Float(f) => Ok(Value::Float(-f)),
         Value::Rational(r) => Ok(Value::Rational(-r)),
         Value::Complex(c) => Ok(Value::Complex(-c)),
@@ -106,11 +190,70 @@ pub fn additive_inverse(args: &[Value]) -> Result<Value> {
 pub fn xor(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a ^ b)),
+        (Value::BigInt(a), Value::BigInt(b)) => Ok(Value::normalize_bigint(a ^ b)),
+        (Value::BigInt(a), Value::Int(b)) => Ok(Value::normalize_bigint(a ^ BigInt::from(*b))),
+        (Value::Int(a), Value::BigInt(b)) => Ok(Value::normalize_bigint(BigInt::from(*a) ^ b)),
         (Value::Bool(a), Value::Bool(b)) => Ok(Value::Bool(*a ^ *b)),
         _ => Err(JtvError::TypeError("xor requires integer or bool arguments".to_string())),
     }
 }
 
+// ===== PROPERTY-BASED REVERSIBILITY CHECKS =====
+// Calculational-proof-style verification: rather than hand-writing one
+// round-trip assertion per interesting input, generate a domain of inputs
+// (including the usual edge cases -- zero, negative, boundary integers)
+// and check `inverse(forward(x)) == x` for every one of them, reporting
+// the first `x` that breaks invertibility.
+
+/// A representative `Value::Int` domain to probe invertibility over,
+/// seeded with the edge cases that actually break naive arithmetic: zero,
+/// negation, and the `i64` boundaries.
+pub fn int_domain() -> Vec<Value> {
+    vec![
+        Value::Int(0),
+        Value::Int(1),
+        Value::Int(-1),
+        Value::Int(42),
+        Value::Int(-42),
+        Value::Int(i64::MAX),
+        Value::Int(i64::MIN),
+    ]
+}
+
+/// `int_domain` plus a few representative `Value::Float`s, for operations
+/// that accept both.
+pub fn numeric_domain() -> Vec<Value> {
+    let mut domain = int_domain();
+    domain.extend([Value::Float(0.0), Value::Float(-1.5), Value::Float(3.25)]);
+    domain
+}
+
+/// The full `Value::Bool` domain.
+pub fn bool_domain() -> Vec<Value> {
+    vec![Value::Bool(true), Value::Bool(false)]
+}
+
+/// Checks `inverse(forward(x)) == x` for every `x` in `domain`, returning
+/// the first counterexample found instead of merely a pass/fail count.
+/// For a self-inverse gate (`xor`, `cnot`, `swap`, ...), pass the same
+/// operation as both `forward` and `inverse`.
+pub fn assert_reversible(
+    forward: impl Fn(&Value) -> Result<Value>,
+    inverse: impl Fn(&Value) -> Result<Value>,
+    domain: &[Value],
+) -> Result<()> {
+    for x in domain {
+        let forwarded = forward(x)?;
+        let restored = inverse(&forwarded)?;
+        if &restored != x {
+            return Err(JtvError::RuntimeError(format!(
+                "reversibility violated: inverse(forward({x})) = {restored}, expected {x}"
+            )));
+        }
+    }
+    Ok(())
+}
+
 // ===== HISTORY TRACKING =====
 // For reversible execution, we may need to track operation history
 
@@ -140,44 +283,352 @@ pub fn history_new(args: &[Value]) -> Result<Value> {
     }
 }
 
+/// An inverse operation captured at record time: given the value an
+/// operation produced, returns the value it had before.
+type InverseFn = Box<dyn Fn(&Value) -> Result<Value>>;
+
+/// One recorded forward operation on a `ReversibleTrace`: a display name
+/// plus the closure that undoes it, given the value it produced.
+struct TraceRecord {
+    operation_name: &'static str,
+    inverse: InverseFn,
+}
+
+/// A real uncompute mechanism for the operations above, unlike
+/// `make_history_entry`'s plain tuple-packing: an append-only stack of
+/// `(operation_name, inverse_closure)` records built up as each `record_*`
+/// method runs its operation forward, so `rewind` can pop records LIFO and
+/// apply each inverse to restore prior state (Bennett-style uncomputation).
+/// `checkpoint`/`restore` mark and jump back to a position in the trace.
+///
+/// This mirrors `crate::reversible::ReversibleInterpreter`'s forward/reverse
+/// trace, but over a single `Value` and the library's builtin operations
+/// (add/subtract, increment/decrement, swap, xor, cnot) rather than the
+/// AST-level `AddAssign`/`SubAssign` a `reverse { ... }` block compiles to.
+pub struct ReversibleTrace {
+    current: Value,
+    records: Vec<TraceRecord>,
+    checkpoints: Vec<usize>,
+}
+
+impl ReversibleTrace {
+    /// Start a new trace holding `initial`.
+    pub fn new(initial: Value) -> Self {
+        ReversibleTrace {
+            current: initial,
+            records: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// The current value, after whatever operations have been recorded.
+    pub fn value(&self) -> &Value {
+        &self.current
+    }
+
+    /// Number of operations currently on the trace.
+    pub fn trace_len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Display names of the recorded operations, oldest first.
+    pub fn operation_names(&self) -> Vec<&'static str> {
+        self.records.iter().map(|r| r.operation_name).collect()
+    }
+
+    fn push(&mut self, operation_name: &'static str, new_value: Value, inverse: impl Fn(&Value) -> Result<Value> + 'static) {
+        self.records.push(TraceRecord { operation_name, inverse: Box::new(inverse) });
+        self.current = new_value;
+    }
+
+    /// `current += b` (inverse: `subtract(_, b)`).
+    pub fn add(&mut self, b: Value) -> Result<()> {
+        let new_value = self.current.add(&b)?;
+        self.push("add", new_value, move |v| subtract(&[v.clone(), b.clone()]));
+        Ok(())
+    }
+
+    /// `current -= b` (inverse: `current.add(b)`).
+    pub fn subtract(&mut self, b: Value) -> Result<()> {
+        let new_value = subtract(&[self.current.clone(), b.clone()])?;
+        self.push("subtract", new_value, move |v| v.add(&b));
+        Ok(())
+    }
+
+    /// `current += 1` (inverse: `decrement`).
+    pub fn increment(&mut self) -> Result<()> {
+        let new_value = increment(std::slice::from_ref(&self.current))?;
+        self.push("increment", new_value, |v| decrement(std::slice::from_ref(v)));
+        Ok(())
+    }
+
+    /// `current -= 1` (inverse: `increment`).
+    pub fn decrement(&mut self) -> Result<()> {
+        let new_value = decrement(std::slice::from_ref(&self.current))?;
+        self.push("decrement", new_value, |v| increment(std::slice::from_ref(v)));
+        Ok(())
+    }
+
+    /// Swaps the two elements of the current 2-tuple in place (self-inverse:
+    /// swapping again undoes it exactly).
+    pub fn swap(&mut self) -> Result<()> {
+        let new_value = match &self.current {
+            Value::Tuple(items) if items.len() == 2 => {
+                self::swap(&[items[0].clone(), items[1].clone()])?
+            }
+            _ => return Err(JtvError::TypeError("swap requires a 2-tuple".to_string())),
+        };
+        self.push("swap", new_value, |v| match v {
+            Value::Tuple(items) if items.len() == 2 => self::swap(&[items[0].clone(), items[1].clone()]),
+            _ => Err(JtvError::TypeError("swap rewind requires a 2-tuple".to_string())),
+        });
+        Ok(())
+    }
+
+    /// `current = xor(current, b)` (self-inverse: `xor(_, b)` again).
+    pub fn xor(&mut self, b: Value) -> Result<()> {
+        let new_value = xor(&[self.current.clone(), b.clone()])?;
+        self.push("xor", new_value, move |v| xor(&[v.clone(), b.clone()]));
+        Ok(())
+    }
+
+    /// `current = cnot(control, current)` (self-inverse: `cnot(control, _)` again).
+    pub fn cnot(&mut self, control: Value) -> Result<()> {
+        let new_value = cnot(&[control.clone(), self.current.clone()])?;
+        self.push("cnot", new_value, move |v| cnot(&[control.clone(), v.clone()]));
+        Ok(())
+    }
+
+    /// Pop up to `n` records LIFO, applying each inverse to restore prior
+    /// state. Returns the number actually rewound (fewer than `n` if the
+    /// trace is shorter).
+    pub fn rewind(&mut self, n: usize) -> Result<usize> {
+        let mut undone = 0;
+        while undone < n {
+            match self.records.pop() {
+                Some(record) => {
+                    self.current = (record.inverse)(&self.current)?;
+                    undone += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(undone)
+    }
+
+    /// Rewind the entire trace back to its starting value.
+    pub fn rewind_all(&mut self) -> Result<usize> {
+        self.rewind(self.records.len())
+    }
+
+    /// Mark the current position so a later `restore` can jump back to it.
+    pub fn checkpoint(&mut self) {
+        self.checkpoints.push(self.records.len());
+    }
+
+    /// Rewind back to the most recent `checkpoint`, consuming it.
+    pub fn restore(&mut self) -> Result<()> {
+        let target_len = self
+            .checkpoints
+            .pop()
+            .ok_or_else(|| JtvError::RuntimeError("restore called with no matching checkpoint".to_string()))?;
+        self.rewind(self.records.len() - target_len)?;
+        Ok(())
+    }
+}
+
 // ===== QUANTUM SIMULATION PRIMITIVES =====
-// Basic building blocks for quantum algorithm simulation
+// A real state-vector engine: an n-qubit register is a `Value::Qubits`
+// holding the `2^n` basis-state amplitudes, index by index, where bit `j`
+// of the index is qubit `j`. `hadamard_bool`, `measure`, and `phase_rotate`
+// stay the public entry points, but now delegate to this engine whenever
+// they're handed a register instead of a plain `Bool`/`List`/`Complex`.
+
+/// Build a fresh n-qubit register in the |0...0> state.
+pub fn qubits_new(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Int(n) if *n > 0 && *n <= 20 => {
+            let size = 1usize << n;
+            let mut amps = vec![Complex64::new(0.0, 0.0); size];
+            amps[0] = Complex64::new(1.0, 0.0);
+            Ok(Value::Qubits(amps))
+        }
+        Value::Int(_) => Err(JtvError::RuntimeError(
+            "qubits_new requires a qubit count between 1 and 20".to_string(),
+        )),
+        _ => Err(JtvError::TypeError("qubits_new requires an Int".to_string())),
+    }
+}
+
+/// Validate `(amps, qubit index)` and return the index's bit mask.
+fn qubit_mask(amps: &[Complex64], qubit: i64) -> Result<usize> {
+    let n_qubits = amps.len().trailing_zeros() as i64;
+    if !amps.len().is_power_of_two() || qubit < 0 || qubit >= n_qubits {
+        return Err(JtvError::RuntimeError(format!(
+            "qubit index {} out of range for a {}-qubit register",
+            qubit, n_qubits
+        )));
+    }
+    Ok(1usize << qubit)
+}
+
+/// Hadamard gate on qubit `i`: for every pair of indices differing only in
+/// bit `i`, maps amplitudes `(a, b) -> ((a+b)/sqrt(2), (a-b)/sqrt(2))`.
+pub fn hadamard(args: &[Value]) -> Result<Value> {
+    match (&args[0], &args[1]) {
+        (Value::Qubits(amps), Value::Int(i)) => {
+            let mask = qubit_mask(amps, *i)?;
+            let inv_sqrt2 = std::f64::consts::FRAC_1_SQRT_2;
+            let mut out = amps.clone();
+            for (idx, &a) in amps.iter().enumerate() {
+                if idx & mask == 0 {
+                    let partner = idx | mask;
+                    let b = amps[partner];
+                    out[idx] = (a + b) * inv_sqrt2;
+                    out[partner] = (a - b) * inv_sqrt2;
+                }
+            }
+            Ok(Value::Qubits(out))
+        }
+        _ => Err(JtvError::TypeError("hadamard requires (Qubits, Int)".to_string())),
+    }
+}
+
+/// CNOT gate: for every index with control bit `c` set, swap its amplitude
+/// with the index obtained by flipping target bit `t`.
+pub fn cnot_qubits(args: &[Value]) -> Result<Value> {
+    match (&args[0], &args[1], &args[2]) {
+        (Value::Qubits(amps), Value::Int(c), Value::Int(t)) => {
+            let control_mask = qubit_mask(amps, *c)?;
+            let target_mask = qubit_mask(amps, *t)?;
+            if control_mask == target_mask {
+                return Err(JtvError::RuntimeError(
+                    "cnot_qubits requires distinct control and target qubits".to_string(),
+                ));
+            }
+            let mut out = amps.clone();
+            for idx in 0..amps.len() {
+                if idx & control_mask != 0 {
+                    let partner = idx ^ target_mask;
+                    if partner > idx {
+                        out.swap(idx, partner);
+                    }
+                }
+            }
+            Ok(Value::Qubits(out))
+        }
+        _ => Err(JtvError::TypeError("cnot_qubits requires (Qubits, Int, Int)".to_string())),
+    }
+}
+
+/// Phase/Rz gate: multiplies every amplitude with bit `i` set by `e^{i*theta}`.
+pub fn apply_phase(args: &[Value]) -> Result<Value> {
+    match (&args[0], &args[1], &args[2]) {
+        (Value::Qubits(amps), Value::Int(i), Value::Float(theta)) => {
+            let mask = qubit_mask(amps, *i)?;
+            let rotation = Complex64::from_polar(1.0, *theta);
+            let out = amps
+                .iter()
+                .enumerate()
+                .map(|(idx, &a)| if idx & mask != 0 { a * rotation } else { a })
+                .collect();
+            Ok(Value::Qubits(out))
+        }
+        _ => Err(JtvError::TypeError("apply_phase requires (Qubits, Int, Float)".to_string())),
+    }
+}
+
+/// Deterministic, seedable `[0, 1)` sample (splitmix64) so measurement
+/// outcomes are reproducible from a caller-supplied seed instead of relying
+/// on hidden global randomness.
+fn seeded_unit_float(seed: i64) -> f64 {
+    let mut z = (seed as u64).wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Measure qubit `i`: computes `P(1) = sum |amp|^2` over indices with bit
+/// `i` set, samples against the seeded RNG, then zeroes the branch
+/// inconsistent with the outcome and renormalizes. Returns
+/// `(collapsed: Bool, state: Qubits)`.
+pub fn measure_qubit(args: &[Value]) -> Result<Value> {
+    match (&args[0], &args[1], &args[2]) {
+        (Value::Qubits(amps), Value::Int(i), Value::Int(seed)) => {
+            let mask = qubit_mask(amps, *i)?;
+            let p1: f64 = amps
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| idx & mask != 0)
+                .map(|(_, a)| a.norm_sqr())
+                .sum();
+            let outcome = seeded_unit_float(*seed) < p1;
+            let p = if outcome { p1 } else { 1.0 - p1 };
+            if p < 1e-12 {
+                return Err(JtvError::RuntimeError(
+                    "measure_qubit: outcome has zero probability".to_string(),
+                ));
+            }
+            let norm = p.sqrt();
+            let collapsed = amps
+                .iter()
+                .enumerate()
+                .map(|(idx, &a)| {
+                    if (idx & mask != 0) == outcome {
+                        a / norm
+                    } else {
+                        Complex64::new(0.0, 0.0)
+                    }
+                })
+                .collect();
+            Ok(Value::Tuple(vec![Value::Bool(outcome), Value::Qubits(collapsed)]))
+        }
+        _ => Err(JtvError::TypeError("measure_qubit requires (Qubits, Int, Int)".to_string())),
+    }
+}
 
-/// Hadamard-like transformation on boolean
-/// Represents superposition (returns both possibilities)
+/// Hadamard-like transformation on boolean, or -- when given a register and
+/// a qubit index -- the real `hadamard` gate above.
 pub fn hadamard_bool(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Bool(_) => {
             // Returns both possible outcomes as a list
-            Ok(Value::List(vec![Value::Bool(true), Value::Bool(false)]))
+            Ok(Value::List(PVector::from_vec(vec![Value::Bool(true), Value::Bool(false)])))
         }
+        Value::Qubits(_) => hadamard(args),
         _ => Err(JtvError::TypeError("hadamard_bool requires a Bool".to_string())),
     }
 }
 
-/// Measure a superposition (collapse to single value)
-/// Takes first element (deterministic for now)
+/// Measure a superposition: a `List` collapses to its first element (the
+/// original deterministic placeholder, kept for `hadamard_bool`'s output),
+/// while a real `Qubits` register goes through `measure_qubit`.
 pub fn measure(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::List(items) if !items.is_empty() => Ok(items[0].clone()),
+        Value::Qubits(_) => measure_qubit(args),
         _ => Err(JtvError::TypeError("measure requires a non-empty list".to_string())),
     }
 }
 
-/// Phase rotation (for quantum phase estimation simulation)
+/// Phase rotation: a raw `Complex`/`Float` amplitude rotates by `angle*PI`
+/// (half-turn units, the original convention), while a real `Qubits`
+/// register goes through `apply_phase`'s `e^{i*theta}` (radians).
 pub fn phase_rotate(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::Complex(c), Value::Float(angle)) => {
             use std::f64::consts::PI;
-            let phase = num_complex::Complex64::from_polar(1.0, angle * PI);
+            let phase = Complex64::from_polar(1.0, angle * PI);
             Ok(Value::Complex(c * phase))
         }
         (Value::Float(f), Value::Float(angle)) => {
             use std::f64::consts::PI;
-            let c = num_complex::Complex64::new(*f, 0.0);
-            let phase = num_complex::Complex64::from_polar(1.0, angle * PI);
+            let c = Complex64::new(*f, 0.0);
+            let phase = Complex64::from_polar(1.0, angle * PI);
             Ok(Value::Complex(c * phase))
         }
+        (Value::Qubits(_), _) => apply_phase(args),
         _ => Err(JtvError::TypeError("phase_rotate requires (Complex, Float)".to_string())),
     }
 }
@@ -192,6 +643,9 @@ pub fn bit_count(args: &[Value]) -> Result<Value> {
             let bits = if *n == 0 { 0 } else { (64 - n.abs().leading_zeros()) as i64 };
             Ok(Value::Int(bits))
         }
+        // `BigInt::bits` is the true bit-length of the magnitude, so this
+        // stays exact however large `n` grows instead of saturating at 64.
+        Value::BigInt(n) => Ok(Value::Int(n.bits() as i64)),
         Value::List(items) => {
             Ok(Value::Int(items.len() as i64))
         }
@@ -220,6 +674,34 @@ mod tests {
         assert_eq!(subtract(&[Value::Int(10), Value::Int(3)]).unwrap(), Value::Int(7));
     }
 
+    #[test]
+    fn test_subtract_overflow_promotes_to_bigint() {
+        let result = subtract(&[Value::Int(i64::MIN), Value::Int(1)]).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MIN) - 1));
+    }
+
+    #[test]
+    fn test_increment_overflow_promotes_to_bigint() {
+        let result = increment(&[Value::Int(i64::MAX)]).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MAX) + 1));
+    }
+
+    #[test]
+    fn test_decrement_overflow_promotes_to_bigint() {
+        let result = decrement(&[Value::Int(i64::MIN)]).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MIN) - 1));
+    }
+
+    #[test]
+    fn test_increment_then_decrement_is_exact_across_overflow() {
+        // Round-tripping through the BigInt promotion must still recover
+        // the original Int -- invertibility can't be lost to overflow.
+        let original = Value::Int(i64::MAX);
+        let incremented = increment(std::slice::from_ref(&original)).unwrap();
+        let restored = decrement(&[incremented]).unwrap();
+        assert_eq!(restored, original);
+    }
+
     #[test]
     fn test_swap() {
         let result = swap(&[Value::Int(1), Value::Int(2)]).unwrap();
@@ -242,6 +724,30 @@ mod tests {
         assert_eq!(inv, Value::Int(-5));
     }
 
+    #[test]
+    fn test_additive_inverse_of_int_min_promotes_to_bigint() {
+        // -i64::MIN is not representable as an i64, but is as a BigInt:
+        // additive_inverse is total over all integers, not just Int's range.
+        let result = additive_inverse(&[Value::Int(i64::MIN)]).unwrap();
+        assert_eq!(result, Value::BigInt(-BigInt::from(i64::MIN)));
+    }
+
+    #[test]
+    fn test_xor_promotes_to_bigint_and_stays_self_inverse() {
+        let big = Value::BigInt(BigInt::from(i64::MAX) + 1);
+        let mask = Value::Int(0xFF);
+        let xored = xor(&[big.clone(), mask.clone()]).unwrap();
+        let restored = xor(&[xored, mask]).unwrap();
+        assert_eq!(restored, big);
+    }
+
+    #[test]
+    fn test_bit_count_of_bigint_uses_true_bit_length() {
+        // 2^100 needs 101 bits, far past what any Int-based count could give.
+        let huge = Value::BigInt(BigInt::from(1) << 100u32);
+        assert_eq!(bit_count(&[huge]).unwrap(), Value::Int(101));
+    }
+
     #[test]
     fn test_cnot() {
         // Control false -> no change
@@ -249,4 +755,325 @@ mod tests {
         // Control true -> negate
         assert_eq!(cnot(&[Value::Bool(true), Value::Int(5)]).unwrap(), Value::Int(-5));
     }
+
+    #[test]
+    fn test_toffoli() {
+        // Both controls false -> no change
+        assert_eq!(toffoli(&[Value::Bool(false), Value::Bool(false), Value::Int(5)]).unwrap(), Value::Int(5));
+        // One control false -> no change
+        assert_eq!(toffoli(&[Value::Bool(true), Value::Bool(false), Value::Int(5)]).unwrap(), Value::Int(5));
+        // Both controls true -> negate
+        assert_eq!(toffoli(&[Value::Bool(true), Value::Bool(true), Value::Int(5)]).unwrap(), Value::Int(-5));
+    }
+
+    #[test]
+    fn test_toffoli_self_inverse() {
+        // Applying toffoli twice with the same controls returns the original value
+        let a = Value::Int(7);
+        let once = toffoli(&[Value::Bool(true), Value::Bool(true), a.clone()]).unwrap();
+        let twice = toffoli(&[Value::Bool(true), Value::Bool(true), once]).unwrap();
+        assert_eq!(twice, a);
+    }
+
+    #[test]
+    fn test_fredkin() {
+        // Control false -> unchanged order
+        assert_eq!(
+            fredkin(&[Value::Bool(false), Value::Int(1), Value::Int(2)]).unwrap(),
+            Value::Tuple(vec![Value::Int(1), Value::Int(2)])
+        );
+        // Control true -> swapped
+        assert_eq!(
+            fredkin(&[Value::Bool(true), Value::Int(1), Value::Int(2)]).unwrap(),
+            Value::Tuple(vec![Value::Int(2), Value::Int(1)])
+        );
+    }
+
+    #[test]
+    fn test_fredkin_self_inverse() {
+        // Swapping twice with the same control returns the original pair
+        let a = Value::Int(3);
+        let b = Value::Int(9);
+        let once = fredkin(&[Value::Bool(true), a.clone(), b.clone()]).unwrap();
+        let (a1, b1) = match once {
+            Value::Tuple(items) => (items[0].clone(), items[1].clone()),
+            _ => panic!("expected a tuple"),
+        };
+        let twice = fredkin(&[Value::Bool(true), a1, b1]).unwrap();
+        assert_eq!(twice, Value::Tuple(vec![a, b]));
+    }
+
+    fn qubits(v: &Value) -> &[Complex64] {
+        match v {
+            Value::Qubits(amps) => amps,
+            _ => panic!("expected Value::Qubits"),
+        }
+    }
+
+    #[test]
+    fn test_hadamard_creates_equal_superposition() {
+        let reg = qubits_new(&[Value::Int(1)]).unwrap();
+        let after = hadamard(&[reg, Value::Int(0)]).unwrap();
+        let amps = qubits(&after);
+        let inv_sqrt2 = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((amps[0].re - inv_sqrt2).abs() < 1e-9);
+        assert!((amps[1].re - inv_sqrt2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cnot_qubits_entangles_bell_pair() {
+        let reg = qubits_new(&[Value::Int(2)]).unwrap();
+        let superposed = hadamard(&[reg, Value::Int(0)]).unwrap();
+        let bell = cnot_qubits(&[superposed, Value::Int(0), Value::Int(1)]).unwrap();
+        let amps = qubits(&bell);
+        // |00> and |11> each have amplitude 1/sqrt(2); |01> and |10> are empty.
+        let inv_sqrt2 = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((amps[0b00].re - inv_sqrt2).abs() < 1e-9);
+        assert!((amps[0b11].re - inv_sqrt2).abs() < 1e-9);
+        assert!(amps[0b01].norm() < 1e-9);
+        assert!(amps[0b10].norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_apply_phase_rotates_set_bit_only() {
+        let reg = qubits_new(&[Value::Int(1)]).unwrap();
+        let excited = hadamard(&[reg, Value::Int(0)]).unwrap();
+        let rotated = apply_phase(&[excited, Value::Int(0), Value::Float(std::f64::consts::FRAC_PI_2)]).unwrap();
+        let amps = qubits(&rotated);
+        // |0> amplitude is untouched; |1> amplitude picks up a +90 degree phase.
+        assert!(amps[0].im.abs() < 1e-9);
+        assert!(amps[1].re.abs() < 1e-9);
+        assert!(amps[1].im > 0.0);
+    }
+
+    #[test]
+    fn test_measure_qubit_collapses_and_renormalizes() {
+        let reg = qubits_new(&[Value::Int(1)]).unwrap();
+        let superposed = hadamard(&[reg, Value::Int(0)]).unwrap();
+        let result = measure_qubit(&[superposed, Value::Int(0), Value::Int(42)]).unwrap();
+        match result {
+            Value::Tuple(parts) => {
+                let collapsed_bit = matches!(parts[0], Value::Bool(true));
+                let amps = qubits(&parts[1]);
+                let live = if collapsed_bit { amps[1] } else { amps[0] };
+                let dead = if collapsed_bit { amps[0] } else { amps[1] };
+                assert!((live.norm() - 1.0).abs() < 1e-9);
+                assert!(dead.norm() < 1e-9);
+            }
+            _ => panic!("expected a (Bool, Qubits) tuple"),
+        }
+    }
+
+    #[test]
+    fn test_measure_qubit_is_deterministic_for_a_fixed_seed() {
+        let reg = qubits_new(&[Value::Int(1)]).unwrap();
+        let superposed = hadamard(&[reg, Value::Int(0)]).unwrap();
+        let a = measure_qubit(&[superposed.clone(), Value::Int(0), Value::Int(7)]).unwrap();
+        let b = measure_qubit(&[superposed, Value::Int(0), Value::Int(7)]).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_hadamard_bool_and_measure_still_work_on_the_old_contract() {
+        // The legacy Bool/List entry points keep their original behavior.
+        let result = hadamard_bool(&[Value::Bool(true)]).unwrap();
+        assert_eq!(measure(&[result]).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_reversible_trace_rewind_undoes_in_lifo_order() {
+        let mut trace = ReversibleTrace::new(Value::Int(10));
+        trace.add(Value::Int(5)).unwrap();
+        trace.increment().unwrap();
+        trace.xor(Value::Int(3)).unwrap();
+        assert_eq!(trace.trace_len(), 3);
+
+        let undone = trace.rewind(3).unwrap();
+        assert_eq!(undone, 3);
+        assert_eq!(trace.value(), &Value::Int(10));
+        assert_eq!(trace.trace_len(), 0);
+    }
+
+    #[test]
+    fn test_reversible_trace_partial_rewind() {
+        let mut trace = ReversibleTrace::new(Value::Int(0));
+        trace.increment().unwrap();
+        trace.increment().unwrap();
+        trace.increment().unwrap();
+        assert_eq!(trace.value(), &Value::Int(3));
+
+        let undone = trace.rewind(1).unwrap();
+        assert_eq!(undone, 1);
+        assert_eq!(trace.value(), &Value::Int(2));
+        assert_eq!(trace.trace_len(), 2);
+    }
+
+    #[test]
+    fn test_reversible_trace_rewind_more_than_available_stops_at_zero() {
+        let mut trace = ReversibleTrace::new(Value::Int(1));
+        trace.increment().unwrap();
+        let undone = trace.rewind(10).unwrap();
+        assert_eq!(undone, 1);
+        assert_eq!(trace.value(), &Value::Int(1));
+    }
+
+    #[test]
+    fn test_reversible_trace_checkpoint_and_restore() {
+        let mut trace = ReversibleTrace::new(Value::Int(100));
+        trace.increment().unwrap();
+        trace.checkpoint();
+        trace.increment().unwrap();
+        trace.increment().unwrap();
+        assert_eq!(trace.value(), &Value::Int(103));
+
+        trace.restore().unwrap();
+        assert_eq!(trace.value(), &Value::Int(101));
+        assert_eq!(trace.trace_len(), 1);
+    }
+
+    #[test]
+    fn test_reversible_trace_restore_without_checkpoint_is_an_error() {
+        let mut trace = ReversibleTrace::new(Value::Int(0));
+        assert!(trace.restore().is_err());
+    }
+
+    #[test]
+    fn test_reversible_trace_swap_round_trip() {
+        let mut trace = ReversibleTrace::new(Value::Tuple(vec![Value::Int(5), Value::Int(9)]));
+        trace.swap().unwrap();
+        assert_eq!(trace.value(), &Value::Tuple(vec![Value::Int(9), Value::Int(5)]));
+        trace.rewind_all().unwrap();
+        assert_eq!(trace.value(), &Value::Tuple(vec![Value::Int(5), Value::Int(9)]));
+    }
+
+    #[test]
+    fn test_reversible_trace_cnot_round_trip() {
+        let mut trace = ReversibleTrace::new(Value::Int(5));
+        trace.cnot(Value::Bool(true)).unwrap();
+        assert_eq!(trace.value(), &Value::Int(-5));
+        trace.rewind_all().unwrap();
+        assert_eq!(trace.value(), &Value::Int(5));
+    }
+
+    #[test]
+    fn test_assert_reversible_add_and_subtract() {
+        let b = Value::Int(17);
+        assert_reversible(
+            |v| v.add(&b),
+            |v| subtract(&[v.clone(), b.clone()]),
+            &numeric_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_increment_and_decrement() {
+        assert_reversible(
+            |v| increment(std::slice::from_ref(v)),
+            |v| decrement(std::slice::from_ref(v)),
+            &numeric_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_additive_inverse_is_self_inverse() {
+        assert_reversible(
+            |v| additive_inverse(std::slice::from_ref(v)),
+            |v| additive_inverse(std::slice::from_ref(v)),
+            &numeric_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_xor_is_self_inverse() {
+        let mask = Value::Int(0xFF);
+        assert_reversible(
+            |v| xor(&[v.clone(), mask.clone()]),
+            |v| xor(&[v.clone(), mask.clone()]),
+            &int_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_cnot_is_self_inverse() {
+        let control = Value::Bool(true);
+        assert_reversible(
+            |v| cnot(&[control.clone(), v.clone()]),
+            |v| cnot(&[control.clone(), v.clone()]),
+            &int_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_swap_is_self_inverse() {
+        let pairs = vec![
+            Value::Tuple(vec![Value::Int(0), Value::Int(0)]),
+            Value::Tuple(vec![Value::Int(1), Value::Int(-1)]),
+            Value::Tuple(vec![Value::Int(i64::MAX), Value::Int(i64::MIN)]),
+        ];
+        assert_reversible(
+            |v| match v {
+                Value::Tuple(items) => swap(&[items[0].clone(), items[1].clone()]),
+                _ => panic!("expected a tuple"),
+            },
+            |v| match v {
+                Value::Tuple(items) => swap(&[items[0].clone(), items[1].clone()]),
+                _ => panic!("expected a tuple"),
+            },
+            &pairs,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_toffoli_is_self_inverse() {
+        assert_reversible(
+            |v| toffoli(&[Value::Bool(true), Value::Bool(true), v.clone()]),
+            |v| toffoli(&[Value::Bool(true), Value::Bool(true), v.clone()]),
+            &int_domain(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_fredkin_is_self_inverse() {
+        let pairs = vec![
+            Value::Tuple(vec![Value::Int(3), Value::Int(9)]),
+            Value::Tuple(vec![Value::Int(-1), Value::Int(1)]),
+        ];
+        assert_reversible(
+            |v| match v {
+                Value::Tuple(items) => fredkin(&[Value::Bool(true), items[0].clone(), items[1].clone()]),
+                _ => panic!("expected a tuple"),
+            },
+            |v| match v {
+                Value::Tuple(items) => fredkin(&[Value::Bool(true), items[0].clone(), items[1].clone()]),
+                _ => panic!("expected a tuple"),
+            },
+            &pairs,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_reversible_reports_first_counterexample() {
+        // `increment` paired with itself (instead of `decrement`) is not a
+        // real inverse, so the very first domain value should fail.
+        let err = assert_reversible(
+            |v| increment(std::slice::from_ref(v)),
+            |v| increment(std::slice::from_ref(v)),
+            &numeric_domain(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, JtvError::RuntimeError(_)));
+    }
+
+    #[test]
+    fn test_assert_reversible_bool_domain_covers_both_values() {
+        assert_eq!(bool_domain(), vec![Value::Bool(true), Value::Bool(false)]);
+    }
 }
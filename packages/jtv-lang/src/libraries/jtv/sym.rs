@@ -0,0 +1,655 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// JtV Symbolic Algebra Engine
+//
+// `Value::Symbolic` stores a raw `String`, so every builtin in this module
+// parses that string into a `Sym` expression tree, operates on the tree,
+// and formats the result back out through `Display` -- the wire
+// representation stays a plain string (so `type_of`/equality/serde don't
+// need to change), but `simplify`/`expand`/`differentiate`/`substitute`
+// all work over real structure instead of text.
+
+use crate::error::{JtvError, Result};
+use std::fmt;
+
+/// A symbolic expression tree. Deliberately small -- just enough algebra
+/// to support `simplify`/`expand`/`differentiate`/`substitute` -- rather
+/// than a general CAS: no `Div` (`a/b` isn't representable; `ln`'s
+/// derivative is built from `Pow(_, Num(-1))` instead) and no
+/// arbitrary-arity `Add`/`Mul` (binary, like `Pow`, so the parser stays a
+/// plain precedence-climbing recursive descent).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sym {
+    Num(f64),
+    Var(String),
+    Add(Box<Sym>, Box<Sym>),
+    Mul(Box<Sym>, Box<Sym>),
+    Pow(Box<Sym>, Box<Sym>),
+    Neg(Box<Sym>),
+    Call(String, Vec<Sym>),
+}
+
+impl Sym {
+    fn num(n: f64) -> Sym {
+        Sym::Num(n)
+    }
+
+    /// Every free variable this expression mentions, for callers that want
+    /// to know "is this ground?" after a substitution.
+    fn free_vars(&self, out: &mut std::collections::HashSet<String>) {
+        match self {
+            Sym::Num(_) => {}
+            Sym::Var(v) => {
+                out.insert(v.clone());
+            }
+            Sym::Add(a, b) | Sym::Mul(a, b) | Sym::Pow(a, b) => {
+                a.free_vars(out);
+                b.free_vars(out);
+            }
+            Sym::Neg(a) => a.free_vars(out),
+            Sym::Call(_, args) => {
+                for a in args {
+                    a.free_vars(out);
+                }
+            }
+        }
+    }
+
+    pub fn is_ground(&self) -> bool {
+        let mut vars = std::collections::HashSet::new();
+        self.free_vars(&mut vars);
+        vars.is_empty()
+    }
+
+    /// `Some(n)` if this is exactly a numeric leaf -- used by `simplify`'s
+    /// constant folding and by the caller that converts a fully-substituted
+    /// expression back into a `Value`.
+    pub fn as_num(&self) -> Option<f64> {
+        match self {
+            Sym::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Replace every `Var(name)` with `replacement`, leaving everything
+    /// else structurally unchanged.
+    pub fn substitute(&self, name: &str, replacement: &Sym) -> Sym {
+        match self {
+            Sym::Num(n) => Sym::Num(*n),
+            Sym::Var(v) if v == name => replacement.clone(),
+            Sym::Var(v) => Sym::Var(v.clone()),
+            Sym::Add(a, b) => Sym::Add(
+                Box::new(a.substitute(name, replacement)),
+                Box::new(b.substitute(name, replacement)),
+            ),
+            Sym::Mul(a, b) => Sym::Mul(
+                Box::new(a.substitute(name, replacement)),
+                Box::new(b.substitute(name, replacement)),
+            ),
+            Sym::Pow(a, b) => Sym::Pow(
+                Box::new(a.substitute(name, replacement)),
+                Box::new(b.substitute(name, replacement)),
+            ),
+            Sym::Neg(a) => Sym::Neg(Box::new(a.substitute(name, replacement))),
+            Sym::Call(f, args) => Sym::Call(
+                f.clone(),
+                args.iter().map(|a| a.substitute(name, replacement)).collect(),
+            ),
+        }
+    }
+
+    /// Fold numeric constants, drop `+0`/`*1`, collapse `*0` to `0`, and
+    /// flatten nested `Add`/`Mul` (e.g. `(1 + x) + 2` folds its two
+    /// constant terms together instead of leaving them on opposite sides
+    /// of the tree). Recurses bottom-up so a parent can fold children that
+    /// only became constant after their own simplification.
+    pub fn simplify(&self) -> Sym {
+        match self {
+            Sym::Num(n) => Sym::Num(*n),
+            Sym::Var(v) => Sym::Var(v.clone()),
+            Sym::Add(..) => simplify_add(flatten_add(self)),
+            Sym::Mul(..) => simplify_mul(flatten_mul(self)),
+            Sym::Pow(base, exp) => {
+                let base = base.simplify();
+                let exp = exp.simplify();
+                match (&base, &exp) {
+                    (_, Sym::Num(n)) if *n == 1.0 => base,
+                    (_, Sym::Num(n)) if *n == 0.0 => Sym::Num(1.0),
+                    (Sym::Num(b), Sym::Num(e)) => Sym::Num(b.powf(*e)),
+                    _ => Sym::Pow(Box::new(base), Box::new(exp)),
+                }
+            }
+            Sym::Neg(a) => match a.simplify() {
+                Sym::Num(n) => Sym::Num(-n),
+                Sym::Neg(inner) => *inner,
+                other => Sym::Neg(Box::new(other)),
+            },
+            Sym::Call(f, args) => Sym::Call(f.clone(), args.iter().map(Sym::simplify).collect()),
+        }
+    }
+
+    /// Distribute `Mul` over `Add` (`a*(b+c)` -> `a*b + a*c`), recursively,
+    /// then simplify the result.
+    pub fn expand(&self) -> Sym {
+        let expanded = match self {
+            Sym::Num(n) => Sym::Num(*n),
+            Sym::Var(v) => Sym::Var(v.clone()),
+            Sym::Add(a, b) => Sym::Add(Box::new(a.expand()), Box::new(b.expand())),
+            Sym::Mul(a, b) => {
+                let a = a.expand();
+                let b = b.expand();
+                distribute(&a, &b)
+            }
+            Sym::Pow(base, exp) => Sym::Pow(Box::new(base.expand()), Box::new(exp.clone())),
+            Sym::Neg(a) => Sym::Neg(Box::new(a.expand())),
+            Sym::Call(f, args) => Sym::Call(f.clone(), args.iter().map(Sym::expand).collect()),
+        };
+        expanded.simplify()
+    }
+
+    /// Differentiate with respect to `var`, applying the standard rules:
+    /// `d(u+v)=du+dv`, the product rule `d(uv)=u*dv+v*du`, the power rule
+    /// `d(u^n)=n*u^(n-1)*du` for a constant `n`, the chain rule for
+    /// `sin`/`cos`/`exp`/`ln`, and `d(const)=0`/`d(x)=1`.
+    pub fn differentiate(&self, var: &str) -> Result<Sym> {
+        Ok(match self {
+            Sym::Num(_) => Sym::Num(0.0),
+            Sym::Var(v) => Sym::Num(if v == var { 1.0 } else { 0.0 }),
+            Sym::Add(a, b) => Sym::Add(
+                Box::new(a.differentiate(var)?),
+                Box::new(b.differentiate(var)?),
+            ),
+            Sym::Mul(a, b) => {
+                let du = a.differentiate(var)?;
+                let dv = b.differentiate(var)?;
+                Sym::Add(
+                    Box::new(Sym::Mul(a.clone(), Box::new(dv))),
+                    Box::new(Sym::Mul(Box::new(du), b.clone())),
+                )
+            }
+            Sym::Pow(base, exp) => match exp.as_num() {
+                Some(n) => {
+                    let du = base.differentiate(var)?;
+                    Sym::Mul(
+                        Box::new(Sym::Mul(
+                            Box::new(Sym::Num(n)),
+                            Box::new(Sym::Pow(base.clone(), Box::new(Sym::Num(n - 1.0)))),
+                        )),
+                        Box::new(du),
+                    )
+                }
+                None => {
+                    return Err(JtvError::RuntimeError(
+                        "differentiate: only a constant exponent is supported (power rule)"
+                            .to_string(),
+                    ))
+                }
+            },
+            Sym::Neg(a) => Sym::Neg(Box::new(a.differentiate(var)?)),
+            Sym::Call(f, args) if args.len() == 1 => {
+                let u = &args[0];
+                let du = u.differentiate(var)?;
+                let outer = match f.as_str() {
+                    "sin" => Sym::Call("cos".to_string(), vec![u.clone()]),
+                    "cos" => Sym::Neg(Box::new(Sym::Call("sin".to_string(), vec![u.clone()]))),
+                    "exp" => Sym::Call("exp".to_string(), vec![u.clone()]),
+                    "ln" => Sym::Pow(Box::new(u.clone()), Box::new(Sym::Num(-1.0))),
+                    other => {
+                        return Err(JtvError::RuntimeError(format!(
+                            "differentiate: don't know the derivative of `{}`",
+                            other
+                        )))
+                    }
+                };
+                Sym::Mul(Box::new(outer), Box::new(du))
+            }
+            Sym::Call(f, _) => {
+                return Err(JtvError::RuntimeError(format!(
+                    "differentiate: `{}` must be called with exactly one argument",
+                    f
+                )))
+            }
+        })
+    }
+}
+
+/// Collects a left-to-right list of `Add` operands, flattening any nested
+/// `Add` so `(a + b) + c` and `a + (b + c)` both yield `[a, b, c]`.
+fn flatten_add(expr: &Sym) -> Vec<Sym> {
+    match expr {
+        Sym::Add(a, b) => {
+            let mut terms = flatten_add(a);
+            terms.extend(flatten_add(b));
+            terms
+        }
+        other => vec![other.simplify()],
+    }
+}
+
+/// Sums every constant term together, drops any resulting/original `0`,
+/// and rebuilds an `Add` chain with the folded constant leading (or the
+/// lone survivor, or `Num(0)` if nothing is left).
+fn simplify_add(terms: Vec<Sym>) -> Sym {
+    let mut constant = 0.0;
+    let mut rest = vec![];
+    for term in terms {
+        match term.as_num() {
+            Some(n) => constant += n,
+            None => rest.push(term),
+        }
+    }
+    if constant != 0.0 || rest.is_empty() {
+        rest.insert(0, Sym::Num(constant));
+    }
+    rest.into_iter()
+        .reduce(|acc, term| Sym::Add(Box::new(acc), Box::new(term)))
+        .unwrap_or(Sym::Num(0.0))
+}
+
+fn flatten_mul(expr: &Sym) -> Vec<Sym> {
+    match expr {
+        Sym::Mul(a, b) => {
+            let mut terms = flatten_mul(a);
+            terms.extend(flatten_mul(b));
+            terms
+        }
+        other => vec![other.simplify()],
+    }
+}
+
+fn simplify_mul(terms: Vec<Sym>) -> Sym {
+    let mut constant = 1.0;
+    let mut rest = vec![];
+    for term in terms {
+        match term.as_num() {
+            Some(n) => constant *= n,
+            None => rest.push(term),
+        }
+    }
+    if constant == 0.0 {
+        return Sym::Num(0.0);
+    }
+    if constant != 1.0 || rest.is_empty() {
+        rest.insert(0, Sym::Num(constant));
+    }
+    rest.into_iter()
+        .reduce(|acc, term| Sym::Mul(Box::new(acc), Box::new(term)))
+        .unwrap_or(Sym::Num(1.0))
+}
+
+/// `a*(b+c) -> a*b + a*c`, from either side, and falls back to a plain
+/// `Mul` when neither operand is an `Add`.
+fn distribute(a: &Sym, b: &Sym) -> Sym {
+    match (a, b) {
+        (Sym::Add(x, y), _) => Sym::Add(
+            Box::new(distribute(x, b)),
+            Box::new(distribute(y, b)),
+        ),
+        (_, Sym::Add(x, y)) => Sym::Add(
+            Box::new(distribute(a, x)),
+            Box::new(distribute(a, y)),
+        ),
+        _ => Sym::Mul(Box::new(a.clone()), Box::new(b.clone())),
+    }
+}
+
+impl fmt::Display for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sym::Num(n) => {
+                if n.fract() == 0.0 && n.abs() < 1e15 {
+                    write!(f, "{}", *n as i64)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            Sym::Var(v) => write!(f, "{}", v),
+            Sym::Add(a, b) => write!(f, "{} + {}", a, b),
+            Sym::Mul(a, b) => write!(f, "{} * {}", Paren(a, self), Paren(b, self)),
+            Sym::Pow(a, b) => write!(f, "{}^{}", Paren(a, self), Paren(b, self)),
+            Sym::Neg(a) => write!(f, "-{}", Paren(a, self)),
+            Sym::Call(name, args) => {
+                write!(f, "{}(", name)?;
+                for (i, a) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", a)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Wraps a child so it only grows parentheses when its own precedence is
+/// lower than its parent's -- mirrors `formatter.rs`'s precedence-aware
+/// parenthesization for `DataExpr`, scaled down to this much smaller
+/// expression language.
+struct Paren<'a>(&'a Sym, &'a Sym);
+
+fn precedence(s: &Sym) -> u8 {
+    match s {
+        Sym::Add(..) => 1,
+        Sym::Neg(..) => 2,
+        Sym::Mul(..) => 3,
+        Sym::Pow(..) => 4,
+        Sym::Num(_) | Sym::Var(_) | Sym::Call(..) => 5,
+    }
+}
+
+impl fmt::Display for Paren<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if precedence(self.0) < precedence(self.1) {
+            write!(f, "({})", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+// ===== Parser =====
+//
+// A small precedence-climbing recursive descent parser: `expr := term
+// (('+' | '-') term)*`, `term := unary ('*' unary)*`, `unary := '-' unary |
+// power`, `power := atom ('^' unary)?` (right-associative, and binding
+// tighter than a leading `-`, so `-x^2` is `-(x^2)`), `atom :=
+// number | ident | ident '(' args ')' | '(' expr ')'`.
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Num(f64),
+    Ident(&'a str),
+    Plus,
+    Minus,
+    Star,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token<'_>>> {
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    let mut tokens = vec![];
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '^' => {
+                tokens.push(Token::Caret);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            _ if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < bytes.len() && matches!(bytes[i] as char, '0'..='9' | '.') {
+                    i += 1;
+                }
+                let text = &input[start..i];
+                let n = text.parse::<f64>().map_err(|_| {
+                    JtvError::ParseError(format!("invalid number literal `{}`", text))
+                })?;
+                tokens.push(Token::Num(n));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < bytes.len() && ((bytes[i] as char).is_alphanumeric() || bytes[i] as char == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(&input[start..i]));
+            }
+            other => {
+                return Err(JtvError::ParseError(format!(
+                    "unexpected character `{}` in symbolic expression",
+                    other
+                )))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token<'a>> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, tok: &Token<'a>) -> Result<()> {
+        if self.peek() == Some(tok) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(JtvError::ParseError(format!(
+                "expected {:?} in symbolic expression",
+                tok
+            )))
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Sym> {
+        let mut lhs = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.advance();
+                    let rhs = self.parse_term()?;
+                    lhs = Sym::Add(Box::new(lhs), Box::new(rhs));
+                }
+                Some(Token::Minus) => {
+                    self.advance();
+                    let rhs = self.parse_term()?;
+                    lhs = Sym::Add(Box::new(lhs), Box::new(Sym::Neg(Box::new(rhs))));
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self) -> Result<Sym> {
+        let mut lhs = self.parse_unary()?;
+        while self.peek() == Some(&Token::Star) {
+            self.advance();
+            let rhs = self.parse_unary()?;
+            lhs = Sym::Mul(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Sym> {
+        if self.peek() == Some(&Token::Minus) {
+            self.advance();
+            return Ok(Sym::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_pow()
+    }
+
+    /// Right-associative: `2^3^2 == 2^(3^2)`.
+    fn parse_pow(&mut self) -> Result<Sym> {
+        let base = self.parse_atom()?;
+        if self.peek() == Some(&Token::Caret) {
+            self.advance();
+            let exp = self.parse_unary()?;
+            return Ok(Sym::Pow(Box::new(base), Box::new(exp)));
+        }
+        Ok(base)
+    }
+
+    fn parse_atom(&mut self) -> Result<Sym> {
+        match self.advance() {
+            Some(Token::Num(n)) => Ok(Sym::num(n)),
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.advance();
+                    let mut args = vec![];
+                    if self.peek() != Some(&Token::RParen) {
+                        args.push(self.parse_expr()?);
+                        while self.peek() == Some(&Token::Comma) {
+                            self.advance();
+                            args.push(self.parse_expr()?);
+                        }
+                    }
+                    self.expect(&Token::RParen)?;
+                    Ok(Sym::Call(name.to_string(), args))
+                } else {
+                    Ok(Sym::Var(name.to_string()))
+                }
+            }
+            Some(Token::LParen) => {
+                let inner = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(inner)
+            }
+            other => Err(JtvError::ParseError(format!(
+                "unexpected token {:?} in symbolic expression",
+                other
+            ))),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Sym> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(JtvError::ParseError(format!(
+            "trailing input in symbolic expression `{}`",
+            input
+        )));
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_round_trips() {
+        let expr = parse("x + 2 * y").unwrap();
+        assert_eq!(expr.to_string(), "x + 2 * y");
+    }
+
+    #[test]
+    fn test_parse_respects_precedence_and_associativity() {
+        let expr = parse("2 + 3 * x ^ 2").unwrap();
+        assert_eq!(
+            expr,
+            Sym::Add(
+                Box::new(Sym::Num(2.0)),
+                Box::new(Sym::Mul(
+                    Box::new(Sym::Num(3.0)),
+                    Box::new(Sym::Pow(Box::new(Sym::Var("x".to_string())), Box::new(Sym::Num(2.0))))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn test_simplify_folds_constants_and_drops_identities() {
+        let expr = parse("x * 1 + 0 + 2 + 3").unwrap();
+        assert_eq!(expr.simplify().to_string(), "5 + x");
+    }
+
+    #[test]
+    fn test_simplify_collapses_mul_by_zero() {
+        let expr = parse("x * 0").unwrap();
+        assert_eq!(expr.simplify(), Sym::Num(0.0));
+    }
+
+    #[test]
+    fn test_expand_distributes_multiplication_over_addition() {
+        let expr = parse("x * (y + 1)").unwrap();
+        assert_eq!(expr.expand().to_string(), "x * y + x");
+    }
+
+    #[test]
+    fn test_substitute_replaces_variable() {
+        let expr = parse("x + y").unwrap();
+        let out = expr.substitute("x", &Sym::Num(5.0)).simplify();
+        assert_eq!(out.to_string(), "5 + y");
+    }
+
+    #[test]
+    fn test_substitute_to_ground_expression() {
+        let expr = parse("x + 1").unwrap();
+        let out = expr.substitute("x", &Sym::Num(5.0)).simplify();
+        assert!(out.is_ground());
+        assert_eq!(out.as_num(), Some(6.0));
+    }
+
+    #[test]
+    fn test_differentiate_power_rule() {
+        let expr = parse("x ^ 3").unwrap();
+        let d = expr.differentiate("x").unwrap().simplify();
+        assert_eq!(d.to_string(), "3 * x^2");
+    }
+
+    #[test]
+    fn test_differentiate_product_rule() {
+        let expr = parse("x * x").unwrap();
+        let d = expr.differentiate("x").unwrap().simplify();
+        assert_eq!(d.to_string(), "x + x");
+    }
+
+    #[test]
+    fn test_differentiate_sum_and_constant_rules() {
+        let expr = parse("x + 5").unwrap();
+        let d = expr.differentiate("x").unwrap().simplify();
+        assert_eq!(d.to_string(), "1");
+    }
+
+    #[test]
+    fn test_differentiate_sin_chain_rule() {
+        let expr = parse("sin(x)").unwrap();
+        let d = expr.differentiate("x").unwrap().simplify();
+        assert_eq!(d.to_string(), "cos(x)");
+    }
+
+    #[test]
+    fn test_differentiate_rejects_non_constant_exponent() {
+        let expr = parse("x ^ x").unwrap();
+        assert!(expr.differentiate("x").is_err());
+    }
+}
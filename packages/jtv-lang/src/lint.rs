@@ -0,0 +1,1210 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Lint registry
+//
+// Style/suspicious-pattern checks, in the spirit of clippy's lint
+// registry. Unlike `TypeChecker`/`PurityChecker`, a lint never fails the
+// build on its own -- `jtv lint` reports each one it finds at its
+// `Lint::default_level()` (`Allow`/`Warn`/`Deny`), and only a `Deny`
+// finding makes the command exit nonzero. A `LintDiagnostic` is
+// deliberately looser than `crate::diagnostics::Diagnostic` (no
+// `expected`/`found` shape) since most lints have nothing to compare --
+// just a name, a level, a message, and a span.
+
+use crate::ast::*;
+use crate::purity::PurityChecker;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warning"),
+            LintLevel::Deny => write!(f, "error"),
+        }
+    }
+}
+
+/// One finding from a `Lint`, already stamped with the level the registry
+/// ran it at (which may differ from the lint's own `default_level()`, if
+/// the caller passed `--warn`/`--deny`/`--allow`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintDiagnostic {
+    pub lint: String,
+    pub level: LintLevel,
+    pub message: String,
+    pub span: Span,
+}
+
+/// A single check that walks a whole `Program` and reports every
+/// violation it finds. `name()` is what `--allow`/`--warn`/`--deny` and
+/// `--format json` identify it by, so it must be unique across the
+/// registry and stable across releases.
+pub trait Lint {
+    fn name(&self) -> &'static str;
+    fn default_level(&self) -> LintLevel;
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic>;
+}
+
+/// Runs every registered `Lint` at its configured level (`Allow` skips
+/// it entirely). Construct with `LintRegistry::new()` for the default
+/// rule set at each lint's `default_level()`, then adjust with
+/// `set_level` for `--allow`/`--warn`/`--deny`.
+pub struct LintRegistry {
+    lints: Vec<Box<dyn Lint>>,
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+impl LintRegistry {
+    pub fn new() -> Self {
+        let lints: Vec<Box<dyn Lint>> = vec![
+            Box::new(UnusedBindings),
+            Box::new(UnreachableAfterReturn),
+            Box::new(DataInControlCondition),
+            Box::new(ShadowedAcrossBoundary),
+            Box::new(DeadEffectFreeComputation),
+        ];
+        let levels = lints.iter().map(|lint| (lint.name(), lint.default_level())).collect();
+        LintRegistry { lints, levels }
+    }
+
+    /// Every lint's name, for validating a `--allow`/`--warn`/`--deny`
+    /// argument and for listing the rule set to a user.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.lints.iter().map(|lint| lint.name())
+    }
+
+    /// Overrides `name`'s level. Returns `false` (and changes nothing) if
+    /// `name` doesn't match a registered lint, so the CLI can report an
+    /// unknown `--allow`/`--warn`/`--deny` argument instead of silently
+    /// ignoring it.
+    pub fn set_level(&mut self, name: &str, level: LintLevel) -> bool {
+        match self.lints.iter().find(|lint| lint.name() == name) {
+            Some(lint) => {
+                self.levels.insert(lint.name(), level);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs every lint whose current level isn't `Allow`, stamping each
+    /// finding with that level (overriding whatever `Lint::check` set, so
+    /// a `--deny` override is honored even though the lint's own code
+    /// still thinks of itself as a warning).
+    pub fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut out = Vec::new();
+        for lint in &self.lints {
+            let level = self.levels[lint.name()];
+            if level == LintLevel::Allow {
+                continue;
+            }
+            for mut diagnostic in lint.check(program) {
+                diagnostic.level = level;
+                out.push(diagnostic);
+            }
+        }
+        out
+    }
+}
+
+impl Default for LintRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Walks every `FunctionDecl`/`TestDecl` body in `program` (including ones
+/// nested in a `ModuleDecl`), calling `f` with the body and the span to
+/// attribute a finding to -- the shared traversal every lint below needs,
+/// since none of them care about top-level `Import`/`Struct` items or
+/// bare `Control` statements.
+fn for_each_body<'a>(program: &'a Program, mut f: impl FnMut(&'a [ControlStmt], Span)) {
+    fn walk<'a>(items: &'a [TopLevel], f: &mut impl FnMut(&'a [ControlStmt], Span)) {
+        for item in items {
+            match item {
+                TopLevel::Function(func) => f(&func.body, func.span),
+                TopLevel::Test(test) => f(&test.body, test.span),
+                TopLevel::Module(module) => walk(&module.body, f),
+                TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Control(_) => {}
+            }
+        }
+    }
+    walk(&program.statements, &mut f);
+}
+
+/// Collects every identifier read by `data`, recursing through every
+/// `DataExpr`/`ControlExpr` shape that can embed another one -- `Add`'s
+/// operands, a `FunctionCall`'s arguments, a `ListComprehension`'s body/
+/// generators/condition, a `StructLiteral`'s field values, and so on.
+fn collect_reads_data(data: &DataExpr, out: &mut HashSet<String>) {
+    match data {
+        DataExpr::Number(_) => {}
+        DataExpr::Identifier(name) => {
+            out.insert(name.clone());
+        }
+        DataExpr::Add(left, right) => {
+            collect_reads_data(left, out);
+            collect_reads_data(right, out);
+        }
+        DataExpr::Negate(inner) => collect_reads_data(inner, out),
+        DataExpr::FunctionCall(call) => {
+            for arg in &call.args {
+                collect_reads_data(arg, out);
+            }
+        }
+        DataExpr::List(items) | DataExpr::Tuple(items) => {
+            for item in items {
+                collect_reads_data(item, out);
+            }
+        }
+        DataExpr::FieldAccess(inner, _) => collect_reads_data(inner, out),
+        DataExpr::Index(inner, index) => {
+            collect_reads_data(inner, out);
+            collect_reads_data(index, out);
+        }
+        DataExpr::StructLiteral(_, fields) => {
+            for (_, value) in fields {
+                collect_reads_data(value, out);
+            }
+        }
+        DataExpr::ListComprehension(comprehension) => {
+            collect_reads_data(&comprehension.body, out);
+            for (_, source) in &comprehension.generators {
+                collect_reads_data(source, out);
+            }
+            if let Some(condition) = &comprehension.condition {
+                collect_reads_control(condition, out);
+            }
+        }
+    }
+}
+
+fn collect_reads_control(control: &ControlExpr, out: &mut HashSet<String>) {
+    match control {
+        ControlExpr::Data(data) => collect_reads_data(data, out),
+        ControlExpr::Comparison(left, _, right) => {
+            collect_reads_data(left, out);
+            collect_reads_data(right, out);
+        }
+        ControlExpr::Logical(left, _, right) => {
+            collect_reads_control(left, out);
+            collect_reads_control(right, out);
+        }
+        ControlExpr::Not(inner) => collect_reads_control(inner, out),
+        ControlExpr::Contains(left, right) => {
+            collect_reads_data(left, out);
+            collect_reads_data(right, out);
+        }
+    }
+}
+
+fn collect_reads_expr(expr: &Expr, out: &mut HashSet<String>) {
+    match expr {
+        Expr::Data(data) => collect_reads_data(data, out),
+        Expr::Control(control) => collect_reads_control(control, out),
+    }
+}
+
+fn collect_reads_stmt(stmt: &ControlStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ControlStmt::Assignment(assign) => collect_reads_expr(&assign.value, out),
+        ControlStmt::If(if_stmt) => {
+            collect_reads_control(&if_stmt.condition, out);
+            for stmt in &if_stmt.then_branch {
+                collect_reads_stmt(stmt, out);
+            }
+            for stmt in if_stmt.else_branch.iter().flatten() {
+                collect_reads_stmt(stmt, out);
+            }
+        }
+        ControlStmt::While(while_stmt) => {
+            collect_reads_control(&while_stmt.condition, out);
+            for stmt in &while_stmt.body {
+                collect_reads_stmt(stmt, out);
+            }
+        }
+        ControlStmt::For(for_stmt) => {
+            collect_reads_data(&for_stmt.range.start, out);
+            collect_reads_data(&for_stmt.range.end, out);
+            if let Some(step) = &for_stmt.range.step {
+                collect_reads_data(step, out);
+            }
+            for stmt in &for_stmt.body {
+                collect_reads_stmt(stmt, out);
+            }
+        }
+        ControlStmt::Return(expr) => {
+            if let Some(expr) = expr {
+                collect_reads_data(expr, out);
+            }
+        }
+        ControlStmt::Print(exprs) => {
+            for expr in exprs {
+                collect_reads_data(expr, out);
+            }
+        }
+        ControlStmt::ReverseBlock(block) => {
+            for stmt in &block.body {
+                collect_reads_reversible_stmt(stmt, out);
+            }
+        }
+        ControlStmt::Block(stmts) => {
+            for stmt in stmts {
+                collect_reads_stmt(stmt, out);
+            }
+        }
+        ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+    }
+}
+
+fn collect_reads_reversible_stmt(stmt: &ReversibleStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ReversibleStmt::AddAssign(_, expr)
+        | ReversibleStmt::SubAssign(_, expr)
+        | ReversibleStmt::MulAssign(_, expr)
+        | ReversibleStmt::DivAssign(_, expr)
+        | ReversibleStmt::Assign(_, expr) => {
+            collect_reads_data(expr, out);
+        }
+        ReversibleStmt::If(if_stmt) => {
+            collect_reads_control(&if_stmt.condition, out);
+            for stmt in &if_stmt.then_branch {
+                collect_reads_stmt(stmt, out);
+            }
+            for stmt in if_stmt.else_branch.iter().flatten() {
+                collect_reads_stmt(stmt, out);
+            }
+        }
+        ReversibleStmt::For { from, to, step, body, .. } => {
+            collect_reads_data(from, out);
+            collect_reads_data(to, out);
+            if let Some(step) = step {
+                collect_reads_data(step, out);
+            }
+            for stmt in body {
+                collect_reads_reversible_stmt(stmt, out);
+            }
+        }
+        ReversibleStmt::Switch { scrutinee, cases, default } => {
+            collect_reads_data(scrutinee, out);
+            for (value, body) in cases {
+                collect_reads_data(value, out);
+                for stmt in body {
+                    collect_reads_reversible_stmt(stmt, out);
+                }
+            }
+            for stmt in default.iter().flatten() {
+                collect_reads_reversible_stmt(stmt, out);
+            }
+        }
+    }
+}
+
+/// Flags an `Assignment` whose target is never read again anywhere else
+/// in the same body -- a binding computed and then dropped on the floor,
+/// almost always either a typo or dead code. Conservative by design: a
+/// name assigned more than once, or reassigned to itself, only counts as
+/// unused if none of its occurrences are ever read.
+struct UnusedBindings;
+
+impl Lint for UnusedBindings {
+    fn name(&self) -> &'static str {
+        "unused_bindings"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut out = Vec::new();
+        for_each_body(program, |body, span| {
+            let mut reads = HashSet::new();
+            for stmt in body {
+                collect_reads_stmt(stmt, &mut reads);
+            }
+            let mut reported = HashSet::new();
+            for stmt in body {
+                if let ControlStmt::Assignment(assign) = stmt {
+                    if !reads.contains(&assign.target) && reported.insert(assign.target.clone()) {
+                        out.push(LintDiagnostic {
+                            lint: self.name().to_string(),
+                            level: self.default_level(),
+                            message: format!("binding `{}` is never read", assign.target),
+                            span,
+                        });
+                    }
+                }
+            }
+        });
+        out
+    }
+}
+
+/// Flags every statement after a `Return` in the same block -- it can
+/// never execute, the same way rustc's `unreachable_code` catches dead
+/// code after a `return`/`panic!`/diverging match.
+struct UnreachableAfterReturn;
+
+impl Lint for UnreachableAfterReturn {
+    fn name(&self) -> &'static str {
+        "unreachable_after_return"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut out = Vec::new();
+        for_each_body(program, |body, span| {
+            self.check_block(body, span, &mut out);
+        });
+        out
+    }
+}
+
+impl UnreachableAfterReturn {
+    fn check_block(&self, body: &[ControlStmt], span: Span, out: &mut Vec<LintDiagnostic>) {
+        if let Some(return_pos) = body.iter().position(|stmt| matches!(stmt, ControlStmt::Return(_))) {
+            if return_pos + 1 < body.len() {
+                out.push(LintDiagnostic {
+                    lint: self.name().to_string(),
+                    level: self.default_level(),
+                    message: format!(
+                        "{} statement(s) after this `return` can never execute",
+                        body.len() - return_pos - 1
+                    ),
+                    span,
+                });
+            }
+        }
+        for stmt in body {
+            match stmt {
+                ControlStmt::If(if_stmt) => {
+                    self.check_block(&if_stmt.then_branch, span, out);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        self.check_block(else_branch, span, out);
+                    }
+                }
+                ControlStmt::While(while_stmt) => self.check_block(&while_stmt.body, span, out),
+                ControlStmt::For(for_stmt) => self.check_block(&for_stmt.body, span, out),
+                ControlStmt::Block(stmts) => self.check_block(stmts, span, out),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Does `data` embed a `Number::Symbolic` literal anywhere? `Symbolic`
+/// wraps an arbitrary, unvalidated string (see `crate::ast::Number`), so
+/// routing one directly into a `ControlExpr` condition lets Data-region
+/// content steer Control-region flow -- exactly the coupling JtV's
+/// Harvard-architecture split (Control is Turing-complete and trusted;
+/// Data is total and untrusted) exists to prevent.
+fn contains_symbolic(data: &DataExpr) -> bool {
+    match data {
+        DataExpr::Number(Number::Symbolic(_)) => true,
+        DataExpr::Number(_) | DataExpr::Identifier(_) => false,
+        DataExpr::Add(left, right) => contains_symbolic(left) || contains_symbolic(right),
+        DataExpr::Negate(inner) => contains_symbolic(inner),
+        DataExpr::FunctionCall(call) => call.args.iter().any(contains_symbolic),
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter().any(contains_symbolic),
+        DataExpr::FieldAccess(inner, _) => contains_symbolic(inner),
+        DataExpr::Index(inner, index) => contains_symbolic(inner) || contains_symbolic(index),
+        DataExpr::StructLiteral(_, fields) => fields.iter().any(|(_, value)| contains_symbolic(value)),
+        DataExpr::ListComprehension(comprehension) => contains_symbolic(&comprehension.body),
+    }
+}
+
+/// Flags a `ControlExpr::Comparison`/`Logical`/`Not` whose `DataExpr`
+/// operand embeds a `Symbolic` literal (see `contains_symbolic`).
+struct DataInControlCondition;
+
+impl Lint for DataInControlCondition {
+    fn name(&self) -> &'static str {
+        "data_in_control_condition"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut out = Vec::new();
+        for_each_body(program, |body, span| {
+            self.check_block(body, span, &mut out);
+        });
+        out
+    }
+}
+
+impl DataInControlCondition {
+    fn check_condition(&self, condition: &ControlExpr, span: Span, out: &mut Vec<LintDiagnostic>) {
+        match condition {
+            ControlExpr::Comparison(left, _, right) => {
+                if contains_symbolic(left) || contains_symbolic(right) {
+                    out.push(LintDiagnostic {
+                        lint: self.name().to_string(),
+                        level: self.default_level(),
+                        message: "a Symbolic value is compared directly in a control condition \
+                                  -- Data-region content is steering Control-region flow"
+                            .to_string(),
+                        span,
+                    });
+                }
+            }
+            ControlExpr::Logical(left, _, right) => {
+                self.check_condition(left, span, out);
+                self.check_condition(right, span, out);
+            }
+            ControlExpr::Not(inner) => self.check_condition(inner, span, out),
+            ControlExpr::Contains(left, right) => {
+                if contains_symbolic(left) || contains_symbolic(right) {
+                    out.push(LintDiagnostic {
+                        lint: self.name().to_string(),
+                        level: self.default_level(),
+                        message: "a Symbolic value is compared directly in a control condition \
+                                  -- Data-region content is steering Control-region flow"
+                            .to_string(),
+                        span,
+                    });
+                }
+            }
+            ControlExpr::Data(_) => {}
+        }
+    }
+
+    fn check_block(&self, body: &[ControlStmt], span: Span, out: &mut Vec<LintDiagnostic>) {
+        for stmt in body {
+            match stmt {
+                ControlStmt::If(if_stmt) => {
+                    self.check_condition(&if_stmt.condition, span, out);
+                    self.check_block(&if_stmt.then_branch, span, out);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        self.check_block(else_branch, span, out);
+                    }
+                }
+                ControlStmt::While(while_stmt) => {
+                    self.check_condition(&while_stmt.condition, span, out);
+                    self.check_block(&while_stmt.body, span, out);
+                }
+                ControlStmt::For(for_stmt) => self.check_block(&for_stmt.body, span, out),
+                ControlStmt::Block(stmts) => self.check_block(stmts, span, out),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Flags a `ControlStmt::Assignment` whose target name collides with a
+/// Data-region declaration (a top-level `Function` or `Struct` name) --
+/// a Control-region variable shadowing a Data-region declaration across
+/// the boundary, which makes it easy to misread which one a later
+/// expression actually refers to.
+struct ShadowedAcrossBoundary;
+
+impl Lint for ShadowedAcrossBoundary {
+    fn name(&self) -> &'static str {
+        "shadowed_across_boundary"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut data_names = HashSet::new();
+        fn index(items: &[TopLevel], out: &mut HashSet<String>) {
+            for item in items {
+                match item {
+                    TopLevel::Function(func) => {
+                        out.insert(func.name.clone());
+                    }
+                    TopLevel::Struct(decl) => {
+                        out.insert(decl.name.clone());
+                    }
+                    TopLevel::Module(module) => index(&module.body, out),
+                    TopLevel::Import(_) | TopLevel::Test(_) | TopLevel::Control(_) => {}
+                }
+            }
+        }
+        index(&program.statements, &mut data_names);
+
+        let mut out = Vec::new();
+        for_each_body(program, |body, span| {
+            self.check_block(body, span, &data_names, &mut out);
+        });
+        out
+    }
+}
+
+impl ShadowedAcrossBoundary {
+    fn check_block(
+        &self,
+        body: &[ControlStmt],
+        span: Span,
+        data_names: &HashSet<String>,
+        out: &mut Vec<LintDiagnostic>,
+    ) {
+        for stmt in body {
+            match stmt {
+                ControlStmt::Assignment(assign) if data_names.contains(&assign.target) => {
+                    out.push(LintDiagnostic {
+                        lint: self.name().to_string(),
+                        level: self.default_level(),
+                        message: format!(
+                            "variable `{}` shadows a Data-region function/struct of the same name",
+                            assign.target
+                        ),
+                        span,
+                    });
+                }
+                ControlStmt::Assignment(_) => {}
+                ControlStmt::If(if_stmt) => {
+                    self.check_block(&if_stmt.then_branch, span, data_names, out);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        self.check_block(else_branch, span, data_names, out);
+                    }
+                }
+                ControlStmt::While(while_stmt) => self.check_block(&while_stmt.body, span, data_names, out),
+                ControlStmt::For(for_stmt) => self.check_block(&for_stmt.body, span, data_names, out),
+                ControlStmt::Block(stmts) => self.check_block(stmts, span, data_names, out),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Does `data` provably perform no observable effect -- every `FunctionCall`
+/// it reaches resolves, through `levels` (`PurityChecker::infer_program`'s
+/// result), to `Pure` or `Total`? An unresolved name is treated the same
+/// conservative way `infer_program` itself treats one: not effect-free.
+fn effect_free_data(data: &DataExpr, levels: &HashMap<String, Purity>) -> bool {
+    match data {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => true,
+        DataExpr::Add(left, right) => effect_free_data(left, levels) && effect_free_data(right, levels),
+        DataExpr::Negate(inner) => effect_free_data(inner, levels),
+        DataExpr::FunctionCall(call) => {
+            matches!(levels.get(&call.name), Some(Purity::Pure) | Some(Purity::Total))
+                && call.args.iter().all(|arg| effect_free_data(arg, levels))
+        }
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter().all(|item| effect_free_data(item, levels)),
+        DataExpr::FieldAccess(inner, _) => effect_free_data(inner, levels),
+        DataExpr::Index(inner, index) => effect_free_data(inner, levels) && effect_free_data(index, levels),
+        DataExpr::StructLiteral(_, fields) => fields.iter().all(|(_, value)| effect_free_data(value, levels)),
+        DataExpr::ListComprehension(comprehension) => {
+            effect_free_data(&comprehension.body, levels)
+                && comprehension.generators.iter().all(|(_, source)| effect_free_data(source, levels))
+                && comprehension
+                    .condition
+                    .as_ref()
+                    .map_or(true, |condition| effect_free_control(condition, levels))
+        }
+    }
+}
+
+fn effect_free_control(control: &ControlExpr, levels: &HashMap<String, Purity>) -> bool {
+    match control {
+        ControlExpr::Data(data) => effect_free_data(data, levels),
+        ControlExpr::Comparison(left, _, right) => effect_free_data(left, levels) && effect_free_data(right, levels),
+        ControlExpr::Logical(left, _, right) => effect_free_control(left, levels) && effect_free_control(right, levels),
+        ControlExpr::Not(inner) => effect_free_control(inner, levels),
+        ControlExpr::Contains(left, right) => effect_free_data(left, levels) && effect_free_data(right, levels),
+    }
+}
+
+/// Does `stmt` (and everything nested inside it) provably perform no
+/// observable effect? A `Print` never is; a `ReverseBlock` never is either
+/// -- a reversible `+=`/`-=` always mutates a named target by definition,
+/// and this checker doesn't track whether that target ever escapes.
+fn effect_free_stmt(stmt: &ControlStmt, levels: &HashMap<String, Purity>) -> bool {
+    match stmt {
+        ControlStmt::Assignment(assign) => match &assign.value {
+            Expr::Data(data) => effect_free_data(data, levels),
+            Expr::Control(control) => effect_free_control(control, levels),
+        },
+        ControlStmt::If(if_stmt) => {
+            effect_free_control(&if_stmt.condition, levels)
+                && if_stmt.then_branch.iter().all(|s| effect_free_stmt(s, levels))
+                && if_stmt
+                    .else_branch
+                    .as_ref()
+                    .map_or(true, |branch| branch.iter().all(|s| effect_free_stmt(s, levels)))
+        }
+        ControlStmt::While(while_stmt) => {
+            effect_free_control(&while_stmt.condition, levels)
+                && while_stmt.body.iter().all(|s| effect_free_stmt(s, levels))
+        }
+        ControlStmt::For(for_stmt) => {
+            effect_free_data(&for_stmt.range.start, levels)
+                && effect_free_data(&for_stmt.range.end, levels)
+                && for_stmt.range.step.as_ref().map_or(true, |step| effect_free_data(step, levels))
+                && for_stmt.body.iter().all(|s| effect_free_stmt(s, levels))
+        }
+        ControlStmt::Return(expr) => expr.as_ref().map_or(true, |expr| effect_free_data(expr, levels)),
+        ControlStmt::Print(_) => false,
+        ControlStmt::ReverseBlock(_) => false,
+        ControlStmt::Block(stmts) => stmts.iter().all(|s| effect_free_stmt(s, levels)),
+        ControlStmt::Break(_) | ControlStmt::Continue(_) => true,
+    }
+}
+
+/// Does `value` contain a `FunctionCall` anywhere? Scopes
+/// `DeadEffectFreeComputation`'s dead-assignment check to RHSes that
+/// actually run a computation worth naming -- a bare literal/identifier
+/// assignment going unread is already `UnusedBindings`'s concern.
+fn expr_contains_call(value: &Expr) -> bool {
+    match value {
+        Expr::Data(data) => data_contains_call(data),
+        Expr::Control(control) => control_contains_call(control),
+    }
+}
+
+fn data_contains_call(data: &DataExpr) -> bool {
+    match data {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => false,
+        DataExpr::FunctionCall(_) => true,
+        DataExpr::Add(left, right) => data_contains_call(left) || data_contains_call(right),
+        DataExpr::Negate(inner) => data_contains_call(inner),
+        DataExpr::List(items) | DataExpr::Tuple(items) => items.iter().any(data_contains_call),
+        DataExpr::FieldAccess(inner, _) => data_contains_call(inner),
+        DataExpr::Index(inner, index) => data_contains_call(inner) || data_contains_call(index),
+        DataExpr::StructLiteral(_, fields) => fields.iter().any(|(_, value)| data_contains_call(value)),
+        DataExpr::ListComprehension(comprehension) => {
+            data_contains_call(&comprehension.body)
+                || comprehension.generators.iter().any(|(_, source)| data_contains_call(source))
+        }
+    }
+}
+
+fn control_contains_call(control: &ControlExpr) -> bool {
+    match control {
+        ControlExpr::Data(data) => data_contains_call(data),
+        ControlExpr::Comparison(left, _, right) => data_contains_call(left) || data_contains_call(right),
+        ControlExpr::Logical(left, _, right) => control_contains_call(left) || control_contains_call(right),
+        ControlExpr::Not(inner) => control_contains_call(inner),
+        ControlExpr::Contains(left, right) => data_contains_call(left) || data_contains_call(right),
+    }
+}
+
+/// Does `stmts` contain a `Return` anywhere, including nested inside an
+/// `If`/`While`/`For`/`Block`? Used to recognize an `If` with no `Return`
+/// in either branch, since one that does return is shaping control flow
+/// even if both branches are themselves effect-free.
+fn contains_return(stmts: &[ControlStmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        ControlStmt::Return(_) => true,
+        ControlStmt::If(if_stmt) => {
+            contains_return(&if_stmt.then_branch)
+                || if_stmt.else_branch.as_ref().map_or(false, |branch| contains_return(branch))
+        }
+        ControlStmt::While(while_stmt) => contains_return(&while_stmt.body),
+        ControlStmt::For(for_stmt) => contains_return(&for_stmt.body),
+        ControlStmt::Block(stmts) => contains_return(stmts),
+        ControlStmt::Assignment(_)
+        | ControlStmt::Print(_)
+        | ControlStmt::ReverseBlock(_)
+        | ControlStmt::Break(_)
+        | ControlStmt::Continue(_) => false,
+    })
+}
+
+/// Collects every name `stmt` assigns (including a `For`'s own loop
+/// variable and a reversible `+=`/`-=`'s target), recursing into nested
+/// blocks -- the writes a `While`/`For` loop is responsible for, used to
+/// tell whether any of them is ever read.
+fn collect_assigned_names(stmt: &ControlStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ControlStmt::Assignment(assign) => {
+            out.insert(assign.target.clone());
+        }
+        ControlStmt::If(if_stmt) => {
+            for stmt in &if_stmt.then_branch {
+                collect_assigned_names(stmt, out);
+            }
+            for stmt in if_stmt.else_branch.iter().flatten() {
+                collect_assigned_names(stmt, out);
+            }
+        }
+        ControlStmt::While(while_stmt) => {
+            for stmt in &while_stmt.body {
+                collect_assigned_names(stmt, out);
+            }
+        }
+        ControlStmt::For(for_stmt) => {
+            out.insert(for_stmt.variable.clone());
+            for stmt in &for_stmt.body {
+                collect_assigned_names(stmt, out);
+            }
+        }
+        ControlStmt::ReverseBlock(block) => {
+            for stmt in &block.body {
+                collect_assigned_names_reversible(stmt, out);
+            }
+        }
+        ControlStmt::Block(stmts) => {
+            for stmt in stmts {
+                collect_assigned_names(stmt, out);
+            }
+        }
+        ControlStmt::Return(_) | ControlStmt::Print(_) | ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+    }
+}
+
+fn collect_assigned_names_reversible(stmt: &ReversibleStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ReversibleStmt::AddAssign(target, _)
+        | ReversibleStmt::SubAssign(target, _)
+        | ReversibleStmt::MulAssign(target, _)
+        | ReversibleStmt::DivAssign(target, _)
+        | ReversibleStmt::Assign(target, _) => {
+            out.insert(target.clone());
+        }
+        ReversibleStmt::If(if_stmt) => {
+            for stmt in &if_stmt.then_branch {
+                collect_assigned_names(stmt, out);
+            }
+            for stmt in if_stmt.else_branch.iter().flatten() {
+                collect_assigned_names(stmt, out);
+            }
+        }
+        ReversibleStmt::For { var, body, .. } => {
+            out.insert(var.clone());
+            for stmt in body {
+                collect_assigned_names_reversible(stmt, out);
+            }
+        }
+        ReversibleStmt::Switch { cases, default, .. } => {
+            for (_, body) in cases {
+                for stmt in body {
+                    collect_assigned_names_reversible(stmt, out);
+                }
+            }
+            for stmt in default.iter().flatten() {
+                collect_assigned_names_reversible(stmt, out);
+            }
+        }
+    }
+}
+
+/// Flags three shapes `PurityChecker::infer_program` can prove have no
+/// observable effect at all, borrowing `UnusedBindings`'s "evaluated but
+/// never read" idea and extending it with purity information:
+///
+/// - an `Assignment` whose RHS calls a `Pure`/`Total`-inferred function
+///   (so it isn't just `UnusedBindings`'s plain-literal case) and whose
+///   target is never read anywhere else in the body;
+/// - a `While`/`For` whose condition/range and body are entirely
+///   effect-free and none of whose writes are ever read elsewhere in the
+///   same statement list -- the whole loop computes nothing anyone uses;
+/// - a `Return`-less `If` whose condition and both branches are entirely
+///   effect-free, so it can't influence anything the function returns or
+///   performs.
+///
+/// Like `UnusedBindings`, this never reports a write it can't prove is
+/// dead -- an unresolved callee or an inferred-`Impure` call anywhere in a
+/// candidate silently disqualifies it.
+struct DeadEffectFreeComputation;
+
+impl Lint for DeadEffectFreeComputation {
+    fn name(&self) -> &'static str {
+        "dead_effect_free_computation"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    fn check(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let levels = PurityChecker::infer_program(program);
+        let mut out = Vec::new();
+        for_each_body(program, |body, span| {
+            let mut reads = HashSet::new();
+            for stmt in body {
+                collect_reads_stmt(stmt, &mut reads);
+            }
+            self.check_block(body, span, &levels, &reads, &mut out);
+        });
+        out
+    }
+}
+
+impl DeadEffectFreeComputation {
+    fn check_block(
+        &self,
+        body: &[ControlStmt],
+        span: Span,
+        levels: &HashMap<String, Purity>,
+        reads: &HashSet<String>,
+        out: &mut Vec<LintDiagnostic>,
+    ) {
+        for (i, stmt) in body.iter().enumerate() {
+            match stmt {
+                ControlStmt::Assignment(assign) => {
+                    if !reads.contains(&assign.target)
+                        && expr_contains_call(&assign.value)
+                        && match &assign.value {
+                            Expr::Data(data) => effect_free_data(data, levels),
+                            Expr::Control(control) => effect_free_control(control, levels),
+                        }
+                    {
+                        out.push(LintDiagnostic {
+                            lint: self.name().to_string(),
+                            level: self.default_level(),
+                            message: format!(
+                                "assignment `{}` computes a provably pure/total value that is never read",
+                                assign.target
+                            ),
+                            span,
+                        });
+                    }
+                }
+                ControlStmt::If(if_stmt) => {
+                    let no_return = !contains_return(&if_stmt.then_branch)
+                        && !if_stmt.else_branch.as_ref().map_or(false, |branch| contains_return(branch));
+                    if no_return
+                        && effect_free_control(&if_stmt.condition, levels)
+                        && if_stmt.then_branch.iter().all(|s| effect_free_stmt(s, levels))
+                        && if_stmt
+                            .else_branch
+                            .as_ref()
+                            .map_or(true, |branch| branch.iter().all(|s| effect_free_stmt(s, levels)))
+                    {
+                        out.push(LintDiagnostic {
+                            lint: self.name().to_string(),
+                            level: self.default_level(),
+                            message: "this `if` never returns and both branches are provably effect-free"
+                                .to_string(),
+                            span,
+                        });
+                    }
+                    self.check_block(&if_stmt.then_branch, span, levels, reads, out);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        self.check_block(else_branch, span, levels, reads, out);
+                    }
+                }
+                ControlStmt::While(while_stmt) => {
+                    let effect_free = effect_free_control(&while_stmt.condition, levels)
+                        && while_stmt.body.iter().all(|s| effect_free_stmt(s, levels));
+                    if effect_free {
+                        self.check_unread_loop_writes(&while_stmt.body, body, i, span, out);
+                    }
+                    self.check_block(&while_stmt.body, span, levels, reads, out);
+                }
+                ControlStmt::For(for_stmt) => {
+                    let range_effect_free = effect_free_data(&for_stmt.range.start, levels)
+                        && effect_free_data(&for_stmt.range.end, levels)
+                        && for_stmt.range.step.as_ref().map_or(true, |step| effect_free_data(step, levels));
+                    if range_effect_free && for_stmt.body.iter().all(|s| effect_free_stmt(s, levels)) {
+                        self.check_unread_loop_writes(&for_stmt.body, body, i, span, out);
+                    }
+                    self.check_block(&for_stmt.body, span, levels, reads, out);
+                }
+                ControlStmt::Block(stmts) => self.check_block(stmts, span, levels, reads, out),
+                ControlStmt::Return(_)
+                | ControlStmt::Print(_)
+                | ControlStmt::ReverseBlock(_)
+                | ControlStmt::Break(_)
+                | ControlStmt::Continue(_) => {}
+            }
+        }
+    }
+
+    /// Is every name `loop_body` writes left unread by every *other*
+    /// statement in `enclosing_body` (the loop's own body is excluded, so
+    /// a loop variable it merely uses to compute its own next iteration
+    /// doesn't count as "observed")? Scoped to the loop's immediate
+    /// enclosing statement list rather than the whole function, the same
+    /// locally-scoped tradeoff `optimizer`'s common-subexpression pass
+    /// makes -- a write only read after control returns to an *outer*
+    /// block still goes unflagged, favoring fewer false positives over
+    /// catching every dead loop.
+    fn check_unread_loop_writes(
+        &self,
+        loop_body: &[ControlStmt],
+        enclosing_body: &[ControlStmt],
+        index: usize,
+        span: Span,
+        out: &mut Vec<LintDiagnostic>,
+    ) {
+        let mut assigned = HashSet::new();
+        for stmt in loop_body {
+            collect_assigned_names(stmt, &mut assigned);
+        }
+
+        let mut reads_elsewhere = HashSet::new();
+        for (j, other) in enclosing_body.iter().enumerate() {
+            if j != index {
+                collect_reads_stmt(other, &mut reads_elsewhere);
+            }
+        }
+
+        if !assigned.is_empty() && assigned.iter().all(|name| !reads_elsewhere.contains(name)) {
+            out.push(LintDiagnostic {
+                lint: self.name().to_string(),
+                level: self.default_level(),
+                message: "this loop is provably effect-free and none of its writes are ever read".to_string(),
+                span,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program_with(func: FunctionDecl) -> Program {
+        Program { statements: vec![TopLevel::Function(func)], span: Span::unknown() }
+    }
+
+    fn trivial_function(name: &str, body: Vec<ControlStmt>) -> FunctionDecl {
+        FunctionDecl {
+            name: name.to_string(),
+            type_params: vec![],
+            params: vec![],
+            return_type: None,
+            purity: Purity::Impure,
+            body,
+            span: Span::unknown(),
+            trivia: Trivia::default(),
+        }
+    }
+
+    #[test]
+    fn test_unused_binding_is_flagged() {
+        let body = vec![
+            ControlStmt::Assignment(Assignment { target: "x".to_string(), value: Expr::Data(DataExpr::number(Number::int(1))) }),
+            ControlStmt::Return(None),
+        ];
+        let program = program_with(trivial_function("f", body));
+        let findings = UnusedBindings.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("`x`"));
+    }
+
+    #[test]
+    fn test_binding_read_in_return_is_not_flagged() {
+        let body = vec![
+            ControlStmt::Assignment(Assignment { target: "x".to_string(), value: Expr::Data(DataExpr::number(Number::int(1))) }),
+            ControlStmt::Return(Some(DataExpr::identifier("x"))),
+        ];
+        let program = program_with(trivial_function("f", body));
+        assert!(UnusedBindings.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_after_return_is_flagged() {
+        let body = vec![
+            ControlStmt::Return(Some(DataExpr::number(Number::int(1)))),
+            ControlStmt::Print(vec![DataExpr::number(Number::int(2))]),
+        ];
+        let program = program_with(trivial_function("f", body));
+        let findings = UnreachableAfterReturn.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains('1'));
+    }
+
+    #[test]
+    fn test_return_as_last_statement_is_not_flagged() {
+        let body = vec![ControlStmt::Return(Some(DataExpr::number(Number::int(1))))];
+        let program = program_with(trivial_function("f", body));
+        assert!(UnreachableAfterReturn.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_symbolic_in_condition_is_flagged() {
+        let condition = ControlExpr::Comparison(
+            Box::new(DataExpr::Number(Number::Symbolic("x".to_string()))),
+            Comparator::Eq,
+            Box::new(DataExpr::number(Number::int(0))),
+        );
+        let body = vec![ControlStmt::If(IfStmt { condition, then_branch: vec![], else_branch: None })];
+        let program = program_with(trivial_function("f", body));
+        let findings = DataInControlCondition.check(&program);
+        assert_eq!(findings.len(), 1);
+    }
+
+    #[test]
+    fn test_numeric_condition_is_not_flagged() {
+        let condition = ControlExpr::Comparison(
+            Box::new(DataExpr::number(Number::int(1))),
+            Comparator::Eq,
+            Box::new(DataExpr::number(Number::int(0))),
+        );
+        let body = vec![ControlStmt::If(IfStmt { condition, then_branch: vec![], else_branch: None })];
+        let program = program_with(trivial_function("f", body));
+        assert!(DataInControlCondition.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_shadowed_across_boundary_is_flagged() {
+        let helper = trivial_function("helper", vec![ControlStmt::Return(None)]);
+        let main_fn = trivial_function(
+            "main",
+            vec![ControlStmt::Assignment(Assignment {
+                target: "helper".to_string(),
+                value: Expr::Data(DataExpr::number(Number::int(1))),
+            })],
+        );
+        let program = Program {
+            statements: vec![TopLevel::Function(helper), TopLevel::Function(main_fn)],
+            span: Span::unknown(),
+        };
+        let findings = ShadowedAcrossBoundary.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("`helper`"));
+    }
+
+    #[test]
+    fn test_registry_skips_allowed_lints() {
+        let mut registry = LintRegistry::new();
+        registry.set_level("unreachable_after_return", LintLevel::Allow);
+        let body = vec![
+            ControlStmt::Return(Some(DataExpr::number(Number::int(1)))),
+            ControlStmt::Print(vec![DataExpr::number(Number::int(2))]),
+        ];
+        let program = program_with(trivial_function("f", body));
+        assert!(registry.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_registry_set_level_rejects_unknown_lint() {
+        let mut registry = LintRegistry::new();
+        assert!(!registry.set_level("not_a_real_lint", LintLevel::Deny));
+    }
+
+    #[test]
+    fn test_registry_stamps_overridden_level() {
+        let mut registry = LintRegistry::new();
+        registry.set_level("unreachable_after_return", LintLevel::Deny);
+        let body = vec![
+            ControlStmt::Return(Some(DataExpr::number(Number::int(1)))),
+            ControlStmt::Print(vec![DataExpr::number(Number::int(2))]),
+        ];
+        let program = program_with(trivial_function("f", body));
+        let findings = registry.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].level, LintLevel::Deny);
+    }
+
+    #[test]
+    fn test_dead_pure_call_assignment_is_flagged() {
+        let helper = trivial_function("double", vec![ControlStmt::Return(Some(DataExpr::add(
+            DataExpr::identifier("n"),
+            DataExpr::identifier("n"),
+        )))]);
+        let main_fn = trivial_function(
+            "main",
+            vec![
+                ControlStmt::Assignment(Assignment {
+                    target: "unused".to_string(),
+                    value: Expr::Data(DataExpr::FunctionCall(FunctionCall {
+                        name: "double".to_string(),
+                        args: vec![DataExpr::number(Number::int(2))],
+                    })),
+                }),
+                ControlStmt::Return(None),
+            ],
+        );
+        let program = Program {
+            statements: vec![TopLevel::Function(helper), TopLevel::Function(main_fn)],
+            span: Span::unknown(),
+        };
+        let findings = DeadEffectFreeComputation.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("`unused`"));
+    }
+
+    #[test]
+    fn test_plain_literal_assignment_is_not_flagged_by_this_lint() {
+        let body = vec![
+            ControlStmt::Assignment(Assignment { target: "x".to_string(), value: Expr::Data(DataExpr::number(Number::int(1))) }),
+            ControlStmt::Return(None),
+        ];
+        let program = program_with(trivial_function("f", body));
+        assert!(DeadEffectFreeComputation.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_effect_free_while_with_no_later_read_is_flagged() {
+        let body = vec![
+            ControlStmt::Assignment(Assignment { target: "i".to_string(), value: Expr::Data(DataExpr::number(Number::int(0))) }),
+            ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::identifier("i")),
+                    Comparator::Lt,
+                    Box::new(DataExpr::number(Number::int(10))),
+                ),
+                body: vec![ControlStmt::Assignment(Assignment {
+                    target: "i".to_string(),
+                    value: Expr::Data(DataExpr::add(DataExpr::identifier("i"), DataExpr::number(Number::int(1)))),
+                })],
+            }),
+            ControlStmt::Return(None),
+        ];
+        let program = program_with(trivial_function("f", body));
+        let findings = DeadEffectFreeComputation.check(&program);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("loop"));
+    }
+
+    #[test]
+    fn test_while_with_print_is_not_flagged() {
+        let body = vec![
+            ControlStmt::Assignment(Assignment { target: "i".to_string(), value: Expr::Data(DataExpr::number(Number::int(0))) }),
+            ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::identifier("i")),
+                    Comparator::Lt,
+                    Box::new(DataExpr::number(Number::int(10))),
+                ),
+                body: vec![
+                    ControlStmt::Print(vec![DataExpr::identifier("i")]),
+                    ControlStmt::Assignment(Assignment {
+                        target: "i".to_string(),
+                        value: Expr::Data(DataExpr::add(DataExpr::identifier("i"), DataExpr::number(Number::int(1)))),
+                    }),
+                ],
+            }),
+            ControlStmt::Return(None),
+        ];
+        let program = program_with(trivial_function("f", body));
+        assert!(DeadEffectFreeComputation.check(&program).is_empty());
+    }
+
+    #[test]
+    fn test_returnless_effect_free_if_is_flagged() {
+        let condition = ControlExpr::Comparison(
+            Box::new(DataExpr::number(Number::int(1))),
+            Comparator::Eq,
+            Box::new(DataExpr::number(Number::int(1))),
+        );
+        let body = vec![
+            ControlStmt::If(IfStmt {
+                condition,
+                then_branch: vec![ControlStmt::Assignment(Assignment {
+                    target: "unused".to_string(),
+                    value: Expr::Data(DataExpr::number(Number::int(2))),
+                })],
+                else_branch: None,
+            }),
+            ControlStmt::Return(None),
+        ];
+        let program = program_with(trivial_function("f", body));
+        let findings = DeadEffectFreeComputation.check(&program);
+        assert!(findings.iter().any(|f| f.message.contains("this `if`")));
+    }
+}
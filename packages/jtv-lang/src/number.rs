@@ -1,14 +1,19 @@
 // Number system implementation supporting 7 types
 use crate::ast::Number;
 use crate::error::{JtvError, Result};
+use crate::iterator::ValueIter;
+use crate::pvector::PVector;
+use num_bigint::BigInt;
 use num_complex::Complex64;
 use num_rational::Ratio;
-use num_traits::Zero;
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     Int(i64),
+    BigInt(BigInt),
     Float(f64),
     Rational(Ratio<i64>),
     Complex(Complex64),
@@ -17,43 +22,331 @@ pub enum Value {
     Symbolic(String),
     Bool(bool),
     String(String),
-    List(Vec<Value>),
+    /// Backed by `PVector` rather than `Vec` so `tail`/`take`/`drop`/`concat`
+    /// (see `crate::stdlib`) can share structure instead of cloning the
+    /// whole list on every call -- see `crate::pvector` for why.
+    List(PVector<Value>),
     Tuple(Vec<Value>),
+    /// An instance of a `struct` declaration: the struct's name plus its
+    /// field values in declaration order.
+    Struct(String, Vec<(String, Value)>),
+    /// A first-class reference to a user-defined function by name, so it
+    /// can be passed around as an ordinary value (bound to a variable,
+    /// passed to `map`/`filter`/...) instead of only being callable by
+    /// writing its name at a call site. Carries no captured environment --
+    /// JtV function bodies don't close over outer scope, so looking this up
+    /// again by name at call time (see `Interpreter::eval_function_call`) is
+    /// equivalent to capturing it.
+    Closure(String),
+    /// The same first-class use as `Closure`, but for a `StdLib` builtin
+    /// instead of a user-defined function.
+    Builtin(String),
+    /// A `Builtin` under-applied at its call site (fewer arguments than its
+    /// declared `Arity` requires), produced by `StdLib::call` instead of an
+    /// `ArityMismatch` -- mirrors `complexpr`'s `Func::Partial`. Carries the
+    /// builtin's name and the arguments gathered so far; calling it again
+    /// appends the new arguments and re-dispatches through `StdLib::call`,
+    /// which either completes the call or returns a further `PartialApp`.
+    /// This is what lets a builtin be curried into a callback, e.g.
+    /// `map(pow(2), xs)`.
+    PartialApp { name: String, collected: Vec<Value> },
+    /// A lazy, pull-based sequence (see `crate::iterator::ValueIter`) --
+    /// `range`, `take`, `drop`, `takeWhile`, `enumerate`, and `chain` all
+    /// produce one of these instead of a fully materialized `List`; only
+    /// `collect` forces it into one.
+    Iterator(ValueIter),
+    /// An n-qubit register for the state-vector quantum simulator (see
+    /// `crate::libraries::jtv::reversible`): amplitudes of all `2^n` basis
+    /// states, indexed so that bit `j` of the index is qubit `j`'s
+    /// contribution. Always has a power-of-two length.
+    Qubits(Vec<Complex64>),
     Unit,
 }
 
+/// How integer operations should handle overflow of the fixed-width `i64`
+/// accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArithmeticMode {
+    /// Overflow is an error (`JtvError::IntegerOverflow`).
+    Checked,
+    /// Overflow wraps around, like `i64::wrapping_add`.
+    Wrapping,
+    /// Overflow widens the operand(s) to `Value::BigInt` and retries.
+    #[default]
+    Promoting,
+}
+
+/// Evaluation-wide knobs threaded through the numeric operations and
+/// collection construction, so a host embedding the interpreter can trade
+/// exactness for speed/safety instead of the behavior being hard-coded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvalOptions {
+    pub arithmetic_mode: ArithmeticMode,
+    /// Maximum nesting depth allowed for `List`/`Tuple` values.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        EvalOptions {
+            arithmetic_mode: ArithmeticMode::default(),
+            max_nesting_depth: 256,
+        }
+    }
+}
+
+/// Rank of a value along the numeric promotion tower: Int (and its
+/// fixed-width cousins Hex/Binary/BigInt) < Rational < Float < Complex.
+/// Returns `None` for non-numeric values, which `promote` leaves untouched.
+fn numeric_rank(v: &Value) -> Option<u8> {
+    match v {
+        Value::Int(_) | Value::Hex(_) | Value::Binary(_) | Value::BigInt(_) => Some(0),
+        Value::Rational(_) => Some(1),
+        Value::Float(_) => Some(2),
+        Value::Complex(_) => Some(3),
+        _ => None,
+    }
+}
+
+/// Convert the ratio to the nearest `f64`.
+pub fn ratio_to_f64(r: &Ratio<i64>) -> f64 {
+    *r.numer() as f64 / *r.denom() as f64
+}
+
+/// Unify a mixed pair of rank-0 (integral) variants: if either side is a
+/// `BigInt`, both become `BigInt`; otherwise Hex/Binary collapse to `Int`.
+fn unify_integral(a: Value, b: Value) -> (Value, Value) {
+    let to_big = matches!(a, Value::BigInt(_)) || matches!(b, Value::BigInt(_));
+    let lift = |v: Value| -> Value {
+        match v {
+            Value::BigInt(n) => Value::BigInt(n),
+            Value::Int(n) | Value::Hex(n) | Value::Binary(n) => {
+                if to_big {
+                    Value::BigInt(BigInt::from(n))
+                } else {
+                    Value::Int(n)
+                }
+            }
+            other => other,
+        }
+    };
+    (lift(a), lift(b))
+}
+
+/// Lift a rank-0/1/2 value up to the given tower rank (1 = Rational,
+/// 2 = Float, 3 = Complex). Values already at or above `target` are
+/// returned unchanged.
+fn lift_to_rank(v: Value, target: u8) -> Value {
+    let as_i64 = |v: &Value| -> Option<i64> {
+        match v {
+            Value::Int(n) | Value::Hex(n) | Value::Binary(n) => Some(*n),
+            _ => None,
+        }
+    };
+    match target {
+        1 => match v {
+            Value::Rational(_) => v,
+            _ if as_i64(&v).is_some() => Value::Rational(Ratio::from_integer(as_i64(&v).unwrap())),
+            Value::BigInt(n) => Value::Rational(Ratio::from_integer(n.to_i64().unwrap_or(i64::MAX))),
+            other => other,
+        },
+        2 => match v {
+            Value::Float(_) => v,
+            Value::Rational(r) => Value::Float(ratio_to_f64(&r)),
+            Value::BigInt(n) => Value::Float(n.to_f64().unwrap_or(f64::NAN)),
+            _ if as_i64(&v).is_some() => Value::Float(as_i64(&v).unwrap() as f64),
+            other => other,
+        },
+        3 => match v {
+            Value::Complex(_) => v,
+            Value::Float(f) => Value::Complex(Complex64::new(f, 0.0)),
+            Value::Rational(r) => Value::Complex(Complex64::new(ratio_to_f64(&r), 0.0)),
+            Value::BigInt(n) => Value::Complex(Complex64::new(n.to_f64().unwrap_or(f64::NAN), 0.0)),
+            _ if as_i64(&v).is_some() => Value::Complex(Complex64::new(as_i64(&v).unwrap() as f64, 0.0)),
+            other => other,
+        },
+        _ => v,
+    }
+}
+
+/// Lift both operands to their common type along the numeric tower
+/// Int → Rational → Float → Complex (Hex/Binary count as Int, and a
+/// `BigInt` partner widens an Int-tier pair to `BigInt` instead). Values
+/// that aren't numeric, or are already the same variant, pass through
+/// untouched — callers match only on same-type pairs afterwards.
+pub fn promote(a: Value, b: Value) -> (Value, Value) {
+    if std::mem::discriminant(&a) == std::mem::discriminant(&b) {
+        return (a, b);
+    }
+    match (numeric_rank(&a), numeric_rank(&b)) {
+        (Some(0), Some(0)) => unify_integral(a, b),
+        (Some(ra), Some(rb)) if ra == rb => (a, b),
+        (Some(ra), Some(rb)) if ra < rb => (lift_to_rank(a, rb), b),
+        (Some(ra), Some(rb)) if ra > rb => (a, lift_to_rank(b, ra)),
+        _ => (a, b),
+    }
+}
+
+/// Apply a checked `i64` op per `opts.arithmetic_mode`, promoting to
+/// `Value::BigInt` on overflow in `Promoting` mode rather than erroring.
+fn checked_int_op(
+    x: i64,
+    y: i64,
+    opts: &EvalOptions,
+    checked: fn(&i64, i64) -> Option<i64>,
+    wrapping: fn(&i64, i64) -> i64,
+) -> Result<Value> {
+    match opts.arithmetic_mode {
+        ArithmeticMode::Checked => checked(&x, y).map(Value::Int).ok_or(JtvError::IntegerOverflow),
+        ArithmeticMode::Wrapping => Ok(Value::Int(wrapping(&x, y))),
+        ArithmeticMode::Promoting => match checked(&x, y) {
+            Some(sum) => Ok(Value::Int(sum)),
+            None => Ok(Value::normalize_bigint(BigInt::from(x) + BigInt::from(y))),
+        },
+    }
+}
+
+/// Like [`checked_int_op`] but for the fixed-width `Hex`/`Binary` variants,
+/// which have no `BigInt` promotion path: `Promoting` falls back to
+/// `Checked` since there is nowhere further to widen to.
+fn checked_fixed_op(
+    x: i64,
+    y: i64,
+    opts: &EvalOptions,
+    checked: fn(&i64, i64) -> Option<i64>,
+    wrapping: fn(&i64, i64) -> i64,
+    ctor: fn(i64) -> Value,
+) -> Result<Value> {
+    match opts.arithmetic_mode {
+        ArithmeticMode::Wrapping => Ok(ctor(wrapping(&x, y))),
+        ArithmeticMode::Checked | ArithmeticMode::Promoting => {
+            checked(&x, y).map(ctor).ok_or(JtvError::IntegerOverflow)
+        }
+    }
+}
+
 impl Value {
+    /// Demote a `BigInt` back to `Int` when it fits in an `i64`.
+    pub(crate) fn normalize_bigint(n: BigInt) -> Value {
+        match n.to_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::BigInt(n),
+        }
+    }
+
     // Addition operation (the only arithmetic operation in Data Language)
     pub fn add(&self, other: &Value) -> Result<Value> {
+        self.add_with(other, &EvalOptions::default())
+    }
+
+    /// Addition with an explicit [`EvalOptions`], so hosts can choose
+    /// checked/wrapping/promoting behavior for `i64`/`Hex`/`Binary` overflow
+    /// instead of it being hard-coded.
+    pub fn add_with(&self, other: &Value, opts: &EvalOptions) -> Result<Value> {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => a
-                .checked_add(*b)
-                .map(Value::Int)
-                .ok_or(JtvError::IntegerOverflow),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
-            (Value::Rational(a), Value::Rational(b)) => Ok(Value::Rational(a + b)),
-            (Value::Complex(a), Value::Complex(b)) => Ok(Value::Complex(a + b)),
-            (Value::Hex(a), Value::Hex(b)) => a
-                .checked_add(*b)
-                .map(Value::Hex)
-                .ok_or(JtvError::IntegerOverflow),
-            (Value::Binary(a), Value::Binary(b)) => a
-                .checked_add(*b)
-                .map(Value::Binary)
-                .ok_or(JtvError::IntegerOverflow),
             (Value::Symbolic(a), Value::Symbolic(b)) => {
                 Ok(Value::Symbolic(format!("{} + {}", a, b)))
             }
             (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
-            // Type coercion
-            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(*a as f64 + b)),
-            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a + *b as f64)),
-            (Value::Int(a), Value::Rational(b)) => Ok(Value::Rational(Ratio::from_integer(*a) + b)),
-            (Value::Rational(a), Value::Int(b)) => Ok(Value::Rational(a + Ratio::from_integer(*b))),
-            (Value::Float(a), Value::Complex(b)) => Ok(Value::Complex(Complex64::new(*a, 0.0) + b)),
-            (Value::Complex(a), Value::Float(b)) => Ok(Value::Complex(a + Complex64::new(*b, 0.0))),
+            _ => {
+                let (a, b) = promote(self.clone(), other.clone());
+                match (&a, &b) {
+                    (Value::Int(x), Value::Int(y)) => {
+                        checked_int_op(*x, *y, opts, i64::checked_add, i64::wrapping_add)
+                    }
+                    (Value::BigInt(x), Value::BigInt(y)) => Ok(Value::normalize_bigint(x + y)),
+                    (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
+                    (Value::Rational(x), Value::Rational(y)) => Ok(Value::Rational(x + y)),
+                    (Value::Complex(x), Value::Complex(y)) => Ok(Value::Complex(x + y)),
+                    (Value::Hex(x), Value::Hex(y)) => {
+                        checked_fixed_op(*x, *y, opts, i64::checked_add, i64::wrapping_add, Value::Hex)
+                    }
+                    (Value::Binary(x), Value::Binary(y)) => {
+                        checked_fixed_op(*x, *y, opts, i64::checked_add, i64::wrapping_add, Value::Binary)
+                    }
+                    _ => Err(JtvError::TypeError(format!(
+                        "Cannot add {:?} and {:?}",
+                        self, other
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Multiplication, used by the reversible subsystem's `MulAssign` (see
+    /// `crate::reversible`) -- `DataExpr` itself has no general `Mul` node,
+    /// so this isn't reachable through ordinary language arithmetic.
+    pub fn mul(&self, other: &Value) -> Result<Value> {
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => match x.checked_mul(*y) {
+                Some(product) => Ok(Value::Int(product)),
+                None => Ok(Value::normalize_bigint(BigInt::from(*x) * BigInt::from(*y))),
+            },
+            (Value::BigInt(x), Value::BigInt(y)) => Ok(Value::normalize_bigint(x * y)),
+            (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x * y)),
+            (Value::Rational(x), Value::Rational(y)) => Ok(Value::Rational(x * y)),
+            (Value::Complex(x), Value::Complex(y)) => Ok(Value::Complex(x * y)),
+            (Value::Hex(x), Value::Hex(y)) => {
+                checked_fixed_op(*x, *y, &EvalOptions::default(), i64::checked_mul, i64::wrapping_mul, Value::Hex)
+            }
+            (Value::Binary(x), Value::Binary(y)) => {
+                checked_fixed_op(*x, *y, &EvalOptions::default(), i64::checked_mul, i64::wrapping_mul, Value::Binary)
+            }
+            _ => Err(JtvError::TypeError(format!(
+                "Cannot multiply {:?} and {:?}",
+                self, other
+            ))),
+        }
+    }
+
+    /// Division, used by the reversible subsystem's `DivAssign` (see
+    /// `crate::reversible`) -- same no-general-language-node caveat as
+    /// `mul`. Only rejects a zero divisor here; the stricter "must divide
+    /// `Int`s exactly" invariant `RecordedOp::MulAssign::inverse` needs to
+    /// guarantee `(x * k) / k == x` is checked there, not by this method.
+    pub fn div(&self, other: &Value) -> Result<Value> {
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => {
+                if *y == 0 {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::Int(x / y))
+            }
+            (Value::BigInt(x), Value::BigInt(y)) => {
+                if y.is_zero() {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::normalize_bigint(x / y))
+            }
+            (Value::Float(x), Value::Float(y)) => {
+                if *y == 0.0 {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::Float(x / y))
+            }
+            (Value::Rational(x), Value::Rational(y)) => {
+                if y.is_zero() {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::Rational(x / y))
+            }
+            (Value::Complex(x), Value::Complex(y)) => Ok(Value::Complex(x / y)),
+            (Value::Hex(x), Value::Hex(y)) => {
+                if *y == 0 {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::Hex(x / y))
+            }
+            (Value::Binary(x), Value::Binary(y)) => {
+                if *y == 0 {
+                    return Err(JtvError::DivisionByZero);
+                }
+                Ok(Value::Binary(x / y))
+            }
             _ => Err(JtvError::TypeError(format!(
-                "Cannot add {:?} and {:?}",
+                "Cannot divide {:?} by {:?}",
                 self, other
             ))),
         }
@@ -61,18 +354,76 @@ impl Value {
 
     // Negation operation
     pub fn negate(&self) -> Result<Value> {
+        self.negate_with(&EvalOptions::default())
+    }
+
+    /// Negation with an explicit [`EvalOptions`] governing how overflow of
+    /// `i64::MIN.negate()` is handled.
+    pub fn negate_with(&self, opts: &EvalOptions) -> Result<Value> {
         match self {
-            Value::Int(n) => Ok(Value::Int(-n)),
+            Value::Int(n) => match opts.arithmetic_mode {
+                ArithmeticMode::Checked => n
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or(JtvError::IntegerOverflow),
+                ArithmeticMode::Wrapping => Ok(Value::Int(n.wrapping_neg())),
+                ArithmeticMode::Promoting => match n.checked_neg() {
+                    Some(r) => Ok(Value::Int(r)),
+                    None => Ok(Value::normalize_bigint(-BigInt::from(*n))),
+                },
+            },
+            Value::BigInt(n) => Ok(Value::normalize_bigint(-n)),
             Value::Float(n) => Ok(Value::Float(-n)),
             Value::Rational(n) => Ok(Value::Rational(-n)),
             Value::Complex(n) => Ok(Value::Complex(-n)),
-            Value::Hex(n) => Ok(Value::Hex(-n)),
-            Value::Binary(n) => Ok(Value::Binary(-n)),
+            // `Hex`/`Binary` have no `BigInt` escape hatch, so `Promoting`
+            // falls back to `Checked` here the same way `checked_fixed_op`
+            // does for the binary ops.
+            Value::Hex(n) => match opts.arithmetic_mode {
+                ArithmeticMode::Wrapping => Ok(Value::Hex(n.wrapping_neg())),
+                ArithmeticMode::Checked | ArithmeticMode::Promoting => {
+                    n.checked_neg().map(Value::Hex).ok_or(JtvError::IntegerOverflow)
+                }
+            },
+            Value::Binary(n) => match opts.arithmetic_mode {
+                ArithmeticMode::Wrapping => Ok(Value::Binary(n.wrapping_neg())),
+                ArithmeticMode::Checked | ArithmeticMode::Promoting => {
+                    n.checked_neg().map(Value::Binary).ok_or(JtvError::IntegerOverflow)
+                }
+            },
             Value::Symbolic(s) => Ok(Value::Symbolic(format!("-({})", s))),
             _ => Err(JtvError::TypeError(format!("Cannot negate {:?}", self))),
         }
     }
 
+    /// Maximum nesting depth of `List`/`Tuple` values (0 for a scalar).
+    pub fn nesting_depth(&self) -> usize {
+        match self {
+            Value::List(items) => {
+                1 + items.iter().map(Value::nesting_depth).max().unwrap_or(0)
+            }
+            Value::Tuple(items) => {
+                1 + items.iter().map(Value::nesting_depth).max().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Reject values whose `List`/`Tuple` nesting exceeds `opts.max_nesting_depth`,
+    /// so pathological inputs produce a clean error instead of overflowing the
+    /// stack during later recursive traversal (e.g. `Display`).
+    pub fn check_nesting_depth(&self, opts: &EvalOptions) -> Result<()> {
+        let depth = self.nesting_depth();
+        if depth > opts.max_nesting_depth {
+            Err(JtvError::RuntimeError(format!(
+                "nesting depth {} exceeds the maximum of {}",
+                depth, opts.max_nesting_depth
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
     // Comparison operations (for Control expressions)
     pub fn eq(&self, other: &Value) -> Result<bool> {
         Ok(self == other)
@@ -82,11 +433,30 @@ impl Value {
         Ok(self != other)
     }
 
+    /// `self in collection` -- true when `self` equals any element of a
+    /// `List`/`Tuple`, built on the same `PartialEq` every `Value` already
+    /// derives so it works for any comparable value type.
+    pub fn contains(&self, collection: &Value) -> Result<bool> {
+        match collection {
+            Value::List(items) => Ok(items.contains(self)),
+            Value::Tuple(items) => Ok(items.contains(self)),
+            other => Err(JtvError::TypeError(format!(
+                "cannot test membership in {:?} (expected a List or Tuple)",
+                other
+            ))),
+        }
+    }
+
     pub fn lt(&self, other: &Value) -> Result<bool> {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(a < b),
-            (Value::Float(a), Value::Float(b)) => Ok(a < b),
-            (Value::Rational(a), Value::Rational(b)) => Ok(a < b),
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => Ok(x < y),
+            (Value::BigInt(x), Value::BigInt(y)) => Ok(x < y),
+            (Value::Rational(x), Value::Rational(y)) => Ok(x < y),
+            (Value::Float(x), Value::Float(y)) => Ok(x < y),
+            (Value::Complex(_), Value::Complex(_)) => Err(JtvError::TypeError(
+                "Cannot compare Complex values (no total order)".to_string(),
+            )),
             _ => Err(JtvError::TypeError(format!(
                 "Cannot compare {:?} and {:?}",
                 self, other
@@ -95,10 +465,15 @@ impl Value {
     }
 
     pub fn le(&self, other: &Value) -> Result<bool> {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(a <= b),
-            (Value::Float(a), Value::Float(b)) => Ok(a <= b),
-            (Value::Rational(a), Value::Rational(b)) => Ok(a <= b),
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => Ok(x <= y),
+            (Value::BigInt(x), Value::BigInt(y)) => Ok(x <= y),
+            (Value::Rational(x), Value::Rational(y)) => Ok(x <= y),
+            (Value::Float(x), Value::Float(y)) => Ok(x <= y),
+            (Value::Complex(_), Value::Complex(_)) => Err(JtvError::TypeError(
+                "Cannot compare Complex values (no total order)".to_string(),
+            )),
             _ => Err(JtvError::TypeError(format!(
                 "Cannot compare {:?} and {:?}",
                 self, other
@@ -107,10 +482,15 @@ impl Value {
     }
 
     pub fn gt(&self, other: &Value) -> Result<bool> {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(a > b),
-            (Value::Float(a), Value::Float(b)) => Ok(a > b),
-            (Value::Rational(a), Value::Rational(b)) => Ok(a > b),
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => Ok(x > y),
+            (Value::BigInt(x), Value::BigInt(y)) => Ok(x > y),
+            (Value::Rational(x), Value::Rational(y)) => Ok(x > y),
+            (Value::Float(x), Value::Float(y)) => Ok(x > y),
+            (Value::Complex(_), Value::Complex(_)) => Err(JtvError::TypeError(
+                "Cannot compare Complex values (no total order)".to_string(),
+            )),
             _ => Err(JtvError::TypeError(format!(
                 "Cannot compare {:?} and {:?}",
                 self, other
@@ -119,10 +499,15 @@ impl Value {
     }
 
     pub fn ge(&self, other: &Value) -> Result<bool> {
-        match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(a >= b),
-            (Value::Float(a), Value::Float(b)) => Ok(a >= b),
-            (Value::Rational(a), Value::Rational(b)) => Ok(a >= b),
+        let (a, b) = promote(self.clone(), other.clone());
+        match (&a, &b) {
+            (Value::Int(x), Value::Int(y)) => Ok(x >= y),
+            (Value::BigInt(x), Value::BigInt(y)) => Ok(x >= y),
+            (Value::Rational(x), Value::Rational(y)) => Ok(x >= y),
+            (Value::Float(x), Value::Float(y)) => Ok(x >= y),
+            (Value::Complex(_), Value::Complex(_)) => Err(JtvError::TypeError(
+                "Cannot compare Complex values (no total order)".to_string(),
+            )),
             _ => Err(JtvError::TypeError(format!(
                 "Cannot compare {:?} and {:?}",
                 self, other
@@ -164,10 +549,13 @@ impl Value {
         match self {
             Value::Bool(b) => *b,
             Value::Int(n) => *n != 0,
-            Value::Float(f) => *f != 0.0,
+            Value::BigInt(n) => !n.is_zero(),
+            Value::Float(f) => !f.is_nan() && *f != 0.0,
             Value::Rational(r) => !r.is_zero(),
+            Value::Complex(c) => !(c.re == 0.0 && c.im == 0.0) && !c.is_nan(),
             Value::String(s) => !s.is_empty(),
             Value::List(l) => !l.is_empty(),
+            Value::Tuple(t) => !t.is_empty(),
             Value::Unit => false,
             _ => true,
         }
@@ -178,6 +566,7 @@ impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Int(n) => write!(f, "{}", n),
+            Value::BigInt(n) => write!(f, "{}", n),
             Value::Float(n) => write!(f, "{}", n),
             Value::Rational(r) => write!(f, "{}/{}", r.numer(), r.denom()),
             Value::Complex(c) => {
@@ -212,6 +601,23 @@ impl fmt::Display for Value {
                 }
                 write!(f, ")")
             }
+            Value::Struct(name, fields) => {
+                write!(f, "{} {{ ", name)?;
+                for (i, (field_name, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", field_name, v)?;
+                }
+                write!(f, " }}")
+            }
+            Value::Closure(name) => write!(f, "<function {}>", name),
+            Value::Builtin(name) => write!(f, "<builtin {}>", name),
+            Value::PartialApp { name, collected } => {
+                write!(f, "<partial {} ({} arg(s) collected)>", name, collected.len())
+            }
+            Value::Iterator(_) => write!(f, "<iterator>"),
+            Value::Qubits(amps) => write!(f, "<qubits: {} state(s)>", amps.len()),
             Value::Unit => write!(f, "()"),
         }
     }
@@ -284,4 +690,194 @@ mod tests {
         let result = a.add(&b).unwrap();
         assert!(matches!(result, Value::Float(_)));
     }
+
+    #[test]
+    fn test_rational_float_coercion() {
+        let a = Value::Rational(Ratio::new(1, 2));
+        let b = Value::Float(1.5);
+        let result = a.add(&b).unwrap();
+        assert_eq!(result, Value::Float(2.0));
+    }
+
+    #[test]
+    fn test_rational_complex_coercion() {
+        let a = Value::Rational(Ratio::new(1, 2));
+        let b = Value::Complex(Complex64::new(1.0, 1.0));
+        let result = a.add(&b).unwrap();
+        assert_eq!(result, Value::Complex(Complex64::new(1.5, 1.0)));
+    }
+
+    #[test]
+    fn test_int_complex_coercion() {
+        let a = Value::Int(2);
+        let b = Value::Complex(Complex64::new(0.0, 1.0));
+        let result = a.add(&b).unwrap();
+        assert_eq!(result, Value::Complex(Complex64::new(2.0, 1.0)));
+    }
+
+    #[test]
+    fn test_hex_int_coercion() {
+        let a = Value::Hex(0x10);
+        let b = Value::Int(5);
+        let result = a.add(&b).unwrap();
+        assert_eq!(result, Value::Int(21));
+    }
+
+    #[test]
+    fn test_binary_int_coercion() {
+        let a = Value::Binary(0b10);
+        let b = Value::Int(1);
+        let result = a.add(&b).unwrap();
+        assert_eq!(result, Value::Int(3));
+    }
+
+    #[test]
+    fn test_rational_float_lt() {
+        let a = Value::Rational(Ratio::new(1, 2));
+        let b = Value::Float(0.75);
+        assert!(a.lt(&b).unwrap());
+    }
+
+    #[test]
+    fn test_complex_comparison_errors() {
+        let a = Value::Complex(Complex64::new(1.0, 0.0));
+        let b = Value::Complex(Complex64::new(2.0, 0.0));
+        assert!(a.lt(&b).is_err());
+    }
+
+    #[test]
+    fn test_nan_float_is_falsy() {
+        assert!(!Value::Float(f64::NAN).is_truthy());
+    }
+
+    #[test]
+    fn test_zero_complex_is_falsy() {
+        assert!(!Value::Complex(Complex64::new(0.0, 0.0)).is_truthy());
+    }
+
+    #[test]
+    fn test_nan_complex_is_falsy() {
+        assert!(!Value::Complex(Complex64::new(f64::NAN, 1.0)).is_truthy());
+    }
+
+    #[test]
+    fn test_nonzero_complex_is_truthy() {
+        assert!(Value::Complex(Complex64::new(1.0, 0.0)).is_truthy());
+    }
+
+    #[test]
+    fn test_empty_tuple_is_falsy() {
+        assert!(!Value::Tuple(vec![]).is_truthy());
+    }
+
+    #[test]
+    fn test_contains_finds_element_in_list() {
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        assert_eq!(Value::Int(2).contains(&list), Ok(true));
+        assert_eq!(Value::Int(5).contains(&list), Ok(false));
+    }
+
+    #[test]
+    fn test_contains_errors_on_non_collection() {
+        assert!(matches!(
+            Value::Int(1).contains(&Value::Int(5)),
+            Err(JtvError::TypeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_checked_mode_errors_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Checked,
+            ..EvalOptions::default()
+        };
+        let result = Value::Int(i64::MAX).add_with(&Value::Int(1), &opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_wrapping_mode_wraps_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Wrapping,
+            ..EvalOptions::default()
+        };
+        let result = Value::Int(i64::MAX).add_with(&Value::Int(1), &opts).unwrap();
+        assert_eq!(result, Value::Int(i64::MIN));
+    }
+
+    #[test]
+    fn test_promoting_mode_widens_to_bigint_on_overflow() {
+        let result = Value::Int(i64::MAX).add(&Value::Int(1)).unwrap();
+        assert_eq!(
+            result,
+            Value::BigInt(BigInt::from(i64::MAX) + BigInt::from(1))
+        );
+    }
+
+    #[test]
+    fn test_hex_checked_mode_errors_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Checked,
+            ..EvalOptions::default()
+        };
+        let result = Value::Hex(i64::MAX).add_with(&Value::Hex(1), &opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_hex_negate_checked_mode_errors_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Checked,
+            ..EvalOptions::default()
+        };
+        let result = Value::Hex(i64::MIN).negate_with(&opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_hex_negate_wrapping_mode_wraps_on_overflow() {
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Wrapping,
+            ..EvalOptions::default()
+        };
+        let result = Value::Hex(i64::MIN).negate_with(&opts).unwrap();
+        assert_eq!(result, Value::Hex(i64::MIN));
+    }
+
+    #[test]
+    fn test_binary_negate_promoting_mode_errors_on_overflow() {
+        // Unlike `Int`, `Binary` has no `BigInt` to widen into, so
+        // `Promoting` falls back to `Checked` instead of silently wrapping.
+        let opts = EvalOptions {
+            arithmetic_mode: ArithmeticMode::Promoting,
+            ..EvalOptions::default()
+        };
+        let result = Value::Binary(i64::MIN).negate_with(&opts);
+        assert!(matches!(result, Err(JtvError::IntegerOverflow)));
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let v = Value::List(PVector::from_vec(vec![Value::List(PVector::from_vec(vec![Value::Int(1)]))]));
+        assert_eq!(v.nesting_depth(), 2);
+        assert_eq!(Value::Int(1).nesting_depth(), 0);
+    }
+
+    #[test]
+    fn test_check_nesting_depth_rejects_deep_values() {
+        let opts = EvalOptions {
+            max_nesting_depth: 1,
+            ..EvalOptions::default()
+        };
+        let deep = Value::List(PVector::from_vec(vec![Value::List(PVector::from_vec(vec![Value::Int(1)]))]));
+        assert!(deep.check_nesting_depth(&opts).is_err());
+        let shallow = Value::List(PVector::from_vec(vec![Value::Int(1)]));
+        assert!(shallow.check_nesting_depth(&opts).is_ok());
+    }
+
+    #[test]
+    fn test_float_division_rejects_zero_divisor() {
+        let result = Value::Float(1.0).div(&Value::Float(0.0));
+        assert!(matches!(result, Err(JtvError::DivisionByZero)));
+    }
 }
@@ -0,0 +1,1143 @@
+// AST optimizer for Julia the Viper, driven by `purity::PurityChecker`'s
+// inferred purity levels rather than declared annotations (so a private
+// helper missing a `@total` still gets folded/hoisted like one that has
+// it -- see `PurityChecker::infer_program`).
+//
+// Three rewrites, applied to every function body in a `Program`:
+//
+// - constant folding: a call to an inferred-`Total` function whose
+//   arguments are all already number literals is replaced by its computed
+//   result, evaluated by actually running it on a scratch `Interpreter`
+//   (see `evaluate_total_call`) rather than re-deriving arithmetic here;
+// - common-subexpression hoisting: a second call to the same
+//   inferred-`Pure`-or-`Total` function with the same (already-folded)
+//   arguments, appearing later in the *same* straight-line statement list
+//   with nothing reassigning what it reads in between, is replaced by a
+//   reference to the variable the first call's result was already bound
+//   to -- see `common_subexpressions` for why this doesn't look across
+//   `if`/`while`/`for` block boundaries;
+// - dead-store elimination: an assignment whose target is never read
+//   anywhere else in the function, and whose value only calls
+//   inferred-`Pure`-or-`Total` functions, is dropped outright.
+//
+// All three refuse to touch anything that might reach an inferred-`Impure`
+// call: folding and hoisting simply never match such an expression, and
+// dead-store elimination leaves the whole statement in place rather than
+// silently dropping a side effect this AST has no way to keep without the
+// binding (there's no bare "evaluate for effect" statement here -- see
+// `ast::ControlStmt`). A `Print` can't appear inside a `DataExpr` at all,
+// so it's never at risk from any of these three directly.
+
+use crate::ast::*;
+use crate::error::JtvError;
+use crate::interpreter::Interpreter;
+use crate::number::Value;
+use crate::purity::PurityChecker;
+use crate::pvector::PVector;
+use std::collections::{HashMap, HashSet};
+
+/// How much `optimize_program` actually changed, for callers that want to
+/// report or test on it rather than just trusting the rewritten `Program`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    pub constants_folded: usize,
+    pub common_subexpressions_hoisted: usize,
+    pub dead_stores_eliminated: usize,
+}
+
+/// Rewrites every function body in `program` in place using the three
+/// purity-driven passes described in the module doc comment above, and
+/// reports how many times each one fired.
+pub fn optimize_program(program: &mut Program) -> OptimizationStats {
+    let levels = PurityChecker::infer_program(program);
+    let functions: HashMap<String, FunctionDecl> = program
+        .statements
+        .iter()
+        .filter_map(|item| match item {
+            TopLevel::Function(func) => Some((func.name.clone(), func.clone())),
+            _ => None,
+        })
+        .collect();
+
+    let mut stats = OptimizationStats::default();
+    optimize_items(&mut program.statements, &levels, &functions, &mut stats);
+    stats
+}
+
+fn optimize_items(
+    items: &mut [TopLevel],
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    for item in items {
+        match item {
+            TopLevel::Function(func) => optimize_function(func, levels, functions, stats),
+            TopLevel::Module(module) => optimize_items(&mut module.body, levels, functions, stats),
+            TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Test(_) | TopLevel::Control(_) => {}
+        }
+    }
+}
+
+fn optimize_function(
+    func: &mut FunctionDecl,
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    fold_body(&mut func.body, levels, functions, stats);
+    common_subexpressions(&mut func.body, levels, stats);
+
+    let mut read_names = HashSet::new();
+    collect_read_names(&func.body, &mut read_names);
+    eliminate_dead_stores(&mut func.body, &read_names, levels, stats);
+}
+
+// ===== Constant folding =====
+
+fn fold_body(
+    stmts: &mut [ControlStmt],
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    for stmt in stmts {
+        fold_stmt(stmt, levels, functions, stats);
+    }
+}
+
+fn fold_stmt(
+    stmt: &mut ControlStmt,
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    match stmt {
+        ControlStmt::Assignment(assign) => match &mut assign.value {
+            Expr::Data(expr) => fold_data_expr(expr, levels, functions, stats),
+            Expr::Control(expr) => fold_control_expr(expr, levels, functions, stats),
+        },
+        ControlStmt::If(if_stmt) => {
+            fold_control_expr(&mut if_stmt.condition, levels, functions, stats);
+            fold_body(&mut if_stmt.then_branch, levels, functions, stats);
+            if let Some(else_branch) = &mut if_stmt.else_branch {
+                fold_body(else_branch, levels, functions, stats);
+            }
+        }
+        ControlStmt::While(while_stmt) => {
+            fold_control_expr(&mut while_stmt.condition, levels, functions, stats);
+            fold_body(&mut while_stmt.body, levels, functions, stats);
+        }
+        ControlStmt::For(for_stmt) => {
+            fold_data_expr(&mut for_stmt.range.start, levels, functions, stats);
+            fold_data_expr(&mut for_stmt.range.end, levels, functions, stats);
+            if let Some(step) = &mut for_stmt.range.step {
+                fold_data_expr(step, levels, functions, stats);
+            }
+            fold_body(&mut for_stmt.body, levels, functions, stats);
+        }
+        ControlStmt::Return(Some(expr)) => fold_data_expr(expr, levels, functions, stats),
+        ControlStmt::Return(None) => {}
+        ControlStmt::Print(exprs) => {
+            for expr in exprs {
+                fold_data_expr(expr, levels, functions, stats);
+            }
+        }
+        ControlStmt::ReverseBlock(block) => {
+            for stmt in &mut block.body {
+                fold_reversible_stmt(stmt, levels, functions, stats);
+            }
+        }
+        ControlStmt::Block(inner) => fold_body(inner, levels, functions, stats),
+        ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+    }
+}
+
+fn fold_reversible_stmt(
+    stmt: &mut ReversibleStmt,
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    match stmt {
+        ReversibleStmt::AddAssign(_, expr)
+        | ReversibleStmt::SubAssign(_, expr)
+        | ReversibleStmt::MulAssign(_, expr)
+        | ReversibleStmt::DivAssign(_, expr)
+        | ReversibleStmt::Assign(_, expr) => {
+            fold_data_expr(expr, levels, functions, stats)
+        }
+        ReversibleStmt::If(if_stmt) => {
+            fold_control_expr(&mut if_stmt.condition, levels, functions, stats);
+            fold_body(&mut if_stmt.then_branch, levels, functions, stats);
+            if let Some(else_branch) = &mut if_stmt.else_branch {
+                fold_body(else_branch, levels, functions, stats);
+            }
+        }
+        ReversibleStmt::For { from, to, step, body, .. } => {
+            fold_data_expr(from, levels, functions, stats);
+            fold_data_expr(to, levels, functions, stats);
+            if let Some(step) = step {
+                fold_data_expr(step, levels, functions, stats);
+            }
+            for stmt in body {
+                fold_reversible_stmt(stmt, levels, functions, stats);
+            }
+        }
+        ReversibleStmt::Switch { scrutinee, cases, default } => {
+            fold_data_expr(scrutinee, levels, functions, stats);
+            for (value, body) in cases {
+                fold_data_expr(value, levels, functions, stats);
+                for stmt in body {
+                    fold_reversible_stmt(stmt, levels, functions, stats);
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    fold_reversible_stmt(stmt, levels, functions, stats);
+                }
+            }
+        }
+    }
+}
+
+fn fold_control_expr(
+    expr: &mut ControlExpr,
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    match expr {
+        ControlExpr::Data(data) => fold_data_expr(data, levels, functions, stats),
+        ControlExpr::Comparison(left, _, right) => {
+            fold_data_expr(left, levels, functions, stats);
+            fold_data_expr(right, levels, functions, stats);
+        }
+        ControlExpr::Logical(left, _, right) => {
+            fold_control_expr(left, levels, functions, stats);
+            fold_control_expr(right, levels, functions, stats);
+        }
+        ControlExpr::Not(inner) => fold_control_expr(inner, levels, functions, stats),
+        ControlExpr::Contains(left, right) => {
+            fold_data_expr(left, levels, functions, stats);
+            fold_data_expr(right, levels, functions, stats);
+        }
+    }
+}
+
+fn fold_data_expr(
+    expr: &mut DataExpr,
+    levels: &HashMap<String, Purity>,
+    functions: &HashMap<String, FunctionDecl>,
+    stats: &mut OptimizationStats,
+) {
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => {}
+        DataExpr::Add(left, right) => {
+            fold_data_expr(left, levels, functions, stats);
+            fold_data_expr(right, levels, functions, stats);
+        }
+        DataExpr::Negate(inner) => fold_data_expr(inner, levels, functions, stats),
+        DataExpr::FunctionCall(call) => {
+            for arg in &mut call.args {
+                fold_data_expr(arg, levels, functions, stats);
+            }
+            let foldable = levels.get(&call.name) == Some(&Purity::Total)
+                && call.args.iter().all(|arg| matches!(arg, DataExpr::Number(_)));
+            if foldable {
+                if let Some(folded) = evaluate_total_call(call, functions) {
+                    *expr = folded;
+                    stats.constants_folded += 1;
+                }
+            }
+        }
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => {
+            for elem in elems {
+                fold_data_expr(elem, levels, functions, stats);
+            }
+        }
+        DataExpr::FieldAccess(base, _) => fold_data_expr(base, levels, functions, stats),
+        DataExpr::StructLiteral(_, fields) => {
+            for (_, expr) in fields {
+                fold_data_expr(expr, levels, functions, stats);
+            }
+        }
+        DataExpr::ListComprehension(comp) => {
+            fold_data_expr(&mut comp.body, levels, functions, stats);
+            for (_, source) in &mut comp.generators {
+                fold_data_expr(source, levels, functions, stats);
+            }
+            if let Some(condition) = &mut comp.condition {
+                fold_control_expr(condition, levels, functions, stats);
+            }
+        }
+        DataExpr::Index(base, index) => {
+            fold_data_expr(base, levels, functions, stats);
+            fold_data_expr(index, levels, functions, stats);
+        }
+    }
+}
+
+/// Computes `call`'s result by actually running it -- `call`'s callee plus
+/// every function it might reach are registered on a scratch `Interpreter`
+/// exactly as they're declared in the source, and the call is assigned to
+/// a throwaway binding whose final value becomes the folded literal.
+/// Returns `None` (leaving the call as-is) if the run errors, or if the
+/// result isn't a number this AST can write back as a `DataExpr::Number`.
+fn evaluate_total_call(call: &FunctionCall, functions: &HashMap<String, FunctionDecl>) -> Option<DataExpr> {
+    const RESULT_BINDING: &str = "__optimizer_fold_result";
+
+    let mut statements: Vec<TopLevel> = functions.values().cloned().map(TopLevel::Function).collect();
+    statements.push(TopLevel::Control(ControlStmt::Assignment(Assignment {
+        target: RESULT_BINDING.to_string(),
+        value: Expr::Data(DataExpr::FunctionCall(call.clone())),
+    })));
+    let synthetic = Program { statements, span: Span::unknown() };
+
+    let mut interpreter = Interpreter::new();
+    interpreter.run(&synthetic).ok()?;
+    match interpreter.globals().get(RESULT_BINDING)? {
+        Value::Int(n) => Some(DataExpr::Number(Number::Int(*n))),
+        Value::Float(n) => Some(DataExpr::Number(Number::Float(*n))),
+        _ => None,
+    }
+}
+
+// ===== Common-subexpression hoisting =====
+
+/// Within a single straight-line `Vec<ControlStmt>` (one function's own
+/// top-level body, or one branch of an `If`/`While`/`For`/`Block` -- each
+/// visited with its own independent cache, never shared across the
+/// boundary), replaces a `FunctionCall` to an inferred-`Pure`-or-`Total`
+/// function with the variable already holding an earlier, identical
+/// (structurally, via `DataExpr`'s `PartialEq`) call's result -- as long as
+/// nothing has reassigned a name either call depends on in between.
+fn common_subexpressions(stmts: &mut [ControlStmt], levels: &HashMap<String, Purity>, stats: &mut OptimizationStats) {
+    let mut seen: Vec<(DataExpr, String)> = Vec::new();
+
+    for stmt in stmts.iter_mut() {
+        if let ControlStmt::Assignment(assign) = stmt {
+            let target = assign.target.clone();
+            seen.retain(|(cached_expr, cached_var)| {
+                cached_var != &target && !data_expr_references(cached_expr, &target)
+            });
+
+            if let Expr::Data(expr) = &mut assign.value {
+                if matches!(expr, DataExpr::FunctionCall(_)) && call_is_safe(expr, levels) {
+                    match seen.iter().find(|(cached_expr, _)| cached_expr == expr) {
+                        Some((_, existing)) => {
+                            *expr = DataExpr::Identifier(existing.clone());
+                            stats.common_subexpressions_hoisted += 1;
+                        }
+                        None => seen.push((expr.clone(), target)),
+                    }
+                }
+            }
+        }
+
+        match stmt {
+            ControlStmt::If(if_stmt) => {
+                common_subexpressions(&mut if_stmt.then_branch, levels, stats);
+                if let Some(else_branch) = &mut if_stmt.else_branch {
+                    common_subexpressions(else_branch, levels, stats);
+                }
+            }
+            ControlStmt::While(while_stmt) => common_subexpressions(&mut while_stmt.body, levels, stats),
+            ControlStmt::For(for_stmt) => common_subexpressions(&mut for_stmt.body, levels, stats),
+            ControlStmt::Block(inner) => common_subexpressions(inner, levels, stats),
+            _ => {}
+        }
+    }
+}
+
+/// Does every `FunctionCall` in `expr`'s tree call an inferred-`Pure`-or-
+/// `Total` function? An unindexed/external name is not safe (unlike
+/// `check_program`'s own `ImpureCall` check, which lets a call to an
+/// unresolved callee pass unreported).
+fn call_is_safe(expr: &DataExpr, levels: &HashMap<String, Purity>) -> bool {
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => true,
+        DataExpr::Add(left, right) => call_is_safe(left, levels) && call_is_safe(right, levels),
+        DataExpr::Negate(inner) => call_is_safe(inner, levels),
+        DataExpr::FunctionCall(call) => {
+            matches!(levels.get(&call.name), Some(Purity::Pure) | Some(Purity::Total))
+                && call.args.iter().all(|arg| call_is_safe(arg, levels))
+        }
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => elems.iter().all(|elem| call_is_safe(elem, levels)),
+        DataExpr::FieldAccess(base, _) => call_is_safe(base, levels),
+        DataExpr::StructLiteral(_, fields) => fields.iter().all(|(_, expr)| call_is_safe(expr, levels)),
+        DataExpr::ListComprehension(comp) => {
+            call_is_safe(&comp.body, levels)
+                && comp.generators.iter().all(|(_, source)| call_is_safe(source, levels))
+        }
+        DataExpr::Index(base, index) => call_is_safe(base, levels) && call_is_safe(index, levels),
+    }
+}
+
+/// Does `expr` mention the identifier `var` anywhere in its tree? Mirrors
+/// `purity::data_expr_references`, which isn't `pub(crate)` from that
+/// module.
+fn data_expr_references(expr: &DataExpr, var: &str) -> bool {
+    match expr {
+        DataExpr::Number(_) => false,
+        DataExpr::Identifier(name) => name == var,
+        DataExpr::Add(left, right) => data_expr_references(left, var) || data_expr_references(right, var),
+        DataExpr::Negate(inner) => data_expr_references(inner, var),
+        DataExpr::FunctionCall(call) => call.args.iter().any(|arg| data_expr_references(arg, var)),
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => elems.iter().any(|elem| data_expr_references(elem, var)),
+        DataExpr::FieldAccess(base, _) => data_expr_references(base, var),
+        DataExpr::StructLiteral(_, fields) => fields.iter().any(|(_, expr)| data_expr_references(expr, var)),
+        DataExpr::ListComprehension(comp) => {
+            data_expr_references(&comp.body, var)
+                || comp.generators.iter().any(|(_, source)| data_expr_references(source, var))
+        }
+        DataExpr::Index(base, index) => data_expr_references(base, var) || data_expr_references(index, var),
+    }
+}
+
+// ===== Dead-store elimination =====
+
+fn eliminate_dead_stores(
+    stmts: &mut Vec<ControlStmt>,
+    read_names: &HashSet<String>,
+    levels: &HashMap<String, Purity>,
+    stats: &mut OptimizationStats,
+) {
+    stmts.retain(|stmt| match stmt {
+        ControlStmt::Assignment(assign) => {
+            let removable = !read_names.contains(&assign.target) && call_is_safe_expr(&assign.value, levels);
+            if removable {
+                stats.dead_stores_eliminated += 1;
+            }
+            !removable
+        }
+        _ => true,
+    });
+
+    for stmt in stmts.iter_mut() {
+        match stmt {
+            ControlStmt::If(if_stmt) => {
+                eliminate_dead_stores(&mut if_stmt.then_branch, read_names, levels, stats);
+                if let Some(else_branch) = &mut if_stmt.else_branch {
+                    eliminate_dead_stores(else_branch, read_names, levels, stats);
+                }
+            }
+            ControlStmt::While(while_stmt) => eliminate_dead_stores(&mut while_stmt.body, read_names, levels, stats),
+            ControlStmt::For(for_stmt) => eliminate_dead_stores(&mut for_stmt.body, read_names, levels, stats),
+            ControlStmt::Block(inner) => eliminate_dead_stores(inner, read_names, levels, stats),
+            _ => {}
+        }
+    }
+}
+
+fn call_is_safe_expr(value: &Expr, levels: &HashMap<String, Purity>) -> bool {
+    match value {
+        Expr::Data(expr) => call_is_safe(expr, levels),
+        Expr::Control(expr) => call_is_safe_control(expr, levels),
+    }
+}
+
+fn call_is_safe_control(expr: &ControlExpr, levels: &HashMap<String, Purity>) -> bool {
+    match expr {
+        ControlExpr::Data(data) => call_is_safe(data, levels),
+        ControlExpr::Comparison(left, _, right) => call_is_safe(left, levels) && call_is_safe(right, levels),
+        ControlExpr::Logical(left, _, right) => call_is_safe_control(left, levels) && call_is_safe_control(right, levels),
+        ControlExpr::Not(inner) => call_is_safe_control(inner, levels),
+        ControlExpr::Contains(left, right) => call_is_safe(left, levels) && call_is_safe(right, levels),
+    }
+}
+
+/// Collects every identifier `stmts` reads from -- everywhere a `DataExpr`
+/// or `ControlExpr` is evaluated, plus a reversible `+=`/`-=`'s target
+/// (which reads the old value before writing the new one) -- but not a
+/// plain `Assignment`'s own target, which only ever writes.
+fn collect_read_names(stmts: &[ControlStmt], out: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            ControlStmt::Assignment(assign) => match &assign.value {
+                Expr::Data(expr) => collect_read_names_data(expr, out),
+                Expr::Control(expr) => collect_read_names_control(expr, out),
+            },
+            ControlStmt::If(if_stmt) => {
+                collect_read_names_control(&if_stmt.condition, out);
+                collect_read_names(&if_stmt.then_branch, out);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    collect_read_names(else_branch, out);
+                }
+            }
+            ControlStmt::While(while_stmt) => {
+                collect_read_names_control(&while_stmt.condition, out);
+                collect_read_names(&while_stmt.body, out);
+            }
+            ControlStmt::For(for_stmt) => {
+                collect_read_names_data(&for_stmt.range.start, out);
+                collect_read_names_data(&for_stmt.range.end, out);
+                if let Some(step) = &for_stmt.range.step {
+                    collect_read_names_data(step, out);
+                }
+                collect_read_names(&for_stmt.body, out);
+            }
+            ControlStmt::Return(Some(expr)) => collect_read_names_data(expr, out),
+            ControlStmt::Return(None) => {}
+            ControlStmt::Print(exprs) => {
+                for expr in exprs {
+                    collect_read_names_data(expr, out);
+                }
+            }
+            ControlStmt::ReverseBlock(block) => {
+                for stmt in &block.body {
+                    collect_read_names_reversible(stmt, out);
+                }
+            }
+            ControlStmt::Block(inner) => collect_read_names(inner, out),
+            ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+        }
+    }
+}
+
+fn collect_read_names_reversible(stmt: &ReversibleStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ReversibleStmt::AddAssign(target, expr)
+        | ReversibleStmt::SubAssign(target, expr)
+        | ReversibleStmt::MulAssign(target, expr)
+        | ReversibleStmt::DivAssign(target, expr) => {
+            out.insert(target.clone());
+            collect_read_names_data(expr, out);
+        }
+        // Unlike the read-modify-write ops above, a plain `Assign` doesn't
+        // read its own target -- same as `ControlStmt::Assignment` above.
+        ReversibleStmt::Assign(_, expr) => collect_read_names_data(expr, out),
+        ReversibleStmt::If(if_stmt) => {
+            collect_read_names_control(&if_stmt.condition, out);
+            collect_read_names(&if_stmt.then_branch, out);
+            if let Some(else_branch) = &if_stmt.else_branch {
+                collect_read_names(else_branch, out);
+            }
+        }
+        ReversibleStmt::For { from, to, step, body, .. } => {
+            collect_read_names_data(from, out);
+            collect_read_names_data(to, out);
+            if let Some(step) = step {
+                collect_read_names_data(step, out);
+            }
+            for stmt in body {
+                collect_read_names_reversible(stmt, out);
+            }
+        }
+        ReversibleStmt::Switch { scrutinee, cases, default } => {
+            collect_read_names_data(scrutinee, out);
+            for (value, body) in cases {
+                collect_read_names_data(value, out);
+                for stmt in body {
+                    collect_read_names_reversible(stmt, out);
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    collect_read_names_reversible(stmt, out);
+                }
+            }
+        }
+    }
+}
+
+fn collect_read_names_control(expr: &ControlExpr, out: &mut HashSet<String>) {
+    match expr {
+        ControlExpr::Data(data) => collect_read_names_data(data, out),
+        ControlExpr::Comparison(left, _, right) => {
+            collect_read_names_data(left, out);
+            collect_read_names_data(right, out);
+        }
+        ControlExpr::Logical(left, _, right) => {
+            collect_read_names_control(left, out);
+            collect_read_names_control(right, out);
+        }
+        ControlExpr::Not(inner) => collect_read_names_control(inner, out),
+        ControlExpr::Contains(left, right) => {
+            collect_read_names_data(left, out);
+            collect_read_names_data(right, out);
+        }
+    }
+}
+
+fn collect_read_names_data(expr: &DataExpr, out: &mut HashSet<String>) {
+    match expr {
+        DataExpr::Number(_) => {}
+        DataExpr::Identifier(name) => {
+            out.insert(name.clone());
+        }
+        DataExpr::Add(left, right) => {
+            collect_read_names_data(left, out);
+            collect_read_names_data(right, out);
+        }
+        DataExpr::Negate(inner) => collect_read_names_data(inner, out),
+        DataExpr::FunctionCall(call) => {
+            for arg in &call.args {
+                collect_read_names_data(arg, out);
+            }
+        }
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => {
+            for elem in elems {
+                collect_read_names_data(elem, out);
+            }
+        }
+        DataExpr::FieldAccess(base, _) => collect_read_names_data(base, out),
+        DataExpr::StructLiteral(_, fields) => {
+            for (_, expr) in fields {
+                collect_read_names_data(expr, out);
+            }
+        }
+        DataExpr::ListComprehension(comp) => {
+            collect_read_names_data(&comp.body, out);
+            for (_, source) in &comp.generators {
+                collect_read_names_data(source, out);
+            }
+            if let Some(condition) = &comp.condition {
+                collect_read_names_control(condition, out);
+            }
+        }
+        DataExpr::Index(base, index) => {
+            collect_read_names_data(base, out);
+            collect_read_names_data(index, out);
+        }
+    }
+}
+
+// ===== Constant index bounds checking =====
+
+/// Walks every function body in `program` and reports a
+/// `JtvError::IndexOutOfRange` for any `DataExpr::Index` whose base and
+/// index both fold down to compile-time literals -- a literal list/tuple
+/// paired with a literal (possibly negative) integer -- putting the bound
+/// out of range. Unlike `optimize_program`'s three rewrites this never
+/// touches the `Program`; it's a pure diagnostic a caller can run before
+/// `Interpreter::run` (e.g. an LSP's `diagnose` path) to catch something
+/// like `[1, 2, 3][5]` without executing anything.
+///
+/// Only literal containers are handled -- there's no string-literal
+/// `DataExpr` in this AST to fold (`Number::Symbolic` names a symbolic-math
+/// variable, not a string), so a runtime `Value::String` produced by a
+/// stdlib call is checked only when the interpreter actually indexes it,
+/// not here.
+pub fn check_constant_indices(program: &Program) -> Vec<JtvError> {
+    let mut errors = Vec::new();
+    for item in &program.statements {
+        check_constant_indices_item(item, &mut errors);
+    }
+    errors
+}
+
+fn check_constant_indices_item(item: &TopLevel, errors: &mut Vec<JtvError>) {
+    match item {
+        TopLevel::Function(func) => {
+            for stmt in &func.body {
+                check_constant_indices_stmt(stmt, errors);
+            }
+        }
+        TopLevel::Module(module) => {
+            for item in &module.body {
+                check_constant_indices_item(item, errors);
+            }
+        }
+        TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Test(_) | TopLevel::Control(_) => {}
+    }
+}
+
+fn check_constant_indices_stmt(stmt: &ControlStmt, errors: &mut Vec<JtvError>) {
+    match stmt {
+        ControlStmt::Assignment(assign) => match &assign.value {
+            Expr::Data(expr) => check_constant_indices_data(expr, errors),
+            Expr::Control(expr) => check_constant_indices_control(expr, errors),
+        },
+        ControlStmt::If(if_stmt) => {
+            check_constant_indices_control(&if_stmt.condition, errors);
+            for stmt in &if_stmt.then_branch {
+                check_constant_indices_stmt(stmt, errors);
+            }
+            if let Some(else_branch) = &if_stmt.else_branch {
+                for stmt in else_branch {
+                    check_constant_indices_stmt(stmt, errors);
+                }
+            }
+        }
+        ControlStmt::While(while_stmt) => {
+            check_constant_indices_control(&while_stmt.condition, errors);
+            for stmt in &while_stmt.body {
+                check_constant_indices_stmt(stmt, errors);
+            }
+        }
+        ControlStmt::For(for_stmt) => {
+            check_constant_indices_data(&for_stmt.range.start, errors);
+            check_constant_indices_data(&for_stmt.range.end, errors);
+            if let Some(step) = &for_stmt.range.step {
+                check_constant_indices_data(step, errors);
+            }
+            for stmt in &for_stmt.body {
+                check_constant_indices_stmt(stmt, errors);
+            }
+        }
+        ControlStmt::Return(Some(expr)) => check_constant_indices_data(expr, errors),
+        ControlStmt::Return(None) => {}
+        ControlStmt::Print(exprs) => {
+            for expr in exprs {
+                check_constant_indices_data(expr, errors);
+            }
+        }
+        ControlStmt::ReverseBlock(block) => {
+            for stmt in &block.body {
+                check_constant_indices_reversible(stmt, errors);
+            }
+        }
+        ControlStmt::Block(inner) => {
+            for stmt in inner {
+                check_constant_indices_stmt(stmt, errors);
+            }
+        }
+        ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+    }
+}
+
+fn check_constant_indices_reversible(stmt: &ReversibleStmt, errors: &mut Vec<JtvError>) {
+    match stmt {
+        ReversibleStmt::AddAssign(_, expr)
+        | ReversibleStmt::SubAssign(_, expr)
+        | ReversibleStmt::MulAssign(_, expr)
+        | ReversibleStmt::DivAssign(_, expr)
+        | ReversibleStmt::Assign(_, expr) => {
+            check_constant_indices_data(expr, errors)
+        }
+        ReversibleStmt::If(if_stmt) => {
+            check_constant_indices_control(&if_stmt.condition, errors);
+            for stmt in &if_stmt.then_branch {
+                check_constant_indices_stmt(stmt, errors);
+            }
+            if let Some(else_branch) = &if_stmt.else_branch {
+                for stmt in else_branch {
+                    check_constant_indices_stmt(stmt, errors);
+                }
+            }
+        }
+        ReversibleStmt::For { from, to, step, body, .. } => {
+            check_constant_indices_data(from, errors);
+            check_constant_indices_data(to, errors);
+            if let Some(step) = step {
+                check_constant_indices_data(step, errors);
+            }
+            for stmt in body {
+                check_constant_indices_reversible(stmt, errors);
+            }
+        }
+        ReversibleStmt::Switch { scrutinee, cases, default } => {
+            check_constant_indices_data(scrutinee, errors);
+            for (value, body) in cases {
+                check_constant_indices_data(value, errors);
+                for stmt in body {
+                    check_constant_indices_reversible(stmt, errors);
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    check_constant_indices_reversible(stmt, errors);
+                }
+            }
+        }
+    }
+}
+
+fn check_constant_indices_control(expr: &ControlExpr, errors: &mut Vec<JtvError>) {
+    match expr {
+        ControlExpr::Data(data) => check_constant_indices_data(data, errors),
+        ControlExpr::Comparison(left, _, right) => {
+            check_constant_indices_data(left, errors);
+            check_constant_indices_data(right, errors);
+        }
+        ControlExpr::Logical(left, _, right) => {
+            check_constant_indices_control(left, errors);
+            check_constant_indices_control(right, errors);
+        }
+        ControlExpr::Not(inner) => check_constant_indices_control(inner, errors),
+        ControlExpr::Contains(left, right) => {
+            check_constant_indices_data(left, errors);
+            check_constant_indices_data(right, errors);
+        }
+    }
+}
+
+fn check_constant_indices_data(expr: &DataExpr, errors: &mut Vec<JtvError>) {
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => {}
+        DataExpr::Add(left, right) => {
+            check_constant_indices_data(left, errors);
+            check_constant_indices_data(right, errors);
+        }
+        DataExpr::Negate(inner) => check_constant_indices_data(inner, errors),
+        DataExpr::FunctionCall(call) => {
+            for arg in &call.args {
+                check_constant_indices_data(arg, errors);
+            }
+        }
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => {
+            for elem in elems {
+                check_constant_indices_data(elem, errors);
+            }
+        }
+        DataExpr::FieldAccess(base, _) => check_constant_indices_data(base, errors),
+        DataExpr::StructLiteral(_, fields) => {
+            for (_, expr) in fields {
+                check_constant_indices_data(expr, errors);
+            }
+        }
+        DataExpr::ListComprehension(comp) => {
+            check_constant_indices_data(&comp.body, errors);
+            for (_, source) in &comp.generators {
+                check_constant_indices_data(source, errors);
+            }
+            if let Some(condition) = &comp.condition {
+                check_constant_indices_control(condition, errors);
+            }
+        }
+        DataExpr::Index(base, index) => {
+            check_constant_indices_data(base, errors);
+            check_constant_indices_data(index, errors);
+
+            let size = match eval_literal(base) {
+                Some(Value::List(items)) => Some(items.len()),
+                Some(Value::Tuple(items)) => Some(items.len()),
+                _ => None,
+            };
+            if let (Some(size), Some(Value::Int(i))) = (size, eval_literal(index)) {
+                if i < 0 || i as usize >= size {
+                    errors.push(JtvError::IndexOutOfRange {
+                        index: i.to_string(),
+                        size,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Evaluate `expr` if (and only if) it's composed solely of literals --
+/// `Number`s, `Add`/`Negate` of other literals, and `List`/`Tuple` of
+/// literals -- returning `None` for anything that reads a variable, calls a
+/// function, or otherwise isn't known until the program actually runs.
+fn eval_literal(expr: &DataExpr) -> Option<Value> {
+    match expr {
+        DataExpr::Number(num) => Value::from_number(num).ok(),
+        DataExpr::Add(left, right) => eval_literal(left)?.add(&eval_literal(right)?).ok(),
+        DataExpr::Negate(inner) => eval_literal(inner)?.negate().ok(),
+        DataExpr::List(elems) => elems
+            .iter()
+            .map(eval_literal)
+            .collect::<Option<Vec<_>>>()
+            .map(|items| Value::List(PVector::from_vec(items))),
+        DataExpr::Tuple(elems) => elems
+            .iter()
+            .map(eval_literal)
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Tuple),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(name: &str, purity: Purity, body: Vec<ControlStmt>) -> FunctionDecl {
+        FunctionDecl {
+            name: name.to_string(),
+            type_params: vec![],
+            params: vec![],
+            return_type: None,
+            purity,
+            body,
+            span: Span { start: 0, end: 0, line: 1, col: 1 },
+            trivia: Trivia::default(),
+        }
+    }
+
+    fn func_with_params(name: &str, purity: Purity, params: Vec<&str>, body: Vec<ControlStmt>) -> FunctionDecl {
+        FunctionDecl {
+            params: params
+                .into_iter()
+                .map(|p| Param { name: p.to_string(), type_annotation: None })
+                .collect(),
+            ..func(name, purity, body)
+        }
+    }
+
+    fn program(functions: Vec<FunctionDecl>) -> Program {
+        Program {
+            statements: functions.into_iter().map(TopLevel::Function).collect(),
+            span: Span::unknown(),
+        }
+    }
+
+    fn call(name: &str, args: Vec<DataExpr>) -> DataExpr {
+        DataExpr::FunctionCall(FunctionCall { name: name.to_string(), args })
+    }
+
+    #[test]
+    fn test_folds_total_call_on_literal_arguments() {
+        let mut prog = program(vec![
+            func_with_params(
+                "add_one",
+                Purity::Total,
+                vec!["n"],
+                vec![ControlStmt::Return(Some(DataExpr::Add(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                )))],
+            ),
+            func(
+                "main",
+                Purity::Impure,
+                vec![ControlStmt::Assignment(Assignment {
+                    target: "x".to_string(),
+                    value: Expr::Data(call("add_one", vec![DataExpr::Number(Number::Int(41))])),
+                })],
+            ),
+        ]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.constants_folded, 1);
+
+        let TopLevel::Function(main) = &prog.statements[1] else { panic!("expected function") };
+        let ControlStmt::Assignment(assign) = &main.body[0] else { panic!("expected assignment") };
+        assert_eq!(assign.value, Expr::Data(DataExpr::Number(Number::Int(42))));
+    }
+
+    #[test]
+    fn test_refuses_to_fold_call_to_impure_function() {
+        let mut prog = program(vec![
+            func_with_params(
+                "add_one",
+                Purity::Impure,
+                vec!["n"],
+                vec![ControlStmt::Return(Some(DataExpr::Add(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                )))],
+            ),
+            func(
+                "main",
+                Purity::Impure,
+                vec![
+                    ControlStmt::Print(vec![DataExpr::Number(Number::Int(0))]),
+                    ControlStmt::Assignment(Assignment {
+                        target: "x".to_string(),
+                        value: Expr::Data(call("add_one", vec![DataExpr::Number(Number::Int(41))])),
+                    }),
+                ],
+            ),
+        ]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.constants_folded, 0);
+    }
+
+    #[test]
+    fn test_hoists_repeated_pure_call_in_same_block() {
+        let mut prog = program(vec![
+            func_with_params("double", Purity::Pure, vec!["n"], vec![ControlStmt::Return(Some(
+                DataExpr::Add(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                ),
+            ))]),
+            func(
+                "main",
+                Purity::Impure,
+                vec![
+                    ControlStmt::Assignment(Assignment {
+                        target: "a".to_string(),
+                        value: Expr::Data(call("double", vec![DataExpr::Identifier("x".to_string())])),
+                    }),
+                    ControlStmt::Assignment(Assignment {
+                        target: "b".to_string(),
+                        value: Expr::Data(call("double", vec![DataExpr::Identifier("x".to_string())])),
+                    }),
+                    ControlStmt::Return(Some(DataExpr::Add(
+                        Box::new(DataExpr::Identifier("a".to_string())),
+                        Box::new(DataExpr::Identifier("b".to_string())),
+                    ))),
+                ],
+            ),
+        ]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.common_subexpressions_hoisted, 1);
+
+        let TopLevel::Function(main) = &prog.statements[1] else { panic!("expected function") };
+        let ControlStmt::Assignment(second) = &main.body[1] else { panic!("expected assignment") };
+        assert_eq!(second.value, Expr::Data(DataExpr::Identifier("a".to_string())));
+    }
+
+    #[test]
+    fn test_does_not_hoist_across_intervening_reassignment() {
+        let mut prog = program(vec![
+            func_with_params(
+                "double",
+                Purity::Pure,
+                vec!["n"],
+                vec![ControlStmt::Return(Some(DataExpr::Add(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                )))],
+            ),
+            func(
+                "main",
+                Purity::Impure,
+                vec![
+                    ControlStmt::Assignment(Assignment {
+                        target: "a".to_string(),
+                        value: Expr::Data(call("double", vec![DataExpr::Identifier("x".to_string())])),
+                    }),
+                    ControlStmt::Assignment(Assignment {
+                        target: "x".to_string(),
+                        value: Expr::Data(DataExpr::Number(Number::Int(9))),
+                    }),
+                    ControlStmt::Assignment(Assignment {
+                        target: "b".to_string(),
+                        value: Expr::Data(call("double", vec![DataExpr::Identifier("x".to_string())])),
+                    }),
+                ],
+            ),
+        ]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.common_subexpressions_hoisted, 0);
+    }
+
+    #[test]
+    fn test_eliminates_unread_pure_assignment() {
+        let mut prog = program(vec![func(
+            "main",
+            Purity::Impure,
+            vec![
+                ControlStmt::Assignment(Assignment {
+                    target: "unused".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                }),
+                ControlStmt::Return(Some(DataExpr::Number(Number::Int(2)))),
+            ],
+        )]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.dead_stores_eliminated, 1);
+
+        let TopLevel::Function(main) = &prog.statements[0] else { panic!("expected function") };
+        assert_eq!(main.body.len(), 1);
+    }
+
+    #[test]
+    fn test_keeps_unread_assignment_whose_call_is_impure() {
+        let mut prog = program(vec![
+            func("log_and_return_one", Purity::Impure, vec![
+                ControlStmt::Print(vec![DataExpr::Number(Number::Int(0))]),
+                ControlStmt::Return(Some(DataExpr::Number(Number::Int(1)))),
+            ]),
+            func(
+                "main",
+                Purity::Impure,
+                vec![
+                    ControlStmt::Assignment(Assignment {
+                        target: "unused".to_string(),
+                        value: Expr::Data(call("log_and_return_one", vec![])),
+                    }),
+                    ControlStmt::Return(Some(DataExpr::Number(Number::Int(2)))),
+                ],
+            ),
+        ]);
+
+        let stats = optimize_program(&mut prog);
+        assert_eq!(stats.dead_stores_eliminated, 0);
+
+        let TopLevel::Function(main) = &prog.statements[1] else { panic!("expected function") };
+        assert_eq!(main.body.len(), 2);
+    }
+
+    #[test]
+    fn test_check_constant_indices_flags_literal_list_out_of_range() {
+        let prog = program(vec![func(
+            "main",
+            Purity::Pure,
+            vec![ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Data(DataExpr::Index(
+                    Box::new(DataExpr::List(vec![
+                        DataExpr::Number(Number::Int(1)),
+                        DataExpr::Number(Number::Int(2)),
+                    ])),
+                    Box::new(DataExpr::Number(Number::Int(5))),
+                )),
+            })],
+        )]);
+
+        let errors = check_constant_indices(&prog);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            JtvError::IndexOutOfRange { index, size: 2 } if index == "5"
+        ));
+    }
+
+    #[test]
+    fn test_check_constant_indices_flags_negative_literal_index() {
+        let prog = program(vec![func(
+            "main",
+            Purity::Pure,
+            vec![ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Data(DataExpr::Index(
+                    Box::new(DataExpr::Tuple(vec![DataExpr::Number(Number::Int(1))])),
+                    Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+                )),
+            })],
+        )]);
+
+        let errors = check_constant_indices(&prog);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0], JtvError::IndexOutOfRange { index, .. } if index == "-1"));
+    }
+
+    #[test]
+    fn test_check_constant_indices_accepts_in_range_literal_index() {
+        let prog = program(vec![func(
+            "main",
+            Purity::Pure,
+            vec![ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Data(DataExpr::Index(
+                    Box::new(DataExpr::List(vec![
+                        DataExpr::Number(Number::Int(1)),
+                        DataExpr::Number(Number::Int(2)),
+                    ])),
+                    Box::new(DataExpr::Number(Number::Int(1))),
+                )),
+            })],
+        )]);
+
+        assert!(check_constant_indices(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_check_constant_indices_ignores_variable_index() {
+        // `xs[i]` can't be folded without running the program -- `i` isn't
+        // a literal, so this must not be flagged.
+        let prog = program(vec![func(
+            "main",
+            Purity::Pure,
+            vec![ControlStmt::Assignment(Assignment {
+                target: "x".to_string(),
+                value: Expr::Data(DataExpr::Index(
+                    Box::new(DataExpr::List(vec![DataExpr::Number(Number::Int(1))])),
+                    Box::new(DataExpr::Identifier("i".to_string())),
+                )),
+            })],
+        )]);
+
+        assert!(check_constant_indices(&prog).is_empty());
+    }
+}
@@ -0,0 +1,1728 @@
+// Purity and totality verification for Julia the Viper.
+//
+// `FunctionDecl::purity` is only ever read as a label today; nothing checks
+// that a function actually lives up to it. `PurityChecker` is a `Visitor`
+// (see `crate::ast::Visitor`) that walks a `Program` after its functions
+// (including those nested in a `ModuleDecl`, and reachable through an
+// `ImportStmt` alias) have been indexed, and rejects:
+//
+// - an `@pure` function whose body contains a `While`, `For`, or `Print`,
+//   assigns (or reversibly `+=`/`-=`s) a name bound outside its own frame,
+//   or calls a function whose own declared purity isn't `Pure`;
+// - an `@total` function whose body contains a `While` with no recognized
+//   decreasing, bounded-below measure; a `For` whose bounds reference its
+//   own loop variable or whose body reassigns it; or whose direct/
+//   mutually-recursive call isn't provably on a structurally smaller
+//   argument.
+//
+// "Bound outside its own frame" only covers what this AST can actually
+// express: a name also assigned at module top level (tracked as `globals`
+// below). `Param` carries no `mut`/by-reference flag and every call binds
+// its arguments by value (see `Interpreter::call_*`), so parameters are
+// always frame-local here; likewise there's no indexed-assignment target
+// (`arr[i] = ...`), so "a mutable collection element reached through an
+// identifier the function didn't allocate" has nothing to check yet.
+//
+// Diagnostics are attributed to the enclosing `FunctionDecl`'s `span`
+// (`crate::ast::Span`) rather than the offending statement's -- `ControlStmt`
+// and `DataExpr` don't carry their own spans yet, so "line N" here means
+// the function's declaration line, not the exact loop/call site.
+//
+// `check_program` only ever checks a function against *its own* declared
+// `purity`, trusting a callee's declared purity at face value -- which
+// leaves a (mutually) recursive group no better than whatever its weakest
+// member happens to be annotated. `PurityChecker::infer_program` instead
+// asks what the strongest `Purity` every function could truthfully carry,
+// regardless of its annotation: it resolves the call graph's strongly-
+// connected components with Tarjan's algorithm (`tarjan_sccs`) and
+// fixpoint-iterates each one in dependency order, starting every member
+// optimistically at `Pure` and only weakening a member once the current
+// (possibly still-converging, for an intra-SCC callee) levels of its own
+// callees rule that out -- so a mutually-recursive pair resolves to its
+// real minimal level instead of whatever its declaration says.
+//
+// `PurityErrorKind::EffectfulConstruct` additionally names which
+// `crate::effects::Effect` the offending construct performs (`Io` for
+// `print`, `NonTermination` for `while`/`for`), so a diagnostic reads as
+// "performs a disallowed `io` effect" rather than a generic "contains a
+// `print`" -- see `effects` for why that's as far as the effect lattice
+// reaches here (no `Alloc`/`Random`/`Partial` checking, no `@effects(...)`
+// annotation surface).
+
+use crate::ast::*;
+use crate::effects::{Effect, EffectSet};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The `Effect`s an `@pure` function may perform -- used in place of the
+/// `current.purity == Purity::Pure` checks `report`'s callers used to do
+/// directly, now routed through `EffectSet::satisfies` so `Io` and
+/// `Mutation` are gated by the same lattice their diagnostics are
+/// attributed from. `Total` and `Impure` both allow every effect this
+/// checker tracks outside of termination, which is checked separately
+/// (structurally, not by a static mask -- see `while_has_decreasing_measure`
+/// and `for_loop_is_bounded`).
+fn allowed_effects(purity: &Purity) -> EffectSet {
+    match purity {
+        Purity::Pure => EffectSet::empty(),
+        Purity::Total | Purity::Impure => EffectSet::of(Effect::Io).combine(EffectSet::of(Effect::Mutation)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PurityErrorKind {
+    /// An `@pure` function's body contains a `while`, `for`, or `print`,
+    /// performing the named disallowed `Effect`.
+    EffectfulConstruct { construct: &'static str, effect: Effect },
+    /// An `@pure` function calls a function whose declared purity isn't `Pure`.
+    ImpureCall { callee: String },
+    /// An `@pure` function assigns (or reversibly `+=`/`-=`s) a name that is
+    /// also bound at module top level, rather than one it introduced itself.
+    NonLocalWrite { target: String },
+    /// An `@total` function's `while` loop has no decreasing, bounded-below
+    /// measure the checker can recognize.
+    UnboundedLoop,
+    /// An `@total` function's `for` loop reassigns its own loop variable,
+    /// or its range bounds reference it, so the checker can't treat it as
+    /// iterating a fixed, already-bounded range.
+    NonTerminatingFor,
+    /// An `@total` function's (possibly mutually) recursive call isn't
+    /// provably on a structurally smaller argument.
+    UnprovenRecursion { callee: String },
+}
+
+impl fmt::Display for PurityErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PurityErrorKind::EffectfulConstruct { construct, effect } => {
+                write!(
+                    f,
+                    "contains a `{}`, performing a disallowed `{}` effect",
+                    construct,
+                    effect.name()
+                )
+            }
+            PurityErrorKind::ImpureCall { callee } => {
+                write!(f, "calls non-pure function `{}`", callee)
+            }
+            PurityErrorKind::NonLocalWrite { target } => {
+                write!(
+                    f,
+                    "assigns `{}`, which is bound outside its own frame, performing a disallowed `mutation` effect",
+                    target
+                )
+            }
+            PurityErrorKind::UnboundedLoop => {
+                write!(f, "contains a `while` loop (potentially unbounded)")
+            }
+            PurityErrorKind::NonTerminatingFor => write!(
+                f,
+                "contains a `for` loop whose bounds or loop variable aren't fixed for the whole loop"
+            ),
+            PurityErrorKind::UnprovenRecursion { callee } => write!(
+                f,
+                "recursive call to `{}` is not provably on a structurally smaller argument",
+                callee
+            ),
+        }
+    }
+}
+
+/// A single located purity/totality violation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurityDiagnostic {
+    pub function: String,
+    /// The enclosing function's declaration line -- see the module-level
+    /// doc comment for why this isn't the offending statement's own line.
+    pub line: u32,
+    pub kind: PurityErrorKind,
+}
+
+impl fmt::Display for PurityDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "function '{}' marked @{} but {} at line {}",
+            self.function,
+            if matches!(
+                self.kind,
+                PurityErrorKind::EffectfulConstruct { .. }
+                    | PurityErrorKind::ImpureCall { .. }
+                    | PurityErrorKind::NonLocalWrite { .. }
+            ) {
+                "pure"
+            } else {
+                "total"
+            },
+            self.kind,
+            self.line
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CurrentFn {
+    name: String,
+    purity: Purity,
+    span: Span,
+}
+
+/// Walks a `Program` and reports every `PurityDiagnostic` it can find.
+/// Construct with [`PurityChecker::new`] and drive it with
+/// [`PurityChecker::check_program`] (a thin wrapper over the `Visitor`
+/// `accept` call) rather than implementing `Visitor` by hand at the call
+/// site.
+pub struct PurityChecker {
+    /// Every function in scope, by name, flattened out of nested
+    /// `ModuleDecl`s. Import aliases are added as extra keys pointing at
+    /// the same `FunctionDecl`.
+    functions: HashMap<String, FunctionDecl>,
+    /// Direct callees of each function, used to detect (mutual) recursion
+    /// for the `@total` check.
+    calls: HashMap<String, HashSet<String>>,
+    /// Names assigned by a top-level (module-scope) `Assignment`, for
+    /// recognizing when an `@pure` function's assignment target escapes
+    /// its own frame instead of introducing a fresh local.
+    globals: HashSet<String>,
+    /// `For`-loop variables currently in scope, for recognizing `f(i)`
+    /// as a structurally smaller recursive argument.
+    loop_vars: HashSet<String>,
+    /// Names the function currently being checked has bound itself --
+    /// its parameters, plus every name it has assigned so far while
+    /// walking its body in order. An assignment to a name outside this
+    /// set that also appears in `globals` is a `NonLocalWrite`; either
+    /// way, the target joins this set afterward so repeated writes to the
+    /// same name are only ever reported once per function.
+    locals: HashSet<String>,
+    current: Option<CurrentFn>,
+    diagnostics: Vec<PurityDiagnostic>,
+}
+
+impl PurityChecker {
+    pub fn new() -> Self {
+        PurityChecker {
+            functions: HashMap::new(),
+            calls: HashMap::new(),
+            globals: HashSet::new(),
+            loop_vars: HashSet::new(),
+            locals: HashSet::new(),
+            current: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Check every function declared in `program` (including inside nested
+    /// modules) and return every violation found. Never bails early, so a
+    /// program with several offending functions reports all of them.
+    pub fn check_program(program: &Program) -> Vec<PurityDiagnostic> {
+        let mut checker = PurityChecker::new();
+        checker.visit_program(program);
+        checker.diagnostics
+    }
+
+    /// Checks a single `TestDecl`'s body as though it were declared
+    /// `@pure`, for `jtv test`'s `pure test "name" { ... }` support: a
+    /// test that claims purity but contains a `While`/`For`/`Print` gets
+    /// back the same `PurityDiagnostic`s an `@pure` function would, which
+    /// the runner turns into a test failure instead of letting it pass
+    /// silently. Calls are not checked: no functions are indexed here, so
+    /// a call to an impure function is not reported.
+    pub fn check_test(test: &TestDecl) -> Vec<PurityDiagnostic> {
+        let mut checker = PurityChecker::new();
+        let synthetic = FunctionDecl {
+            name: test.name.clone(),
+            type_params: Vec::new(),
+            params: Vec::new(),
+            return_type: None,
+            purity: Purity::Pure,
+            body: test.body.clone(),
+            span: test.span,
+            trivia: Trivia::default(),
+        };
+        checker.visit_function_decl(&synthetic);
+        checker.diagnostics
+    }
+
+    /// Infers the strongest `Purity` each function in `program` could
+    /// truthfully be labeled, independent of its own declared annotation --
+    /// see the module-level doc comment above for why this differs from
+    /// `check_program`. A function outside the returned map was never
+    /// indexed at all (e.g. a name only ever reached through an import this
+    /// checker couldn't resolve); treat it as `Impure`. (`check_program`
+    /// itself lets a call to an unresolvable callee pass unreported.)
+    pub fn infer_program(program: &Program) -> HashMap<String, Purity> {
+        let mut indexer = PurityChecker::new();
+        indexer.index(&program.statements);
+
+        let sccs = tarjan_sccs(&indexer.functions, &indexer.calls);
+        let mut levels: HashMap<String, Purity> = HashMap::new();
+
+        for scc in &sccs {
+            for name in scc {
+                levels.insert(name.clone(), Purity::Pure);
+            }
+            loop {
+                let mut changed = false;
+                for name in scc {
+                    let achieved = best_achievable_purity(
+                        name,
+                        &indexer.functions,
+                        &indexer.calls,
+                        &indexer.globals,
+                        &levels,
+                    );
+                    if purity_rank(&achieved) > purity_rank(&levels[name]) {
+                        levels.insert(name.clone(), achieved);
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        levels
+    }
+
+    /// Indexes every function (including those nested in a `ModuleDecl`,
+    /// wherever it appears) in one pass, then resolves `ImportStmt` aliases
+    /// in a second pass -- so an import doesn't miss a function declared
+    /// later in the same `Program` (e.g. a module defined after the
+    /// `import` that pulls from it).
+    fn index(&mut self, items: &[TopLevel]) {
+        self.index_functions(items);
+        self.index_imports(items);
+        self.index_globals(items);
+    }
+
+    /// Collects every name assigned by a top-level `Control` statement
+    /// (including one nested in a `ModuleDecl`) -- the module-scope state
+    /// an `@pure` function's own assignments must not collide with.
+    fn index_globals(&mut self, items: &[TopLevel]) {
+        for item in items {
+            match item {
+                TopLevel::Control(stmt) => collect_assigned_names(std::slice::from_ref(stmt), &mut self.globals),
+                TopLevel::Module(module) => self.index_globals(&module.body),
+                TopLevel::Function(_) | TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Test(_) => {}
+            }
+        }
+    }
+
+    fn index_functions(&mut self, items: &[TopLevel]) {
+        for item in items {
+            match item {
+                TopLevel::Function(func) => {
+                    self.calls.insert(func.name.clone(), collect_calls(&func.body));
+                    self.functions.insert(func.name.clone(), func.clone());
+                }
+                TopLevel::Module(module) => self.index_functions(&module.body),
+                TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Test(_) | TopLevel::Control(_) => {}
+            }
+        }
+    }
+
+    fn index_imports(&mut self, items: &[TopLevel]) {
+        for item in items {
+            match item {
+                // Best-effort: an aliased import makes the imported
+                // function callable under the alias too. The imported
+                // name itself is whatever the last path segment names;
+                // there's no separate module/file resolution here, so this
+                // only helps when the target was indexed from the same
+                // `Program` (e.g. a sibling `ModuleDecl`).
+                TopLevel::Import(import) => {
+                    if let (Some(alias), Some(target)) = (&import.alias, import.path.last()) {
+                        if let Some(func) = self.functions.get(target).cloned() {
+                            self.functions.insert(alias.clone(), func);
+                        }
+                    }
+                }
+                TopLevel::Module(module) => self.index_imports(&module.body),
+                TopLevel::Function(_) | TopLevel::Struct(_) | TopLevel::Test(_) | TopLevel::Control(_) => {}
+            }
+        }
+    }
+
+    fn check_items(&mut self, items: &[TopLevel]) {
+        for item in items {
+            match item {
+                TopLevel::Function(func) => self.visit_function_decl(func),
+                TopLevel::Module(module) => self.check_items(&module.body),
+                TopLevel::Import(_) | TopLevel::Struct(_) | TopLevel::Test(_) => {}
+                TopLevel::Control(stmt) => self.visit_control_stmt(stmt),
+            }
+        }
+    }
+
+    fn report(&mut self, kind: PurityErrorKind) {
+        if let Some(current) = &self.current {
+            self.diagnostics.push(PurityDiagnostic {
+                function: current.name.clone(),
+                line: current.span.line,
+                kind,
+            });
+        }
+    }
+
+    /// Is `callee` a direct or mutually-recursive call from the function
+    /// currently being checked?
+    fn is_recursive_call(&self, callee: &str) -> bool {
+        let current = match &self.current {
+            Some(current) => current,
+            None => return false,
+        };
+        if callee == current.name {
+            return true;
+        }
+        reachable_from(&self.calls, &current.name).contains(callee)
+            && reachable_from(&self.calls, callee).contains(current.name.as_str())
+    }
+
+    /// Does `arg` provably shrink on every recursive call, per the
+    /// syntactic forms this checker recognizes:
+    /// - a `For`-loop variable currently in scope;
+    /// - `x + (-n)` for a literal `n` (an `Add` of an identifier and a
+    ///   negated number), which strictly decreases `x`;
+    /// - a `Negate` of an already-recognized smaller expression.
+    ///
+    /// This is a conservative syntactic approximation, not a termination
+    /// prover: it accepts the shapes the `@total` fragment of the language
+    /// is expected to be written in, and rejects everything else rather
+    /// than trying to evaluate whether an arbitrary expression shrinks.
+    fn arg_is_structurally_smaller(&self, arg: &DataExpr) -> bool {
+        match arg {
+            DataExpr::Identifier(name) => self.loop_vars.contains(name),
+            DataExpr::Add(left, right) => {
+                matches!(left.as_ref(), DataExpr::Identifier(_))
+                    && matches!(right.as_ref(), DataExpr::Negate(inner) if matches!(inner.as_ref(), DataExpr::Number(_)))
+            }
+            DataExpr::Negate(inner) => self.arg_is_structurally_smaller(inner),
+            _ => false,
+        }
+    }
+
+    /// Reports a `NonLocalWrite` if `target` escapes the current `@pure`
+    /// function's frame (it names a module-level global the function
+    /// hasn't already bound itself), then records it as local either way
+    /// so a second write to the same name isn't reported again.
+    fn check_assignment_target(&mut self, target: &str) {
+        if let Some(current) = &self.current {
+            if !allowed_effects(&current.purity).contains(Effect::Mutation)
+                && self.globals.contains(target)
+                && !self.locals.contains(target)
+            {
+                self.report(PurityErrorKind::NonLocalWrite {
+                    target: target.to_string(),
+                });
+            }
+        }
+        self.locals.insert(target.to_string());
+    }
+
+    fn visit_data_expr_list(&mut self, exprs: &[DataExpr]) {
+        for expr in exprs {
+            self.visit_data_expr(expr);
+        }
+    }
+
+    fn visit_control_expr(&mut self, expr: &ControlExpr) {
+        match expr {
+            ControlExpr::Data(data) => self.visit_data_expr(data),
+            ControlExpr::Comparison(left, _, right) => {
+                self.visit_data_expr(left);
+                self.visit_data_expr(right);
+            }
+            ControlExpr::Logical(left, _, right) => {
+                self.visit_control_expr(left);
+                self.visit_control_expr(right);
+            }
+            ControlExpr::Not(inner) => self.visit_control_expr(inner),
+            ControlExpr::Contains(left, right) => {
+                self.visit_data_expr(left);
+                self.visit_data_expr(right);
+            }
+        }
+    }
+
+    fn visit_reversible_stmt(&mut self, stmt: &ReversibleStmt) {
+        match stmt {
+            ReversibleStmt::AddAssign(target, expr)
+            | ReversibleStmt::SubAssign(target, expr)
+            | ReversibleStmt::MulAssign(target, expr)
+            | ReversibleStmt::DivAssign(target, expr)
+            | ReversibleStmt::Assign(target, expr) => {
+                self.check_assignment_target(target);
+                self.visit_data_expr(expr)
+            }
+            ReversibleStmt::If(if_stmt) => {
+                self.visit_control_expr(&if_stmt.condition);
+                for stmt in &if_stmt.then_branch {
+                    self.visit_control_stmt(stmt);
+                }
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    for stmt in else_branch {
+                        self.visit_control_stmt(stmt);
+                    }
+                }
+            }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                self.visit_data_expr(from);
+                self.visit_data_expr(to);
+                if let Some(step) = step {
+                    self.visit_data_expr(step);
+                }
+
+                let shadowed = self.loop_vars.insert(var.clone());
+                self.locals.insert(var.clone());
+                for stmt in body {
+                    self.visit_reversible_stmt(stmt);
+                }
+                if shadowed {
+                    self.loop_vars.remove(var);
+                }
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                self.visit_data_expr(scrutinee);
+                for (value, body) in cases {
+                    self.visit_data_expr(value);
+                    for stmt in body {
+                        self.visit_reversible_stmt(stmt);
+                    }
+                }
+                if let Some(default) = default {
+                    for stmt in default {
+                        self.visit_reversible_stmt(stmt);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for PurityChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Visitor for PurityChecker {
+    fn visit_program(&mut self, program: &Program) {
+        self.index(&program.statements);
+        self.check_items(&program.statements);
+    }
+
+    fn visit_function_decl(&mut self, func: &FunctionDecl) {
+        let previous = self.current.replace(CurrentFn {
+            name: func.name.clone(),
+            purity: func.purity.clone(),
+            span: func.span,
+        });
+        let previous_loop_vars = std::mem::take(&mut self.loop_vars);
+        let previous_locals = std::mem::take(&mut self.locals);
+        self.locals.extend(func.params.iter().map(|p| p.name.clone()));
+
+        for stmt in &func.body {
+            self.visit_control_stmt(stmt);
+        }
+
+        self.loop_vars = previous_loop_vars;
+        self.locals = previous_locals;
+        self.current = previous;
+    }
+
+    fn visit_control_stmt(&mut self, stmt: &ControlStmt) {
+        match stmt {
+            ControlStmt::Assignment(assign) => {
+                self.check_assignment_target(&assign.target);
+                match &assign.value {
+                    Expr::Data(expr) => self.visit_data_expr(expr),
+                    Expr::Control(expr) => self.visit_control_expr(expr),
+                }
+            }
+            ControlStmt::If(if_stmt) => {
+                self.visit_control_expr(&if_stmt.condition);
+                for stmt in &if_stmt.then_branch {
+                    self.visit_control_stmt(stmt);
+                }
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    for stmt in else_branch {
+                        self.visit_control_stmt(stmt);
+                    }
+                }
+            }
+            ControlStmt::While(while_stmt) => {
+                if let Some(current) = &self.current {
+                    match current.purity {
+                        Purity::Pure => self.report(PurityErrorKind::EffectfulConstruct {
+                            construct: "while",
+                            effect: Effect::NonTermination,
+                        }),
+                        Purity::Total => {
+                            if !while_has_decreasing_measure(while_stmt) {
+                                self.report(PurityErrorKind::UnboundedLoop);
+                            }
+                        }
+                        Purity::Impure => {}
+                    }
+                }
+                self.visit_control_expr(&while_stmt.condition);
+                for stmt in &while_stmt.body {
+                    self.visit_control_stmt(stmt);
+                }
+            }
+            ControlStmt::For(for_stmt) => {
+                if let Some(current) = &self.current {
+                    match current.purity {
+                        Purity::Pure => self.report(PurityErrorKind::EffectfulConstruct {
+                            construct: "for",
+                            effect: Effect::NonTermination,
+                        }),
+                        Purity::Total => {
+                            if !for_loop_is_bounded(for_stmt) {
+                                self.report(PurityErrorKind::NonTerminatingFor);
+                            }
+                        }
+                        Purity::Impure => {}
+                    }
+                }
+                self.visit_data_expr(&for_stmt.range.start);
+                self.visit_data_expr(&for_stmt.range.end);
+                if let Some(step) = &for_stmt.range.step {
+                    self.visit_data_expr(step);
+                }
+
+                let shadowed = self.loop_vars.insert(for_stmt.variable.clone());
+                self.locals.insert(for_stmt.variable.clone());
+                for stmt in &for_stmt.body {
+                    self.visit_control_stmt(stmt);
+                }
+                if shadowed {
+                    self.loop_vars.remove(&for_stmt.variable);
+                }
+            }
+            ControlStmt::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_data_expr(expr);
+                }
+            }
+            ControlStmt::Print(exprs) => {
+                if let Some(current) = &self.current {
+                    if !allowed_effects(&current.purity).contains(Effect::Io) {
+                        self.report(PurityErrorKind::EffectfulConstruct {
+                            construct: "print",
+                            effect: Effect::Io,
+                        });
+                    }
+                }
+                self.visit_data_expr_list(exprs);
+            }
+            ControlStmt::ReverseBlock(block) => {
+                for stmt in &block.body {
+                    self.visit_reversible_stmt(stmt);
+                }
+            }
+            ControlStmt::Block(stmts) => {
+                for stmt in stmts {
+                    self.visit_control_stmt(stmt);
+                }
+            }
+            ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+        }
+    }
+
+    fn visit_data_expr(&mut self, expr: &DataExpr) {
+        match expr {
+            DataExpr::Number(_) | DataExpr::Identifier(_) => {}
+            DataExpr::Add(left, right) => {
+                self.visit_data_expr(left);
+                self.visit_data_expr(right);
+            }
+            DataExpr::Negate(inner) => self.visit_data_expr(inner),
+            DataExpr::FunctionCall(call) => {
+                self.visit_data_expr_list(&call.args);
+
+                let (purity, callee_declared) = match &self.current {
+                    Some(current) => (
+                        current.purity.clone(),
+                        self.functions.get(&call.name).map(|f| f.purity.clone()),
+                    ),
+                    None => return,
+                };
+
+                match purity {
+                    Purity::Pure => {
+                        if let Some(callee_purity) = callee_declared {
+                            if callee_purity != Purity::Pure {
+                                self.report(PurityErrorKind::ImpureCall {
+                                    callee: call.name.clone(),
+                                });
+                            }
+                        }
+                    }
+                    Purity::Total if self.is_recursive_call(&call.name) => {
+                        let shrinks = call.args.iter().any(|arg| self.arg_is_structurally_smaller(arg));
+                        if !shrinks {
+                            self.report(PurityErrorKind::UnprovenRecursion {
+                                callee: call.name.clone(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            DataExpr::List(elems) | DataExpr::Tuple(elems) => self.visit_data_expr_list(elems),
+            DataExpr::FieldAccess(base, _) => self.visit_data_expr(base),
+            DataExpr::StructLiteral(_, fields) => {
+                for (_, expr) in fields {
+                    self.visit_data_expr(expr);
+                }
+            }
+            DataExpr::ListComprehension(comp) => {
+                self.visit_data_expr(&comp.body);
+                for (_, source) in &comp.generators {
+                    self.visit_data_expr(source);
+                }
+                if let Some(condition) = &comp.condition {
+                    self.visit_control_expr(condition);
+                }
+            }
+            DataExpr::Index(base, index) => {
+                self.visit_data_expr(base);
+                self.visit_data_expr(index);
+            }
+        }
+    }
+}
+
+fn reachable_from(calls: &HashMap<String, HashSet<String>>, start: &str) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![start.to_string()];
+    while let Some(name) = stack.pop() {
+        if let Some(callees) = calls.get(&name) {
+            for callee in callees {
+                if seen.insert(callee.clone()) {
+                    stack.push(callee.clone());
+                }
+            }
+        }
+    }
+    seen
+}
+
+/// Ordering used only by `infer_program`'s fixpoint to decide whether a
+/// freshly computed level is a *weakening* of the one on file: higher rank
+/// is less pure. Not `PartialOrd` on `Purity` itself -- nothing else in
+/// this module compares purity levels against each other, only against a
+/// function's own declaration.
+fn purity_rank(purity: &Purity) -> u8 {
+    match purity {
+        Purity::Pure => 0,
+        Purity::Total => 1,
+        Purity::Impure => 2,
+    }
+}
+
+/// The strongest `Purity` `name` could be declared given the *current*
+/// (possibly still-converging, for a fellow SCC member) `levels` of every
+/// other indexed function, found by probing a scratch `PurityChecker` with
+/// `name` tentatively declared `Pure`, then `Total`, and keeping the first
+/// one that comes back with no diagnostics. Reuses `visit_function_decl`
+/// rather than re-deriving its rules, so this always stays in lockstep with
+/// whatever `check_program` itself actually enforces.
+fn best_achievable_purity(
+    name: &str,
+    functions: &HashMap<String, FunctionDecl>,
+    calls: &HashMap<String, HashSet<String>>,
+    globals: &HashSet<String>,
+    levels: &HashMap<String, Purity>,
+) -> Purity {
+    let mut probe_functions = functions.clone();
+    for (fname, purity) in levels {
+        if let Some(f) = probe_functions.get_mut(fname) {
+            f.purity = purity.clone();
+        }
+    }
+
+    for candidate in [Purity::Pure, Purity::Total] {
+        let mut func = probe_functions[name].clone();
+        func.purity = candidate.clone();
+
+        let mut probe = PurityChecker {
+            functions: probe_functions.clone(),
+            calls: calls.clone(),
+            globals: globals.clone(),
+            loop_vars: HashSet::new(),
+            locals: HashSet::new(),
+            current: None,
+            diagnostics: Vec::new(),
+        };
+        probe.visit_function_decl(&func);
+        if probe.diagnostics.is_empty() {
+            return candidate;
+        }
+    }
+    Purity::Impure
+}
+
+/// Tarjan's strongly-connected-components algorithm over the call graph
+/// `calls` restricts to edges between functions actually present in
+/// `functions` (an edge to an unresolved/external name is simply dropped,
+/// the same way `is_recursive_call` only ever sees calls within `calls`).
+/// Returned in the order Tarjan itself produces components: a component's
+/// outgoing edges only ever reach components earlier in the list, so
+/// folding over the result left to right always has a callee's component
+/// already resolved (or, for an edge within the same component, converging
+/// alongside it).
+fn tarjan_sccs(
+    functions: &HashMap<String, FunctionDecl>,
+    calls: &HashMap<String, HashSet<String>>,
+) -> Vec<Vec<String>> {
+    struct State {
+        index: HashMap<String, usize>,
+        low_link: HashMap<String, usize>,
+        on_stack: HashSet<String>,
+        stack: Vec<String>,
+        next_index: usize,
+        sccs: Vec<Vec<String>>,
+    }
+
+    fn strong_connect(
+        name: &str,
+        functions: &HashMap<String, FunctionDecl>,
+        calls: &HashMap<String, HashSet<String>>,
+        state: &mut State,
+    ) {
+        state.index.insert(name.to_string(), state.next_index);
+        state.low_link.insert(name.to_string(), state.next_index);
+        state.next_index += 1;
+        state.stack.push(name.to_string());
+        state.on_stack.insert(name.to_string());
+
+        if let Some(callees) = calls.get(name) {
+            for callee in callees {
+                if !functions.contains_key(callee) {
+                    continue;
+                }
+                if !state.index.contains_key(callee) {
+                    strong_connect(callee, functions, calls, state);
+                    let low = state.low_link[name].min(state.low_link[callee]);
+                    state.low_link.insert(name.to_string(), low);
+                } else if state.on_stack.contains(callee) {
+                    let low = state.low_link[name].min(state.index[callee]);
+                    state.low_link.insert(name.to_string(), low);
+                }
+            }
+        }
+
+        if state.low_link[name] == state.index[name] {
+            let mut component = Vec::new();
+            loop {
+                let member = state.stack.pop().expect("name's own frame is still on the stack");
+                state.on_stack.remove(&member);
+                let is_root = member == name;
+                component.push(member);
+                if is_root {
+                    break;
+                }
+            }
+            state.sccs.push(component);
+        }
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        next_index: 0,
+        sccs: Vec::new(),
+    };
+
+    let mut names: Vec<&String> = functions.keys().collect();
+    names.sort();
+    for name in names {
+        if !state.index.contains_key(name) {
+            strong_connect(name, functions, calls, &mut state);
+        }
+    }
+    state.sccs
+}
+
+/// Does `while_stmt` have an integer measure the checker can show is
+/// strictly decreased and bounded below on every path through its body?
+/// The only shape recognized: the condition compares some variable `x`
+/// against a constant (`x > 0`, `0 < x`, ...), and every path through the
+/// body reassigns `x` to `x + (-c)` for a literal positive `c` -- the same
+/// shape `arg_is_structurally_smaller` accepts for recursive arguments.
+/// Like that check, this is a conservative syntactic approximation: a loop
+/// written some other provably-terminating way is rejected, not a
+/// counterexample found.
+fn while_has_decreasing_measure(while_stmt: &WhileStmt) -> bool {
+    match measure_candidate(&while_stmt.condition) {
+        Some(var) => stmts_always_decrement(&while_stmt.body, &var),
+        None => false,
+    }
+}
+
+/// The variable a `while` condition bounds against a constant, if any --
+/// only for a comparator shape where decrementing `var` provably approaches
+/// the bound (`var > c` or `c < var`). Any other direction (`Lt`/`Le`/`Ge`
+/// with `var` on the decrementing side, plus `Eq`/`Ne`) would have the loop
+/// keep running -- or stop on the wrong side of the bound -- forever as
+/// `var` decreases, so `stmts_always_decrement` finding a decrement is not
+/// actually evidence of termination for those shapes.
+fn measure_candidate(condition: &ControlExpr) -> Option<String> {
+    match condition {
+        ControlExpr::Comparison(left, Comparator::Gt, right) => match (left.as_ref(), right.as_ref()) {
+            (DataExpr::Identifier(name), DataExpr::Number(_)) => Some(name.clone()),
+            _ => None,
+        },
+        ControlExpr::Comparison(left, Comparator::Lt, right) => match (left.as_ref(), right.as_ref()) {
+            (DataExpr::Number(_), DataExpr::Identifier(name)) => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Is there a statement on every control-flow path through `stmts` that
+/// decrements `var`? A plain sequence counts if any one statement in it
+/// does (it always executes on the way through); an `if` only counts if
+/// both of its branches do.
+fn stmts_always_decrement(stmts: &[ControlStmt], var: &str) -> bool {
+    stmts.iter().any(|stmt| stmt_always_decrements(stmt, var))
+}
+
+fn stmt_always_decrements(stmt: &ControlStmt, var: &str) -> bool {
+    match stmt {
+        ControlStmt::Assignment(assign) => is_decrement_of(assign, var),
+        ControlStmt::If(if_stmt) => {
+            stmts_always_decrement(&if_stmt.then_branch, var)
+                && match &if_stmt.else_branch {
+                    Some(else_branch) => stmts_always_decrement(else_branch, var),
+                    None => false,
+                }
+        }
+        ControlStmt::Block(inner) => stmts_always_decrement(inner, var),
+        _ => false,
+    }
+}
+
+/// Is `assign` of the shape `var = var + (-c)` for a literal positive `c`
+/// -- the `x -= c` form `ReversibleStmt::SubAssign` desugars to, written
+/// out here since a plain (non-reversible) `while` body only ever has
+/// `ControlStmt::Assignment`.
+fn is_decrement_of(assign: &Assignment, var: &str) -> bool {
+    if assign.target != var {
+        return false;
+    }
+    match &assign.value {
+        Expr::Data(DataExpr::Add(left, right)) => {
+            matches!(left.as_ref(), DataExpr::Identifier(name) if name == var)
+                && matches!(
+                    right.as_ref(),
+                    DataExpr::Negate(inner)
+                        if matches!(inner.as_ref(), DataExpr::Number(Number::Int(n)) if *n > 0)
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Is `for_stmt` a loop the checker can treat as iterating a fixed,
+/// already-bounded range -- neither range bound references the loop's own
+/// variable, and the body never reassigns it?
+fn for_loop_is_bounded(for_stmt: &ForStmt) -> bool {
+    !data_expr_references(&for_stmt.range.start, &for_stmt.variable)
+        && !data_expr_references(&for_stmt.range.end, &for_stmt.variable)
+        && match &for_stmt.range.step {
+            Some(step) => !data_expr_references(step, &for_stmt.variable),
+            None => true,
+        }
+        && !body_reassigns(&for_stmt.body, &for_stmt.variable)
+}
+
+fn body_reassigns(stmts: &[ControlStmt], var: &str) -> bool {
+    stmts.iter().any(|stmt| stmt_reassigns(stmt, var))
+}
+
+fn stmt_reassigns(stmt: &ControlStmt, var: &str) -> bool {
+    match stmt {
+        ControlStmt::Assignment(assign) => assign.target == var,
+        ControlStmt::If(if_stmt) => {
+            body_reassigns(&if_stmt.then_branch, var)
+                || match &if_stmt.else_branch {
+                    Some(else_branch) => body_reassigns(else_branch, var),
+                    None => false,
+                }
+        }
+        ControlStmt::While(while_stmt) => body_reassigns(&while_stmt.body, var),
+        // A nested `for` that shadows `var` with its own loop variable of
+        // the same name reassigns a different binding, not this one.
+        ControlStmt::For(for_stmt) => for_stmt.variable != var && body_reassigns(&for_stmt.body, var),
+        ControlStmt::Block(inner) => body_reassigns(inner, var),
+        _ => false,
+    }
+}
+
+/// Does `expr` mention the identifier `var` anywhere in its tree?
+fn data_expr_references(expr: &DataExpr, var: &str) -> bool {
+    match expr {
+        DataExpr::Number(_) => false,
+        DataExpr::Identifier(name) => name == var,
+        DataExpr::Add(left, right) => {
+            data_expr_references(left, var) || data_expr_references(right, var)
+        }
+        DataExpr::Negate(inner) => data_expr_references(inner, var),
+        DataExpr::FunctionCall(call) => call.args.iter().any(|arg| data_expr_references(arg, var)),
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => {
+            elems.iter().any(|elem| data_expr_references(elem, var))
+        }
+        DataExpr::FieldAccess(base, _) => data_expr_references(base, var),
+        DataExpr::StructLiteral(_, fields) => {
+            fields.iter().any(|(_, expr)| data_expr_references(expr, var))
+        }
+        DataExpr::ListComprehension(comp) => {
+            data_expr_references(&comp.body, var)
+                || comp.generators.iter().any(|(_, source)| data_expr_references(source, var))
+        }
+        DataExpr::Index(base, index) => {
+            data_expr_references(base, var) || data_expr_references(index, var)
+        }
+    }
+}
+
+/// Collects every name directly assigned (by a plain `Assignment` or a
+/// reversible `AddAssign`/`SubAssign`) anywhere in `stmts`, recursing
+/// through `If`/`While`/`For`/`Block`/`ReverseBlock` the same way
+/// `collect_calls_stmt` does. Used to find a program's module-level
+/// globals from its top-level `Control` statements.
+fn collect_assigned_names(stmts: &[ControlStmt], out: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            ControlStmt::Assignment(assign) => {
+                out.insert(assign.target.clone());
+            }
+            ControlStmt::If(if_stmt) => {
+                collect_assigned_names(&if_stmt.then_branch, out);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    collect_assigned_names(else_branch, out);
+                }
+            }
+            ControlStmt::While(while_stmt) => collect_assigned_names(&while_stmt.body, out),
+            ControlStmt::For(for_stmt) => collect_assigned_names(&for_stmt.body, out),
+            ControlStmt::ReverseBlock(block) => {
+                for stmt in &block.body {
+                    collect_assigned_names_reversible(stmt, out);
+                }
+            }
+            ControlStmt::Block(inner) => collect_assigned_names(inner, out),
+            ControlStmt::Return(_)
+            | ControlStmt::Print(_)
+            | ControlStmt::Break(_)
+            | ControlStmt::Continue(_) => {}
+        }
+    }
+}
+
+fn collect_assigned_names_reversible(stmt: &ReversibleStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ReversibleStmt::AddAssign(target, _)
+        | ReversibleStmt::SubAssign(target, _)
+        | ReversibleStmt::MulAssign(target, _)
+        | ReversibleStmt::DivAssign(target, _)
+        | ReversibleStmt::Assign(target, _) => {
+            out.insert(target.clone());
+        }
+        ReversibleStmt::If(if_stmt) => {
+            collect_assigned_names(&if_stmt.then_branch, out);
+            if let Some(else_branch) = &if_stmt.else_branch {
+                collect_assigned_names(else_branch, out);
+            }
+        }
+        ReversibleStmt::For { body, .. } => {
+            for stmt in body {
+                collect_assigned_names_reversible(stmt, out);
+            }
+        }
+        ReversibleStmt::Switch { cases, default, .. } => {
+            for (_, body) in cases {
+                for stmt in body {
+                    collect_assigned_names_reversible(stmt, out);
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    collect_assigned_names_reversible(stmt, out);
+                }
+            }
+        }
+    }
+}
+
+fn collect_calls(stmts: &[ControlStmt]) -> HashSet<String> {
+    let mut out = HashSet::new();
+    for stmt in stmts {
+        collect_calls_stmt(stmt, &mut out);
+    }
+    out
+}
+
+fn collect_calls_stmt(stmt: &ControlStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ControlStmt::Assignment(assign) => match &assign.value {
+            Expr::Data(expr) => collect_calls_data_expr(expr, out),
+            Expr::Control(expr) => collect_calls_control_expr(expr, out),
+        },
+        ControlStmt::If(if_stmt) => {
+            collect_calls_control_expr(&if_stmt.condition, out);
+            for stmt in &if_stmt.then_branch {
+                collect_calls_stmt(stmt, out);
+            }
+            if let Some(else_branch) = &if_stmt.else_branch {
+                for stmt in else_branch {
+                    collect_calls_stmt(stmt, out);
+                }
+            }
+        }
+        ControlStmt::While(while_stmt) => {
+            collect_calls_control_expr(&while_stmt.condition, out);
+            for stmt in &while_stmt.body {
+                collect_calls_stmt(stmt, out);
+            }
+        }
+        ControlStmt::For(for_stmt) => {
+            collect_calls_data_expr(&for_stmt.range.start, out);
+            collect_calls_data_expr(&for_stmt.range.end, out);
+            if let Some(step) = &for_stmt.range.step {
+                collect_calls_data_expr(step, out);
+            }
+            for stmt in &for_stmt.body {
+                collect_calls_stmt(stmt, out);
+            }
+        }
+        ControlStmt::Return(Some(expr)) => collect_calls_data_expr(expr, out),
+        ControlStmt::Return(None) => {}
+        ControlStmt::Print(exprs) => {
+            for expr in exprs {
+                collect_calls_data_expr(expr, out);
+            }
+        }
+        ControlStmt::ReverseBlock(block) => {
+            for stmt in &block.body {
+                collect_calls_reversible(stmt, out);
+            }
+        }
+        ControlStmt::Block(stmts) => {
+            for stmt in stmts {
+                collect_calls_stmt(stmt, out);
+            }
+        }
+        ControlStmt::Break(_) | ControlStmt::Continue(_) => {}
+    }
+}
+
+fn collect_calls_reversible(stmt: &ReversibleStmt, out: &mut HashSet<String>) {
+    match stmt {
+        ReversibleStmt::AddAssign(_, expr)
+        | ReversibleStmt::SubAssign(_, expr)
+        | ReversibleStmt::MulAssign(_, expr)
+        | ReversibleStmt::DivAssign(_, expr)
+        | ReversibleStmt::Assign(_, expr) => {
+            collect_calls_data_expr(expr, out)
+        }
+        ReversibleStmt::If(if_stmt) => {
+            collect_calls_control_expr(&if_stmt.condition, out);
+            for stmt in &if_stmt.then_branch {
+                collect_calls_stmt(stmt, out);
+            }
+            if let Some(else_branch) = &if_stmt.else_branch {
+                for stmt in else_branch {
+                    collect_calls_stmt(stmt, out);
+                }
+            }
+        }
+        ReversibleStmt::For { from, to, step, body, .. } => {
+            collect_calls_data_expr(from, out);
+            collect_calls_data_expr(to, out);
+            if let Some(step) = step {
+                collect_calls_data_expr(step, out);
+            }
+            for stmt in body {
+                collect_calls_reversible(stmt, out);
+            }
+        }
+        ReversibleStmt::Switch { scrutinee, cases, default } => {
+            collect_calls_data_expr(scrutinee, out);
+            for (value, body) in cases {
+                collect_calls_data_expr(value, out);
+                for stmt in body {
+                    collect_calls_reversible(stmt, out);
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    collect_calls_reversible(stmt, out);
+                }
+            }
+        }
+    }
+}
+
+fn collect_calls_control_expr(expr: &ControlExpr, out: &mut HashSet<String>) {
+    match expr {
+        ControlExpr::Data(data) => collect_calls_data_expr(data, out),
+        ControlExpr::Comparison(left, _, right) => {
+            collect_calls_data_expr(left, out);
+            collect_calls_data_expr(right, out);
+        }
+        ControlExpr::Logical(left, _, right) => {
+            collect_calls_control_expr(left, out);
+            collect_calls_control_expr(right, out);
+        }
+        ControlExpr::Not(inner) => collect_calls_control_expr(inner, out),
+        ControlExpr::Contains(left, right) => {
+            collect_calls_data_expr(left, out);
+            collect_calls_data_expr(right, out);
+        }
+    }
+}
+
+fn collect_calls_data_expr(expr: &DataExpr, out: &mut HashSet<String>) {
+    match expr {
+        DataExpr::Number(_) | DataExpr::Identifier(_) => {}
+        DataExpr::Add(left, right) => {
+            collect_calls_data_expr(left, out);
+            collect_calls_data_expr(right, out);
+        }
+        DataExpr::Negate(inner) => collect_calls_data_expr(inner, out),
+        DataExpr::FunctionCall(call) => {
+            out.insert(call.name.clone());
+            for arg in &call.args {
+                collect_calls_data_expr(arg, out);
+            }
+        }
+        DataExpr::List(elems) | DataExpr::Tuple(elems) => {
+            for elem in elems {
+                collect_calls_data_expr(elem, out);
+            }
+        }
+        DataExpr::FieldAccess(base, _) => collect_calls_data_expr(base, out),
+        DataExpr::StructLiteral(_, fields) => {
+            for (_, expr) in fields {
+                collect_calls_data_expr(expr, out);
+            }
+        }
+        DataExpr::ListComprehension(comp) => {
+            collect_calls_data_expr(&comp.body, out);
+            for (_, source) in &comp.generators {
+                collect_calls_data_expr(source, out);
+            }
+            if let Some(condition) = &comp.condition {
+                collect_calls_control_expr(condition, out);
+            }
+        }
+        DataExpr::Index(base, index) => {
+            collect_calls_data_expr(base, out);
+            collect_calls_data_expr(index, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(name: &str, purity: Purity, body: Vec<ControlStmt>) -> FunctionDecl {
+        FunctionDecl {
+            name: name.to_string(),
+            type_params: vec![],
+            params: vec![],
+            return_type: None,
+            purity,
+            body,
+            span: Span { start: 0, end: 0, line: 1, col: 1 },
+            trivia: Trivia::default(),
+        }
+    }
+
+    fn func_with_params(name: &str, purity: Purity, params: Vec<&str>, body: Vec<ControlStmt>) -> FunctionDecl {
+        FunctionDecl {
+            params: params
+                .into_iter()
+                .map(|p| Param { name: p.to_string(), type_annotation: None })
+                .collect(),
+            ..func(name, purity, body)
+        }
+    }
+
+    fn program(functions: Vec<FunctionDecl>) -> Program {
+        Program {
+            statements: functions.into_iter().map(TopLevel::Function).collect(),
+            span: Span::unknown(),
+        }
+    }
+
+    /// A program whose top level assigns `global_name` before declaring
+    /// `functions` -- for testing `NonLocalWrite` detection.
+    fn program_with_global(global_name: &str, functions: Vec<FunctionDecl>) -> Program {
+        let mut statements = vec![TopLevel::Control(ControlStmt::Assignment(Assignment {
+            target: global_name.to_string(),
+            value: Expr::Data(DataExpr::Number(Number::Int(0))),
+        }))];
+        statements.extend(functions.into_iter().map(TopLevel::Function));
+        Program { statements, span: Span::unknown() }
+    }
+
+    fn call(name: &str, args: Vec<DataExpr>) -> DataExpr {
+        DataExpr::FunctionCall(FunctionCall { name: name.to_string(), args })
+    }
+
+    #[test]
+    fn test_pure_function_rejects_while() {
+        let prog = program(vec![func(
+            "f",
+            Purity::Pure,
+            vec![ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Data(DataExpr::Number(Number::Int(1))),
+                body: vec![],
+            })],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].kind,
+            PurityErrorKind::EffectfulConstruct { construct: "while", effect: Effect::NonTermination }
+        );
+    }
+
+    #[test]
+    fn test_pure_function_rejects_print() {
+        let prog = program(vec![func(
+            "f",
+            Purity::Pure,
+            vec![ControlStmt::Print(vec![DataExpr::Number(Number::Int(1))])],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(
+            diags[0].kind,
+            PurityErrorKind::EffectfulConstruct { construct: "print", effect: Effect::Io }
+        );
+    }
+
+    #[test]
+    fn test_pure_function_rejects_call_to_impure_function() {
+        let prog = program(vec![
+            func(
+                "pure_caller",
+                Purity::Pure,
+                vec![ControlStmt::Return(Some(call("impure_callee", vec![])))],
+            ),
+            func("impure_callee", Purity::Impure, vec![]),
+        ]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].kind,
+            PurityErrorKind::ImpureCall { callee: "impure_callee".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_pure_function_with_clean_body_has_no_diagnostics() {
+        let prog = program(vec![
+            func(
+                "pure_caller",
+                Purity::Pure,
+                vec![ControlStmt::Return(Some(call("pure_callee", vec![])))],
+            ),
+            func("pure_callee", Purity::Pure, vec![]),
+        ]);
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_total_function_rejects_while() {
+        let prog = program(vec![func(
+            "f",
+            Purity::Total,
+            vec![ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Data(DataExpr::Number(Number::Int(1))),
+                body: vec![],
+            })],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags[0].kind, PurityErrorKind::UnboundedLoop);
+    }
+
+    #[test]
+    fn test_total_function_accepts_while_with_decreasing_measure() {
+        let prog = program(vec![func(
+            "countdown",
+            Purity::Total,
+            vec![ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Comparator::Gt,
+                    Box::new(DataExpr::Number(Number::Int(0))),
+                ),
+                body: vec![ControlStmt::Assignment(Assignment {
+                    target: "n".to_string(),
+                    value: Expr::Data(DataExpr::Add(
+                        Box::new(DataExpr::Identifier("n".to_string())),
+                        Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+                    )),
+                })],
+            })],
+        )]);
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_total_function_rejects_decrement_toward_an_upper_bound() {
+        // `while n < 10 { n = n + (-1) }` decrements `n` on every iteration,
+        // but the loop keeps running for as long as `n < 10` holds -- which
+        // a decreasing `n` only ever gets closer to satisfying, not further
+        // from -- so this is an infinite loop, not a terminating one, and
+        // must not be accepted as a valid decreasing measure.
+        let prog = program(vec![func(
+            "f",
+            Purity::Total,
+            vec![ControlStmt::While(WhileStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Comparator::Lt,
+                    Box::new(DataExpr::Number(Number::Int(10))),
+                ),
+                body: vec![ControlStmt::Assignment(Assignment {
+                    target: "n".to_string(),
+                    value: Expr::Data(DataExpr::Add(
+                        Box::new(DataExpr::Identifier("n".to_string())),
+                        Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+                    )),
+                })],
+            })],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags[0].kind, PurityErrorKind::UnboundedLoop);
+    }
+
+    #[test]
+    fn test_total_function_rejects_unproven_recursion() {
+        let prog = program(vec![func(
+            "countdown",
+            Purity::Total,
+            vec![ControlStmt::Return(Some(call(
+                "countdown",
+                vec![DataExpr::Identifier("n".to_string())],
+            )))],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].kind,
+            PurityErrorKind::UnprovenRecursion { callee: "countdown".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_total_function_accepts_recursion_on_shrinking_argument() {
+        let prog = program(vec![func(
+            "countdown",
+            Purity::Total,
+            vec![ControlStmt::Return(Some(call(
+                "countdown",
+                vec![DataExpr::Add(
+                    Box::new(DataExpr::Identifier("n".to_string())),
+                    Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+                )],
+            )))],
+        )]);
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_total_function_accepts_recursion_on_for_loop_variable() {
+        let prog = program(vec![func(
+            "walk",
+            Purity::Total,
+            vec![ControlStmt::For(ForStmt {
+                variable: "i".to_string(),
+                range: RangeExpr {
+                    start: Box::new(DataExpr::Number(Number::Int(0))),
+                    end: Box::new(DataExpr::Number(Number::Int(10))),
+                    step: None,
+                },
+                body: vec![ControlStmt::Assignment(Assignment {
+                    target: "_".to_string(),
+                    value: Expr::Data(call("walk", vec![DataExpr::Identifier("i".to_string())])),
+                })],
+            })],
+        )]);
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_mutual_recursion_detected_across_functions() {
+        let prog = program(vec![
+            func(
+                "is_even",
+                Purity::Total,
+                vec![ControlStmt::Return(Some(call(
+                    "is_odd",
+                    vec![DataExpr::Identifier("n".to_string())],
+                )))],
+            ),
+            func(
+                "is_odd",
+                Purity::Total,
+                vec![ControlStmt::Return(Some(call(
+                    "is_even",
+                    vec![DataExpr::Identifier("n".to_string())],
+                )))],
+            ),
+        ]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 2);
+        assert!(diags
+            .iter()
+            .all(|d| matches!(d.kind, PurityErrorKind::UnprovenRecursion { .. })));
+    }
+
+    #[test]
+    fn test_total_function_rejects_for_loop_reassigning_its_variable() {
+        let prog = program(vec![func(
+            "f",
+            Purity::Total,
+            vec![ControlStmt::For(ForStmt {
+                variable: "i".to_string(),
+                range: RangeExpr {
+                    start: Box::new(DataExpr::Number(Number::Int(0))),
+                    end: Box::new(DataExpr::Number(Number::Int(10))),
+                    step: None,
+                },
+                body: vec![ControlStmt::Assignment(Assignment {
+                    target: "i".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(0))),
+                })],
+            })],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].kind, PurityErrorKind::NonTerminatingFor);
+    }
+
+    #[test]
+    fn test_pure_function_rejects_write_to_module_level_global() {
+        let prog = program_with_global(
+            "counter",
+            vec![func(
+                "bump",
+                Purity::Pure,
+                vec![ControlStmt::Assignment(Assignment {
+                    target: "counter".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                })],
+            )],
+        );
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].kind,
+            PurityErrorKind::NonLocalWrite { target: "counter".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_pure_function_accepts_write_to_own_parameter_of_same_name_as_global() {
+        let prog = program_with_global(
+            "counter",
+            vec![func_with_params(
+                "bump",
+                Purity::Pure,
+                vec!["counter"],
+                vec![ControlStmt::Assignment(Assignment {
+                    target: "counter".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                })],
+            )],
+        );
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_pure_function_accepts_write_to_its_own_fresh_local() {
+        let prog = program_with_global(
+            "counter",
+            vec![func(
+                "compute",
+                Purity::Pure,
+                vec![ControlStmt::Assignment(Assignment {
+                    target: "total".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                })],
+            )],
+        );
+
+        assert!(PurityChecker::check_program(&prog).is_empty());
+    }
+
+    #[test]
+    fn test_pure_function_reports_non_local_write_only_once() {
+        let prog = program_with_global(
+            "counter",
+            vec![func(
+                "bump_twice",
+                Purity::Pure,
+                vec![
+                    ControlStmt::Assignment(Assignment {
+                        target: "counter".to_string(),
+                        value: Expr::Data(DataExpr::Number(Number::Int(1))),
+                    }),
+                    ControlStmt::Assignment(Assignment {
+                        target: "counter".to_string(),
+                        value: Expr::Data(DataExpr::Number(Number::Int(2))),
+                    }),
+                ],
+            )],
+        );
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags.len(), 1);
+    }
+
+    #[test]
+    fn test_effectful_construct_diagnostic_names_its_effect() {
+        let prog = program(vec![func(
+            "f",
+            Purity::Pure,
+            vec![ControlStmt::Print(vec![DataExpr::Number(Number::Int(1))])],
+        )]);
+
+        let diags = PurityChecker::check_program(&prog);
+        assert_eq!(diags[0].kind, PurityErrorKind::EffectfulConstruct {
+            construct: "print",
+            effect: Effect::Io,
+        });
+        assert!(diags[0].to_string().contains("`io` effect"));
+    }
+
+    #[test]
+    fn test_infer_program_promotes_misannotated_pure_helper() {
+        // Declared `@total` (an unnecessarily weak annotation), but its
+        // body is in fact clean.
+        let prog = program(vec![func(
+            "add_one",
+            Purity::Total,
+            vec![ControlStmt::Return(Some(DataExpr::Add(
+                Box::new(DataExpr::Identifier("n".to_string())),
+                Box::new(DataExpr::Number(Number::Int(1))),
+            )))],
+        )]);
+
+        let levels = PurityChecker::infer_program(&prog);
+        assert_eq!(levels.get("add_one"), Some(&Purity::Pure));
+    }
+
+    #[test]
+    fn test_infer_program_accepts_self_recursion_with_print_as_total() {
+        // Prints, so it can never be `Pure`; but its recursive argument
+        // strictly shrinks, so it is `Total`.
+        let prog = program(vec![func(
+            "countdown",
+            Purity::Impure,
+            vec![
+                ControlStmt::Print(vec![DataExpr::Identifier("n".to_string())]),
+                ControlStmt::Return(Some(call(
+                    "countdown",
+                    vec![DataExpr::Add(
+                        Box::new(DataExpr::Identifier("n".to_string())),
+                        Box::new(DataExpr::Negate(Box::new(DataExpr::Number(Number::Int(1))))),
+                    )],
+                ))),
+            ],
+        )]);
+
+        let levels = PurityChecker::infer_program(&prog);
+        assert_eq!(levels.get("countdown"), Some(&Purity::Total));
+    }
+
+    #[test]
+    fn test_infer_program_resolves_mutually_recursive_pair_as_pure() {
+        // Neither calls itself directly, and neither loops, prints, or
+        // writes anywhere non-local -- the fixpoint should settle with
+        // both still at the optimistic `Pure` it starts them at.
+        let prog = program(vec![
+            func(
+                "is_even",
+                Purity::Impure,
+                vec![ControlStmt::Return(Some(call(
+                    "is_odd",
+                    vec![DataExpr::Identifier("n".to_string())],
+                )))],
+            ),
+            func(
+                "is_odd",
+                Purity::Impure,
+                vec![ControlStmt::Return(Some(call(
+                    "is_even",
+                    vec![DataExpr::Identifier("n".to_string())],
+                )))],
+            ),
+        ]);
+
+        let levels = PurityChecker::infer_program(&prog);
+        assert_eq!(levels.get("is_even"), Some(&Purity::Pure));
+        assert_eq!(levels.get("is_odd"), Some(&Purity::Pure));
+    }
+
+    #[test]
+    fn test_infer_program_converges_mutually_recursive_pair_to_impure() {
+        // `f` calls `g` and `g` calls `f` back on the *same* argument (no
+        // shrink), and `g` also prints -- so neither can be `Pure` (once
+        // the other is known impure) nor `Total` (the recursion doesn't
+        // shrink). Both start optimistically `Pure`, so `f` (which itself
+        // does nothing but call `g`) only fails once `g`'s own level has
+        // dropped.
+        let prog = program(vec![
+            func(
+                "f",
+                Purity::Impure,
+                vec![ControlStmt::Return(Some(call(
+                    "g",
+                    vec![DataExpr::Identifier("n".to_string())],
+                )))],
+            ),
+            func(
+                "g",
+                Purity::Impure,
+                vec![
+                    ControlStmt::Print(vec![DataExpr::Identifier("n".to_string())]),
+                    ControlStmt::Return(Some(call(
+                        "f",
+                        vec![DataExpr::Identifier("n".to_string())],
+                    ))),
+                ],
+            ),
+        ]);
+
+        let levels = PurityChecker::infer_program(&prog);
+        assert_eq!(levels.get("f"), Some(&Purity::Impure));
+        assert_eq!(levels.get("g"), Some(&Purity::Impure));
+    }
+}
@@ -0,0 +1,427 @@
+// Persistent vector backing `Value::List`.
+//
+// Every `Vec<Value>` clone in the collections builtins (`tail`, `init`,
+// `take`, `drop`, `concat`, ...) used to copy the whole list, so a chain of
+// list transforms was quadratic in time and memory for large lists. `PVector`
+// replaces that flat `Vec` with a sequence of fixed-capacity, `Rc`-shared
+// chunks: untouched chunks are reused by pointer on every split or
+// concatenation, and only the (at most one) chunk actually straddling a cut
+// point is ever copied -- and only its own elements, never the rest of the
+// vector.
+//
+// This is a simplified stand-in for a full RRB-tree (a balanced radix tree
+// with relaxed internal node sizes, as used by Clojure's/Scala's persistent
+// vectors): a real RRB gives `O(log32 n)` `split_at`/`concat` by rebalancing
+// only the nodes along the seam, at the cost of a much more involved
+// concat algorithm. This chunk-list gives `O(chunks)` = `O(n / 32)` for the
+// same operations with no per-element copying outside the boundary chunk --
+// asymptotically looser than a true RRB tree, but still eliminates the
+// `O(n)` full clone every one of these operations used to pay, and is a lot
+// easier to keep correct. Revisit if profiling ever shows chunk-count
+// overhead actually matters.
+
+use std::rc::Rc;
+
+/// Maximum elements per chunk -- matches the branching factor a real
+/// RRB-tree node would use.
+const CHUNK_CAPACITY: usize = 32;
+
+/// A structurally-shared, immutable vector. Cloning a `PVector` is an `Rc`
+/// clone of its chunk list (`O(chunks)`, not `O(n)`); every operation below
+/// returns a new `PVector` rather than mutating `self`, in keeping with
+/// JtV's value semantics.
+#[derive(Debug, Clone)]
+pub struct PVector<T> {
+    chunks: Rc<Vec<Rc<Vec<T>>>>,
+    len: usize,
+}
+
+impl<T> PVector<T> {
+    pub fn new() -> Self {
+        PVector { chunks: Rc::new(Vec::new()), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        let mut remaining = index;
+        for chunk in self.chunks.iter() {
+            if remaining < chunk.len() {
+                return chunk.get(remaining);
+            }
+            remaining -= chunk.len();
+        }
+        None
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.get(self.len - 1)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.chunks.as_slice())
+    }
+}
+
+impl<T: Clone> PVector<T> {
+    pub fn from_vec(items: Vec<T>) -> Self {
+        if items.is_empty() {
+            return Self::new();
+        }
+        let len = items.len();
+        let chunks: Vec<Rc<Vec<T>>> = items
+            .chunks(CHUNK_CAPACITY)
+            .map(|slice| Rc::new(slice.to_vec()))
+            .collect();
+        PVector { chunks: Rc::new(chunks), len }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut out = Vec::with_capacity(self.len);
+        for chunk in self.chunks.iter() {
+            out.extend_from_slice(chunk);
+        }
+        out
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|item| item == value)
+    }
+
+    /// Appends one element. Shares every existing chunk; only copies the
+    /// last chunk (and only when it's already full does it allocate a new,
+    /// single-element one instead).
+    pub fn push_back(&self, value: T) -> Self {
+        let mut chunks = (*self.chunks).clone();
+        let needs_new_chunk = match chunks.last() {
+            Some(last) => last.len() >= CHUNK_CAPACITY,
+            None => true,
+        };
+        if needs_new_chunk {
+            chunks.push(Rc::new(vec![value]));
+        } else {
+            let last_index = chunks.len() - 1;
+            let mut new_last = (*chunks[last_index]).clone();
+            new_last.push(value);
+            chunks[last_index] = Rc::new(new_last);
+        }
+        PVector { chunks: Rc::new(chunks), len: self.len + 1 }
+    }
+
+    /// Splits into `(left, right)` at `index` (`left` holds `index`
+    /// elements). Every chunk entirely on one side of `index` is shared by
+    /// `Rc` clone; at most one chunk straddles the cut and is copied, split,
+    /// and rewrapped.
+    pub fn split_at(&self, index: usize) -> (Self, Self) {
+        assert!(index <= self.len, "split_at: index out of bounds");
+        let mut left_chunks: Vec<Rc<Vec<T>>> = Vec::new();
+        let mut right_chunks: Vec<Rc<Vec<T>>> = Vec::new();
+        let mut consumed = 0;
+        for chunk in self.chunks.iter() {
+            if consumed + chunk.len() <= index {
+                left_chunks.push(chunk.clone());
+            } else if consumed >= index {
+                right_chunks.push(chunk.clone());
+            } else {
+                let split_point = index - consumed;
+                let (l, r) = chunk.split_at(split_point);
+                if !l.is_empty() {
+                    left_chunks.push(Rc::new(l.to_vec()));
+                }
+                if !r.is_empty() {
+                    right_chunks.push(Rc::new(r.to_vec()));
+                }
+            }
+            consumed += chunk.len();
+        }
+        (
+            PVector { chunks: Rc::new(left_chunks), len: index },
+            PVector { chunks: Rc::new(right_chunks), len: self.len - index },
+        )
+    }
+
+    /// Concatenates `self` and `other`. Every chunk from both sides is
+    /// reused by `Rc` clone; the only copy is merging `self`'s last chunk
+    /// with `other`'s first when both fit in one `CHUNK_CAPACITY`, which
+    /// keeps repeated small concatenations from leaving a trail of
+    /// near-empty chunks behind.
+    pub fn concat(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return other.clone();
+        }
+        if other.is_empty() {
+            return self.clone();
+        }
+        let mut chunks: Vec<Rc<Vec<T>>> = Vec::with_capacity(self.chunks.len() + other.chunks.len());
+        chunks.extend(self.chunks.iter().cloned());
+        let merge = matches!(
+            (chunks.last(), other.chunks.first()),
+            (Some(last), Some(first)) if last.len() + first.len() <= CHUNK_CAPACITY
+        );
+        if merge {
+            let last_index = chunks.len() - 1;
+            let mut merged = (*chunks[last_index]).clone();
+            merged.extend_from_slice(&other.chunks[0]);
+            chunks[last_index] = Rc::new(merged);
+            chunks.extend(other.chunks.iter().skip(1).cloned());
+        } else {
+            chunks.extend(other.chunks.iter().cloned());
+        }
+        PVector { chunks: Rc::new(chunks), len: self.len + other.len }
+    }
+
+    /// A new, reversed `PVector`. The chunk-list design has no cheap lazily
+    /// reversed view (every other operation would need to know which side
+    /// it's reading from), so this stays an `O(n)` rebuild -- the same cost
+    /// `Vec::reverse` paid, just without mutating a shared chunk in place.
+    pub fn reverse(&self) -> Self {
+        let mut items = self.to_vec();
+        items.reverse();
+        Self::from_vec(items)
+    }
+}
+
+impl<T> Default for PVector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PVector<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> std::ops::Index<usize> for PVector<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("PVector index out of bounds")
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PVector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T: Clone> IntoIterator for PVector<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.to_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PVector<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Serializes as a plain sequence of elements -- the chunked `Rc` layout is
+/// an internal sharing optimization, not part of the value this vector
+/// represents, so the wire format matches a plain `Vec<T>` instead of
+/// leaking chunk boundaries.
+impl<T: serde::Serialize> serde::Serialize for PVector<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Clone + serde::Deserialize<'de>> serde::Deserialize<'de> for PVector<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(PVector::from_vec)
+    }
+}
+
+/// A double-ended iterator over a `PVector`'s elements, walking its chunks
+/// front-to-back (or back-to-front via `next_back`/`.rev()`) without
+/// flattening them into a new allocation.
+pub struct Iter<'a, T> {
+    chunks: &'a [Rc<Vec<T>>],
+    front_chunk: usize,
+    front_item: usize,
+    back_chunk: usize,
+    back_item: usize,
+    done: bool,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(chunks: &'a [Rc<Vec<T>>]) -> Self {
+        let done = chunks.is_empty();
+        let (back_chunk, back_item) = if done {
+            (0, 0)
+        } else {
+            (chunks.len() - 1, chunks[chunks.len() - 1].len())
+        };
+        Iter { chunks, front_chunk: 0, front_item: 0, back_chunk, back_item, done }
+    }
+
+    fn crossed(&self) -> bool {
+        self.front_chunk > self.back_chunk
+            || (self.front_chunk == self.back_chunk && self.front_item >= self.back_item)
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.crossed() {
+                self.done = true;
+                return None;
+            }
+            let chunk = &self.chunks[self.front_chunk];
+            if self.front_item < chunk.len() {
+                let item = &chunk[self.front_item];
+                self.front_item += 1;
+                return Some(item);
+            }
+            self.front_chunk += 1;
+            self.front_item = 0;
+        }
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.crossed() {
+                self.done = true;
+                return None;
+            }
+            if self.back_item > 0 {
+                self.back_item -= 1;
+                return Some(&self.chunks[self.back_chunk][self.back_item]);
+            }
+            if self.back_chunk == 0 {
+                self.done = true;
+                return None;
+            }
+            self.back_chunk -= 1;
+            self.back_item = self.chunks[self.back_chunk].len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pv(items: &[i32]) -> PVector<i32> {
+        PVector::from_vec(items.to_vec())
+    }
+
+    #[test]
+    fn test_from_vec_round_trips() {
+        let v = pv(&[1, 2, 3, 4, 5]);
+        assert_eq!(v.to_vec(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(v.len(), 5);
+    }
+
+    #[test]
+    fn test_get_and_index() {
+        let v = pv(&[10, 20, 30]);
+        assert_eq!(v.get(1), Some(&20));
+        assert_eq!(v.get(3), None);
+        assert_eq!(v[0], 10);
+    }
+
+    #[test]
+    fn test_split_at_spanning_multiple_chunks() {
+        let items: Vec<i32> = (0..100).collect();
+        let v = PVector::from_vec(items.clone());
+        let (left, right) = v.split_at(40);
+        assert_eq!(left.to_vec(), items[..40]);
+        assert_eq!(right.to_vec(), items[40..]);
+    }
+
+    #[test]
+    fn test_concat_round_trips() {
+        let a = pv(&[1, 2, 3]);
+        let b = pv(&[4, 5, 6]);
+        assert_eq!(a.concat(&b).to_vec(), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_concat_with_empty() {
+        let a = pv(&[1, 2]);
+        let empty: PVector<i32> = PVector::new();
+        assert_eq!(a.concat(&empty), a);
+        assert_eq!(empty.concat(&a), a);
+    }
+
+    #[test]
+    fn test_push_back() {
+        let mut v = PVector::new();
+        for i in 0..40 {
+            v = v.push_back(i);
+        }
+        assert_eq!(v.to_vec(), (0..40).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let v = pv(&[1, 2, 3]);
+        assert_eq!(v.reverse().to_vec(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_iter_is_double_ended() {
+        let v = pv(&[1, 2, 3, 4]);
+        let mut it = v.iter();
+        assert_eq!(it.next(), Some(&1));
+        assert_eq!(it.next_back(), Some(&4));
+        assert_eq!(it.next(), Some(&2));
+        assert_eq!(it.next_back(), Some(&3));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        assert_eq!(pv(&[1, 2, 3]), pv(&[1, 2, 3]));
+        assert_ne!(pv(&[1, 2, 3]), pv(&[1, 2]));
+    }
+
+    #[test]
+    fn test_split_then_concat_reconstructs() {
+        let items: Vec<i32> = (0..77).collect();
+        let v = PVector::from_vec(items.clone());
+        let (left, right) = v.split_at(33);
+        assert_eq!(left.concat(&right).to_vec(), items);
+    }
+}
@@ -4,10 +4,33 @@
 use crate::ast::*;
 use crate::number::Value;
 use crate::error::{JtvError, Result};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Whether `value` is numerically zero, promoting it to `Int(0)`'s type
+/// first (see `number::promote`) so a bare `Value::eq` mismatch -- e.g.
+/// `Float(0.0)` never equalling `Int(0)` -- doesn't let a zero multiplier
+/// or divisor slip past the guards in `MulAssign`/`DivAssign` below.
+fn is_zero(value: &Value) -> Result<bool> {
+    let (a, b) = crate::number::promote(value.clone(), Value::Int(0));
+    a.eq(&b)
+}
+
+/// Extract an `Int` out of a reversible for loop's `from`/`to`/`step`, the
+/// same "Range must be integers" restriction `Interpreter::eval_control_stmt`
+/// enforces for an ordinary `ControlStmt::For`.
+fn value_as_int(value: &Value) -> Result<i64> {
+    match value {
+        Value::Int(n) => Ok(*n),
+        other => Err(JtvError::TypeError(format!(
+            "reversible for loop bounds must be Int, got {}",
+            other
+        ))),
+    }
+}
 
 /// A recorded operation that can be reversed
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RecordedOp {
     /// x += value (reverse: x -= value)
     AddAssign {
@@ -19,12 +42,54 @@ pub enum RecordedOp {
         target: String,
         value: Value,
     },
+    /// x *= value (reverse: x /= value). `value` is captured at record
+    /// time, not re-evaluated during reversal -- same invariant
+    /// `AddAssign`/`SubAssign` rely on.
+    MulAssign {
+        target: String,
+        value: Value,
+    },
+    /// x /= value (reverse: x *= value)
+    DivAssign {
+        target: String,
+        value: Value,
+    },
+    /// A general, non-algebraic overwrite (`x = expr`) reversed
+    /// Bennett-style: `old_value` is whatever `target` held right before
+    /// the overwrite (`None` if it was unset), snapshotted at record time.
+    /// Unlike every other `RecordedOp`, applying a `Store` always means
+    /// "restore `old_value`" -- there's no separate forward/reverse pair,
+    /// so `.inverse()` just returns the same `Store` unchanged.
+    Store {
+        target: String,
+        old_value: Option<Value>,
+    },
     /// Conditional branch (reverse requires same condition)
     If {
         condition_was_true: bool,
         then_ops: Vec<RecordedOp>,
         else_ops: Vec<RecordedOp>,
     },
+    /// A bounded `for` loop. `iterations` is the exact number of times the
+    /// loop body ran, recorded once up front so the reverse pass replays
+    /// `body_ops` that many times regardless of what the loop variable (or
+    /// anything `from`/`to`/`step` read) looks like afterwards -- it never
+    /// re-derives the count by re-evaluating the loop header.
+    Loop {
+        iterations: usize,
+        body_ops: Vec<Vec<RecordedOp>>,
+    },
+    /// A multi-way branch. `arm_taken` is the index into the original
+    /// `cases` list of the arm that ran, or `None` if `default` ran (or no
+    /// arm matched and there was no `default`). Reversal never re-evaluates
+    /// the scrutinee -- it just replays `arm_ops`'s inverse, so a `body`
+    /// that mutates something the scrutinee or a case value reads can't
+    /// steer the reverse pass down a different arm than the forward pass
+    /// took.
+    Switch {
+        arm_taken: Option<usize>,
+        arm_ops: Vec<RecordedOp>,
+    },
 }
 
 impl RecordedOp {
@@ -39,6 +104,18 @@ impl RecordedOp {
                 target: target.clone(),
                 value: value.clone(),
             },
+            RecordedOp::MulAssign { target, value } => RecordedOp::DivAssign {
+                target: target.clone(),
+                value: value.clone(),
+            },
+            RecordedOp::DivAssign { target, value } => RecordedOp::MulAssign {
+                target: target.clone(),
+                value: value.clone(),
+            },
+            RecordedOp::Store { target, old_value } => RecordedOp::Store {
+                target: target.clone(),
+                old_value: old_value.clone(),
+            },
             RecordedOp::If {
                 condition_was_true,
                 then_ops,
@@ -48,12 +125,26 @@ impl RecordedOp {
                 then_ops: then_ops.iter().rev().map(|op| op.inverse()).collect(),
                 else_ops: else_ops.iter().rev().map(|op| op.inverse()).collect(),
             },
+            RecordedOp::Loop { iterations, body_ops } => RecordedOp::Loop {
+                iterations: *iterations,
+                // Undo the most recent iteration first, and within each
+                // iteration undo its own operations in reverse order too.
+                body_ops: body_ops
+                    .iter()
+                    .rev()
+                    .map(|ops| ops.iter().rev().map(|op| op.inverse()).collect())
+                    .collect(),
+            },
+            RecordedOp::Switch { arm_taken, arm_ops } => RecordedOp::Switch {
+                arm_taken: *arm_taken,
+                arm_ops: arm_ops.iter().rev().map(|op| op.inverse()).collect(),
+            },
         }
     }
 }
 
 /// Execution trace for a reverse block
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReverseTrace {
     /// Operations recorded during forward execution
     operations: Vec<RecordedOp>,
@@ -79,6 +170,96 @@ impl ReverseTrace {
             .map(|op| op.inverse())
             .collect()
     }
+
+    /// Depth-first traversal of the recorded operation tree, without
+    /// exposing `operations` itself -- `f` is called with each op and its
+    /// nesting depth (0 at the top level), and the walk stops everywhere,
+    /// not just the current subtree, the moment `f` returns `false`.
+    pub fn walk<F: FnMut(&RecordedOp, usize) -> bool>(&self, f: &mut F) {
+        Self::walk_ops(&self.operations, 0, f);
+    }
+
+    fn walk_ops<F: FnMut(&RecordedOp, usize) -> bool>(ops: &[RecordedOp], depth: usize, f: &mut F) -> bool {
+        for op in ops {
+            if !f(op, depth) {
+                return false;
+            }
+            let keep_going = match op {
+                RecordedOp::If { then_ops, else_ops, .. } => {
+                    Self::walk_ops(then_ops, depth + 1, f) && Self::walk_ops(else_ops, depth + 1, f)
+                }
+                RecordedOp::Loop { body_ops, .. } => {
+                    body_ops.iter().all(|ops| Self::walk_ops(ops, depth + 1, f))
+                }
+                RecordedOp::Switch { arm_ops, .. } => Self::walk_ops(arm_ops, depth + 1, f),
+                RecordedOp::AddAssign { .. }
+                | RecordedOp::SubAssign { .. }
+                | RecordedOp::MulAssign { .. }
+                | RecordedOp::DivAssign { .. }
+                | RecordedOp::Store { .. } => true,
+            };
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Mutable counterpart of [`ReverseTrace::walk`], for an in-place
+    /// rewrite over the recorded tree.
+    pub fn walk_mut<F: FnMut(&mut RecordedOp, usize) -> bool>(&mut self, f: &mut F) {
+        Self::walk_ops_mut(&mut self.operations, 0, f);
+    }
+
+    fn walk_ops_mut<F: FnMut(&mut RecordedOp, usize) -> bool>(
+        ops: &mut [RecordedOp],
+        depth: usize,
+        f: &mut F,
+    ) -> bool {
+        for op in ops {
+            if !f(op, depth) {
+                return false;
+            }
+            let keep_going = match op {
+                RecordedOp::If { then_ops, else_ops, .. } => {
+                    Self::walk_ops_mut(then_ops, depth + 1, f) && Self::walk_ops_mut(else_ops, depth + 1, f)
+                }
+                RecordedOp::Loop { body_ops, .. } => {
+                    body_ops.iter_mut().all(|ops| Self::walk_ops_mut(ops, depth + 1, f))
+                }
+                RecordedOp::Switch { arm_ops, .. } => Self::walk_ops_mut(arm_ops, depth + 1, f),
+                RecordedOp::AddAssign { .. }
+                | RecordedOp::SubAssign { .. }
+                | RecordedOp::MulAssign { .. }
+                | RecordedOp::DivAssign { .. }
+                | RecordedOp::Store { .. } => true,
+            };
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Every variable any recorded op in this trace assigns to, found via
+    /// [`ReverseTrace::walk`] rather than a hand-written recursive match.
+    pub fn affected_variables(&self) -> HashSet<String> {
+        let mut out = HashSet::new();
+        self.walk(&mut |op, _depth| {
+            match op {
+                RecordedOp::AddAssign { target, .. }
+                | RecordedOp::SubAssign { target, .. }
+                | RecordedOp::MulAssign { target, .. }
+                | RecordedOp::DivAssign { target, .. }
+                | RecordedOp::Store { target, .. } => {
+                    out.insert(target.clone());
+                }
+                RecordedOp::If { .. } | RecordedOp::Loop { .. } | RecordedOp::Switch { .. } => {}
+            }
+            true
+        });
+        out
+    }
 }
 
 impl Default for ReverseTrace {
@@ -87,6 +268,16 @@ impl Default for ReverseTrace {
     }
 }
 
+/// A persistable snapshot of a `ReversibleInterpreter`'s state, for
+/// step-back debugging across process boundaries: save one to disk between
+/// the forward and reverse phases, then `restore` it (in this process or a
+/// later one) to pick reversal back up exactly where it left off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub variables: HashMap<String, Value>,
+    pub trace: ReverseTrace,
+}
+
 /// Reversible interpreter that records operations for reversal
 pub struct ReversibleInterpreter {
     variables: HashMap<String, Value>,
@@ -134,6 +325,39 @@ impl ReversibleInterpreter {
         self.execute_reverse()
     }
 
+    /// Snapshot the interpreter's variables and trace so a host program can
+    /// persist it (e.g. to disk, between forward and reverse phases) and
+    /// `restore` it later -- see `RecordedOp`/`ReverseTrace`'s `serde`
+    /// derives.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            variables: self.variables.clone(),
+            trace: self.trace.clone(),
+        }
+    }
+
+    /// Replace this interpreter's variables and trace with a previously
+    /// taken `Checkpoint`, e.g. one just deserialized from disk.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.variables = checkpoint.variables;
+        self.trace = checkpoint.trace;
+    }
+
+    /// Undo just the last `n` top-level recorded operations instead of the
+    /// whole trace, so a step-back debugger can single-step backward one
+    /// (or a handful of) operations at a time rather than only supporting
+    /// the all-or-nothing `execute_reverse`. The undone operations are
+    /// popped off the trace, same as `execute_reverse` clears it entirely.
+    pub fn step_back(&mut self, n: usize) -> Result<()> {
+        for _ in 0..n {
+            let Some(op) = self.trace.operations.pop() else {
+                break;
+            };
+            self.apply_operation(&op.inverse())?;
+        }
+        Ok(())
+    }
+
     fn execute_reversible_stmt(&mut self, stmt: &ReversibleStmt) -> Result<()> {
         match stmt {
             ReversibleStmt::AddAssign(target, expr) => {
@@ -165,6 +389,55 @@ impl ReversibleInterpreter {
                 self.variables.insert(target.clone(), new_value);
                 Ok(())
             }
+            ReversibleStmt::MulAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                if is_zero(&value)? {
+                    return Err(JtvError::RuntimeError(
+                        "reversible *= multiplier cannot be zero".to_string(),
+                    ));
+                }
+                let current = self.get_variable(target)?;
+                let new_value = current.mul(&value)?;
+
+                self.trace.record(RecordedOp::MulAssign {
+                    target: target.clone(),
+                    value: value.clone(),
+                });
+
+                self.variables.insert(target.clone(), new_value);
+                Ok(())
+            }
+            ReversibleStmt::DivAssign(target, expr) => {
+                let value = self.eval_data_expr(expr)?;
+                if is_zero(&value)? {
+                    return Err(JtvError::RuntimeError(
+                        "reversible /= divisor cannot be zero".to_string(),
+                    ));
+                }
+                let current = self.get_variable(target)?;
+                let new_value = current.div(&value)?;
+
+                self.trace.record(RecordedOp::DivAssign {
+                    target: target.clone(),
+                    value: value.clone(),
+                });
+
+                self.variables.insert(target.clone(), new_value);
+                Ok(())
+            }
+            ReversibleStmt::Assign(target, expr) => {
+                // No algebraic inverse, so this falls back to Bennett-style
+                // save-on-write: snapshot whatever `target` held before the
+                // overwrite and let reversal simply restore it, rather than
+                // trying to derive the old value from the new one.
+                let value = self.eval_data_expr(expr)?;
+                self.trace.record(RecordedOp::Store {
+                    target: target.clone(),
+                    old_value: self.variables.get(target).cloned(),
+                });
+                self.variables.insert(target.clone(), value);
+                Ok(())
+            }
             ReversibleStmt::If(if_stmt) => {
                 let condition = self.eval_control_expr(&if_stmt.condition)?;
                 let condition_true = condition.is_truthy();
@@ -196,28 +469,107 @@ impl ReversibleInterpreter {
                     else_ops: else_trace.operations,
                 });
 
+                Ok(())
+            }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                // `from`/`to`/`step` are evaluated once, up front -- the
+                // loop header never gets re-read once iteration starts, so
+                // a `body` that's mutated state they'd otherwise reference
+                // can't change how many times the loop runs.
+                let from_val = value_as_int(&self.eval_data_expr(from)?)?;
+                let to_val = value_as_int(&self.eval_data_expr(to)?)?;
+                let step_val = match step {
+                    Some(step_expr) => value_as_int(&self.eval_data_expr(step_expr)?)?,
+                    None => 1,
+                };
+                if step_val == 0 {
+                    return Err(JtvError::RuntimeError(
+                        "reversible for loop step cannot be zero".to_string(),
+                    ));
+                }
+
+                let mut body_ops = Vec::new();
+                let mut i = from_val;
+                while (step_val > 0 && i < to_val) || (step_val < 0 && i > to_val) {
+                    self.variables.insert(var.clone(), Value::Int(i));
+
+                    let old_trace = std::mem::take(&mut self.trace);
+                    for stmt in body {
+                        self.execute_reversible_stmt(stmt)?;
+                    }
+                    let iter_trace = std::mem::replace(&mut self.trace, old_trace);
+                    body_ops.push(iter_trace.operations);
+
+                    i += step_val;
+                }
+
+                self.trace.record(RecordedOp::Loop {
+                    iterations: body_ops.len(),
+                    body_ops,
+                });
+
+                Ok(())
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                let scrutinee_val = self.eval_data_expr(scrutinee)?;
+
+                let mut arm_taken = None;
+                let mut matching_body: Option<&[ReversibleStmt]> = None;
+                for (idx, (value, body)) in cases.iter().enumerate() {
+                    let case_val = self.eval_data_expr(value)?;
+                    if scrutinee_val.eq(&case_val)? {
+                        arm_taken = Some(idx);
+                        matching_body = Some(body);
+                        break;
+                    }
+                }
+                let body = matching_body.or(default.as_deref());
+
+                let old_trace = std::mem::take(&mut self.trace);
+                if let Some(body) = body {
+                    for stmt in body {
+                        self.execute_reversible_stmt(stmt)?;
+                    }
+                }
+                let arm_trace = std::mem::replace(&mut self.trace, old_trace);
+
+                self.trace.record(RecordedOp::Switch {
+                    arm_taken,
+                    arm_ops: arm_trace.operations,
+                });
+
                 Ok(())
             }
         }
     }
 
     fn execute_control_stmt_reversible(&mut self, stmt: &ControlStmt) -> Result<()> {
-        // Only assignments are allowed in reversible context
-        if let ControlStmt::Assignment(assign) = stmt {
-            let value = match &assign.value {
-                Expr::Data(expr) => self.eval_data_expr(expr)?,
-                Expr::Control(_) => {
-                    return Err(JtvError::RuntimeError(
-                        "Control expressions not allowed in reverse blocks".to_string(),
-                    ))
-                }
-            };
-            self.variables.insert(assign.target.clone(), value);
-            Ok(())
-        } else {
-            Err(JtvError::RuntimeError(
-                "Only assignments allowed in reversible if branches".to_string(),
-            ))
+        match stmt {
+            ControlStmt::Assignment(assign) => {
+                let value = match &assign.value {
+                    Expr::Data(expr) => self.eval_data_expr(expr)?,
+                    Expr::Control(_) => {
+                        return Err(JtvError::RuntimeError(
+                            "Control expressions not allowed in reverse blocks".to_string(),
+                        ))
+                    }
+                };
+                self.trace.record(RecordedOp::Store {
+                    target: assign.target.clone(),
+                    old_value: self.variables.get(&assign.target).cloned(),
+                });
+                self.variables.insert(assign.target.clone(), value);
+                Ok(())
+            }
+            // A reversible if's branches may nest another reverse block (this
+            // is how `invert` produces an invertible branch body); run it
+            // forward the same as a top-level one, recording into whichever
+            // trace is currently swapped in by the enclosing `If` arm above.
+            ControlStmt::ReverseBlock(block) => self.execute_forward(block),
+            _ => Err(JtvError::RuntimeError(
+                "Only assignments and nested reverse blocks are allowed in reversible if branches"
+                    .to_string(),
+            )),
         }
     }
 
@@ -236,6 +588,42 @@ impl ReversibleInterpreter {
                 self.variables.insert(target.clone(), new_value);
                 Ok(())
             }
+            RecordedOp::MulAssign { target, value } => {
+                let current = self.get_variable(target)?;
+                let new_value = current.mul(value)?;
+                self.variables.insert(target.clone(), new_value);
+                Ok(())
+            }
+            RecordedOp::DivAssign { target, value } => {
+                // This is reached either for a genuinely forward `x /=
+                // value` or as the reverse of a forward `x *= value` --
+                // either way, if both sides are `Int` the division must be
+                // exact, or `(x * k) / k == x` wouldn't hold and the
+                // "reversal" would silently lose information.
+                let current = self.get_variable(target)?;
+                if let (Value::Int(c), Value::Int(v)) = (&current, value) {
+                    if *v != 0 && c % v != 0 {
+                        return Err(JtvError::RuntimeError(format!(
+                            "cannot reverse `{} *= ...`: {} is not evenly divisible by {}",
+                            target, c, v
+                        )));
+                    }
+                }
+                let new_value = current.div(value)?;
+                self.variables.insert(target.clone(), new_value);
+                Ok(())
+            }
+            RecordedOp::Store { target, old_value } => {
+                match old_value {
+                    Some(value) => {
+                        self.variables.insert(target.clone(), value.clone());
+                    }
+                    None => {
+                        self.variables.remove(target);
+                    }
+                }
+                Ok(())
+            }
             RecordedOp::If {
                 condition_was_true,
                 then_ops,
@@ -248,6 +636,27 @@ impl ReversibleInterpreter {
                 }
                 Ok(())
             }
+            RecordedOp::Loop { body_ops, .. } => {
+                // `inverse()` already reversed both the iteration order and
+                // each iteration's own operations, so replaying in order
+                // here undoes the loop exactly.
+                for ops in body_ops {
+                    for nested_op in ops {
+                        self.apply_operation(nested_op)?;
+                    }
+                }
+                Ok(())
+            }
+            RecordedOp::Switch { arm_ops, .. } => {
+                // Doesn't re-evaluate the scrutinee or any case value --
+                // `arm_taken` already says which arm ran, and `arm_ops` is
+                // that arm's own trace, so replaying it is all reversal
+                // needs regardless of what the scrutinee looks like now.
+                for nested_op in arm_ops {
+                    self.apply_operation(nested_op)?;
+                }
+                Ok(())
+            }
         }
     }
 
@@ -292,6 +701,11 @@ impl ReversibleInterpreter {
                 let val = self.eval_control_expr(inner)?;
                 Ok(Value::Bool(!val.is_truthy()))
             }
+            ControlExpr::Contains(left, right) => {
+                let left_val = self.eval_data_expr(left)?;
+                let right_val = self.eval_data_expr(right)?;
+                Ok(Value::Bool(left_val.contains(&right_val)?))
+            }
         }
     }
 
@@ -314,6 +728,17 @@ impl ReversibleInterpreter {
             DataExpr::List(_) | DataExpr::Tuple(_) => Err(JtvError::RuntimeError(
                 "Collections not supported in reversible context".to_string(),
             )),
+            DataExpr::FieldAccess(_, _) | DataExpr::StructLiteral(_, _) => {
+                Err(JtvError::RuntimeError(
+                    "Structs not supported in reversible context".to_string(),
+                ))
+            }
+            DataExpr::ListComprehension(_) => Err(JtvError::RuntimeError(
+                "List comprehensions not supported in reversible context".to_string(),
+            )),
+            DataExpr::Index(_, _) => Err(JtvError::RuntimeError(
+                "Subscript indexing not supported in reversible context".to_string(),
+            )),
         }
     }
 
@@ -367,6 +792,26 @@ fn check_reversible_stmt(stmt: &ReversibleStmt) -> Result<()> {
             }
             Ok(())
         }
+        ReversibleStmt::MulAssign(target, expr) | ReversibleStmt::DivAssign(target, expr) => {
+            if expr_contains_var(expr, target) {
+                return Err(JtvError::RuntimeError(format!(
+                    "Variable '{}' cannot appear in its own reversible assignment (breaks reversibility)",
+                    target
+                )));
+            }
+            if matches!(expr, DataExpr::Number(Number::Int(0))) {
+                return Err(JtvError::RuntimeError(format!(
+                    "reversible *=//= on '{}' cannot use a multiplier/divisor of zero",
+                    target
+                )));
+            }
+            Ok(())
+        }
+        // Unlike `AddAssign`/`SubAssign`/`MulAssign`/`DivAssign`, a plain
+        // `Assign` has no algebraic inverse to break -- it's reversed by
+        // restoring a `RecordedOp::Store`'s snapshot of the old value, so
+        // `target` appearing in `expr` (e.g. `x = x * 2 + y`) is fine here.
+        ReversibleStmt::Assign(..) => Ok(()),
         ReversibleStmt::If(if_stmt) => {
             // Recursively check branches
             for stmt in &if_stmt.then_branch {
@@ -383,6 +828,140 @@ fn check_reversible_stmt(stmt: &ReversibleStmt) -> Result<()> {
             }
             Ok(())
         }
+        ReversibleStmt::For { var, body, .. } => {
+            // Assigning to the loop variable itself would make the
+            // recorded iteration count meaningless to replay against --
+            // the whole point of recording `iterations`/`body_ops` is that
+            // the reverse pass never has to trust `var`'s final value.
+            let mut written = HashSet::new();
+            written_vars_reversible(body, &mut written);
+            if written.contains(var) {
+                return Err(JtvError::RuntimeError(format!(
+                    "loop variable '{}' cannot be assigned to inside its own reversible for loop body (breaks reversibility)",
+                    var
+                )));
+            }
+            for stmt in body {
+                check_reversible_stmt(stmt)?;
+            }
+            Ok(())
+        }
+        ReversibleStmt::Switch { cases, default, .. } => {
+            for (_, body) in cases {
+                for stmt in body {
+                    check_reversible_stmt(stmt)?;
+                }
+            }
+            if let Some(default) = default {
+                for stmt in default {
+                    check_reversible_stmt(stmt)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// A stricter sibling of `check_reversibility`, used by
+/// `Interpreter::eval_reverse_block_backward`: not only must a statement's
+/// own target stay out of its own expression, but no expression or branch
+/// condition anywhere in `block` may read *any* variable the block mutates.
+/// Running a reverse block backward replays its statements in the opposite
+/// order, so a read of a variable the block itself writes would see a
+/// different value on the way back than it did on the way forward, making
+/// the inverse ambiguous rather than just wrong.
+pub fn check_fully_reversible(block: &ReverseBlock) -> Result<()> {
+    let mut written = HashSet::new();
+    written_vars_reversible(&block.body, &mut written);
+    check_fully_reversible_stmts(&block.body, &written)
+}
+
+fn check_fully_reversible_stmts(stmts: &[ReversibleStmt], written: &HashSet<String>) -> Result<()> {
+    for stmt in stmts {
+        match stmt {
+            ReversibleStmt::AddAssign(target, expr) | ReversibleStmt::SubAssign(target, expr) => {
+                if let Some(culprit) = written.iter().find(|var| *var != target && expr_contains_var(expr, var)) {
+                    return Err(JtvError::NonReversible(format!(
+                        "reversible assignment to '{}' reads '{}', which this block also mutates: \
+                         reversing the block would read it at a different point in the computation",
+                        target, culprit
+                    )));
+                }
+                if expr_contains_var(expr, target) {
+                    return Err(JtvError::NonReversible(format!(
+                        "variable '{}' cannot appear in its own reversible assignment (breaks reversibility)",
+                        target
+                    )));
+                }
+                Ok(())
+            }
+            ReversibleStmt::MulAssign(target, expr) | ReversibleStmt::DivAssign(target, expr) => {
+                if let Some(culprit) = written.iter().find(|var| *var != target && expr_contains_var(expr, var)) {
+                    return Err(JtvError::NonReversible(format!(
+                        "reversible assignment to '{}' reads '{}', which this block also mutates: \
+                         reversing the block would read it at a different point in the computation",
+                        target, culprit
+                    )));
+                }
+                if expr_contains_var(expr, target) {
+                    return Err(JtvError::NonReversible(format!(
+                        "variable '{}' cannot appear in its own reversible assignment (breaks reversibility)",
+                        target
+                    )));
+                }
+                if matches!(expr, DataExpr::Number(Number::Int(0))) {
+                    return Err(JtvError::NonReversible(format!(
+                        "reversible *=//= on '{}' cannot use a multiplier/divisor of zero",
+                        target
+                    )));
+                }
+                Ok(())
+            }
+            ReversibleStmt::If(if_stmt) => {
+                if let Some(culprit) = written
+                    .iter()
+                    .find(|var| control_expr_contains_var(&if_stmt.condition, var))
+                {
+                    return Err(JtvError::NonReversible(format!(
+                        "reversible if condition reads '{}', which this block mutates: \
+                         the forward and backward passes could pick different branches",
+                        culprit
+                    )));
+                }
+                for stmt in &if_stmt.then_branch {
+                    if let ControlStmt::ReverseBlock(nested) = stmt {
+                        check_fully_reversible(nested)?;
+                    }
+                }
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    for stmt in else_branch {
+                        if let ControlStmt::ReverseBlock(nested) = stmt {
+                            check_fully_reversible(nested)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            ReversibleStmt::For { .. } => Err(JtvError::NonReversible(
+                "a reversible for loop doesn't have a defined static inverse yet -- only \
+                 AddAssign/SubAssign/If can run backward via check_fully_reversible; run it \
+                 through ReversibleInterpreter's trace-based execute_reverse instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::Switch { .. } => Err(JtvError::NonReversible(
+                "a reversible switch doesn't have a defined static inverse yet -- only \
+                 AddAssign/SubAssign/If can run backward via check_fully_reversible; run it \
+                 through ReversibleInterpreter's trace-based execute_reverse instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::Assign(..) => Err(JtvError::NonReversible(
+                "a plain reversible assignment has no algebraic inverse, so only \
+                 AddAssign/SubAssign/If can run backward via check_fully_reversible; run it \
+                 through ReversibleInterpreter's trace-based execute_reverse instead, which \
+                 reverses it Bennett-style via RecordedOp::Store"
+                    .to_string(),
+            )),
+        }
     }
 }
 
@@ -395,35 +974,234 @@ fn expr_contains_var(expr: &DataExpr, var: &str) -> bool {
         DataExpr::FunctionCall(call) => call.args.iter().any(|arg| expr_contains_var(arg, var)),
         DataExpr::List(elems) => elems.iter().any(|e| expr_contains_var(e, var)),
         DataExpr::Tuple(elems) => elems.iter().any(|e| expr_contains_var(e, var)),
+        DataExpr::FieldAccess(base, _) => expr_contains_var(base, var),
+        DataExpr::StructLiteral(_, fields) => {
+            fields.iter().any(|(_, e)| expr_contains_var(e, var))
+        }
+        DataExpr::ListComprehension(comp) => {
+            expr_contains_var(&comp.body, var)
+                || comp.generators.iter().any(|(_, source)| expr_contains_var(source, var))
+        }
+        DataExpr::Index(base, index) => {
+            expr_contains_var(base, var) || expr_contains_var(index, var)
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::parser::parse_program;
-
-    #[test]
-    fn test_forward_execution() {
-        let mut interp = ReversibleInterpreter::new();
-        interp.set("x".to_string(), Value::Int(10));
+pub(crate) fn control_expr_contains_var(expr: &ControlExpr, var: &str) -> bool {
+    match expr {
+        ControlExpr::Data(data) => expr_contains_var(data, var),
+        ControlExpr::Comparison(left, _, right) => {
+            expr_contains_var(left, var) || expr_contains_var(right, var)
+        }
+        ControlExpr::Logical(left, _, right) => {
+            control_expr_contains_var(left, var) || control_expr_contains_var(right, var)
+        }
+        ControlExpr::Not(inner) => control_expr_contains_var(inner, var),
+        ControlExpr::Contains(left, right) => {
+            expr_contains_var(left, var) || expr_contains_var(right, var)
+        }
+    }
+}
 
-        let block = ReverseBlock {
-            body: vec![
-                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
-            ],
-        };
+/// Collect every variable assigned anywhere in `stmts` -- by a bare
+/// `ControlStmt::Assignment`, or transitively through a nested
+/// `ReverseBlock`'s `AddAssign`/`SubAssign` targets.
+pub(crate) fn written_vars(stmts: &[ControlStmt], out: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            ControlStmt::Assignment(assign) => {
+                out.insert(assign.target.clone());
+            }
+            ControlStmt::ReverseBlock(block) => written_vars_reversible(&block.body, out),
+            _ => {}
+        }
+    }
+}
 
-        interp.execute_forward(&block).unwrap();
-        assert_eq!(interp.get("x"), Some(&Value::Int(15)));
+fn written_vars_reversible(stmts: &[ReversibleStmt], out: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            ReversibleStmt::AddAssign(target, _)
+            | ReversibleStmt::SubAssign(target, _)
+            | ReversibleStmt::MulAssign(target, _)
+            | ReversibleStmt::DivAssign(target, _)
+            | ReversibleStmt::Assign(target, _) => {
+                out.insert(target.clone());
+            }
+            ReversibleStmt::If(if_stmt) => {
+                written_vars(&if_stmt.then_branch, out);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    written_vars(else_branch, out);
+                }
+            }
+            ReversibleStmt::For { var, body, .. } => {
+                out.insert(var.clone());
+                written_vars_reversible(body, out);
+            }
+            ReversibleStmt::Switch { cases, default, .. } => {
+                for (_, body) in cases {
+                    written_vars_reversible(body, out);
+                }
+                if let Some(default) = default {
+                    written_vars_reversible(default, out);
+                }
+            }
+        }
     }
+}
 
-    #[test]
-    fn test_reverse_execution() {
-        let mut interp = ReversibleInterpreter::new();
-        interp.set("x".to_string(), Value::Int(10));
+/// Statically invert a reverse block, Janus-style: run the statements in
+/// reverse order and swap each `AddAssign`/`SubAssign` for the other, so
+/// that executing `block` followed by `invert(block)` returns every
+/// variable to its starting value.
+///
+/// An `If` is inverted by keeping its condition as-is and inverting each
+/// branch's statement list. The Janus invariant this relies on is that the
+/// condition, re-evaluated once the inverse reaches it, must still pick the
+/// same branch it picked on the way forward -- i.e. the condition can't
+/// read anything either branch wrote, or a value the branch changed would
+/// silently steer the reverse run down the wrong side. That's checked
+/// here and rejected with a `JtvError::RuntimeError` rather than produced
+/// as a silently-wrong inverse.
+pub fn invert(block: &ReverseBlock) -> Result<ReverseBlock> {
+    let mut inverted = Vec::with_capacity(block.body.len());
+    for stmt in block.body.iter().rev() {
+        inverted.push(invert_stmt(stmt)?);
+    }
+    Ok(ReverseBlock { body: inverted })
+}
 
-        let block = ReverseBlock {
+fn invert_stmt(stmt: &ReversibleStmt) -> Result<ReversibleStmt> {
+    match stmt {
+        ReversibleStmt::AddAssign(target, expr) => {
+            Ok(ReversibleStmt::SubAssign(target.clone(), expr.clone()))
+        }
+        ReversibleStmt::SubAssign(target, expr) => {
+            Ok(ReversibleStmt::AddAssign(target.clone(), expr.clone()))
+        }
+        ReversibleStmt::MulAssign(target, expr) => {
+            Ok(ReversibleStmt::DivAssign(target.clone(), expr.clone()))
+        }
+        ReversibleStmt::DivAssign(target, expr) => {
+            Ok(ReversibleStmt::MulAssign(target.clone(), expr.clone()))
+        }
+        ReversibleStmt::If(if_stmt) => Ok(ReversibleStmt::If(invert_if(if_stmt)?)),
+        ReversibleStmt::For { .. } => Err(JtvError::RuntimeError(
+            "cannot statically invert a reversible for loop yet -- only AddAssign/SubAssign/If \
+             have a defined `invert`; run it through ReversibleInterpreter's trace-based \
+             execute_reverse instead"
+                .to_string(),
+        )),
+        ReversibleStmt::Switch { .. } => Err(JtvError::RuntimeError(
+            "cannot statically invert a reversible switch yet -- only AddAssign/SubAssign/If \
+             have a defined `invert`; run it through ReversibleInterpreter's trace-based \
+             execute_reverse instead"
+                .to_string(),
+        )),
+        ReversibleStmt::Assign(..) => Err(JtvError::RuntimeError(
+            "cannot statically invert a plain reversible assignment -- it has no algebraic \
+             inverse, only AddAssign/SubAssign/If have a defined `invert`; run it through \
+             ReversibleInterpreter's trace-based execute_reverse instead, which reverses it \
+             Bennett-style via RecordedOp::Store"
+                .to_string(),
+        )),
+    }
+}
+
+fn invert_if(if_stmt: &IfStmt) -> Result<IfStmt> {
+    let mut written = HashSet::new();
+    written_vars(&if_stmt.then_branch, &mut written);
+    if let Some(else_branch) = &if_stmt.else_branch {
+        written_vars(else_branch, &mut written);
+    }
+    if let Some(culprit) = written
+        .iter()
+        .find(|var| control_expr_contains_var(&if_stmt.condition, var))
+    {
+        return Err(JtvError::RuntimeError(format!(
+            "reversible if condition reads '{}', which is written inside a branch: \
+             the entry test and exit assertion must agree, so the condition must not \
+             depend on anything the branches assign",
+            culprit
+        )));
+    }
+    Ok(IfStmt {
+        condition: if_stmt.condition.clone(),
+        then_branch: invert_control_stmts(&if_stmt.then_branch)?,
+        else_branch: if_stmt
+            .else_branch
+            .as_ref()
+            .map(|branch| invert_control_stmts(branch))
+            .transpose()?,
+    })
+}
+
+fn invert_control_stmts(stmts: &[ControlStmt]) -> Result<Vec<ControlStmt>> {
+    let mut inverted = Vec::with_capacity(stmts.len());
+    for stmt in stmts.iter().rev() {
+        inverted.push(invert_control_stmt(stmt)?);
+    }
+    Ok(inverted)
+}
+
+fn invert_control_stmt(stmt: &ControlStmt) -> Result<ControlStmt> {
+    match stmt {
+        ControlStmt::ReverseBlock(block) => Ok(ControlStmt::ReverseBlock(invert(block)?)),
+        other => Err(JtvError::RuntimeError(format!(
+            "cannot invert {:?} inside a reversible if branch: only AddAssign/SubAssign, \
+             wrapped in a nested reverse block, have a defined inverse there",
+            other
+        ))),
+    }
+}
+
+/// Run `block` forward from `initial`, then run `invert(block)` forward
+/// from the resulting state, and report whether the environment landed
+/// back on `initial`. Unlike `ReversibleInterpreter::execute_and_reverse`
+/// (which replays a recorded trace), this actually executes the inverted
+/// program produced by `invert`, proving the static inversion is correct
+/// rather than just the trace replay.
+pub fn run_and_verify_round_trip(
+    initial: HashMap<String, Value>,
+    block: &ReverseBlock,
+) -> Result<bool> {
+    let mut forward = ReversibleInterpreter::with_state(initial.clone());
+    forward.execute_forward(block)?;
+
+    let inverse = invert(block)?;
+    let mut backward = ReversibleInterpreter::with_state(forward.get_state().clone());
+    backward.execute_forward(&inverse)?;
+
+    Ok(*backward.get_state() == initial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn test_forward_execution() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(10));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+            ],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(15)));
+    }
+
+    #[test]
+    fn test_reverse_execution() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(10));
+
+        let block = ReverseBlock {
             body: vec![
                 ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
             ],
@@ -464,6 +1242,19 @@ mod tests {
         assert_eq!(interp.get("y"), original_y.as_ref());
     }
 
+    #[test]
+    fn test_eval_control_expr_contains_checks_list_membership() {
+        let interp = ReversibleInterpreter::new();
+        let condition = ControlExpr::Contains(
+            Box::new(DataExpr::Number(Number::Int(2))),
+            Box::new(DataExpr::List(vec![
+                DataExpr::Number(Number::Int(1)),
+                DataExpr::Number(Number::Int(2)),
+            ])),
+        );
+        assert_eq!(interp.eval_control_expr(&condition).unwrap(), Value::Bool(true));
+    }
+
     #[test]
     fn test_reversibility_check_fails() {
         // x += x is not reversible because we can't recover original x
@@ -494,6 +1285,36 @@ mod tests {
         assert!(check_reversibility(&block).is_ok());
     }
 
+    #[test]
+    fn test_check_fully_reversible_rejects_read_of_another_mutated_var() {
+        // y = y + x, then x = x + 1 -- x is read by the first statement but
+        // also mutated by the second, so the backward pass would read it at
+        // a different point than the forward pass did.
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("y".to_string(), DataExpr::Identifier("x".to_string())),
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(1))),
+            ],
+        };
+
+        match check_fully_reversible(&block) {
+            Err(JtvError::NonReversible(msg)) => assert!(msg.contains('x')),
+            other => panic!("expected NonReversible naming 'x', got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_fully_reversible_accepts_independent_vars() {
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(1))),
+                ReversibleStmt::SubAssign("y".to_string(), DataExpr::Number(Number::Int(2))),
+            ],
+        };
+
+        assert!(check_fully_reversible(&block).is_ok());
+    }
+
     #[test]
     fn test_execute_and_reverse_identity() {
         let mut interp = ReversibleInterpreter::new();
@@ -514,4 +1335,557 @@ mod tests {
         // State should be identical to original
         assert_eq!(interp.get_state(), &original_state);
     }
+
+    #[test]
+    fn test_invert_swaps_add_and_sub_and_reverses_order() {
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::SubAssign("y".to_string(), DataExpr::Number(Number::Int(3))),
+            ],
+        };
+
+        let inverse = invert(&block).unwrap();
+        assert_eq!(
+            inverse.body,
+            vec![
+                ReversibleStmt::AddAssign("y".to_string(), DataExpr::Number(Number::Int(3))),
+                ReversibleStmt::SubAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+            ]
+        );
+    }
+
+    fn sample_block_with_if() -> ReverseBlock {
+        ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::If(IfStmt {
+                    condition: ControlExpr::Comparison(
+                        Box::new(DataExpr::Identifier("x".to_string())),
+                        Comparator::Gt,
+                        Box::new(DataExpr::Number(Number::Int(0))),
+                    ),
+                    then_branch: vec![ControlStmt::ReverseBlock(ReverseBlock {
+                        body: vec![ReversibleStmt::AddAssign(
+                            "y".to_string(),
+                            DataExpr::Number(Number::Int(1)),
+                        )],
+                    })],
+                    else_branch: Some(vec![ControlStmt::ReverseBlock(ReverseBlock {
+                        body: vec![ReversibleStmt::SubAssign(
+                            "y".to_string(),
+                            DataExpr::Number(Number::Int(1)),
+                        )],
+                    })]),
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_invert_round_trip_through_if_branch() {
+        let block = sample_block_with_if();
+        let mut initial = HashMap::new();
+        initial.insert("x".to_string(), Value::Int(10));
+        initial.insert("y".to_string(), Value::Int(0));
+
+        assert!(run_and_verify_round_trip(initial, &block).unwrap());
+    }
+
+    #[test]
+    fn test_invert_rejects_condition_reading_branch_written_var() {
+        // The condition reads `y`, but the then-branch writes `y` -- the
+        // entry test and exit assertion can't be guaranteed to agree.
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::If(IfStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Identifier("y".to_string())),
+                    Comparator::Gt,
+                    Box::new(DataExpr::Number(Number::Int(0))),
+                ),
+                then_branch: vec![ControlStmt::ReverseBlock(ReverseBlock {
+                    body: vec![ReversibleStmt::AddAssign(
+                        "y".to_string(),
+                        DataExpr::Number(Number::Int(1)),
+                    )],
+                })],
+                else_branch: None,
+            })],
+        };
+
+        match invert(&block) {
+            Err(JtvError::RuntimeError(msg)) => assert!(msg.contains('y')),
+            other => panic!("expected a RuntimeError naming the offending variable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reversible_for_executes_and_reverses() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("total".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::For {
+                var: "i".to_string(),
+                from: DataExpr::Number(Number::Int(0)),
+                to: DataExpr::Number(Number::Int(5)),
+                step: None,
+                body: vec![ReversibleStmt::AddAssign(
+                    "total".to_string(),
+                    DataExpr::Identifier("i".to_string()),
+                )],
+            }],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        // total = 0 + 1 + 2 + 3 + 4 = 10
+        assert_eq!(interp.get("total"), Some(&Value::Int(10)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(0)));
+    }
+
+    #[test]
+    fn test_reversible_for_descending_step() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("total".to_string(), Value::Int(100));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::For {
+                var: "i".to_string(),
+                from: DataExpr::Number(Number::Int(5)),
+                to: DataExpr::Number(Number::Int(0)),
+                step: Some(DataExpr::Number(Number::Int(-1))),
+                body: vec![ReversibleStmt::SubAssign(
+                    "total".to_string(),
+                    DataExpr::Identifier("i".to_string()),
+                )],
+            }],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        // total = 100 - 5 - 4 - 3 - 2 - 1 = 85
+        assert_eq!(interp.get("total"), Some(&Value::Int(85)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(100)));
+    }
+
+    #[test]
+    fn test_reversible_for_zero_step_is_rejected() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("total".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::For {
+                var: "i".to_string(),
+                from: DataExpr::Number(Number::Int(0)),
+                to: DataExpr::Number(Number::Int(5)),
+                step: Some(DataExpr::Number(Number::Int(0))),
+                body: vec![],
+            }],
+        };
+
+        assert!(interp.execute_forward(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversibility_check_rejects_for_loop_assigning_to_its_own_var() {
+        // Mutating the loop variable inside the body would make it
+        // impossible to recompute the iteration count on replay.
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::For {
+                var: "i".to_string(),
+                from: DataExpr::Number(Number::Int(0)),
+                to: DataExpr::Number(Number::Int(5)),
+                step: None,
+                body: vec![ReversibleStmt::AddAssign(
+                    "i".to_string(),
+                    DataExpr::Number(Number::Int(1)),
+                )],
+            }],
+        };
+
+        assert!(check_reversibility(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversible_switch_executes_matching_case_and_reverses() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("day".to_string(), Value::Int(2));
+        interp.set("hours".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::Switch {
+                scrutinee: DataExpr::Identifier("day".to_string()),
+                cases: vec![
+                    (
+                        DataExpr::Number(Number::Int(1)),
+                        vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(8)))],
+                    ),
+                    (
+                        DataExpr::Number(Number::Int(2)),
+                        vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(6)))],
+                    ),
+                ],
+                default: Some(vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(0)))]),
+            }],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(6)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(0)));
+    }
+
+    #[test]
+    fn test_reversible_switch_falls_through_to_default() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("day".to_string(), Value::Int(9));
+        interp.set("hours".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::Switch {
+                scrutinee: DataExpr::Identifier("day".to_string()),
+                cases: vec![(
+                    DataExpr::Number(Number::Int(1)),
+                    vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(8)))],
+                )],
+                default: Some(vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(1)))]),
+            }],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(1)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(0)));
+    }
+
+    #[test]
+    fn test_reversible_switch_no_match_no_default_is_a_no_op() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("day".to_string(), Value::Int(9));
+        interp.set("hours".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::Switch {
+                scrutinee: DataExpr::Identifier("day".to_string()),
+                cases: vec![(
+                    DataExpr::Number(Number::Int(1)),
+                    vec![ReversibleStmt::AddAssign("hours".to_string(), DataExpr::Number(Number::Int(8)))],
+                )],
+                default: None,
+            }],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(5)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("hours"), Some(&Value::Int(5)));
+    }
+
+    #[test]
+    fn test_reversible_mul_assign_executes_and_reverses() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Number(Number::Int(3)))],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(15)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(5)));
+    }
+
+    #[test]
+    fn test_reversible_div_assign_executes_and_reverses() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(20));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::DivAssign("x".to_string(), DataExpr::Number(Number::Int(4)))],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(5)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(20)));
+    }
+
+    #[test]
+    fn test_reversible_mul_assign_rejects_zero_multiplier() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Number(Number::Int(0)))],
+        };
+
+        assert!(interp.execute_forward(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversible_mul_assign_rejects_zero_float_multiplier() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Number(Number::Float(0.0)))],
+        };
+
+        assert!(interp.execute_forward(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversible_div_assign_rejects_zero_float_divisor() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::DivAssign("x".to_string(), DataExpr::Number(Number::Float(0.0)))],
+        };
+
+        assert!(interp.execute_forward(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversible_mul_assign_rejects_non_exact_reverse_division() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Number(Number::Int(3)))],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(15)));
+
+        // Tamper with `x` between forward and reverse so the recorded `/ 3`
+        // can no longer divide evenly -- reversal must reject it rather than
+        // silently truncating.
+        interp.set("x".to_string(), Value::Int(16));
+        assert!(interp.execute_reverse().is_err());
+    }
+
+    #[test]
+    fn test_check_reversibility_rejects_zero_multiplier() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Number(Number::Int(0)))],
+        };
+        assert!(check_reversibility(&block).is_err());
+    }
+
+    #[test]
+    fn test_check_reversibility_rejects_self_referential_mul_assign() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::MulAssign("x".to_string(), DataExpr::Identifier("x".to_string()))],
+        };
+        assert!(check_reversibility(&block).is_err());
+    }
+
+    #[test]
+    fn test_reversible_assign_allows_self_reference_and_reverses() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("x".to_string(), Value::Int(5));
+        interp.set("y".to_string(), Value::Int(3));
+
+        // `x = x * 2 + y` has no algebraic inverse -- this is exactly the
+        // destructive statement `check_reversible_stmt` used to reject.
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::Assign(
+                "x".to_string(),
+                DataExpr::Add(
+                    Box::new(DataExpr::Identifier("x".to_string())),
+                    Box::new(DataExpr::Identifier("y".to_string())),
+                ),
+            )],
+        };
+
+        assert!(check_reversibility(&block).is_ok());
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(8)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(5)));
+    }
+
+    #[test]
+    fn test_reversible_assign_restores_unset_variable_by_removing_it() {
+        let mut interp = ReversibleInterpreter::new();
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::Assign("x".to_string(), DataExpr::Number(Number::Int(42)))],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(42)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("x"), None);
+    }
+
+    #[test]
+    fn test_reversible_if_branch_assignment_reverses_via_store() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("flag".to_string(), Value::Bool(true));
+        interp.set("x".to_string(), Value::Int(1));
+
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::If(IfStmt {
+                condition: ControlExpr::Data(DataExpr::Identifier("flag".to_string())),
+                then_branch: vec![ControlStmt::Assignment(Assignment {
+                    target: "x".to_string(),
+                    value: Expr::Data(DataExpr::Number(Number::Int(99))),
+                })],
+                else_branch: None,
+            })],
+        };
+
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(99)));
+
+        interp.execute_reverse().unwrap();
+        assert_eq!(interp.get("x"), Some(&Value::Int(1)));
+    }
+
+    #[test]
+    fn test_walk_visits_ops_depth_first_with_nesting_depth() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("flag".to_string(), Value::Bool(true));
+        interp.set("total".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("total".to_string(), DataExpr::Number(Number::Int(1))),
+                ReversibleStmt::If(IfStmt {
+                    condition: ControlExpr::Data(DataExpr::Identifier("flag".to_string())),
+                    then_branch: vec![],
+                    else_branch: None,
+                }),
+            ],
+        };
+        interp.execute_forward(&block).unwrap();
+
+        let mut visited = vec![];
+        interp.trace.walk(&mut |op, depth| {
+            visited.push((depth, op.clone()));
+            true
+        });
+
+        assert_eq!(visited.len(), 2);
+        assert_eq!(visited[0].0, 0);
+        assert!(matches!(visited[0].1, RecordedOp::AddAssign { .. }));
+        assert_eq!(visited[1].0, 0);
+        assert!(matches!(visited[1].1, RecordedOp::If { .. }));
+    }
+
+    #[test]
+    fn test_walk_stops_everywhere_once_callback_returns_false() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("a".to_string(), Value::Int(0));
+        interp.set("b".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("a".to_string(), DataExpr::Number(Number::Int(1))),
+                ReversibleStmt::AddAssign("b".to_string(), DataExpr::Number(Number::Int(1))),
+            ],
+        };
+        interp.execute_forward(&block).unwrap();
+
+        let mut count = 0;
+        interp.trace.walk(&mut |_op, _depth| {
+            count += 1;
+            false
+        });
+
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_affected_variables_collects_targets_nested_inside_if() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("flag".to_string(), Value::Bool(true));
+        interp.set("x".to_string(), Value::Int(1));
+        interp.set("outer".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("outer".to_string(), DataExpr::Number(Number::Int(1))),
+                ReversibleStmt::If(IfStmt {
+                    condition: ControlExpr::Data(DataExpr::Identifier("flag".to_string())),
+                    then_branch: vec![ControlStmt::Assignment(Assignment {
+                        target: "x".to_string(),
+                        value: Expr::Data(DataExpr::Number(Number::Int(99))),
+                    })],
+                    else_branch: None,
+                }),
+            ],
+        };
+        interp.execute_forward(&block).unwrap();
+
+        let affected = interp.trace.affected_variables();
+        assert_eq!(affected, HashSet::from(["outer".to_string(), "x".to_string()]));
+    }
+
+    #[test]
+    fn test_checkpoint_binary_round_trip_then_reverse_reproduces_original_state() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("total".to_string(), Value::Int(10));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("total".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::MulAssign("total".to_string(), DataExpr::Number(Number::Int(3))),
+            ],
+        };
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(45)));
+
+        // Snapshot mid-flight (forward done, reverse not yet run), round-trip
+        // through a compact binary encoding as if it had been written to
+        // disk and loaded back in a later process.
+        let checkpoint = interp.checkpoint();
+        let bytes = serde_json::to_vec(&checkpoint).unwrap();
+        let restored: Checkpoint = serde_json::from_slice(&bytes).unwrap();
+
+        let mut reloaded = ReversibleInterpreter::new();
+        reloaded.restore(restored);
+        assert_eq!(reloaded.get("total"), Some(&Value::Int(45)));
+
+        reloaded.execute_reverse().unwrap();
+        assert_eq!(reloaded.get("total"), Some(&Value::Int(10)));
+    }
+
+    #[test]
+    fn test_step_back_undoes_only_the_last_n_operations() {
+        let mut interp = ReversibleInterpreter::new();
+        interp.set("total".to_string(), Value::Int(0));
+
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("total".to_string(), DataExpr::Number(Number::Int(1))),
+                ReversibleStmt::AddAssign("total".to_string(), DataExpr::Number(Number::Int(10))),
+                ReversibleStmt::AddAssign("total".to_string(), DataExpr::Number(Number::Int(100))),
+            ],
+        };
+        interp.execute_forward(&block).unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(111)));
+
+        // Step back just the last op (the += 100).
+        interp.step_back(1).unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(11)));
+
+        // Stepping back further than what's left just runs out of history.
+        interp.step_back(5).unwrap();
+        assert_eq!(interp.get("total"), Some(&Value::Int(0)));
+    }
 }
@@ -0,0 +1,604 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Bidirectional bytecode VM for `reverse { ... }` blocks.
+//
+// `bytecode::BytecodeVM` compiles the whole language to a flat `Opcode`
+// stream, but its `ReverseBlock` handling is just a pair of inert
+// `BeginReverse`/`EndReverse` markers -- there is no backward execution.
+// This module is a small VM scoped specifically to reversible blocks: every
+// `RvmOp` has a statically known inverse, so a `VmCode` compiled once can be
+// replayed forward or backward by walking `ops` (or its reverse) and
+// dispatching each opcode or its `inverse()` -- no tree-walking, no trace
+// recording. `crate::libraries::jtv::reversible`'s functions are the
+// semantic reference the gate opcodes (`Cnot`, `Xor`, `Swap`) delegate to,
+// so the VM can never drift from the stdlib definitions of those gates.
+
+use crate::ast::*;
+use crate::error::{JtvError, Result};
+use crate::libraries::jtv::reversible as gates;
+use crate::number::Value;
+use crate::reversible::{control_expr_contains_var, written_vars};
+use std::collections::{HashMap, HashSet};
+
+/// Which way to replay a `VmCode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// A pure (non-mutating) operand expression, evaluated via a small stack
+/// machine. Staging never touches a register that an enclosing `RvmOp`
+/// mutates -- `compile_reverse_block` rejects any statement whose operand
+/// reads its own target -- so re-running the same stage forward or backward
+/// always yields the same value (the Janus invariant this VM relies on).
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageOp {
+    Push(Value),
+    PushReg(u32),
+    Add,
+    Neg,
+}
+
+fn eval_stage(stage: &[StageOp], registers: &[Value]) -> Result<Value> {
+    let mut stack: Vec<Value> = Vec::new();
+    for op in stage {
+        match op {
+            StageOp::Push(v) => stack.push(v.clone()),
+            StageOp::PushReg(r) => stack.push(registers[*r as usize].clone()),
+            StageOp::Add => {
+                let b = stack.pop().ok_or_else(stack_underflow)?;
+                let a = stack.pop().ok_or_else(stack_underflow)?;
+                stack.push(a.add(&b)?);
+            }
+            StageOp::Neg => {
+                let a = stack.pop().ok_or_else(stack_underflow)?;
+                stack.push(a.negate()?);
+            }
+        }
+    }
+    stack.pop().ok_or_else(stack_underflow)
+}
+
+fn stack_underflow() -> JtvError {
+    JtvError::RuntimeError("reversible VM operand stack underflow".to_string())
+}
+
+/// A condition expression, evaluated the same way going forward or
+/// backward -- an `If`'s inverse keeps its `condition` untouched and only
+/// inverts the branches (see `RvmOp::inverse`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum CondOp {
+    Compare(Vec<StageOp>, Comparator, Vec<StageOp>),
+    And(Box<CondOp>, Box<CondOp>),
+    Or(Box<CondOp>, Box<CondOp>),
+    Not(Box<CondOp>),
+}
+
+fn eval_cond(cond: &CondOp, registers: &[Value]) -> Result<bool> {
+    match cond {
+        CondOp::Compare(lhs, op, rhs) => {
+            let l = eval_stage(lhs, registers)?;
+            let r = eval_stage(rhs, registers)?;
+            match op {
+                Comparator::Eq => l.eq(&r),
+                Comparator::Ne => l.ne(&r),
+                Comparator::Lt => l.lt(&r),
+                Comparator::Le => l.le(&r),
+                Comparator::Gt => l.gt(&r),
+                Comparator::Ge => l.ge(&r),
+            }
+        }
+        CondOp::And(l, r) => Ok(eval_cond(l, registers)? && eval_cond(r, registers)?),
+        CondOp::Or(l, r) => Ok(eval_cond(l, registers)? || eval_cond(r, registers)?),
+        CondOp::Not(inner) => Ok(!eval_cond(inner, registers)?),
+    }
+}
+
+/// A reversible VM instruction. Every variant but `If` has a fixed,
+/// statically known inverse; `If` inverts by keeping its condition and
+/// inverting+reversing each branch (the same Janus scheme as
+/// `reversible::invert`/`invert_if`, just lowered to registers).
+#[derive(Debug, Clone, PartialEq)]
+pub enum RvmOp {
+    /// `reg[target] += operand` (inverse: `Sub`)
+    Add { target: u32, operand: Vec<StageOp> },
+    /// `reg[target] -= operand` (inverse: `Add`)
+    Sub { target: u32, operand: Vec<StageOp> },
+    /// `reg[target] += 1` (inverse: `Dec`)
+    Inc { target: u32 },
+    /// `reg[target] -= 1` (inverse: `Inc`)
+    Dec { target: u32 },
+    /// If `reg[control]` is truthy, negate `reg[target]` (self-inverse,
+    /// delegates to `gates::cnot`)
+    Cnot { control: u32, target: u32 },
+    /// `reg[target] ^= operand` (self-inverse, delegates to `gates::xor`)
+    Xor { target: u32, operand: Vec<StageOp> },
+    /// Swap the contents of two registers (self-inverse, delegates to
+    /// `gates::swap`)
+    Swap { a: u32, b: u32 },
+    /// Evaluate `condition`; run `then_branch` if truthy, else `else_branch`.
+    If {
+        condition: CondOp,
+        then_branch: Vec<RvmOp>,
+        else_branch: Vec<RvmOp>,
+    },
+}
+
+impl RvmOp {
+    /// The statically known inverse of this opcode.
+    pub fn inverse(&self) -> RvmOp {
+        match self {
+            RvmOp::Add { target, operand } => RvmOp::Sub { target: *target, operand: operand.clone() },
+            RvmOp::Sub { target, operand } => RvmOp::Add { target: *target, operand: operand.clone() },
+            RvmOp::Inc { target } => RvmOp::Dec { target: *target },
+            RvmOp::Dec { target } => RvmOp::Inc { target: *target },
+            RvmOp::Cnot { control, target } => RvmOp::Cnot { control: *control, target: *target },
+            RvmOp::Xor { target, operand } => RvmOp::Xor { target: *target, operand: operand.clone() },
+            RvmOp::Swap { a, b } => RvmOp::Swap { a: *a, b: *b },
+            RvmOp::If { condition, then_branch, else_branch } => RvmOp::If {
+                condition: condition.clone(),
+                then_branch: then_branch.iter().rev().map(RvmOp::inverse).collect(),
+                else_branch: else_branch.iter().rev().map(RvmOp::inverse).collect(),
+            },
+        }
+    }
+}
+
+fn execute_op(op: &RvmOp, registers: &mut [Value]) -> Result<()> {
+    match op {
+        RvmOp::Add { target, operand } => {
+            let rhs = eval_stage(operand, registers)?;
+            registers[*target as usize] = registers[*target as usize].add(&rhs)?;
+        }
+        RvmOp::Sub { target, operand } => {
+            let rhs = eval_stage(operand, registers)?;
+            registers[*target as usize] =
+                gates::subtract(&[registers[*target as usize].clone(), rhs])?;
+        }
+        RvmOp::Inc { target } => {
+            registers[*target as usize] =
+                gates::increment(std::slice::from_ref(&registers[*target as usize]))?;
+        }
+        RvmOp::Dec { target } => {
+            registers[*target as usize] =
+                gates::decrement(std::slice::from_ref(&registers[*target as usize]))?;
+        }
+        RvmOp::Cnot { control, target } => {
+            registers[*target as usize] = gates::cnot(&[
+                registers[*control as usize].clone(),
+                registers[*target as usize].clone(),
+            ])?;
+        }
+        RvmOp::Xor { target, operand } => {
+            let rhs = eval_stage(operand, registers)?;
+            registers[*target as usize] =
+                gates::xor(&[registers[*target as usize].clone(), rhs])?;
+        }
+        RvmOp::Swap { a, b } => {
+            let swapped =
+                gates::swap(&[registers[*a as usize].clone(), registers[*b as usize].clone()])?;
+            match swapped {
+                Value::Tuple(mut pair) if pair.len() == 2 => {
+                    registers[*b as usize] = pair.remove(0);
+                    registers[*a as usize] = pair.remove(0);
+                }
+                other => {
+                    return Err(JtvError::RuntimeError(format!(
+                        "gates::swap returned an unexpected shape: {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+        RvmOp::If { condition, then_branch, else_branch } => {
+            let branch = if eval_cond(condition, registers)? { then_branch } else { else_branch };
+            for inner in branch {
+                execute_op(inner, registers)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A compiled reversible block: a fixed-size register file plus a flat,
+/// linear instruction stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmCode {
+    pub register_count: u32,
+    /// Maps each variable named anywhere in the block to the register it
+    /// was assigned, so callers can seed/read registers by name.
+    pub var_index: HashMap<String, u32>,
+    pub ops: Vec<RvmOp>,
+}
+
+/// Run `code` forward or backward over `registers` (which must have at
+/// least `code.register_count` slots, seeded by the caller via
+/// `code.var_index`). Forward executes `ops` in order; backward walks `ops`
+/// in reverse, dispatching each opcode's `inverse()` instead of itself.
+pub fn run(code: &VmCode, registers: &mut [Value], direction: Direction) -> Result<()> {
+    match direction {
+        Direction::Forward => {
+            for op in &code.ops {
+                execute_op(op, registers)?;
+            }
+        }
+        Direction::Backward => {
+            for op in code.ops.iter().rev() {
+                execute_op(&op.inverse(), registers)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Compiler {
+    vars: HashMap<String, u32>,
+}
+
+impl Compiler {
+    fn reg(&mut self, name: &str) -> u32 {
+        let next = self.vars.len() as u32;
+        *self.vars.entry(name.to_string()).or_insert(next)
+    }
+
+    fn compile_stmts(&mut self, stmts: &[ReversibleStmt]) -> Result<Vec<RvmOp>> {
+        stmts.iter().map(|s| self.compile_stmt(s)).collect()
+    }
+
+    fn compile_stmt(&mut self, stmt: &ReversibleStmt) -> Result<RvmOp> {
+        match stmt {
+            ReversibleStmt::AddAssign(target, expr) => {
+                let operand = self.compile_stage(expr, Some(target))?;
+                Ok(RvmOp::Add { target: self.reg(target), operand })
+            }
+            ReversibleStmt::SubAssign(target, expr) => {
+                let operand = self.compile_stage(expr, Some(target))?;
+                Ok(RvmOp::Sub { target: self.reg(target), operand })
+            }
+            ReversibleStmt::If(if_stmt) => self.compile_if(if_stmt),
+            ReversibleStmt::MulAssign(..) => Err(JtvError::RuntimeError(
+                "cannot compile a reversible mul-assign for the bytecode VM yet: only \
+                 AddAssign/SubAssign/If have a defined `RvmOp`, run it through \
+                 `ReversibleInterpreter` instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::DivAssign(..) => Err(JtvError::RuntimeError(
+                "cannot compile a reversible div-assign for the bytecode VM yet: only \
+                 AddAssign/SubAssign/If have a defined `RvmOp`, run it through \
+                 `ReversibleInterpreter` instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::For { .. } => Err(JtvError::RuntimeError(
+                "cannot compile a reversible for loop for the bytecode VM yet: only \
+                 AddAssign/SubAssign/If have a defined `RvmOp`, run it through \
+                 `ReversibleInterpreter` instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::Switch { .. } => Err(JtvError::RuntimeError(
+                "cannot compile a reversible switch for the bytecode VM yet: only \
+                 AddAssign/SubAssign/If have a defined `RvmOp`, run it through \
+                 `ReversibleInterpreter` instead"
+                    .to_string(),
+            )),
+            ReversibleStmt::Assign(..) => Err(JtvError::RuntimeError(
+                "cannot compile a general reversible assignment for the bytecode VM yet: it has \
+                 no algebraic inverse, so there's no `RvmOp` for it -- only AddAssign/SubAssign/If \
+                 have a defined `RvmOp`, run it through `ReversibleInterpreter` instead, which \
+                 reverses it Bennett-style via `RecordedOp::Store`"
+                    .to_string(),
+            )),
+        }
+    }
+
+    fn compile_if(&mut self, if_stmt: &IfStmt) -> Result<RvmOp> {
+        // Janus invariant: the condition is re-evaluated unchanged on the
+        // way back, so it must not depend on anything either branch wrote.
+        let mut written = HashSet::new();
+        written_vars(&if_stmt.then_branch, &mut written);
+        if let Some(else_branch) = &if_stmt.else_branch {
+            written_vars(else_branch, &mut written);
+        }
+        if let Some(culprit) = written
+            .iter()
+            .find(|var| control_expr_contains_var(&if_stmt.condition, var))
+        {
+            return Err(JtvError::RuntimeError(format!(
+                "reversible if condition reads '{}', which is written inside a branch: \
+                 the entry test and exit assertion must agree, so the condition must not \
+                 depend on anything the branches assign",
+                culprit
+            )));
+        }
+
+        let condition = self.compile_cond(&if_stmt.condition)?;
+        let then_branch = self.compile_branch(&if_stmt.then_branch)?;
+        let else_branch = match &if_stmt.else_branch {
+            Some(branch) => self.compile_branch(branch)?,
+            None => Vec::new(),
+        };
+        Ok(RvmOp::If { condition, then_branch, else_branch })
+    }
+
+    fn compile_branch(&mut self, stmts: &[ControlStmt]) -> Result<Vec<RvmOp>> {
+        let mut ops = Vec::new();
+        for stmt in stmts {
+            match stmt {
+                ControlStmt::ReverseBlock(block) => ops.extend(self.compile_stmts(&block.body)?),
+                other => {
+                    return Err(JtvError::RuntimeError(format!(
+                        "cannot compile {:?} inside a reversible if branch for the bytecode VM: \
+                         only nested reverse blocks have a defined inverse there",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(ops)
+    }
+
+    fn compile_cond(&mut self, expr: &ControlExpr) -> Result<CondOp> {
+        match expr {
+            ControlExpr::Comparison(left, op, right) => Ok(CondOp::Compare(
+                self.compile_stage(left, None)?,
+                op.clone(),
+                self.compile_stage(right, None)?,
+            )),
+            ControlExpr::Logical(left, LogicalOp::And, right) => {
+                Ok(CondOp::And(Box::new(self.compile_cond(left)?), Box::new(self.compile_cond(right)?)))
+            }
+            ControlExpr::Logical(left, LogicalOp::Or, right) => {
+                Ok(CondOp::Or(Box::new(self.compile_cond(left)?), Box::new(self.compile_cond(right)?)))
+            }
+            ControlExpr::Not(inner) => Ok(CondOp::Not(Box::new(self.compile_cond(inner)?))),
+            ControlExpr::Data(data) => {
+                // A bare data expression used as a condition is "truthy" --
+                // model it as `data != 0` so it lowers to the same Compare op.
+                Ok(CondOp::Compare(
+                    self.compile_stage(data, None)?,
+                    Comparator::Ne,
+                    vec![StageOp::Push(Value::Int(0))],
+                ))
+            }
+            ControlExpr::Contains(..) => Err(JtvError::RuntimeError(
+                "the `in` membership test has no `CondOp` lowering yet -- only comparisons, \
+                 logical combinations of them, and bare data conditions compile for this VM"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Compile a `DataExpr` operand into a stage program, rejecting
+    /// anything that would break reversibility or that this VM doesn't
+    /// (yet) lower -- the same restricted subset
+    /// `ReversibleInterpreter::eval_data_expr` accepts. `own_target`, when
+    /// set, is the variable this stage feeds into; an `Identifier` reading
+    /// it is rejected the same way `check_reversibility` rejects `x += x`.
+    fn compile_stage(&mut self, expr: &DataExpr, own_target: Option<&str>) -> Result<Vec<StageOp>> {
+        let mut stage = Vec::new();
+        self.compile_stage_into(expr, own_target, &mut stage)?;
+        Ok(stage)
+    }
+
+    fn compile_stage_into(
+        &mut self,
+        expr: &DataExpr,
+        own_target: Option<&str>,
+        stage: &mut Vec<StageOp>,
+    ) -> Result<()> {
+        match expr {
+            DataExpr::Number(num) => {
+                stage.push(StageOp::Push(Value::from_number(num)?));
+                Ok(())
+            }
+            DataExpr::Identifier(name) => {
+                if own_target == Some(name.as_str()) {
+                    return Err(JtvError::RuntimeError(format!(
+                        "Variable '{}' cannot appear in its own reversible assignment \
+                         (breaks reversibility)",
+                        name
+                    )));
+                }
+                stage.push(StageOp::PushReg(self.reg(name)));
+                Ok(())
+            }
+            DataExpr::Add(left, right) => {
+                self.compile_stage_into(left, own_target, stage)?;
+                self.compile_stage_into(right, own_target, stage)?;
+                stage.push(StageOp::Add);
+                Ok(())
+            }
+            DataExpr::Negate(inner) => {
+                self.compile_stage_into(inner, own_target, stage)?;
+                stage.push(StageOp::Neg);
+                Ok(())
+            }
+            other => Err(JtvError::RuntimeError(format!(
+                "{:?} is not supported in a reversible VM operand: only numbers, identifiers, \
+                 addition and negation are",
+                other
+            ))),
+        }
+    }
+}
+
+/// Compile a `ReverseBlock` into a `VmCode`. Every variable named anywhere
+/// in the block (read or written) is assigned a stable register slot in
+/// `VmCode::var_index`.
+pub fn compile_reverse_block(block: &ReverseBlock) -> Result<VmCode> {
+    let mut compiler = Compiler::default();
+    let ops = compiler.compile_stmts(&block.body)?;
+    Ok(VmCode { register_count: compiler.vars.len() as u32, var_index: compiler.vars, ops })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_forward(block: &ReverseBlock, initial: &[(&str, Value)]) -> (VmCode, Vec<Value>) {
+        let code = compile_reverse_block(block).unwrap();
+        let mut registers = vec![Value::Int(0); code.register_count as usize];
+        for (name, value) in initial {
+            registers[code.var_index[*name] as usize] = value.clone();
+        }
+        run(&code, &mut registers, Direction::Forward).unwrap();
+        (code, registers)
+    }
+
+    #[test]
+    fn test_add_assign_runs_forward() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::AddAssign(
+                "x".to_string(),
+                DataExpr::Number(Number::Int(5)),
+            )],
+        };
+        let (code, registers) = run_forward(&block, &[("x", Value::Int(10))]);
+        assert_eq!(registers[code.var_index["x"] as usize], Value::Int(15));
+    }
+
+    #[test]
+    fn test_backward_run_is_exact_inverse_of_forward() {
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::SubAssign("y".to_string(), DataExpr::Number(Number::Int(3))),
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Identifier("y".to_string())),
+            ],
+        };
+        let code = compile_reverse_block(&block).unwrap();
+        let mut registers = vec![Value::Int(0); code.register_count as usize];
+        registers[code.var_index["x"] as usize] = Value::Int(10);
+        registers[code.var_index["y"] as usize] = Value::Int(20);
+        let initial = registers.clone();
+
+        run(&code, &mut registers, Direction::Forward).unwrap();
+        assert_ne!(registers, initial);
+
+        run(&code, &mut registers, Direction::Backward).unwrap();
+        assert_eq!(registers, initial);
+    }
+
+    #[test]
+    fn test_inc_dec_are_inverses() {
+        let block = ReverseBlock { body: vec![] };
+        let code = compile_reverse_block(&block).unwrap();
+        let inc = RvmOp::Inc { target: 0 };
+        assert_eq!(inc.inverse(), RvmOp::Dec { target: 0 });
+        assert_eq!(inc.inverse().inverse(), inc);
+        let _ = code;
+    }
+
+    #[test]
+    fn test_cnot_xor_swap_are_self_inverse() {
+        assert_eq!(
+            (RvmOp::Cnot { control: 0, target: 1 }).inverse(),
+            RvmOp::Cnot { control: 0, target: 1 }
+        );
+        assert_eq!(
+            (RvmOp::Xor { target: 0, operand: vec![StageOp::Push(Value::Int(1))] }).inverse(),
+            RvmOp::Xor { target: 0, operand: vec![StageOp::Push(Value::Int(1))] }
+        );
+        assert_eq!((RvmOp::Swap { a: 0, b: 1 }).inverse(), RvmOp::Swap { a: 0, b: 1 });
+    }
+
+    #[test]
+    fn test_self_assignment_is_rejected() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::AddAssign(
+                "x".to_string(),
+                DataExpr::Identifier("x".to_string()),
+            )],
+        };
+        assert!(compile_reverse_block(&block).is_err());
+    }
+
+    #[test]
+    fn test_if_round_trips_through_the_vm() {
+        // Mirrors reversible::tests::sample_block_with_if, lowered to the VM.
+        let block = ReverseBlock {
+            body: vec![
+                ReversibleStmt::AddAssign("x".to_string(), DataExpr::Number(Number::Int(5))),
+                ReversibleStmt::If(IfStmt {
+                    condition: ControlExpr::Comparison(
+                        Box::new(DataExpr::Identifier("x".to_string())),
+                        Comparator::Gt,
+                        Box::new(DataExpr::Number(Number::Int(0))),
+                    ),
+                    then_branch: vec![ControlStmt::ReverseBlock(ReverseBlock {
+                        body: vec![ReversibleStmt::AddAssign(
+                            "y".to_string(),
+                            DataExpr::Number(Number::Int(1)),
+                        )],
+                    })],
+                    else_branch: Some(vec![ControlStmt::ReverseBlock(ReverseBlock {
+                        body: vec![ReversibleStmt::SubAssign(
+                            "y".to_string(),
+                            DataExpr::Number(Number::Int(1)),
+                        )],
+                    })]),
+                }),
+            ],
+        };
+
+        let code = compile_reverse_block(&block).unwrap();
+        let mut registers = vec![Value::Int(0); code.register_count as usize];
+        registers[code.var_index["x"] as usize] = Value::Int(10);
+        registers[code.var_index["y"] as usize] = Value::Int(0);
+        let initial = registers.clone();
+
+        run(&code, &mut registers, Direction::Forward).unwrap();
+        assert_eq!(registers[code.var_index["x"] as usize], Value::Int(15));
+        assert_eq!(registers[code.var_index["y"] as usize], Value::Int(1));
+
+        run(&code, &mut registers, Direction::Backward).unwrap();
+        assert_eq!(registers, initial);
+    }
+
+    #[test]
+    fn test_if_condition_using_contains_is_rejected() {
+        // `in` has no `CondOp` lowering yet -- see `compile_cond`.
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::If(IfStmt {
+                condition: ControlExpr::Contains(
+                    Box::new(DataExpr::Identifier("x".to_string())),
+                    Box::new(DataExpr::List(vec![DataExpr::Number(Number::Int(1))])),
+                ),
+                then_branch: vec![ControlStmt::ReverseBlock(ReverseBlock {
+                    body: vec![ReversibleStmt::AddAssign(
+                        "y".to_string(),
+                        DataExpr::Number(Number::Int(1)),
+                    )],
+                })],
+                else_branch: None,
+            })],
+        };
+        assert!(compile_reverse_block(&block).is_err());
+    }
+
+    #[test]
+    fn test_if_condition_reading_branch_written_var_is_rejected() {
+        let block = ReverseBlock {
+            body: vec![ReversibleStmt::If(IfStmt {
+                condition: ControlExpr::Comparison(
+                    Box::new(DataExpr::Identifier("y".to_string())),
+                    Comparator::Gt,
+                    Box::new(DataExpr::Number(Number::Int(0))),
+                ),
+                then_branch: vec![ControlStmt::ReverseBlock(ReverseBlock {
+                    body: vec![ReversibleStmt::AddAssign(
+                        "y".to_string(),
+                        DataExpr::Number(Number::Int(1)),
+                    )],
+                })],
+                else_branch: None,
+            })],
+        };
+        match compile_reverse_block(&block) {
+            Err(JtvError::RuntimeError(msg)) => assert!(msg.contains('y')),
+            other => panic!("expected a RuntimeError naming the offending variable, got {:?}", other),
+        }
+    }
+}
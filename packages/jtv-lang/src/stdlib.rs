@@ -3,42 +3,131 @@
 //
 // Julia the Viper - Standard Library
 
-use crate::number::Value;
+use crate::number::{promote, Value};
 use crate::error::{JtvError, Result};
+use crate::iterator::ValueIter;
+use crate::pvector::PVector;
 use std::collections::HashMap;
-use num_traits::Signed;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 
 /// Built-in function signature
 pub type BuiltinFn = fn(&[Value]) -> Result<Value>;
 
+/// How many arguments a builtin accepts. Most take a fixed count, but a
+/// handful -- `max`, `min`, `gcd`, `lcm`, `concat` -- fold over any number
+/// of arguments, so a plain `usize` can't describe every builtin's arity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+    Range(usize, usize),
+    Variadic,
+}
+
+impl Arity {
+    fn accepts(&self, got: usize) -> bool {
+        match self {
+            Arity::Exact(n) => got == *n,
+            Arity::AtLeast(n) => got >= *n,
+            Arity::Range(lo, hi) => got >= *lo && got <= *hi,
+            Arity::Variadic => true,
+        }
+    }
+
+    /// Fewest arguments this arity can ever be satisfied by. A call with
+    /// fewer than this is an under-application (see `StdLib::call`'s
+    /// `Value::PartialApp` path), not a hard `ArityMismatch`.
+    fn min_required(&self) -> usize {
+        match self {
+            Arity::Exact(n) | Arity::AtLeast(n) => *n,
+            Arity::Range(lo, _) => *lo,
+            Arity::Variadic => 0,
+        }
+    }
+
+    fn check(&self, name: &str, got: usize) -> Result<()> {
+        if self.accepts(got) {
+            return Ok(());
+        }
+        match self {
+            Arity::Exact(n) => Err(JtvError::ArityMismatch { expected: *n, got }),
+            Arity::AtLeast(n) => Err(JtvError::RuntimeError(format!(
+                "{} requires at least {} argument(s), got {}",
+                name, n, got
+            ))),
+            Arity::Range(lo, hi) => Err(JtvError::RuntimeError(format!(
+                "{} requires between {} and {} arguments, got {}",
+                name, lo, hi, got
+            ))),
+            Arity::Variadic => unreachable!("Variadic.accepts always returns true"),
+        }
+    }
+}
+
+/// A builtin that may need to call back into a JtV function value while it
+/// runs, rather than only operate on plain data -- either because it was
+/// handed one directly (`map`, `filter`, `foldl`, ...) or because forcing a
+/// lazy `Value::Iterator` can invoke one internally (a `takeWhile`
+/// predicate, say, when `collect`/`length`/`sum`/`product` drives the
+/// pipeline to completion). `apply` is supplied by whatever is driving the
+/// call (the interpreter, in practice) since only the caller knows how to
+/// invoke a `Closure` -- `StdLib` itself has no notion of user functions or
+/// a call stack.
+pub type HofFn = fn(&[Value], &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value>;
+
 /// Standard library registry
 pub struct StdLib {
-    functions: HashMap<String, (BuiltinFn, usize)>,  // (function, arity)
+    functions: HashMap<String, (BuiltinFn, Arity)>,
+    hof_functions: HashMap<String, (HofFn, usize)>,
 }
 
 impl StdLib {
     pub fn new() -> Self {
         let mut lib = StdLib {
             functions: HashMap::new(),
+            hof_functions: HashMap::new(),
         };
         lib.register_prelude();
         lib.register_math();
         lib.register_collections();
+        lib.register_higher_order();
+        lib.register_linalg();
+        lib.register_testing();
+        lib.register_bitwise();
         lib
     }
 
-    pub fn get(&self, name: &str) -> Option<&(BuiltinFn, usize)> {
+    pub fn get(&self, name: &str) -> Option<&(BuiltinFn, Arity)> {
         self.functions.get(name)
     }
 
+    /// Every registered builtin name, including higher-order ones -- for a
+    /// caller that wants to offer them as completion candidates rather than
+    /// look one up to call it.
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str).chain(self.hof_functions.keys().map(String::as_str))
+    }
+
+    /// Calls a registered builtin, or -- mirroring `complexpr`'s
+    /// `Func::Partial` -- curries it: a call with fewer arguments than its
+    /// declared arity requires doesn't raise `ArityMismatch`, it returns a
+    /// `Value::PartialApp` that remembers the builtin's name and the
+    /// arguments collected so far. Calling `StdLib::call` again with that
+    /// `PartialApp`'s name and `collected ++ more_args` (see
+    /// `Interpreter::eval_function_call`/`call_hof`) either completes the
+    /// call or yields a further partial application; supplying more
+    /// arguments than the arity allows still reports a clear
+    /// `ArityMismatch`/range error, same as before.
     pub fn call(&self, name: &str, args: &[Value]) -> Result<Value> {
         if let Some((func, arity)) = self.functions.get(name) {
-            if args.len() != *arity {
-                return Err(JtvError::ArityMismatch {
-                    expected: *arity,
-                    got: args.len(),
+            if args.len() < arity.min_required() {
+                return Ok(Value::PartialApp {
+                    name: name.to_string(),
+                    collected: args.to_vec(),
                 });
             }
+            arity.check(name, args.len())?;
             func(args)
         } else {
             Err(JtvError::UndefinedFunction(name.to_string()))
@@ -49,51 +138,137 @@ impl StdLib {
         self.functions.contains_key(name)
     }
 
+    pub fn has_hof(&self, name: &str) -> bool {
+        self.hof_functions.contains_key(name)
+    }
+
+    pub fn get_hof(&self, name: &str) -> Option<&(HofFn, usize)> {
+        self.hof_functions.get(name)
+    }
+
+    /// Calls a registered higher-order builtin, handing it `apply` so it can
+    /// invoke whichever `Value::Closure`/`Value::Builtin` argument it was
+    /// passed once per element (`map`), short-circuit on the first match
+    /// (`any`), accumulate (`foldl`), and so on.
+    pub fn call_hof(
+        &self,
+        name: &str,
+        args: &[Value],
+        apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>,
+    ) -> Result<Value> {
+        if let Some((func, arity)) = self.hof_functions.get(name) {
+            if args.len() != *arity {
+                return Err(JtvError::ArityMismatch {
+                    expected: *arity,
+                    got: args.len(),
+                });
+            }
+            func(args, apply)
+        } else {
+            Err(JtvError::UndefinedFunction(name.to_string()))
+        }
+    }
+
     pub fn list_functions(&self) -> Vec<&String> {
         self.functions.keys().collect()
     }
 
-    fn register(&mut self, name: &str, func: BuiltinFn, arity: usize) {
+    fn register(&mut self, name: &str, func: BuiltinFn, arity: Arity) {
         self.functions.insert(name.to_string(), (func, arity));
     }
 
+    fn register_hof(&mut self, name: &str, func: HofFn, arity: usize) {
+        self.hof_functions.insert(name.to_string(), (func, arity));
+    }
+
     // ===== std.prelude (auto-imported) =====
     fn register_prelude(&mut self) {
-        self.register("abs", stdlib_abs, 1);
-        self.register("max", stdlib_max, 2);
-        self.register("min", stdlib_min, 2);
-        self.register("sign", stdlib_sign, 1);
-        self.register("clamp", stdlib_clamp, 3);
-        self.register("floor", stdlib_floor, 1);
-        self.register("ceil", stdlib_ceil, 1);
-        self.register("round", stdlib_round, 1);
+        self.register("abs", stdlib_abs, Arity::Exact(1));
+        self.register("max", stdlib_max, Arity::AtLeast(1));
+        self.register("min", stdlib_min, Arity::AtLeast(1));
+        self.register("sign", stdlib_sign, Arity::Exact(1));
+        self.register("clamp", stdlib_clamp, Arity::Exact(3));
+        self.register("floor", stdlib_floor, Arity::Exact(1));
+        self.register("ceil", stdlib_ceil, Arity::Exact(1));
+        self.register("round", stdlib_round, Arity::Exact(1));
     }
 
     // ===== std.math =====
     fn register_math(&mut self) {
-        self.register("gcd", stdlib_gcd, 2);
-        self.register("lcm", stdlib_lcm, 2);
-        self.register("factorial", stdlib_factorial, 1);
-        self.register("isPrime", stdlib_is_prime, 1);
-        self.register("pow", stdlib_pow, 2);
-        self.register("sqrt", stdlib_sqrt, 1);
-        self.register("mod", stdlib_mod, 2);
+        self.register("gcd", stdlib_gcd, Arity::AtLeast(1));
+        self.register("lcm", stdlib_lcm, Arity::AtLeast(1));
+        self.register("factorial", stdlib_factorial, Arity::Exact(1));
+        self.register("isPrime", stdlib_is_prime, Arity::Exact(1));
+        self.register("pow", stdlib_pow, Arity::Exact(2));
+        self.register("sqrt", stdlib_sqrt, Arity::Exact(1));
+        self.register("mod", stdlib_mod, Arity::Exact(2));
+        self.register("modEuclid", stdlib_mod_euclid, Arity::Exact(2));
+        self.register("mulMod", stdlib_mul_mod, Arity::Exact(3));
+        self.register("powMod", stdlib_pow_mod, Arity::Exact(3));
+        self.register("modInv", stdlib_mod_inv, Arity::Exact(2));
     }
 
     // ===== std.collections =====
     fn register_collections(&mut self) {
-        self.register("length", stdlib_length, 1);
-        self.register("sum", stdlib_sum, 1);
-        self.register("product", stdlib_product, 1);
-        self.register("head", stdlib_head, 1);
-        self.register("tail", stdlib_tail, 1);
-        self.register("last", stdlib_last, 1);
-        self.register("init", stdlib_init, 1);
-        self.register("reverse", stdlib_reverse, 1);
-        self.register("range", stdlib_range, 2);
-        self.register("concat", stdlib_concat, 2);
-        self.register("contains", stdlib_contains, 2);
-        self.register("at", stdlib_at, 2);
+        self.register("head", stdlib_head, Arity::Exact(1));
+        self.register("tail", stdlib_tail, Arity::Exact(1));
+        self.register("last", stdlib_last, Arity::Exact(1));
+        self.register("init", stdlib_init, Arity::Exact(1));
+        self.register("reverse", stdlib_reverse, Arity::Exact(1));
+        self.register("range", stdlib_range, Arity::Exact(2));
+        self.register("concat", stdlib_concat, Arity::AtLeast(1));
+        self.register("contains", stdlib_contains, Arity::Exact(2));
+        self.register("at", stdlib_at, Arity::Exact(2));
+        self.register("take", stdlib_take, Arity::Exact(2));
+        self.register("drop", stdlib_drop, Arity::Exact(2));
+        self.register("takeWhile", stdlib_take_while, Arity::Exact(2));
+        self.register("enumerate", stdlib_enumerate, Arity::Exact(1));
+        self.register("chain", stdlib_chain, Arity::Exact(2));
+    }
+
+    // ===== std.testing =====
+    fn register_testing(&mut self) {
+        self.register("assert", stdlib_assert, Arity::Range(1, 2));
+    }
+
+    // ===== std.higher_order =====
+    fn register_higher_order(&mut self) {
+        self.register_hof("map", stdlib_map, 2);
+        self.register_hof("filter", stdlib_filter, 2);
+        self.register_hof("foldl", stdlib_foldl, 3);
+        self.register_hof("foldr", stdlib_foldr, 3);
+        self.register_hof("zip", stdlib_zip, 2);
+        self.register_hof("zipWith", stdlib_zip_with, 3);
+        self.register_hof("all", stdlib_all, 2);
+        self.register_hof("any", stdlib_any, 2);
+        // These aren't higher-order in the usual sense (no function value
+        // is handed to the caller), but forcing a lazy `Value::Iterator`
+        // can invoke one internally (a `takeWhile` predicate), so they need
+        // the same `apply` plumbing as the rest of this registry.
+        self.register_hof("length", stdlib_length, 1);
+        self.register_hof("sum", stdlib_sum, 1);
+        self.register_hof("product", stdlib_product, 1);
+        self.register_hof("collect", stdlib_collect, 1);
+    }
+
+    // ===== std.linalg =====
+    fn register_linalg(&mut self) {
+        self.register("matMul", stdlib_mat_mul, Arity::Exact(2));
+        self.register("matPow", stdlib_mat_pow, Arity::Exact(2));
+        self.register("identity", stdlib_identity, Arity::Exact(1));
+        self.register("transpose", stdlib_transpose, Arity::Exact(1));
+    }
+
+    // ===== std.bitwise =====
+    fn register_bitwise(&mut self) {
+        self.register("bitAnd", stdlib_bit_and, Arity::Exact(2));
+        self.register("bitOr", stdlib_bit_or, Arity::Exact(2));
+        self.register("bitXor", stdlib_bit_xor, Arity::Exact(2));
+        self.register("bitNot", stdlib_bit_not, Arity::Exact(1));
+        self.register("shiftLeft", stdlib_shift_left, Arity::Exact(2));
+        self.register("shiftRight", stdlib_shift_right, Arity::Exact(2));
+        self.register("popcount", stdlib_popcount, Arity::Exact(1));
+        self.register("bitLength", stdlib_bit_length, Arity::Exact(1));
     }
 }
 
@@ -108,6 +283,7 @@ impl Default for StdLib {
 fn stdlib_abs(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Int(n) => Ok(Value::Int(n.abs())),
+        Value::BigInt(n) => Ok(normalize_bigint(n.abs())),
         Value::Float(f) => Ok(Value::Float(f.abs())),
         Value::Rational(r) => Ok(Value::Rational(r.abs())),
         _ => Err(JtvError::TypeError("abs requires a numeric argument".to_string())),
@@ -115,7 +291,11 @@ fn stdlib_abs(args: &[Value]) -> Result<Value> {
 }
 
 fn stdlib_max(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
+    args[1..].iter().try_fold(args[0].clone(), |acc, v| binary_max(&acc, v))
+}
+
+fn binary_max(a: &Value, b: &Value) -> Result<Value> {
+    match (a, b) {
         (Value::Int(a), Value::Int(b)) => Ok(Value::Int(*a.max(b))),
         (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.max(*b))),
         (Value::Int(a), Value::Float(b)) => Ok(Value::Float((*a as f64).max(*b))),
@@ -125,7 +305,11 @@ fn stdlib_max(args: &[Value]) -> Result<Value> {
 }
 
 fn stdlib_min(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
+    args[1..].iter().try_fold(args[0].clone(), |acc, v| binary_min(&acc, v))
+}
+
+fn binary_min(a: &Value, b: &Value) -> Result<Value> {
+    match (a, b) {
         (Value::Int(a), Value::Int(b)) => Ok(Value::Int(*a.min(b))),
         (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.min(*b))),
         (Value::Int(a), Value::Float(b)) => Ok(Value::Float((*a as f64).min(*b))),
@@ -137,6 +321,13 @@ fn stdlib_min(args: &[Value]) -> Result<Value> {
 fn stdlib_sign(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Int(n) => Ok(Value::Int(n.signum())),
+        Value::BigInt(n) => Ok(Value::Int(if n.is_zero() {
+            0
+        } else if n.is_negative() {
+            -1
+        } else {
+            1
+        })),
         Value::Float(f) => {
             if *f > 0.0 { Ok(Value::Int(1)) }
             else if *f < 0.0 { Ok(Value::Int(-1)) }
@@ -184,37 +375,83 @@ fn stdlib_round(args: &[Value]) -> Result<Value> {
 
 // ===== Math Functions =====
 
+/// Demote a `BigInt` back to `Int` when it fits in an `i64`, mirroring the
+/// private `Value::normalize_bigint` in `number.rs` (not visible from here).
+fn normalize_bigint(n: BigInt) -> Value {
+    match n.to_i64() {
+        Some(i) => Value::Int(i),
+        None => Value::BigInt(n),
+    }
+}
+
+fn binary_gcd(a: i64, b: i64) -> i64 {
+    let mut a = a.abs();
+    let mut b = b.abs();
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a
+}
+
+fn gcd_bigint(a: BigInt, b: BigInt) -> BigInt {
+    let mut a = a.abs();
+    let mut b = b.abs();
+    while !b.is_zero() {
+        let t = b.clone();
+        b = &a % &b;
+        a = t;
+    }
+    a
+}
+
+/// `gcd`/`lcm`'s pairwise step, promoted to `BigInt` (via `promote`) if
+/// either operand already is one.
+fn binary_gcd_value(a: &Value, b: &Value, fn_name: &str) -> Result<Value> {
+    match promote(a.clone(), b.clone()) {
+        (Value::Int(x), Value::Int(y)) => Ok(Value::Int(binary_gcd(x, y))),
+        (Value::BigInt(x), Value::BigInt(y)) => Ok(normalize_bigint(gcd_bigint(x, y))),
+        _ => Err(JtvError::TypeError(format!("{} requires integer arguments", fn_name))),
+    }
+}
+
 fn stdlib_gcd(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
-        (Value::Int(a), Value::Int(b)) => {
-            let mut a = a.abs();
-            let mut b = b.abs();
-            while b != 0 {
-                let t = b;
-                b = a % b;
-                a = t;
-            }
-            Ok(Value::Int(a))
-        }
-        _ => Err(JtvError::TypeError("gcd requires integer arguments".to_string())),
+    let mut result = as_integral(&args[0], "gcd")?;
+    for arg in &args[1..] {
+        result = binary_gcd_value(&result, arg, "gcd")?;
     }
+    Ok(result)
 }
 
 fn stdlib_lcm(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
-        (Value::Int(a), Value::Int(b)) => {
-            let gcd_result = stdlib_gcd(args)?;
-            if let Value::Int(g) = gcd_result {
-                if g == 0 {
-                    Ok(Value::Int(0))
+    let mut result = as_integral(&args[0], "lcm")?;
+    for arg in &args[1..] {
+        result = match promote(result, arg.clone()) {
+            (Value::Int(x), Value::Int(y)) => {
+                let g = binary_gcd(x, y);
+                Value::Int(if g == 0 { 0 } else { (x.abs() / g) * y.abs() })
+            }
+            (Value::BigInt(x), Value::BigInt(y)) => {
+                let g = gcd_bigint(x.clone(), y.clone());
+                if g.is_zero() {
+                    Value::Int(0)
                 } else {
-                    Ok(Value::Int((a.abs() / g) * b.abs()))
+                    normalize_bigint((x.abs() / &g) * y.abs())
                 }
-            } else {
-                unreachable!()
             }
-        }
-        _ => Err(JtvError::TypeError("lcm requires integer arguments".to_string())),
+            _ => return Err(JtvError::TypeError("lcm requires integer arguments".to_string())),
+        };
+    }
+    Ok(result)
+}
+
+/// Accepts `Int` or `BigInt` unchanged; everything else is a type error.
+/// Used by `gcd`/`lcm` so a single argument doesn't need a pairwise step.
+fn as_integral(value: &Value, fn_name: &str) -> Result<Value> {
+    match value {
+        Value::Int(_) | Value::BigInt(_) => Ok(value.clone()),
+        _ => Err(JtvError::TypeError(format!("{} requires integer arguments", fn_name))),
     }
 }
 
@@ -224,11 +461,11 @@ fn stdlib_factorial(args: &[Value]) -> Result<Value> {
             if *n < 0 {
                 return Err(JtvError::RuntimeError("factorial of negative number".to_string()));
             }
-            let mut result: i64 = 1;
+            let mut result = BigInt::from(1);
             for i in 2..=*n {
-                result = result.saturating_mul(i);
+                result *= BigInt::from(i);
             }
-            Ok(Value::Int(result))
+            Ok(normalize_bigint(result))
         }
         _ => Err(JtvError::TypeError("factorial requires an integer argument".to_string())),
     }
@@ -236,27 +473,140 @@ fn stdlib_factorial(args: &[Value]) -> Result<Value> {
 
 fn stdlib_is_prime(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::Int(n) => {
-            if *n <= 1 {
-                return Ok(Value::Bool(false));
-            }
-            if *n <= 3 {
-                return Ok(Value::Bool(true));
-            }
-            if n % 2 == 0 || n % 3 == 0 {
-                return Ok(Value::Bool(false));
+        Value::Int(n) => Ok(Value::Bool(is_prime_i64(*n))),
+        Value::BigInt(n) => Ok(Value::Bool(is_prime_bigint(n))),
+        _ => Err(JtvError::TypeError("isPrime requires an integer argument".to_string())),
+    }
+}
+
+/// Witnesses that make Miller-Rabin deterministic for every `n` below
+/// ~3.3e24 (see Pomerance/Selfridge/Wagstaff-style witness tables) --
+/// comfortably past `i64::MAX` and useful for `BigInt` inputs besides.
+const MILLER_RABIN_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+fn mod_pow_i128(base: i128, exp: i128, m: i128) -> i128 {
+    let mut result: i128 = 1 % m;
+    let mut base = base.rem_euclid(m);
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = (result * base).rem_euclid(m);
+        }
+        base = (base * base).rem_euclid(m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Miller-Rabin over `i128` (to hold the squared intermediates of an `i64`
+/// modulus without overflow), replacing the old trial-division loop which
+/// was impractically slow near `i64::MAX`.
+fn is_prime_i64(n: i64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n < 4 {
+        return true;
+    }
+    if n % 2 == 0 {
+        return false;
+    }
+    let n128 = n as i128;
+    let mut d = n128 - 1;
+    let mut r = 0u32;
+    while d % 2 == 0 {
+        d /= 2;
+        r += 1;
+    }
+    'witness: for &a in &MILLER_RABIN_WITNESSES {
+        let a = a as i128;
+        if a >= n128 {
+            continue;
+        }
+        let mut x = mod_pow_i128(a, d, n128);
+        if x == 1 || x == n128 - 1 {
+            continue;
+        }
+        for _ in 0..r.saturating_sub(1) {
+            x = (x * x).rem_euclid(n128);
+            if x == n128 - 1 {
+                continue 'witness;
             }
-            let mut i = 5i64;
-            while i * i <= *n {
-                if n % i == 0 || n % (i + 2) == 0 {
-                    return Ok(Value::Bool(false));
-                }
-                i += 6;
+        }
+        return false;
+    }
+    true
+}
+
+fn mod_pow_bigint(base: &BigInt, exp: &BigInt, m: &BigInt) -> BigInt {
+    let one = BigInt::from(1);
+    let two = BigInt::from(2);
+    let mut result = &one % m;
+    let mut base = base % m;
+    let mut exp = exp.clone();
+    while exp > BigInt::zero() {
+        if &exp % &two == one {
+            result = (&result * &base) % m;
+        }
+        base = (&base * &base) % m;
+        exp /= &two;
+    }
+    result
+}
+
+/// Same Miller-Rabin test as [`is_prime_i64`], carried out over `BigInt` so
+/// it also replaces the earlier trial-division path for big inputs.
+fn is_prime_bigint(n: &BigInt) -> bool {
+    let one = BigInt::from(1);
+    let two = BigInt::from(2);
+    if *n < two {
+        return false;
+    }
+    if *n == two || *n == BigInt::from(3) {
+        return true;
+    }
+    if (n % &two).is_zero() {
+        return false;
+    }
+    let n_minus_one = n - &one;
+    let mut d = n_minus_one.clone();
+    let mut r = 0u32;
+    while (&d % &two).is_zero() {
+        d /= &two;
+        r += 1;
+    }
+    'witness: for &a in &MILLER_RABIN_WITNESSES {
+        let a = BigInt::from(a);
+        if a >= *n {
+            continue;
+        }
+        let mut x = mod_pow_bigint(&a, &d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 0..r.saturating_sub(1) {
+            x = (&x * &x) % n;
+            if x == n_minus_one {
+                continue 'witness;
             }
-            Ok(Value::Bool(true))
         }
-        _ => Err(JtvError::TypeError("isPrime requires an integer argument".to_string())),
+        return false;
+    }
+    true
+}
+
+/// Exponentiation by squaring over `BigInt`, so `pow` promotes instead of
+/// saturating the way the old `i64`-only implementation did.
+fn bigint_pow(mut base: BigInt, mut exp: u32) -> BigInt {
+    let mut result = BigInt::from(1);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result *= &base;
+        }
+        base = &base * &base;
+        exp >>= 1;
     }
+    result
 }
 
 fn stdlib_pow(args: &[Value]) -> Result<Value> {
@@ -265,17 +615,13 @@ fn stdlib_pow(args: &[Value]) -> Result<Value> {
             if *exp < 0 {
                 return Err(JtvError::RuntimeError("negative exponent for integers".to_string()));
             }
-            let mut result: i64 = 1;
-            let mut base = *base;
-            let mut exp = *exp as u32;
-            while exp > 0 {
-                if exp & 1 == 1 {
-                    result = result.saturating_mul(base);
-                }
-                base = base.saturating_mul(base);
-                exp >>= 1;
+            Ok(normalize_bigint(bigint_pow(BigInt::from(*base), *exp as u32)))
+        }
+        (Value::BigInt(base), Value::Int(exp)) => {
+            if *exp < 0 {
+                return Err(JtvError::RuntimeError("negative exponent for integers".to_string()));
             }
-            Ok(Value::Int(result))
+            Ok(normalize_bigint(bigint_pow(base.clone(), *exp as u32)))
         }
         (Value::Float(base), Value::Int(exp)) => {
             Ok(Value::Float(base.powi(*exp as i32)))
@@ -306,55 +652,238 @@ fn stdlib_sqrt(args: &[Value]) -> Result<Value> {
 }
 
 fn stdlib_mod(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
+    match promote(args[0].clone(), args[1].clone()) {
         (Value::Int(a), Value::Int(b)) => {
-            if *b == 0 {
+            if b == 0 {
                 return Err(JtvError::RuntimeError("modulo by zero".to_string()));
             }
             Ok(Value::Int(a % b))
         }
+        (Value::BigInt(a), Value::BigInt(b)) => {
+            if b.is_zero() {
+                return Err(JtvError::RuntimeError("modulo by zero".to_string()));
+            }
+            Ok(normalize_bigint(a % b))
+        }
         _ => Err(JtvError::TypeError("mod requires integer arguments".to_string())),
     }
 }
 
+fn as_int(value: &Value, fn_name: &str) -> Result<i64> {
+    match value {
+        Value::Int(n) => Ok(*n),
+        _ => Err(JtvError::TypeError(format!("{} requires integer arguments", fn_name))),
+    }
+}
+
+/// `a.rem_euclid(m)`, i.e. `mod`'s Euclidean sibling: the result is always
+/// in `[0, |m|)`, which is what `modInv`'s Bezout coefficient is reduced
+/// with too, so the two stay consistent with each other.
+fn stdlib_mod_euclid(args: &[Value]) -> Result<Value> {
+    let a = as_int(&args[0], "modEuclid")?;
+    let m = as_int(&args[1], "modEuclid")?;
+    if m == 0 {
+        return Err(JtvError::RuntimeError("modulo by zero".to_string()));
+    }
+    Ok(Value::Int(a.rem_euclid(m)))
+}
+
+/// `(a * b) % m`, reducing in `i128` so the product can't overflow `i64`
+/// before the modulo brings it back down.
+fn stdlib_mul_mod(args: &[Value]) -> Result<Value> {
+    let a = as_int(&args[0], "mulMod")? as i128;
+    let b = as_int(&args[1], "mulMod")? as i128;
+    let m = as_int(&args[2], "mulMod")?;
+    if m == 0 {
+        return Err(JtvError::RuntimeError("modulo by zero".to_string()));
+    }
+    Ok(Value::Int((a * b).rem_euclid(m as i128) as i64))
+}
+
+/// `base^exp % m` via binary exponentiation, reducing modulo `m` after
+/// every multiply (in `i128`, to absorb the squared intermediate) instead
+/// of computing the full power first.
+fn stdlib_pow_mod(args: &[Value]) -> Result<Value> {
+    let base = as_int(&args[0], "powMod")?;
+    let exp = as_int(&args[1], "powMod")?;
+    let m = as_int(&args[2], "powMod")?;
+    if m == 0 {
+        return Err(JtvError::RuntimeError("modulo by zero".to_string()));
+    }
+    if exp < 0 {
+        return Err(JtvError::RuntimeError("negative exponent for powMod".to_string()));
+    }
+    Ok(Value::Int(mod_pow_i128(base as i128, exp as i128, m as i128) as i64))
+}
+
+/// Modular inverse of `a` mod `m` via the extended Euclidean algorithm:
+/// tracks `(old_r, r)`/`(old_s, s)` through the gcd loop and returns
+/// `old_s.rem_euclid(m)` once `gcd(a, m) == old_r.abs() == 1`.
+fn stdlib_mod_inv(args: &[Value]) -> Result<Value> {
+    let a = as_int(&args[0], "modInv")?;
+    let m = as_int(&args[1], "modInv")?;
+    if m == 0 {
+        return Err(JtvError::RuntimeError("modulo by zero".to_string()));
+    }
+    let (mut old_r, mut r) = (a, m);
+    let (mut old_s, mut s) = (1i64, 0i64);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r.abs() != 1 {
+        return Err(JtvError::RuntimeError(format!(
+            "modular inverse of {} does not exist mod {}",
+            a, m
+        )));
+    }
+    Ok(Value::Int(old_s.rem_euclid(m)))
+}
+
+// ===== Bitwise Functions =====
+
+/// Extracts the underlying `i64` of an `Int`/`Hex`/`Binary` value, the same
+/// three variants [`promote`]'s fixed-width-cousins comment groups together
+/// -- bitwise ops work on all three, unlike `as_int`, which only accepts
+/// plain `Int`.
+fn as_bits(value: &Value, fn_name: &str) -> Result<i64> {
+    match value {
+        Value::Int(n) | Value::Hex(n) | Value::Binary(n) => Ok(*n),
+        other => Err(JtvError::TypeError(format!(
+            "{} requires Int/Hex/Binary arguments, got {}",
+            fn_name, other
+        ))),
+    }
+}
+
+/// Which "display flavor" a bitwise result should come back as: `Hex` wins
+/// over `Binary` wins over plain `Int`, so `bitAnd(0xFF, 0x0F)` stays `Hex`,
+/// `bitOr(0b1010, 0b0101)` stays `Binary`, and a mixed Hex/Binary pair
+/// defaults to `Hex` -- matching neither operand losing its flavor to a
+/// plain `Int` if either one carries one.
+fn bitwise_ctor(a: &Value, b: &Value) -> fn(i64) -> Value {
+    match (a, b) {
+        (Value::Hex(_), _) | (_, Value::Hex(_)) => Value::Hex,
+        (Value::Binary(_), _) | (_, Value::Binary(_)) => Value::Binary,
+        _ => Value::Int,
+    }
+}
+
+fn stdlib_bit_and(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "bitAnd")?;
+    let b = as_bits(&args[1], "bitAnd")?;
+    Ok(bitwise_ctor(&args[0], &args[1])(a & b))
+}
+
+fn stdlib_bit_or(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "bitOr")?;
+    let b = as_bits(&args[1], "bitOr")?;
+    Ok(bitwise_ctor(&args[0], &args[1])(a | b))
+}
+
+fn stdlib_bit_xor(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "bitXor")?;
+    let b = as_bits(&args[1], "bitXor")?;
+    Ok(bitwise_ctor(&args[0], &args[1])(a ^ b))
+}
+
+fn stdlib_bit_not(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "bitNot")?;
+    Ok(bitwise_ctor(&args[0], &args[0])(!a))
+}
+
+fn stdlib_shift_left(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "shiftLeft")?;
+    let shift = as_bits(&args[1], "shiftLeft")?;
+    if !(0..64).contains(&shift) {
+        return Err(JtvError::RuntimeError(format!(
+            "shiftLeft amount must be in 0..64, got {}",
+            shift
+        )));
+    }
+    Ok(bitwise_ctor(&args[0], &args[1])(a << shift))
+}
+
+fn stdlib_shift_right(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "shiftRight")?;
+    let shift = as_bits(&args[1], "shiftRight")?;
+    if !(0..64).contains(&shift) {
+        return Err(JtvError::RuntimeError(format!(
+            "shiftRight amount must be in 0..64, got {}",
+            shift
+        )));
+    }
+    Ok(bitwise_ctor(&args[0], &args[1])(a >> shift))
+}
+
+fn stdlib_popcount(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "popcount")?;
+    Ok(Value::Int(a.count_ones() as i64))
+}
+
+/// Number of bits needed to represent `a`'s magnitude (`0` for `0`), i.e.
+/// `64 - leading_zeros` of its absolute value -- mirrors the other
+/// bit-counting helper, `popcount`, by also always returning a plain `Int`.
+fn stdlib_bit_length(args: &[Value]) -> Result<Value> {
+    let a = as_bits(&args[0], "bitLength")?;
+    Ok(Value::Int((64 - a.unsigned_abs().leading_zeros()) as i64))
+}
+
 // ===== Collection Functions =====
 
-fn stdlib_length(args: &[Value]) -> Result<Value> {
-    match &args[0] {
-        Value::List(items) => Ok(Value::Int(items.len() as i64)),
-        Value::Tuple(items) => Ok(Value::Int(items.len() as i64)),
-        _ => Err(JtvError::TypeError("length requires a list or tuple".to_string())),
+/// Views a `Value` as a `ValueIter` without materializing it: a `List` is
+/// wrapped as-is (its elements are already in memory, but no copy beyond
+/// the clone happens until something actually steps through it) and an
+/// `Iterator` is taken as-is.
+fn as_iter(value: &Value) -> Result<ValueIter> {
+    match value {
+        Value::List(items) => Ok(ValueIter::from_list(items.to_vec())),
+        Value::Iterator(iter) => Ok(iter.clone()),
+        other => Err(JtvError::TypeError(format!(
+            "expected a list or iterator, got {}",
+            other
+        ))),
     }
 }
 
-fn stdlib_sum(args: &[Value]) -> Result<Value> {
-    match &args[0] {
-        Value::List(items) => {
-            let mut result = Value::Int(0);
-            for item in items {
-                result = add_values(&result, item)?;
-            }
-            Ok(result)
-        }
-        _ => Err(JtvError::TypeError("sum requires a list".to_string())),
+fn stdlib_length(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    if let Value::Tuple(items) = &args[0] {
+        return Ok(Value::Int(items.len() as i64));
     }
+    let mut iter = as_iter(&args[0])?;
+    let mut count: i64 = 0;
+    while iter.step(apply)?.is_some() {
+        count += 1;
+    }
+    Ok(Value::Int(count))
 }
 
-fn stdlib_product(args: &[Value]) -> Result<Value> {
-    match &args[0] {
-        Value::List(items) => {
-            let mut result: i64 = 1;
-            for item in items {
-                if let Value::Int(n) = item {
-                    result = result.saturating_mul(*n);
-                } else {
-                    return Err(JtvError::TypeError("product requires a list of integers".to_string()));
-                }
-            }
-            Ok(Value::Int(result))
-        }
-        _ => Err(JtvError::TypeError("product requires a list".to_string())),
+fn stdlib_sum(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    let mut iter = as_iter(&args[0])?;
+    let mut result = Value::Int(0);
+    while let Some(item) = iter.step(apply)? {
+        result = add_values(&result, &item)?;
     }
+    Ok(result)
+}
+
+fn stdlib_product(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    let mut iter = as_iter(&args[0])?;
+    let mut result = Value::Int(1);
+    while let Some(item) = iter.step(apply)? {
+        result = multiply_values(&result, &item)?;
+    }
+    Ok(result)
+}
+
+fn stdlib_collect(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    let mut iter = as_iter(&args[0])?;
+    let mut items = Vec::new();
+    while let Some(item) = iter.step(apply)? {
+        items.push(item);
+    }
+    Ok(Value::List(PVector::from_vec(items)))
 }
 
 fn stdlib_head(args: &[Value]) -> Result<Value> {
@@ -374,7 +903,7 @@ fn stdlib_tail(args: &[Value]) -> Result<Value> {
             if items.is_empty() {
                 return Err(JtvError::RuntimeError("tail of empty list".to_string()));
             }
-            Ok(Value::List(items[1..].to_vec()))
+            Ok(Value::List(items.split_at(1).1))
         }
         _ => Err(JtvError::TypeError("tail requires a list".to_string())),
     }
@@ -397,7 +926,7 @@ fn stdlib_init(args: &[Value]) -> Result<Value> {
             if items.is_empty() {
                 return Err(JtvError::RuntimeError("init of empty list".to_string()));
             }
-            Ok(Value::List(items[..items.len()-1].to_vec()))
+            Ok(Value::List(items.split_at(items.len() - 1).0))
         }
         _ => Err(JtvError::TypeError("init requires a list".to_string())),
     }
@@ -405,34 +934,30 @@ fn stdlib_init(args: &[Value]) -> Result<Value> {
 
 fn stdlib_reverse(args: &[Value]) -> Result<Value> {
     match &args[0] {
-        Value::List(items) => {
-            let mut reversed = items.clone();
-            reversed.reverse();
-            Ok(Value::List(reversed))
-        }
+        Value::List(items) => Ok(Value::List(items.reverse())),
         _ => Err(JtvError::TypeError("reverse requires a list".to_string())),
     }
 }
 
+/// Produces a lazy `Value::Iterator` rather than an eagerly materialized
+/// `Value::List`, so `range(0, 1000000000) |> take(5)` only ever computes
+/// the 5 elements actually pulled. Call `collect` to force it into a list.
 fn stdlib_range(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
-        (Value::Int(start), Value::Int(end)) => {
-            let list: Vec<Value> = (*start..*end).map(Value::Int).collect();
-            Ok(Value::List(list))
-        }
+        (Value::Int(start), Value::Int(end)) => Ok(Value::Iterator(ValueIter::range(*start, *end, 1))),
         _ => Err(JtvError::TypeError("range requires integer arguments".to_string())),
     }
 }
 
 fn stdlib_concat(args: &[Value]) -> Result<Value> {
-    match (&args[0], &args[1]) {
-        (Value::List(a), Value::List(b)) => {
-            let mut result = a.clone();
-            result.extend(b.clone());
-            Ok(Value::List(result))
+    let mut result = PVector::new();
+    for arg in args {
+        match arg {
+            Value::List(items) => result = result.concat(items),
+            _ => return Err(JtvError::TypeError("concat requires lists".to_string())),
         }
-        _ => Err(JtvError::TypeError("concat requires two lists".to_string())),
     }
+    Ok(Value::List(result))
 }
 
 fn stdlib_contains(args: &[Value]) -> Result<Value> {
@@ -462,85 +987,840 @@ fn stdlib_at(args: &[Value]) -> Result<Value> {
     }
 }
 
-// Helper function
-fn add_values(a: &Value, b: &Value) -> Result<Value> {
-    match (a, b) {
-        (Value::Int(x), Value::Int(y)) => Ok(Value::Int(x + y)),
-        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
-        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(*x as f64 + y)),
-        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(x + *y as f64)),
-        _ => Err(JtvError::TypeError(format!("Cannot add {:?} and {:?}", a, b))),
+fn stdlib_take(args: &[Value]) -> Result<Value> {
+    match &args[1] {
+        Value::Int(n) if *n >= 0 => Ok(Value::Iterator(as_iter(&args[0])?.take(*n as usize))),
+        Value::Int(_) => Err(JtvError::TypeError("take requires a non-negative count".to_string())),
+        _ => Err(JtvError::TypeError("take requires an integer count".to_string())),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_abs() {
-        let lib = StdLib::new();
-        assert_eq!(lib.call("abs", &[Value::Int(-5)]).unwrap(), Value::Int(5));
-        assert_eq!(lib.call("abs", &[Value::Int(5)]).unwrap(), Value::Int(5));
+fn stdlib_drop(args: &[Value]) -> Result<Value> {
+    match &args[1] {
+        Value::Int(n) if *n >= 0 => Ok(Value::Iterator(as_iter(&args[0])?.drop(*n as usize))),
+        Value::Int(_) => Err(JtvError::TypeError("drop requires a non-negative count".to_string())),
+        _ => Err(JtvError::TypeError("drop requires an integer count".to_string())),
     }
+}
 
-    #[test]
-    fn test_max_min() {
-        let lib = StdLib::new();
-        assert_eq!(lib.call("max", &[Value::Int(3), Value::Int(5)]).unwrap(), Value::Int(5));
-        assert_eq!(lib.call("min", &[Value::Int(3), Value::Int(5)]).unwrap(), Value::Int(3));
-    }
+fn stdlib_take_while(args: &[Value]) -> Result<Value> {
+    Ok(Value::Iterator(as_iter(&args[0])?.take_while(args[1].clone())))
+}
 
-    #[test]
-    fn test_gcd() {
-        let lib = StdLib::new();
-        assert_eq!(lib.call("gcd", &[Value::Int(12), Value::Int(8)]).unwrap(), Value::Int(4));
-    }
+fn stdlib_enumerate(args: &[Value]) -> Result<Value> {
+    Ok(Value::Iterator(as_iter(&args[0])?.enumerate()))
+}
 
-    #[test]
-    fn test_factorial() {
-        let lib = StdLib::new();
-        assert_eq!(lib.call("factorial", &[Value::Int(5)]).unwrap(), Value::Int(120));
-    }
+fn stdlib_chain(args: &[Value]) -> Result<Value> {
+    Ok(Value::Iterator(as_iter(&args[0])?.chain(as_iter(&args[1])?)))
+}
 
-    #[test]
-    fn test_is_prime() {
-        let lib = StdLib::new();
-        assert_eq!(lib.call("isPrime", &[Value::Int(7)]).unwrap(), Value::Bool(true));
-        assert_eq!(lib.call("isPrime", &[Value::Int(8)]).unwrap(), Value::Bool(false));
-    }
+// ===== Higher-Order Functions =====
+//
+// Each of these takes the list (or lists) first and the function value(s)
+// last, matching the positional order of the plain collection functions
+// above (`concat(a, b)`, `at(list, index)`, ...) so a caller doesn't have to
+// remember a different convention just because one of the arguments happens
+// to be callable.
 
-    #[test]
-    fn test_length() {
-        let lib = StdLib::new();
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
-        assert_eq!(lib.call("length", &[list]).unwrap(), Value::Int(3));
+fn stdlib_map(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            let mut result = Vec::with_capacity(items.len());
+            for item in items {
+                result.push(apply(&args[1], &[item.clone()])?);
+            }
+            Ok(Value::List(PVector::from_vec(result)))
+        }
+        _ => Err(JtvError::TypeError("map requires a list".to_string())),
     }
+}
 
-    #[test]
-    fn test_sum() {
-        let lib = StdLib::new();
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
-        assert_eq!(lib.call("sum", &[list]).unwrap(), Value::Int(6));
+fn stdlib_filter(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            let mut result = Vec::new();
+            for item in items {
+                if apply(&args[1], &[item.clone()])?.is_truthy() {
+                    result.push(item.clone());
+                }
+            }
+            Ok(Value::List(PVector::from_vec(result)))
+        }
+        _ => Err(JtvError::TypeError("filter requires a list".to_string())),
     }
+}
 
-    #[test]
+fn stdlib_foldl(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            let mut acc = args[1].clone();
+            for item in items {
+                acc = apply(&args[2], &[acc, item.clone()])?;
+            }
+            Ok(acc)
+        }
+        _ => Err(JtvError::TypeError("foldl requires a list".to_string())),
+    }
+}
+
+fn stdlib_foldr(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            let mut acc = args[1].clone();
+            for item in items.iter().rev() {
+                acc = apply(&args[2], &[item.clone(), acc])?;
+            }
+            Ok(acc)
+        }
+        _ => Err(JtvError::TypeError("foldr requires a list".to_string())),
+    }
+}
+
+fn stdlib_zip(args: &[Value], _apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match (&args[0], &args[1]) {
+        (Value::List(a), Value::List(b)) => {
+            let zipped = a.iter()
+                .zip(b.iter())
+                .map(|(x, y)| Value::Tuple(vec![x.clone(), y.clone()]))
+                .collect();
+            Ok(Value::List(zipped))
+        }
+        _ => Err(JtvError::TypeError("zip requires two lists".to_string())),
+    }
+}
+
+fn stdlib_zip_with(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match (&args[0], &args[1]) {
+        (Value::List(a), Value::List(b)) => {
+            let mut result = Vec::with_capacity(a.len().min(b.len()));
+            for (x, y) in a.iter().zip(b.iter()) {
+                result.push(apply(&args[2], &[x.clone(), y.clone()])?);
+            }
+            Ok(Value::List(PVector::from_vec(result)))
+        }
+        _ => Err(JtvError::TypeError("zipWith requires two lists".to_string())),
+    }
+}
+
+fn stdlib_all(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            for item in items {
+                if !apply(&args[1], &[item.clone()])?.is_truthy() {
+                    return Ok(Value::Bool(false));
+                }
+            }
+            Ok(Value::Bool(true))
+        }
+        _ => Err(JtvError::TypeError("all requires a list".to_string())),
+    }
+}
+
+fn stdlib_any(args: &[Value], apply: &mut dyn FnMut(&Value, &[Value]) -> Result<Value>) -> Result<Value> {
+    match &args[0] {
+        Value::List(items) => {
+            for item in items {
+                if apply(&args[1], &[item.clone()])?.is_truthy() {
+                    return Ok(Value::Bool(true));
+                }
+            }
+            Ok(Value::Bool(false))
+        }
+        _ => Err(JtvError::TypeError("any requires a list".to_string())),
+    }
+}
+
+// ===== Linear Algebra Functions =====
+
+/// A matrix as the interpreter sees it: a `Value::List` of equal-length
+/// `Value::List` rows. Kept as `Vec<Vec<Value>>` internally so `matMul`/
+/// `matPow` can index into it directly instead of re-destructuring `Value`
+/// at every cell.
+type Matrix = Vec<Vec<Value>>;
+
+fn as_matrix(value: &Value, fn_name: &str) -> Result<Matrix> {
+    let rows = match value {
+        Value::List(rows) => rows,
+        _ => return Err(JtvError::TypeError(format!("{} requires a matrix (a list of lists)", fn_name))),
+    };
+    let mut matrix = Vec::with_capacity(rows.len());
+    let mut width = None;
+    for row in rows {
+        let cells = match row {
+            Value::List(cells) => cells,
+            _ => return Err(JtvError::TypeError(format!("{} requires a matrix (a list of lists)", fn_name))),
+        };
+        match width {
+            None => width = Some(cells.len()),
+            Some(w) if w != cells.len() => {
+                return Err(JtvError::RuntimeError(format!("{} requires a rectangular matrix", fn_name)));
+            }
+            _ => {}
+        }
+        matrix.push(cells.to_vec());
+    }
+    Ok(matrix)
+}
+
+fn matrix_to_value(m: Matrix) -> Value {
+    Value::List(PVector::from_vec(
+        m.into_iter().map(|row| Value::List(PVector::from_vec(row))).collect(),
+    ))
+}
+
+fn identity_matrix(n: usize) -> Matrix {
+    (0..n)
+        .map(|i| (0..n).map(|j| Value::Int(if i == j { 1 } else { 0 })).collect())
+        .collect()
+}
+
+/// The standard triple loop, with a `cols(a) == rows(b)` dimension check up
+/// front. Dot products go through `add_values`/`multiply_values` so matrix
+/// entries promote to `BigInt` on overflow the same way `sum`/`product` do.
+fn matmul(a: &Matrix, b: &Matrix, fn_name: &str) -> Result<Matrix> {
+    let a_rows = a.len();
+    let a_cols = a.first().map_or(0, |row| row.len());
+    let b_rows = b.len();
+    let b_cols = b.first().map_or(0, |row| row.len());
+    if a_cols != b_rows {
+        return Err(JtvError::RuntimeError(format!(
+            "{}: incompatible dimensions ({}x{} vs {}x{})",
+            fn_name, a_rows, a_cols, b_rows, b_cols
+        )));
+    }
+    let mut result = Vec::with_capacity(a_rows);
+    for i in 0..a_rows {
+        let mut row = Vec::with_capacity(b_cols);
+        for j in 0..b_cols {
+            let mut sum = Value::Int(0);
+            for k in 0..a_cols {
+                sum = add_values(&sum, &multiply_values(&a[i][k], &b[k][j])?)?;
+            }
+            row.push(sum);
+        }
+        result.push(row);
+    }
+    Ok(result)
+}
+
+fn stdlib_mat_mul(args: &[Value]) -> Result<Value> {
+    let a = as_matrix(&args[0], "matMul")?;
+    let b = as_matrix(&args[1], "matMul")?;
+    Ok(matrix_to_value(matmul(&a, &b, "matMul")?))
+}
+
+/// Binary exponentiation: start from the identity, and for each set bit of
+/// `k` (low to high) square the base and fold it into the accumulator --
+/// O(log k) matrix multiplies instead of `k - 1`.
+fn stdlib_mat_pow(args: &[Value]) -> Result<Value> {
+    let m = as_matrix(&args[0], "matPow")?;
+    let n = m.len();
+    if m.iter().any(|row| row.len() != n) {
+        return Err(JtvError::RuntimeError("matPow requires a square matrix".to_string()));
+    }
+    let mut exp = match &args[1] {
+        Value::Int(k) if *k >= 0 => *k,
+        _ => return Err(JtvError::RuntimeError("matPow requires a non-negative integer exponent".to_string())),
+    };
+    let mut result = identity_matrix(n);
+    let mut base = m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = matmul(&result, &base, "matPow")?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = matmul(&base, &base, "matPow")?;
+        }
+    }
+    Ok(matrix_to_value(result))
+}
+
+fn stdlib_identity(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Int(n) if *n >= 0 => Ok(matrix_to_value(identity_matrix(*n as usize))),
+        _ => Err(JtvError::TypeError("identity requires a non-negative integer size".to_string())),
+    }
+}
+
+fn stdlib_transpose(args: &[Value]) -> Result<Value> {
+    let m = as_matrix(&args[0], "transpose")?;
+    let rows = m.len();
+    let cols = m.first().map_or(0, |row| row.len());
+    let mut result = vec![Vec::with_capacity(rows); cols];
+    for row in &m {
+        for (j, cell) in row.iter().enumerate() {
+            result[j].push(cell.clone());
+        }
+    }
+    Ok(matrix_to_value(result))
+}
+
+// ===== Testing Functions =====
+
+/// `assert(condition)` / `assert(condition, message)` -- fails the call
+/// with a `JtvError::InvalidOperation` (caught by `jtv test` and reported
+/// as a failing test, same as any other runtime error) when `condition`
+/// isn't `true`. The default message is generic since, unlike `jtv test`'s
+/// own failure reporting, a bare `assert` has no source text for the
+/// condition to quote back.
+fn stdlib_assert(args: &[Value]) -> Result<Value> {
+    let condition = match &args[0] {
+        Value::Bool(b) => *b,
+        other => return Err(JtvError::TypeError(format!("assert requires a Bool condition, got {:?}", other))),
+    };
+    if condition {
+        return Ok(Value::Bool(true));
+    }
+    let message = match args.get(1) {
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => return Err(JtvError::TypeError(format!("assert's message must be a String, got {:?}", other))),
+        None => "assertion failed".to_string(),
+    };
+    Err(JtvError::InvalidOperation(message))
+}
+
+// Helper function
+fn add_values(a: &Value, b: &Value) -> Result<Value> {
+    match promote(a.clone(), b.clone()) {
+        (Value::Int(x), Value::Int(y)) => match x.checked_add(y) {
+            Some(sum) => Ok(Value::Int(sum)),
+            None => Ok(normalize_bigint(BigInt::from(x) + BigInt::from(y))),
+        },
+        (Value::BigInt(x), Value::BigInt(y)) => Ok(normalize_bigint(x + y)),
+        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
+        _ => Err(JtvError::TypeError(format!("Cannot add {:?} and {:?}", a, b))),
+    }
+}
+
+/// Like `add_values`, but for `product`: promotes to `BigInt` on `i64`
+/// overflow instead of saturating.
+fn multiply_values(a: &Value, b: &Value) -> Result<Value> {
+    match promote(a.clone(), b.clone()) {
+        (Value::Int(x), Value::Int(y)) => match x.checked_mul(y) {
+            Some(product) => Ok(Value::Int(product)),
+            None => Ok(normalize_bigint(BigInt::from(x) * BigInt::from(y))),
+        },
+        (Value::BigInt(x), Value::BigInt(y)) => Ok(normalize_bigint(x * y)),
+        _ => Err(JtvError::TypeError("product requires a list of integers".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abs() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("abs", &[Value::Int(-5)]).unwrap(), Value::Int(5));
+        assert_eq!(lib.call("abs", &[Value::Int(5)]).unwrap(), Value::Int(5));
+    }
+
+    #[test]
+    fn test_max_min() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("max", &[Value::Int(3), Value::Int(5)]).unwrap(), Value::Int(5));
+        assert_eq!(lib.call("min", &[Value::Int(3), Value::Int(5)]).unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn test_max_min_variadic() {
+        let lib = StdLib::new();
+        let args = vec![Value::Int(3), Value::Int(7), Value::Int(1), Value::Int(5)];
+        assert_eq!(lib.call("max", &args).unwrap(), Value::Int(7));
+        assert_eq!(lib.call("min", &args).unwrap(), Value::Int(1));
+        assert_eq!(lib.call("max", &[Value::Int(9)]).unwrap(), Value::Int(9));
+    }
+
+    #[test]
+    fn test_gcd() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("gcd", &[Value::Int(12), Value::Int(8)]).unwrap(), Value::Int(4));
+    }
+
+    #[test]
+    fn test_gcd_lcm_variadic() {
+        let lib = StdLib::new();
+        assert_eq!(
+            lib.call("gcd", &[Value::Int(12), Value::Int(8), Value::Int(20)]).unwrap(),
+            Value::Int(4)
+        );
+        assert_eq!(
+            lib.call("lcm", &[Value::Int(2), Value::Int(3), Value::Int(4)]).unwrap(),
+            Value::Int(12)
+        );
+    }
+
+    #[test]
+    fn test_concat_variadic() {
+        let lib = StdLib::new();
+        let a = Value::List(PVector::from_vec(vec![Value::Int(1)]));
+        let b = Value::List(PVector::from_vec(vec![Value::Int(2)]));
+        let c = Value::List(PVector::from_vec(vec![Value::Int(3)]));
+        assert_eq!(
+            lib.call("concat", &[a, b, c]).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]))
+        );
+    }
+
+    #[test]
+    fn test_factorial() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("factorial", &[Value::Int(5)]).unwrap(), Value::Int(120));
+    }
+
+    #[test]
+    fn test_factorial_promotes_to_bigint() {
+        let lib = StdLib::new();
+        let expected: BigInt = "51090942171709440000".parse().unwrap();
+        assert_eq!(lib.call("factorial", &[Value::Int(21)]).unwrap(), Value::BigInt(expected));
+    }
+
+    #[test]
+    fn test_pow_promotes_to_bigint() {
+        let lib = StdLib::new();
+        let expected: BigInt = "1267650600228229401496703205376".parse().unwrap();
+        assert_eq!(
+            lib.call("pow", &[Value::Int(2), Value::Int(100)]).unwrap(),
+            Value::BigInt(expected)
+        );
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("isPrime", &[Value::Int(7)]).unwrap(), Value::Bool(true));
+        assert_eq!(lib.call("isPrime", &[Value::Int(8)]).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_is_prime_bigint() {
+        let lib = StdLib::new();
+        let big_prime: BigInt = "51090942171709440031".parse().unwrap();
+        let big_composite: BigInt = "51090942171709440000".parse().unwrap();
+        assert_eq!(lib.call("isPrime", &[Value::BigInt(big_composite)]).unwrap(), Value::Bool(false));
+        assert_eq!(lib.call("isPrime", &[Value::BigInt(big_prime)]).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_abs_sign_gcd_mod_on_bigint() {
+        let lib = StdLib::new();
+        let neg: BigInt = "-51090942171709440000".parse().unwrap();
+        let pos: BigInt = "51090942171709440000".parse().unwrap();
+        assert_eq!(lib.call("abs", &[Value::BigInt(neg.clone())]).unwrap(), Value::BigInt(pos.clone()));
+        assert_eq!(lib.call("sign", &[Value::BigInt(neg)]).unwrap(), Value::Int(-1));
+        assert_eq!(
+            lib.call("gcd", &[Value::BigInt(pos.clone()), Value::Int(2)]).unwrap(),
+            Value::Int(2)
+        );
+        assert_eq!(
+            lib.call("mod", &[Value::BigInt(pos), Value::Int(7)]).unwrap(),
+            Value::Int(0)
+        );
+    }
+
+    #[test]
+    fn test_under_applied_builtin_returns_partial_app() {
+        let lib = StdLib::new();
+        let partial = lib.call("pow", &[Value::Int(2)]).unwrap();
+        assert_eq!(
+            partial,
+            Value::PartialApp { name: "pow".to_string(), collected: vec![Value::Int(2)] }
+        );
+    }
+
+    #[test]
+    fn test_partial_app_composes_with_map() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        let powers_of_two = Value::PartialApp { name: "pow".to_string(), collected: vec![Value::Int(2)] };
+        let mut apply = |f: &Value, args: &[Value]| -> Result<Value> {
+            match f {
+                Value::PartialApp { name, collected } => {
+                    let mut full = collected.clone();
+                    full.extend_from_slice(args);
+                    lib.call(name, &full)
+                }
+                _ => unreachable!(),
+            }
+        };
+        let result = lib.call_hof("map", &[list, powers_of_two], &mut apply).unwrap();
+        assert_eq!(result, Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(4), Value::Int(8)])));
+    }
+
+    #[test]
+    fn test_over_applied_builtin_still_errors() {
+        let lib = StdLib::new();
+        assert!(lib.call("pow", &[Value::Int(2), Value::Int(3), Value::Int(4)]).is_err());
+    }
+
+    #[test]
+    fn test_mod_euclid_mul_mod_pow_mod() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("modEuclid", &[Value::Int(-7), Value::Int(3)]).unwrap(), Value::Int(2));
+        assert_eq!(
+            lib.call("mulMod", &[Value::Int(i64::MAX / 2), Value::Int(3), Value::Int(1_000_000_007)]).unwrap(),
+            Value::Int(436758003)
+        );
+        assert_eq!(lib.call("powMod", &[Value::Int(4), Value::Int(13), Value::Int(497)]).unwrap(), Value::Int(445));
+    }
+
+    #[test]
+    fn test_mod_inv() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("modInv", &[Value::Int(3), Value::Int(11)]).unwrap(), Value::Int(4));
+        assert!(lib.call("modInv", &[Value::Int(2), Value::Int(4)]).is_err());
+    }
+
+    #[test]
+    fn test_length() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+        assert_eq!(
+            lib.call_hof("length", &[list], &mut no_predicate).unwrap(),
+            Value::Int(3)
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+        assert_eq!(
+            lib.call_hof("sum", &[list], &mut no_predicate).unwrap(),
+            Value::Int(6)
+        );
+    }
+
+    #[test]
+    fn test_sum_and_product_promote_to_bigint_on_overflow() {
+        let lib = StdLib::new();
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+
+        let sum_list = Value::List(PVector::from_vec(vec![Value::Int(i64::MAX), Value::Int(1)]));
+        let expected_sum = normalize_bigint(BigInt::from(i64::MAX) + BigInt::from(1));
+        assert_eq!(
+            lib.call_hof("sum", &[sum_list], &mut no_predicate).unwrap(),
+            expected_sum
+        );
+
+        let product_list = Value::List(PVector::from_vec(vec![Value::Int(i64::MAX), Value::Int(2)]));
+        let expected_product = normalize_bigint(BigInt::from(i64::MAX) * BigInt::from(2));
+        assert_eq!(
+            lib.call_hof("product", &[product_list], &mut no_predicate).unwrap(),
+            expected_product
+        );
+    }
+
+    #[test]
     fn test_head_tail() {
         let lib = StdLib::new();
-        let list = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
         assert_eq!(lib.call("head", &[list.clone()]).unwrap(), Value::Int(1));
         assert_eq!(
             lib.call("tail", &[list]).unwrap(),
-            Value::List(vec![Value::Int(2), Value::Int(3)])
+            Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(3)]))
         );
     }
 
     #[test]
     fn test_range() {
         let lib = StdLib::new();
+        let produced = lib.call("range", &[Value::Int(1), Value::Int(4)]).unwrap();
+        assert!(matches!(produced, Value::Iterator(_)));
+
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
         assert_eq!(
-            lib.call("range", &[Value::Int(1), Value::Int(4)]).unwrap(),
-            Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+            lib.call_hof("collect", &[produced], &mut no_predicate).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]))
         );
     }
+
+    #[test]
+    fn test_take_drop_take_while() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)]));
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+
+        let taken = lib.call("take", &[list.clone(), Value::Int(2)]).unwrap();
+        assert_eq!(
+            lib.call_hof("collect", &[taken], &mut no_predicate).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2)]))
+        );
+
+        let dropped = lib.call("drop", &[list.clone(), Value::Int(2)]).unwrap();
+        assert_eq!(
+            lib.call_hof("collect", &[dropped], &mut no_predicate).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(3), Value::Int(4)]))
+        );
+
+        let taken_while = lib
+            .call("takeWhile", &[list, Value::Builtin("isSmall".to_string())])
+            .unwrap();
+        let mut is_small = |_f: &Value, args: &[Value]| -> Result<Value> {
+            match &args[0] {
+                Value::Int(n) => Ok(Value::Bool(*n < 3)),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(
+            lib.call_hof("collect", &[taken_while], &mut is_small).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2)]))
+        );
+    }
+
+    #[test]
+    fn test_enumerate_and_chain() {
+        let lib = StdLib::new();
+        let mut no_predicate = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+
+        let a = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2)]));
+        let enumerated = lib.call("enumerate", &[a.clone()]).unwrap();
+        assert_eq!(
+            lib.call_hof("collect", &[enumerated], &mut no_predicate).unwrap(),
+            Value::List(PVector::from_vec(vec![
+                Value::Tuple(vec![Value::Int(0), Value::Int(1)]),
+                Value::Tuple(vec![Value::Int(1), Value::Int(2)]),
+            ]))
+        );
+
+        let b = Value::List(PVector::from_vec(vec![Value::Int(3), Value::Int(4)]));
+        let chained = lib.call("chain", &[a, b]).unwrap();
+        assert_eq!(
+            lib.call_hof("collect", &[chained], &mut no_predicate).unwrap(),
+            Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)]))
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        let mut apply = |_f: &Value, args: &[Value]| -> Result<Value> { lib.call("abs", args) };
+        let result = lib
+            .call_hof("map", &[list, Value::Builtin("abs".to_string())], &mut apply)
+            .unwrap();
+        assert_eq!(result, Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)])));
+    }
+
+    #[test]
+    fn test_filter() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(3), Value::Int(4)]));
+        let mut apply = |_f: &Value, args: &[Value]| -> Result<Value> { lib.call("isPrime", args) };
+        let result = lib
+            .call_hof("filter", &[list, Value::Builtin("isPrime".to_string())], &mut apply)
+            .unwrap();
+        assert_eq!(result, Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(3)])));
+    }
+
+    #[test]
+    fn test_foldl() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        let mut apply = |_f: &Value, args: &[Value]| -> Result<Value> { add_values(&args[0], &args[1]) };
+        let result = lib
+            .call_hof(
+                "foldl",
+                &[list, Value::Int(0), Value::Builtin("add".to_string())],
+                &mut apply,
+            )
+            .unwrap();
+        assert_eq!(result, Value::Int(6));
+    }
+
+    #[test]
+    fn test_zip_and_zip_with() {
+        let lib = StdLib::new();
+        let a = Value::List(PVector::from_vec(vec![Value::Int(1), Value::Int(2)]));
+        let b = Value::List(PVector::from_vec(vec![Value::Int(10), Value::Int(20)]));
+        let mut no_op = |_f: &Value, _args: &[Value]| -> Result<Value> { unreachable!() };
+        let zipped = lib.call_hof("zip", &[a.clone(), b.clone()], &mut no_op).unwrap();
+        assert_eq!(
+            zipped,
+            Value::List(PVector::from_vec(vec![
+                Value::Tuple(vec![Value::Int(1), Value::Int(10)]),
+                Value::Tuple(vec![Value::Int(2), Value::Int(20)]),
+            ]))
+        );
+
+        let mut apply = |_f: &Value, args: &[Value]| -> Result<Value> { add_values(&args[0], &args[1]) };
+        let summed = lib
+            .call_hof("zipWith", &[a, b, Value::Builtin("add".to_string())], &mut apply)
+            .unwrap();
+        assert_eq!(summed, Value::List(PVector::from_vec(vec![Value::Int(11), Value::Int(22)])));
+    }
+
+    #[test]
+    fn test_all_and_any() {
+        let lib = StdLib::new();
+        let list = Value::List(PVector::from_vec(vec![Value::Int(2), Value::Int(4), Value::Int(6)]));
+        let mut is_even = |_f: &Value, args: &[Value]| -> Result<Value> {
+            match &args[0] {
+                Value::Int(n) => Ok(Value::Bool(n % 2 == 0)),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(
+            lib.call_hof("all", &[list.clone(), Value::Builtin("isEven".to_string())], &mut is_even)
+                .unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            lib.call_hof("any", &[list, Value::Builtin("isEven".to_string())], &mut is_even)
+                .unwrap(),
+            Value::Bool(true)
+        );
+    }
+
+    fn int_matrix(rows: &[&[i64]]) -> Value {
+        Value::List(
+            rows.iter()
+                .map(|row| Value::List(row.iter().map(|n| Value::Int(*n)).collect()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_mat_mul() {
+        let lib = StdLib::new();
+        let a = int_matrix(&[&[1, 2], &[3, 4]]);
+        let b = int_matrix(&[&[5, 6], &[7, 8]]);
+        assert_eq!(
+            lib.call("matMul", &[a, b]).unwrap(),
+            int_matrix(&[&[19, 22], &[43, 50]])
+        );
+    }
+
+    #[test]
+    fn test_mat_mul_rejects_incompatible_dimensions() {
+        let lib = StdLib::new();
+        let a = int_matrix(&[&[1, 2, 3]]);
+        let b = int_matrix(&[&[1, 2]]);
+        assert!(lib.call("matMul", &[a, b]).is_err());
+    }
+
+    #[test]
+    fn test_identity_and_transpose() {
+        let lib = StdLib::new();
+        assert_eq!(
+            lib.call("identity", &[Value::Int(3)]).unwrap(),
+            int_matrix(&[&[1, 0, 0], &[0, 1, 0], &[0, 0, 1]])
+        );
+        let m = int_matrix(&[&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!(
+            lib.call("transpose", &[m]).unwrap(),
+            int_matrix(&[&[1, 4], &[2, 5], &[3, 6]])
+        );
+    }
+
+    #[test]
+    fn test_mat_pow_fibonacci() {
+        let lib = StdLib::new();
+        let fib_matrix = int_matrix(&[&[1, 1], &[1, 0]]);
+        assert_eq!(
+            lib.call("matPow", &[fib_matrix.clone(), Value::Int(0)]).unwrap(),
+            int_matrix(&[&[1, 0], &[0, 1]])
+        );
+        // [[1,1],[1,0]]^10 encodes fib(11)/fib(10)/fib(9) in its entries.
+        assert_eq!(
+            lib.call("matPow", &[fib_matrix, Value::Int(10)]).unwrap(),
+            int_matrix(&[&[89, 55], &[55, 34]])
+        );
+    }
+
+    #[test]
+    fn test_mat_pow_requires_square_matrix() {
+        let lib = StdLib::new();
+        let m = int_matrix(&[&[1, 2, 3], &[4, 5, 6]]);
+        assert!(lib.call("matPow", &[m, Value::Int(2)]).is_err());
+    }
+
+    #[test]
+    fn test_assert_passes_on_true() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("assert", &[Value::Bool(true)]).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_assert_fails_on_false_with_default_message() {
+        let lib = StdLib::new();
+        let err = lib.call("assert", &[Value::Bool(false)]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid operation: assertion failed");
+    }
+
+    #[test]
+    fn test_assert_fails_with_custom_message() {
+        let lib = StdLib::new();
+        let err = lib
+            .call("assert", &[Value::Bool(false), Value::String("x should be positive".to_string())])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid operation: x should be positive");
+    }
+
+    #[test]
+    fn test_bit_and_preserves_hex_flavor() {
+        let lib = StdLib::new();
+        assert_eq!(
+            lib.call("bitAnd", &[Value::Hex(0xFF), Value::Hex(0x0F)]).unwrap(),
+            Value::Hex(0x0F)
+        );
+    }
+
+    #[test]
+    fn test_bit_or_preserves_binary_flavor() {
+        let lib = StdLib::new();
+        assert_eq!(
+            lib.call("bitOr", &[Value::Binary(0b1010), Value::Binary(0b0101)]).unwrap(),
+            Value::Binary(0b1111)
+        );
+    }
+
+    #[test]
+    fn test_bit_xor_mixed_hex_binary_defaults_to_hex() {
+        let lib = StdLib::new();
+        assert_eq!(
+            lib.call("bitXor", &[Value::Hex(0xFF), Value::Binary(0b1111)]).unwrap(),
+            Value::Hex(0xF0)
+        );
+    }
+
+    #[test]
+    fn test_bit_not_preserves_flavor() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("bitNot", &[Value::Hex(0)]).unwrap(), Value::Hex(-1));
+        assert_eq!(lib.call("bitNot", &[Value::Int(0)]).unwrap(), Value::Int(-1));
+    }
+
+    #[test]
+    fn test_shift_left_and_right() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("shiftLeft", &[Value::Int(1), Value::Int(4)]).unwrap(), Value::Int(16));
+        assert_eq!(lib.call("shiftRight", &[Value::Int(16), Value::Int(4)]).unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    fn test_shift_rejects_out_of_range_amount() {
+        let lib = StdLib::new();
+        assert!(lib.call("shiftLeft", &[Value::Int(1), Value::Int(64)]).is_err());
+        assert!(lib.call("shiftLeft", &[Value::Int(1), Value::Int(-1)]).is_err());
+    }
+
+    #[test]
+    fn test_popcount_and_bit_length() {
+        let lib = StdLib::new();
+        assert_eq!(lib.call("popcount", &[Value::Hex(0b1011)]).unwrap(), Value::Int(3));
+        assert_eq!(lib.call("bitLength", &[Value::Int(0)]).unwrap(), Value::Int(0));
+        assert_eq!(lib.call("bitLength", &[Value::Int(0b1011)]).unwrap(), Value::Int(4));
+    }
+
+    #[test]
+    fn test_bitwise_rejects_non_integral_argument() {
+        let lib = StdLib::new();
+        assert!(lib.call("bitAnd", &[Value::Float(1.5), Value::Int(1)]).is_err());
+    }
 }
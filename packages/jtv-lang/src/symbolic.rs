@@ -0,0 +1,412 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Exact symbolic/rational constant folding
+//
+// The Data language is addition-only (`DataExpr::Add`/`Negate`), but
+// nothing folds a tree of those over `Number`'s exact variants -- a
+// `Rational` built by hand stays unreduced, `Complex` values aren't added
+// component-wise, and a `Symbolic` operand has no representation for
+// "the sum of two of these" other than collapsing to a float. `eval_exact`
+// does that folding, keeping everything exact: `Rational`s are always
+// returned in lowest terms with a positive denominator, and like
+// `Symbolic` terms collect into a linear combination (`x + x` -> `2*x`)
+// instead of being evaluated away.
+
+use crate::ast::{DataExpr, Number};
+use std::collections::HashMap;
+
+/// A normalized, hashable key over just the variants of [`Number`] for
+/// which equality is actually well-defined: `Int`, a gcd-reduced/
+/// sign-canonical `Rational`, and `Symbolic`. `Number` itself derives
+/// `PartialEq` over `Float`/`Complex`'s raw `f64`s, which is fuzzy (NaN,
+/// `-0.0` vs. `0.0`) and unsound to key a hash table with -- deriving
+/// `Eq`/`Hash` on `Number` directly, the way its `PartialEq` derive might
+/// invite, would inherit that unsoundness. `ExactKey` exists so the term
+/// table in [`LinearCombination`] below can be hashed without that risk;
+/// `Float`/`Complex` leaves are folded eagerly instead (see
+/// `LinearCombination::inexact`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExactKey {
+    Int(i64),
+    /// Always `reduce_rational`-normalized: gcd-reduced, denominator > 0.
+    Rational(i64, i64),
+    Symbolic(String),
+}
+
+impl ExactKey {
+    /// `None` for `Float`/`Complex` (not exact) and a zero-denominator
+    /// `Rational` (not a number at all).
+    pub fn from_number(n: &Number) -> Option<Self> {
+        match n {
+            Number::Int(i) => Some(ExactKey::Int(*i)),
+            Number::Rational(num, den) => {
+                if *den == 0 {
+                    None
+                } else {
+                    let (num, den) = reduce_rational(*num, *den);
+                    Some(ExactKey::Rational(num, den))
+                }
+            }
+            Number::Symbolic(s) => Some(ExactKey::Symbolic(s.clone())),
+            Number::Float(_) | Number::Complex(_, _) | Number::Hex(_) | Number::Binary(_) => None,
+        }
+    }
+}
+
+/// `gcd(0, n) == n`, so a zero numerator reduces to `0/1` rather than
+/// dividing by zero.
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Reduces `num/den` to lowest terms with `den > 0`. `den == 0` is passed
+/// through unchanged -- this function only normalizes, it doesn't validate.
+pub fn reduce_rational(num: i64, den: i64) -> (i64, i64) {
+    if den == 0 {
+        return (num, den);
+    }
+    let sign: i64 = if den < 0 { -1 } else { 1 };
+    let num = num.saturating_mul(sign);
+    let den = den.saturating_mul(sign);
+    let divisor = gcd(num.unsigned_abs(), den.unsigned_abs()).max(1) as i64;
+    (num / divisor, den / divisor)
+}
+
+/// Reduces a `Number::Rational`, collapsing it to `Int` once its
+/// denominator hits 1. Other variants pass through unchanged.
+fn reduce_number(n: Number) -> Number {
+    match n {
+        Number::Rational(num, den) => {
+            let (num, den) = reduce_rational(num, den);
+            if den == 1 {
+                Number::Int(num)
+            } else {
+                Number::Rational(num, den)
+            }
+        }
+        other => other,
+    }
+}
+
+/// Reads `Int`/`Hex`/`Binary` as a plain `i64` -- the "integral tier" of
+/// the promotion order below, the same way `Value`'s own tower in
+/// `number.rs` treats `Hex`/`Binary` as `Int`-equivalent for arithmetic.
+fn as_integral(n: &Number) -> Option<i64> {
+    match n {
+        Number::Int(i) => Some(*i),
+        Number::Hex(s) => i64::from_str_radix(s.trim_start_matches("0x"), 16).ok(),
+        Number::Binary(s) => i64::from_str_radix(s.trim_start_matches("0b"), 2).ok(),
+        _ => None,
+    }
+}
+
+fn as_rational(n: &Number) -> Option<(i64, i64)> {
+    match n {
+        Number::Rational(num, den) => Some((*num, *den)),
+        _ => as_integral(n).map(|i| (i, 1)),
+    }
+}
+
+fn as_f64(n: &Number) -> Option<f64> {
+    match n {
+        Number::Float(f) => Some(*f),
+        Number::Rational(num, den) => Some(*num as f64 / *den as f64),
+        _ => as_integral(n).map(|i| i as f64),
+    }
+}
+
+fn as_complex(n: &Number) -> Option<(f64, f64)> {
+    match n {
+        Number::Complex(re, im) => Some((*re, *im)),
+        _ => as_f64(n).map(|f| (f, 0.0)),
+    }
+}
+
+/// Rank of a numeric `Number` along the promotion tower this evaluator
+/// adds along: `Int`/`Hex`/`Binary` (0) < `Rational` (1) < `Float` (2) <
+/// `Complex` (3). `None` for `Symbolic`, which isn't numeric.
+fn rank(n: &Number) -> Option<u8> {
+    match n {
+        Number::Int(_) | Number::Hex(_) | Number::Binary(_) => Some(0),
+        Number::Rational(_, _) => Some(1),
+        Number::Float(_) => Some(2),
+        Number::Complex(_, _) => Some(3),
+        Number::Symbolic(_) => None,
+    }
+}
+
+/// Adds two numeric (non-`Symbolic`) `Number`s, promoting to the higher
+/// of the two's rank, reducing a `Rational` result by gcd with a positive
+/// denominator, and falling back to `Float` rather than panicking if an
+/// `i64`/`Rational` product or sum would overflow.
+fn add_numeric(a: &Number, b: &Number) -> Number {
+    let (ra, rb) = match (rank(a), rank(b)) {
+        (Some(ra), Some(rb)) => (ra, rb),
+        _ => return Number::Float(as_f64(a).unwrap_or(f64::NAN) + as_f64(b).unwrap_or(f64::NAN)),
+    };
+    match ra.max(rb) {
+        0 => match (as_integral(a), as_integral(b)) {
+            (Some(x), Some(y)) => match x.checked_add(y) {
+                Some(sum) => Number::Int(sum),
+                None => Number::Float(x as f64 + y as f64),
+            },
+            _ => Number::Float(as_f64(a).unwrap_or(f64::NAN) + as_f64(b).unwrap_or(f64::NAN)),
+        },
+        1 => {
+            let (an, ad) = as_rational(a).unwrap_or((0, 1));
+            let (bn, bd) = as_rational(b).unwrap_or((0, 1));
+            let num = (an as i128) * (bd as i128) + (bn as i128) * (ad as i128);
+            let den = (ad as i128) * (bd as i128);
+            match (i64::try_from(num), i64::try_from(den)) {
+                (Ok(num), Ok(den)) => reduce_number(Number::Rational(num, den)),
+                _ => Number::Float(as_f64(a).unwrap_or(f64::NAN) + as_f64(b).unwrap_or(f64::NAN)),
+            }
+        }
+        2 => Number::Float(as_f64(a).unwrap_or(f64::NAN) + as_f64(b).unwrap_or(f64::NAN)),
+        _ => {
+            let (ar, ai) = as_complex(a).unwrap_or((0.0, 0.0));
+            let (br, bi) = as_complex(b).unwrap_or((0.0, 0.0));
+            Number::Complex(ar + br, ai + bi)
+        }
+    }
+}
+
+/// Negates a single `Number`, distributing over `Symbolic` the way
+/// `Value::negate` does (`"-(x)"`) rather than failing.
+fn negate_numeric(n: &Number) -> Number {
+    match n {
+        Number::Int(x) => x.checked_neg().map(Number::Int).unwrap_or_else(|| Number::Float(-(*x as f64))),
+        Number::Hex(_) | Number::Binary(_) => match as_integral(n) {
+            Some(x) => Number::Int(x.wrapping_neg()),
+            None => Number::Float(-as_f64(n).unwrap_or(f64::NAN)),
+        },
+        Number::Rational(num, den) => reduce_number(Number::Rational(num.saturating_neg(), *den)),
+        Number::Float(f) => Number::Float(-f),
+        Number::Complex(re, im) => Number::Complex(-re, -im),
+        Number::Symbolic(s) => Number::Symbolic(format!("-({})", s)),
+    }
+}
+
+/// The result of folding a tree of `DataExpr::Add`/`Negate` over `Number`
+/// leaves: a running numeric constant plus a signed count per distinct
+/// exact term (see [`ExactKey`]) -- since the Data language has no
+/// multiplication, repeated addition of the same term is the only way its
+/// coefficient can become anything other than 1, so a plain `i64` count
+/// is exact.
+#[derive(Debug, Default)]
+struct LinearCombination {
+    /// Signed occurrence count per `Int`/`Rational`/`Symbolic` term.
+    exact_terms: HashMap<ExactKey, i64>,
+    /// The running sum of any `Float`/`Complex` leaf encountered -- these
+    /// have no hashable key (see [`ExactKey`]'s doc comment), so they're
+    /// combined eagerly instead of collected.
+    inexact: Option<Number>,
+}
+
+impl LinearCombination {
+    fn leaf(n: &Number) -> Self {
+        let mut combo = LinearCombination::default();
+        match ExactKey::from_number(n) {
+            Some(key) => {
+                combo.exact_terms.insert(key, 1);
+            }
+            None => combo.inexact = Some(n.clone()),
+        }
+        combo
+    }
+
+    fn add(mut self, other: LinearCombination) -> Self {
+        for (key, count) in other.exact_terms {
+            *self.exact_terms.entry(key).or_insert(0) += count;
+        }
+        self.inexact = match (self.inexact, other.inexact) {
+            (Some(a), Some(b)) => Some(add_numeric(&a, &b)),
+            (Some(a), None) => Some(a),
+            (None, b) => b,
+        };
+        self
+    }
+
+    fn negated(mut self) -> Self {
+        for count in self.exact_terms.values_mut() {
+            *count = -*count;
+        }
+        self.inexact = self.inexact.as_ref().map(negate_numeric);
+        self
+    }
+
+    /// Assembles the collected terms back into a `DataExpr`: the folded
+    /// constant (if any, or if it's the only term) first, then each
+    /// distinct symbol in a stable (sorted) order as `name` or
+    /// `"{count}*{name}"`, added together left to right.
+    fn into_data_expr(self) -> DataExpr {
+        let mut constant = self.inexact;
+        let mut symbols: Vec<(String, i64)> = Vec::new();
+
+        for (key, count) in self.exact_terms {
+            if count == 0 {
+                continue;
+            }
+            match key {
+                ExactKey::Int(n) => {
+                    let term = Number::Int(n.saturating_mul(count));
+                    constant = Some(match constant {
+                        Some(c) => add_numeric(&c, &term),
+                        None => term,
+                    });
+                }
+                ExactKey::Rational(num, den) => {
+                    let term = reduce_number(Number::Rational(num.saturating_mul(count), den));
+                    constant = Some(match constant {
+                        Some(c) => add_numeric(&c, &term),
+                        None => term,
+                    });
+                }
+                ExactKey::Symbolic(name) => symbols.push((name, count)),
+            }
+        }
+
+        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut parts: Vec<DataExpr> = Vec::new();
+        let constant_is_zero = matches!(constant, Some(Number::Int(0)) | None);
+        if let Some(c) = constant {
+            if !constant_is_zero || symbols.is_empty() {
+                parts.push(DataExpr::Number(c));
+            }
+        }
+        for (name, count) in symbols {
+            if count == 0 {
+                continue;
+            }
+            let term = if count == 1 { name } else { format!("{}*{}", count, name) };
+            parts.push(DataExpr::Number(Number::Symbolic(term)));
+        }
+
+        match parts.into_iter().reduce(|acc, next| DataExpr::Add(Box::new(acc), Box::new(next))) {
+            Some(expr) => expr,
+            None => DataExpr::Number(Number::Int(0)),
+        }
+    }
+}
+
+/// Folds a tree of `DataExpr::Add`/`Negate` over `Number` leaves into a
+/// `LinearCombination`. Returns `None` for anything this evaluator can't
+/// reason about (an `Identifier`, `FunctionCall`, ...) -- `eval_exact`
+/// returns the original expression unchanged in that case rather than
+/// guessing.
+fn fold(expr: &DataExpr) -> Option<LinearCombination> {
+    match expr {
+        DataExpr::Number(n) => Some(LinearCombination::leaf(n)),
+        DataExpr::Add(left, right) => Some(fold(left)?.add(fold(right)?)),
+        DataExpr::Negate(inner) => Some(fold(inner)?.negated()),
+        _ => None,
+    }
+}
+
+/// Evaluates a `DataExpr` built from `Add`/`Negate`/`Number` nodes into a
+/// fully reduced `Number` (if it has no remaining symbols) or a canonical
+/// symbolic `DataExpr` (if it does), normalizing rationals and adding
+/// complex numbers component-wise along the way. Any subexpression this
+/// can't fold (an `Identifier`, `FunctionCall`, ...) is returned as-is.
+pub fn eval_exact(expr: &DataExpr) -> DataExpr {
+    match fold(expr) {
+        Some(combo) => combo.into_data_expr(),
+        None => expr.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(left: DataExpr, right: DataExpr) -> DataExpr {
+        DataExpr::Add(Box::new(left), Box::new(right))
+    }
+
+    fn num(n: Number) -> DataExpr {
+        DataExpr::Number(n)
+    }
+
+    #[test]
+    fn test_adds_integers_exactly() {
+        let expr = add(num(Number::Int(2)), num(Number::Int(3)));
+        assert_eq!(eval_exact(&expr), num(Number::Int(5)));
+    }
+
+    #[test]
+    fn test_reduces_rational_by_gcd_and_canonicalizes_sign() {
+        let expr = add(num(Number::Rational(1, 2)), num(Number::Rational(1, -2)));
+        assert_eq!(eval_exact(&expr), num(Number::Int(0)));
+
+        let expr = add(num(Number::Rational(1, 3)), num(Number::Rational(1, 6)));
+        assert_eq!(eval_exact(&expr), num(Number::Rational(1, 2)));
+    }
+
+    #[test]
+    fn test_adds_complex_numbers_component_wise() {
+        let expr = add(num(Number::Complex(1.0, 2.0)), num(Number::Complex(3.0, -1.0)));
+        assert_eq!(eval_exact(&expr), num(Number::Complex(4.0, 1.0)));
+    }
+
+    #[test]
+    fn test_symbolic_linear_combination_collects_like_terms() {
+        let expr = add(num(Number::Symbolic("x".to_string())), num(Number::Symbolic("x".to_string())));
+        assert_eq!(eval_exact(&expr), num(Number::Symbolic("2*x".to_string())));
+    }
+
+    #[test]
+    fn test_constant_folds_alongside_symbol_terms() {
+        // 3 + x + 5 -> 8 + x
+        let expr = add(add(num(Number::Int(3)), num(Number::Symbolic("x".to_string()))), num(Number::Int(5)));
+        assert_eq!(eval_exact(&expr), add(num(Number::Int(8)), num(Number::Symbolic("x".to_string()))));
+    }
+
+    #[test]
+    fn test_negate_distributes_over_symbols_and_constants() {
+        // -(x + x) -> -2*x
+        let expr = DataExpr::Negate(Box::new(add(
+            num(Number::Symbolic("x".to_string())),
+            num(Number::Symbolic("x".to_string())),
+        )));
+        assert_eq!(eval_exact(&expr), num(Number::Symbolic("-2*x".to_string())));
+    }
+
+    #[test]
+    fn test_unlike_symbols_stay_distinct_in_sorted_order() {
+        let expr = add(num(Number::Symbolic("y".to_string())), num(Number::Symbolic("x".to_string())));
+        assert_eq!(
+            eval_exact(&expr),
+            add(num(Number::Symbolic("x".to_string())), num(Number::Symbolic("y".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_exact_key_normalizes_equal_rationals_to_same_key() {
+        assert_eq!(
+            ExactKey::from_number(&Number::Rational(2, 4)),
+            ExactKey::from_number(&Number::Rational(1, 2))
+        );
+        assert_eq!(
+            ExactKey::from_number(&Number::Rational(1, -2)),
+            ExactKey::from_number(&Number::Rational(-1, 2))
+        );
+    }
+
+    #[test]
+    fn test_exact_key_is_none_for_float_and_complex() {
+        assert_eq!(ExactKey::from_number(&Number::Float(1.5)), None);
+        assert_eq!(ExactKey::from_number(&Number::Complex(1.0, 2.0)), None);
+    }
+
+    #[test]
+    fn test_bails_out_on_non_foldable_subexpression() {
+        let expr = add(num(Number::Int(1)), DataExpr::Identifier("x".to_string()));
+        assert_eq!(eval_exact(&expr), expr);
+    }
+}
@@ -21,7 +21,19 @@ pub enum Type {
     List(Box<Type>),
     Tuple(Vec<Type>),
     Function(Vec<Type>, Box<Type>),
+    /// A named record type, e.g. `Complex3D { re: Float, im: Float, w: Symbolic }`.
+    /// Two struct types unify only if their names match and every field unifies.
+    Struct(String, Vec<(String, Type)>),
     Any, // For type inference placeholder
+    /// An inference variable allocated by a [`UnificationTable`]. Stands in
+    /// for an as-yet-unknown type (an unannotated parameter, an empty list's
+    /// element type) until `unify` pins it down.
+    Var(u32),
+    /// A declared generic parameter (the `T` in `fn id<T>(x: T) -> T`) as it
+    /// appears in a function's signature, before a call site instantiates it
+    /// with a fresh [`Type::Var`]. Never reaches `unify` directly — see
+    /// [`substitute_type_params`].
+    Param(String),
 }
 
 impl Type {
@@ -44,6 +56,13 @@ impl Type {
                 // Any matches everything (for inference)
                 | (Type::Any, _)
                 | (_, Type::Any)
+                // An unresolved inference variable matches everything too
+                | (Type::Var(_), _)
+                | (_, Type::Var(_))
+                // A not-yet-instantiated generic parameter matches
+                // everything within its own declaration's body
+                | (Type::Param(_), _)
+                | (_, Type::Param(_))
         )
     }
 
@@ -71,6 +90,17 @@ impl Type {
             // Any type
             (Type::Any, t) | (t, Type::Any) => Some(t.clone()),
 
+            // An unresolved inference variable takes on whatever the other
+            // side turns out to be; the caller is responsible for binding
+            // it back in the unification table.
+            (Type::Var(_), t) | (t, Type::Var(_)) => Some(t.clone()),
+
+            // Inside a generic function's own body, its declared type
+            // parameters are opaque but assumed well-formed — a real
+            // Numeric bound violation is caught at the call site instead,
+            // once the parameter is instantiated to a concrete type.
+            (Type::Param(_), t) | (t, Type::Param(_)) => Some(t.clone()),
+
             _ => None,
         }
     }
@@ -86,6 +116,8 @@ impl Type {
             Type::Binary => Some(Type::Binary),
             Type::Symbolic => Some(Type::Symbolic),
             Type::Any => Some(Type::Any),
+            Type::Var(_) => Some(self.clone()),
+            Type::Param(_) => Some(self.clone()),
             _ => None,
         }
     }
@@ -125,7 +157,10 @@ impl std::fmt::Display for Type {
                 }
                 write!(f, ") -> {}", ret)
             }
+            Type::Struct(name, _) => write!(f, "{}", name),
             Type::Any => write!(f, "Any"),
+            Type::Var(id) => write!(f, "?{}", id),
+            Type::Param(name) => write!(f, "{}", name),
         }
     }
 }
@@ -135,6 +170,14 @@ impl std::fmt::Display for Type {
 pub struct TypeEnv {
     vars: HashMap<String, Type>,
     funcs: HashMap<String, (Vec<Type>, Type, Purity)>, // (params, return, purity)
+    structs: HashMap<String, Vec<(String, Type)>>,
+    /// Declared type parameters per generic function, keyed by function
+    /// name, in declaration order with their optional bound.
+    generics: HashMap<String, Vec<(String, Option<TypeBound>)>>,
+    /// Per struct name, `(has_eq, has_lt)` — whether an `eq`/`lt` function
+    /// over two values of that struct has been declared, so the rest of the
+    /// relational operators can be derived from it.
+    struct_operators: HashMap<String, (bool, bool)>,
 }
 
 impl TypeEnv {
@@ -142,6 +185,9 @@ impl TypeEnv {
         TypeEnv {
             vars: HashMap::new(),
             funcs: HashMap::new(),
+            structs: HashMap::new(),
+            generics: HashMap::new(),
+            struct_operators: HashMap::new(),
         }
     }
 
@@ -160,6 +206,39 @@ impl TypeEnv {
     pub fn set_func(&mut self, name: String, params: Vec<Type>, ret: Type, purity: Purity) {
         self.funcs.insert(name, (params, ret, purity));
     }
+
+    pub fn get_struct(&self, name: &str) -> Option<&Vec<(String, Type)>> {
+        self.structs.get(name)
+    }
+
+    pub fn set_struct(&mut self, name: String, fields: Vec<(String, Type)>) {
+        self.structs.insert(name, fields);
+    }
+
+    pub fn get_generics(&self, func_name: &str) -> Option<&Vec<(String, Option<TypeBound>)>> {
+        self.generics.get(func_name)
+    }
+
+    pub fn set_generics(&mut self, func_name: String, params: Vec<(String, Option<TypeBound>)>) {
+        self.generics.insert(func_name, params);
+    }
+
+    /// Record that `struct_name` has an `eq` (`is_lt = false`) or `lt`
+    /// (`is_lt = true`) function defined over it.
+    pub fn mark_operator(&mut self, struct_name: String, is_lt: bool) {
+        let entry = self.struct_operators.entry(struct_name).or_insert((false, false));
+        if is_lt {
+            entry.1 = true;
+        } else {
+            entry.0 = true;
+        }
+    }
+
+    /// `(has_eq, has_lt)` for `struct_name`, both `false` if neither was
+    /// ever declared.
+    pub fn operators_for(&self, struct_name: &str) -> (bool, bool) {
+        self.struct_operators.get(struct_name).copied().unwrap_or((false, false))
+    }
 }
 
 impl Default for TypeEnv {
@@ -168,50 +247,140 @@ impl Default for TypeEnv {
     }
 }
 
-/// Type checker for JtV programs
-pub struct TypeChecker {
-    env: TypeEnv,
-    errors: Vec<JtvError>,
-    /// Track type constraints for inference
-    constraints: Vec<TypeConstraint>,
-    /// Current function return type (for return statement checking)
-    expected_return: Option<Type>,
-}
-
-/// Type constraint for inference
-#[derive(Debug, Clone)]
-pub struct TypeConstraint {
-    pub lhs: Type,
-    pub rhs: Type,
-    pub context: String,
+/// Union-find table backing real Hindley-Milner-style inference: each
+/// `Type::Var` id maps to either an unbound placeholder or a type it has
+/// since been unified with. Unannotated parameters, empty-list elements,
+/// and other not-yet-known types get a fresh variable here instead of
+/// collapsing straight to `Type::Any`, and `unify` refines it as more of
+/// the program is visited.
+#[derive(Debug, Clone, Default)]
+pub struct UnificationTable {
+    bindings: Vec<Option<Type>>,
+    /// For a variable born from a numeric literal, the set of concrete
+    /// numeric types it may still resolve to. `None` means unrestricted
+    /// (an ordinary type variable, not a numeric placeholder).
+    numeric_bounds: Vec<Option<Vec<Type>>>,
 }
 
-impl TypeChecker {
+impl UnificationTable {
     pub fn new() -> Self {
-        TypeChecker {
-            env: TypeEnv::new(),
-            errors: vec![],
-            constraints: vec![],
-            expected_return: None,
+        UnificationTable { bindings: Vec::new(), numeric_bounds: Vec::new() }
+    }
+
+    /// Allocate a fresh, as-yet-unbound type variable.
+    pub fn fresh_var(&mut self) -> Type {
+        let id = self.bindings.len() as u32;
+        self.bindings.push(None);
+        self.numeric_bounds.push(None);
+        Type::Var(id)
+    }
+
+    /// Allocate a fresh type variable restricted to the numeric types in
+    /// `bound` — used for numeric literals, whose final type depends on how
+    /// they're used (`5` as `Int`, or as `Float` once added to one).
+    pub fn fresh_numeric_var(&mut self, bound: Vec<Type>) -> Type {
+        let id = self.bindings.len() as u32;
+        self.bindings.push(None);
+        self.numeric_bounds.push(Some(bound));
+        Type::Var(id)
+    }
+
+    /// Follow a variable's binding chain to its current representative.
+    /// Concrete types, and variables with no binding yet, come back
+    /// unchanged.
+    pub fn find(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Var(id) => match self.bindings.get(*id as usize).and_then(|b| b.as_ref()) {
+                Some(bound) => self.find(bound),
+                None => ty.clone(),
+            },
+            _ => ty.clone(),
         }
     }
 
-    /// Add a type constraint for inference
-    fn add_constraint(&mut self, lhs: Type, rhs: Type, context: &str) {
-        self.constraints.push(TypeConstraint {
-            lhs,
-            rhs,
-            context: context.to_string(),
-        });
+    /// Does variable `id` occur (transitively, through bindings) inside
+    /// `ty`? Rejects infinite types like `T = List<T>` before we'd build
+    /// one.
+    fn occurs_in(&self, id: u32, ty: &Type) -> bool {
+        match self.find(ty) {
+            Type::Var(other) => other == id,
+            Type::List(inner) => self.occurs_in(id, &inner),
+            Type::Tuple(items) => items.iter().any(|t| self.occurs_in(id, t)),
+            Type::Function(params, ret) => {
+                params.iter().any(|t| self.occurs_in(id, t)) || self.occurs_in(id, &ret)
+            }
+            Type::Struct(_, fields) => fields.iter().any(|(_, t)| self.occurs_in(id, t)),
+            _ => false,
+        }
+    }
+
+    /// Bind an unbound variable directly to a concrete type, failing the
+    /// occurs check rather than constructing an infinite type.
+    pub fn bind(&mut self, id: u32, ty: Type) -> bool {
+        if self.occurs_in(id, &ty) {
+            return false;
+        }
+        match &ty {
+            // Two numeric placeholders meeting: narrow to whichever
+            // concrete types both could still resolve to, and move the
+            // narrowed bound onto the variable that survives as the
+            // representative.
+            Type::Var(other) => {
+                match (self.numeric_bounds[id as usize].clone(), self.numeric_bounds[*other as usize].clone()) {
+                    (Some(mine), Some(theirs)) => {
+                        let intersection: Vec<Type> =
+                            mine.into_iter().filter(|t| theirs.contains(t)).collect();
+                        if intersection.is_empty() {
+                            return false;
+                        }
+                        self.numeric_bounds[*other as usize] = Some(intersection);
+                    }
+                    (Some(mine), None) => {
+                        self.numeric_bounds[*other as usize] = Some(mine);
+                    }
+                    (None, _) => {}
+                }
+            }
+            // A numeric placeholder meeting a concrete type: it must be a
+            // member of the placeholder's bound, or the literal is being
+            // used somewhere it can never be (e.g. a float literal where
+            // only `Int`/`Hex`/`Binary` make sense).
+            _ => {
+                if let Some(allowed) = &self.numeric_bounds[id as usize] {
+                    if !allowed.contains(&ty) {
+                        return false;
+                    }
+                }
+            }
+        }
+        self.bindings[id as usize] = Some(ty);
+        true
     }
 
-    /// Unify two types, returning the unified type or None if incompatible
-    fn unify(&self, t1: &Type, t2: &Type) -> Option<Type> {
-        if t1 == t2 {
-            return Some(t1.clone());
+    /// Unify two types, binding any unresolved variables along the way.
+    /// Returns the unified type, or `None` if the two can never agree.
+    pub fn unify(&mut self, t1: &Type, t2: &Type) -> Option<Type> {
+        let a = self.find(t1);
+        let b = self.find(t2);
+
+        if a == b {
+            return Some(a);
         }
 
-        match (t1, t2) {
+        match (&a, &b) {
+            (Type::Var(id), _) => {
+                if !self.bind(*id, b.clone()) {
+                    return None;
+                }
+                Some(b)
+            }
+            (_, Type::Var(id)) => {
+                if !self.bind(*id, a.clone()) {
+                    return None;
+                }
+                Some(a)
+            }
+
             // Any unifies with anything
             (Type::Any, t) | (t, Type::Any) => Some(t.clone()),
 
@@ -224,26 +393,36 @@ impl TypeChecker {
             (Type::Binary, Type::Int) | (Type::Int, Type::Binary) => Some(Type::Int),
 
             // Lists unify if their element types unify
-            (Type::List(a), Type::List(b)) => {
-                self.unify(a, b).map(|t| Type::List(Box::new(t)))
-            }
+            (Type::List(x), Type::List(y)) => self.unify(x, y).map(|t| Type::List(Box::new(t))),
 
             // Tuples unify if all elements unify
-            (Type::Tuple(a), Type::Tuple(b)) if a.len() == b.len() => {
-                let unified: Option<Vec<Type>> = a
+            (Type::Tuple(xs), Type::Tuple(ys)) if xs.len() == ys.len() => {
+                let unified: Option<Vec<Type>> = xs
                     .iter()
-                    .zip(b.iter())
-                    .map(|(t1, t2)| self.unify(t1, t2))
+                    .zip(ys.iter())
+                    .map(|(x, y)| self.unify(x, y))
                     .collect();
                 unified.map(Type::Tuple)
             }
 
+            // Structs unify only if they're the same named type and every
+            // field unifies; field order is irrelevant, so look each up by
+            // name rather than zipping positionally.
+            (Type::Struct(n1, f1), Type::Struct(n2, f2)) if n1 == n2 && f1.len() == f2.len() => {
+                let mut unified_fields = Vec::with_capacity(f1.len());
+                for (field_name, field_ty) in f1 {
+                    let other_ty = f2.iter().find(|(n, _)| n == field_name).map(|(_, t)| t)?;
+                    unified_fields.push((field_name.clone(), self.unify(field_ty, other_ty)?));
+                }
+                Some(Type::Struct(n1.clone(), unified_fields))
+            }
+
             // Functions unify if params and return types unify
-            (Type::Function(p1, r1), Type::Function(p2, r2)) if p1.len() == p2.len() => {
-                let unified_params: Option<Vec<Type>> = p1
+            (Type::Function(ps1, r1), Type::Function(ps2, r2)) if ps1.len() == ps2.len() => {
+                let unified_params: Option<Vec<Type>> = ps1
                     .iter()
-                    .zip(p2.iter())
-                    .map(|(t1, t2)| self.unify(t1, t2))
+                    .zip(ps2.iter())
+                    .map(|(x, y)| self.unify(x, y))
                     .collect();
                 let unified_ret = self.unify(r1, r2);
                 match (unified_params, unified_ret) {
@@ -256,6 +435,396 @@ impl TypeChecker {
         }
     }
 
+    /// Replace every resolved `Type::Var` in `ty` with its representative,
+    /// defaulting a variable that was never constrained to `Type::Any`.
+    pub fn zonk(&self, ty: &Type) -> Type {
+        match self.find(ty) {
+            // A numeric placeholder that was never pinned down by usage
+            // defaults to `Int` if that's still an option (it came from an
+            // integer literal), otherwise `Float` (it came from a float
+            // literal, which can never settle on `Int`).
+            Type::Var(id) => match &self.numeric_bounds[id as usize] {
+                Some(allowed) if allowed.contains(&Type::Int) => Type::Int,
+                Some(_) => Type::Float,
+                None => Type::Any,
+            },
+            Type::List(inner) => Type::List(Box::new(self.zonk(&inner))),
+            Type::Tuple(items) => Type::Tuple(items.iter().map(|t| self.zonk(t)).collect()),
+            Type::Function(params, ret) => Type::Function(
+                params.iter().map(|t| self.zonk(t)).collect(),
+                Box::new(self.zonk(&ret)),
+            ),
+            Type::Struct(name, fields) => Type::Struct(
+                name,
+                fields.into_iter().map(|(n, t)| (n, self.zonk(&t))).collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+/// Replace every `Type::Param` in `ty` with its instantiation from `subst`,
+/// leaving any name not present (shouldn't happen for a well-formed call)
+/// as-is. Used to turn a generic function's declared signature into the
+/// concrete signature for one call site.
+fn substitute_type_params(ty: &Type, subst: &HashMap<String, Type>) -> Type {
+    match ty {
+        Type::Param(name) => subst.get(name).cloned().unwrap_or_else(|| ty.clone()),
+        Type::List(inner) => Type::List(Box::new(substitute_type_params(inner, subst))),
+        Type::Tuple(items) => {
+            Type::Tuple(items.iter().map(|t| substitute_type_params(t, subst)).collect())
+        }
+        Type::Function(params, ret) => Type::Function(
+            params.iter().map(|t| substitute_type_params(t, subst)).collect(),
+            Box::new(substitute_type_params(ret, subst)),
+        ),
+        Type::Struct(name, fields) => Type::Struct(
+            name.clone(),
+            fields
+                .iter()
+                .map(|(n, t)| (n.clone(), substitute_type_params(t, subst)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Collect the name of every `Type::Param` reachable from `ty`, in first-seen
+/// order with no duplicates. Used to discover which type variables a
+/// function's signature quantifies over, whether declared via `<T>` or just
+/// referenced inline in a parameter/return annotation.
+fn collect_param_names(ty: &Type, out: &mut Vec<String>) {
+    match ty {
+        Type::Param(name) => {
+            if !out.contains(name) {
+                out.push(name.clone());
+            }
+        }
+        Type::List(inner) => collect_param_names(inner, out),
+        Type::Tuple(items) => items.iter().for_each(|t| collect_param_names(t, out)),
+        Type::Function(params, ret) => {
+            params.iter().for_each(|t| collect_param_names(t, out));
+            collect_param_names(ret, out);
+        }
+        Type::Struct(_, fields) => fields.iter().for_each(|(_, t)| collect_param_names(t, out)),
+        _ => {}
+    }
+}
+
+/// Does `ty` satisfy `bound`? `None` (no bound declared) always passes.
+fn satisfies_bound(ty: &Type, bound: &Option<TypeBound>) -> bool {
+    match bound {
+        None => true,
+        Some(TypeBound::Numeric) => matches!(
+            ty,
+            Type::Int
+                | Type::Float
+                | Type::Rational
+                | Type::Complex
+                | Type::Hex
+                | Type::Binary
+                | Type::Symbolic
+        ),
+    }
+}
+
+/// Resolve a byte offset into 1-based (line, column) plus the text of that
+/// line, for rendering a `Diagnostic`'s span as a source excerpt.
+fn line_and_column(source: &str, offset: usize) -> Option<(usize, usize, &str)> {
+    let mut consumed = 0;
+    for (line_no, line) in source.lines().enumerate() {
+        let line_end = consumed + line.len();
+        if offset <= line_end {
+            return Some((line_no + 1, offset - consumed + 1, line));
+        }
+        consumed = line_end + 1; // +1 for the newline
+    }
+    None
+}
+
+/// Type checker for JtV programs
+pub struct TypeChecker {
+    env: TypeEnv,
+    errors: Vec<JtvError>,
+    /// Every problem found so far, accumulated rather than short-circuited
+    /// on the first one. See [`TypeChecker::diagnostics`].
+    diagnostics: Vec<Diagnostic>,
+    /// Track type constraints for inference
+    constraints: Vec<TypeConstraint>,
+    /// Union-find table solving the constraints above and resolving the
+    /// `Type::Var`s allocated for unannotated bindings.
+    table: UnificationTable,
+    /// Current function return type (for return statement checking)
+    expected_return: Option<Type>,
+    /// Name, declared purity, and declaration span of the function whose
+    /// body is currently being checked, for effect-checking `Print`/
+    /// `ReverseBlock`/impure calls against it and for attributing
+    /// span-carrying diagnostics. `None` at the top level.
+    current_function: Option<(String, Purity, Span)>,
+    /// Every `UnificationFailure` diagnostic reported while a function body
+    /// was being checked, carried over as a span-carrying
+    /// `crate::diagnostics::Diagnostic` attributed to that function's
+    /// declaration span -- individual expressions don't have their own
+    /// spans yet, so this is as precise as location gets today.
+    span_diagnostics: Vec<crate::diagnostics::Diagnostic>,
+    /// Every parameter or return type the checker filled in because its
+    /// `TypeAnnotation` was omitted, reported back the way an editor's
+    /// "infer type annotations" action would.
+    inferred_annotations: Vec<InferredAnnotation>,
+}
+
+/// Where an [`InferredAnnotation`] applies.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InferredAnnotationTarget {
+    Param { function: String, param: String },
+    Return { function: String },
+}
+
+/// A type the checker worked out for a parameter or return position whose
+/// `TypeAnnotation` was left out, surfaced the way an editor's "infer type
+/// annotations" action reports them back to the user rather than silently
+/// using the inferred type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredAnnotation {
+    pub target: InferredAnnotationTarget,
+    pub ty: Type,
+}
+
+/// Type constraint for inference
+#[derive(Debug, Clone)]
+pub struct TypeConstraint {
+    pub lhs: Type,
+    pub rhs: Type,
+    pub context: String,
+    /// Byte-offset span in the source the constraint came from, once the
+    /// AST carries spans. `None` until then.
+    pub span: Option<(usize, usize)>,
+}
+
+/// How serious a [`Diagnostic`] is. Only `Error` is produced today; the
+/// variant exists so warning-level checks (e.g. an unreachable branch) have
+/// somewhere to report without inventing a parallel type later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A structured classification of a [`Diagnostic`], so a caller (a future
+/// LSP backend, or a test) can match on the shape of a problem instead of
+/// parsing `Diagnostic::message`. `Other` covers every case not yet worth
+/// breaking out into its own variant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeErrorKind {
+    UndefinedVariable(String),
+    UndefinedFunction(String),
+    ArityMismatch { expected: usize, got: usize },
+    UnificationFailure { expected: Type, found: Type },
+    IndexOutOfBounds { index: i64, len: usize },
+    Other,
+}
+
+/// A single located problem found while type checking. Unlike `JtvError`,
+/// which `check_program` still returns to keep its `Result<()>` contract,
+/// diagnostics accumulate — `check_program` keeps walking after recording
+/// one instead of bailing, so a file with ten mistakes reports all ten in
+/// a single pass.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub kind: TypeErrorKind,
+    /// Byte-offset span into the source, when available. AST nodes don't
+    /// carry spans yet, so this is `None` until that lands.
+    pub span: Option<(usize, usize)>,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// Derive a [`TypeErrorKind`] from the `JtvError` `report` was given, for
+/// the shapes that already carry enough structure to classify on their own.
+fn classify_error(err: &JtvError) -> TypeErrorKind {
+    match err {
+        JtvError::UndefinedVariable(name) => TypeErrorKind::UndefinedVariable(name.clone()),
+        JtvError::UndefinedFunction(name) => TypeErrorKind::UndefinedFunction(name.clone()),
+        JtvError::ArityMismatch { expected, got } => {
+            TypeErrorKind::ArityMismatch { expected: *expected, got: *got }
+        }
+        _ => TypeErrorKind::Other,
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            env: TypeEnv::new(),
+            errors: vec![],
+            diagnostics: vec![],
+            constraints: vec![],
+            table: UnificationTable::new(),
+            expected_return: None,
+            current_function: None,
+            span_diagnostics: vec![],
+            inferred_annotations: vec![],
+        }
+    }
+
+    /// Every `UnificationFailure`/`UndefinedVariable`/`UndefinedFunction`
+    /// found during the last `check_program` call, as a span-carrying
+    /// `crate::diagnostics::Diagnostic` rather than this module's own
+    /// `Diagnostic`. See [`TypeChecker::diagnostics`] for the full,
+    /// unfiltered list (including kinds, like `ArityMismatch`, that don't
+    /// have a span representation here yet).
+    pub fn span_diagnostics(&self) -> &[crate::diagnostics::Diagnostic] {
+        &self.span_diagnostics
+    }
+
+    /// Every parameter/return type the checker inferred because its
+    /// annotation was omitted, collected across the last `check_program`
+    /// call.
+    pub fn inferred_annotations(&self) -> &[InferredAnnotation] {
+        &self.inferred_annotations
+    }
+
+    /// All diagnostics accumulated by the last `check_program` call, most
+    /// recently reported last.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Render accumulated diagnostics as a labeled report. Spans are shown
+    /// as an underlined excerpt of `source` when present; otherwise the
+    /// diagnostic is rendered as a plain located-less error line.
+    pub fn render_diagnostics(&self, source: &str) -> String {
+        let mut out = String::new();
+        for diag in &self.diagnostics {
+            let label = match diag.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            out.push_str(&format!("{}: {}\n", label, diag.message));
+            if let Some((start, end)) = diag.span {
+                if let Some(line_info) = line_and_column(source, start) {
+                    let (line_no, col_no, line_text) = line_info;
+                    let underline_len = end.saturating_sub(start).max(1);
+                    out.push_str(&format!("  --> line {}:{}\n", line_no, col_no));
+                    out.push_str(&format!("   | {}\n", line_text));
+                    out.push_str(&format!(
+                        "   | {}{}\n",
+                        " ".repeat(col_no.saturating_sub(1)),
+                        "^".repeat(underline_len)
+                    ));
+                }
+            }
+            if let Some(suggestion) = &diag.suggestion {
+                out.push_str(&format!("   = help: {}\n", suggestion));
+            }
+        }
+        out
+    }
+
+    /// Record a type error and return `Type::Any` so the caller can keep
+    /// walking instead of bailing on the first mistake — this is what lets
+    /// `check_program` surface every error in a file, not just the first.
+    fn report(&mut self, err: JtvError, suggestion: Option<String>) -> Type {
+        let kind = classify_error(&err);
+        self.report_typed(kind, err, suggestion)
+    }
+
+    /// Like [`TypeChecker::report`], but with an explicit [`TypeErrorKind`]
+    /// for call sites that have more structure on hand (e.g. the two
+    /// resolved types of a failed unification) than `classify_error` can
+    /// recover from the rendered `JtvError` alone.
+    fn report_typed(&mut self, kind: TypeErrorKind, err: JtvError, suggestion: Option<String>) -> Type {
+        // None of `UndefinedVariable`/`UndefinedFunction`/`UnificationFailure`
+        // have a span of their own yet -- no call site threads one in -- so
+        // this falls back to the span of the function currently being
+        // checked, same as the other two. Coarser than pointing at the exact
+        // identifier, but still enough for an editor to land on the right
+        // line instead of line 1 for every error.
+        let span_kind = match &kind {
+            TypeErrorKind::UnificationFailure { expected, found } => Some(
+                crate::diagnostics::DiagnosticKind::TypeMismatch {
+                    expected: expected.to_string(),
+                    found: found.to_string(),
+                },
+            ),
+            TypeErrorKind::UndefinedVariable(name) => {
+                Some(crate::diagnostics::DiagnosticKind::UndefinedVariable { name: name.clone() })
+            }
+            TypeErrorKind::UndefinedFunction(name) => {
+                Some(crate::diagnostics::DiagnosticKind::UndefinedFunction { name: name.clone() })
+            }
+            _ => None,
+        };
+        if let Some(span_kind) = span_kind {
+            let location = self
+                .current_function
+                .as_ref()
+                .map(|(_, _, span)| *span)
+                .unwrap_or_else(Span::unknown);
+            self.span_diagnostics.push(crate::diagnostics::Diagnostic::at(location, span_kind));
+        }
+
+        self.diagnostics.push(Diagnostic {
+            kind,
+            span: None,
+            severity: Severity::Error,
+            message: err.to_string(),
+            suggestion,
+        });
+        self.errors.push(err);
+        Type::Any
+    }
+
+    /// Report a `PurityViolation` if the function whose body is currently
+    /// being checked is declared `@pure` — pure functions may not perform
+    /// IO, mutate state via a reverse block, or call an impure function.
+    /// A no-op outside a function body, or inside a `Total`/default-impure
+    /// one, since only `Pure` forbids effects outright.
+    fn check_effect(&mut self, reason: &str) {
+        if let Some((name, Purity::Pure, _)) = self.current_function.clone() {
+            self.report(
+                JtvError::PurityViolation(format!("'{}' {}", name, reason)),
+                None,
+            );
+        }
+    }
+
+    /// Add a type constraint for inference
+    fn add_constraint(&mut self, lhs: Type, rhs: Type, context: &str) {
+        self.constraints.push(TypeConstraint {
+            lhs,
+            rhs,
+            context: context.to_string(),
+            span: None,
+        });
+    }
+
+    /// Drain and solve every constraint recorded since the last solve,
+    /// against the unification table. Most constraints are already
+    /// satisfied by the eager unification performed during inference; this
+    /// pass exists to catch the ones that could only be connected once
+    /// both sides had been visited.
+    fn solve_constraints(&mut self) -> Result<()> {
+        let constraints = std::mem::take(&mut self.constraints);
+        for constraint in constraints {
+            if self.table.unify(&constraint.lhs, &constraint.rhs).is_none() {
+                return Err(JtvError::TypeError(format!(
+                    "Cannot unify {} and {} ({})",
+                    constraint.lhs, constraint.rhs, constraint.context
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Unify two types, returning the unified type or None if incompatible.
+    /// Delegates to the `UnificationTable` so variables get resolved as a
+    /// side effect.
+    fn unify(&mut self, t1: &Type, t2: &Type) -> Option<Type> {
+        self.table.unify(t1, t2)
+    }
+
     /// Get a helpful suggestion for type mismatches
     fn type_suggestion(&self, expected: &Type, got: &Type) -> String {
         match (expected, got) {
@@ -269,10 +838,14 @@ impl TypeChecker {
 
     /// Check a complete program
     pub fn check_program(&mut self, program: &Program) -> Result<()> {
-        // First pass: collect function signatures
+        // First pass: collect struct and function signatures, so a function
+        // can reference a struct declared later in the file, and a struct
+        // literal can be type-checked against a declaration in either order.
         for stmt in &program.statements {
-            if let TopLevel::Function(func) = stmt {
-                self.register_function(func)?;
+            match stmt {
+                TopLevel::Struct(decl) => self.register_struct(decl),
+                TopLevel::Function(func) => self.register_function(func)?,
+                _ => {}
             }
         }
 
@@ -281,6 +854,10 @@ impl TypeChecker {
             self.check_top_level(stmt)?;
         }
 
+        // Solve pass: resolve every constraint gathered during inference
+        // against the union-find table.
+        self.solve_constraints()?;
+
         if self.errors.is_empty() {
             Ok(())
         } else {
@@ -289,25 +866,77 @@ impl TypeChecker {
     }
 
     fn register_function(&mut self, func: &FunctionDecl) -> Result<()> {
-        let params: Vec<Type> = func
-            .params
+        let mut generics: Vec<(String, Option<TypeBound>)> = func
+            .type_params
             .iter()
-            .map(|p| self.annotation_to_type(&p.type_annotation))
+            .map(|tp| (tp.name.clone(), tp.bound.clone()))
             .collect();
 
+        let mut params: Vec<Type> = Vec::new();
+        for (i, param) in func.params.iter().enumerate() {
+            match &param.type_annotation {
+                // An unannotated parameter is implicitly polymorphic: quantify
+                // over it like an explicit `<T>` so each call site instantiates
+                // a fresh variable, instead of pinning the function's one
+                // stored signature to whichever argument type unifies first.
+                None => params.push(Type::Param(format!("'{}#{}", func.name, i))),
+                Some(_) => params.push(self.annotation_to_type(&param.type_annotation)),
+            }
+        }
+
         let ret = func
             .return_type
             .as_ref()
             .map(|t| self.annotation_to_type(&Some(t.clone())))
             .unwrap_or(Type::Unit);
 
+        // Any `Type::Param` reachable from the signature — whether declared
+        // via `<T>` or just written inline as `x: T` — is a quantified
+        // variable of this function's type scheme and must be freshly
+        // instantiated at every call site, not shared across calls.
+        let mut scheme_vars = Vec::new();
+        for ty in params.iter().chain(std::iter::once(&ret)) {
+            collect_param_names(ty, &mut scheme_vars);
+        }
+        for name in scheme_vars {
+            if !generics.iter().any(|(n, _)| n == &name) {
+                generics.push((name, None));
+            }
+        }
+
+        // A function literally named `eq` or `lt`, taking two arguments of
+        // the same struct type, is that struct's equality/ordering
+        // definition — every other relational operator on that type is
+        // derived from it (`!=` from `==`; `>`, `<=`, `>=` from `<`) rather
+        // than requiring the user to write all six by hand.
+        if let [Type::Struct(name, _), Type::Struct(other, _)] = params.as_slice() {
+            if name == other && (func.name == "eq" || func.name == "lt") {
+                self.env.mark_operator(name.clone(), func.name == "lt");
+            }
+        }
+
+        self.env.set_generics(func.name.clone(), generics);
         self.env.set_func(func.name.clone(), params, ret, func.purity.clone());
         Ok(())
     }
 
-    fn annotation_to_type(&self, ann: &Option<TypeAnnotation>) -> Type {
+    /// Register a struct declaration so its field types are known before any
+    /// field access or struct literal referencing it is checked.
+    fn register_struct(&mut self, decl: &StructDecl) {
+        let fields: Vec<(String, Type)> = decl
+            .fields
+            .iter()
+            .map(|(name, ann)| (name.clone(), self.annotation_to_type(&Some(ann.clone()))))
+            .collect();
+        self.env.set_struct(decl.name.clone(), fields);
+    }
+
+    fn annotation_to_type(&mut self, ann: &Option<TypeAnnotation>) -> Type {
         match ann {
-            None => Type::Any,
+            // No annotation: allocate a fresh inference variable instead of
+            // collapsing straight to `Any`, so usage inside the body can
+            // pin down a concrete type.
+            None => self.table.fresh_var(),
             Some(TypeAnnotation::Basic(basic)) => match basic {
                 BasicType::Int => Type::Int,
                 BasicType::Float => Type::Float,
@@ -335,6 +964,7 @@ impl TypeChecker {
                     Box::new(self.annotation_to_type(&Some(*ret.clone()))),
                 )
             }
+            Some(TypeAnnotation::Generic(name)) => Type::Param(name.clone()),
         }
     }
 
@@ -347,7 +977,9 @@ impl TypeChecker {
                 Ok(())
             }
             TopLevel::Import(_) => Ok(()), // Imports handled separately
+            TopLevel::Struct(_) => Ok(()), // Already registered in the first pass
             TopLevel::Function(func) => self.check_function(func),
+            TopLevel::Test(_) => Ok(()), // `jtv test` type-checks test bodies itself
             TopLevel::Control(stmt) => {
                 self.check_control_stmt(stmt)?;
                 Ok(())
@@ -359,6 +991,8 @@ impl TypeChecker {
         // Create new scope with parameters
         let old_env = self.env.clone();
         let old_expected_return = self.expected_return.clone();
+        let old_current_function = self.current_function.clone();
+        self.current_function = Some((func.name.clone(), func.purity.clone(), func.span));
 
         // Set expected return type
         let expected_ret = func
@@ -368,8 +1002,16 @@ impl TypeChecker {
             .unwrap_or(Type::Unit);
         self.expected_return = Some(expected_ret.clone());
 
+        // Parameters left without a `TypeAnnotation` get a fresh inference
+        // variable here (see `annotation_to_type`); remembered so that once
+        // the body has pinned it down, the resolved type can be reported
+        // back as an inferred annotation instead of just used silently.
+        let mut unannotated_params = Vec::new();
         for param in &func.params {
             let ty = self.annotation_to_type(&param.type_annotation);
+            if param.type_annotation.is_none() {
+                unannotated_params.push((param.name.clone(), ty.clone()));
+            }
             self.env.set_var(param.name.clone(), ty);
         }
 
@@ -381,22 +1023,47 @@ impl TypeChecker {
             }
         }
 
-        // Verify inferred return matches declared return
+        // Verify inferred return matches declared return. Reported rather
+        // than bailed on, so the rest of the program still gets checked.
         if expected_ret != Type::Unit && inferred_return != Type::Any {
             if self.unify(&expected_ret, &inferred_return).is_none() {
-                return Err(JtvError::TypeError(format!(
-                    "Function '{}' declares return type {} but returns {}. {}",
-                    func.name,
-                    expected_ret,
-                    inferred_return,
-                    self.type_suggestion(&expected_ret, &inferred_return)
-                )));
+                let resolved_expected = self.table.zonk(&expected_ret);
+                let resolved_inferred = self.table.zonk(&inferred_return);
+                let suggestion = self.type_suggestion(&resolved_expected, &resolved_inferred);
+                self.report_typed(
+                    TypeErrorKind::UnificationFailure {
+                        expected: resolved_expected.clone(),
+                        found: resolved_inferred.clone(),
+                    },
+                    JtvError::TypeError(format!(
+                        "Function '{}' declares return type {} but returns {}",
+                        func.name, resolved_expected, resolved_inferred
+                    )),
+                    Some(suggestion),
+                );
             }
         }
 
+        for (name, ty) in unannotated_params {
+            self.inferred_annotations.push(InferredAnnotation {
+                target: InferredAnnotationTarget::Param {
+                    function: func.name.clone(),
+                    param: name,
+                },
+                ty: self.table.zonk(&ty),
+            });
+        }
+        if func.return_type.is_none() {
+            self.inferred_annotations.push(InferredAnnotation {
+                target: InferredAnnotationTarget::Return { function: func.name.clone() },
+                ty: self.table.zonk(&inferred_return),
+            });
+        }
+
         // Restore environment
         self.env = old_env;
         self.expected_return = old_expected_return;
+        self.current_function = old_current_function;
         Ok(())
     }
 
@@ -410,15 +1077,18 @@ impl TypeChecker {
                     Type::Unit
                 };
 
-                // Check against expected return type
-                if let Some(expected) = &self.expected_return {
-                    if self.unify(expected, &ret_ty).is_none() {
-                        return Err(JtvError::TypeError(format!(
-                            "Return type mismatch: expected {}, got {}. {}",
-                            expected,
-                            ret_ty,
-                            self.type_suggestion(expected, &ret_ty)
-                        )));
+                // Check against expected return type; report and keep
+                // walking rather than aborting the rest of the function.
+                if let Some(expected) = self.expected_return.clone() {
+                    if self.unify(&expected, &ret_ty).is_none() {
+                        let suggestion = self.type_suggestion(&expected, &ret_ty);
+                        self.report(
+                            JtvError::TypeError(format!(
+                                "Return type mismatch: expected {}, got {}",
+                                expected, ret_ty
+                            )),
+                            Some(suggestion),
+                        );
                     }
                 }
 
@@ -522,6 +1192,7 @@ impl TypeChecker {
                 Ok(())
             }
             ControlStmt::Print(exprs) => {
+                self.check_effect("performs IO via `print`");
                 for expr in exprs {
                     self.infer_data_expr(expr)?;
                 }
@@ -539,14 +1210,17 @@ impl TypeChecker {
                 }
                 Ok(())
             }
+            ControlStmt::Break(_) | ControlStmt::Continue(_) => Ok(()),
         }
     }
 
     fn check_reversible_stmt(&mut self, stmt: &ReversibleStmt) -> Result<()> {
         match stmt {
             ReversibleStmt::AddAssign(target, expr) | ReversibleStmt::SubAssign(target, expr) => {
+                self.check_effect(&format!("mutates `{}` inside a reverse block", target));
                 let expr_ty = self.infer_data_expr(expr)?;
                 let target_ty = self.env.get_var(target).cloned().unwrap_or(Type::Any);
+                let target_ty = self.table.find(&target_ty);
 
                 if target_ty.add_result(&expr_ty).is_none() {
                     return Err(JtvError::TypeError(format!(
@@ -556,6 +1230,26 @@ impl TypeChecker {
                 }
                 Ok(())
             }
+            ReversibleStmt::MulAssign(target, expr) | ReversibleStmt::DivAssign(target, expr) => {
+                self.check_effect(&format!("mutates `{}` inside a reverse block", target));
+                let expr_ty = self.infer_data_expr(expr)?;
+                let target_ty = self.env.get_var(target).cloned().unwrap_or(Type::Any);
+                let target_ty = self.table.find(&target_ty);
+
+                if target_ty.add_result(&expr_ty).is_none() {
+                    return Err(JtvError::TypeError(format!(
+                        "Cannot multiply {} by {}",
+                        target_ty, expr_ty
+                    )));
+                }
+                Ok(())
+            }
+            ReversibleStmt::Assign(target, expr) => {
+                self.check_effect(&format!("mutates `{}` inside a reverse block", target));
+                let ty = self.infer_data_expr(expr)?;
+                self.env.set_var(target.clone(), ty);
+                Ok(())
+            }
             ReversibleStmt::If(if_stmt) => {
                 self.infer_control_expr(&if_stmt.condition)?;
                 for stmt in &if_stmt.then_branch {
@@ -568,74 +1262,214 @@ impl TypeChecker {
                 }
                 Ok(())
             }
+            ReversibleStmt::For { var, from, to, step, body } => {
+                let from_ty = self.infer_data_expr(from)?;
+                let to_ty = self.infer_data_expr(to)?;
+
+                if !from_ty.coercible_to(&Type::Int) {
+                    return Err(JtvError::TypeError(format!(
+                        "Range start must be Int, got {}",
+                        from_ty
+                    )));
+                }
+                if !to_ty.coercible_to(&Type::Int) {
+                    return Err(JtvError::TypeError(format!(
+                        "Range end must be Int, got {}",
+                        to_ty
+                    )));
+                }
+                if let Some(step) = step {
+                    let step_ty = self.infer_data_expr(step)?;
+                    if !step_ty.coercible_to(&Type::Int) {
+                        return Err(JtvError::TypeError(format!(
+                            "Range step must be Int, got {}",
+                            step_ty
+                        )));
+                    }
+                }
+
+                // Loop variable is Int
+                self.env.set_var(var.clone(), Type::Int);
+
+                for stmt in body {
+                    self.check_reversible_stmt(stmt)?;
+                }
+                Ok(())
+            }
+            ReversibleStmt::Switch { scrutinee, cases, default } => {
+                self.infer_data_expr(scrutinee)?;
+                for (value, body) in cases {
+                    self.infer_data_expr(value)?;
+                    for stmt in body {
+                        self.check_reversible_stmt(stmt)?;
+                    }
+                }
+                if let Some(default) = default {
+                    for stmt in default {
+                        self.check_reversible_stmt(stmt)?;
+                    }
+                }
+                Ok(())
+            }
         }
     }
 
     /// Infer the type of a Data expression
-    pub fn infer_data_expr(&self, expr: &DataExpr) -> Result<Type> {
+    pub fn infer_data_expr(&mut self, expr: &DataExpr) -> Result<Type> {
         match expr {
             DataExpr::Number(num) => Ok(self.number_type(num)),
-            DataExpr::Identifier(name) => self
-                .env
-                .get_var(name)
-                .cloned()
-                .ok_or_else(|| JtvError::UndefinedVariable(name.clone())),
+            DataExpr::Identifier(name) => match self.env.get_var(name).cloned() {
+                Some(ty) => Ok(ty),
+                None => Ok(self.report(JtvError::UndefinedVariable(name.clone()), None)),
+            },
             DataExpr::Add(left, right) => {
                 let left_ty = self.infer_data_expr(left)?;
                 let right_ty = self.infer_data_expr(right)?;
+                let resolved_left = self.table.find(&left_ty);
+                let resolved_right = self.table.find(&right_ty);
+                self.add_constraint(resolved_left.clone(), resolved_right.clone(), "operands of +");
+
+                let result_ty = match resolved_left.add_result(&resolved_right) {
+                    Some(ty) => ty,
+                    None => self.report(
+                        JtvError::TypeError(format!("Cannot add {} and {}", left_ty, right_ty)),
+                        None,
+                    ),
+                };
+
+                // An unresolved operand is now known to be whatever the
+                // other side turned out to be; bind it back.
+                if let Type::Var(id) = resolved_left {
+                    if !matches!(&result_ty, Type::Var(rid) if *rid == id) {
+                        self.table.bind(id, result_ty.clone());
+                    }
+                }
+                if let Type::Var(id) = resolved_right {
+                    if !matches!(&result_ty, Type::Var(rid) if *rid == id) {
+                        self.table.bind(id, result_ty.clone());
+                    }
+                }
 
-                left_ty.add_result(&right_ty).ok_or_else(|| {
-                    JtvError::TypeError(format!("Cannot add {} and {}", left_ty, right_ty))
-                })
+                Ok(result_ty)
             }
             DataExpr::Negate(inner) => {
                 let inner_ty = self.infer_data_expr(inner)?;
-                inner_ty.negate_result().ok_or_else(|| {
-                    JtvError::TypeError(format!("Cannot negate {}", inner_ty))
-                })
+                let resolved = self.table.find(&inner_ty);
+                let result_ty = match resolved.negate_result() {
+                    Some(ty) => ty,
+                    None => self.report(
+                        JtvError::TypeError(format!("Cannot negate {}", inner_ty)),
+                        None,
+                    ),
+                };
+                if let Type::Var(id) = resolved {
+                    if !matches!(&result_ty, Type::Var(rid) if *rid == id) {
+                        self.table.bind(id, result_ty.clone());
+                    }
+                }
+                Ok(result_ty)
             }
             DataExpr::FunctionCall(call) => {
-                if let Some((param_types, ret_ty, _)) = self.env.get_func(&call.name) {
-                    // Check argument count
+                if let Some((param_types, ret_ty, callee_purity)) = self.env.get_func(&call.name).cloned() {
+                    if callee_purity == Purity::Impure {
+                        self.check_effect(&format!("calls impure function `{}`", call.name));
+                    }
+
+                    // A generic function gets a fresh unification variable
+                    // per declared type parameter for this call, so two
+                    // calls to the same generic function can each settle
+                    // on a different concrete type.
+                    let generics = self.env.get_generics(&call.name).cloned().unwrap_or_default();
+                    let subst: HashMap<String, Type> = generics
+                        .iter()
+                        .map(|(name, _)| (name.clone(), self.table.fresh_var()))
+                        .collect();
+                    let param_types: Vec<Type> = param_types
+                        .iter()
+                        .map(|t| substitute_type_params(t, &subst))
+                        .collect();
+                    let ret_ty = substitute_type_params(&ret_ty, &subst);
+
+                    // Check argument count, but keep checking the args we
+                    // do have rather than bailing immediately.
                     if call.args.len() != param_types.len() {
-                        return Err(JtvError::ArityMismatch {
-                            expected: param_types.len(),
-                            got: call.args.len(),
-                        });
+                        self.report(
+                            JtvError::ArityMismatch {
+                                expected: param_types.len(),
+                                got: call.args.len(),
+                            },
+                            None,
+                        );
                     }
 
-                    // Check argument types
+                    // Check argument types, unifying against the
+                    // (possibly generic-instantiated, possibly still
+                    // inferred) declared parameter types.
                     for (arg, expected_ty) in call.args.iter().zip(param_types.iter()) {
                         let arg_ty = self.infer_data_expr(arg)?;
-                        if !arg_ty.coercible_to(expected_ty) {
-                            return Err(JtvError::TypeError(format!(
-                                "Expected {}, got {}",
-                                expected_ty, arg_ty
-                            )));
+                        self.add_constraint(expected_ty.clone(), arg_ty.clone(), "function call argument");
+                        if self.table.unify(expected_ty, &arg_ty).is_none() {
+                            let resolved_expected = self.table.zonk(expected_ty);
+                            let resolved_arg = self.table.zonk(&arg_ty);
+                            let suggestion = self.type_suggestion(&resolved_expected, &resolved_arg);
+                            self.report_typed(
+                                TypeErrorKind::UnificationFailure {
+                                    expected: resolved_expected.clone(),
+                                    found: resolved_arg.clone(),
+                                },
+                                JtvError::TypeError(format!(
+                                    "Expected {}, got {}",
+                                    resolved_expected, resolved_arg
+                                )),
+                                Some(suggestion),
+                            );
+                        }
+                    }
+
+                    // Every type parameter must have been pinned down by
+                    // argument unification; check its solved type honors
+                    // the declared bound (e.g. `T: Numeric`).
+                    for (name, bound) in &generics {
+                        let solved = self.table.zonk(&subst[name]);
+                        if !satisfies_bound(&solved, bound) {
+                            self.report(
+                                JtvError::TypeError(format!(
+                                    "Type parameter {} of '{}' is bound to {}, which does not satisfy its bound",
+                                    name, call.name, solved
+                                )),
+                                None,
+                            );
                         }
                     }
 
-                    Ok(ret_ty.clone())
+                    Ok(self.table.zonk(&ret_ty))
                 } else {
-                    Err(JtvError::UndefinedFunction(call.name.clone()))
+                    Ok(self.report(JtvError::UndefinedFunction(call.name.clone()), None))
                 }
             }
             DataExpr::List(elements) => {
                 if elements.is_empty() {
-                    Ok(Type::List(Box::new(Type::Any)))
+                    // The element type is unknown until something else
+                    // constrains it; a fresh variable defaults to `Any`
+                    // via `zonk` if it never is.
+                    Ok(Type::List(Box::new(self.table.fresh_var())))
                 } else {
-                    let first_ty = self.infer_data_expr(&elements[0])?;
-                    // Check all elements have compatible types
+                    let mut elem_ty = self.infer_data_expr(&elements[0])?;
                     for elem in &elements[1..] {
-                        let elem_ty = self.infer_data_expr(elem)?;
-                        if !elem_ty.coercible_to(&first_ty) && !first_ty.coercible_to(&elem_ty) {
-                            return Err(JtvError::TypeError(format!(
-                                "List elements must have consistent types: {} vs {}",
-                                first_ty, elem_ty
-                            )));
-                        }
+                        let next_ty = self.infer_data_expr(elem)?;
+                        self.add_constraint(elem_ty.clone(), next_ty.clone(), "list element");
+                        elem_ty = match self.table.unify(&elem_ty, &next_ty) {
+                            Some(unified) => unified,
+                            None => self.report(
+                                JtvError::TypeError(format!(
+                                    "List elements must have consistent types: {} vs {}",
+                                    elem_ty, next_ty
+                                )),
+                                None,
+                            ),
+                        };
                     }
-                    Ok(Type::List(Box::new(first_ty)))
+                    Ok(Type::List(Box::new(elem_ty)))
                 }
             }
             DataExpr::Tuple(elements) => {
@@ -643,33 +1477,329 @@ impl TypeChecker {
                     elements.iter().map(|e| self.infer_data_expr(e)).collect();
                 Ok(Type::Tuple(types?))
             }
+            DataExpr::ListComprehension(comp) => {
+                let old_env = self.env.clone();
+
+                for (variable, source) in &comp.generators {
+                    let source_ty = self.infer_data_expr(source)?;
+                    let elem_var = self.table.fresh_var();
+                    if self
+                        .table
+                        .unify(&source_ty, &Type::List(Box::new(elem_var.clone())))
+                        .is_none()
+                    {
+                        self.env = old_env;
+                        return Ok(self.report(
+                            JtvError::TypeError(format!(
+                                "Cannot iterate over {} in a comprehension; expected a List",
+                                self.table.zonk(&source_ty)
+                            )),
+                            None,
+                        ));
+                    }
+                    self.env.set_var(variable.clone(), elem_var);
+                }
+
+                if let Some(condition) = &comp.condition {
+                    let cond_ty = self.infer_control_expr(condition)?;
+                    if self.table.unify(&cond_ty, &Type::Bool).is_none() {
+                        self.report(
+                            JtvError::TypeError(format!(
+                                "Comprehension filter must be Bool, got {}",
+                                self.table.zonk(&cond_ty)
+                            )),
+                            None,
+                        );
+                    }
+                }
+
+                let body_ty = self.infer_data_expr(&comp.body)?;
+                self.env = old_env;
+                Ok(Type::List(Box::new(body_ty)))
+            }
+            DataExpr::Index(base, index) => {
+                let base_ty = self.infer_data_expr(base)?;
+                let index_ty = self.infer_data_expr(index)?;
+                if self.table.unify(&index_ty, &Type::Int).is_none() {
+                    return Ok(self.report(
+                        JtvError::TypeError(format!(
+                            "Subscript index must be Int, got {}",
+                            self.table.zonk(&index_ty)
+                        )),
+                        None,
+                    ));
+                }
+
+                match self.table.find(&base_ty) {
+                    Type::Tuple(elems) => match index.as_ref() {
+                        DataExpr::Number(Number::Int(i)) => {
+                            let i = *i;
+                            if i < 0 || i as usize >= elems.len() {
+                                Ok(self.report_typed(
+                                    TypeErrorKind::IndexOutOfBounds { index: i, len: elems.len() },
+                                    JtvError::TypeError(format!(
+                                        "Tuple index {} out of bounds for a tuple of size {}",
+                                        i,
+                                        elems.len()
+                                    )),
+                                    None,
+                                ))
+                            } else {
+                                Ok(elems[i as usize].clone())
+                            }
+                        }
+                        _ => Ok(self.report(
+                            JtvError::TypeError(
+                                "Tuple subscript index must be a compile-time integer literal"
+                                    .to_string(),
+                            ),
+                            None,
+                        )),
+                    },
+                    Type::List(elem) => Ok(*elem),
+                    Type::Any | Type::Var(_) => Ok(Type::Any),
+                    other => Ok(self.report(
+                        JtvError::TypeError(format!("Cannot index into {}", other)),
+                        None,
+                    )),
+                }
+            }
+            DataExpr::FieldAccess(base, field) => {
+                let base_ty = self.infer_data_expr(base)?;
+                let resolved = self.table.find(&base_ty);
+                match &resolved {
+                    Type::Struct(name, fields) => {
+                        match fields.iter().find(|(n, _)| n == field) {
+                            Some((_, ty)) => Ok(ty.clone()),
+                            None => Ok(self.report(
+                                JtvError::TypeError(format!(
+                                    "no field `{}` on type {}",
+                                    field, name
+                                )),
+                                Some(format!(
+                                    "`{}` has fields: {}",
+                                    name,
+                                    fields
+                                        .iter()
+                                        .map(|(n, _)| n.as_str())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                )),
+                            )),
+                        }
+                    }
+                    Type::Any | Type::Var(_) => Ok(Type::Any),
+                    other => Ok(self.report(
+                        JtvError::TypeError(format!(
+                            "no field `{}` on type {}",
+                            field, other
+                        )),
+                        None,
+                    )),
+                }
+            }
+            DataExpr::StructLiteral(name, provided) => {
+                match self.env.get_struct(name).cloned() {
+                    Some(fields) => {
+                        let provided_names: Vec<&String> =
+                            provided.iter().map(|(n, _)| n).collect();
+                        let missing: Vec<&str> = fields
+                            .iter()
+                            .filter(|(n, _)| !provided_names.iter().any(|p| *p == n))
+                            .map(|(n, _)| n.as_str())
+                            .collect();
+                        if !missing.is_empty() {
+                            self.report(
+                                JtvError::TypeError(format!(
+                                    "struct `{}` is missing field(s): {}",
+                                    name,
+                                    missing.join(", ")
+                                )),
+                                None,
+                            );
+                        }
+                        let extra: Vec<&str> = provided_names
+                            .iter()
+                            .filter(|p| !fields.iter().any(|(n, _)| n == **p))
+                            .map(|p| p.as_str())
+                            .collect();
+                        if !extra.is_empty() {
+                            self.report(
+                                JtvError::TypeError(format!(
+                                    "struct `{}` has no field(s): {}",
+                                    name,
+                                    extra.join(", ")
+                                )),
+                                None,
+                            );
+                        }
+
+                        for (field_name, field_expr) in provided {
+                            let field_ty = self.infer_data_expr(field_expr)?;
+                            if let Some((_, expected_ty)) =
+                                fields.iter().find(|(n, _)| n == field_name)
+                            {
+                                if self.table.unify(expected_ty, &field_ty).is_none() {
+                                    let suggestion = self.type_suggestion(expected_ty, &field_ty);
+                                    self.report(
+                                        JtvError::TypeError(format!(
+                                            "field `{}` of struct `{}` expects {}, got {}",
+                                            field_name, name, expected_ty, field_ty
+                                        )),
+                                        Some(suggestion),
+                                    );
+                                }
+                            }
+                        }
+
+                        Ok(Type::Struct(name.clone(), fields))
+                    }
+                    None => Ok(self.report(
+                        JtvError::TypeError(format!("undefined struct `{}`", name)),
+                        None,
+                    )),
+                }
+            }
         }
     }
 
-    fn infer_control_expr(&self, expr: &ControlExpr) -> Result<Type> {
+    fn infer_control_expr(&mut self, expr: &ControlExpr) -> Result<Type> {
         match expr {
             ControlExpr::Data(data) => self.infer_data_expr(data),
-            ControlExpr::Comparison(left, _, right) => {
-                self.infer_data_expr(left)?;
-                self.infer_data_expr(right)?;
+            ControlExpr::Comparison(left, op, right) => {
+                let left_ty = self.infer_data_expr(left)?;
+                let right_ty = self.infer_data_expr(right)?;
+                self.add_constraint(left_ty.clone(), right_ty.clone(), "operands of a comparison");
+                match self.table.unify(&left_ty, &right_ty) {
+                    None => {
+                        let suggestion = self.type_suggestion(&left_ty, &right_ty);
+                        self.report(
+                            JtvError::TypeError(format!(
+                                "Cannot compare {} and {}",
+                                self.table.zonk(&left_ty),
+                                self.table.zonk(&right_ty)
+                            )),
+                            Some(suggestion),
+                        );
+                    }
+                    // A struct type is only comparable through a derived
+                    // operator: `==`/`!=` need an `eq` definition, the other
+                    // four need a `lt` one that `<`, `>`, `<=`, `>=` all
+                    // synthesize from.
+                    Some(unified) => {
+                        if let Type::Struct(name, _) = self.table.zonk(&unified) {
+                            let (has_eq, has_lt) = self.env.operators_for(&name);
+                            let (needed, available) = match op {
+                                Comparator::Eq | Comparator::Ne => ("`==`", has_eq),
+                                Comparator::Lt | Comparator::Le | Comparator::Gt | Comparator::Ge => {
+                                    ("`<`", has_lt)
+                                }
+                            };
+                            if !available {
+                                self.report(
+                                    JtvError::TypeError(format!(
+                                        "Type {} has no {} defined, so it cannot be compared with this operator",
+                                        name, needed
+                                    )),
+                                    None,
+                                );
+                            }
+                        }
+                    }
+                }
                 Ok(Type::Bool)
             }
             ControlExpr::Logical(left, _, right) => {
-                self.infer_control_expr(left)?;
-                self.infer_control_expr(right)?;
+                let left_ty = self.infer_control_expr(left)?;
+                let right_ty = self.infer_control_expr(right)?;
+                if self.table.unify(&left_ty, &Type::Bool).is_none() {
+                    self.report(
+                        JtvError::TypeError(format!(
+                            "Logical operator expects Bool, got {}",
+                            self.table.zonk(&left_ty)
+                        )),
+                        None,
+                    );
+                }
+                if self.table.unify(&right_ty, &Type::Bool).is_none() {
+                    self.report(
+                        JtvError::TypeError(format!(
+                            "Logical operator expects Bool, got {}",
+                            self.table.zonk(&right_ty)
+                        )),
+                        None,
+                    );
+                }
                 Ok(Type::Bool)
             }
             ControlExpr::Not(inner) => {
-                self.infer_control_expr(inner)?;
+                let inner_ty = self.infer_control_expr(inner)?;
+                if self.table.unify(&inner_ty, &Type::Bool).is_none() {
+                    self.report(
+                        JtvError::TypeError(format!(
+                            "`!` expects Bool, got {}",
+                            self.table.zonk(&inner_ty)
+                        )),
+                        None,
+                    );
+                }
+                Ok(Type::Bool)
+            }
+            ControlExpr::Contains(left, right) => {
+                let left_ty = self.infer_data_expr(left)?;
+                let right_ty = self.infer_data_expr(right)?;
+                match self.table.find(&right_ty) {
+                    Type::List(elem) => {
+                        if self.table.unify(&left_ty, &elem).is_none() {
+                            self.report(
+                                JtvError::TypeError(format!(
+                                    "`in` expects {} on the left for a List<{}>, got {}",
+                                    self.table.zonk(&elem),
+                                    self.table.zonk(&elem),
+                                    self.table.zonk(&left_ty)
+                                )),
+                                None,
+                            );
+                        }
+                    }
+                    Type::Tuple(elems) => {
+                        if !elems.iter().any(|elem| self.table.unify(&left_ty, elem).is_some()) {
+                            self.report(
+                                JtvError::TypeError(format!(
+                                    "{} is not a member type of this tuple",
+                                    self.table.zonk(&left_ty)
+                                )),
+                                None,
+                            );
+                        }
+                    }
+                    Type::Any | Type::Var(_) => {}
+                    other => {
+                        self.report(
+                            JtvError::TypeError(format!(
+                                "`in` expects a List or Tuple on the right, got {}",
+                                other
+                            )),
+                            None,
+                        );
+                    }
+                }
                 Ok(Type::Bool)
             }
         }
     }
 
-    fn number_type(&self, num: &Number) -> Type {
+    fn number_type(&mut self, num: &Number) -> Type {
         match num {
-            Number::Int(_) => Type::Int,
-            Number::Float(_) => Type::Float,
+            // Integer and float literals are deferred: a bare `5` could end
+            // up `Int`, `Float`, `Rational`, or `Complex` depending on how
+            // it's used, while `5.0` can never settle back down to `Int`.
+            Number::Int(_) => self
+                .table
+                .fresh_numeric_var(vec![Type::Int, Type::Float, Type::Rational, Type::Complex]),
+            Number::Float(_) => self
+                .table
+                .fresh_numeric_var(vec![Type::Float, Type::Rational, Type::Complex]),
             Number::Rational(_, _) => Type::Rational,
             Number::Complex(_, _) => Type::Complex,
             Number::Hex(_) => Type::Hex,
@@ -781,6 +1911,50 @@ mod tests {
         assert!(checker.check_program(&program).is_ok());
     }
 
+    #[test]
+    fn test_tuple_subscript_returns_exact_element_type() {
+        let mut checker = TypeChecker::new();
+        let tuple = DataExpr::Tuple(vec![
+            DataExpr::number(Number::Int(10)),
+            DataExpr::number(Number::Float(3.14)),
+        ]);
+        let index = DataExpr::Index(Box::new(tuple), Box::new(DataExpr::number(Number::Int(1))));
+        let ty = checker.infer_data_expr(&index).unwrap();
+        assert_eq!(checker.table.zonk(&ty), Type::Float);
+        assert!(checker.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_tuple_subscript_out_of_bounds_is_a_type_error() {
+        let mut checker = TypeChecker::new();
+        let tuple = DataExpr::Tuple(vec![DataExpr::number(Number::Int(10))]);
+        let index = DataExpr::Index(Box::new(tuple), Box::new(DataExpr::number(Number::Int(5))));
+        checker.infer_data_expr(&index).unwrap();
+        assert!(!checker.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_list_subscript_returns_element_type_for_any_integer_index() {
+        let code = r#"
+            numbers = [1, 2, 3]
+            first = numbers[0]
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_subscript_with_non_integer_index_is_a_type_error() {
+        let code = r#"
+            numbers = [1, 2, 3]
+            first = numbers["zero"]
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
     #[test]
     fn test_comparison_type_bool() {
         let code = r#"
@@ -840,4 +2014,631 @@ mod tests {
         let mut checker = TypeChecker::new();
         assert!(checker.check_program(&program).is_err());
     }
+
+    #[test]
+    fn test_empty_list_infers_element_type() {
+        let code = r#"
+            xs = []
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_unannotated_parameter_infers_from_usage() {
+        let code = r#"
+            fn double(x) {
+                return x + x
+            }
+            result = double(5)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_unification_table_occurs_check_rejects_infinite_type() {
+        let mut table = UnificationTable::new();
+        let var = table.fresh_var();
+        let id = match var {
+            Type::Var(id) => id,
+            _ => unreachable!(),
+        };
+        let infinite = Type::List(Box::new(var.clone()));
+        assert!(!table.bind(id, infinite));
+    }
+
+    #[test]
+    fn test_unification_table_zonk_defaults_unbound_var_to_any() {
+        let mut table = UnificationTable::new();
+        let var = table.fresh_var();
+        assert_eq!(table.zonk(&var), Type::Any);
+    }
+
+    #[test]
+    fn test_unification_table_resolves_var_through_unify() {
+        let mut table = UnificationTable::new();
+        let var = table.fresh_var();
+        assert_eq!(table.unify(&var, &Type::Int), Some(Type::Int));
+        assert_eq!(table.zonk(&var), Type::Int);
+    }
+
+    #[test]
+    fn test_reports_every_error_in_a_file_not_just_the_first() {
+        let code = r#"
+            x = undefined_one + 1
+            y = undefined_two + 1
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert_eq!(checker.diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_struct_field_access_type_checks() {
+        let code = r#"
+            struct Complex3D {
+                re: Float,
+                im: Float,
+                w: Float
+            }
+            point = Complex3D { re: 1.0, im: 2.0, w: 3.0 }
+            y = point.re
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_struct_field_access_unknown_field_errors() {
+        let code = r#"
+            struct Point {
+                x: Int,
+                y: Int
+            }
+            p = Point { x: 1, y: 2 }
+            z = p.w
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_struct_literal_missing_field_errors() {
+        let code = r#"
+            struct Point {
+                x: Int,
+                y: Int
+            }
+            p = Point { x: 1 }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_unification_table_unifies_matching_struct_types() {
+        let mut table = UnificationTable::new();
+        let a = Type::Struct("Point".to_string(), vec![("x".to_string(), Type::Int)]);
+        let b = Type::Struct("Point".to_string(), vec![("x".to_string(), Type::Int)]);
+        assert_eq!(table.unify(&a, &b), Some(a));
+    }
+
+    #[test]
+    fn test_generic_identity_function_instantiates_per_call() {
+        let code = r#"
+            fn id<T>(x: T): T {
+                return x
+            }
+            a = id(5)
+            b = id(3.14)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_generic_numeric_bound_accepts_number_types() {
+        let code = r#"
+            fn double<T: Numeric>(x: T): T {
+                return x + x
+            }
+            result = double(5)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_generic_numeric_bound_rejects_non_numeric_argument() {
+        let code = r#"
+            fn double<T: Numeric>(x: T): T {
+                return x + x
+            }
+            result = double("not a number")
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_pure_function_printing_is_a_purity_violation() {
+        let code = r#"
+            @pure
+            fn greet() {
+                print("hi")
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_pure_function_calling_impure_function_is_a_purity_violation() {
+        let code = r#"
+            fn log_it() {
+                print("side effect")
+            }
+            @pure
+            fn wrapper() {
+                log_it()
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_pure_function_calling_pure_function_is_ok() {
+        let code = r#"
+            @pure
+            fn square(x: Int): Int {
+                return x + x
+            }
+            @pure
+            fn wrapper(x: Int): Int {
+                return square(x)
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_impure_function_may_print_and_call_impure_functions() {
+        let code = r#"
+            fn log_it() {
+                print("side effect")
+            }
+            fn wrapper() {
+                log_it()
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_render_diagnostics_includes_message_and_suggestion() {
+        let code = r#"
+            fn needs_int(x: Int): Int {
+                return x
+            }
+            result = needs_int([1, 2, 3])
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        let report = checker.render_diagnostics(code);
+        assert!(report.contains("error:"));
+        assert!(report.contains("help:"));
+    }
+
+    #[test]
+    fn test_comparison_between_incompatible_types_is_a_type_error() {
+        let code = r#"
+            if 1 == "not a number" {
+                print("unreachable")
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_comparison_between_matching_types_is_ok() {
+        let code = r#"
+            if 1 == 2 {
+                print("fine")
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_logical_operator_on_non_bool_operand_is_a_type_error() {
+        let code = r#"
+            if (1 == 1) and 5 {
+                print("unreachable")
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_unannotated_function_is_let_polymorphic_across_calls() {
+        let code = r#"
+            fn id(x) {
+                return x
+            }
+            a = id(5)
+            b = id(3.14)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_inline_generic_without_declared_type_param_instantiates_per_call() {
+        let code = r#"
+            fn id(x: T): T {
+                return x
+            }
+            a = id(5)
+            b = id(3.14)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_int_literal_deferred_to_float_when_used_as_one() {
+        let code = r#"
+            fn needs_float(x: Float): Float {
+                return x
+            }
+            result = needs_float(5)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_int_literal_deferred_to_rational_when_used_as_one() {
+        let code = r#"
+            fn needs_rational(x: Rational): Rational {
+                return x
+            }
+            result = needs_rational(5)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_float_literal_rejected_where_hex_expected() {
+        let code = r#"
+            fn needs_hex(x: Hex): Hex {
+                return x
+            }
+            result = needs_hex(3.14)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_unbound_numeric_literal_defaults_to_int() {
+        let code = r#"
+            x = 5
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        let mut table = UnificationTable::new();
+        let var = table.fresh_numeric_var(vec![Type::Int, Type::Float, Type::Rational, Type::Complex]);
+        assert_eq!(table.zonk(&var), Type::Int);
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_list_comprehension_infers_list_of_body_type() {
+        let code = r#"
+            numbers = [1, 2, 3]
+            result = [x + 1 for x in numbers]
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_list_comprehension_with_bool_filter_is_ok() {
+        let code = r#"
+            numbers = [1, 2, 3]
+            result = [x for x in numbers if x > 0]
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_list_comprehension_with_non_bool_filter_is_a_type_error() {
+        let code = r#"
+            numbers = [1, 2, 3]
+            result = [x for x in numbers if x]
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_list_comprehension_over_non_list_is_a_type_error() {
+        let comp = Comprehension {
+            body: Box::new(DataExpr::identifier("x")),
+            generators: vec![("x".to_string(), DataExpr::number(Number::Int(5)))],
+            condition: None,
+        };
+        let mut checker = TypeChecker::new();
+        let result = checker.infer_data_expr(&DataExpr::ListComprehension(comp));
+        assert!(result.is_ok());
+        assert!(!checker.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_nested_list_comprehension_chains_generators() {
+        let comp = Comprehension {
+            body: DataExpr::add(DataExpr::identifier("x"), DataExpr::identifier("y")).into(),
+            generators: vec![
+                ("x".to_string(), DataExpr::List(vec![DataExpr::number(Number::Int(1))])),
+                ("y".to_string(), DataExpr::List(vec![DataExpr::number(Number::Int(2))])),
+            ],
+            condition: None,
+        };
+        let mut checker = TypeChecker::new();
+        let ty = checker.infer_data_expr(&DataExpr::ListComprehension(comp)).unwrap();
+        assert!(checker.diagnostics().is_empty());
+        assert!(matches!(checker.table.zonk(&ty), Type::List(_)));
+    }
+
+    #[test]
+    fn test_diagnostic_kind_classifies_undefined_variable() {
+        let code = r#"
+            x = undefined_one + 1
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert_eq!(
+            checker.diagnostics()[0].kind,
+            TypeErrorKind::UndefinedVariable("undefined_one".to_string())
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_kind_classifies_arity_mismatch() {
+        let code = r#"
+            fn add(a: Int, b: Int): Int {
+                return a + b
+            }
+            result = add(1)
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert!(checker
+            .diagnostics()
+            .iter()
+            .any(|d| d.kind == TypeErrorKind::ArityMismatch { expected: 2, got: 1 }));
+    }
+
+    #[test]
+    fn test_diagnostic_kind_classifies_unification_failure_with_resolved_types() {
+        let code = r#"
+            fn needs_int(x: Int): Int {
+                return x
+            }
+            result = needs_int([1, 2, 3])
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert!(checker.diagnostics().iter().any(|d| matches!(
+            &d.kind,
+            TypeErrorKind::UnificationFailure { expected, .. } if *expected == Type::Int
+        )));
+    }
+
+    #[test]
+    fn test_span_diagnostics_locates_unification_failure_at_function_span() {
+        let code = r#"
+            fn needs_int(x: Int): Int {
+                return x
+            }
+            result = needs_int([1, 2, 3])
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert_eq!(checker.span_diagnostics().len(), 1);
+        assert!(matches!(
+            &checker.span_diagnostics()[0].kind,
+            crate::diagnostics::DiagnosticKind::TypeMismatch { expected, .. } if expected == "Int"
+        ));
+        assert!(checker.span_diagnostics()[0].location.is_some());
+    }
+
+    #[test]
+    fn test_span_diagnostics_locates_undefined_variable_at_function_span() {
+        let code = r#"
+            fn broken(): Int {
+                return missing_name
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+        assert_eq!(checker.span_diagnostics().len(), 1);
+        assert!(matches!(
+            &checker.span_diagnostics()[0].kind,
+            crate::diagnostics::DiagnosticKind::UndefinedVariable { name } if name == "missing_name"
+        ));
+        assert!(checker.span_diagnostics()[0].location.is_some());
+    }
+
+    #[test]
+    fn test_inferred_annotations_reports_unannotated_param_and_return() {
+        let code = r#"
+            fn double(x) {
+                return x + x
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+        assert!(checker.inferred_annotations().iter().any(|a| matches!(
+            &a.target,
+            InferredAnnotationTarget::Param { function, param }
+                if function == "double" && param == "x"
+        )));
+        assert!(checker
+            .inferred_annotations()
+            .iter()
+            .any(|a| matches!(&a.target, InferredAnnotationTarget::Return { function } if function == "double")));
+    }
+
+    #[test]
+    fn test_inferred_annotations_empty_when_fully_annotated() {
+        let code = r#"
+            fn double(x: Int): Int {
+                return x + x
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+        assert!(checker.inferred_annotations().is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_kind_classifies_index_out_of_bounds() {
+        let mut checker = TypeChecker::new();
+        let tuple = DataExpr::Tuple(vec![DataExpr::number(Number::Int(10))]);
+        let index = DataExpr::Index(Box::new(tuple), Box::new(DataExpr::number(Number::Int(5))));
+        checker.infer_data_expr(&index).unwrap();
+        assert_eq!(
+            checker.diagnostics()[0].kind,
+            TypeErrorKind::IndexOutOfBounds { index: 5, len: 1 }
+        );
+    }
+
+    #[test]
+    fn test_contains_accepts_matching_element_type() {
+        let mut checker = TypeChecker::new();
+        let list = DataExpr::List(vec![DataExpr::number(Number::Int(1)), DataExpr::number(Number::Int(2))]);
+        let condition = ControlExpr::Contains(Box::new(DataExpr::number(Number::Int(1))), Box::new(list));
+        let ty = checker.infer_control_expr(&condition).unwrap();
+        assert_eq!(ty, Type::Bool);
+        assert!(checker.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_contains_rejects_mismatched_element_type() {
+        let code = r#"
+            xs = [(1, 2), (3, 4)]
+            result = 1.5 in xs
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_struct_without_eq_or_lt_cannot_be_compared() {
+        let code = r#"
+            struct Point { x: Int, y: Int }
+            a = Point { x: 1, y: 2 }
+            b = Point { x: 1, y: 2 }
+            result = a == b
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
+
+    #[test]
+    fn test_struct_with_eq_can_be_compared_with_eq_and_ne() {
+        let code = r#"
+            struct Point { x: Int, y: Int }
+            fn eq(a: Point, b: Point): Bool {
+                return a.x == b.x
+            }
+            a = Point { x: 1, y: 2 }
+            b = Point { x: 1, y: 2 }
+            same = a == b
+            different = a != b
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_struct_with_lt_derives_the_other_three_ordering_operators() {
+        let code = r#"
+            struct Point { x: Int, y: Int }
+            fn lt(a: Point, b: Point): Bool {
+                return a.x < b.x
+            }
+            a = Point { x: 1, y: 2 }
+            b = Point { x: 3, y: 4 }
+            lesser = a < b
+            greater = a > b
+            lesser_eq = a <= b
+            greater_eq = a >= b
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_struct_with_eq_still_cannot_be_ordered() {
+        let code = r#"
+            struct Point { x: Int, y: Int }
+            fn eq(a: Point, b: Point): Bool {
+                return a.x == b.x
+            }
+            a = Point { x: 1, y: 2 }
+            b = Point { x: 3, y: 4 }
+            result = a < b
+        "#;
+        let program = parse_program(code).unwrap();
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_program(&program).is_err());
+    }
 }
@@ -6,16 +6,179 @@
 
 use crate::bytecode::{Opcode, CompiledModule, CompiledFunction, Value as BcValue};
 use crate::error::{JtvError, Result};
+use std::collections::HashMap;
 use wasm_encoder::{
     Module, TypeSection, FunctionSection, CodeSection, ExportSection,
     MemorySection, MemoryType, Function, Instruction, ValType, ExportKind,
-    GlobalSection, GlobalType, ConstExpr,
+    GlobalSection, GlobalType, ConstExpr, BlockType, ImportSection, EntityType,
+    MemArg, NameSection, NameMap, CustomSection,
 };
 
+/// Global index of the bump-allocator heap pointer. Global 0 is the stack
+/// pointer `compile` already reserves (unused by any instruction so far);
+/// this is the second (and, for now, only other) global, so it's always 1.
+const HEAP_PTR_GLOBAL: u32 = 1;
+
+/// A host function the generated module imports instead of implementing
+/// itself -- the embedding host (wasmtime, a JS runtime, ...) supplies the
+/// real definition at instantiation time. This is how the module reaches
+/// the outside world at all, since WASM code otherwise can't do I/O.
+#[derive(Clone)]
+pub struct HostImport {
+    pub module: String,
+    pub name: String,
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+impl HostImport {
+    /// `env.print_i64(i64)`: prints integers and booleans, which this
+    /// codegen still lowers to a raw `i64` (see `Opcode::Push`'s
+    /// bool-as-0/1 handling).
+    pub fn print_i64() -> Self {
+        HostImport {
+            module: "env".to_string(),
+            name: "print_i64".to_string(),
+            params: vec![ValType::I64],
+            results: vec![],
+        }
+    }
+
+    /// `env.print_f64(f64)`: prints floats, now that the compile-time type
+    /// stack (see `SlotType`) tells `Opcode::Print` which of these two
+    /// imports to call. `print_str` still isn't wired up, since strings
+    /// aren't represented in linear memory yet.
+    pub fn print_f64() -> Self {
+        HostImport {
+            module: "env".to_string(),
+            name: "print_f64".to_string(),
+            params: vec![ValType::F64],
+            results: vec![],
+        }
+    }
+}
+
+/// The WASM type a bytecode value is lowered to. Every JtV value ends up as
+/// either `i64` (ints, bools, and -- until strings/closures get their own
+/// memory representation -- aggregate handles) or `f64` (floats, kept as
+/// real floating-point values now rather than boxed as reinterpreted i64
+/// bits). `compile_opcodes`/`emit_opcode` track one of these per slot of the
+/// abstract operand stack and per local, so arithmetic and comparisons can
+/// choose the right instruction family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotType {
+    I64,
+    F64,
+}
+
+impl SlotType {
+    fn to_val_type(self) -> ValType {
+        match self {
+            SlotType::I64 => ValType::I64,
+            SlotType::F64 => ValType::F64,
+        }
+    }
+}
+
+/// One emitted instruction's position, recorded during final code-section
+/// emission (after the Relooper pass and the heap allocator have already
+/// decided what actually gets written, so this reflects real offsets rather
+/// than an upfront estimate that later passes would invalidate).
+#[derive(Debug, Clone)]
+pub struct InstructionMapping {
+    /// Position of the originating `Opcode` within its function's flat
+    /// instruction stream, counting only opcodes that reach `emit_opcode`
+    /// (jumps are consumed as basic-block terminators in `compile_opcodes`
+    /// and don't get their own entry).
+    pub opcode_index: usize,
+    /// Byte offset of this instruction's encoding, relative to the start of
+    /// its function's body in the code section.
+    pub offset: u32,
+    /// (line, column) in the original JtV source. Always `None` today --
+    /// `Opcode` doesn't carry a source span yet, so there is nothing to
+    /// populate this from. Kept as a real field rather than left out so
+    /// that wiring up span-carrying opcodes later only changes what fills
+    /// this in, not the shape downstream tooling consumes.
+    pub source_span: Option<(u32, u32)>,
+}
+
+/// The code-section byte range and per-instruction mapping for one compiled
+/// function.
+#[derive(Debug, Clone)]
+pub struct FunctionMapping {
+    pub name: String,
+    pub start_offset: u32,
+    pub end_offset: u32,
+    pub instructions: Vec<InstructionMapping>,
+}
+
+/// Instruction-level debug info for a compiled module, built up as
+/// `compile` emits the code section. Meant to be serialized into the
+/// module's own custom sections (see `to_json`, used for the
+/// `jtv-source-map` custom section `compile` writes when
+/// `set_record_source_map(true)` is in effect) as well as handed back
+/// directly via `WasmGenerator::take_source_map` for in-process tooling
+/// (debuggers, the differential fuzzer) that would rather not reparse it.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    pub functions: Vec<FunctionMapping>,
+}
+
+impl SourceMap {
+    /// A minimal hand-rolled JSON encoding -- just objects, arrays, strings
+    /// and numbers, all of which function names and offsets already are, so
+    /// pulling in a JSON crate for this one section isn't worth it.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"functions\":[");
+        for (fi, f) in self.functions.iter().enumerate() {
+            if fi > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!(
+                "{{\"name\":{},\"start\":{},\"end\":{},\"instructions\":[",
+                json_escape(&f.name),
+                f.start_offset,
+                f.end_offset,
+            ));
+            for (ii, inst) in f.instructions.iter().enumerate() {
+                if ii > 0 {
+                    out.push(',');
+                }
+                let span = match inst.source_span {
+                    Some((line, col)) => format!("[{},{}]", line, col),
+                    None => "null".to_string(),
+                };
+                out.push_str(&format!(
+                    "{{\"opcode_index\":{},\"offset\":{},\"span\":{}}}",
+                    inst.opcode_index, inst.offset, span
+                ));
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 /// WASM code generator that compiles JtV bytecode to WebAssembly
 pub struct WasmGenerator {
     module: Module,
     type_section: TypeSection,
+    import_section: ImportSection,
     function_section: FunctionSection,
     export_section: ExportSection,
     code_section: CodeSection,
@@ -23,13 +186,83 @@ pub struct WasmGenerator {
     global_section: GlobalSection,
     function_count: u32,
     type_count: u32,
+    /// Function index of the first non-imported function. Imports occupy
+    /// `0..user_function_offset` in WASM's shared function index space, so
+    /// `Opcode::Call`'s function indices (which number user-defined
+    /// functions from 0) need this added back in.
+    user_function_offset: u32,
+    print_i64_idx: Option<u32>,
+    print_f64_idx: Option<u32>,
+    /// Scratch locals reserved in the function currently being compiled, for
+    /// the heap bump-allocation and indexing sequences emitted by
+    /// `emit_make_aggregate`/`Opcode::Index`, and (`scratch_f64`) for
+    /// mixed-type binary-op coercion in `coerce_binary_operands`. Set fresh
+    /// by `compile_main_function`/`compile_function` before each call into
+    /// `compile_opcodes`, since every compiled function gets its own copy of
+    /// these locals.
+    scratch_a: u32,
+    scratch_b: u32,
+    heap_base_local: u32,
+    scratch_f64: u32,
+    /// The abstract type of each slot of the operand stack, mirroring the
+    /// real WASM stack as `compile_opcodes` emits instructions for the
+    /// function currently being compiled. Reset at the start of each
+    /// function.
+    value_types: Vec<SlotType>,
+    /// The WASM type of each local slot (params followed by declared
+    /// locals) of the function currently being compiled, indexed the same
+    /// way bytecode's `LoadLocal`/`StoreLocal` index them. Set fresh per
+    /// function by `infer_local_types`.
+    local_types: Vec<SlotType>,
+    /// Per-function-index arity and return-float-ness, used at `Call` sites
+    /// to keep `value_types` in sync with the real stack effect of calling
+    /// into another compiled function.
+    function_arities: Vec<usize>,
+    function_returns_float: Vec<bool>,
+    /// The module's constant pool, set once per `compile` call. Resolves
+    /// `Opcode::LoadConst`'s index the same way `execute_instruction`
+    /// resolves it against `CompiledModule::constants` at runtime.
+    constants: Vec<BcValue>,
+    /// Whether `compile` runs the finished module through `wasmparser`
+    /// before returning it. On by default in debug builds, since a
+    /// Relooper/type-tracking bug here should show up as a compile-time
+    /// error pointing at the broken module, not a trap from whatever host
+    /// eventually loads the bytes. Off by default in release builds, so
+    /// shipped compiles don't pay the validation pass. See `set_validate`
+    /// to override either default.
+    validate: bool,
+    /// Whether `compile` records a `SourceMap` and embeds it (plus a `name`
+    /// custom section) in the finished module. Off by default: unlike
+    /// `validate`, this is a debugging convenience rather than a
+    /// correctness guard, so it stays opt-in via `set_record_source_map`.
+    record_source_map: bool,
+    /// `(function_index, name)` pairs collected as functions are compiled,
+    /// independent of `record_source_map` -- cheap enough to always track,
+    /// and used to build the `name` custom section when recording is on.
+    function_names: Vec<(u32, String)>,
+    /// Per-instruction offsets for the function currently being compiled;
+    /// flushed into `source_map` as a `FunctionMapping` once that function's
+    /// body is fully emitted.
+    current_instructions: Vec<InstructionMapping>,
+    /// Position of the next `Opcode` to reach `emit_opcode` within the
+    /// current function's flat instruction stream. Reset to 0 at the start
+    /// of each function.
+    current_opcode_index: usize,
+    source_map: SourceMap,
 }
 
 impl WasmGenerator {
     pub fn new() -> Self {
-        WasmGenerator {
+        Self::with_imports(vec![HostImport::print_i64(), HostImport::print_f64()])
+    }
+
+    /// Like `new`, but with a caller-chosen set of host imports instead of
+    /// the default `env.print_i64`/`env.print_f64` pair.
+    pub fn with_imports(imports: Vec<HostImport>) -> Self {
+        let mut gen = WasmGenerator {
             module: Module::new(),
             type_section: TypeSection::new(),
+            import_section: ImportSection::new(),
             function_section: FunctionSection::new(),
             export_section: ExportSection::new(),
             code_section: CodeSection::new(),
@@ -37,7 +270,65 @@ impl WasmGenerator {
             global_section: GlobalSection::new(),
             function_count: 0,
             type_count: 0,
+            user_function_offset: 0,
+            print_i64_idx: None,
+            print_f64_idx: None,
+            scratch_a: 0,
+            scratch_b: 0,
+            heap_base_local: 0,
+            scratch_f64: 0,
+            value_types: Vec::new(),
+            local_types: Vec::new(),
+            function_arities: Vec::new(),
+            function_returns_float: Vec::new(),
+            constants: Vec::new(),
+            validate: cfg!(debug_assertions),
+            record_source_map: false,
+            function_names: Vec::new(),
+            current_instructions: Vec::new(),
+            current_opcode_index: 0,
+            source_map: SourceMap::default(),
+        };
+
+        for import in &imports {
+            gen.type_section.function(import.params.clone(), import.results.clone());
+            let type_idx = gen.type_count;
+            gen.type_count += 1;
+
+            gen.import_section.import(&import.module, &import.name, EntityType::Function(type_idx));
+            let fn_idx = gen.function_count;
+            gen.function_count += 1;
+            gen.function_names.push((fn_idx, import.name.clone()));
+
+            if import.name == "print_i64" {
+                gen.print_i64_idx = Some(fn_idx);
+            } else if import.name == "print_f64" {
+                gen.print_f64_idx = Some(fn_idx);
+            }
         }
+        gen.user_function_offset = gen.function_count;
+
+        gen
+    }
+
+    /// Overrides the debug/release default for whether `compile` validates
+    /// the module it produces (see the `validate` field).
+    pub fn set_validate(&mut self, validate: bool) {
+        self.validate = validate;
+    }
+
+    /// Turns on (or off) source-map recording and the `name`/`jtv-source-map`
+    /// custom sections `compile` embeds in the module. See `take_source_map`
+    /// to read the mapping back out after compiling.
+    pub fn set_record_source_map(&mut self, record: bool) {
+        self.record_source_map = record;
+    }
+
+    /// Takes the `SourceMap` accumulated by the most recent `compile` call,
+    /// leaving an empty one in its place. Returns an empty map if
+    /// `record_source_map` wasn't enabled.
+    pub fn take_source_map(&mut self) -> SourceMap {
+        std::mem::take(&mut self.source_map)
     }
 
     /// Compile a JtV bytecode module to WASM binary
@@ -59,6 +350,24 @@ impl WasmGenerator {
             &ConstExpr::i32_const(0), // Stack pointer starts at 0
         );
 
+        // Heap pointer for the bump allocator backing `MakeList`/`MakeTuple`.
+        // Starts at 0 since the stack pointer above is never actually used
+        // by any emitted instruction yet.
+        self.global_section.global(
+            GlobalType {
+                val_type: ValType::I32,
+                mutable: true,
+            },
+            &ConstExpr::i32_const(0),
+        );
+
+        // Needed at `Call` sites (in any function, including main) to keep
+        // the compile-time type stack in sync with a called function's real
+        // stack effect.
+        self.function_arities = compiled_module.functions.iter().map(|f| f.arity).collect();
+        self.function_returns_float = compiled_module.functions.iter().map(|f| f.returns_float).collect();
+        self.constants = compiled_module.constants.clone();
+
         // Compile main function from top-level code if present
         if !compiled_module.code.is_empty() {
             self.compile_main_function(&compiled_module.code)?;
@@ -69,16 +378,60 @@ impl WasmGenerator {
             self.compile_function(func)?;
         }
 
-        // Assemble the module
+        // Assemble the module. Section order is fixed by the WASM spec:
+        // type, import, function, memory, global, export, code.
         let mut module = Module::new();
         module.section(&self.type_section);
+        module.section(&self.import_section);
         module.section(&self.function_section);
         module.section(&self.memory_section);
         module.section(&self.global_section);
         module.section(&self.export_section);
         module.section(&self.code_section);
 
-        Ok(module.finish())
+        if self.record_source_map {
+            let mut names = NameSection::new();
+            let mut function_names = NameMap::new();
+            let mut sorted_names = self.function_names.clone();
+            sorted_names.sort_by_key(|(idx, _)| *idx);
+            for (idx, name) in &sorted_names {
+                function_names.append(*idx, name);
+            }
+            names.functions(&function_names);
+            module.section(&names);
+
+            let json = self.source_map.to_json();
+            module.section(&CustomSection {
+                name: "jtv-source-map".into(),
+                data: json.as_bytes().into(),
+            });
+        }
+
+        let bytes = module.finish();
+        if self.validate {
+            self.validate_module(&bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Runs `wasmparser`'s validator over the finished module, turning a
+    /// structural bug in this backend -- a bad branch depth from the
+    /// Relooper pass, a stack-height mismatch, a local/operand type
+    /// mismatch from the float-tracking above -- into a descriptive
+    /// `JtvError` instead of an opaque trap the first time some host tries
+    /// to instantiate the module. `wasmparser`'s error already names the
+    /// byte offset and the function/opcode it was decoding when validation
+    /// failed, so that's threaded through verbatim rather than re-derived.
+    fn validate_module(&self, bytes: &[u8]) -> Result<()> {
+        wasmparser::Validator::new()
+            .validate_all(bytes)
+            .map_err(|e| {
+                JtvError::RuntimeError(format!(
+                    "wasmgen: generated module failed validation: {}",
+                    e
+                ))
+            })?;
+        Ok(())
     }
 
     fn compile_main_function(&mut self, opcodes: &[Opcode]) -> Result<()> {
@@ -93,22 +446,64 @@ impl WasmGenerator {
         // Export as "_start"
         self.export_section.export("_start", ExportKind::Func, self.function_count);
 
-        // Compile function body
-        let mut func = Function::new(vec![(1, ValType::I64)]); // 1 local for result
+        // Compile function body. Locals: 1 for the result, plus the fixed
+        // scratch locals `emit_make_aggregate`/`Opcode::Index`/binary-op
+        // coercion need. Top-level code has no declared signature to infer
+        // types from, so the result local stays `i64` as before; only its
+        // own single local slot (index 0) gets its type inferred from how
+        // it's actually used, same as any other function's locals.
+        let result_locals = 1u32;
+        self.scratch_a = result_locals;
+        self.scratch_b = result_locals + 1;
+        self.heap_base_local = result_locals + 2;
+        self.scratch_f64 = result_locals + 3;
+        self.local_types = infer_local_types(opcodes, &[], result_locals, &self.function_arities, &self.function_returns_float, &self.constants);
+        self.value_types = Vec::new();
+
+        let mut locals_decl: Vec<(u32, ValType)> = Vec::new();
+        for idx in 0..result_locals {
+            let t = self.local_types.get(idx as usize).copied().unwrap_or(SlotType::I64);
+            locals_decl.push((1, t.to_val_type()));
+        }
+        locals_decl.push((2, ValType::I64));
+        locals_decl.push((1, ValType::I32));
+        locals_decl.push((1, ValType::F64));
+        let mut func = Function::new(locals_decl);
+        self.current_opcode_index = 0;
+        self.current_instructions = Vec::new();
+        let start_offset = func.byte_len() as u32;
         self.compile_opcodes(&mut func, opcodes)?;
+        let end_offset = func.byte_len() as u32;
         func.instruction(&Instruction::LocalGet(0)); // Return result
         func.instruction(&Instruction::End);
 
+        self.function_names.push((self.function_count, "_start".to_string()));
+        if self.record_source_map {
+            self.source_map.functions.push(FunctionMapping {
+                name: "_start".to_string(),
+                start_offset,
+                end_offset,
+                instructions: std::mem::take(&mut self.current_instructions),
+            });
+        }
+
         self.code_section.function(&func);
         self.function_count += 1;
+        self.user_function_offset += 1;
 
         Ok(())
     }
 
     fn compile_function(&mut self, func: &CompiledFunction) -> Result<()> {
-        // Determine function signature based on arity
-        let params: Vec<ValType> = (0..func.arity).map(|_| ValType::I64).collect();
-        let results = vec![ValType::I64];
+        // Determine function signature from the declared param/return types
+        // (falling back to `i64`, the numeric default for everything that
+        // isn't a `Float`).
+        let param_types: Vec<SlotType> = func.param_is_float
+            .iter()
+            .map(|&is_float| if is_float { SlotType::F64 } else { SlotType::I64 })
+            .collect();
+        let params: Vec<ValType> = param_types.iter().map(|t| t.to_val_type()).collect();
+        let results = vec![if func.returns_float { ValType::F64 } else { ValType::I64 }];
 
         self.type_section.function(params, results);
         let type_idx = self.type_count;
@@ -120,200 +515,738 @@ impl WasmGenerator {
         self.export_section.export(&func.name, ExportKind::Func, self.function_count);
 
         // Compile function body
-        // Local count = arity + local variables needed
+        // Local count = arity + local variables needed, plus the fixed
+        // scratch locals `emit_make_aggregate`/`Opcode::Index`/binary-op
+        // coercion need. `local_count` (as already noted by the existing
+        // offsets below) double-counts the arity-many param slots as
+        // padding at the end of the declared-locals range -- harmless,
+        // since bytecode never addresses that padding, and preserved here
+        // rather than tightened, since fixing it is unrelated to typing.
         let local_count = func.locals.max(func.arity) as u32;
-        let mut wasm_func = Function::new(vec![(local_count, ValType::I64)]);
-
+        self.scratch_a = func.arity as u32 + local_count;
+        self.scratch_b = self.scratch_a + 1;
+        self.heap_base_local = self.scratch_a + 2;
+        self.scratch_f64 = self.scratch_a + 3;
+        self.local_types = infer_local_types(&func.code, &param_types, func.arity as u32 + local_count, &self.function_arities, &self.function_returns_float, &self.constants);
+        self.value_types = Vec::new();
+
+        let mut locals_decl: Vec<(u32, ValType)> = Vec::new();
+        for k in 0..local_count {
+            let idx = func.arity as u32 + k;
+            let t = self.local_types.get(idx as usize).copied().unwrap_or(SlotType::I64);
+            locals_decl.push((1, t.to_val_type()));
+        }
+        locals_decl.push((2, ValType::I64));
+        locals_decl.push((1, ValType::I32));
+        locals_decl.push((1, ValType::F64));
+        let mut wasm_func = Function::new(locals_decl);
+
+        self.current_opcode_index = 0;
+        self.current_instructions = Vec::new();
+        let start_offset = wasm_func.byte_len() as u32;
         self.compile_opcodes(&mut wasm_func, &func.code)?;
+        let end_offset = wasm_func.byte_len() as u32;
         wasm_func.instruction(&Instruction::End);
 
+        self.function_names.push((self.function_count, func.name.clone()));
+        if self.record_source_map {
+            self.source_map.functions.push(FunctionMapping {
+                name: func.name.clone(),
+                start_offset,
+                end_offset,
+                instructions: std::mem::take(&mut self.current_instructions),
+            });
+        }
+
         self.code_section.function(&wasm_func);
         self.function_count += 1;
 
         Ok(())
     }
 
+    // Bytecode jumps are plain instruction-index gotos, but WASM only offers
+    // structured control flow (`block`/`loop`/`br`/`br_if`). `compile_opcodes`
+    // runs a Relooper-style pass instead of emitting one `Br` per jump: it
+    // splits the opcode stream into basic blocks, then walks them in order
+    // maintaining a stack of the `block`/`loop` scopes currently open, so a
+    // jump can be translated into a `br`/`br_if` at the right nesting depth.
+    //
+    // The bytecode compiler (`BytecodeCompiler`) only ever emits jumps in two
+    // shapes, matching the `if`/`while`/`for` constructs it compiles from:
+    //   - a forward jump that skips over a branch (the "then" of an `if`, or
+    //     the "else" of an `if`/`else`, found via `JumpIfFalse`/`Jump`)
+    //   - a backward jump back to a loop's condition check (`while`/`for`)
+    // so this pass recognizes exactly those two shapes rather than a fully
+    // general irreducible-CFG reconstruction: every `Simple` region becomes a
+    // `block` wrapping the skipped code, every `Loop` region becomes a `loop`
+    // nested inside an implicit `block` (so `break`-style forward exits from
+    // inside the loop have somewhere structured to land).
     fn compile_opcodes(&mut self, func: &mut Function, opcodes: &[Opcode]) -> Result<()> {
-        let mut pc = 0;
-
-        while pc < opcodes.len() {
-            match &opcodes[pc] {
-                Opcode::Push(value) => {
-                    match value {
-                        BcValue::Int(n) => {
-                            func.instruction(&Instruction::I64Const(*n));
-                        }
-                        BcValue::Bool(b) => {
-                            func.instruction(&Instruction::I64Const(if *b { 1 } else { 0 }));
-                        }
-                        BcValue::Float(f) => {
-                            // Store float as reinterpreted i64 bits
-                            func.instruction(&Instruction::I64Const(f.to_bits() as i64));
-                        }
-                        _ => {
-                            // For complex types, push a placeholder
-                            func.instruction(&Instruction::I64Const(0));
-                        }
-                    }
+        let blocks = split_into_blocks(opcodes);
+        let loop_headers = find_loop_headers(&blocks);
+
+        // Scopes currently open, innermost last. `branch_match` is the block
+        // index a jump targeting this scope resolves to (for a loop, that's
+        // the header, since branching to a WASM `loop` label means "continue"
+        // from the top, not "exit"); `close_at` is the block index we reach
+        // that scope's `end` at.
+        let mut labels: Vec<OpenLabel> = Vec::new();
+        let mut i = 0usize;
+
+        while i <= blocks.len() {
+            while let Some(top) = labels.last() {
+                if top.close_at == i {
+                    func.instruction(&Instruction::End);
+                    labels.pop();
+                } else {
+                    break;
                 }
+            }
 
-                Opcode::Pop => {
-                    func.instruction(&Instruction::Drop);
-                }
+            if i == blocks.len() {
+                break;
+            }
 
-                Opcode::Dup => {
-                    // WASM doesn't have native dup, use local
-                    func.instruction(&Instruction::LocalTee(0));
-                    func.instruction(&Instruction::LocalGet(0));
-                }
+            if let Some(&loop_end) = loop_headers.get(&i) {
+                // Always wrap the loop in an enclosing block, whether or not
+                // this particular loop has a forward exit branch: it costs
+                // one harmless extra scope when unused, and gives any exit
+                // jump inside the body a `block` (break) to target instead
+                // of the `loop` label (continue).
+                labels.push(OpenLabel { branch_match: loop_end, close_at: loop_end });
+                func.instruction(&Instruction::Block(BlockType::Empty));
+                labels.push(OpenLabel { branch_match: i, close_at: loop_end });
+                func.instruction(&Instruction::Loop(BlockType::Empty));
+            }
 
-                Opcode::LoadLocal(idx) => {
-                    func.instruction(&Instruction::LocalGet(*idx));
-                }
+            let block = &blocks[i];
+            for op in &block.body {
+                self.emit_opcode(func, op)?;
+            }
 
-                Opcode::StoreLocal(idx) => {
-                    func.instruction(&Instruction::LocalSet(*idx));
-                }
+            if let Some((kind, target)) = block.term {
+                let forward = target > i;
+                let depth = match find_label_depth(&labels, target) {
+                    Some(depth) => depth,
+                    None if forward => {
+                        // No enclosing scope already resolves to `target`:
+                        // open one now. If the block right before `target`
+                        // ends in its own forward jump past it, that's the
+                        // `if { .. } else { .. }` pattern -- the "then"
+                        // branch jumping past the "else" -- so the `else`'s
+                        // own merge point must be the outer scope, open
+                        // before (and closing after) this one.
+                        if let Some(merge) = if_else_merge(&blocks, target) {
+                            labels.push(OpenLabel { branch_match: merge, close_at: merge });
+                            func.instruction(&Instruction::Block(BlockType::Empty));
+                        }
+                        labels.push(OpenLabel { branch_match: target, close_at: target });
+                        func.instruction(&Instruction::Block(BlockType::Empty));
+                        0
+                    }
+                    None => {
+                        // A backward jump with no matching loop scope open:
+                        // not a shape this compiler emits. Fail loudly rather
+                        // than emit a `Br` to the wrong place.
+                        return Err(JtvError::InvalidOperation(format!(
+                            "wasmgen: unresolvable backward jump to block {} from block {}",
+                            target, i
+                        )));
+                    }
+                };
 
-                Opcode::LoadGlobal(idx) => {
-                    func.instruction(&Instruction::GlobalGet(*idx));
-                }
+                match kind {
+                    TermKind::Jump => {
+                        func.instruction(&Instruction::Br(depth));
+                    }
+                    TermKind::JumpIfFalse => {
+                        self.value_types.pop();
+                        func.instruction(&Instruction::I64Eqz);
+                        func.instruction(&Instruction::I32WrapI64);
+                        func.instruction(&Instruction::BrIf(depth));
+                    }
+                    TermKind::JumpIfTrue => {
+                        self.value_types.pop();
+                        func.instruction(&Instruction::I32WrapI64);
+                        func.instruction(&Instruction::BrIf(depth));
+                    }
+                };
+            }
 
-                Opcode::StoreGlobal(idx) => {
-                    func.instruction(&Instruction::GlobalSet(*idx));
-                }
+            i += 1;
+        }
 
-                Opcode::Add => {
-                    func.instruction(&Instruction::I64Add);
-                }
+        Ok(())
+    }
 
-                Opcode::Neg => {
-                    // Negate: push the value, push 0, subtract (0 - value)
-                    // But we need to reorder since we have value on stack
-                    // Use: local.tee 0, i64.const 0, local.get 0, i64.sub
-                    func.instruction(&Instruction::LocalTee(0));
-                    func.instruction(&Instruction::Drop);
-                    func.instruction(&Instruction::I64Const(0));
-                    func.instruction(&Instruction::LocalGet(0));
-                    func.instruction(&Instruction::I64Sub);
+    fn emit_opcode(&mut self, func: &mut Function, opcode: &Opcode) -> Result<()> {
+        if self.record_source_map {
+            self.current_instructions.push(InstructionMapping {
+                opcode_index: self.current_opcode_index,
+                offset: func.byte_len() as u32,
+                source_span: None,
+            });
+        }
+        self.current_opcode_index += 1;
+
+        match opcode {
+            Opcode::Push(value) => {
+                match value {
+                    BcValue::Int(n) => {
+                        func.instruction(&Instruction::I64Const(*n));
+                        self.value_types.push(SlotType::I64);
+                    }
+                    BcValue::Bool(b) => {
+                        func.instruction(&Instruction::I64Const(if *b { 1 } else { 0 }));
+                        self.value_types.push(SlotType::I64);
+                    }
+                    BcValue::Float(f) => {
+                        func.instruction(&Instruction::F64Const(*f));
+                        self.value_types.push(SlotType::F64);
+                    }
+                    _ => {
+                        // For complex types, push a placeholder
+                        func.instruction(&Instruction::I64Const(0));
+                        self.value_types.push(SlotType::I64);
+                    }
                 }
+            }
 
-                Opcode::Eq => {
-                    func.instruction(&Instruction::I64Eq);
-                    func.instruction(&Instruction::I64ExtendI32U);
+            Opcode::LoadConst(idx) => {
+                let value = self.constants.get(*idx as usize).cloned().unwrap_or(BcValue::Int(0));
+                match value {
+                    BcValue::Int(n) => {
+                        func.instruction(&Instruction::I64Const(n));
+                        self.value_types.push(SlotType::I64);
+                    }
+                    BcValue::Bool(b) => {
+                        func.instruction(&Instruction::I64Const(if b { 1 } else { 0 }));
+                        self.value_types.push(SlotType::I64);
+                    }
+                    BcValue::Float(f) => {
+                        func.instruction(&Instruction::F64Const(f));
+                        self.value_types.push(SlotType::F64);
+                    }
+                    _ => {
+                        // For complex types, push a placeholder
+                        func.instruction(&Instruction::I64Const(0));
+                        self.value_types.push(SlotType::I64);
+                    }
                 }
+            }
 
-                Opcode::Ne => {
-                    func.instruction(&Instruction::I64Ne);
-                    func.instruction(&Instruction::I64ExtendI32U);
-                }
+            Opcode::Pop => {
+                func.instruction(&Instruction::Drop);
+                self.value_types.pop();
+            }
 
-                Opcode::Lt => {
-                    func.instruction(&Instruction::I64LtS);
-                    func.instruction(&Instruction::I64ExtendI32U);
+            Opcode::Dup => {
+                // WASM doesn't have native dup, use local
+                func.instruction(&Instruction::LocalTee(0));
+                func.instruction(&Instruction::LocalGet(0));
+                if let Some(&t) = self.value_types.last() {
+                    self.value_types.push(t);
                 }
+            }
 
-                Opcode::Le => {
-                    func.instruction(&Instruction::I64LeS);
-                    func.instruction(&Instruction::I64ExtendI32U);
-                }
+            Opcode::LoadLocal(idx) => {
+                func.instruction(&Instruction::LocalGet(*idx));
+                let t = self.local_types.get(*idx as usize).copied().unwrap_or(SlotType::I64);
+                self.value_types.push(t);
+            }
 
-                Opcode::Gt => {
-                    func.instruction(&Instruction::I64GtS);
-                    func.instruction(&Instruction::I64ExtendI32U);
-                }
+            Opcode::StoreLocal(idx) => {
+                func.instruction(&Instruction::LocalSet(*idx));
+                self.value_types.pop();
+            }
 
-                Opcode::Ge => {
-                    func.instruction(&Instruction::I64GeS);
-                    func.instruction(&Instruction::I64ExtendI32U);
-                }
+            Opcode::LoadGlobal(idx) => {
+                func.instruction(&Instruction::GlobalGet(*idx));
+                self.value_types.push(SlotType::I64);
+            }
 
-                Opcode::And => {
-                    func.instruction(&Instruction::I64And);
-                }
+            Opcode::StoreGlobal(idx) => {
+                func.instruction(&Instruction::GlobalSet(*idx));
+                self.value_types.pop();
+            }
 
-                Opcode::Or => {
-                    func.instruction(&Instruction::I64Or);
-                }
+            Opcode::Add => {
+                let b = self.value_types.pop().unwrap_or(SlotType::I64);
+                let a = self.value_types.pop().unwrap_or(SlotType::I64);
+                let result = self.coerce_binary_operands(func, a, b);
+                match result {
+                    SlotType::F64 => func.instruction(&Instruction::F64Add),
+                    SlotType::I64 => func.instruction(&Instruction::I64Add),
+                };
+                self.value_types.push(result);
+            }
 
-                Opcode::Not => {
-                    func.instruction(&Instruction::I64Eqz);
-                    func.instruction(&Instruction::I64ExtendI32U);
-                }
+            Opcode::Neg => {
+                let t = self.value_types.pop().unwrap_or(SlotType::I64);
+                match t {
+                    SlotType::F64 => {
+                        func.instruction(&Instruction::F64Neg);
+                    }
+                    SlotType::I64 => {
+                        // Negate: push the value, push 0, subtract (0 - value)
+                        // But we need to reorder since we have value on stack
+                        // Use: local.tee 0, i64.const 0, local.get 0, i64.sub
+                        func.instruction(&Instruction::LocalTee(0));
+                        func.instruction(&Instruction::Drop);
+                        func.instruction(&Instruction::I64Const(0));
+                        func.instruction(&Instruction::LocalGet(0));
+                        func.instruction(&Instruction::I64Sub);
+                    }
+                };
+                self.value_types.push(t);
+            }
 
-                Opcode::Jump(target) => {
-                    // Calculate relative jump depth for WASM block structure
-                    // WASM uses structured control flow, need to emit proper blocks
-                    let depth = self.calculate_jump_depth(pc, *target as usize, opcodes);
-                    func.instruction(&Instruction::Br(depth));
-                }
+            Opcode::Eq | Opcode::Ne | Opcode::Lt | Opcode::Le | Opcode::Gt | Opcode::Ge => {
+                let b = self.value_types.pop().unwrap_or(SlotType::I64);
+                let a = self.value_types.pop().unwrap_or(SlotType::I64);
+                let operand_type = self.coerce_binary_operands(func, a, b);
+                match (opcode, operand_type) {
+                    (Opcode::Eq, SlotType::F64) => func.instruction(&Instruction::F64Eq),
+                    (Opcode::Eq, SlotType::I64) => func.instruction(&Instruction::I64Eq),
+                    (Opcode::Ne, SlotType::F64) => func.instruction(&Instruction::F64Ne),
+                    (Opcode::Ne, SlotType::I64) => func.instruction(&Instruction::I64Ne),
+                    (Opcode::Lt, SlotType::F64) => func.instruction(&Instruction::F64Lt),
+                    (Opcode::Lt, SlotType::I64) => func.instruction(&Instruction::I64LtS),
+                    (Opcode::Le, SlotType::F64) => func.instruction(&Instruction::F64Le),
+                    (Opcode::Le, SlotType::I64) => func.instruction(&Instruction::I64LeS),
+                    (Opcode::Gt, SlotType::F64) => func.instruction(&Instruction::F64Gt),
+                    (Opcode::Gt, SlotType::I64) => func.instruction(&Instruction::I64GtS),
+                    (Opcode::Ge, SlotType::F64) => func.instruction(&Instruction::F64Ge),
+                    (Opcode::Ge, SlotType::I64) => func.instruction(&Instruction::I64GeS),
+                    _ => unreachable!("only comparison opcodes reach this arm"),
+                };
+                func.instruction(&Instruction::I64ExtendI32U);
+                self.value_types.push(SlotType::I64); // comparisons always yield a bool
+            }
 
-                Opcode::JumpIfFalse(target) => {
-                    // Conditional branch
-                    func.instruction(&Instruction::I64Eqz);
-                    func.instruction(&Instruction::I32WrapI64);
-                    let depth = self.calculate_jump_depth(pc, *target as usize, opcodes);
-                    func.instruction(&Instruction::BrIf(depth));
-                }
+            Opcode::And => {
+                func.instruction(&Instruction::I64And);
+                self.value_types.pop();
+                self.value_types.pop();
+                self.value_types.push(SlotType::I64);
+            }
 
-                Opcode::JumpIfTrue(target) => {
-                    func.instruction(&Instruction::I32WrapI64);
-                    let depth = self.calculate_jump_depth(pc, *target as usize, opcodes);
-                    func.instruction(&Instruction::BrIf(depth));
-                }
+            Opcode::Or => {
+                func.instruction(&Instruction::I64Or);
+                self.value_types.pop();
+                self.value_types.pop();
+                self.value_types.push(SlotType::I64);
+            }
 
-                Opcode::Call(func_idx) => {
-                    // Offset by 1 because main function is at index 0
-                    func.instruction(&Instruction::Call(*func_idx + 1));
-                }
+            Opcode::Not => {
+                func.instruction(&Instruction::I64Eqz);
+                func.instruction(&Instruction::I64ExtendI32U);
+            }
 
-                Opcode::Return => {
-                    func.instruction(&Instruction::Return);
-                }
+            Opcode::Jump(_) | Opcode::JumpIfFalse(_) | Opcode::JumpIfTrue(_) => {
+                unreachable!("jumps are consumed as basic-block terminators in compile_opcodes")
+            }
 
-                Opcode::Print => {
-                    // Print would need to call an imported host function
-                    // For now, just drop the value (no-op in pure WASM)
-                    func.instruction(&Instruction::Drop);
+            Opcode::Call(func_idx) => {
+                // Offset past the imports (and the main function, if any),
+                // since user functions are numbered from 0 but share WASM's
+                // single function index space with both of those.
+                func.instruction(&Instruction::Call(self.user_function_offset + *func_idx));
+                let arity = self.function_arities.get(*func_idx as usize).copied().unwrap_or(0);
+                for _ in 0..arity {
+                    self.value_types.pop();
                 }
+                let returns_float = self.function_returns_float.get(*func_idx as usize).copied().unwrap_or(false);
+                self.value_types.push(if returns_float { SlotType::F64 } else { SlotType::I64 });
+            }
 
-                Opcode::MakeList(count) => {
-                    // Lists require memory allocation - for now, just handle count
-                    // Drop all elements and push 0 (placeholder)
-                    for _ in 0..*count {
-                        func.instruction(&Instruction::Drop);
-                    }
-                    func.instruction(&Instruction::I64Const(0));
-                }
+            Opcode::Return => {
+                func.instruction(&Instruction::Return);
+                self.value_types.pop();
+            }
 
-                Opcode::MakeTuple(count) => {
-                    // Similar to list
-                    for _ in 0..*count {
+            Opcode::Print => {
+                let t = self.value_types.pop().unwrap_or(SlotType::I64);
+                let idx = match t {
+                    SlotType::F64 => self.print_f64_idx,
+                    SlotType::I64 => self.print_i64_idx,
+                };
+                match idx {
+                    Some(idx) => {
+                        func.instruction(&Instruction::Call(idx));
+                    }
+                    None => {
+                        // No printing import of this type was configured for
+                        // this module -- drop the value rather than calling
+                        // a function index that doesn't exist.
                         func.instruction(&Instruction::Drop);
                     }
-                    func.instruction(&Instruction::I64Const(0));
                 }
+            }
 
-                Opcode::BeginReverse | Opcode::EndReverse => {
-                    // Reverse blocks are handled at compile time
-                }
+            Opcode::MakeList(count) => {
+                let elem_types = self.pop_element_types(*count);
+                self.emit_make_aggregate(func, *count, true, &elem_types);
+                self.value_types.push(SlotType::I64);
+            }
 
-                Opcode::Halt => {
-                    func.instruction(&Instruction::Unreachable);
-                }
+            Opcode::MakeTuple(count) => {
+                let elem_types = self.pop_element_types(*count);
+                self.emit_make_aggregate(func, *count, false, &elem_types);
+                self.value_types.push(SlotType::I64);
+            }
+
+            Opcode::Index => {
+                self.value_types.pop();
+                self.value_types.pop();
+                self.value_types.push(SlotType::I64);
+                // Stack: [handle: i64, index: i64]. Only lists carry the
+                // length-word header `emit_make_aggregate` writes, and
+                // `Opcode::Index` is only ever compiled from `DataExpr::Index`
+                // (list subscripting); tuple field access goes through
+                // `DataExpr::FieldAccess`, which the bytecode compiler
+                // doesn't support yet either, so there's no tuple layout to
+                // reconcile here.
+                func.instruction(&Instruction::LocalSet(self.scratch_b)); // index
+                func.instruction(&Instruction::LocalSet(self.scratch_a)); // handle
+                func.instruction(&Instruction::LocalGet(self.scratch_a));
+                func.instruction(&Instruction::I32WrapI64);
+                func.instruction(&Instruction::LocalGet(self.scratch_b));
+                func.instruction(&Instruction::I32WrapI64);
+                func.instruction(&Instruction::I32Const(3)); // * 8
+                func.instruction(&Instruction::I32Shl);
+                func.instruction(&Instruction::I32Add);
+                func.instruction(&Instruction::I64Load(MemArg {
+                    offset: 8, // skip the length word
+                    align: 3,
+                    memory_index: 0,
+                }));
+            }
+
+            Opcode::BeginReverse | Opcode::EndReverse => {
+                // Reverse blocks are handled at compile time
             }
 
-            pc += 1;
+            Opcode::Halt => {
+                func.instruction(&Instruction::Unreachable);
+            }
         }
 
         Ok(())
     }
 
-    fn calculate_jump_depth(&self, _from: usize, _to: usize, _opcodes: &[Opcode]) -> u32 {
-        // Simplified: return 0 for now, proper implementation needs control flow analysis
-        // WASM structured control flow requires converting to blocks/loops
-        0
+    /// Coerces the top two stack values (in place, preserving their
+    /// relative order) so both are the same type, converting any `I64`
+    /// operand to `F64` via a real numeric conversion (not a bit
+    /// reinterpret) when the two differ -- matching the interpreter's
+    /// Int+Float coercion in `add_values`/the comparison helpers. Returns
+    /// the resulting common type. No-op (and returns `a`) when `a == b`.
+    fn coerce_binary_operands(&mut self, func: &mut Function, a: SlotType, b: SlotType) -> SlotType {
+        if a == b {
+            return a;
+        }
+        // Exactly one of the two is Float, so both become Float. `b` is on
+        // top of the stack; convert it in place, stash it in a scratch
+        // local so `a` (now on top) can be converted too, then restore `b`.
+        if b == SlotType::I64 {
+            func.instruction(&Instruction::F64ConvertI64S);
+        }
+        func.instruction(&Instruction::LocalSet(self.scratch_f64));
+        if a == SlotType::I64 {
+            func.instruction(&Instruction::F64ConvertI64S);
+        }
+        func.instruction(&Instruction::LocalGet(self.scratch_f64));
+        SlotType::F64
+    }
+
+    /// Pops and returns the abstract types of the top `count` operand-stack
+    /// slots, in push order (index 0 is the first-pushed/deepest element).
+    fn pop_element_types(&mut self, count: u32) -> Vec<SlotType> {
+        let mut types = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            types.push(self.value_types.pop().unwrap_or(SlotType::I64));
+        }
+        types.reverse();
+        types
+    }
+
+    /// Bump-allocates room for `count` elements in linear memory (plus a
+    /// leading 8-byte length word for lists), pops `count` values off the
+    /// stack into it in order, advances the heap pointer past what was
+    /// written, and pushes the base address back as an i64 handle. This is
+    /// what gives compiled `MakeList`/`MakeTuple` real aggregate values
+    /// instead of dropping everything and pushing 0: the interpreter already
+    /// represents lists and tuples as real values, and this is the memory
+    /// layout that lets compiled code do the same.
+    ///
+    /// `elem_types[i]` is the type (in push order) of the i-th element;
+    /// `F64` elements are bit-reinterpreted to `i64` before being stored,
+    /// since every memory slot in this layout is 8 raw bytes, same as the
+    /// `i64`-lowering the rest of this backend uses for everything that
+    /// isn't arithmetic on a real float.
+    fn emit_make_aggregate(&mut self, func: &mut Function, count: u32, has_length_header: bool, elem_types: &[SlotType]) {
+        let header_bytes: u64 = if has_length_header { 8 } else { 0 };
+
+        func.instruction(&Instruction::GlobalGet(HEAP_PTR_GLOBAL));
+        func.instruction(&Instruction::LocalSet(self.heap_base_local));
+
+        // Elements arrive on the stack in order with the last one on top, so
+        // popping them off (in that same top-down order) and storing each at
+        // its final offset lands every element in the right place without
+        // needing to reverse anything.
+        for i in (0..count).rev() {
+            if elem_types.get(i as usize).copied() == Some(SlotType::F64) {
+                func.instruction(&Instruction::I64ReinterpretF64);
+            }
+            func.instruction(&Instruction::LocalSet(self.scratch_a));
+            func.instruction(&Instruction::LocalGet(self.heap_base_local));
+            func.instruction(&Instruction::LocalGet(self.scratch_a));
+            func.instruction(&Instruction::I64Store(MemArg {
+                offset: header_bytes + (i as u64) * 8,
+                align: 3,
+                memory_index: 0,
+            }));
+        }
+
+        if has_length_header {
+            func.instruction(&Instruction::LocalGet(self.heap_base_local));
+            func.instruction(&Instruction::I64Const(count as i64));
+            func.instruction(&Instruction::I64Store(MemArg {
+                offset: 0,
+                align: 3,
+                memory_index: 0,
+            }));
+        }
+
+        func.instruction(&Instruction::LocalGet(self.heap_base_local));
+        func.instruction(&Instruction::I32Const((header_bytes + (count as u64) * 8) as i32));
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::GlobalSet(HEAP_PTR_GLOBAL));
+
+        func.instruction(&Instruction::LocalGet(self.heap_base_local));
+        func.instruction(&Instruction::I64ExtendI32U);
+    }
+}
+
+/// One basic block: straight-line opcodes followed by at most one jump.
+struct BasicBlock {
+    body: Vec<Opcode>,
+    term: Option<(TermKind, usize)>, // (kind, target *block* index)
+}
+
+#[derive(Clone, Copy)]
+enum TermKind {
+    Jump,
+    JumpIfFalse,
+    JumpIfTrue,
+}
+
+/// A `block`/`loop` scope currently open while walking basic blocks.
+/// `branch_match` is the block index a jump resolves to this scope for
+/// (for `Loop`, that's the header, since WASM branches to a loop label
+/// continue the loop rather than exiting it); `close_at` is the block
+/// index reached once this scope's `end` should be emitted.
+struct OpenLabel {
+    branch_match: usize,
+    close_at: usize,
+}
+
+/// Splits a flat opcode stream into basic blocks, starting a new block at
+/// instruction 0, at every jump target, and right after every jump.
+fn split_into_blocks(opcodes: &[Opcode]) -> Vec<BasicBlock> {
+    if opcodes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut leaders: Vec<usize> = vec![0];
+    for (pc, op) in opcodes.iter().enumerate() {
+        if let Opcode::Jump(t) | Opcode::JumpIfFalse(t) | Opcode::JumpIfTrue(t) = op {
+            leaders.push(*t as usize);
+            if pc + 1 < opcodes.len() {
+                leaders.push(pc + 1);
+            }
+        }
     }
+    leaders.sort_unstable();
+    leaders.dedup();
+
+    let pc_to_block: HashMap<usize, usize> =
+        leaders.iter().enumerate().map(|(i, &pc)| (pc, i)).collect();
+    // A jump can target one past the last instruction (falling off the end
+    // of the function); that position has no basic block of its own, so it
+    // resolves to a virtual index right after the last real block.
+    let resolve = |target: usize| -> usize {
+        pc_to_block.get(&target).copied().unwrap_or(leaders.len())
+    };
+
+    leaders
+        .iter()
+        .enumerate()
+        .map(|(bi, &start)| {
+            let end = leaders.get(bi + 1).copied().unwrap_or(opcodes.len());
+            let mut body = Vec::new();
+            let mut term = None;
+            for op in &opcodes[start..end] {
+                match op {
+                    Opcode::Jump(t) => term = Some((TermKind::Jump, resolve(*t as usize))),
+                    Opcode::JumpIfFalse(t) => term = Some((TermKind::JumpIfFalse, resolve(*t as usize))),
+                    Opcode::JumpIfTrue(t) => term = Some((TermKind::JumpIfTrue, resolve(*t as usize))),
+                    other => body.push(other.clone()),
+                }
+            }
+            BasicBlock { body, term }
+        })
+        .collect()
+}
+
+/// Maps each loop header (a block targeted by a backward jump) to the block
+/// index right after its last back-edge, i.e. where the loop exits to.
+fn find_loop_headers(blocks: &[BasicBlock]) -> HashMap<usize, usize> {
+    let mut headers = HashMap::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if let Some((TermKind::Jump, target)) = block.term {
+            if target <= i {
+                let loop_end = i + 1;
+                headers
+                    .entry(target)
+                    .and_modify(|end: &mut usize| *end = (*end).max(loop_end))
+                    .or_insert(loop_end);
+            }
+        }
+    }
+    headers
+}
+
+/// Recognizes the `if { .. } else { .. }` shape: the "then" branch (ending
+/// right before `target`) itself ends with an unconditional forward jump
+/// past the "else" branch. When present, that jump's target is the true
+/// merge point and must become the *outer* scope, with `target` (the
+/// "else" entry point) nested inside it.
+fn if_else_merge(blocks: &[BasicBlock], target: usize) -> Option<usize> {
+    let then_tail = target.checked_sub(1)?;
+    match blocks.get(then_tail)?.term {
+        Some((TermKind::Jump, merge)) if merge > target => Some(merge),
+        _ => None,
+    }
+}
+
+/// Depth (for `Br`/`BrIf`) of the innermost open scope resolving `target`.
+fn find_label_depth(labels: &[OpenLabel], target: usize) -> Option<u32> {
+    labels
+        .iter()
+        .rev()
+        .position(|label| label.branch_match == target)
+        .map(|pos| pos as u32)
+}
+
+/// Infers each local slot's WASM type (`I64` or `F64`) from how
+/// `BytecodeCompiler` used it, by walking the function's opcodes once in
+/// their literal program order (the same order `compile_opcodes` emits
+/// them in) and tracking an abstract value-type stack alongside it. Params
+/// seed their own slots directly from the declared signature (`param_types`);
+/// every other slot starts `I64` and is overwritten by whatever type a
+/// `StoreLocal` commits to it. This isn't a general dataflow analysis -- like
+/// the Relooper pass above, it trusts the bytecode compiler's own emission
+/// shape (here, that a variable's stores are already type-consistent, since
+/// that's a typechecker concern, not a codegen one) rather than verifying it.
+fn infer_local_types(
+    opcodes: &[Opcode],
+    param_types: &[SlotType],
+    local_count: u32,
+    fn_arities: &[usize],
+    fn_returns_float: &[bool],
+    constants: &[BcValue],
+) -> Vec<SlotType> {
+    let mut locals = vec![SlotType::I64; local_count as usize];
+    for (i, &t) in param_types.iter().enumerate() {
+        if i < locals.len() {
+            locals[i] = t;
+        }
+    }
+
+    let mut stack: Vec<SlotType> = Vec::new();
+    for op in opcodes {
+        match op {
+            Opcode::Push(BcValue::Float(_)) => stack.push(SlotType::F64),
+            Opcode::Push(_) => stack.push(SlotType::I64),
+            Opcode::LoadConst(idx) => {
+                stack.push(match constants.get(*idx as usize) {
+                    Some(BcValue::Float(_)) => SlotType::F64,
+                    _ => SlotType::I64,
+                });
+            }
+            Opcode::LoadLocal(idx) => {
+                stack.push(locals.get(*idx as usize).copied().unwrap_or(SlotType::I64));
+            }
+            Opcode::StoreLocal(idx) => {
+                let t = stack.pop().unwrap_or(SlotType::I64);
+                if let Some(slot) = locals.get_mut(*idx as usize) {
+                    *slot = t;
+                }
+            }
+            Opcode::LoadGlobal(_) => stack.push(SlotType::I64),
+            Opcode::StoreGlobal(_) => {
+                stack.pop();
+            }
+            Opcode::Add => {
+                let b = stack.pop().unwrap_or(SlotType::I64);
+                let a = stack.pop().unwrap_or(SlotType::I64);
+                stack.push(if a == SlotType::F64 || b == SlotType::F64 { SlotType::F64 } else { SlotType::I64 });
+            }
+            Opcode::Neg => {} // unary, type unchanged
+            Opcode::Eq | Opcode::Ne | Opcode::Lt | Opcode::Le | Opcode::Gt | Opcode::Ge => {
+                stack.pop();
+                stack.pop();
+                stack.push(SlotType::I64); // comparisons always yield a bool
+            }
+            Opcode::And | Opcode::Or => {
+                stack.pop();
+                stack.pop();
+                stack.push(SlotType::I64);
+            }
+            Opcode::Not => {}
+            Opcode::Dup => {
+                if let Some(&t) = stack.last() {
+                    stack.push(t);
+                }
+            }
+            Opcode::Pop => {
+                stack.pop();
+            }
+            Opcode::JumpIfFalse(_) | Opcode::JumpIfTrue(_) => {
+                stack.pop();
+            }
+            Opcode::Jump(_) => {}
+            Opcode::Call(idx) => {
+                let arity = fn_arities.get(*idx as usize).copied().unwrap_or(0);
+                for _ in 0..arity {
+                    stack.pop();
+                }
+                let returns_float = fn_returns_float.get(*idx as usize).copied().unwrap_or(false);
+                stack.push(if returns_float { SlotType::F64 } else { SlotType::I64 });
+            }
+            Opcode::Return => {
+                stack.pop();
+            }
+            Opcode::Print => {
+                stack.pop();
+            }
+            Opcode::MakeList(n) | Opcode::MakeTuple(n) => {
+                for _ in 0..*n {
+                    stack.pop();
+                }
+                stack.push(SlotType::I64);
+            }
+            Opcode::Index => {
+                stack.pop();
+                stack.pop();
+                stack.push(SlotType::I64);
+            }
+            Opcode::BeginReverse | Opcode::EndReverse | Opcode::Halt => {}
+        }
+    }
+
+    locals
 }
 
 impl Default for WasmGenerator {
@@ -381,4 +1314,131 @@ fn add(a: Int, b: Int): Int {
         // Check WASM version (1)
         assert_eq!(&wasm[4..8], &[0x01, 0x00, 0x00, 0x00]);
     }
+
+    #[test]
+    fn test_compile_if_else() {
+        let source = r#"
+fn choose(a: Int, b: Int): Int {
+    if a > b {
+        return a
+    } else {
+        return b
+    }
+}
+"#;
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_print_calls_the_print_import() {
+        let source = "print(42)";
+        let wasm = compile_to_wasm(source).unwrap();
+        // env.print_i64 is imported first, so it gets function index 0.
+        assert!(WasmGenerator::new().print_i64_idx == Some(0));
+        assert_eq!(&wasm[0..4], &[0x00, 0x61, 0x73, 0x6D]);
+    }
+
+    #[test]
+    fn test_compile_while_loop() {
+        let source = r#"
+fn countdown(n: Int): Int {
+    while n > 0 {
+        n = n + -1
+    }
+    return n
+}
+"#;
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_compile_list_literal() {
+        let source = "xs = [1, 2, 3]";
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_compile_list_index() {
+        let source = r#"
+fn first(xs: List): Int {
+    return xs[0]
+}
+"#;
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_compile_float_function() {
+        // Mixed int/float params exercise both the typed-signature lowering
+        // (param_is_float) and coerce_binary_operands's int->float promotion.
+        let source = r#"
+fn addf(a: Float, b: Int): Float {
+    return a + b
+}
+"#;
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validation_passes_on_well_formed_output() {
+        // `compile_to_wasm` already validates by default in debug builds, so
+        // this only fails if a Relooper/type-tracking regression produces a
+        // structurally broken module.
+        let source = r#"
+fn countdown(n: Int): Int {
+    while n > 0 {
+        n = n + -1
+    }
+    return n
+}
+"#;
+        assert!(compile_to_wasm(source).is_ok());
+    }
+
+    #[test]
+    fn test_validate_can_be_disabled() {
+        let mut gen = WasmGenerator::new();
+        gen.set_validate(false);
+        assert!(!gen.validate);
+    }
+
+    #[test]
+    fn test_source_map_records_one_entry_per_function() {
+        let source = r#"
+fn add(a: Int, b: Int): Int {
+    return a + b
+}
+"#;
+        let program = crate::parser::parse_program(source).unwrap();
+        let compiled_module = crate::bytecode::BytecodeCompiler::new().compile(&program).unwrap();
+
+        let mut gen = WasmGenerator::new();
+        gen.set_record_source_map(true);
+        let wasm = gen.compile(&compiled_module).unwrap();
+        let map = gen.take_source_map();
+
+        assert_eq!(map.functions.len(), 1);
+        assert_eq!(map.functions[0].name, "add");
+        assert!(!map.functions[0].instructions.is_empty());
+        assert!(map.functions[0].end_offset > map.functions[0].start_offset);
+        // The "name" and "jtv-source-map" custom sections should have made
+        // it into the module bytes too.
+        assert!(wasm.len() > 8);
+    }
+
+    #[test]
+    fn test_compile_float_comparison() {
+        let source = r#"
+fn greater(a: Float, b: Float): Bool {
+    return a > b
+}
+"#;
+        let result = compile_to_wasm(source);
+        assert!(result.is_ok());
+    }
 }
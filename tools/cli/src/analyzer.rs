@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper CLI - `jtv analyze`: legacy-extraction candidate scanner
+//
+// A native replacement for the Deno/TypeScript analyzer this command used
+// to shell out to. Each supported language gets a lightweight, purely
+// textual scanner behind the `Analyzer` trait -- no external parser, just
+// line-oriented heuristics for "does this function look like it could be
+// mechanically lifted into JtV's pure-function model": no I/O calls, no
+// mutation of anything outside its own locals, and a body whose returns
+// only ever reference its own parameters (or literals).
+//
+// These are heuristics, not a proof -- a `Candidate`'s `purity_score` and
+// `reasons` are meant to rank and explain, not to guarantee the function
+// really is pure. A human still reviews the candidate before extracting it.
+
+use colored::*;
+use serde::Serialize;
+use std::fs;
+
+/// A function-shaped span of source that looks like it could be
+/// extracted into JtV's pure-function model.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub name: String,
+    /// 1-based, inclusive.
+    pub start_line: usize,
+    /// 1-based, inclusive.
+    pub end_line: usize,
+    /// `0.0` (looks thoroughly impure) to `1.0` (no red flags found at
+    /// all) -- see `Analyzer::analyze` implementations for exactly which
+    /// patterns cost how much.
+    pub purity_score: f64,
+    /// Why this score, both the deductions and the all-clear checks that
+    /// passed -- e.g. `"no network/file calls detected"`.
+    pub reasons: Vec<String>,
+}
+
+/// A per-language legacy-extraction scanner. Implementations are
+/// intentionally dumb relative to a real parser: they scan source lines
+/// for language-specific function-definition syntax and a fixed set of
+/// impurity signals, rather than building and walking a real AST.
+pub trait Analyzer {
+    fn lang(&self) -> &'static str;
+    fn analyze(&self, source: &str) -> Vec<Candidate>;
+}
+
+/// Returns the `Analyzer` registered for `lang`, or `None` if `lang`
+/// isn't supported -- the `--lang` values this match covers are the same
+/// ones the old Deno tool accepted.
+pub fn analyzer_for(lang: &str) -> Option<Box<dyn Analyzer>> {
+    match lang {
+        "python" => Some(Box::new(PythonAnalyzer)),
+        "javascript" => Some(Box::new(JavaScriptAnalyzer)),
+        "ruby" => Some(Box::new(RubyAnalyzer)),
+        _ => None,
+    }
+}
+
+/// Runs `jtv analyze`: reads `file`, scans it with whichever `Analyzer`
+/// `lang` names, and prints a ranked report (highest `purity_score`
+/// first) as either annotated text or a `--format json` array. Returns
+/// `false` on an unsupported `lang` or an unreadable file.
+pub fn run_analyze(file: &str, lang: &str, format: &str) -> bool {
+    let Some(analyzer) = analyzer_for(lang) else {
+        eprintln!(
+            "{} unsupported --lang '{}' (expected one of: python, javascript, ruby)",
+            "error:".red().bold(),
+            lang
+        );
+        return false;
+    };
+
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{} couldn't read {}: {}", "error:".red().bold(), file, e);
+            return false;
+        }
+    };
+
+    let candidates = analyzer.analyze(&source);
+
+    if format == "json" {
+        match serde_json::to_string_pretty(&candidates) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("{} failed to serialize candidates: {}", "error:".red().bold(), e);
+                return false;
+            }
+        }
+        return true;
+    }
+
+    if candidates.is_empty() {
+        println!("No extraction candidates found in {}", file);
+        return true;
+    }
+
+    println!(
+        "{} {} extraction candidate(s) in {} ({})",
+        "=== JtV Extraction Analysis ===".cyan().bold(),
+        candidates.len(),
+        file,
+        lang
+    );
+    for candidate in &candidates {
+        println!(
+            "\n{} (lines {}-{}) -- purity score {:.2}",
+            candidate.name.yellow().bold(),
+            candidate.start_line,
+            candidate.end_line,
+            candidate.purity_score
+        );
+        for reason in &candidate.reasons {
+            println!("  - {}", reason);
+        }
+    }
+    true
+}
+
+/// Names that, if called anywhere in a function's body, are a strong
+/// signal of I/O or other non-determinism -- shared across the three
+/// scanners since "don't talk to the network/filesystem/console/clock/
+/// RNG" is the same impurity regardless of language.
+const IO_CALL_NAMES: &[&str] = &[
+    "open", "read", "write", "print", "println", "puts", "input", "fetch", "request", "socket",
+    "connect", "query", "execute", "now", "random", "rand", "sleep", "exit", "system", "exec",
+    "require", "import",
+];
+
+/// Scores and explains a candidate's body once a language-specific scanner
+/// has already extracted its `params` and `body` lines -- the part that's
+/// genuinely shared, unlike finding the function's boundaries which is
+/// different syntax per language.
+fn score_body(params: &[String], body: &[&str], io_calls: &[&str], global_markers: &[&str]) -> (f64, Vec<String>) {
+    let mut score = 1.0;
+    let mut reasons = Vec::new();
+
+    let joined = body.join("\n");
+
+    let found_io: Vec<&str> = io_calls.iter().filter(|name| mentions_call(&joined, name)).copied().collect();
+    if found_io.is_empty() {
+        reasons.push("no network/file/console calls detected".to_string());
+    } else {
+        score -= 0.4;
+        reasons.push(format!("calls flagged as I/O or non-deterministic: {}", found_io.join(", ")));
+    }
+
+    let found_globals: Vec<&str> = global_markers.iter().filter(|marker| joined.contains(*marker)).copied().collect();
+    if found_globals.is_empty() {
+        reasons.push("no global/outer-scope mutation markers found".to_string());
+    } else {
+        score -= 0.4;
+        reasons.push(format!("possible global/outer-scope mutation: {}", found_globals.join(", ")));
+    }
+
+    if returns_depend_only_on(&joined, params) {
+        reasons.push("all returns depend only on parameters or literals".to_string());
+    } else {
+        score -= 0.2;
+        reasons.push("a return references a name outside its parameters".to_string());
+    }
+
+    (score.max(0.0), reasons)
+}
+
+fn mentions_call(body: &str, name: &str) -> bool {
+    body.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+        .any(|token| token == *name || token.ends_with(&format!(".{}", name)))
+}
+
+/// Heuristic for "every `return`'s expression only references this
+/// function's own parameters (or nothing -- a literal/bare `return`)":
+/// collects the identifier-looking tokens on each `return` line and checks
+/// each one is either a parameter name, a number, or a known keyword/
+/// literal (`None`, `True`, `False`, `null`, `nil`, ...). Conservative by
+/// construction -- any identifier it doesn't recognize counts against
+/// purity, which undercounts pure functions that call another pure
+/// function (a known limitation; see the request this shipped for).
+fn returns_depend_only_on(body: &str, params: &[String]) -> bool {
+    const ALLOWED_LITERALS: &[&str] = &["None", "True", "False", "null", "nil", "undefined", "self", "this"];
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        let Some(rest) = trimmed.strip_prefix("return") else { continue };
+        let rest = rest.trim();
+        if rest.is_empty() {
+            continue;
+        }
+        for token in rest.split(|c: char| !c.is_alphanumeric() && c != '_') {
+            if token.is_empty() || token.chars().next().unwrap().is_ascii_digit() {
+                continue;
+            }
+            if params.iter().any(|p| p == token) || ALLOWED_LITERALS.contains(&token) {
+                continue;
+            }
+            return false;
+        }
+    }
+    true
+}
+
+/// Extracts comma-separated parameter names from `params_src` (the text
+/// between a function's parentheses), stripping default values (`=...`),
+/// type annotations (`: Type`), and a leading `*`/`**`/`...` variadic
+/// marker -- just enough to get plain names back for `returns_depend_only_on`.
+fn parse_param_names(params_src: &str) -> Vec<String> {
+    params_src
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let p = p.trim_start_matches('*').trim_start_matches("...").trim();
+            let name = p.split(|c| c == ':' || c == '=').next().unwrap_or("").trim();
+            name.to_string()
+        })
+        .filter(|p| !p.is_empty() && p != "self")
+        .collect()
+}
+
+fn rank(mut candidates: Vec<Candidate>) -> Vec<Candidate> {
+    candidates.sort_by(|a, b| b.purity_score.partial_cmp(&a.purity_score).unwrap());
+    candidates
+}
+
+// ===== Python =====
+
+struct PythonAnalyzer;
+
+impl Analyzer for PythonAnalyzer {
+    fn lang(&self) -> &'static str {
+        "python"
+    }
+
+    fn analyze(&self, source: &str) -> Vec<Candidate> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut candidates = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix("def ") {
+                if let Some(paren) = rest.find('(') {
+                    let name = rest[..paren].trim().to_string();
+                    let close = rest[paren..].find(')').map(|p| paren + p);
+                    let params = close.map(|c| parse_param_names(&rest[paren + 1..c])).unwrap_or_default();
+
+                    let body_start = i + 1;
+                    let mut body_end = lines.len();
+                    for (j, candidate_line) in lines.iter().enumerate().skip(body_start) {
+                        if candidate_line.trim().is_empty() {
+                            continue;
+                        }
+                        let candidate_indent = candidate_line.len() - candidate_line.trim_start().len();
+                        if candidate_indent <= indent {
+                            body_end = j;
+                            break;
+                        }
+                    }
+
+                    let body = &lines[body_start..body_end];
+                    let global_markers: Vec<String> =
+                        ["global ", "nonlocal "].iter().map(|s| s.to_string()).collect();
+                    let global_markers: Vec<&str> = global_markers.iter().map(String::as_str).collect();
+                    let (score, reasons) = score_body(&params, body, IO_CALL_NAMES, &global_markers);
+
+                    candidates.push(Candidate {
+                        name,
+                        start_line: i + 1,
+                        end_line: body_end,
+                        purity_score: score,
+                        reasons,
+                    });
+
+                    i = body_end;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        rank(candidates)
+    }
+}
+
+// ===== JavaScript =====
+
+struct JavaScriptAnalyzer;
+
+impl Analyzer for JavaScriptAnalyzer {
+    fn lang(&self) -> &'static str {
+        "javascript"
+    }
+
+    fn analyze(&self, source: &str) -> Vec<Candidate> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut candidates = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            if let Some((name, params_src)) = js_function_header(lines[i]) {
+                if !lines[i].contains('{') {
+                    i += 1;
+                    continue;
+                }
+                let mut depth = 0i32;
+                let mut body_end = i;
+                for (j, line) in lines.iter().enumerate().skip(i) {
+                    depth += line.matches('{').count() as i32;
+                    depth -= line.matches('}').count() as i32;
+                    if depth <= 0 {
+                        body_end = j;
+                        break;
+                    }
+                }
+
+                let params = parse_param_names(&params_src);
+                let body = &lines[(i + 1).min(body_end)..body_end];
+                let global_markers = ["window.", "document.", "globalThis."];
+                let (score, reasons) = score_body(&params, body, IO_CALL_NAMES, &global_markers);
+
+                candidates.push(Candidate {
+                    name,
+                    start_line: i + 1,
+                    end_line: body_end + 1,
+                    purity_score: score,
+                    reasons,
+                });
+
+                i = body_end + 1;
+                continue;
+            }
+            i += 1;
+        }
+        rank(candidates)
+    }
+}
+
+/// Recognizes `function name(params) {`, `const name = (params) => {`, and
+/// `const name = function(params) {` -- the three shapes most legacy JS
+/// extraction candidates are written in. Returns `(name, params_src)`.
+fn js_function_header(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("function ") {
+        let paren = rest.find('(')?;
+        let close = rest[paren..].find(')')? + paren;
+        return Some((rest[..paren].trim().to_string(), rest[paren + 1..close].to_string()));
+    }
+    for prefix in ["const ", "let ", "var "] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            let eq = rest.find('=')?;
+            let name = rest[..eq].trim().to_string();
+            let after_eq = rest[eq + 1..].trim_start();
+            let paren = after_eq.find('(')?;
+            let close = after_eq[paren..].find(')')? + paren;
+            let is_function_shape = after_eq[close..].trim_start().starts_with("=>")
+                || after_eq.starts_with("function");
+            if is_function_shape {
+                let params_start = if after_eq.starts_with("function") {
+                    after_eq.find('(')?
+                } else {
+                    paren
+                };
+                let params_close = after_eq[params_start..].find(')')? + params_start;
+                return Some((name, after_eq[params_start + 1..params_close].to_string()));
+            }
+        }
+    }
+    None
+}
+
+// ===== Ruby =====
+
+struct RubyAnalyzer;
+
+impl Analyzer for RubyAnalyzer {
+    fn lang(&self) -> &'static str {
+        "ruby"
+    }
+
+    fn analyze(&self, source: &str) -> Vec<Candidate> {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut candidates = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].trim_start();
+            if let Some(rest) = trimmed.strip_prefix("def ") {
+                let name_end = rest.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(rest.len());
+                let name = rest[..name_end].trim().to_string();
+                let params = rest
+                    .find('(')
+                    .and_then(|open| rest[open..].find(')').map(|close| (open, open + close)))
+                    .map(|(open, close)| parse_param_names(&rest[open + 1..close]))
+                    .unwrap_or_default();
+
+                let mut depth = 1i32;
+                let mut body_end = lines.len();
+                for (j, line) in lines.iter().enumerate().skip(i + 1) {
+                    depth += ruby_block_delta(line);
+                    if depth <= 0 {
+                        body_end = j;
+                        break;
+                    }
+                }
+
+                let body = &lines[(i + 1).min(body_end)..body_end];
+                let global_markers = ["$", "@@"];
+                let (score, reasons) = score_body(&params, body, IO_CALL_NAMES, &global_markers);
+
+                candidates.push(Candidate {
+                    name,
+                    start_line: i + 1,
+                    end_line: body_end + 1,
+                    purity_score: score,
+                    reasons,
+                });
+
+                i = body_end + 1;
+                continue;
+            }
+            i += 1;
+        }
+        rank(candidates)
+    }
+}
+
+/// How many more `end`s this line obliges relative to how many it closes
+/// -- `+1` per block-opening keyword, `-1` per `end`, net over the line
+/// (a one-line `if ... end` cancels out). Not a real Ruby parser: doesn't
+/// distinguish a modifier `if`/`unless` (which takes no `end`) from a
+/// block-opening one, so it's conservative rather than exact.
+fn ruby_block_delta(line: &str) -> i32 {
+    let mut delta = 0i32;
+    for word in line.split_whitespace() {
+        let word = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+        if matches!(word, "def" | "do" | "if" | "unless" | "while" | "until" | "case" | "begin" | "class" | "module")
+        {
+            delta += 1;
+        } else if word == "end" {
+            delta -= 1;
+        }
+    }
+    delta
+}
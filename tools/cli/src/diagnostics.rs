@@ -0,0 +1,313 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper CLI - Span-based diagnostic rendering
+//
+// An `ariadne`/`chumsky`-style renderer for `jtv_lang::JtvError`: instead
+// of the flat "Parse error: {message}" strings `run`/`parse`/`check` used
+// to print, a `Diagnostic` carries a severity, a message, a primary label
+// (the span the error is about, plus a short note), optional secondary
+// labels, and an optional one-line `help`, and renders all of it as an
+// annotated source snippet -- offending line(s), an underline spanning
+// the label's span, and the note below it.
+
+use colored::*;
+use jtv_lang::error::Span;
+use jtv_lang::JtvError;
+
+/// How serious a `Diagnostic` is. Only changes the leading word and its
+/// color; rendering is otherwise identical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A span-anchored note attached to a `Diagnostic`. The primary label
+/// says what went wrong at its span; secondary labels add supporting
+/// context (e.g. where a conflicting declaration lives).
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// An annotated diagnostic in the style of `ariadne`/`chumsky`: a
+/// top-level message, a primary label every diagnostic has (it always
+/// points somewhere), zero or more secondary labels, and an optional
+/// one-line `help`.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub primary: Label,
+    pub secondary: Vec<Label>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>, primary: Label) -> Self {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            primary,
+            secondary: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn with_secondary(mut self, label: Label) -> Self {
+        self.secondary.push(label);
+        self
+    }
+
+    /// Builds an error `Diagnostic` from a `JtvError`, anchored at
+    /// whatever span it carries (see `JtvError::span`). Falls back to a
+    /// zero-width span at the end of `source` for the handful of
+    /// variants that don't carry one, since a `Diagnostic` always needs
+    /// somewhere to point -- `render` clamps an out-of-range span to the
+    /// last line the same way it does for a genuine EOF span.
+    pub fn from_error(err: &JtvError, source: &str) -> Self {
+        let span = err.span().unwrap_or(Span {
+            start: source.len(),
+            end: source.len(),
+        });
+        Diagnostic::new(
+            Severity::Error,
+            err.to_string(),
+            Label {
+                span,
+                message: "here".to_string(),
+            },
+        )
+    }
+
+    /// Renders this diagnostic as an annotated source snippet: a header
+    /// line, a `file:line:col` location line, the primary label's source
+    /// excerpt with its underline and message, each secondary label the
+    /// same way, and the `help` line last if present.
+    pub fn render(&self, file: &str, source: &str) -> String {
+        let mut out = String::new();
+
+        let tag = match self.severity {
+            Severity::Error => "error".red().bold(),
+            Severity::Warning => "warning".yellow().bold(),
+        };
+        out.push_str(&format!("{}: {}\n", tag, self.message.bold()));
+
+        let start = locate(source, self.primary.span.start);
+        out.push_str(&format!(
+            "  {} {}:{}:{}\n",
+            "-->".blue().bold(),
+            display_file(file),
+            start.line,
+            start.col
+        ));
+
+        out.push_str(&render_label(source, &self.primary, true));
+        for label in &self.secondary {
+            out.push_str(&render_label(source, label, false));
+        }
+
+        if let Some(help) = &self.help {
+            out.push_str(&format!("  {} {}\n", "help:".cyan().bold(), help));
+        }
+
+        out
+    }
+}
+
+/// `file`, except the stdin pseudo-path (`-`) renders as `<stdin>` --
+/// `-` is meaningful to the shell, not to a reader of the diagnostic.
+fn display_file(file: &str) -> &str {
+    if file == "-" {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+/// 1-based (line, column) for byte offset `offset` into `source`.
+/// Columns count chars, not bytes, so a multi-byte UTF-8 character
+/// before the span doesn't throw off the underline's position. Clamps
+/// to the last line/column when `offset` is at or past the end of
+/// `source` (an EOF span), rather than panicking or pointing nowhere.
+fn locate(source: &str, offset: usize) -> Position {
+    let mut line = 1usize;
+    let mut col = 1usize;
+    for (i, ch) in source.char_indices() {
+        if i >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    Position { line, col }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    line: usize,
+    col: usize,
+}
+
+/// Renders `label`'s source line, an underline spanning its span's
+/// columns, and its message below -- in `primary`'s color (red) when
+/// it's the diagnostic's primary label, otherwise a secondary's color
+/// (yellow). A span that crosses a newline only underlines its first
+/// line (underlining to end-of-line) and adds a one-line note for how
+/// many more lines the span covers, rather than trying to underline
+/// every line it touches.
+fn render_label(source: &str, label: &Label, primary: bool) -> String {
+    let start = locate(source, label.span.start);
+    let end = locate(source, label.span.end.max(label.span.start));
+    let lines: Vec<&str> = source.lines().collect();
+    let line_idx = start.line.saturating_sub(1).min(lines.len().saturating_sub(1));
+    let text = lines.get(line_idx).copied().unwrap_or("");
+
+    let gutter = format!("{:>4}", start.line.min(lines.len().max(1)));
+    let gutter_pad = " ".repeat(gutter.len());
+
+    let end_col = if end.line == start.line {
+        end.col.max(start.col + 1)
+    } else {
+        text.chars().count() + 2
+    };
+    let underline_width = end_col.saturating_sub(start.col).max(1);
+    let marker = if primary { "^" } else { "-" };
+    let underline = marker.repeat(underline_width);
+    let underline = if primary { underline.red().bold() } else { underline.yellow() };
+
+    let mut out = String::new();
+    out.push_str(&format!("{} {} {}\n", gutter, "|".blue().bold(), text));
+    out.push_str(&format!(
+        "{} {} {}{} {}\n",
+        gutter_pad,
+        "|".blue().bold(),
+        " ".repeat(start.col.saturating_sub(1)),
+        underline,
+        label.message
+    ));
+    if end.line != start.line {
+        out.push_str(&format!(
+            "{} {} ...spans {} more line(s)\n",
+            gutter_pad,
+            "|".blue().bold(),
+            end.line - start.line
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut in_escape = false;
+        for ch in s.chars() {
+            if ch == '\u{1b}' {
+                in_escape = true;
+            } else if in_escape {
+                if ch == 'm' {
+                    in_escape = false;
+                }
+            } else {
+                out.push(ch);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_render_single_line_span() {
+        let source = "x = 1 +\n";
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "expected an operand",
+            Label { span: Span { start: 6, end: 7 }, message: "here".to_string() },
+        );
+        let rendered = strip_ansi(&diag.render("example.jtv", source));
+        assert!(rendered.contains("error: expected an operand"));
+        assert!(rendered.contains("--> example.jtv:1:7"));
+        assert!(rendered.contains("x = 1 +"));
+        assert!(rendered.contains("^ here"));
+    }
+
+    #[test]
+    fn test_render_uses_stdin_pseudo_name() {
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "boom",
+            Label { span: Span { start: 0, end: 1 }, message: "here".to_string() },
+        );
+        let rendered = strip_ansi(&diag.render("-", "x"));
+        assert!(rendered.contains("<stdin>:1:1"));
+    }
+
+    #[test]
+    fn test_render_counts_utf8_columns_not_bytes() {
+        let source = "x = \"héllo\" + 1";
+        // Byte offset of the `+`, which sits after a 2-byte 'é'.
+        let plus_offset = source.find('+').unwrap();
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "type mismatch",
+            Label { span: Span { start: plus_offset, end: plus_offset + 1 }, message: "here".to_string() },
+        );
+        let rendered = strip_ansi(&diag.render("example.jtv", source));
+        let char_col = source[..plus_offset].chars().count() + 1;
+        assert!(rendered.contains(&format!(":1:{}", char_col)));
+    }
+
+    #[test]
+    fn test_render_clamps_eof_span() {
+        let source = "x = 1";
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "unexpected end of input",
+            Label { span: Span { start: 100, end: 100 }, message: "here".to_string() },
+        );
+        let rendered = strip_ansi(&diag.render("example.jtv", source));
+        assert!(rendered.contains(":1:6"));
+    }
+
+    #[test]
+    fn test_render_multiline_span_notes_extra_lines() {
+        let source = "a = [\n  1,\n  2,\n]\n";
+        let start = source.find('[').unwrap();
+        let end = source.find(']').unwrap() + 1;
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "unterminated list",
+            Label { span: Span { start, end }, message: "starts here".to_string() },
+        );
+        let rendered = strip_ansi(&diag.render("example.jtv", source));
+        assert!(rendered.contains("spans 3 more line(s)"));
+    }
+
+    #[test]
+    fn test_render_includes_help_and_secondary_labels() {
+        let source = "x = y";
+        let diag = Diagnostic::new(
+            Severity::Error,
+            "undefined variable `y`",
+            Label { span: Span { start: 4, end: 5 }, message: "not found".to_string() },
+        )
+        .with_secondary(Label { span: Span { start: 0, end: 1 }, message: "assigned here".to_string() })
+        .with_help("did you mean to declare `y` first?");
+        let rendered = strip_ansi(&diag.render("example.jtv", source));
+        assert!(rendered.contains("- assigned here"));
+        assert!(rendered.contains("help: did you mean to declare `y` first?"));
+    }
+}
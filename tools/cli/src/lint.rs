@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper CLI - `jtv lint`
+//
+// Parses a file, runs `jtv_lang::lint::LintRegistry` over it (with
+// `--allow`/`--warn`/`--deny` applied), and reports what it finds -- as
+// annotated `Diagnostic`s by default, or as a JSON array of records with
+// `--format json` for editors/CI to consume.
+
+use crate::diagnostics::{Diagnostic, Label, Severity};
+use colored::*;
+use jtv_lang::lint::{LintDiagnostic, LintLevel, LintRegistry};
+use serde::Serialize;
+use std::fs;
+
+/// Runs `jtv lint`. Returns `true` iff nothing found came back at `Deny`
+/// (an empty or warn-only run counts as passing), so the caller can map
+/// it straight to an exit code.
+pub fn run_lint(file: &str, allow: &[String], warn: &[String], deny: &[String], format: &str) -> bool {
+    let mut registry = LintRegistry::new();
+    let mut ok = true;
+    for name in allow {
+        ok &= set_level_or_report(&mut registry, name, LintLevel::Allow);
+    }
+    for name in warn {
+        ok &= set_level_or_report(&mut registry, name, LintLevel::Warn);
+    }
+    for name in deny {
+        ok &= set_level_or_report(&mut registry, name, LintLevel::Deny);
+    }
+    if !ok {
+        return false;
+    }
+
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{} couldn't read {}: {}", "error:".red().bold(), file, e);
+            return false;
+        }
+    };
+
+    let program = match jtv_lang::parse_program(&source) {
+        Ok(program) => program,
+        Err(e) => {
+            eprint!("{}", Diagnostic::from_error(&e, &source).render(file, &source));
+            return false;
+        }
+    };
+
+    let findings = registry.check(&program);
+    if format == "json" {
+        print_json(file, &findings);
+    } else {
+        for finding in &findings {
+            print_text(file, &source, finding);
+        }
+        if findings.is_empty() {
+            println!("{} no lint findings", "✓".green().bold());
+        }
+    }
+
+    !findings.iter().any(|finding| finding.level == LintLevel::Deny)
+}
+
+/// Applies `level` to `name` in `registry`, reporting (and returning
+/// `false`) if `name` isn't a registered lint rather than silently
+/// ignoring a typo'd `--allow`/`--warn`/`--deny` argument.
+fn set_level_or_report(registry: &mut LintRegistry, name: &str, level: LintLevel) -> bool {
+    if registry.set_level(name, level) {
+        true
+    } else {
+        let known: Vec<&str> = registry.names().collect();
+        eprintln!(
+            "{} unknown lint `{}` (known lints: {})",
+            "error:".red().bold(),
+            name,
+            known.join(", ")
+        );
+        false
+    }
+}
+
+fn print_text(file: &str, source: &str, finding: &LintDiagnostic) {
+    let severity = match finding.level {
+        LintLevel::Deny => Severity::Error,
+        _ => Severity::Warning,
+    };
+    let diagnostic = Diagnostic::new(
+        severity,
+        format!("{} [{}]", finding.message, finding.lint),
+        Label {
+            span: jtv_lang::error::Span { start: finding.span.start, end: finding.span.end },
+            message: "here".to_string(),
+        },
+    );
+    eprint!("{}", diagnostic.render(file, source));
+}
+
+#[derive(Serialize)]
+struct JsonFinding<'a> {
+    file: &'a str,
+    lint: &'a str,
+    level: LintLevel,
+    message: &'a str,
+    line: u32,
+    column: u32,
+}
+
+fn print_json(file: &str, findings: &[LintDiagnostic]) {
+    let records: Vec<JsonFinding> = findings
+        .iter()
+        .map(|finding| JsonFinding {
+            file,
+            lint: &finding.lint,
+            level: finding.level,
+            message: &finding.message,
+            line: finding.span.line,
+            column: finding.span.col,
+        })
+        .collect();
+    match serde_json::to_string_pretty(&records) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("{} failed to serialize lint findings: {}", "error:".red().bold(), e),
+    }
+}
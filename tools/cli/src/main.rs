@@ -11,9 +11,14 @@ use std::fs;
 use std::io::{self, Read};
 use std::path::PathBuf;
 
+mod analyzer;
+mod diagnostics;
+mod lint;
 mod repl;
 mod rsr_check;
-use repl::Repl;
+mod test_runner;
+use diagnostics::Diagnostic;
+use repl::{ColorMode, EditModeArg, Repl, ReplConfig};
 use rsr_check::RsrChecker;
 
 #[derive(Parser)]
@@ -65,16 +70,75 @@ enum Commands {
         /// Language (python, javascript, ruby)
         #[arg(short, long, default_value = "javascript")]
         lang: String,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
 
     /// Display version and build information
     Version,
 
     /// Check RSR (Rhodium Standard Repository) compliance
-    RsrCheck,
+    RsrCheck {
+        /// Output format (text, json, or sarif)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
 
     /// Start the interactive REPL
-    Repl,
+    Repl {
+        /// Where to load/save line-editor history (default: ~/.jtv_history)
+        #[arg(long)]
+        histfile: Option<PathBuf>,
+
+        /// Colorize the banner, prompt, and result lines
+        #[arg(long, value_enum, default_value = "auto")]
+        color: ColorMode,
+
+        /// Line-editor key bindings
+        #[arg(long = "edit-mode", value_enum, default_value = "emacs")]
+        edit_mode: EditModeArg,
+
+        /// A .jtv file to load and run, like `:load`, before the prompt appears
+        script: Option<String>,
+    },
+
+    /// Discover and run `test "name" { ... }` blocks
+    Test {
+        /// A .jtv file, or a directory to search recursively
+        path: String,
+
+        /// Only run tests whose name contains this substring
+        #[arg(long)]
+        filter: Option<String>,
+
+        /// Stop after the first failing test
+        #[arg(long)]
+        fail_fast: bool,
+    },
+
+    /// Check a file for style/suspicious-pattern issues (non-fatal by default)
+    Lint {
+        /// Path to the .jtv file
+        file: String,
+
+        /// Downgrade a lint to a non-reported allow (may be repeated)
+        #[arg(long = "allow")]
+        allow: Vec<String>,
+
+        /// Upgrade a lint to a non-fatal warning (may be repeated)
+        #[arg(long = "warn")]
+        warn: Vec<String>,
+
+        /// Upgrade a lint to a fatal error (may be repeated)
+        #[arg(long = "deny")]
+        deny: Vec<String>,
+
+        /// Output format (text or json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
 }
 
 fn main() {
@@ -82,50 +146,71 @@ fn main() {
 
     match cli.command {
         Commands::Run { file, trace, vars } => {
-            if let Err(e) = run_file(&file, trace, vars) {
-                eprintln!("{} {}", "Error:".red().bold(), e);
+            if run_file(&file, trace, vars).is_err() {
                 std::process::exit(1);
             }
         }
         Commands::Parse { file, format } => {
-            if let Err(e) = parse_file(&file, &format) {
-                eprintln!("{} {}", "Error:".red().bold(), e);
+            if parse_file(&file, &format).is_err() {
                 std::process::exit(1);
             }
         }
         Commands::Check { file } => {
-            if let Err(e) = check_file(&file) {
-                eprintln!("{} {}", "Error:".red().bold(), e);
+            if check_file(&file).is_err() {
                 std::process::exit(1);
             } else {
                 println!("{} No errors found", "✓".green().bold());
             }
         }
-        Commands::Analyze { file, lang } => {
-            eprintln!("{} Analyzer not yet fully implemented", "Warning:".yellow().bold());
-            eprintln!("Please use: deno run --allow-read packages/jtv-analyzer/src/main.ts {} {}", file, lang);
+        Commands::Analyze { file, lang, format } => {
+            if !analyzer::run_analyze(&file, &lang, &format) {
+                std::process::exit(1);
+            }
         }
         Commands::Version => {
             print_version();
         }
-        Commands::RsrCheck => {
-            let mut checker = RsrChecker::new();
-            checker.check_all();
-        }
-        Commands::Repl => {
-            let mut repl = Repl::new();
+        Commands::RsrCheck { format } => match format.as_str() {
+            "json" => {
+                let mut checker = RsrChecker::new_quiet();
+                checker.check_all();
+                println!("{}", checker.report_json());
+            }
+            "sarif" => {
+                let mut checker = RsrChecker::new_quiet();
+                checker.check_all();
+                println!("{}", checker.report_sarif());
+            }
+            _ => {
+                let mut checker = RsrChecker::new();
+                checker.check_all();
+            }
+        },
+        Commands::Repl { histfile, color, edit_mode, script } => {
+            let mut repl = Repl::new(ReplConfig { histfile, color, edit_mode, script });
             if let Err(e) = repl.run() {
                 eprintln!("{} {}", "Error:".red().bold(), e);
                 std::process::exit(1);
             }
         }
+        Commands::Test { path, filter, fail_fast } => {
+            if !test_runner::run_tests(&path, filter.as_deref(), fail_fast) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Lint { file, allow, warn, deny, format } => {
+            if !lint::run_lint(&file, &allow, &warn, &deny, &format) {
+                std::process::exit(1);
+            }
+        }
     }
 }
 
-fn run_file(file_path: &str, trace: bool, show_vars: bool) -> Result<(), String> {
-    let code = read_file(file_path)?;
+fn run_file(file_path: &str, trace: bool, show_vars: bool) -> Result<(), ()> {
+    let code = read_file(file_path).map_err(print_plain_error)?;
 
-    let program = parse_program(&code).map_err(|e| format!("Parse error: {}", e))?;
+    let program = parse_program(&code)
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     let mut interpreter = Interpreter::new();
 
@@ -133,7 +218,9 @@ fn run_file(file_path: &str, trace: bool, show_vars: bool) -> Result<(), String>
         interpreter.enable_trace();
     }
 
-    interpreter.run(&program).map_err(|e| format!("Runtime error: {}", e))?;
+    interpreter
+        .run(&program)
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     if trace {
         println!("\n{}", "=== Execution Trace ===".cyan().bold());
@@ -151,14 +238,17 @@ fn run_file(file_path: &str, trace: bool, show_vars: bool) -> Result<(), String>
     Ok(())
 }
 
-fn parse_file(file_path: &str, format: &str) -> Result<(), String> {
-    let code = read_file(file_path)?;
+fn parse_file(file_path: &str, format: &str) -> Result<(), ()> {
+    let code = read_file(file_path).map_err(print_plain_error)?;
 
-    let program = parse_program(&code).map_err(|e| format!("Parse error: {}", e))?;
+    let program = parse_program(&code)
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     match format {
         "json" => {
-            let json = serde_json::to_string_pretty(&program).map_err(|e| e.to_string())?;
+            let json = serde_json::to_string_pretty(&program).map_err(|e| {
+                eprintln!("{} {}", "Error:".red().bold(), e);
+            })?;
             println!("{}", json);
         }
         "pretty" | _ => {
@@ -170,26 +260,42 @@ fn parse_file(file_path: &str, format: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn check_file(file_path: &str) -> Result<(), String> {
-    let code = read_file(file_path)?;
+fn check_file(file_path: &str) -> Result<(), ()> {
+    let code = read_file(file_path).map_err(print_plain_error)?;
 
-    let program = parse_program(&code).map_err(|e| format!("Parse error: {}", e))?;
+    let program = parse_program(&code)
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     // Type checking
     let mut type_checker = TypeChecker::new();
     type_checker
         .check_program(&program)
-        .map_err(|e| format!("Type error: {}", e))?;
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     // Purity checking
     let mut purity_checker = PurityChecker::new();
     purity_checker
         .check_program(&program)
-        .map_err(|e| format!("Purity error: {}", e))?;
+        .map_err(|e| print_error_diagnostic(&e, file_path, &code))?;
 
     Ok(())
 }
 
+/// Prints `err` as an annotated source snippet pointing at whatever span
+/// it carries (see `Diagnostic::from_error`) -- the shared tail of
+/// `run_file`/`parse_file`/`check_file`'s parse, type, and purity error
+/// paths, so all three produce the same kind of output.
+fn print_error_diagnostic(err: &jtv_lang::JtvError, file_path: &str, code: &str) {
+    eprint!("{}", Diagnostic::from_error(err, code).render(file_path, code));
+}
+
+/// Prints an error that has no source span to point at (I/O failures,
+/// JSON serialization failures), in the plain `Error: ...` style the CLI
+/// always used before `Diagnostic` existed.
+fn print_plain_error(message: String) {
+    eprintln!("{} {}", "Error:".red().bold(), message);
+}
+
 fn read_file(file_path: &str) -> Result<String, String> {
     if file_path == "-" {
         let mut buffer = String::new();
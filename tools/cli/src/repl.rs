@@ -0,0 +1,577 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper - Interactive REPL
+
+use crate::diagnostics::Diagnostic;
+use clap::ValueEnum;
+use colored::*;
+use jtv_lang::{parse_program, ControlStmt, Interpreter, TopLevel};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Config, Context, EditMode, Editor, Helper};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+const KEYWORDS: &[&str] = &["fn", "if", "else", "while", "reverse", "return", "@pure", "@total"];
+const DEFAULT_HISTORY_FILE: &str = ".jtv_history";
+
+/// Line-edit mode for the REPL's `rustyline` editor, surfaced as
+/// `--edit-mode emacs|vi` so a Vi user isn't stuck with Emacs bindings.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum EditModeArg {
+    Emacs,
+    Vi,
+}
+
+impl From<EditModeArg> for EditMode {
+    fn from(mode: EditModeArg) -> Self {
+        match mode {
+            EditModeArg::Emacs => EditMode::Emacs,
+            EditModeArg::Vi => EditMode::Vi,
+        }
+    }
+}
+
+/// Whether the REPL colorizes its banner, prompt, and result lines,
+/// surfaced as `--color auto|always|never`. `Auto` makes no `colored`
+/// override at all, leaving `colored`'s own terminal/`NO_COLOR`
+/// detection in charge; `Always`/`Never` force an override so the REPL
+/// can be scripted or piped without inheriting whatever terminal it
+/// happens to run in.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    fn apply(self) {
+        match self {
+            ColorMode::Auto => {}
+            ColorMode::Always => colored::control::set_override(true),
+            ColorMode::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+/// Command-line configuration for a [`Repl`], built by `main.rs` from
+/// `Commands::Repl`'s `--histfile`/`--color`/`--edit-mode` flags and
+/// positional startup script -- bundled into one struct rather than
+/// threaded through `Repl::new` as four separate arguments.
+pub struct ReplConfig {
+    pub histfile: Option<PathBuf>,
+    pub color: ColorMode,
+    pub edit_mode: EditModeArg,
+    pub script: Option<String>,
+}
+
+impl Default for ReplConfig {
+    fn default() -> Self {
+        ReplConfig {
+            histfile: None,
+            color: ColorMode::Auto,
+            edit_mode: EditModeArg::Emacs,
+            script: None,
+        }
+    }
+}
+
+/// Where history is saved when `--histfile` isn't given -- `~/.jtv_history`,
+/// or `./.jtv_history` if the home directory can't be found.
+fn default_histfile() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(DEFAULT_HISTORY_FILE)
+}
+
+/// A persistent, multiline-aware REPL. One `Interpreter` lives for the
+/// whole session, so `globals` and `functions` defined by an earlier entry
+/// are still there for a later one -- what lets a user type a multi-line
+/// `fn` definition and then call it interactively on the next line.
+pub struct Repl {
+    interpreter: Interpreter,
+    trace_enabled: bool,
+    variables: Rc<RefCell<Vec<String>>>,
+    histfile: PathBuf,
+    edit_mode: EditModeArg,
+    script: Option<String>,
+}
+
+impl Repl {
+    pub fn new(config: ReplConfig) -> Self {
+        config.color.apply();
+        Repl {
+            interpreter: Interpreter::new(),
+            trace_enabled: false,
+            variables: Rc::new(RefCell::new(Vec::new())),
+            histfile: config.histfile.unwrap_or_else(default_histfile),
+            edit_mode: config.edit_mode,
+            script: config.script,
+        }
+    }
+
+    /// Reads entries from the line editor until EOF, evaluating each against
+    /// the same `Interpreter`. A `JtvError` from parsing or running an entry
+    /// is printed inline and the session continues; only an I/O failure
+    /// from the editor ends it early.
+    pub fn run(&mut self) -> io::Result<()> {
+        println!("{}", "Julia the Viper REPL".cyan().bold());
+        println!(
+            "Type {} to toggle the execution trace, {} or Ctrl-D to quit.",
+            ":trace".yellow(),
+            ":quit".yellow()
+        );
+
+        let config = Config::builder()
+            .history_ignore_space(true)
+            .edit_mode(self.edit_mode.into())
+            .build();
+
+        let mut rl: Editor<JtvHelper, rustyline::history::DefaultHistory> =
+            Editor::with_config(config).map_err(to_io_error)?;
+        rl.set_helper(Some(JtvHelper::new(&self.interpreter, self.variables.clone())));
+        let _ = rl.load_history(&self.histfile); // no history file yet on a first run
+
+        if let Some(script) = self.script.take() {
+            match fs_read(&script) {
+                Ok(code) => {
+                    self.eval_entry(&code);
+                    self.refresh_variable_names();
+                }
+                Err(err) => eprintln!("{} {}", "Error:".red().bold(), err),
+            }
+        }
+
+        loop {
+            let prompt = ">".green().bold().to_string();
+            match rl.readline(&format!("{} ", prompt)) {
+                Ok(line) => {
+                    match line.trim() {
+                        ":quit" | ":q" => break,
+                        ":trace" => {
+                            self.toggle_trace();
+                            continue;
+                        }
+                        "" => continue,
+                        _ => {}
+                    }
+
+                    let _ = rl.add_history_entry(line.as_str());
+                    self.eval_entry(&line);
+                    self.refresh_variable_names();
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => {
+                    println!();
+                    break;
+                }
+                Err(err) => return Err(to_io_error(err)),
+            }
+        }
+
+        let _ = rl.save_history(&self.histfile);
+        Ok(())
+    }
+
+    fn toggle_trace(&mut self) {
+        self.trace_enabled = !self.trace_enabled;
+        if self.trace_enabled {
+            self.interpreter.enable_trace();
+            println!("trace enabled");
+        } else {
+            let trace = self.interpreter.get_trace();
+            println!("trace disabled -- {} entries recorded this session:", trace.len());
+            for entry in trace {
+                println!("  {}: {}", entry.stmt_type.yellow(), entry.line);
+                for (name, value) in &entry.env {
+                    println!("    {} = {}", name, value);
+                }
+            }
+        }
+    }
+
+    fn eval_entry(&mut self, entry: &str) {
+        let program = match parse_program(entry) {
+            Ok(program) => program,
+            Err(err) => {
+                eprint!("{}", Diagnostic::from_error(&err, entry).render("<repl>", entry));
+                return;
+            }
+        };
+
+        let last_assigned = last_assigned_variable(&program);
+
+        match self.interpreter.run(&program) {
+            Ok(()) => {
+                if let Some(name) = last_assigned {
+                    if let Some(value) = self.interpreter.globals().get(name) {
+                        println!("{}", value);
+                    }
+                }
+            }
+            Err(err) => eprint!("{}", Diagnostic::from_error(&err, entry).render("<repl>", entry)),
+        }
+    }
+
+    /// Refreshes the completer's variable candidates from the interpreter's
+    /// current globals -- called after every entry so a variable bound this
+    /// turn is completable on the next one.
+    fn refresh_variable_names(&mut self) {
+        let mut names: Vec<String> = self.interpreter.globals().keys().cloned().collect();
+        names.sort();
+        *self.variables.borrow_mut() = names;
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new(ReplConfig::default())
+    }
+}
+
+fn to_io_error(err: ReadlineError) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.to_string())
+}
+
+/// Reads a startup script's source, for the positional `script` argument
+/// that's loaded-and-run like `:load` before the REPL starts reading
+/// interactive entries.
+fn fs_read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read file '{}': {}", path, e))
+}
+
+/// The target of the last top-level `Assignment` in `program`, if its
+/// final statement is one -- what the REPL prints after a successful
+/// `run`, mirroring how a Python/Node REPL echoes the value just bound.
+fn last_assigned_variable(program: &jtv_lang::Program) -> Option<&str> {
+    match program.statements.last()? {
+        TopLevel::Control(ControlStmt::Assignment(assignment)) => Some(assignment.target.as_str()),
+        _ => None,
+    }
+}
+
+/// Whether `src` has no unclosed `{`/`(`/`[` outside a string literal or a
+/// line comment -- the signal that an entry is complete and ready to hand
+/// to `parse_program` rather than a `fn`/`if`/block still waiting on its
+/// closing brace. A lightweight character scan rather than a real token
+/// stream, since the crate doesn't expose its lexer separately from the
+/// parser that consumes it. Shared by `JtvHelper`'s `Validator` impl, so
+/// the line editor's own multiline detection can't drift from it.
+fn is_balanced(src: &str) -> bool {
+    let mut depth = 0i32;
+    let mut chars = src.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth <= 0 && !in_string
+}
+
+/// rustyline `Helper` bundling JtV-aware syntax highlighting, builtin and
+/// variable completion, and `is_balanced`-driven multiline validation into
+/// the REPL's line editor. `variables` is shared with the `Repl` that owns
+/// the `Editor` (rather than borrowed) because `Completer::complete` only
+/// gets `&self` -- the `Repl` refreshes it after every entry via
+/// `refresh_variable_names`, so completion always sees the latest globals.
+struct JtvHelper {
+    builtins: Vec<String>,
+    variables: Rc<RefCell<Vec<String>>>,
+}
+
+impl JtvHelper {
+    fn new(interpreter: &Interpreter, variables: Rc<RefCell<Vec<String>>>) -> Self {
+        let mut builtins: Vec<String> = interpreter.builtin_names().map(str::to_string).collect();
+        builtins.sort();
+        JtvHelper { builtins, variables }
+    }
+}
+
+impl Helper for JtvHelper {}
+
+impl Completer for JtvHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos]
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let prefix = &line[start..pos];
+        if prefix.is_empty() {
+            return Ok((start, vec![]));
+        }
+
+        let mut candidates: Vec<Pair> = self
+            .builtins
+            .iter()
+            .chain(self.variables.borrow().iter())
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair { display: name.clone(), replacement: name.clone() })
+            .collect();
+        candidates.sort_by(|a, b| a.replacement.cmp(&b.replacement));
+        candidates.dedup_by(|a, b| a.replacement == b.replacement);
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for JtvHelper {
+    type Hint = String;
+}
+
+impl Validator for JtvHelper {
+    fn validate(&self, ctx: &mut ValidationContext<'_>) -> rustyline::Result<ValidationResult> {
+        if is_balanced(ctx.input()) {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+impl Highlighter for JtvHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        Cow::Owned(highlight_line(line))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
+}
+
+/// Colorizes one line of JtV source: keywords, numeric literals (plain,
+/// hex `0xFF`, binary `0b1010`, complex `1+2i`), and brackets. Walks the
+/// line token-by-token rather than running a single regex pass, so the
+/// colorizer never needs to special-case overlap between e.g. a keyword
+/// and an identifier that merely starts with one.
+fn highlight_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let bytes: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+
+        if c == '"' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i] != '"' {
+                if bytes[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            if i < bytes.len() {
+                i += 1;
+            }
+            let text: String = bytes[start..i].iter().collect();
+            out.push_str(&text.green().to_string());
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = i;
+            if c == '0' && bytes.get(i + 1).map(|c| *c == 'x' || *c == 'X').unwrap_or(false) {
+                i += 2;
+                while i < bytes.len() && bytes[i].is_ascii_hexdigit() {
+                    i += 1;
+                }
+            } else if c == '0' && bytes.get(i + 1).map(|c| *c == 'b' || *c == 'B').unwrap_or(false) {
+                i += 2;
+                while i < bytes.len() && (bytes[i] == '0' || bytes[i] == '1') {
+                    i += 1;
+                }
+            } else {
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == '.') {
+                    i += 1;
+                }
+                // Trailing `+<digits>i` completes a complex literal like `1+2i`.
+                if bytes.get(i) == Some(&'+') {
+                    let mut j = i + 1;
+                    while j < bytes.len() && bytes[j].is_ascii_digit() {
+                        j += 1;
+                    }
+                    if j > i + 1 && bytes.get(j) == Some(&'i') {
+                        i = j + 1;
+                    }
+                }
+            }
+            let text: String = bytes[start..i].iter().collect();
+            out.push_str(&text.magenta().to_string());
+            continue;
+        }
+
+        if c == '(' || c == ')' || c == '{' || c == '}' || c == '[' || c == ']' {
+            out.push_str(&c.to_string().cyan().bold().to_string());
+            i += 1;
+            continue;
+        }
+
+        if c == '@' || c.is_alphabetic() || c == '_' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && (bytes[i].is_alphanumeric() || bytes[i] == '_') {
+                i += 1;
+            }
+            let word: String = bytes[start..i].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                out.push_str(&word.blue().bold().to_string());
+            } else {
+                out.push_str(&word);
+            }
+            continue;
+        }
+
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_mode_arg_converts_to_rustyline_edit_mode() {
+        assert_eq!(EditMode::from(EditModeArg::Emacs), EditMode::Emacs);
+        assert_eq!(EditMode::from(EditModeArg::Vi), EditMode::Vi);
+    }
+
+    #[test]
+    fn test_default_histfile_ends_with_the_expected_filename() {
+        assert!(default_histfile().ends_with(DEFAULT_HISTORY_FILE));
+    }
+
+    #[test]
+    fn test_repl_config_default_is_auto_color_and_emacs_mode() {
+        let config = ReplConfig::default();
+        assert!(config.histfile.is_none());
+        assert!(config.script.is_none());
+        assert!(matches!(config.color, ColorMode::Auto));
+        assert!(matches!(config.edit_mode, EditModeArg::Emacs));
+    }
+
+    #[test]
+    fn test_is_balanced_accepts_a_complete_statement() {
+        assert!(is_balanced("x = 5 + 3"));
+    }
+
+    #[test]
+    fn test_is_balanced_rejects_an_open_function_body() {
+        assert!(!is_balanced("fn add(a: Int, b: Int): Int {\n    return a + b"));
+    }
+
+    #[test]
+    fn test_is_balanced_accepts_a_closed_function_body() {
+        assert!(is_balanced("fn add(a: Int, b: Int): Int {\n    return a + b\n}"));
+    }
+
+    #[test]
+    fn test_is_balanced_ignores_braces_inside_a_string_literal() {
+        assert!(is_balanced(r#"print("{ not a block")"#));
+    }
+
+    #[test]
+    fn test_is_balanced_counts_interpolation_braces_inside_a_string() {
+        assert!(is_balanced(r#"x = "value: {y}""#));
+    }
+
+    #[test]
+    fn test_is_balanced_ignores_braces_inside_a_line_comment() {
+        assert!(is_balanced("x = 5 // {"));
+    }
+
+    #[test]
+    fn test_last_assigned_variable_finds_final_assignment() {
+        let program = jtv_lang::Program {
+            statements: vec![TopLevel::Control(ControlStmt::Assignment(jtv_lang::Assignment {
+                target: "x".to_string(),
+                value: jtv_lang::Expr::Data(jtv_lang::DataExpr::number(jtv_lang::Number::Int(5))),
+            }))],
+            span: jtv_lang::Span::unknown(),
+        };
+        assert_eq!(last_assigned_variable(&program), Some("x"));
+    }
+
+    #[test]
+    fn test_last_assigned_variable_none_for_a_print_statement() {
+        let program = jtv_lang::Program {
+            statements: vec![TopLevel::Control(ControlStmt::Print(vec![
+                jtv_lang::DataExpr::number(jtv_lang::Number::Int(5)),
+            ]))],
+            span: jtv_lang::Span::unknown(),
+        };
+        assert_eq!(last_assigned_variable(&program), None);
+    }
+
+    #[test]
+    fn test_highlight_line_colors_a_keyword() {
+        let highlighted = highlight_line("fn add");
+        assert!(highlighted.contains("fn"));
+        assert_ne!(highlighted, "fn add");
+    }
+
+    #[test]
+    fn test_highlight_line_colors_a_hex_literal() {
+        let highlighted = highlight_line("0xFF");
+        assert!(highlighted.contains("0xFF"));
+        assert_ne!(highlighted, "0xFF");
+    }
+
+    #[test]
+    fn test_highlight_line_colors_a_complex_literal() {
+        let highlighted = highlight_line("1+2i");
+        assert!(highlighted.contains("1+2i"));
+        assert_ne!(highlighted, "1+2i");
+    }
+
+    #[test]
+    fn test_completer_offers_builtin_and_variable_candidates() {
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&parse_program("x = 1").unwrap()).unwrap();
+        let variables = Rc::new(RefCell::new(vec!["x".to_string()]));
+        let helper = JtvHelper::new(&interpreter, variables);
+        assert!(helper.builtins.contains(&"abs".to_string()) || !helper.builtins.is_empty());
+        assert!(helper.variables.borrow().contains(&"x".to_string()));
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: MIT OR GPL-3.0-or-later OR Palimpsest-0.8
+// SPDX-FileCopyrightText: 2025 Julia the Viper Contributors
+//
+// Julia the Viper CLI - `jtv test`: discovers and runs `test "name" { ... }`
+// blocks.
+//
+// Walks `path` (a single `.jtv` file or a directory, recursively) for
+// `.jtv` files, parses each, and pulls out every top-level `TestDecl`
+// (including ones nested in a `ModuleDecl`). Each test runs in its own
+// fresh `Interpreter`, seeded only with the file's function/struct/import
+// declarations -- never with another test's state, or with side effects
+// from the file's own bare top-level statements -- so tests can't leak
+// into each other. A failing test is reported with `Diagnostic`, anchored
+// at the `test` block's span.
+
+use crate::diagnostics::{Diagnostic, Label, Severity};
+use colored::*;
+use jtv_lang::{parse_program, Interpreter, JtvError, Program, PurityChecker, TestDecl, TopLevel};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Runs every test discovered under `path`. Returns `true` iff every test
+/// that ran passed (an empty run counts as passing). Prints a line per
+/// test as it finishes, a `Diagnostic` for each failure, and a final
+/// `N passed, M failed` summary.
+pub fn run_tests(path: &str, filter: Option<&str>, fail_fast: bool) -> bool {
+    let root = PathBuf::from(path);
+    let files = if root.is_dir() {
+        discover_jtv_files(&root)
+    } else {
+        vec![root]
+    };
+
+    let mut passed = 0usize;
+    let mut failed = 0usize;
+
+    'files: for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{} couldn't read {}: {}", "error:".red().bold(), file.display(), e);
+                failed += 1;
+                if fail_fast {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let program = match parse_program(&source) {
+            Ok(program) => program,
+            Err(e) => {
+                eprint!("{}", Diagnostic::from_error(&e, &source).render(&file.display().to_string(), &source));
+                failed += 1;
+                if fail_fast {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        for decl in extract_tests(&program) {
+            if let Some(substr) = filter {
+                if !decl.name.contains(substr) {
+                    continue;
+                }
+            }
+
+            let start = Instant::now();
+            let result = run_one_test(&program, decl);
+            let elapsed = start.elapsed();
+
+            match result {
+                Ok(()) => {
+                    passed += 1;
+                    println!("{} {} ({})", "ok".green().bold(), decl.name, format_duration(elapsed));
+                }
+                Err(err) => {
+                    failed += 1;
+                    println!("{} {} ({})", "FAILED".red().bold(), decl.name, format_duration(elapsed));
+                    let diag = Diagnostic::new(
+                        Severity::Error,
+                        err.to_string(),
+                        Label {
+                            span: jtv_lang::error::Span { start: decl.span.start, end: decl.span.end },
+                            message: "in this test".to_string(),
+                        },
+                    );
+                    eprint!("{}", diag.render(&file.display().to_string(), &source));
+                    if fail_fast {
+                        break 'files;
+                    }
+                }
+            }
+        }
+    }
+
+    println!("\n{} passed, {} failed", passed, failed);
+    failed == 0
+}
+
+/// Recursively collects every `.jtv` file under `root`, in a stable
+/// (sorted) order so a run's output doesn't depend on directory-listing
+/// order.
+fn discover_jtv_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_jtv_files(root, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_jtv_files(path: &Path, out: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else { return };
+        for entry in entries.flatten() {
+            collect_jtv_files(&entry.path(), out);
+        }
+    } else if path.extension().is_some_and(|ext| ext == "jtv") {
+        out.push(path.to_path_buf());
+    }
+}
+
+/// Every `TestDecl` declared at the top level of `program`, including ones
+/// nested inside a `ModuleDecl` -- the same reach `PurityChecker::index`
+/// gives function declarations.
+fn extract_tests(program: &Program) -> Vec<&TestDecl> {
+    fn walk<'a>(items: &'a [TopLevel], out: &mut Vec<&'a TestDecl>) {
+        for item in items {
+            match item {
+                TopLevel::Test(decl) => out.push(decl),
+                TopLevel::Module(module) => walk(&module.body, out),
+                TopLevel::Import(_) | TopLevel::Function(_) | TopLevel::Struct(_) | TopLevel::Control(_) => {}
+            }
+        }
+    }
+    let mut tests = Vec::new();
+    walk(&program.statements, &mut tests);
+    tests
+}
+
+/// Runs a single `TestDecl` in its own `Interpreter`: loads the file's
+/// function/struct/import declarations (so the body can call helpers
+/// defined elsewhere in the same file) but none of its bare top-level
+/// `Control` statements or any other test's state, checks purity first if
+/// the test is declared `pure`, then executes the body.
+fn run_one_test(program: &Program, decl: &TestDecl) -> Result<(), JtvError> {
+    if decl.pure {
+        if let Some(violation) = PurityChecker::check_test(decl).into_iter().next() {
+            return Err(JtvError::PurityViolation(violation.to_string()));
+        }
+    }
+
+    let mut interpreter = Interpreter::new();
+
+    let setup: Vec<TopLevel> = program
+        .statements
+        .iter()
+        .filter(|item| !matches!(item, TopLevel::Test(_) | TopLevel::Control(_)))
+        .cloned()
+        .collect();
+    interpreter.run(&Program { statements: setup, span: jtv_lang::ast::Span::unknown() })?;
+
+    let body: Vec<TopLevel> = decl.body.iter().cloned().map(TopLevel::Control).collect();
+    interpreter.run(&Program { statements: body, span: decl.span })
+}
+
+fn format_duration(d: Duration) -> String {
+    if d.as_millis() == 0 {
+        format!("{}us", d.as_micros())
+    } else {
+        format!("{}ms", d.as_millis())
+    }
+}